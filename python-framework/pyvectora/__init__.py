@@ -14,6 +14,7 @@ Example usage:
 """
 
 from .app import App
+from .application import Application
 from .request import Request
 from .response import Response
 from .controller import Controller, get, post, put, delete, patch, head, options
@@ -29,8 +30,93 @@ except ImportError:
     def _native_version() -> str:
         return "0.1.0"
 
+try:
+    from pyvectora.pyvectora_native import retry
+except ImportError:
+    retry = None  # type: ignore
+
+try:
+    from pyvectora.pyvectora_native import rust_extensions
+except ImportError:
+    def rust_extensions() -> list[str]:  # type: ignore
+        """Names of the compiled extensions (none without the native module)."""
+        return []
+
+try:
+    from pyvectora.pyvectora_native import Runtime, RuntimeConfig, configure_runtime
+except ImportError:
+    Runtime = None  # type: ignore
+    RuntimeConfig = None  # type: ignore
+    configure_runtime = None  # type: ignore
+
+try:
+    from pyvectora.pyvectora_native import ConfigurationError
+except ImportError:
+    class ConfigurationError(Exception):  # type: ignore
+        """Raised for invalid app configuration, e.g. conflicting routes."""
+
+try:
+    from pyvectora.pyvectora_native import (
+        NotFoundError,
+        ValidationError,
+        AuthenticationError,
+    )
+except ImportError:
+    class NotFoundError(Exception):  # type: ignore
+        """Raised in a handler to answer 404."""
+
+    class ValidationError(Exception):  # type: ignore
+        """Raised in a handler to answer 422."""
+
+    class AuthenticationError(Exception):  # type: ignore
+        """Raised in a handler to answer 401."""
+
+try:
+    from pyvectora.pyvectora_native import (
+        RequestLimitError,
+        PayloadTooLarge,
+        UriTooLong,
+        TooManyQueryParams,
+    )
+except ImportError:
+    class RequestLimitError(ValidationError):  # type: ignore
+        """A request exceeded a configured limit; ``limit`` and ``actual`` give the sizes."""
+
+        limit = None
+        actual = None
+
+    class PayloadTooLarge(RequestLimitError):  # type: ignore
+        """The body exceeded the size limit (413)."""
+
+    class UriTooLong(RequestLimitError):  # type: ignore
+        """The path and query string exceeded the length limit (414)."""
+
+    class TooManyQueryParams(RequestLimitError):  # type: ignore
+        """The query string had more parameters than allowed (400)."""
+
+try:
+    from pyvectora.pyvectora_native import HandlerTimeout
+except ImportError:
+    import asyncio as _asyncio
+
+    class HandlerTimeout(_asyncio.CancelledError):  # type: ignore
+        """The route timeout fired (504); raised by ``context.raise_if_cancelled()``."""
+
+try:
+    from pyvectora.pyvectora_native import MultipartError, UploadTooLarge
+except ImportError:
+    class MultipartError(ValueError):  # type: ignore
+        """Raised by ``request.form()`` / ``request.files()`` for a malformed body (400)."""
+
+    class UploadTooLarge(MultipartError):  # type: ignore
+        """Raised when a form part exceeds the upload limit (413)."""
+
 from .contract import Contract
+from .extension import Extension
+from .exceptions import HTTPException, RequestValidationError
 from .guard import Guard
+from .health import HealthRegistry
+from . import events
 try:
     from .database import Database, Transaction, DatabaseError
 except Exception:
@@ -38,22 +124,35 @@ except Exception:
     Transaction = None  # type: ignore
     DatabaseError = None  # type: ignore
 from .repository import Repository
+from .feed import Feed, FeedEntry
+from .sitemap import Sitemap, SitemapURL
+from .routing import RouteGroup
 from .response import (
     StreamingResponse,
     EventSourceResponse,
+    NDJSONResponse,
+    CSVResponse,
+    SpooledResponse,
     sse_event,
     sse_json
 )
 
+try:
+    from pyvectora.pyvectora_native import SSEResponse
+except ImportError:
+    SSEResponse = EventSourceResponse  # type: ignore
+
 PyVectora = App
 DatabasePool = Database
 
 __version__ = _native_version()
 __all__ = [
-    "App", "PyVectora", "Request", "Response",
+    "App", "Application", "PyVectora", "Request", "Response",
     "Controller", "get", "post", "put", "delete", "patch", "head", "options",
     "Provider", "Contract", "Guard", "Database", "DatabasePool", "Transaction", "DatabaseError",
-    "Repository",
-    "StreamingResponse", "EventSourceResponse", "sse_event", "sse_json",
+    "ConfigurationError", "HTTPException", "NotFoundError", "ValidationError", "AuthenticationError", "MultipartError", "UploadTooLarge", "RequestValidationError",
+    "RequestLimitError", "PayloadTooLarge", "UriTooLong", "TooManyQueryParams", "HandlerTimeout", "Extension", "Runtime", "RuntimeConfig", "configure_runtime",
+    "HealthRegistry", "events", "Repository", "retry", "rust_extensions", "Feed", "FeedEntry", "Sitemap", "SitemapURL", "RouteGroup",
+    "StreamingResponse", "EventSourceResponse", "NDJSONResponse", "CSVResponse", "SpooledResponse", "SSEResponse", "sse_event", "sse_json",
     "__native_available__", "__version__"
 ]
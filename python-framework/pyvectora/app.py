@@ -9,11 +9,28 @@ from __future__ import annotations
 
 from typing import TYPE_CHECKING, Any, Callable, Type, List
 import asyncio
-from dataclasses import dataclass
+import os
+import re
+import sys
+import warnings
+from dataclasses import dataclass, field
+from datetime import date, datetime
 
 from .di import Provider, register_global_provider, wrap_handler_with_di
 from .controller import ControllerMeta
 from .auth import AuthGuard
+from .sitemap import Sitemap
+from .routing import RouteGroup
+from .resource import Resource
+from .health import HealthRegistry
+from .application import Application
+from . import events
+from . import logging as log_context
+
+# Let's Encrypt production directory, the default ``enable_acme`` CA
+LETS_ENCRYPT = "https://acme-v02.api.letsencrypt.org/directory"
+# Seconds between checks for a renewed ACME certificate on disk
+ACME_RELOAD_INTERVAL = 3600.0
 
 if TYPE_CHECKING:
     pass
@@ -25,6 +42,50 @@ class Route:
     path: str
     handler: Callable[..., Any]
     auth: bool = False
+    middlewares: List[Any] = field(default_factory=list)
+    requires: dict[str, Any] | None = None
+    stream: bool = False
+    max_body: int | None = None
+    timeout: float | None = None
+    parse_json: bool = False
+    deprecated_after: date | str | None = None
+    canary: dict[str, Any] | None = None
+
+    def __post_init__(self) -> None:
+        if self.max_body is not None and self.max_body < 0:
+            raise ValueError("max_body must not be negative")
+        if self.timeout is not None and self.timeout <= 0:
+            raise ValueError("timeout must be positive")
+        if isinstance(self.deprecated_after, datetime):
+            self.deprecated_after = self.deprecated_after.date()
+        if isinstance(self.deprecated_after, str):
+            self.deprecated_after = date.fromisoformat(self.deprecated_after)
+        if self.deprecated_after is not None:
+            self.deprecated_after = self.deprecated_after.isoformat()
+
+
+def _route_shape(path: str) -> str:
+    """``path`` with parameter names and types erased (``/users/{}``, ``/files/{*}``)."""
+    return re.sub(r"\{(\*?)[^}]*\}", r"{\1}", path)
+
+
+def _describe(handler: Callable[..., Any]) -> str:
+    """``module.qualname`` of a handler, for registration errors."""
+    name = getattr(handler, "__qualname__", None) or repr(handler)
+    module = getattr(handler, "__module__", None)
+    return f"{module}.{name}" if module else name
+
+
+def _worker_count(workers: int | str) -> int:
+    """Worker processes for ``serve(workers=...)`` (``"auto"``: one per CPU)."""
+    if workers == "auto":
+        workers = os.cpu_count() or 1
+    if isinstance(workers, bool) or not isinstance(workers, int) or workers < 1:
+        raise ValueError(f"workers must be a positive integer or 'auto', got {workers!r}")
+    if workers > 1 and sys.platform == "win32":
+        raise ValueError("Worker processes need Unix; run one worker per service on Windows")
+    return workers
+
 
 class App:
     """
@@ -38,7 +99,11 @@ class App:
         host: str = "127.0.0.1",
         port: int = 8000,
         lifespan: Callable[[App], Any] | None = None,
-        enable_health_check: bool = True
+        enable_health_check: bool = True,
+        runtime: Any = None,
+        debug: bool = False,
+        uds: str | None = None,
+        uds_mode: int | None = None,
     ) -> None:
         """
         Initialize a new PyVectora application.
@@ -47,19 +112,85 @@ class App:
             host: Server host address
             port: Server port
             lifespan: Optional async context manager for startup/shutdown
-            enable_health_check: Enable /health endpoint (default: True)
+            enable_health_check: Enable the /health (liveness) and /ready
+                (readiness, runs ``app.health`` probes) endpoints (default: True)
+            runtime: ``RuntimeConfig`` giving this app its own Tokio runtime,
+                or a ``Runtime`` shared with other apps (default: the
+                process-wide runtime, see ``configure_runtime``; operators
+                can tune it with ``PYVECTORA_WORKER_THREADS``,
+                ``PYVECTORA_MAX_BLOCKING_THREADS``, ``PYVECTORA_THREAD_NAME``
+                and ``PYVECTORA_THREAD_STACK_SIZE``)
+            debug: Answer unhandled exceptions with their traceback and the
+                request instead of a terse 500, and unmatched requests with the
+                nearest registered routes (never enable in production)
+            uds: Path of a Unix domain socket to serve on instead of
+                ``host:port``, e.g. behind nginx or envoy on the same host
+                (Unix only; a stale socket file is replaced)
+            uds_mode: Permission bits of the socket file, e.g. ``0o660`` so
+                only the proxy's group can connect (default: the umask)
         """
+        if uds is not None and not uds:
+            raise ValueError("uds must be a socket path")
+        if uds_mode is not None:
+            if uds is None:
+                raise ValueError("uds_mode needs uds")
+            if not 0 <= uds_mode <= 0o7777:
+                raise ValueError(f"Invalid socket mode {uds_mode:#o}")
         self.host = host
         self.port = port
         self.lifespan = lifespan
         self.enable_health_check = enable_health_check
+        self.runtime = runtime
+        self.debug = debug
+        self.uds = uds
+        self.uds_mode = uds_mode
+        self.health = HealthRegistry()
 
         self._routes: List[Route] = []
+        self._ws_routes: List[tuple[str, Callable]] = []
+        self._wsgi_mounts: List[tuple[str, Callable]] = []
         self._controllers: List[Any] = []
         self._jwt_secret: str | None = None
+        self._tls: tuple[str, str, float | None] | None = None
+        self._tls_sni: List[tuple[str, str, str]] = []
+        self._acme_challenges: Any = None
+        self._acme: dict[str, Any] | None = None
+        self._profiling: tuple[str, str | None, bool] | None = None
+        self._metrics: dict[str, Any] | None = None
+        self._read_only: dict[str, Any] | None = None
+        self._shutdown_signals: List[str] | None = None
+        self._warmup: List[tuple[str, str, dict[str, str] | None]] = []
+        self._admin: tuple[str, dict[str, Any], str | None] | None = None
+        self._error_reporting: dict[str, Any] | None = None
+        self._rust_extensions: List[tuple[str, dict[str, Any] | None]] = []
+        self._bad_request_handler: Callable[[str, str], Any] | None = None
+        self._http2: bool | None = None
         self._middlewares: List[tuple[str, dict[str, Any]]] = []
         self._python_middlewares: List[Any] = []
         self._max_body_size: int | None = None
+        self._handler_timeout: float | None = None
+        self._deprecation: dict[str, str | None] | None = None
+        self._upload_limits: dict[str, Any] | None = None
+        self._uri_limits: dict[str, int | None] | None = None
+        self._max_connections: int | None = None
+        self._listeners: List[str] = []
+        self._socket_options: dict[str, Any] | None = None
+        self._socket_activation = False
+        self._param_mismatch: str | None = None
+        self._duplicate_routes: str | None = None
+        self._error_format: str | None = None
+        self._response_policy: dict[str, bool] | None = None
+        self._memory_limits: tuple[int | None, int | None, float | None] | None = None
+        self._max_requests: int | None = None
+        self._startup_banner: bool = False
+        self._resource_accounting: bool = False
+        self._resource_stats_path: str | None = None
+        self._gil_warning_threshold: float | None = 50.0
+        self._serializers: List[tuple[type, Callable[[Any], Any]]] = []
+        self._exception_handlers: List[tuple[type, Callable[..., Any]]] = []
+        self._sitemap: Sitemap | None = None
+        self._extensions: dict[str, Any] = {}
+        self.native_app = None
 
         self._startup_handlers: List[Callable] = []
         self._shutdown_handlers: List[Callable] = []
@@ -72,6 +203,99 @@ class App:
         """Set the JWT secret for authentication."""
         self._jwt_secret = secret
 
+    def enable_tls(self, cert_path: str, key_path: str, reload_interval: float | None = None) -> None:
+        """
+        Serve HTTPS directly using a PEM certificate chain and private key.
+
+        With ``reload_interval`` (seconds) the files are checked for changes
+        and renewed certificates (e.g. from an ACME client) are used for new
+        connections without a restart.
+        """
+        self._tls = (cert_path, key_path, reload_interval)
+
+    def add_tls_certificate(self, hostname: str, cert_path: str, key_path: str) -> None:
+        """
+        Serve a different certificate to clients requesting ``hostname`` (SNI).
+
+        ``hostname`` may be a ``*.example.com`` wildcard matching one label.
+        Clients asking for other hosts get the ``enable_tls`` certificate.
+        Reloaded together with it when ``reload_interval`` is set.
+        """
+        if self._tls is None:
+            raise RuntimeError("Call enable_tls() before adding SNI certificates")
+        self._tls_sni.append((hostname, cert_path, key_path))
+
+    def enable_acme_http01(self, challenges: Any = None) -> Any:
+        """
+        Serve ACME HTTP-01 challenges under ``/.well-known/acme-challenge/``.
+
+        Returns the challenge store; an ACME client publishes responses with
+        ``challenges.set(token, key_authorization)`` and clears them with
+        ``challenges.remove(token)``. Pass the same store to another app to
+        answer challenges on plain HTTP port 80 while this app serves TLS.
+        """
+        if challenges is None:
+            from pyvectora.pyvectora_native import AcmeChallenges
+
+            challenges = AcmeChallenges()
+        self._acme_challenges = challenges
+        return challenges
+
+    def enable_acme(
+        self,
+        domains: List[str],
+        email: str | None = None,
+        storage: str = "acme",
+        directory: str = LETS_ENCRYPT,
+        renew_before_days: float = 30,
+        http01_port: int | None = 80,
+        agree_tos: bool = False,
+        challenges: Any = None,
+    ) -> Any:
+        """
+        Serve HTTPS with a certificate obtained and renewed from an ACME CA.
+
+        A missing or expiring certificate is issued into ``storage`` before
+        the app listens, then renewed ``renew_before_days`` ahead of expiry
+        and picked up without a restart. HTTP-01 challenges are answered on
+        ``http01_port``; pass ``None`` when another app sharing
+        ``challenges`` already serves port 80. ``agree_tos`` accepts the
+        CA's terms of service. Returns the challenge store.
+
+        Example:
+            app.enable_acme(["example.com"], email="ops@example.com", agree_tos=True)
+        """
+        if not agree_tos:
+            raise ValueError("Pass agree_tos=True to accept the ACME CA's terms of service")
+        if not domains:
+            raise ValueError("ACME needs at least one domain")
+        if renew_before_days <= 0:
+            raise ValueError(f"renew_before_days must be positive, got {renew_before_days!r}")
+        if http01_port is not None and not 0 < http01_port < 65536:
+            raise ValueError(f"Invalid http01_port {http01_port!r}")
+        challenges = self.enable_acme_http01(challenges)
+        self._acme = {
+            "domains": list(domains),
+            "storage": storage,
+            "directory": directory,
+            "email": email,
+            "renew_before": renew_before_days * 86400,
+            "http01_port": http01_port,
+        }
+        cert_path = os.path.join(storage, "cert.pem")
+        key_path = os.path.join(storage, "key.pem")
+        self.enable_tls(cert_path, key_path, reload_interval=ACME_RELOAD_INTERVAL)
+        return challenges
+
+    def enable_http2(self, h2c: bool = False) -> None:
+        """
+        Serve HTTP/2 alongside HTTP/1.1.
+
+        Negotiated via ALPN when TLS is enabled. With ``h2c=True`` plain
+        connections also accept HTTP/2 with prior knowledge.
+        """
+        self._http2 = h2c
+
     def enable_logging(self, log_headers: bool = False) -> None:
         """Enable Rust logging middleware."""
         self._middlewares.append(("logging", {"log_headers": log_headers}))
@@ -82,17 +306,35 @@ class App:
 
     def enable_cors(
         self,
-        allow_origin: str = "*",
+        allow_origin: str | List[str] | Callable[[str], bool] = "*",
         allow_methods: str = "GET, POST, PUT, DELETE, PATCH, OPTIONS",
         allow_headers: str = "Content-Type, Authorization",
+        max_age: int = 600,
+        allow_credentials: bool = False,
+        expose_headers: str | None = None,
     ) -> None:
-        """Enable Rust CORS middleware."""
+        """
+        Enable Rust CORS middleware (answers preflight requests with 204).
+
+        ``allow_origin`` may be a single origin, a list of allowed origins, or a
+        callable ``(origin) -> bool``; the latter two reflect the matched origin.
+        ``allow_credentials`` needs one of those two or a single named origin,
+        not ``"*"``.
+        """
+        if allow_credentials and allow_origin == "*":
+            raise ValueError(
+                'allow_credentials cannot be combined with allow_origin="*"; '
+                "list the allowed origins or pass a validator"
+            )
         self._middlewares.append((
             "cors",
             {
                 "allow_origin": allow_origin,
                 "allow_methods": allow_methods,
                 "allow_headers": allow_headers,
+                "max_age": max_age,
+                "allow_credentials": allow_credentials,
+                "expose_headers": expose_headers,
             }
         ))
 
@@ -100,63 +342,1179 @@ class App:
         """Enable Rust rate limit middleware."""
         self._middlewares.append(("rate_limit", {"capacity": capacity, "refill_per_sec": refill_per_sec}))
 
+    def enable_tiered_rate_limit(
+        self,
+        tiers: dict[str, int],
+        default_tier: str,
+        claim: str = "tier",
+        window: float = 60.0,
+        resolver: Callable[[dict[str, Any] | None], str | None] | None = None,
+    ) -> None:
+        """
+        Rate limit each caller by tier: ``tiers`` maps a tier to the requests
+        it may make per ``window`` seconds.
+
+        The tier is ``resolver(claims)`` when given and not ``None``, else the
+        JWT claim ``claim``, else ``default_tier`` (also used for unknown
+        tiers). Callers are told apart by JWT subject, else by IP address.
+        Handlers see ``request.state.rate_limit`` (``tier``, ``limit``,
+        ``remaining``); responses carry ``X-RateLimit-Limit`` and
+        ``X-RateLimit-Remaining``, and 429s a ``Retry-After``.
+
+        Example:
+            app.enable_tiered_rate_limit({"free": 60, "pro": 600}, default_tier="free")
+        """
+        if default_tier not in tiers:
+            raise ValueError(f"default_tier {default_tier!r} is not one of the tiers")
+        for tier, requests in tiers.items():
+            if not isinstance(requests, int) or requests <= 0:
+                raise ValueError(f"Tier {tier!r} must allow a positive number of requests")
+        if window <= 0:
+            raise ValueError("window must be positive")
+        self._middlewares.append((
+            "tiered_rate_limit",
+            {
+                "tiers": dict(tiers),
+                "default_tier": default_tier,
+                "claim": claim,
+                "window": window,
+                "resolver": resolver,
+            },
+        ))
+
+    def enable_compression(
+        self,
+        min_size: int = 1024,
+        content_types: List[str] | None = None,
+    ) -> None:
+        """
+        Enable Rust gzip/brotli compression middleware.
+
+        ``content_types`` entries ending in ``/`` match a prefix (e.g. ``"text/"``).
+        """
+        self._middlewares.append((
+            "compression",
+            {"min_size": min_size, "content_types": content_types},
+        ))
+
+    def enable_sessions(
+        self,
+        secret_key: str,
+        cookie_name: str = "session",
+        max_age: int | None = None,
+        encrypt: bool = False,
+        secure: bool = False,
+        same_site: str = "lax",
+        domain: str | None = None,
+    ) -> None:
+        """
+        Enable signed cookie sessions, available as ``request.session``.
+
+        The session is a dict-like of JSON values stored in an HMAC-signed
+        cookie (AES-GCM encrypted too with ``encrypt=True``) and saved
+        automatically when a handler changes it. ``secret_key`` must be at
+        least 32 bytes; rotating it invalidates every session.
+
+        Example:
+            app.enable_sessions(os.environ["SESSION_SECRET"], max_age=86400)
+
+            @app.post("/login")
+            async def login(request):
+                request.session["user_id"] = 42
+                return {"ok": True}
+        """
+        if len(secret_key.encode()) < 32:
+            raise ValueError("secret_key must be at least 32 bytes")
+        if same_site.lower() not in ("lax", "strict", "none"):
+            raise ValueError(f"same_site must be 'lax', 'strict' or 'none', not {same_site!r}")
+        self._middlewares.append((
+            "session",
+            {
+                "secret_key": secret_key,
+                "cookie_name": cookie_name,
+                "max_age": max_age,
+                "encrypt": encrypt,
+                "secure": secure,
+                "same_site": same_site,
+                "domain": domain,
+            },
+        ))
+
     def set_body_limit(self, bytes: int) -> None:
         """Set max request body size (bytes)."""
         self._max_body_size = bytes
 
+    def set_handler_timeout(self, seconds: float | None) -> None:
+        """
+        Answer 504 when a handler runs longer than ``seconds`` (None = unlimited).
+
+        The timed out handler's task is cancelled (``asyncio.CancelledError``
+        is raised at its current ``await``) and ``request.context.cancelled()``
+        turns true, so a hung coroutine cannot hold its connection. There
+        ``request.context.timed_out()`` tells a timeout from a client that
+        went away, and ``request.context.raise_if_cancelled()`` raises
+        ``HandlerTimeout``, a ``CancelledError``.
+
+        Example:
+            async def export(request):
+                try:
+                    await build_report()
+                except asyncio.CancelledError:
+                    if request.context.timed_out():
+                        await cleanup_partial_report()
+                    raise
+        """
+        if seconds is not None and seconds <= 0:
+            raise ValueError("timeout must be positive")
+        self._handler_timeout = seconds
+
+    def set_deprecation(self, link: str | None = None, gone: str | None = None) -> None:
+        """
+        What routes registered with ``deprecated_after`` do around their sunset.
+
+        Their responses always carry ``Deprecation: true`` and ``Sunset``
+        (the day after ``deprecated_after``); ``link`` adds
+        ``Link: <link>; rel="deprecation"`` pointing at a migration guide.
+        With ``gone``, requests after the sunset are answered 410 with that
+        message instead of running the handler.
+        """
+        self._deprecation = {"link": link, "gone": gone}
+
+    def set_upload_limits(
+        self,
+        max_part_size: int | None = None,
+        max_parts: int | None = None,
+        spool_threshold: int | None = None,
+        temp_dir: str | None = None,
+    ) -> None:
+        """
+        Limits for ``request.form()`` / ``request.files()``.
+
+        Defaults: 10 MiB per part, 100 parts, files above 1 MiB spooled to
+        the system temp dir. A part above ``max_part_size`` raises
+        ``UploadTooLarge`` (answered 413). Uploads are also bounded by the
+        body limit, so raise ``set_body_limit`` to accept larger files.
+        """
+        self._upload_limits = {
+            "max_part_size": max_part_size,
+            "max_parts": max_parts,
+            "spool_threshold": spool_threshold,
+            "temp_dir": temp_dir,
+        }
+
+    def set_uri_limits(
+        self,
+        max_length: int | None = None,
+        max_query_params: int | None = None,
+    ) -> None:
+        """
+        Limits on the request target (path plus query string).
+
+        Defaults: 8 KiB and 1000 query parameters. Longer targets are
+        answered 414; more parameters are answered 400 with the
+        ``"too_many_query_params"`` kind (see ``on_bad_request``).
+        """
+        for name, value in (("max_length", max_length), ("max_query_params", max_query_params)):
+            if value is not None and value <= 0:
+                raise ValueError(f"{name} must be positive")
+        self._uri_limits = {"max_length": max_length, "max_query_params": max_query_params}
+
+    def set_max_connections(self, limit: int) -> None:
+        """
+        Cap concurrently served connections (default 10000).
+
+        At the cap new connections wait in the listen backlog until one
+        closes. Connections still open after the shutdown timeout are aborted.
+        """
+        if limit < 1:
+            raise ValueError("limit must be at least 1")
+        self._max_connections = limit
+
+    def set_socket_options(
+        self,
+        backlog: int | None = None,
+        nodelay: bool | None = None,
+        keepalive_idle: float | None = None,
+        keepalive_interval: float | None = None,
+        recv_buffer_size: int | None = None,
+        send_buffer_size: int | None = None,
+    ) -> None:
+        """
+        Tune the TCP sockets the server listens and serves on.
+
+        ``backlog`` is the queue of connections not yet accepted (default
+        1024); ``nodelay`` sends small writes immediately; ``keepalive_idle``
+        enables TCP keepalive after that many idle seconds, probing every
+        ``keepalive_interval`` seconds; buffer sizes are in bytes. Options
+        left ``None`` keep the operating system's defaults.
+
+        Example:
+            app.set_socket_options(backlog=4096, nodelay=True, keepalive_idle=60)
+        """
+        for name, value in (
+            ("backlog", backlog),
+            ("recv_buffer_size", recv_buffer_size),
+            ("send_buffer_size", send_buffer_size),
+        ):
+            if value is not None and not 0 < value < 2**32:
+                raise ValueError(f"{name} must be a positive 32-bit integer")
+        for name, seconds in (
+            ("keepalive_idle", keepalive_idle),
+            ("keepalive_interval", keepalive_interval),
+        ):
+            if seconds is not None and seconds <= 0:
+                raise ValueError(f"{name} must be positive")
+        self._socket_options = {
+            "backlog": backlog,
+            "nodelay": nodelay,
+            "keepalive_idle": keepalive_idle,
+            "keepalive_interval": keepalive_interval,
+            "recv_buffer_size": recv_buffer_size,
+            "send_buffer_size": send_buffer_size,
+        }
+
+    def enable_socket_activation(self) -> None:
+        """
+        Serve on the sockets systemd passes when socket-activated.
+
+        systemd binds the ``ListenStream=`` sockets of a ``.socket`` unit,
+        privileged ports included, and hands them over through
+        ``LISTEN_FDS``; they replace ``host``/``port`` (or ``uds``), and
+        connections queue in them while the service restarts. Started
+        without systemd, the app binds its address as usual. Socket
+        activation serves from a single process (``workers=1``).
+
+        Example:
+            app.enable_socket_activation()
+        """
+        self._socket_activation = True
+
+    def add_listener(self, address: str) -> None:
+        """
+        Also accept connections on ``address``, with the same routes.
+
+        ``address`` is ``ip:port`` (``"[::1]:8001"`` for IPv6) or, on Unix,
+        ``unix:<path>`` for a Unix domain socket, e.g. for a reverse proxy on
+        the same host. A stale socket file from an earlier run is replaced
+        and the file is removed on shutdown.
+
+        Example:
+            app.add_listener("unix:/run/myapp.sock")
+        """
+        import ipaddress
+
+        if address.startswith("unix:"):
+            if address == "unix:":
+                raise ValueError("unix: listener needs a socket path")
+        else:
+            host, sep, port = address.rpartition(":")
+            try:
+                ipaddress.ip_address(host.strip("[]"))
+                valid = bool(sep) and 0 <= int(port) <= 65535
+            except ValueError:
+                valid = False
+            if not valid:
+                raise ValueError(
+                    f"Invalid listen address {address!r} (expected ip:port or unix:/path)"
+                )
+        self._listeners.append(address)
+
+    def enable_profiling(
+        self, token: str, prefix: str | None = None, py_spy: bool = False
+    ) -> None:
+        """
+        Serve admin-only profiling endpoints (default prefix ``/_pyvectora/profile``).
+
+        ``GET {prefix}/runtime`` returns runtime metrics, ``GET {prefix}/cpu``
+        a CPU profile (``?seconds=10&format=pprof|flamegraph``; needs the
+        native module built with the ``profiling`` feature). With ``py_spy``
+        ``GET {prefix}/py-spy`` returns a ``py-spy dump`` of the process.
+
+        Args:
+            token: Admin token, sent as ``Authorization: Bearer <token>``
+            prefix: Mount point for the endpoints
+            py_spy: Expose the py-spy endpoint (``py-spy`` must be installed)
+        """
+        if not token:
+            raise ValueError("token must not be empty")
+        self._profiling = (token, prefix, py_spy)
+
+    def enable_metrics(
+        self,
+        path: str = "/metrics",
+        labels: List[str] | None = None,
+        max_series: int = 1000,
+    ) -> None:
+        """
+        Serve request counts and latency histograms for Prometheus at ``path``.
+
+        Series are labelled by route pattern (``route="/users/{id}"``), never
+        by raw path; requests matching no route share ``__unmatched__``.
+        ``labels`` is the allowlist of labels to attach, from ``method``,
+        ``route``, ``status`` and ``status_class`` (default: the first three).
+        Once ``max_series`` label sets exist, new ones are counted in a single
+        ``__overflow__`` series, so a misbehaving label cannot exhaust the
+        scraper.
+
+        Query cache operations (``pyvectora_cache_operations_total`` by
+        ``cache`` and ``outcome``: hit, miss, eviction, invalidation) and rate
+        limiter decisions (``pyvectora_rate_limit_decisions_total`` by
+        ``rule`` and ``decision``) are exported too, for tuning TTLs and limits.
+        """
+        allowed = ("method", "route", "status", "status_class")
+        unknown = [label for label in labels or [] if label not in allowed]
+        if unknown:
+            raise ValueError(
+                f"Unknown metrics labels {unknown} (expected some of: {', '.join(allowed)})"
+            )
+        if max_series < 1:
+            raise ValueError("max_series must be at least 1")
+        self._metrics = {"path": path, "labels": labels, "max_series": max_series}
+
+    def read_only(
+        self,
+        enabled: bool = True,
+        allow: List[str] | None = None,
+        databases: bool | None = None,
+    ) -> None:
+        """
+        Refuse ``POST``, ``PUT``, ``PATCH`` and ``DELETE`` requests with 503.
+
+        Meant for migrations and incident response. Routes whose path
+        pattern is in ``allow`` keep accepting writes (e.g. the endpoint
+        turning the mode off again). With ``databases=True`` every database
+        pool also runs its statements in read-only transactions, so writes
+        fail in the database itself.
+
+        Can be called while serving to switch the mode on or off, provided
+        it was configured before ``serve()`` (``app.read_only(False)`` arms
+        it without refusing anything). ``allow`` and ``databases`` keep
+        their previous values when omitted.
+
+        Example:
+            app.read_only(False, allow=["/admin/read-only"], databases=True)
+
+            @app.post("/admin/read-only", auth=True)
+            async def toggle(request):
+                app.read_only(request.json()["enabled"])
+        """
+        invalid = [path for path in allow or [] if not path.startswith("/")]
+        if invalid:
+            raise ValueError(f"allow must list route paths starting with '/': {invalid}")
+        previous = self._read_only or {"allow": [], "databases": False}
+        self._read_only = {
+            "enabled": enabled,
+            "allow": list(allow) if allow is not None else previous["allow"],
+            "databases": databases if databases is not None else previous["databases"],
+        }
+        if self.native_app is not None:
+            self.native_app.set_read_only(**self._read_only)
+
+    def enable_admin(
+        self, token: str, databases: dict[str, Any], prefix: str | None = None
+    ) -> None:
+        """
+        Serve an admin data browser over ``databases`` (default prefix ``/_pyvectora/admin``).
+
+        Lists each database's tables, pages through their rows and runs
+        read-only SQL queries, rendered as HTML. Browsers prompt for the
+        token through HTTP Basic auth (any user name); scripts may send
+        ``Authorization: Bearer <token>``. Only available with
+        ``App(debug=True)``, so it never reaches production.
+
+        Args:
+            token: Admin token
+            databases: Display name -> connected ``Database``
+            prefix: Mount point for the pages
+        """
+        if not token:
+            raise ValueError("token must not be empty")
+        if not self.debug:
+            raise ValueError("the admin data browser requires App(debug=True)")
+        self._admin = (token, dict(databases), prefix)
+
+    def enable_error_reporting(
+        self,
+        dsn: str,
+        sample_rate: float = 1.0,
+        environment: str | None = None,
+        release: str | None = None,
+    ) -> None:
+        """
+        Report unhandled handler exceptions and native panics to Sentry.
+
+        Reports are sent from a background thread to any Sentry-compatible
+        ``dsn`` and include the request, matched route, authenticated user
+        (the JWT ``sub`` claim), the traceback and breadcrumbs of recent
+        requests and queries. ``HTTPException`` is not reported.
+
+        Args:
+            dsn: Project DSN (``https://<key>@<host>/<project>``)
+            sample_rate: Share of errors reported, from 0.0 to 1.0
+            environment: Environment tag (e.g. ``"production"``)
+            release: Release tag (e.g. a version or commit)
+        """
+        if not dsn:
+            raise ValueError("dsn must not be empty")
+        if not 0.0 <= sample_rate <= 1.0:
+            raise ValueError("sample_rate must be between 0.0 and 1.0")
+        self._error_reporting = {
+            "dsn": dsn,
+            "sample_rate": sample_rate,
+            "environment": environment,
+            "release": release,
+        }
+
+    def enable_rust_extension(self, name: str, options: dict[str, Any] | None = None) -> None:
+        """
+        Enable a compiled (Rust) extension linked into the native module.
+
+        See ``pyvectora.rust_extensions()`` for the available names. Unknown
+        names or invalid options raise ``ConfigurationError`` when the app
+        is built.
+
+        Args:
+            name: Registry name of the extension
+            options: JSON-compatible options passed to the extension
+        """
+        self._rust_extensions.append((name, options))
+
+    def set_memory_limits(
+        self,
+        soft_mb: int | None = None,
+        hard_mb: int | None = None,
+        check_interval: float | None = None,
+    ) -> None:
+        """
+        Protect long-running processes from slow memory leaks.
+
+        Args:
+            soft_mb: RSS above which new requests get 503 until memory drops
+            hard_mb: RSS above which the server shuts down gracefully, to be
+                replaced by the supervisor (``serve(workers=...)``) or a
+                process manager
+            check_interval: Seconds between RSS samples (default: 5)
+        """
+        self._memory_limits = (soft_mb, hard_mb, check_interval)
+
+    def set_max_requests(self, limit: int) -> None:
+        """
+        Shut down gracefully after serving about ``limit`` requests.
+
+        For a process manager that restarts the server; under
+        ``serve(workers=...)`` prefer its ``max_requests``, which replaces
+        one worker at a time.
+        """
+        if limit < 1:
+            raise ValueError("limit must be at least 1")
+        self._max_requests = limit
+
+    def enable_startup_banner(self) -> None:
+        """
+        Log a startup summary when the server starts.
+
+        Lists the bound address, runtime worker threads, the middleware
+        stack and every route with its auth flag, to check a 404 against
+        what was actually registered.
+        """
+        self._startup_banner = True
+
+    def warmup(self, requests: List[tuple]) -> None:
+        """
+        Send requests through the app before it reports ready.
+
+        They run in-process (no network) after the startup handlers, so
+        imports, caches and prepared statements are warmed before ``/ready``
+        answers 200. Each entry is ``(method, path)`` or
+        ``(method, path, headers)``; failures are logged, not raised.
+
+        Example:
+            app.warmup([("GET", "/heavy"), ("GET", "/search?q=a")])
+        """
+        methods = ("GET", "POST", "PUT", "DELETE", "PATCH", "HEAD", "OPTIONS")
+        warmup: List[tuple[str, str, dict[str, str] | None]] = []
+        for entry in requests:
+            if len(entry) not in (2, 3):
+                raise ValueError(f"Warm-up request {entry!r} must be (method, path[, headers])")
+            method, path = entry[0].upper(), entry[1]
+            if method not in methods:
+                raise ValueError(f"Unknown warm-up method {entry[0]!r}")
+            if not path.startswith("/"):
+                raise ValueError(f"Warm-up path {path!r} must start with '/'")
+            warmup.append((method, path, dict(entry[2]) if len(entry) == 3 else None))
+        self._warmup = warmup
+
+    def _run_warmup(self, native_app: Any, requests: List[tuple]) -> None:
+        """Send the warm-up requests through the in-process test path."""
+        import time
+
+        server = native_app.test_client()
+        for method, path, headers in requests:
+            started = time.perf_counter()
+            try:
+                resp = server.test_request(method, path, headers, None)
+            except Exception as e:
+                print(f"⚠️  Warm-up {method} {path} failed: {e}")
+                continue
+            took = (time.perf_counter() - started) * 1000
+            if resp.status >= 500:
+                print(f"⚠️  Warm-up {method} {path} answered {resp.status} ({took:.1f}ms)")
+        print(f"✅ {len(self._warmup)} warm-up request(s) completed")
+
+    def set_shutdown_signals(self, signals: List[str]) -> None:
+        """
+        Choose the signals that shut the server down gracefully.
+
+        The default is ``SIGINT``, ``SIGTERM`` and ``SIGHUP`` on Unix and
+        ``CTRL_C``, ``CTRL_BREAK`` and ``CTRL_CLOSE`` on Windows, so
+        containers stopped with ``SIGTERM`` drain in-flight requests instead
+        of being killed after the grace period. Also accepted: ``SIGQUIT``,
+        ``CTRL_SHUTDOWN`` and ``CTRL_LOGOFF``. Signals the platform does not
+        have are ignored; an empty list disables signal handling.
+
+        Example:
+            app.set_shutdown_signals(["SIGINT", "SIGTERM"])
+        """
+        known = (
+            "SIGINT", "SIGTERM", "SIGHUP", "SIGQUIT",
+            "CTRL_C", "CTRL_BREAK", "CTRL_CLOSE", "CTRL_SHUTDOWN", "CTRL_LOGOFF",
+        )
+        unknown = [name for name in signals if name.upper() not in known]
+        if unknown:
+            raise ValueError(
+                f"Unknown shutdown signals {unknown} (expected some of: {', '.join(known)})"
+            )
+        self._shutdown_signals = [name.upper() for name in signals]
+
+    def set_param_mismatch(self, mode: str) -> None:
+        """
+        Choose how typed path parameters that fail to convert are answered.
+
+        Args:
+            mode: ``"stringify"`` (default) passes the raw string to the
+                handler, ``"reject"`` answers 422 with validation errors and
+                ``"not_found"`` answers 404
+        """
+        if mode not in ("stringify", "reject", "not_found"):
+            raise ValueError(f"unknown param mismatch mode: {mode}")
+        self._param_mismatch = mode
+
+    def set_duplicate_routes(self, mode: str) -> None:
+        """
+        Choose how a route registered twice for the same method is handled.
+
+        Args:
+            mode: ``"error"`` (default) raises ``ValueError`` at registration,
+                ``"warn"`` emits a ``RuntimeWarning`` and the later
+                registration replaces the earlier one
+
+        Patterns differing only in parameter names or types
+        (``/users/{id:int}`` and ``/users/{name}``) are the same route.
+        """
+        if mode not in ("error", "warn"):
+            raise ValueError(f"unknown duplicate routes mode: {mode}")
+        self._duplicate_routes = mode
+
+    def set_error_format(self, format: str) -> None:
+        """
+        Choose the body format of framework-generated errors.
+
+        Args:
+            format: ``"json"`` (default) keeps ``{"error": ...}`` bodies,
+                ``"problem"`` answers 404, 413, 422, 429, 500 and the other
+                built-in errors with RFC 7807 ``application/problem+json``
+                (``type``, ``title``, ``status``, ``detail``, ``instance``)
+        """
+        if format not in ("json", "problem"):
+            raise ValueError(f"unknown error format: {format}")
+        self._error_format = format
+
+    def set_response_policy(
+        self,
+        strip_server: bool = True,
+        utf8_text: bool = True,
+        forbid_powered_by: bool = True,
+    ) -> None:
+        """
+        Sanitize every response after all middleware has run.
+
+        Applies to handler responses and framework-generated ones alike
+        (404, 401, 413...), so the security posture does not depend on every
+        handler doing the right thing. Off until called.
+
+        Args:
+            strip_server: Remove ``Server`` headers
+            utf8_text: Add ``charset=utf-8`` to ``text/*`` content types
+                that declare no charset
+            forbid_powered_by: Remove ``X-Powered-By`` headers
+        """
+        self._response_policy = {
+            "strip_server": strip_server,
+            "utf8_text": utf8_text,
+            "forbid_powered_by": forbid_powered_by,
+        }
+
+    def enable_resource_accounting(self, path: str | None = None) -> None:
+        """
+        Record wall time, GIL-held time and allocations per route.
+
+        Args:
+            path: Optional endpoint serving the per-route stats as JSON
+        """
+        self._resource_accounting = True
+        self._resource_stats_path = path
+
+    def resource_stats(self) -> list[dict[str, Any]]:
+        """Per-route handler cost, most expensive route first."""
+        if self.native_app is None:
+            return []
+        return self.native_app.resource_stats()
+
+    def set_gil_warning_threshold(self, millis: float | None) -> None:
+        """
+        Warn when a sync handler holds the GIL longer than ``millis`` (default: 50).
+
+        Pass ``None`` to disable the warning; GIL time is still measured.
+        """
+        self._gil_warning_threshold = millis
+
+    def gil_stats(self) -> dict[str, Any]:
+        """GIL hold-time counters across all handlers."""
+        if self.native_app is None:
+            return {}
+        return self.native_app.gil_stats()
+
+    def register_serializer(self, type_: type, fn: Callable[[Any], Any] | None = None):
+        """
+        Convert handler return values of ``type_`` (or a subclass) with ``fn``.
+
+        ``fn`` may return a ``Response``, ``str``, ``dict`` or ``list``.
+        Can be used as a decorator when ``fn`` is omitted.
+        """
+        if fn is None:
+            def decorator(func: Callable[[Any], Any]) -> Callable[[Any], Any]:
+                self._serializers.append((type_, func))
+                return func
+            return decorator
+        self._serializers.append((type_, fn))
+        return fn
+
+    def exception_handler(
+        self, exc_type: type[BaseException], handler: Callable[..., Any] | None = None
+    ):
+        """
+        Answer ``exc_type`` (or a subclass) raised by a handler with ``handler``.
+
+        ``handler(request, exc)`` may be async and returns anything a route
+        handler can. Exceptions without a handler get the default response.
+        Can be used as a decorator when ``handler`` is omitted.
+
+        Example:
+            @app.exception_handler(OutOfStock)
+            def out_of_stock(request, exc):
+                return Response.json({"error": str(exc)}, status=409)
+        """
+        if handler is None:
+            def decorator(func: Callable[..., Any]) -> Callable[..., Any]:
+                self._exception_handlers.append((exc_type, func))
+                return func
+            return decorator
+        self._exception_handlers.append((exc_type, handler))
+        return handler
+
+    def enable_sitemap(
+        self,
+        base_url: str,
+        path: str = "/sitemap.xml",
+        max_urls: int = 50000,
+        exclude: List[str] | None = None,
+    ) -> None:
+        """
+        Serve a sitemap built from public GET routes and registered generators.
+
+        Routes with path parameters or ``auth=True`` are skipped; add their
+        URLs with ``@app.sitemap_generator``. Beyond ``max_urls`` entries the
+        sitemap becomes an index of ``?page=N`` pages.
+        """
+        self._sitemap = Sitemap(base_url, path=path, max_urls=max_urls, exclude=exclude or ())
+
+    def sitemap_generator(self, func: Callable[[], Any]) -> Callable[[], Any]:
+        """
+        Register a (sync or async) generator of sitemap entries.
+
+        Entries may be paths, absolute URLs, ``SitemapURL`` objects or dicts
+        with ``loc``, ``lastmod``, ``changefreq`` and ``priority``. Requires
+        ``enable_sitemap()`` to be called first.
+        """
+        if self._sitemap is None:
+            raise RuntimeError("Call enable_sitemap() before registering sitemap generators")
+        return self._sitemap.add_generator(func)
+
+    def on_bad_request(self, func: Callable[[str, str], Any]) -> Callable[[str, str], Any]:
+        """
+        Decorator customizing the 400 response for unparseable requests.
+
+        The handler is called as ``func(kind, message)`` where ``kind`` is
+        ``"invalid_header"``, ``"body_aborted"``, ``"invalid_encoding"`` or
+        ``"too_many_query_params"``.
+        It must be synchronous and may return a response (or a dict for
+        JSON); returning ``None`` keeps the default "Bad Request".
+
+        Example:
+            @app.on_bad_request
+            def bad_request(kind, message):
+                return Response.json({"error": kind}, status=400)
+        """
+        self._bad_request_handler = func
+        return func
+
     def use_middleware(self, middleware: Any) -> None:
-        """Register a Python middleware object or function."""
+        """
+        Register a Python middleware object or function.
+
+        ``before_request`` may change the request for the handler
+        (``request.set_header(...)``, ``request.path = ...``); a rewritten
+        path is routed again.
+        """
         self._python_middlewares.append(middleware)
 
-    def route(self, path: str, methods: List[str] = ["GET"], auth: bool = False):
-        """Decorator to register a route."""
+    def register_extension(self, ext: Any) -> Any:
+        """
+        Plug a reusable extension into the app.
+
+        Calls ``ext.configure(app)`` now, runs ``ext.startup(app)`` with the
+        startup handlers and ``ext.shutdown(app)`` with the shutdown handlers,
+        and adds its ``before_request``/``after_response`` hooks to the
+        middleware pipeline. See ``pyvectora.Extension``.
+
+        Example:
+            app.register_extension(AdminPanel(path="/admin"))
+
+        Raises:
+            ConfigurationError: If an extension with the same name is registered
+        """
+        from functools import partial
+        from . import ConfigurationError
+        from .extension import ExtensionMiddleware, defined_hook, extension_name
+
+        name = extension_name(ext)
+        if name in self._extensions:
+            raise ConfigurationError(f"Extension '{name}' is already registered")
+        self._extensions[name] = ext
+
+        configure = defined_hook(ext, "configure")
+        if configure is not None:
+            configure(self)
+        startup = defined_hook(ext, "startup")
+        if startup is not None:
+            self._startup_handlers.append(partial(startup, self))
+        shutdown = defined_hook(ext, "shutdown")
+        if shutdown is not None:
+            self._shutdown_handlers.append(partial(shutdown, self))
+        hooks = ExtensionMiddleware(ext)
+        if hooks.has_hooks:
+            self._python_middlewares.append(hooks)
+        return ext
+
+    @property
+    def extensions(self) -> dict[str, Any]:
+        """Registered extensions by name."""
+        return dict(self._extensions)
+
+    def group(self, prefix: str, auth: bool = False, middleware: List[Any] | None = None) -> RouteGroup:
+        """
+        Create a route group sharing a path prefix, middleware and auth flag.
+
+        Example:
+            api = app.group("/api/v1", auth=True, middleware=[audit_log])
+
+            @api.get("/users")
+            async def list_users(): ...
+        """
+        return RouteGroup(self, prefix, auth=auth, middleware=middleware)
+
+    def resource(
+        self, path: str, table: str, db: Any, auth: bool = False, **options: Any
+    ) -> Resource:
+        """
+        Generate list/get/create/update/delete routes for a database table.
+
+        ``options`` are passed to ``Resource`` (``fields``, ``filters``,
+        ``page_size``, ``validate``, ...).
+
+        Example:
+            app.resource("/users", table="users", db=db, fields=["name", "email"])
+        """
+        resource = Resource(path, table, db, **options)
+        for method, route_path, handler in resource.routes():
+            self._add_route(Route(method, route_path, handler, auth))
+        return resource
+
+    def route(
+        self,
+        path: str,
+        methods: List[str] = ["GET"],
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        stream: bool = False,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        parse_json: bool = False,
+        deprecated_after: date | str | None = None,
+    ):
+        """
+        Decorator to register a route.
+
+        ``requires`` is checked before the handler runs: ``content_type``
+        (a media type or list of them) answers 415 when not matched, and
+        ``headers`` (a list of names) answers 400 when any is missing.
+
+        With ``stream=True`` the body is not read before the handler runs
+        (nor limited by ``max_body_size``); the handler consumes it with
+        ``async for chunk in request.stream()``.
+
+        ``max_body`` (bytes) and ``timeout`` (seconds, answered with 504)
+        override ``set_body_limit`` and ``set_handler_timeout`` for this route.
+
+        With ``parse_json=True`` the body is parsed in Rust and passed as the
+        handler's second argument (``async def create(request, body)``);
+        an empty or invalid body is answered 400 without calling it.
+
+        ``deprecated_after`` (a date or ``YYYY-MM-DD``) is the last day the
+        route is served: responses announce it with ``Deprecation`` and
+        ``Sunset`` headers, and ``set_deprecation(gone=...)`` retires it with
+        410 afterwards.
+
+        Example:
+            @app.post("/orders", requires={"content_type": "application/json",
+                                           "headers": ["X-Tenant-Id"]})
+            async def create_order(request): ...
+        """
         def decorator(handler):
             for method in methods:
-                self._routes.append(Route(method.upper(), path, handler, auth))
+                self._add_route(Route(
+                    method.upper(), path, handler, auth, requires=requires, stream=stream,
+                    max_body=max_body, timeout=timeout, parse_json=parse_json,
+                    deprecated_after=deprecated_after,
+                ))
             return handler
         return decorator
 
-    def get(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def get(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("GET", path, handler, auth))
+            self._add_route(Route(
+                "GET", path, handler, auth, requires=requires,
+                max_body=max_body, timeout=timeout, deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["GET"], auth)
+        return self.route(
+            path, ["GET"], auth, requires, max_body=max_body, timeout=timeout,
+            deprecated_after=deprecated_after,
+        )
 
-    def post(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def post(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        stream: bool = False,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        parse_json: bool = False,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("POST", path, handler, auth))
+            self._add_route(Route(
+                "POST", path, handler, auth, requires=requires, stream=stream,
+                max_body=max_body, timeout=timeout, parse_json=parse_json,
+                deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["POST"], auth)
+        return self.route(
+            path, ["POST"], auth, requires, stream, max_body, timeout, parse_json,
+            deprecated_after,
+        )
 
-    def put(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def put(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        stream: bool = False,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        parse_json: bool = False,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("PUT", path, handler, auth))
+            self._add_route(Route(
+                "PUT", path, handler, auth, requires=requires, stream=stream,
+                max_body=max_body, timeout=timeout, parse_json=parse_json,
+                deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["PUT"], auth)
+        return self.route(
+            path, ["PUT"], auth, requires, stream, max_body, timeout, parse_json,
+            deprecated_after,
+        )
 
-    def delete(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def delete(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("DELETE", path, handler, auth))
+            self._add_route(Route(
+                "DELETE", path, handler, auth, requires=requires,
+                max_body=max_body, timeout=timeout, deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["DELETE"], auth)
+        return self.route(
+            path, ["DELETE"], auth, requires, max_body=max_body, timeout=timeout,
+            deprecated_after=deprecated_after,
+        )
 
-    def patch(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def patch(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        stream: bool = False,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        parse_json: bool = False,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("PATCH", path, handler, auth))
+            self._add_route(Route(
+                "PATCH", path, handler, auth, requires=requires, stream=stream,
+                max_body=max_body, timeout=timeout, parse_json=parse_json,
+                deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["PATCH"], auth)
+        return self.route(
+            path, ["PATCH"], auth, requires, stream, max_body, timeout, parse_json,
+            deprecated_after,
+        )
 
-    def head(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def head(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("HEAD", path, handler, auth))
+            self._add_route(Route(
+                "HEAD", path, handler, auth, requires=requires,
+                max_body=max_body, timeout=timeout, deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["HEAD"], auth)
+        return self.route(
+            path, ["HEAD"], auth, requires, max_body=max_body, timeout=timeout,
+            deprecated_after=deprecated_after,
+        )
 
-    def options(self, path: str, handler: Callable | None = None, auth: bool = False):
+    def options(
+        self,
+        path: str,
+        handler: Callable | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        deprecated_after: date | str | None = None,
+    ):
         if handler:
-            self._routes.append(Route("OPTIONS", path, handler, auth))
+            self._add_route(Route(
+                "OPTIONS", path, handler, auth, requires=requires,
+                max_body=max_body, timeout=timeout, deprecated_after=deprecated_after,
+            ))
             return handler
-        return self.route(path, ["OPTIONS"], auth)
+        return self.route(
+            path, ["OPTIONS"], auth, requires, max_body=max_body, timeout=timeout,
+            deprecated_after=deprecated_after,
+        )
+
+    def add_route_runtime(
+        self,
+        method: str,
+        path: str,
+        handler: Callable,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+        stream: bool = False,
+        max_body: int | None = None,
+        timeout: float | None = None,
+        parse_json: bool = False,
+        deprecated_after: date | str | None = None,
+    ) -> None:
+        """
+        Register a route, including on the server if it is already serving.
+
+        Lets plugins add endpoints after ``serve()`` started; requests
+        arriving afterwards are routed to ``handler``.
+        """
+        route = Route(
+            method.upper(), path, handler, auth, requires=requires, stream=stream,
+            max_body=max_body, timeout=timeout, parse_json=parse_json,
+            deprecated_after=deprecated_after,
+        )
+        if self.native_app is not None:
+            self.native_app.add_route_runtime(
+                route.method, path, handler, auth=auth, requires=requires, stream=stream,
+                max_body=max_body, timeout=timeout, parse_json=parse_json,
+                deprecated_after=route.deprecated_after,
+            )
+        self._add_route(route)
+
+    def canary(
+        self,
+        method: str,
+        path: str,
+        handler: Callable | None = None,
+        percent: int = 5,
+        sticky_cookie: str | None = None,
+        sticky_header: str | None = None,
+        cookie: str = "pv_canary",
+        ramp: float | None = None,
+    ):
+        """
+        Serve ``percent`` of the traffic of the ``method path`` route with ``handler``.
+
+        Releases a new implementation of one endpoint gradually. A client's
+        assignment is sticky: it is derived from ``sticky_cookie`` or
+        ``sticky_header`` (e.g. a session cookie or ``X-User-Id``) when the
+        request carries it, else remembered in the ``cookie`` cookie. With
+        ``ramp`` (seconds) the share grows from 0 to ``percent`` from when
+        the app starts serving. The route must already be registered.
+
+        Example:
+            @app.canary("GET", "/search", percent=10, sticky_header="X-User-Id")
+            async def search_v2(request): ...
+        """
+        if not 0 <= percent <= 100:
+            raise ValueError("percent must be between 0 and 100")
+        if sticky_cookie is not None and sticky_header is not None:
+            raise ValueError("use sticky_cookie or sticky_header, not both")
+        if ramp is not None and ramp <= 0:
+            raise ValueError("ramp must be positive")
+        method = method.upper()
+        route = next(
+            (r for r in self._routes if r.method == method and r.path == path), None
+        )
+        if route is None:
+            raise ValueError(f"No route {method} {path} to add a canary to")
+
+        def decorator(canary_handler):
+            options: dict[str, Any] = {
+                "handler": canary_handler, "percent": percent, "cookie": cookie,
+            }
+            if sticky_cookie is not None:
+                options["sticky_cookie"] = sticky_cookie
+            if sticky_header is not None:
+                options["sticky_header"] = sticky_header
+            if ramp is not None:
+                options["ramp"] = ramp
+            route.canary = options
+            return canary_handler
+
+        if handler:
+            return decorator(handler)
+        return decorator
+
+    def _add_route(self, route: Route) -> None:
+        """Record ``route``, applying ``set_duplicate_routes`` to a repeated one."""
+        shape = _route_shape(route.path)
+        for existing in self._routes:
+            if existing.method != route.method or _route_shape(existing.path) != shape:
+                continue
+            message = (
+                f"Route {route.method} {route.path} ({_describe(route.handler)}) duplicates "
+                f"{existing.method} {existing.path} ({_describe(existing.handler)})"
+            )
+            if self._duplicate_routes != "warn":
+                raise ValueError(message)
+            warnings.warn(f"{message}; the later one replaces it", RuntimeWarning, stacklevel=3)
+            self._routes.remove(existing)
+            break
+        self._routes.append(route)
+
+    def remove_route(self, method: str, path: str) -> bool:
+        """
+        Unregister the route for ``method`` with exactly ``path``.
+
+        Also removes it from a running server. Returns False if no such
+        route exists.
+        """
+        method = method.upper()
+        found = False
+        for route in list(self._routes):
+            if route.method == method and route.path == path:
+                self._routes.remove(route)
+                found = True
+        if self.native_app is not None:
+            found = self.native_app.remove_route(method, path) or found
+        return found
+
+    def websocket(self, path: str, handler: Callable | None = None):
+        """
+        Register an async WebSocket handler.
+
+        The handler receives a ``WebSocket`` with async ``send``,
+        ``receive`` and ``close`` methods.
+
+        Example:
+            @app.websocket("/ws")
+            async def echo(ws):
+                while (message := await ws.receive()) is not None:
+                    await ws.send(message)
+        """
+        if handler:
+            self._ws_routes.append((path, handler))
+            return handler
+
+        def decorator(handler):
+            self._ws_routes.append((path, handler))
+            return handler
+        return decorator
+
+    def mount_wsgi(self, prefix: str, wsgi_app: Callable) -> None:
+        """
+        Serve a WSGI application for every path under ``prefix``.
+
+        Lets a legacy Flask or Django app keep answering its endpoints while
+        they are ported. Requests run on the blocking thread pool; native
+        routes under the prefix take precedence.
+
+        Example:
+            from legacy import flask_app
+
+            app.mount_wsgi("/legacy", flask_app)
+        """
+        if not prefix.startswith("/"):
+            raise ValueError(f"WSGI mount prefix must start with '/': {prefix}")
+        self._wsgi_mounts.append((prefix, wsgi_app))
 
     def on_startup(self, func: Callable) -> Callable:
         """
@@ -203,16 +1561,29 @@ class App:
         self._ready_handlers.append(func)
         return func
 
+    def on_event(self, event: str) -> Callable[[Callable], Callable]:
+        """
+        Decorator subscribing to a framework event (see ``pyvectora.events``).
+
+        Events are process-wide: the subscriber also sees the events of
+        other apps served by the same process.
+
+        Example:
+            @app.on_event("handler_exception")
+            def report(event):
+                sentry.capture_message(f"{event['exception']}: {event['message']}")
+        """
+        return events.on(event)
+
     async def _execute_handlers(self, handlers: List[Callable]) -> None:
         """Execute a list of handlers (sync or async)."""
         import asyncio
         import inspect
 
         for handler in handlers:
-            if inspect.iscoroutinefunction(handler):
-                await handler()
-            else:
-                handler()
+            result = handler()
+            if inspect.isawaitable(result):
+                await result
 
     @property
     def is_ready(self) -> bool:
@@ -261,7 +1632,7 @@ class App:
 
             wrapped_handler = wrap_handler_with_di(handler_method, guards=all_guards)
 
-            self._routes.append(Route(route_meta.method, full_path, wrapped_handler, auth=is_protected))
+            self._add_route(Route(route_meta.method, full_path, wrapped_handler, auth=is_protected))
             print(f"   └── {route_meta.method} {full_path}")
 
     def register_provider(self, interface: Type[Any], provider_cls: Type[Provider]) -> None:
@@ -281,11 +1652,76 @@ class App:
                 "Native module not available. Run 'maturin develop' to build."
             ) from e
 
-        native_app = NativeApp(self.host, self.port)
+        native_app = NativeApp(self.host, self.port, self.runtime)
+        log_context.install()
+        if self.debug:
+            native_app.set_debug(True)
         if self._jwt_secret:
             native_app.enable_auth(self._jwt_secret)
+        if self._tls:
+            cert_path, key_path, reload_interval = self._tls
+            native_app.enable_tls(cert_path, key_path, reload_interval=reload_interval)
+            for hostname, sni_cert, sni_key in self._tls_sni:
+                native_app.add_tls_certificate(hostname, sni_cert, sni_key)
+        if self._acme_challenges is not None:
+            native_app.enable_acme_http01(self._acme_challenges)
+        if self._acme is not None:
+            native_app.enable_acme(**self._acme)
+        if self._profiling is not None:
+            token, prefix, py_spy = self._profiling
+            native_app.enable_profiling(token, prefix, py_spy)
+        if self._metrics is not None:
+            native_app.enable_metrics(**self._metrics)
+        if self._read_only is not None:
+            native_app.set_read_only(**self._read_only)
+        if self._shutdown_signals is not None:
+            native_app.set_shutdown_signals(self._shutdown_signals)
+        if self._error_reporting is not None:
+            native_app.enable_error_reporting(**self._error_reporting)
+        for name, options in self._rust_extensions:
+            native_app.enable_rust_extension(name, options)
+        if self._admin is not None:
+            token, databases, prefix = self._admin
+            native_dbs = {name: getattr(db, "_db", db) for name, db in databases.items()}
+            native_app.enable_admin(token, native_dbs, prefix)
+        if self._bad_request_handler is not None:
+            native_app.set_bad_request_handler(self._bad_request_handler)
+        if self._http2 is not None:
+            native_app.enable_http2(self._http2)
         if self._max_body_size is not None:
             native_app.set_body_limit(self._max_body_size)
+        if self._handler_timeout is not None:
+            native_app.set_handler_timeout(self._handler_timeout)
+        if self._deprecation is not None:
+            native_app.set_deprecation(**self._deprecation)
+        if self._upload_limits is not None:
+            native_app.set_upload_limits(**self._upload_limits)
+        if self._uri_limits is not None:
+            native_app.set_uri_limits(**self._uri_limits)
+        if self._max_connections is not None:
+            native_app.set_max_connections(self._max_connections)
+        if self.uds is not None:
+            native_app.set_unix_socket(self.uds, self.uds_mode)
+        for address in self._listeners:
+            native_app.add_listener(address)
+        if self._socket_options is not None:
+            native_app.set_socket_options(**self._socket_options)
+        if self._socket_activation:
+            native_app.enable_socket_activation()
+        if self._param_mismatch is not None:
+            native_app.set_param_mismatch(self._param_mismatch)
+        if self._duplicate_routes is not None:
+            native_app.set_duplicate_routes(self._duplicate_routes)
+        if self._error_format is not None:
+            native_app.set_error_format(self._error_format)
+        if self._response_policy is not None:
+            native_app.set_response_policy(**self._response_policy)
+        if self._memory_limits is not None:
+            native_app.set_memory_limits(*self._memory_limits)
+        if self._max_requests is not None:
+            native_app.set_max_requests(self._max_requests)
+        if self._startup_banner:
+            native_app.enable_startup_banner()
 
         for name, cfg in self._middlewares:
             if name == "logging":
@@ -297,16 +1733,36 @@ class App:
                     cfg.get("allow_origin", "*"),
                     cfg.get("allow_methods", "GET, POST, PUT, DELETE, PATCH, OPTIONS"),
                     cfg.get("allow_headers", "Content-Type, Authorization"),
+                    cfg.get("max_age", 600),
+                    cfg.get("allow_credentials", False),
+                    cfg.get("expose_headers"),
                 )
             elif name == "rate_limit":
                 native_app.enable_rate_limit_middleware(
                     cfg.get("capacity", 100),
                     cfg.get("refill_per_sec", 100),
                 )
+            elif name == "tiered_rate_limit":
+                native_app.enable_tiered_rate_limit(**cfg)
+            elif name == "compression":
+                native_app.enable_compression_middleware(
+                    cfg.get("min_size", 1024),
+                    cfg.get("content_types"),
+                )
+            elif name == "session":
+                native_app.enable_session_middleware(**cfg)
 
         for mw in self._python_middlewares:
             native_app.add_python_middleware(mw)
 
+        if self._resource_accounting:
+            native_app.enable_resource_accounting()
+        native_app.set_gil_warning_threshold(self._gil_warning_threshold)
+        for type_, fn in self._serializers:
+            native_app.register_serializer(type_, fn)
+        for exc_type, handler in self._exception_handlers:
+            native_app.register_exception_handler(exc_type, handler)
+
         from .schema import OpenAPIGenerator
         import json
         from .response import Response
@@ -369,25 +1825,165 @@ class App:
                 }
                 return Response.json(health_data)
 
+            async def ready_handler(req):
+                if not self._is_ready:
+                    return Response.json({"status": "starting", "checks": {}}, status=503)
+                result = await self.health.check()
+                return Response.json(result, status=503 if result["status"] == "down" else 200)
+
             native_app.get("/health", make_internal(health_handler))
-            print("❤️  Health check at /health")
+            native_app.get("/ready", make_internal(ready_handler))
+            print("❤️  Health check at /health, readiness at /ready")
+
+        if self._resource_stats_path:
+            def resource_stats_handler(req):
+                return Response.json({"routes": native_app.resource_stats()})
+
+            native_app.get(self._resource_stats_path, make_internal(resource_stats_handler))
+
+        if self._sitemap is not None:
+            from .request import Request
+
+            sitemap = self._sitemap
+            app_routes = list(self._routes)
+
+            async def sitemap_handler(req: Request):
+                page = req.query.get("page")
+                try:
+                    page_number = int(page) if page is not None else None
+                except ValueError:
+                    return Response.json({"error": "Invalid sitemap page"}, status=400)
+                return await sitemap.response(app_routes, page_number)
+
+            native_app.get(sitemap.path, make_internal(sitemap_handler))
 
         for route in self._routes:
+            if (
+                route.middlewares or route.requires or route.stream or route.parse_json
+                or route.max_body is not None or route.timeout is not None
+                or route.deprecated_after is not None or route.canary is not None
+            ):
+                native_app.add_route(
+                    route.method, route.path, route.handler,
+                    auth=route.auth, middlewares=route.middlewares,
+                    requires=route.requires, stream=route.stream,
+                    max_body=route.max_body, timeout=route.timeout,
+                    parse_json=route.parse_json,
+                    deprecated_after=route.deprecated_after, canary=route.canary,
+                )
+                continue
             method = route.method.lower()
             handler_fn = getattr(native_app, method, None)
             if handler_fn:
                 handler_fn(route.path, route.handler, auth=route.auth)
 
-        self.native_app = native_app
+        for path, handler in self._ws_routes:
+            native_app.websocket(path, handler)
+        for prefix, wsgi_app in self._wsgi_mounts:
+            native_app.mount_wsgi(prefix, wsgi_app)
+
         return native_app
 
-    def serve(self):
-        """Start the HTTP server with lifecycle management."""
+    def build(
+        self,
+        workers: int | str = 1,
+        max_requests: int | None = None,
+        heartbeat_timeout: float | None = None,
+    ) -> Application:
+        """
+        Compile the app for serving without starting it.
+
+        Compiles every route, resolves the middleware and checks the
+        configuration, raising ``ConfigurationError`` or ``ValueError`` here
+        rather than when serving. The address, lifecycle handlers and
+        warm-up requests are captured too, so later changes to the app do
+        not affect the result. Takes the same arguments as ``serve``.
+
+        Returns:
+            A frozen ``Application`` to inspect, test or ``run()``
+        """
+        workers = _worker_count(workers)
+        if workers > 1 and self.uds is not None:
+            raise ValueError("A Unix socket cannot be shared by several workers")
+        if workers > 1 and self._socket_activation:
+            raise ValueError("Socket activation serves from a single worker")
+        if workers > 1 and self._acme is not None:
+            raise ValueError("ACME certificates are managed by a single worker")
+
+        native_app = self._build_native_app()
+        native_app.validate()
+        signals = self._shutdown_signals
+        return Application(
+            app=self,
+            native_app=native_app,
+            workers=workers,
+            max_requests=max_requests,
+            heartbeat_timeout=heartbeat_timeout,
+            routes=tuple(f"{route.method} {route.path}" for route in self._routes),
+            middlewares=tuple(name for name, _ in self._middlewares),
+            scheme="https" if self._tls else "http",
+            address=f"unix:{self.uds}" if self.uds is not None else f"{self.host}:{self.port}",
+            listeners=tuple(self._listeners),
+            socket_activation=self._socket_activation,
+            shutdown_signals=None if signals is None else tuple(signals),
+            lifespan=self.lifespan,
+            startup_handlers=tuple(self._startup_handlers),
+            ready_handlers=tuple(self._ready_handlers),
+            shutdown_handlers=tuple(self._shutdown_handlers),
+            warmup=tuple(self._warmup),
+        )
+
+    def serve(
+        self,
+        workers: int | str = 1,
+        max_requests: int | None = None,
+        heartbeat_timeout: float | None = None,
+    ):
+        """
+        Start the HTTP server with lifecycle management.
+
+        Shorthand for ``build(...).run()``.
+
+        Args:
+            workers: Worker processes to run, or ``"auto"`` for one per CPU;
+                above 1 this process becomes a supervisor re-running the
+                current command as each worker, all sharing the port
+                (``SO_REUSEPORT``), so handlers are not limited by one GIL
+                (Unix only). Crashed or hung workers are restarted.
+            max_requests: Replace a worker after it served this many requests
+            heartbeat_timeout: Seconds without a heartbeat before a worker is
+                considered hung (default: 30)
+        """
+        self.build(workers, max_requests, heartbeat_timeout).run()
+
+    def _run(self, application: Application) -> None:
+        """Serve a built application, or supervise its worker processes."""
         import time
         import asyncio
 
-        native_app = self._build_native_app()
-        print(f"🚀 Serving on {self.host}:{self.port}")
+        if application.workers > 1 and "PYVECTORA_WORKER_ID" not in os.environ:
+            from pyvectora.pyvectora_native import supervise
+
+            print(f"🧭 Supervising {application.workers} workers")
+            try:
+                supervise(
+                    [sys.executable, *sys.argv], application.workers,
+                    application.max_requests, application.heartbeat_timeout,
+                    application.shutdown_signals,
+                )
+            except KeyboardInterrupt:
+                print("\n🛑 Shutdown signal received")
+            return
+
+        native_app = application.native_app
+        self.native_app = native_app
+        scheme = application.scheme
+        if application.socket_activation and os.environ.get("LISTEN_PID") == str(os.getpid()):
+            print(f"🚀 Serving {scheme} on sockets passed by systemd")
+        else:
+            print(f"🚀 Serving on {scheme}://{application.address}")
+        for address in application.listeners:
+            print(f"🚀 Also serving on {scheme}://{address}")
 
         try:
             loop = asyncio.get_running_loop()
@@ -395,18 +1991,18 @@ class App:
             loop = asyncio.new_event_loop()
             asyncio.set_event_loop(loop)
 
-        if self._startup_handlers:
+        if application.startup_handlers:
             print("🔄 Running startup handlers...")
             try:
-                loop.run_until_complete(self._execute_handlers(self._startup_handlers))
-                print(f"✅ {len(self._startup_handlers)} startup handler(s) completed")
+                loop.run_until_complete(self._execute_handlers(application.startup_handlers))
+                print(f"✅ {len(application.startup_handlers)} startup handler(s) completed")
             except Exception as e:
                 print(f"❌ Startup handler failed: {e}")
                 raise
 
         ctx = None
-        if self.lifespan:
-            ctx = self.lifespan(self)
+        if application.lifespan:
+            ctx = application.lifespan(self)
             try:
                 loop.run_until_complete(ctx.__aenter__())
                 print("✅ Lifespan: Startup complete")
@@ -414,12 +2010,15 @@ class App:
                 print(f"❌ Lifespan startup failed: {e}")
                 raise
 
+        if application.warmup:
+            self._run_warmup(native_app, application.warmup)
+
         self._startup_time = time.time()
         self._is_ready = True
 
-        if self._ready_handlers:
+        if application.ready_handlers:
             try:
-                loop.run_until_complete(self._execute_handlers(self._ready_handlers))
+                loop.run_until_complete(self._execute_handlers(application.ready_handlers))
             except Exception as e:
                 print(f"⚠️  Ready handler error: {e}")
 
@@ -436,13 +2035,13 @@ class App:
 
             self._is_ready = False
 
-            if self._shutdown_handlers:
+            if application.shutdown_handlers:
                 print("🔄 Running shutdown handlers...")
                 try:
                     loop.run_until_complete(
-                        self._execute_handlers(list(reversed(self._shutdown_handlers)))
+                        self._execute_handlers(list(reversed(application.shutdown_handlers)))
                     )
-                    print(f"✅ {len(self._shutdown_handlers)} shutdown handler(s) completed")
+                    print(f"✅ {len(application.shutdown_handlers)} shutdown handler(s) completed")
                 except Exception as e:
                     print(f"❌ Shutdown handler error: {e}")
 
@@ -454,8 +2053,48 @@ class App:
 
             print("👋 Server stopped")
 
-    def test_client(self):
-        """Return a Zero-Network TestClient for this app."""
+    def test_client(self, raise_limit_errors: bool = False):
+        """
+        Return a Zero-Network TestClient for this app.
+
+        With ``raise_limit_errors``, requests over the body or URI limits
+        raise ``PayloadTooLarge``, ``UriTooLong`` or ``TooManyQueryParams``.
+        """
         from .test_client import TestClient
-        self._build_native_app()
-        return TestClient(self)
+        self.native_app = self._build_native_app()
+        return TestClient(self, raise_limit_errors=raise_limit_errors)
+
+    def export_routes(self, path: str | None = None) -> str:
+        """
+        Export the compiled route table as JSON.
+
+        Args:
+            path: Optional file to write the table to
+
+        Returns:
+            The route table JSON
+        """
+        table = self._build_native_app().export_routes()
+        if path is not None:
+            with open(path, "w", encoding="utf-8") as f:
+                f.write(table)
+        return table
+
+    def check_routes(self, baseline_path: str, allow_removed: bool = False) -> dict[str, list[str]]:
+        """
+        Diff the current routes against a previously exported table.
+
+        Intended for CI: raises ``RuntimeError`` when endpoints present in the
+        baseline are missing, unless ``allow_removed`` is set.
+
+        Returns:
+            Dict with ``added``, ``removed`` and ``changed`` ``"METHOD /path"`` keys
+        """
+        from pyvectora.pyvectora_native import diff_route_tables
+
+        with open(baseline_path, encoding="utf-8") as f:
+            baseline = f.read()
+        diff = diff_route_tables(baseline, self.export_routes())
+        if diff["removed"] and not allow_removed:
+            raise RuntimeError(f"Routes removed since baseline: {', '.join(diff['removed'])}")
+        return diff
@@ -0,0 +1,58 @@
+"""
+PyVectora Application - A built app, frozen and ready to run.
+
+``App.build()`` compiles the routes and checks the configuration once, so
+mistakes surface before anything is served; the ``Application`` it returns
+can be inspected, tested and run any number of times.
+"""
+
+from __future__ import annotations
+
+from dataclasses import dataclass
+from typing import Any, Callable
+
+
+@dataclass(frozen=True)
+class Application:
+    """
+    An ``App`` compiled for serving.
+
+    Holds the native app and a snapshot of the routes, middleware, address,
+    lifecycle handlers and warm-up requests taken at build time; later
+    changes to the ``App`` need a new ``build()``.
+
+    Example:
+        application = app.build(workers=4)
+        print(application.routes)
+        application.run()
+    """
+    app: Any
+    native_app: Any
+    workers: int = 1
+    max_requests: int | None = None
+    heartbeat_timeout: float | None = None
+    routes: tuple[str, ...] = ()
+    middlewares: tuple[str, ...] = ()
+    scheme: str = "http"
+    address: str = ""
+    listeners: tuple[str, ...] = ()
+    socket_activation: bool = False
+    shutdown_signals: tuple[str, ...] | None = None
+    lifespan: Callable[[Any], Any] | None = None
+    startup_handlers: tuple[Callable, ...] = ()
+    ready_handlers: tuple[Callable, ...] = ()
+    shutdown_handlers: tuple[Callable, ...] = ()
+    warmup: tuple[tuple[str, str, dict[str, str] | None], ...] = ()
+
+    def run(self) -> None:
+        """Serve until shutdown (blocks); may be called again afterwards."""
+        self.app._run(self)
+
+    def test_client(self, raise_limit_errors: bool = False):
+        """Return a Zero-Network TestClient for the built app."""
+        from .test_client import TestClient
+        return TestClient(self, raise_limit_errors=raise_limit_errors)
+
+    def export_routes(self) -> str:
+        """The compiled route table as JSON."""
+        return self.native_app.export_routes()
@@ -0,0 +1,85 @@
+"""
+PyVectora Events - framework events for APM and logging integrations.
+
+The Rust core reports what it does on a process-wide event bus:
+
+    request_started    request_id, method, path
+    request_finished   request_id, method, path, route, status, duration_ms
+    handler_exception  request_id, method, path, route, user, exception, message,
+                       frames (filename, function, lineno, context_line)
+    ws_connected       path
+    db_query           sql, duration_ms, rows, error (only on failure)
+    cache              cache, outcome (hit, miss, eviction, invalidation), count
+    rate_limit         rule (the tier, or "default"), allowed
+
+Subscribers get one dict per event (with an ``"event"`` key) and run on
+the server thread that emitted it, so they should record the event and
+return quickly. Exceptions they raise are logged and ignored.
+
+Example:
+    from pyvectora import events
+
+    @events.on("request_finished")
+    def record(event):
+        # The route pattern ("GET /users/{id}"), not the raw path
+        metrics.observe(event.get("route"), event["status"], event["duration_ms"])
+"""
+
+from __future__ import annotations
+
+import inspect
+from typing import Any, Callable, Dict
+
+try:
+    from .pyvectora_native import subscribe_event as _subscribe_event
+    from .pyvectora_native import unsubscribe_event as _unsubscribe_event
+except ImportError:
+    _subscribe_event = None
+    _unsubscribe_event = None
+
+Subscriber = Callable[[Dict[str, Any]], Any]
+
+EVENTS = (
+    "request_started",
+    "request_finished",
+    "handler_exception",
+    "ws_connected",
+    "db_query",
+    "cache",
+    "rate_limit",
+)
+
+
+def subscribe(event: str, callback: Subscriber) -> int:
+    """
+    Call ``callback(payload)`` for every ``event``.
+
+    Returns the subscription id for ``unsubscribe``.
+    """
+    if event not in EVENTS:
+        raise ValueError(f"Unknown event {event!r} (expected one of: {', '.join(EVENTS)})")
+    if inspect.iscoroutinefunction(callback):
+        raise TypeError("Event subscribers must be synchronous callables")
+    if _subscribe_event is None:
+        raise RuntimeError("Native module not available. Run 'maturin develop' to build.")
+    return _subscribe_event(event, callback)
+
+
+def unsubscribe(subscription: int) -> bool:
+    """Remove a subscription; returns False if it was already removed."""
+    if _unsubscribe_event is None:
+        return False
+    return _unsubscribe_event(subscription)
+
+
+def on(event: str) -> Callable[[Subscriber], Subscriber]:
+    """Decorator form of ``subscribe``."""
+
+    def decorator(callback: Subscriber) -> Subscriber:
+        subscribe(event, callback)
+        return callback
+
+    return decorator
+
+
+__all__ = ["EVENTS", "on", "subscribe", "unsubscribe"]
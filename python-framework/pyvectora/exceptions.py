@@ -0,0 +1,66 @@
+"""
+HTTP exceptions raised from handlers and middleware.
+
+The server answers a raised ``HTTPException`` directly with its status,
+a ``{"detail": ...}`` JSON body and its headers, instead of a 500.
+"""
+
+from __future__ import annotations
+
+from http import HTTPStatus
+from typing import Any
+
+try:
+    from pyvectora.pyvectora_native import HTTPException as _NativeHTTPException
+except ImportError:
+    _NativeHTTPException = Exception  # type: ignore
+
+try:
+    from pyvectora.pyvectora_native import RequestValidationError
+except ImportError:
+
+    class RequestValidationError(ValueError):  # type: ignore[no-redef]
+        """Invalid request input (answered 422); ``errors`` lists the field errors."""
+
+        def __init__(self, message: str, errors: list[dict[str, str]] | None = None) -> None:
+            super().__init__(message)
+            self.errors = errors or []
+
+
+class HTTPException(_NativeHTTPException):  # type: ignore[misc, valid-type]
+    """
+    Answer the current request with an HTTP error.
+
+    Example:
+        @app.get("/items/{id:int}")
+        async def get_item(id: int):
+            if id not in items:
+                raise HTTPException(404, "Item not found")
+            return items[id]
+
+    Args:
+        status: HTTP status code
+        detail: JSON-compatible detail (default: the status' reason phrase)
+        headers: Extra response headers
+    """
+
+    def __init__(
+        self, status: int, detail: Any = None, headers: dict[str, str] | None = None
+    ) -> None:
+        if not 100 <= status <= 599:
+            raise ValueError(f"invalid HTTP status code {status}")
+        if detail is None:
+            try:
+                detail = HTTPStatus(status).phrase
+            except ValueError:
+                detail = "Error"
+        super().__init__(status, detail)
+        self.status = status
+        self.detail = detail
+        self.headers = headers
+
+    def __str__(self) -> str:
+        return str(self.detail)
+
+    def __repr__(self) -> str:
+        return f"HTTPException(status={self.status}, detail={self.detail!r})"
@@ -0,0 +1,90 @@
+"""
+Extensions - reusable packages that plug into an App.
+
+An extension bundles routes, middleware and lifecycle code (auth providers,
+admin panels, metrics exporters) behind one ``app.register_extension(ext)``
+call. Its lifecycle is:
+
+1. ``configure(app)`` - at registration: add routes, middleware, settings
+2. ``startup(app)`` - before the server accepts requests
+3. ``before_request(request)`` / ``after_response(request, response)`` -
+   per request, in the Rust middleware pipeline (sync, like middleware)
+4. ``shutdown(app)`` - when the server stops, in reverse registration order
+
+Every hook is optional; per-request hooks only cost a call when defined.
+"""
+
+from __future__ import annotations
+
+from typing import TYPE_CHECKING, Any
+
+if TYPE_CHECKING:
+    from .app import App
+
+REQUEST_HOOKS = ("before_request", "after_response")
+
+
+class Extension:
+    """
+    Base class for extensions. Override the hooks you need.
+
+    Example:
+        class RequestIdHeader(Extension):
+            name = "request-id-header"
+
+            def after_response(self, request, response):
+                response.headers["X-Served-By"] = "api-1"
+                return response
+
+        app.register_extension(RequestIdHeader())
+    """
+
+    #: Unique name; registering two extensions with one name is an error
+    name: str = ""
+
+    def configure(self, app: App) -> None:
+        """Register routes, middleware and settings on ``app``."""
+
+    async def startup(self, app: App) -> None:
+        """Prepare resources before requests are served."""
+
+    async def shutdown(self, app: App) -> None:
+        """Release resources once the server stopped."""
+
+    def before_request(self, request: Any) -> Any:
+        """Change the request, or return a Response to answer it without the handler."""
+        return None
+
+    def after_response(self, request: Any, response: Any) -> Any:
+        """Return a Response to replace the handler's response."""
+        return None
+
+
+def extension_name(ext: Any) -> str:
+    """The extension's ``name``, or its class name when unset."""
+    return getattr(ext, "name", "") or type(ext).__name__
+
+
+def defined_hook(ext: Any, hook: str) -> Any:
+    """The bound ``hook`` of ``ext``, or None when missing or not overridden."""
+    method = getattr(ext, hook, None)
+    if method is None:
+        return None
+    base = getattr(Extension, hook, None)
+    if base is not None and getattr(type(ext), hook, None) is base:
+        return None
+    return method
+
+
+class ExtensionMiddleware:
+    """Exposes only the request hooks an extension defines to the pipeline."""
+
+    def __init__(self, ext: Any) -> None:
+        for hook in REQUEST_HOOKS:
+            method = defined_hook(ext, hook)
+            if method is not None:
+                setattr(self, hook, method)
+
+    @property
+    def has_hooks(self) -> bool:
+        return any(hasattr(self, hook) for hook in REQUEST_HOOKS)
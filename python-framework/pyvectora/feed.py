@@ -0,0 +1,213 @@
+"""
+PyVectora Feeds - RSS 2.0 and Atom feed generation.
+
+Builds feed documents from a list of entries with proper XML escaping and
+serves them through the regular ``Response`` pipeline with caching headers.
+
+Example:
+    feed = Feed(title="Blog", link="https://example.com", description="Posts")
+    feed.add_entry(FeedEntry(title="Hello", link="https://example.com/hello",
+                             updated=datetime.now(timezone.utc)))
+
+    @app.get("/feed.xml")
+    def rss(request):
+        return feed.response("rss", request=request)
+"""
+
+from __future__ import annotations
+
+import dataclasses
+import hashlib
+from datetime import datetime, timezone
+from email.utils import format_datetime
+from typing import Any, List
+from xml.sax.saxutils import escape, quoteattr
+
+from .response import Response
+
+RSS_CONTENT_TYPE = "application/rss+xml; charset=utf-8"
+ATOM_CONTENT_TYPE = "application/atom+xml; charset=utf-8"
+
+
+@dataclasses.dataclass
+class FeedEntry:
+    """A single feed item."""
+
+    title: str
+    link: str
+    updated: datetime
+    id: str | None = None
+    summary: str | None = None
+    content: str | None = None
+    author: str | None = None
+    categories: List[str] = dataclasses.field(default_factory=list)
+
+    @property
+    def guid(self) -> str:
+        """Stable identifier (defaults to the link)."""
+        return self.id or self.link
+
+
+def _utc(dt: datetime) -> datetime:
+    if dt.tzinfo is None:
+        return dt.replace(tzinfo=timezone.utc)
+    return dt.astimezone(timezone.utc)
+
+
+def _rfc3339(dt: datetime) -> str:
+    return _utc(dt).strftime("%Y-%m-%dT%H:%M:%SZ")
+
+
+def _rfc822(dt: datetime) -> str:
+    return format_datetime(_utc(dt), usegmt=True)
+
+
+class Feed:
+    """
+    Feed metadata plus entries, renderable as RSS 2.0 or Atom.
+
+    Entries are rendered newest first. The feed's last-updated time is the
+    newest entry's ``updated`` (or ``updated`` passed explicitly).
+    """
+
+    def __init__(
+        self,
+        title: str,
+        link: str,
+        description: str = "",
+        feed_url: str | None = None,
+        language: str | None = None,
+        author: str | None = None,
+        updated: datetime | None = None,
+        entries: List[FeedEntry] | None = None,
+    ) -> None:
+        self.title = title
+        self.link = link
+        self.description = description
+        self.feed_url = feed_url
+        self.language = language
+        self.author = author
+        self._updated = updated
+        self.entries: List[FeedEntry] = list(entries or [])
+
+    def add_entry(self, entry: FeedEntry) -> None:
+        """Append an entry."""
+        self.entries.append(entry)
+
+    @property
+    def updated(self) -> datetime:
+        """Last-updated time of the feed."""
+        if self._updated is not None:
+            return _utc(self._updated)
+        if self.entries:
+            return max(_utc(e.updated) for e in self.entries)
+        return datetime.now(timezone.utc)
+
+    def _sorted_entries(self) -> List[FeedEntry]:
+        return sorted(self.entries, key=lambda e: _utc(e.updated), reverse=True)
+
+    def to_rss(self) -> str:
+        """Render an RSS 2.0 document."""
+        parts = [
+            '<?xml version="1.0" encoding="utf-8"?>',
+            '<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">',
+            "<channel>",
+            f"<title>{escape(self.title)}</title>",
+            f"<link>{escape(self.link)}</link>",
+            f"<description>{escape(self.description)}</description>",
+            f"<lastBuildDate>{_rfc822(self.updated)}</lastBuildDate>",
+        ]
+        if self.feed_url:
+            parts.append(
+                f'<atom:link href={quoteattr(self.feed_url)} rel="self" type="application/rss+xml"/>'
+            )
+        if self.language:
+            parts.append(f"<language>{escape(self.language)}</language>")
+
+        for entry in self._sorted_entries():
+            parts.append("<item>")
+            parts.append(f"<title>{escape(entry.title)}</title>")
+            parts.append(f"<link>{escape(entry.link)}</link>")
+            is_permalink = "true" if entry.guid == entry.link else "false"
+            parts.append(f'<guid isPermaLink="{is_permalink}">{escape(entry.guid)}</guid>')
+            parts.append(f"<pubDate>{_rfc822(entry.updated)}</pubDate>")
+            description = entry.summary or entry.content
+            if description:
+                parts.append(f"<description>{escape(description)}</description>")
+            if entry.author:
+                parts.append(f"<author>{escape(entry.author)}</author>")
+            for category in entry.categories:
+                parts.append(f"<category>{escape(category)}</category>")
+            parts.append("</item>")
+
+        parts.append("</channel>")
+        parts.append("</rss>")
+        return "\n".join(parts)
+
+    def to_atom(self) -> str:
+        """Render an Atom (RFC 4287) document."""
+        parts = [
+            '<?xml version="1.0" encoding="utf-8"?>',
+            '<feed xmlns="http://www.w3.org/2005/Atom">',
+            f"<title>{escape(self.title)}</title>",
+            f"<id>{escape(self.feed_url or self.link)}</id>",
+            f"<updated>{_rfc3339(self.updated)}</updated>",
+            f'<link href={quoteattr(self.link)}/>',
+        ]
+        if self.feed_url:
+            parts.append(f'<link href={quoteattr(self.feed_url)} rel="self"/>')
+        if self.description:
+            parts.append(f"<subtitle>{escape(self.description)}</subtitle>")
+        if self.author:
+            parts.append(f"<author><name>{escape(self.author)}</name></author>")
+
+        for entry in self._sorted_entries():
+            parts.append("<entry>")
+            parts.append(f"<title>{escape(entry.title)}</title>")
+            parts.append(f"<id>{escape(entry.guid)}</id>")
+            parts.append(f'<link href={quoteattr(entry.link)}/>')
+            parts.append(f"<updated>{_rfc3339(entry.updated)}</updated>")
+            if entry.summary:
+                parts.append(f"<summary>{escape(entry.summary)}</summary>")
+            if entry.content:
+                parts.append(f'<content type="html">{escape(entry.content)}</content>')
+            if entry.author:
+                parts.append(f"<author><name>{escape(entry.author)}</name></author>")
+            for category in entry.categories:
+                parts.append(f"<category term={quoteattr(category)}/>")
+            parts.append("</entry>")
+
+        parts.append("</feed>")
+        return "\n".join(parts)
+
+    def response(self, format: str = "rss", max_age: int = 300, request: Any = None) -> Response:
+        """
+        Render the feed as a cacheable response.
+
+        Sets ``Cache-Control``, ``ETag`` and ``Last-Modified``. When ``request``
+        carries a matching ``If-None-Match`` an empty 304 is returned.
+
+        Args:
+            format: ``"rss"`` or ``"atom"``
+            max_age: ``Cache-Control`` max-age in seconds
+            request: Optional incoming request for conditional GET
+        """
+        if format == "rss":
+            body, content_type = self.to_rss(), RSS_CONTENT_TYPE
+        elif format == "atom":
+            body, content_type = self.to_atom(), ATOM_CONTENT_TYPE
+        else:
+            raise ValueError(f"Unknown feed format: {format!r}")
+
+        etag = '"' + hashlib.sha256(body.encode("utf-8")).hexdigest()[:32] + '"'
+        status = 200
+        if request is not None:
+            if_none_match = getattr(request, "headers", {}).get("if-none-match", "")
+            if etag in [tag.strip() for tag in if_none_match.split(",")]:
+                body, status = "", 304
+
+        resp = Response(body=body, status=status, content_type=content_type)
+        resp.with_header("Cache-Control", f"public, max-age={max_age}")
+        resp.with_header("ETag", etag)
+        resp.with_header("Last-Modified", _rfc822(self.updated))
+        return resp
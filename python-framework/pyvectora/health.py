@@ -0,0 +1,163 @@
+"""
+PyVectora Health - readiness probes for external dependencies.
+
+Probes registered on ``app.health`` run concurrently when ``/ready`` is
+requested; the aggregated result (status and latency per dependency) is
+cached for ``cache_interval`` seconds so load balancers polling the
+endpoint do not hammer the dependencies.
+
+A probe is a sync or async callable taking no arguments; sync probes run
+in a worker thread. It passes by returning anything but ``False``;
+returning ``False``, raising or exceeding its timeout marks the
+dependency down.
+
+Example:
+    app.health.register("redis", lambda: redis.ping())
+
+    @app.health.probe("postgres", timeout=2.0)
+    async def postgres():
+        await db.fetch_one("SELECT 1")
+"""
+
+from __future__ import annotations
+
+import asyncio
+import dataclasses
+import inspect
+import time
+from typing import Any, Callable, Dict, List
+
+Probe = Callable[[], Any]
+
+
+@dataclasses.dataclass
+class _Check:
+    name: str
+    probe: Probe
+    timeout: float
+    critical: bool
+
+
+class HealthRegistry:
+    """
+    Named dependency probes aggregated into the readiness endpoint.
+
+    Args:
+        cache_interval: Seconds a result is reused before probing again
+        default_timeout: Timeout of probes registered without one
+    """
+
+    def __init__(self, cache_interval: float = 5.0, default_timeout: float = 5.0) -> None:
+        self.cache_interval = cache_interval
+        self.default_timeout = default_timeout
+        self._checks: List[_Check] = []
+        self._cached: Dict[str, Any] | None = None
+        self._cached_at = 0.0
+        self._lock: asyncio.Lock | None = None
+
+    def register(
+        self,
+        name: str,
+        probe: Probe,
+        timeout: float | None = None,
+        critical: bool = True,
+    ) -> None:
+        """
+        Register ``probe`` as dependency ``name``.
+
+        A failing non-``critical`` dependency is reported but keeps the
+        app ready (status ``"degraded"``).
+        """
+        if any(check.name == name for check in self._checks):
+            raise ValueError(f"Health check '{name}' is already registered")
+        self._checks.append(
+            _Check(name, probe, timeout if timeout is not None else self.default_timeout, critical)
+        )
+        self._cached = None
+
+    def probe(
+        self, name: str, timeout: float | None = None, critical: bool = True
+    ) -> Callable[[Probe], Probe]:
+        """Decorator form of ``register``."""
+
+        def decorator(func: Probe) -> Probe:
+            self.register(name, func, timeout=timeout, critical=critical)
+            return func
+
+        return decorator
+
+    @property
+    def names(self) -> List[str]:
+        """Registered dependency names."""
+        return [check.name for check in self._checks]
+
+    async def check(self) -> Dict[str, Any]:
+        """
+        Aggregated result, probing again once the cache has expired.
+
+        ``{"status": "ok" | "degraded" | "down", "checked_at": ...,
+        "checks": {name: {"status": "up" | "down", "latency_ms": ...,
+        "critical": ..., "error"?: ...}}}``
+        """
+        if self._fresh():
+            return self._cached  # type: ignore[return-value]
+        if self._lock is None:
+            self._lock = asyncio.Lock()
+        async with self._lock:
+            # Another request may have refreshed while this one waited
+            if not self._fresh():
+                self._cached = await self._run_all()
+                self._cached_at = time.monotonic()
+        return self._cached  # type: ignore[return-value]
+
+    def _fresh(self) -> bool:
+        return (
+            self._cached is not None
+            and time.monotonic() - self._cached_at < self.cache_interval
+        )
+
+    async def _run_all(self) -> Dict[str, Any]:
+        results = await asyncio.gather(*(self._run(check) for check in self._checks))
+        checks = {check.name: result for check, result in zip(self._checks, results)}
+        down = [check for check in self._checks if checks[check.name]["status"] == "down"]
+        if any(check.critical for check in down):
+            status = "down"
+        elif down:
+            status = "degraded"
+        else:
+            status = "ok"
+        return {"status": status, "checked_at": time.time(), "checks": checks}
+
+    async def _run(self, check: _Check) -> Dict[str, Any]:
+        started = time.perf_counter()
+        error: str | None = None
+        try:
+            result = await asyncio.wait_for(self._call(check.probe), check.timeout)
+            if result is False:
+                error = "probe returned False"
+        except asyncio.TimeoutError:
+            error = f"timed out after {check.timeout}s"
+        except Exception as exc:  # noqa: BLE001 - any failure marks the dependency down
+            error = f"{type(exc).__name__}: {exc}"
+        entry: Dict[str, Any] = {
+            "status": "down" if error else "up",
+            "latency_ms": round((time.perf_counter() - started) * 1000, 2),
+            "critical": check.critical,
+        }
+        if error:
+            entry["error"] = error
+        return entry
+
+    @staticmethod
+    async def _call(probe: Probe) -> Any:
+        # Sync probes (blocking client calls) run in a thread so the
+        # timeout applies and the event loop keeps serving
+        if inspect.iscoroutinefunction(probe):
+            return await probe()
+        result = await asyncio.to_thread(probe)
+        if inspect.isawaitable(result):
+            result = await result
+        return result
+
+
+__all__ = ["HealthRegistry"]
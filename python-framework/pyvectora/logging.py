@@ -0,0 +1,159 @@
+"""
+PyVectora Logging - request context on log records and the tracing bridge.
+
+While a handler runs, every ``logging`` record carries the request's
+correlation fields as attributes, so formatters and log shippers can
+group lines by request without formatting them by hand:
+
+    request_id   the ``x-request-id`` of the request
+    route        matched route (``"GET /users/{id}"``)
+    tenant       ``X-Tenant-Id`` header, else the JWT ``tenant`` claim
+    user         JWT ``sub`` claim of an authenticated request
+
+Fields are ``None`` outside a request or when unknown. The Rust core puts
+the same fields on its own (JSON) log lines. ``App`` installs the record
+factory when it starts, so these names can no longer be passed through
+``extra=``; ``bind`` sets the context for work outside a request, such as
+background jobs.
+
+One output for the whole process:
+
+    forward_to_tracing()           Python records join the Rust JSON log
+                                   lines (target ``python``)
+    forward_from_tracing()         Rust events go to Python loggers named
+                                   after their target (``pyvectora_core.server``)
+                                   and the Rust JSON output goes quiet
+
+Both directions may be enabled; records never loop back.
+
+Example:
+    logging.basicConfig(format="%(levelname)s [%(request_id)s] %(message)s")
+    pyvectora.logging.forward_from_tracing()
+"""
+
+from __future__ import annotations
+
+import contextvars
+import logging
+from contextlib import contextmanager
+from typing import Any, Dict, Iterator, Optional
+
+try:
+    from .pyvectora_native import log_context_var as _log_context_var
+
+    _context: contextvars.ContextVar[Optional[Dict[str, Any]]] = _log_context_var()
+except ImportError:
+    _context = contextvars.ContextVar("pyvectora_log_context", default=None)
+
+try:
+    from .pyvectora_native import forward_tracing_to_logging as _forward_tracing_to_logging
+    from .pyvectora_native import log_to_tracing as _log_to_tracing
+except ImportError:
+    _forward_tracing_to_logging = None
+    _log_to_tracing = None
+
+FIELDS = ("request_id", "route", "tenant", "user")
+
+_installed = False
+
+
+def current_context() -> Dict[str, Any]:
+    """Correlation fields of the request being handled (all ``None`` outside one)."""
+    context = _context.get() or {}
+    return {field: context.get(field) for field in FIELDS}
+
+
+@contextmanager
+def bind(**fields: Any) -> Iterator[None]:
+    """Set correlation fields for the duration of a ``with`` block."""
+    unknown = set(fields) - set(FIELDS)
+    if unknown:
+        raise ValueError(f"Unknown log context fields: {', '.join(sorted(unknown))}")
+    token = _context.set({**current_context(), **fields})
+    try:
+        yield
+    finally:
+        _context.reset(token)
+
+
+def install() -> None:
+    """Add the correlation fields to every ``logging`` record (idempotent)."""
+    global _installed
+    if _installed:
+        return
+    previous = logging.getLogRecordFactory()
+
+    def factory(*args: Any, **kwargs: Any) -> logging.LogRecord:
+        record = previous(*args, **kwargs)
+        _enrich(record)
+        return record
+
+    logging.setLogRecordFactory(factory)
+    _installed = True
+
+
+class RequestContextFilter(logging.Filter):
+    """Handler filter adding the correlation fields, for setups that replace the record factory."""
+
+    def filter(self, record: logging.LogRecord) -> bool:
+        _enrich(record)
+        return True
+
+
+class TracingHandler(logging.Handler):
+    """Handler writing records through the Rust ``tracing`` output."""
+
+    def emit(self, record: logging.LogRecord) -> None:
+        if _log_to_tracing is None:
+            return
+        try:
+            context = _context.get() or {}
+            fields = [getattr(record, field, None) or context.get(field) for field in FIELDS]
+            _log_to_tracing(record.levelno, record.name, self.format(record), *fields)
+        except Exception:
+            self.handleError(record)
+
+
+def forward_to_tracing(logger: str | None = None, level: int = logging.NOTSET) -> TracingHandler:
+    """
+    Write the records of ``logger`` (default: root) through the Rust log output.
+
+    Returns the attached handler; remove it with ``removeHandler`` to stop.
+    """
+    if _log_to_tracing is None:
+        raise RuntimeError("Native module not available. Run 'maturin develop' to build.")
+    handler = TracingHandler(level)
+    logging.getLogger(logger).addHandler(handler)
+    return handler
+
+
+def forward_from_tracing(enabled: bool = True) -> None:
+    """
+    Send Rust ``tracing`` events to Python loggers instead of the JSON output.
+
+    Records are named after the event's target (``pyvectora_core.server``)
+    and carry the request context fields. Pass ``False`` to restore the
+    Rust output.
+    """
+    if _forward_tracing_to_logging is None:
+        raise RuntimeError("Native module not available. Run 'maturin develop' to build.")
+    _forward_tracing_to_logging(enabled)
+
+
+def _enrich(record: logging.LogRecord) -> None:
+    context = _context.get() or {}
+    for field in FIELDS:
+        if not hasattr(record, field):
+            setattr(record, field, context.get(field))
+
+
+__all__ = [
+    "FIELDS",
+    "RequestContextFilter",
+    "TracingHandler",
+    "bind",
+    "current_context",
+    "forward_from_tracing",
+    "forward_to_tracing",
+    "install",
+]
@@ -7,7 +7,42 @@ The actual Request comes from the Rust runtime during execution.
 
 from __future__ import annotations
 
-from typing import Any
+import math
+from typing import Any, AsyncIterator, Callable, TypeVar
+
+from .exceptions import RequestValidationError
+
+T = TypeVar("T")
+
+_BOOLS = {
+    "true": True, "1": True, "yes": True, "on": True,
+    "false": False, "0": False, "no": False, "off": False,
+}
+
+
+def _parse_float(value: str) -> float:
+    result = float(value)
+    if not math.isfinite(result):
+        raise ValueError(value)
+    return result
+
+
+def _parse_bool(value: str) -> bool:
+    return _BOOLS[value.lower()]
+
+
+class State:
+    """Request-scoped values: set by middleware as attributes, read by the handler."""
+
+    def get(self, name: str, default: Any = None) -> Any:
+        """Value under ``name``, or ``default``."""
+        return self.__dict__.get(name, default)
+
+    def __contains__(self, name: str) -> bool:
+        return name in self.__dict__
+
+    def __repr__(self) -> str:
+        return f"State({', '.join(sorted(self.__dict__))})"
 
 class Request:
     """
@@ -17,7 +52,13 @@ class Request:
         method: HTTP method (GET, POST, etc.)
         path: Request path
         params: Path parameters extracted from the route
+        query: Query string parameters
+        client: Connected peer as ``(ip, port)``
         body: Raw request body as string
+        raw_headers: Headers as ``(name, value)`` tuples, duplicates kept
+        session: Cookie session (requires ``App.enable_sessions``)
+        files: Uploaded files by field name (see ``files()``)
+        state: Values set by middleware for the handler (``request.state.user``)
 
     Note:
         During actual execution, this is replaced by the Rust-backed Request object.
@@ -30,6 +71,13 @@ class Request:
         params: dict[str, str] | None = None,
         body: str | None = None,
         claims: dict[str, Any] | None = None,
+        raw_headers: list[tuple[str, str]] | None = None,
+        session: dict[str, Any] | None = None,
+        form: dict[str, str] | None = None,
+        files: dict[str, Any] | None = None,
+        query: dict[str, str] | None = None,
+        client: tuple[str, int] | None = None,
+        scheme: str = "http",
     ) -> None:
         """Initialize a Request object (for testing/development)."""
         self._method = method
@@ -37,6 +85,14 @@ class Request:
         self._params = params or {}
         self._body = body
         self._claims = claims
+        self._raw_headers = [(k.lower(), v) for k, v in raw_headers or []]
+        self._session = session
+        self._form = form
+        self._files = files or {}
+        self._query = query or {}
+        self._client = client
+        self._scheme = scheme
+        self._state = State()
 
     @property
     def method(self) -> str:
@@ -48,11 +104,62 @@ class Request:
         """Request path."""
         return self._path
 
+    @path.setter
+    def path(self, path: str) -> None:
+        """Rewrite the request path (in middleware: the request is routed again)."""
+        if not path.startswith("/"):
+            raise ValueError(f"path must start with '/': {path!r}")
+        self._path = path
+
     @property
     def params(self) -> dict[str, str]:
         """Path parameters extracted from the route pattern."""
         return self._params
 
+    @property
+    def client(self) -> tuple[str, int] | None:
+        """Connected peer as ``(ip, port)`` (None outside a real connection)."""
+        return self._client
+
+    @property
+    def url(self) -> str:
+        """Full URL: scheme, ``Host`` header (else ``localhost``), path and query."""
+        from urllib.parse import urlencode
+
+        host = self.headers.get("host", "localhost")
+        query = urlencode(self._query)
+        return f"{self._scheme}://{host}{self._path}" + (f"?{query}" if query else "")
+
+    @property
+    def query(self) -> dict[str, str]:
+        """Query string parameters."""
+        return dict(self._query)
+
+    def query_int(self, name: str, default: int | None = None) -> int | None:
+        """Query parameter as an int; raises ``RequestValidationError`` (422)."""
+        return self._typed_query(name, default, int, "an integer")
+
+    def query_float(self, name: str, default: float | None = None) -> float | None:
+        """Query parameter as a finite float; raises ``RequestValidationError`` (422)."""
+        return self._typed_query(name, default, _parse_float, "a number")
+
+    def query_bool(self, name: str, default: bool | None = None) -> bool | None:
+        """Query parameter as a bool (``true/1/yes/on``, ``false/0/no/off``)."""
+        return self._typed_query(name, default, _parse_bool, "a boolean")
+
+    def _typed_query(
+        self, name: str, default: T | None, parse: Callable[[str], T], expected: str
+    ) -> T | None:
+        if name not in self._query:
+            return default
+        try:
+            return parse(self._query[name].strip())
+        except (KeyError, ValueError):
+            message = f"{name} must be {expected}"
+            raise RequestValidationError(
+                message, [{"field": name, "message": message, "code": "INVALID_TYPE"}]
+            ) from None
+
     @property
     def body(self) -> str | None:
         """Raw request body as string."""
@@ -63,11 +170,83 @@ class Request:
         """Request body as text (alias of body)."""
         return self._body
 
+    @property
+    def headers(self) -> dict[str, str]:
+        """Headers as a dict (the last value wins for repeated headers)."""
+        return dict(self._raw_headers)
+
+    @property
+    def raw_headers(self) -> list[tuple[str, str]]:
+        """Headers as ``(name, value)`` tuples, keeping duplicates."""
+        return list(self._raw_headers)
+
+    @property
+    def cookies(self) -> dict[str, str]:
+        """Cookies from ``Cookie`` headers (the first value of a name wins)."""
+        cookies: dict[str, str] = {}
+        for header in self.get_all("cookie"):
+            for pair in header.split(";"):
+                name, sep, value = pair.partition("=")
+                name, value = name.strip(), value.strip()
+                if not sep or not name:
+                    continue
+                if len(value) >= 2 and value[0] == value[-1] == '"':
+                    value = value[1:-1]
+                cookies.setdefault(name, value)
+        return cookies
+
+    def get_all(self, name: str) -> list[str]:
+        """Every value of a header (case-insensitive)."""
+        name = name.lower()
+        return [v for k, v in self._raw_headers if k == name]
+
+    def set_header(self, name: str, value: str) -> None:
+        """Set or override a header (e.g. in middleware, for the handler)."""
+        self.remove_header(name)
+        self._raw_headers.append((name.lower(), value))
+
+    def remove_header(self, name: str) -> bool:
+        """Remove every value of a header, returning whether there was one."""
+        name = name.lower()
+        kept = [(k, v) for k, v in self._raw_headers if k != name]
+        removed = len(kept) != len(self._raw_headers)
+        self._raw_headers = kept
+        return removed
+
+    @property
+    def session(self) -> dict[str, Any]:
+        """Cookie session; changes are saved on response."""
+        if self._session is None:
+            raise RuntimeError("sessions are not enabled; call app.enable_sessions(secret_key)")
+        return self._session
+
+    @property
+    def state(self) -> State:
+        """Values set by middleware for the handler, as attributes."""
+        return self._state
+
     @property
     def claims(self) -> dict[str, Any] | None:
         """Validated JWT claims (if authenticated)."""
         return self._claims
 
+    def form(self) -> dict[str, str]:
+        """
+        Text fields of a form body (the last value of a name wins).
+
+        Uses the ``form`` given to the constructor, else parses a
+        urlencoded body.
+        """
+        if self._form is not None:
+            return dict(self._form)
+        from urllib.parse import parse_qsl
+
+        return dict(parse_qsl(self._body or ""))
+
+    def files(self) -> dict[str, Any]:
+        """Uploaded files by field name (``UploadedFile`` on the Rust request)."""
+        return dict(self._files)
+
     def json(self) -> dict[str, Any]:
         """
         Parse request body as JSON.
@@ -84,5 +263,16 @@ class Request:
             return {}
         return json.loads(self._body)
 
+    async def stream(self) -> AsyncIterator[bytes]:
+        """
+        Request body in ``bytes`` chunks: ``async for chunk in request.stream()``.
+
+        Yields the whole body as one chunk. On the Rust request, routes
+        registered with ``stream=True`` yield chunks as they arrive and
+        leave ``body`` unset.
+        """
+        if self._body:
+            yield self._body.encode()
+
     def __repr__(self) -> str:
         return f"Request(method={self.method!r}, path={self.path!r})"
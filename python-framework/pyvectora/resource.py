@@ -0,0 +1,224 @@
+"""
+PyVectora Resources - CRUD routes generated for a database table.
+
+``app.resource("/users", table="users", db=db)`` registers::
+
+    GET    /users        list: ?page=, ?per_page=, ?sort=[-]column, column filters
+    POST   /users        create (201 with the stored row)
+    GET    /users/{id}   get one row
+    PUT    /users/{id}   update (all writable fields)
+    PATCH  /users/{id}   partial update
+    DELETE /users/{id}   delete (204; soft delete when configured on ``db``)
+
+Every query is generated in Rust with bound parameters, so column values
+never reach the SQL text.
+
+Example:
+    db = await Database.connect_sqlite("sqlite:tools.db")
+    db.configure_soft_delete("deleted_at")
+
+    def check_user(data, partial):
+        if not partial and "email" not in data:
+            raise HTTPException(422, "email is required")
+        return data
+
+    app.resource("/users", table="users", db=db,
+                 fields=["name", "email"], validate=check_user)
+"""
+
+from __future__ import annotations
+
+import inspect
+import re
+from typing import TYPE_CHECKING, Any, Callable, Dict, List, Optional, Tuple
+
+from .exceptions import HTTPException
+from .response import Response
+
+if TYPE_CHECKING:
+    from .database import Database
+
+Validator = Callable[[Dict[str, Any], bool], Any]
+
+RESERVED_PARAMS = {"page", "per_page", "sort"}
+_INT_RE = re.compile(r"-?\d+")
+
+
+def _coerce(value: str) -> Any:
+    """Query-string value as an int when it looks like one."""
+    return int(value) if _INT_RE.fullmatch(value) else value
+
+
+class Resource:
+    """
+    CRUD handlers for one table.
+
+    Args:
+        path: Collection path, e.g. ``"/users"``
+        table: Table name
+        db: ``Database`` the queries run on
+        id_column: Primary key column
+        id_type: Route parameter type of the key (``"int"`` or ``"str"``)
+        fields: Writable columns (None accepts any column)
+        filters: Columns filterable from the query string (default: ``fields``)
+        page_size: Default rows per page
+        max_page_size: Largest ``per_page`` accepted
+        validate: ``(data, partial) -> data`` hook run before create and
+            update; may be async, may return None to keep ``data`` and may
+            raise ``HTTPException`` to reject the request
+    """
+
+    def __init__(
+        self,
+        path: str,
+        table: str,
+        db: "Database",
+        *,
+        id_column: str = "id",
+        id_type: str = "int",
+        fields: Optional[List[str]] = None,
+        filters: Optional[List[str]] = None,
+        page_size: int = 50,
+        max_page_size: int = 200,
+        validate: Optional[Validator] = None,
+    ) -> None:
+        if not 1 <= page_size <= max_page_size:
+            raise ValueError("page_size must be between 1 and max_page_size")
+        self.path = path.rstrip("/") or "/"
+        self.table = table
+        self.db = db
+        self.id_column = id_column
+        self.id_type = id_type
+        self.fields = list(fields) if fields is not None else None
+        self.filters = list(filters) if filters is not None else self.fields
+        self.page_size = page_size
+        self.max_page_size = max_page_size
+        self.validate = validate
+
+    @property
+    def item_path(self) -> str:
+        """Path of one row, e.g. ``/users/{id:int}``."""
+        param = "{id:int}" if self.id_type == "int" else "{id}"
+        return f"{self.path.rstrip('/')}/{param}"
+
+    def routes(self) -> List[Tuple[str, str, Callable[..., Any]]]:
+        """``(method, path, handler)`` for every generated route."""
+        return [
+            ("GET", self.path, self.list),
+            ("POST", self.path, self.create),
+            ("GET", self.item_path, self.get),
+            ("PUT", self.item_path, self.replace),
+            ("PATCH", self.item_path, self.update),
+            ("DELETE", self.item_path, self.delete),
+        ]
+
+    async def list(self, request: Any) -> Response:
+        """A page of rows: ``{"items", "page", "per_page", "total"}``."""
+        query = dict(request.query)
+        page = self._int_param(query, "page", 1)
+        per_page = self._int_param(query, "per_page", self.page_size)
+        if page < 1 or not 1 <= per_page <= self.max_page_size:
+            raise HTTPException(
+                400, f"page must be >= 1 and per_page between 1 and {self.max_page_size}"
+            )
+        order_by, descending = self._sort(query.get("sort"))
+        filters = self._filters(query)
+
+        items = await self.db.fetch_page(
+            self.table,
+            filters,
+            limit=per_page,
+            offset=(page - 1) * per_page,
+            order_by=order_by,
+            descending=descending,
+        )
+        total = await self.db.count_active(self.table, filters)
+        return Response.json(
+            {"items": items, "page": page, "per_page": per_page, "total": total}
+        )
+
+    async def get(self, request: Any) -> Response:
+        """One row, or 404."""
+        rows = await self.db.fetch_active(self.table, self._key(request))
+        if not rows:
+            raise HTTPException(404, f"{self.table} not found")
+        return Response.json(rows[0])
+
+    async def create(self, request: Any) -> Response:
+        """Insert the JSON body, answering 201 with the stored row."""
+        data = await self._payload(request, partial=False)
+        row = await self.db.insert(self.table, data)
+        return Response.json(row, status=201)
+
+    async def replace(self, request: Any) -> Response:
+        """Update a row from a complete JSON body."""
+        return await self._update(request, partial=False)
+
+    async def update(self, request: Any) -> Response:
+        """Update the fields in the JSON body."""
+        return await self._update(request, partial=True)
+
+    async def delete(self, request: Any) -> Response:
+        """Delete a row (soft delete when configured), answering 204."""
+        if not await self.db.delete(self.table, self._key(request)):
+            raise HTTPException(404, f"{self.table} not found")
+        return Response(status=204)
+
+    async def _update(self, request: Any, partial: bool) -> Response:
+        data = await self._payload(request, partial=partial)
+        rows = await self.db.update(self.table, self._key(request), data)
+        if not rows:
+            raise HTTPException(404, f"{self.table} not found")
+        return Response.json(rows[0])
+
+    def _key(self, request: Any) -> Dict[str, Any]:
+        return {self.id_column: request.params["id"]}
+
+    async def _payload(self, request: Any, partial: bool) -> Dict[str, Any]:
+        try:
+            data = request.json()
+        except ValueError:
+            raise HTTPException(400, "Request body must be JSON") from None
+        if not isinstance(data, dict):
+            raise HTTPException(422, "Request body must be a JSON object")
+        data.pop(self.id_column, None)
+        if self.fields is not None:
+            unknown = sorted(set(data) - set(self.fields))
+            if unknown:
+                raise HTTPException(422, {"error": "Unknown fields", "fields": unknown})
+        if self.validate is not None:
+            result = self.validate(data, partial)
+            if inspect.isawaitable(result):
+                result = await result
+            if result is not None:
+                data = result
+        if not data:
+            raise HTTPException(422, "No fields to write")
+        return data
+
+    def _filters(self, query: Dict[str, str]) -> Dict[str, Any]:
+        filters = {k: _coerce(v) for k, v in query.items() if k not in RESERVED_PARAMS}
+        if self.filters is not None:
+            unknown = sorted(set(filters) - set(self.filters))
+            if unknown:
+                raise HTTPException(400, {"error": "Unknown filters", "filters": unknown})
+        return filters
+
+    def _sort(self, sort: Optional[str]) -> Tuple[str, bool]:
+        if not sort:
+            return self.id_column, False
+        descending = sort.startswith("-")
+        column = sort.lstrip("-")
+        if self.fields is not None and column not in (*self.fields, self.id_column):
+            raise HTTPException(400, f"Cannot sort by {column!r}")
+        return column, descending
+
+    @staticmethod
+    def _int_param(query: Dict[str, str], name: str, default: int) -> int:
+        try:
+            return int(query.get(name, default))
+        except ValueError:
+            raise HTTPException(400, f"{name} must be an integer") from None
+
+
+__all__ = ["Resource"]
@@ -7,6 +7,8 @@ Provides helper classes for creating HTTP responses.
 from __future__ import annotations
 
 import json
+import os
+import tempfile
 from typing import Any
 
 class Response:
@@ -17,6 +19,8 @@ class Response:
         status: HTTP status code (default: 200)
         body: Response body as string
         content_type: Content-Type header value
+        headers: Single-valued headers
+        extra_headers: Headers sent once per entry (e.g. ``Set-Cookie``)
     """
 
     def __init__(
@@ -30,6 +34,7 @@ class Response:
         self.body = body
         self.content_type = content_type
         self.headers: dict[str, str] = {}
+        self.extra_headers: list[tuple[str, str]] = []
 
     @classmethod
     def json(cls, data: dict[str, Any] | list[Any], status: int = 200) -> Response:
@@ -85,6 +90,84 @@ class Response:
             content_type="text/html",
         )
 
+    @classmethod
+    def ndjson(
+        cls,
+        content: Any,
+        status: int = 200,
+        headers: dict[str, str] | None = None,
+    ) -> NDJSONResponse:
+        """
+        Create a streaming newline-delimited JSON response.
+
+        Args:
+            content: Sync or async iterable yielding JSON-serializable items
+            status: HTTP status code (default: 200)
+            headers: Additional headers
+
+        Returns:
+            NDJSONResponse streaming one JSON document per item
+        """
+        return NDJSONResponse(content, status=status, headers=headers)
+
+    @classmethod
+    def csv(
+        cls,
+        rows: Any,
+        header: list[str] | None = None,
+        status: int = 200,
+        gzip: bool = False,
+        filename: str | None = None,
+        headers: dict[str, str] | None = None,
+    ) -> CSVResponse:
+        """
+        Create a streaming CSV response.
+
+        Args:
+            rows: Sync or async iterable of sequences or dicts (e.g. a database cursor)
+            header: Optional header row; required for dict rows (defines column order)
+            status: HTTP status code (default: 200)
+            gzip: Compress the stream (sets ``Content-Encoding: gzip``)
+            filename: Optional download filename (``Content-Disposition``)
+            headers: Additional headers
+
+        Returns:
+            CSVResponse streaming one record per row
+        """
+        return CSVResponse(
+            rows, header=header, status=status, gzip=gzip, filename=filename, headers=headers
+        )
+
+    @classmethod
+    def spooled(
+        cls,
+        content_type: str = "application/octet-stream",
+        status: int = 200,
+        filename: str | None = None,
+        threshold: int = 1024 * 1024,
+        headers: dict[str, str] | None = None,
+    ) -> SpooledResponse:
+        """
+        Create a response written piece by piece and spilled to disk when large.
+
+        Args:
+            content_type: Content-Type header
+            status: HTTP status code (default: 200)
+            filename: Optional download filename (``Content-Disposition``)
+            threshold: Bytes kept in memory before moving to a temp file
+            headers: Additional headers
+
+        Returns:
+            SpooledResponse to ``write()`` the body into
+        """
+        return SpooledResponse(
+            content_type=content_type,
+            status=status,
+            filename=filename,
+            threshold=threshold,
+            headers=headers,
+        )
+
     def with_status(self, status: int) -> Response:
         """Set the status code (Builder pattern)."""
         self.status = status
@@ -98,9 +181,88 @@ class Response:
             self.headers[key] = value
         return self
 
+    def append_header(self, key: str, value: str) -> Response:
+        """Add a header, keeping earlier values of the same name (Builder pattern)."""
+        self.extra_headers.append((key, value))
+        return self
+
+    def set_cookie(
+        self,
+        name: str,
+        value: str,
+        max_age: int | None = None,
+        path: str | None = "/",
+        domain: str | None = None,
+        secure: bool = False,
+        http_only: bool = False,
+        same_site: str | None = "lax",
+    ) -> Response:
+        """
+        Add a ``Set-Cookie`` header (Builder pattern).
+
+        Args:
+            name: Cookie name
+            value: Cookie value; spaces, ``;``, ``"``, ``,``, ``\\`` and
+                non-ASCII characters must be encoded by the caller
+            max_age: Lifetime in seconds; ``0`` deletes the cookie
+            path: Path the cookie is sent for
+            domain: Domain the cookie is sent to
+            secure: Only send over HTTPS
+            http_only: Hide the cookie from JavaScript
+            same_site: ``"lax"``, ``"strict"`` or ``"none"`` (implies ``secure``)
+
+        Returns:
+            The response, with one more ``Set-Cookie`` header
+
+        Raises:
+            ValueError: For an invalid name or value, or a path or domain
+                containing ``;`` or control characters
+        """
+        cookie = _format_cookie(name, value, max_age, path, domain, secure, http_only, same_site)
+        return self.append_header("Set-Cookie", cookie)
+
     def __repr__(self) -> str:
         return f"Response(status={self.status}, content_type={self.content_type!r})"
 
+_SAME_SITE = {"strict": "Strict", "lax": "Lax", "none": "None"}
+# Non-alphanumeric characters allowed in cookie names (HTTP token)
+_TOKEN_CHARS = "!#$%&'*+-.^_`|~"
+
+def _format_cookie(
+    name: str,
+    value: str,
+    max_age: int | None,
+    path: str | None,
+    domain: str | None,
+    secure: bool,
+    http_only: bool,
+    same_site: str | None,
+) -> str:
+    """Render a ``Set-Cookie`` value the way the native ``SetCookie`` does."""
+    if same_site is not None and same_site.lower() not in _SAME_SITE:
+        raise ValueError(f"same_site must be 'lax', 'strict' or 'none', not {same_site!r}")
+    if not name or not all(c.isascii() and (c.isalnum() or c in _TOKEN_CHARS) for c in name):
+        raise ValueError(f"invalid cookie name {name!r}")
+    if not all(0x21 <= ord(c) <= 0x7E and c not in '",;\\' for c in value):
+        raise ValueError(f"invalid cookie value {value!r}")
+    for attribute, text in (("path", path), ("domain", domain)):
+        if text and any(c == ";" or ord(c) < 0x20 or 0x7F <= ord(c) <= 0x9F for c in text):
+            raise ValueError(f"invalid cookie {attribute} {text!r}")
+    parts = [f"{name}={value}"]
+    if max_age is not None:
+        parts.append(f"Max-Age={max(max_age, 0)}")
+    if domain:
+        parts.append(f"Domain={domain}")
+    if path:
+        parts.append(f"Path={path}")
+    if secure or (same_site or "").lower() == "none":
+        parts.append("Secure")
+    if http_only:
+        parts.append("HttpOnly")
+    if same_site is not None:
+        parts.append(f"SameSite={_SAME_SITE[same_site.lower()]}")
+    return "; ".join(parts)
+
 class JSONResponse(Response):
     """Convenience class for JSON responses."""
 
@@ -128,7 +290,8 @@ class StreamingResponse:
     """
     Streaming HTTP response for large content or real-time data.
 
-    Supports both sync and async generators.
+    Supports both sync and async generators. Chunks are flushed to the
+    client as they are produced instead of being buffered in memory.
 
     Example:
         async def generate_data():
@@ -179,6 +342,225 @@ class StreamingResponse:
     def __repr__(self) -> str:
         return f"StreamingResponse(status={self.status}, content_type={self.content_type!r})"
 
+class NDJSONResponse(StreamingResponse):
+    """
+    Newline-delimited JSON response (``application/x-ndjson``).
+
+    Each yielded item is serialized (natively when available) as one JSON
+    document followed by ``\n`` and flushed immediately.
+
+    Example:
+        async def export_rows():
+            async for row in db.fetch_iter("SELECT * FROM events"):
+                yield row
+
+        return Response.ndjson(export_rows())
+    """
+
+    def __init__(
+        self,
+        content: Union[Iterator[Any], AsyncIterator[Any], Callable[[], Union[Iterator[Any], AsyncIterator[Any]]]],
+        status: int = 200,
+        headers: dict[str, str] | None = None
+    ):
+        super().__init__(
+            content=content,
+            status=status,
+            content_type="application/x-ndjson",
+            headers=headers
+        )
+        self._is_ndjson = True
+
+    async def collect(self) -> str:
+        """Collect all items as NDJSON (for testing)."""
+        lines = []
+
+        content = self.content() if callable(self.content) else self.content
+
+        if hasattr(content, '__anext__'):
+            async for item in content:
+                lines.append(json.dumps(item, ensure_ascii=False) + "\n")
+        else:
+            for item in content:
+                lines.append(json.dumps(item, ensure_ascii=False) + "\n")
+
+        return "".join(lines)
+
+    def __repr__(self) -> str:
+        return f"NDJSONResponse(status={self.status})"
+
+class CSVResponse(StreamingResponse):
+    """
+    Streaming CSV response (RFC 4180 quoting, CRLF line endings).
+
+    Rows are encoded natively as they are produced, so exports straight from
+    a database cursor never buffer in Python.
+
+    Example:
+        async def rows():
+            async for row in db.fetch_iter("SELECT id, email FROM users"):
+                yield row
+
+        return Response.csv(rows(), header=["id", "email"], filename="users.csv")
+    """
+
+    def __init__(
+        self,
+        content: Union[Iterator[Any], AsyncIterator[Any], Callable[[], Union[Iterator[Any], AsyncIterator[Any]]]],
+        header: list[str] | None = None,
+        status: int = 200,
+        gzip: bool = False,
+        filename: str | None = None,
+        headers: dict[str, str] | None = None
+    ):
+        super().__init__(
+            content=content,
+            status=status,
+            content_type="text/csv; charset=utf-8",
+            headers=headers
+        )
+        self.header = header
+        self.gzip = gzip
+        self._is_csv = True
+        if filename:
+            self.headers["Content-Disposition"] = f'attachment; filename="{filename}"'
+
+    async def collect(self) -> str:
+        """Collect all rows as uncompressed CSV (for testing)."""
+        import csv
+        import io
+
+        buf = io.StringIO()
+        writer = csv.writer(buf)
+        if self.header:
+            writer.writerow(self.header)
+
+        content = self.content() if callable(self.content) else self.content
+
+        def to_record(row: Any) -> list[Any]:
+            if isinstance(row, dict):
+                return [row.get(key) for key in self.header or []]
+            return list(row)
+
+        if hasattr(content, '__anext__'):
+            async for row in content:
+                writer.writerow(to_record(row))
+        else:
+            for row in content:
+                writer.writerow(to_record(row))
+
+        return buf.getvalue()
+
+    def __repr__(self) -> str:
+        return f"CSVResponse(status={self.status}, gzip={self.gzip})"
+
+class SpooledResponse:
+    """
+    Response written piece by piece, moved to a temp file past ``threshold`` bytes.
+
+    Large generated bodies (reports, exports) never become one giant
+    string: the server streams the file to the client, answers ``Range``
+    requests so downloads can resume, and deletes the file once sent.
+
+    Example:
+        response = Response.spooled(content_type="text/csv", filename="report.csv")
+        for row in build_report():
+            response.write(format_row(row))
+        return response
+    """
+
+    def __init__(
+        self,
+        content_type: str = "application/octet-stream",
+        status: int = 200,
+        filename: str | None = None,
+        threshold: int = 1024 * 1024,
+        headers: dict[str, str] | None = None,
+        dir: str | None = None,
+    ) -> None:
+        """
+        Initialize a spooled response.
+
+        Args:
+            content_type: Content-Type header
+            status: HTTP status code
+            filename: Optional download filename (``Content-Disposition``)
+            threshold: Bytes kept in memory before moving to a temp file
+            headers: Additional headers
+            dir: Directory for the temp file (default: system temp dir)
+        """
+        self.status = status
+        self.content_type = content_type
+        self.headers = dict(headers or {})
+        if filename:
+            self.headers["Content-Disposition"] = f'attachment; filename="{filename}"'
+        self.threshold = threshold
+        self._dir = dir
+        self._buffer = bytearray()
+        self._file: Any = None
+        self._path: str | None = None
+        self._is_spooled = True
+
+    @property
+    def path(self) -> str | None:
+        """Temp file holding the body (None while it fits in memory)."""
+        return self._path
+
+    def write(self, data: str | bytes) -> int:
+        """Append to the body (``str`` is encoded as UTF-8); returns the bytes written."""
+        if isinstance(data, str):
+            data = data.encode("utf-8")
+        if self._file is not None:
+            self._file.write(data)
+        else:
+            self._buffer += data
+            if len(self._buffer) > self.threshold:
+                self._spill()
+        return len(data)
+
+    def writelines(self, lines: Any) -> None:
+        """Append every item of ``lines``."""
+        for line in lines:
+            self.write(line)
+
+    def getvalue(self) -> bytes:
+        """The whole body (for testing)."""
+        if self._path is None:
+            return bytes(self._buffer)
+        self._close()
+        with open(self._path, "rb") as f:
+            return f.read()
+
+    def _spill(self) -> None:
+        self._file = tempfile.NamedTemporaryFile(
+            prefix="pyvectora-response-", dir=self._dir, delete=False
+        )
+        self._path = self._file.name
+        self._file.write(self._buffer)
+        self._buffer = bytearray()
+
+    def _close(self) -> None:
+        if self._file is not None and not self._file.closed:
+            self._file.close()
+
+    def _take(self) -> tuple[str | None, bytes]:
+        """Hand the body to the server, which removes the temp file once sent."""
+        self._close()
+        path, self._path = self._path, None
+        return path, bytes(self._buffer)
+
+    def __del__(self) -> None:
+        # Never handed to the server: don't leave the temp file behind
+        if self._path is not None:
+            self._close()
+            try:
+                os.unlink(self._path)
+            except OSError:
+                pass
+
+    def __repr__(self) -> str:
+        return f"SpooledResponse(status={self.status}, content_type={self.content_type!r})"
+
 class EventSourceResponse(StreamingResponse):
     """
     Server-Sent Events (SSE) response for real-time streaming.
@@ -192,6 +574,10 @@ class EventSourceResponse(StreamingResponse):
         data: <message>\\n\\n
         event: <event-type>\\ndata: <message>\\n\\n
 
+    Each yielded item becomes one event: strings are sent as ``data``,
+    dicts may set ``data``, ``event``, ``id`` and ``retry``. Idle streams
+    get a keep-alive comment every ``keep_alive`` seconds.
+
     Example:
         async def stream_tokens():
             for token in ["Hello", " ", "World", "!"]:
@@ -210,7 +596,9 @@ class EventSourceResponse(StreamingResponse):
         content: Union[Iterator[str], AsyncIterator[str], Callable[[], Union[Iterator[str], AsyncIterator[str]]]],
         status: int = 200,
         event_type: str | None = None,
-        headers: dict[str, str] | None = None
+        headers: dict[str, str] | None = None,
+        retry: int | None = None,
+        keep_alive: float | None = 15.0,
     ):
         """
         Initialize an SSE response.
@@ -220,6 +608,8 @@ class EventSourceResponse(StreamingResponse):
             status: HTTP status code
             event_type: Optional event type (SSE 'event:' field)
             headers: Additional headers
+            retry: Client reconnection delay in milliseconds (SSE 'retry:' field)
+            keep_alive: Seconds of idle time between keep-alive comments (None disables)
         """
         super().__init__(
             content=content,
@@ -228,6 +618,9 @@ class EventSourceResponse(StreamingResponse):
             headers=headers
         )
         self.event_type = event_type
+        self.retry = retry
+        self.keep_alive = keep_alive
+        self._is_sse = True
 
         self.headers.update({
             "Cache-Control": "no-cache",
@@ -271,13 +664,27 @@ class EventSourceResponse(StreamingResponse):
 
         if hasattr(content, '__anext__'):
             async for chunk in content:
-                chunks.append(self.format_event(chunk))
+                chunks.append(self._format_item(chunk))
         else:
             for chunk in content:
-                chunks.append(self.format_event(chunk))
+                chunks.append(self._format_item(chunk))
 
         return "".join(chunks)
 
+    def _format_item(self, item: Any) -> str:
+        """Format a yielded item (string or event dict) the way the server does."""
+        if isinstance(item, dict):
+            data = item.get("data", "")
+            if not isinstance(data, str):
+                data = json.dumps(data)
+            event_id = item.get("id")
+            return self.format_event(
+                data,
+                event=item.get("event"),
+                id=str(event_id) if event_id is not None else None,
+            )
+        return self.format_event(item)
+
     def __repr__(self) -> str:
         return f"EventSourceResponse(status={self.status}, event_type={self.event_type!r})"
 
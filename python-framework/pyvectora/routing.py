@@ -79,3 +79,137 @@ class Router:
 
             new_route = Route(route.method, final_path, route.handler)
             self._routes.append(new_route)
+
+
+def join_path(prefix: str, path: str) -> str:
+    """Join a group prefix and a route path (``"/api/"`` + ``"users"`` -> ``"/api/users"``)."""
+    prefix = prefix.rstrip("/")
+    if prefix and not prefix.startswith("/"):
+        prefix = "/" + prefix
+    path = path.lstrip("/")
+    if not path:
+        return prefix or "/"
+    return f"{prefix}/{path}"
+
+
+class RouteGroup:
+    """
+    Routes sharing a path prefix, middleware and auth requirement.
+
+    Routes are registered on the app immediately with the joined path; the
+    group's middleware runs for its routes only, after app-wide middleware.
+
+    Example:
+        api = app.group("/api/v1", auth=True)
+        api.use_middleware(audit_log)
+
+        @api.get("/users")
+        async def list_users(): ...
+
+        admin = api.group("/admin", middleware=[require_admin])
+    """
+
+    def __init__(
+        self,
+        app: "App",
+        prefix: str,
+        auth: bool = False,
+        middleware: List[Any] | None = None,
+        parent: "RouteGroup" | None = None,
+    ) -> None:
+        self.app = app
+        self.prefix = join_path(parent.prefix, prefix) if parent else join_path(prefix, "")
+        self.auth = auth or (parent.auth if parent else False)
+        self._middlewares: List[Any] = list(middleware or [])
+        self._parent = parent
+
+    @property
+    def middlewares(self) -> List[Any]:
+        """Middleware applied to this group's routes, outermost first."""
+        inherited = self._parent.middlewares if self._parent else []
+        return inherited + self._middlewares
+
+    def use_middleware(self, middleware: Any) -> None:
+        """Add middleware for routes registered on this group afterwards."""
+        self._middlewares.append(middleware)
+
+    def group(
+        self, prefix: str, auth: bool = False, middleware: List[Any] | None = None
+    ) -> "RouteGroup":
+        """Create a nested group inheriting this group's prefix, auth and middleware."""
+        return RouteGroup(self.app, prefix, auth=auth, middleware=middleware, parent=self)
+
+    def add_route(
+        self,
+        method: str,
+        path: str,
+        handler: Callable[..., Any],
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+    ) -> None:
+        """Register a route relative to the group prefix."""
+        from .app import Route as AppRoute
+
+        self.app._add_route(
+            AppRoute(
+                method.upper(),
+                join_path(self.prefix, path),
+                handler,
+                auth or self.auth,
+                list(self.middlewares),
+                requires,
+            )
+        )
+
+    def route(
+        self,
+        path: str,
+        methods: list[str] | None = None,
+        auth: bool = False,
+        requires: dict[str, Any] | None = None,
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        """Decorator to register a route for one or more HTTP methods."""
+        if methods is None:
+            methods = ["GET"]
+
+        def decorator(func: Callable[..., Any]) -> Callable[..., Any]:
+            for method in methods:
+                self.add_route(method, path, func, auth, requires)
+            return func
+
+        return decorator
+
+    def get(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["GET"], auth, requires)
+
+    def post(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["POST"], auth, requires)
+
+    def put(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["PUT"], auth, requires)
+
+    def delete(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["DELETE"], auth, requires)
+
+    def patch(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["PATCH"], auth, requires)
+
+    def head(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["HEAD"], auth, requires)
+
+    def options(
+        self, path: str, auth: bool = False, requires: dict[str, Any] | None = None
+    ) -> Callable[[Callable[..., Any]], Callable[..., Any]]:
+        return self.route(path, ["OPTIONS"], auth, requires)
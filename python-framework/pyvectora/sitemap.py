@@ -0,0 +1,164 @@
+"""
+PyVectora Sitemap - sitemap.xml generation.
+
+Collects parameterless GET routes plus user-provided URL generators and
+renders them as a sitemap. Sites with more URLs than fit in one file are
+served as a sitemap index pointing at ``?page=N`` pages.
+
+Example:
+    app.enable_sitemap("https://example.com")
+
+    @app.sitemap_generator
+    async def posts():
+        for slug in await load_slugs():
+            yield {"loc": f"/posts/{slug}", "changefreq": "weekly"}
+"""
+
+from __future__ import annotations
+
+import dataclasses
+import inspect
+from datetime import date, datetime, timezone
+from typing import Any, Callable, Iterable, List
+from xml.sax.saxutils import escape
+
+from .response import Response
+
+SITEMAP_NS = "http://www.sitemaps.org/schemas/sitemap/0.9"
+MAX_URLS_PER_FILE = 50000
+CONTENT_TYPE = "application/xml; charset=utf-8"
+
+
+@dataclasses.dataclass
+class SitemapURL:
+    """A single ``<url>`` entry."""
+
+    loc: str
+    lastmod: datetime | date | str | None = None
+    changefreq: str | None = None
+    priority: float | None = None
+
+    @classmethod
+    def coerce(cls, item: Any) -> SitemapURL:
+        """Accept a ``SitemapURL``, a path/URL string or a dict of fields."""
+        if isinstance(item, SitemapURL):
+            return item
+        if isinstance(item, str):
+            return cls(loc=item)
+        if isinstance(item, dict):
+            return cls(**item)
+        raise TypeError(f"Unsupported sitemap entry: {item!r}")
+
+
+def _format_lastmod(value: datetime | date | str) -> str:
+    if isinstance(value, datetime):
+        if value.tzinfo is None:
+            value = value.replace(tzinfo=timezone.utc)
+        return value.astimezone(timezone.utc).strftime("%Y-%m-%dT%H:%M:%SZ")
+    if isinstance(value, date):
+        return value.isoformat()
+    return value
+
+
+class Sitemap:
+    """Sitemap builder for an app's static routes and generated URLs."""
+
+    def __init__(
+        self,
+        base_url: str,
+        path: str = "/sitemap.xml",
+        max_urls: int = MAX_URLS_PER_FILE,
+        exclude: Iterable[str] = (),
+    ) -> None:
+        if not 0 < max_urls <= MAX_URLS_PER_FILE:
+            raise ValueError(f"max_urls must be between 1 and {MAX_URLS_PER_FILE}")
+        self.base_url = base_url.rstrip("/")
+        self.path = path
+        self.max_urls = max_urls
+        self.exclude = set(exclude) | {path}
+        self._generators: List[Callable[[], Any]] = []
+
+    def add_generator(self, generator: Callable[[], Any]) -> Callable[[], Any]:
+        """Register a callable returning (or yielding) URL entries."""
+        self._generators.append(generator)
+        return generator
+
+    def absolute(self, loc: str) -> str:
+        """Resolve a path against ``base_url``."""
+        if loc.startswith(("http://", "https://")):
+            return loc
+        return f"{self.base_url}/{loc.lstrip('/')}"
+
+    def static_urls(self, routes: Iterable[Any]) -> List[SitemapURL]:
+        """Public GET routes without path parameters."""
+        seen: set[str] = set()
+        urls = []
+        for route in routes:
+            if route.method != "GET" or route.auth or "{" in route.path:
+                continue
+            if route.path in self.exclude or route.path in seen:
+                continue
+            seen.add(route.path)
+            urls.append(SitemapURL(loc=route.path))
+        return urls
+
+    async def collect(self, routes: Iterable[Any]) -> List[SitemapURL]:
+        """Static routes followed by every generator's entries."""
+        urls = self.static_urls(routes)
+        for generator in self._generators:
+            produced = generator()
+            if inspect.isawaitable(produced):
+                produced = await produced
+            if hasattr(produced, "__aiter__"):
+                async for item in produced:
+                    urls.append(SitemapURL.coerce(item))
+            else:
+                urls.extend(SitemapURL.coerce(item) for item in produced)
+        return urls
+
+    def render_urlset(self, urls: Iterable[SitemapURL]) -> str:
+        """Render a ``<urlset>`` document."""
+        parts = ['<?xml version="1.0" encoding="UTF-8"?>', f'<urlset xmlns="{SITEMAP_NS}">']
+        for url in urls:
+            parts.append("<url>")
+            parts.append(f"<loc>{escape(self.absolute(url.loc))}</loc>")
+            if url.lastmod is not None:
+                parts.append(f"<lastmod>{escape(_format_lastmod(url.lastmod))}</lastmod>")
+            if url.changefreq:
+                parts.append(f"<changefreq>{escape(url.changefreq)}</changefreq>")
+            if url.priority is not None:
+                parts.append(f"<priority>{url.priority:.1f}</priority>")
+            parts.append("</url>")
+        parts.append("</urlset>")
+        return "\n".join(parts)
+
+    def render_index(self, pages: int) -> str:
+        """Render a ``<sitemapindex>`` pointing at ``?page=1..pages``."""
+        parts = ['<?xml version="1.0" encoding="UTF-8"?>', f'<sitemapindex xmlns="{SITEMAP_NS}">']
+        for page in range(1, pages + 1):
+            loc = self.absolute(f"{self.path}?page={page}")
+            parts.append(f"<sitemap><loc>{escape(loc)}</loc></sitemap>")
+        parts.append("</sitemapindex>")
+        return "\n".join(parts)
+
+    async def response(self, routes: Iterable[Any], page: int | None = None) -> Response:
+        """
+        Build the sitemap response.
+
+        Without ``page`` a single urlset is returned when everything fits,
+        otherwise a sitemap index. ``page`` selects one 1-based chunk.
+        """
+        urls = await self.collect(routes)
+        pages = max(1, -(-len(urls) // self.max_urls))
+
+        if page is None:
+            body = self.render_urlset(urls) if pages == 1 else self.render_index(pages)
+        elif 1 <= page <= pages:
+            start = (page - 1) * self.max_urls
+            body = self.render_urlset(urls[start:start + self.max_urls])
+        else:
+            return Response.json({"error": "Sitemap page not found"}, status=404)
+
+        return Response(body=body, content_type=CONTENT_TYPE).with_header(
+            "Cache-Control", "public, max-age=3600"
+        )
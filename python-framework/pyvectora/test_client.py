@@ -9,13 +9,17 @@ class TestClient:
     Zero-network test client for PyVectora applications.
     Executes requests directly against the Rust core, bypassing the OS network stack.
     """
-    def __init__(self, app):
+    def __init__(self, app, raise_limit_errors: bool = False):
         """
         Initialize TestClient.
 
         Args:
             app: Check if it's a PyApp from bindings or our wrapper App.
+            raise_limit_errors: Raise ``PayloadTooLarge``, ``UriTooLong`` or
+                ``TooManyQueryParams`` for a request over the server limits
+                instead of returning the 413, 414 or 400 response
         """
+        self.raise_limit_errors = raise_limit_errors
         if hasattr(app, "native_app"):
             self.server = app.native_app.test_client()
         else:
@@ -47,7 +51,8 @@ class TestClient:
             method,
             path,
             headers,
-            body_bytes
+            body_bytes,
+            raise_limit_errors=self.raise_limit_errors,
         )
 
         py_resp = Response(
@@ -57,6 +62,8 @@ class TestClient:
         )
         if hasattr(resp, "headers"):
             py_resp.headers = resp.headers
+        if hasattr(resp, "extra_headers"):
+            py_resp.extra_headers = resp.extra_headers
         return py_resp
 
     def get(self, path: str, **kwargs) -> Response:
@@ -0,0 +1,177 @@
+//! # Handler Cost Measurement
+//!
+//! Measures wall time, GIL-held time and allocation deltas around Python
+//! handler invocations and feeds them into the core `ResourceAccounting`
+//! and `GilMetrics`. Sync handlers that hold the GIL past a threshold are
+//! reported, since they stall every other request on the runtime.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only measures; aggregation lives in `pyvectora_core::accounting`
+//! - **D**: Handler adapters depend on `HandlerInstrumentation`, not on the tables
+//!
+//! ## Notes
+//!
+//! GIL time covers the sections driven from Rust (calling the handler and
+//! converting its result). Time an async handler spends running on the
+//! event loop between awaits is included in wall time only. Allocation
+//! deltas count Python memory blocks (`sys.getallocatedblocks()`).
+
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyvectora_core::accounting::{GilMetrics, HandlerSample, ResourceAccounting};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Default GIL hold time above which sync handlers are reported
+pub const DEFAULT_GIL_WARN_THRESHOLD: Duration = Duration::from_millis(50);
+
+/// Measurement sinks for one route's handler
+#[derive(Clone)]
+pub struct HandlerInstrumentation {
+    route: Arc<str>,
+    accounting: Option<Arc<ResourceAccounting>>,
+    gil_metrics: Arc<GilMetrics>,
+    gil_warn_threshold: Option<Duration>,
+    warned: Arc<AtomicBool>,
+}
+
+impl HandlerInstrumentation {
+    pub fn new(
+        route: String,
+        accounting: Option<Arc<ResourceAccounting>>,
+        gil_metrics: Arc<GilMetrics>,
+        gil_warn_threshold: Option<Duration>,
+    ) -> Self {
+        Self {
+            route: Arc::from(route),
+            accounting,
+            gil_metrics,
+            gil_warn_threshold,
+            warned: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Start measuring an invocation
+    pub fn meter(&self) -> InvocationMeter {
+        InvocationMeter::new(self.accounting.is_some())
+    }
+
+    /// Record a finished invocation
+    ///
+    /// The first slow sync call on a route is logged as a warning, later
+    /// ones at debug level to keep logs readable under load.
+    pub fn record(&self, meter: &InvocationMeter) {
+        let sample = meter.finish();
+        let slow = meter.sync_handler
+            && self
+                .gil_warn_threshold
+                .is_some_and(|threshold| sample.gil > threshold);
+        self.gil_metrics.record(sample.gil, slow);
+        if slow {
+            let held_ms = sample.gil.as_secs_f64() * 1000.0;
+            if self.warned.swap(true, Ordering::Relaxed) {
+                debug!(route = %self.route, held_ms, "sync handler held the GIL");
+            } else {
+                warn!(
+                    "Sync handler for {} held the GIL for {:.1}ms, blocking other requests; \
+                     make it `async def` or move blocking work to `asyncio.to_thread`",
+                    self.route, held_ms
+                );
+            }
+        }
+        if let Some(accounting) = &self.accounting {
+            accounting.record(&self.route, sample);
+        }
+    }
+}
+
+/// Per-invocation measurement state
+pub struct InvocationMeter {
+    start: Instant,
+    gil: Duration,
+    alloc_blocks: i64,
+    track_allocations: bool,
+    /// Whether the handler ran synchronously while holding the GIL
+    pub sync_handler: bool,
+}
+
+impl InvocationMeter {
+    pub fn new(track_allocations: bool) -> Self {
+        Self {
+            start: Instant::now(),
+            gil: Duration::ZERO,
+            alloc_blocks: 0,
+            track_allocations,
+            sync_handler: false,
+        }
+    }
+
+    /// Run `f` with the GIL held, adding the held time to the meter
+    pub fn with_gil<R>(&mut self, f: impl FnOnce(Python<'_>) -> R) -> R {
+        Python::with_gil(|py| {
+            let held = Instant::now();
+            let blocks_before = self.allocated_blocks(py);
+            let result = f(py);
+            if let (Some(before), Some(after)) = (blocks_before, self.allocated_blocks(py)) {
+                self.alloc_blocks += after - before;
+            }
+            self.gil += held.elapsed();
+            result
+        })
+    }
+
+    pub fn finish(&self) -> HandlerSample {
+        HandlerSample {
+            wall: self.start.elapsed(),
+            gil: self.gil,
+            alloc_blocks: self.alloc_blocks,
+        }
+    }
+
+    fn allocated_blocks(&self, py: Python<'_>) -> Option<i64> {
+        if !self.track_allocations {
+            return None;
+        }
+        py.import("sys")
+            .and_then(|sys| sys.call_method0("getallocatedblocks"))
+            .and_then(PyAny::extract::<i64>)
+            .ok()
+    }
+}
+
+/// Render the GIL counters as a dict
+pub fn gil_stats_to_py(py: Python<'_>, metrics: &GilMetrics) -> PyResult<PyObject> {
+    let snapshot = metrics.snapshot();
+    let stats = PyDict::new(py);
+    stats.set_item("requests", snapshot.requests)?;
+    stats.set_item("total_gil_ms", millis(snapshot.total))?;
+    stats.set_item("max_gil_ms", millis(snapshot.max))?;
+    stats.set_item("slow_requests", snapshot.slow_requests)?;
+    Ok(stats.into_py(py))
+}
+
+/// Render the accounting table as a list of dicts (most expensive first)
+pub fn stats_to_py(py: Python<'_>, table: &ResourceAccounting) -> PyResult<Vec<PyObject>> {
+    table
+        .snapshot()
+        .into_iter()
+        .map(|(route, stats)| {
+            let row = PyDict::new(py);
+            row.set_item("route", route)?;
+            row.set_item("calls", stats.calls)?;
+            row.set_item("total_wall_ms", millis(stats.total_wall))?;
+            row.set_item("mean_wall_ms", millis(stats.mean_wall()))?;
+            row.set_item("max_wall_ms", millis(stats.max_wall))?;
+            row.set_item("total_gil_ms", millis(stats.total_gil))?;
+            row.set_item("alloc_blocks", stats.total_alloc_blocks)?;
+            Ok(row.into_py(py))
+        })
+        .collect()
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,55 @@
+//! # ACME Python Bindings
+//!
+//! Exposes the HTTP-01 challenge store so a Python ACME client can publish
+//! challenge responses on a running app.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only wraps the core `ChallengeStore`
+//! - **D**: Apps read challenges through the shared store, not the client
+
+use pyo3::prelude::*;
+use pyvectora_core::acme::ChallengeStore;
+
+/// Pending ACME HTTP-01 challenges shared with one or more apps
+///
+/// # Example (Python)
+///
+/// ```python
+/// challenges = app.enable_acme_http01()
+/// challenges.set(token, key_authorization)  # from the ACME client
+/// ```
+#[pyclass(name = "AcmeChallenges")]
+#[derive(Clone, Default)]
+pub struct PyAcmeChallenges {
+    pub(crate) inner: ChallengeStore,
+}
+
+#[pymethods]
+impl PyAcmeChallenges {
+    #[new]
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve `key_authorization` for `token`
+    fn set(&self, token: String, key_authorization: String) {
+        self.inner.insert(token, key_authorization);
+    }
+
+    /// Stop serving `token`
+    fn remove(&self, token: &str) -> Option<String> {
+        self.inner.remove(token)
+    }
+
+    /// Pending key authorization for `token`
+    fn get(&self, token: &str) -> Option<String> {
+        self.inner.get(token)
+    }
+}
+
+/// Register ACME classes with Python module
+pub fn register_acme_classes(m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyAcmeChallenges>()?;
+    Ok(())
+}
@@ -1,20 +1,40 @@
+use crate::error::HandlerTimeout;
 use pyo3::prelude::*;
+use pyvectora_core::server::PyRequest as RustRequest;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio_util::sync::CancellationToken;
 
 #[pyclass]
 pub struct PyExecutionContext {
     pub(crate) token: CancellationToken,
+    pub(crate) started: Instant,
+    pub(crate) deadline: Option<Instant>,
+    /// Set when the handler was cancelled by the route timeout (504)
+    pub(crate) timed_out: Arc<AtomicBool>,
 }
 
 #[pymethods]
 impl PyExecutionContext {
-    /// Check if the request has been cancelled
+    /// Check if the request has been cancelled (client gone or timed out)
     fn cancelled(&self) -> bool {
         self.token.is_cancelled()
     }
 
+    /// Check if the request was cancelled because the handler timed out
+    fn timed_out(&self) -> bool {
+        self.timed_out.load(Ordering::Acquire)
+    }
+
     /// Raise an exception if cancelled
+    ///
+    /// Raises `HandlerTimeout` (an `asyncio.CancelledError`) after the
+    /// route timeout fired, else `ConnectionAbortedError`.
     fn raise_if_cancelled(&self) -> PyResult<()> {
+        if self.timed_out() {
+            return Err(HandlerTimeout::new_err("Handler timed out"));
+        }
         if self.cancelled() {
             return Err(pyo3::exceptions::PyConnectionAbortedError::new_err(
                 "Request cancelled",
@@ -22,10 +42,109 @@ impl PyExecutionContext {
         }
         Ok(())
     }
+
+    /// Set a time budget (in seconds) for the rest of the request
+    fn set_timeout(&mut self, seconds: f64) -> PyResult<()> {
+        let timeout = Duration::try_from_secs_f64(seconds)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        self.deadline = Some(Instant::now() + timeout);
+        Ok(())
+    }
+
+    /// Deadline as a UNIX timestamp, or None if no deadline is set
+    ///
+    /// Starts at the route or server handler timeout; `set_timeout`
+    /// replaces it.
+    fn deadline(&self) -> Option<f64> {
+        let deadline = self.deadline?;
+        let now = Instant::now();
+        let wall = if deadline >= now {
+            SystemTime::now() + (deadline - now)
+        } else {
+            SystemTime::now() - (now - deadline)
+        };
+        wall.duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs_f64())
+    }
+
+    /// Seconds left before the deadline, or None if no deadline is set
+    fn remaining(&self) -> Option<f64> {
+        self.deadline
+            .map(|d| d.saturating_duration_since(Instant::now()).as_secs_f64())
+    }
+
+    /// Seconds since the request was received
+    fn elapsed(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
 }
 
 impl PyExecutionContext {
-    pub fn new(token: CancellationToken) -> Self {
-        Self { token }
+    /// Context of `req`: its cancellation token, start time and deadline
+    pub fn new(req: &RustRequest) -> Self {
+        Self {
+            token: req.cancellation.clone(),
+            started: req.received_at,
+            deadline: req.deadline,
+            timed_out: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// Cancels a handler whose response future is dropped before it finishes
+///
+/// The server drops the future on a handler timeout (504) or when the
+/// client goes away; the request's token is then cancelled and so is the
+/// asyncio task running the handler coroutine. A drop past the deadline
+/// is a timeout, recorded in the context before the token is cancelled.
+pub(crate) struct CancelOnDrop {
+    token: Option<CancellationToken>,
+    task: Option<PyObject>,
+    deadline: Option<Instant>,
+    timed_out: Arc<AtomicBool>,
+}
+
+impl CancelOnDrop {
+    /// Guard cancelling the request of `ctx`
+    pub(crate) fn new(ctx: &PyExecutionContext) -> Self {
+        Self {
+            token: Some(ctx.token.clone()),
+            task: None,
+            deadline: ctx.deadline,
+            timed_out: Arc::clone(&ctx.timed_out),
+        }
+    }
+
+    /// Also cancel `task`, the `concurrent.futures.Future` of the coroutine
+    pub(crate) fn watch(&mut self, task: PyObject) {
+        self.task = Some(task);
+    }
+
+    /// The handler finished: drop without cancelling
+    pub(crate) fn disarm(mut self) {
+        self.token = None;
+        self.task = None;
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(token) = self.token.take() {
+            if self
+                .deadline
+                .is_some_and(|deadline| Instant::now() >= deadline)
+            {
+                self.timed_out.store(true, Ordering::Release);
+            }
+            token.cancel();
+        }
+        if let Some(task) = self.task.take() {
+            Python::with_gil(|py| {
+                if let Err(err) = task.call_method0(py, "cancel") {
+                    err.print(py);
+                }
+            });
+        }
     }
 }
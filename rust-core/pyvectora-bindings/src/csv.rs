@@ -0,0 +1,101 @@
+//! # CSV Export Bindings
+//!
+//! Streams Python row iterables (lists, tuples, dicts or database cursors)
+//! as CSV, encoding each row in Rust so large exports never buffer in Python.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only maps Python rows onto CSV records
+//! - **D**: Quoting, header and gzip handling live in `pyvectora_core::csv`
+
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString};
+use pyvectora_core::csv::{encode_record, export_stream, CONTENT_TYPE};
+use pyvectora_core::server::{Bytes, PyResponse as RustResponse};
+
+use crate::{convert_py_error, spawn_chunk_producer, StreamSource};
+
+/// Whether a handler result should be streamed as CSV (`_is_csv` flag)
+pub fn is_csv_response(py: Python<'_>, result: &PyObject) -> bool {
+    result
+        .as_ref(py)
+        .getattr("_is_csv")
+        .and_then(PyAny::extract::<bool>)
+        .unwrap_or(false)
+}
+
+/// Stream a Python CSV response, one record per yielded row
+pub fn stream_csv_response(result: &PyObject, locals: &pyo3_asyncio::TaskLocals) -> RustResponse {
+    let extracted = Python::with_gil(|py| -> PyResult<_> {
+        let resp = result.as_ref(py);
+        let source = StreamSource::extract(resp, CONTENT_TYPE)?;
+        let header = resp
+            .getattr("header")
+            .ok()
+            .map(PyAny::extract::<Option<Vec<String>>>)
+            .transpose()?
+            .flatten();
+        let gzip = resp
+            .getattr("gzip")
+            .and_then(PyAny::extract::<bool>)
+            .unwrap_or(false);
+        Ok((source, header, gzip))
+    });
+    let (source, header, gzip) = match extracted {
+        Ok(v) => v,
+        Err(err) => return convert_py_error(err),
+    };
+
+    let header_line = header.as_ref().map(encode_record);
+    let rows = spawn_chunk_producer(source.iter, source.is_async, locals, move |row| {
+        py_row_to_record(row, header.as_deref()).map(Bytes::from)
+    });
+
+    let mut response =
+        RustResponse::streaming(export_stream(header_line, rows, gzip), source.content_type)
+            .with_status(source.status);
+    response.headers.extend(source.headers);
+    if gzip {
+        response.set_header("Content-Encoding", "gzip");
+    }
+    response
+}
+
+/// Encode a row; dict rows are ordered by `header` (missing keys are empty)
+fn py_row_to_record(row: &PyAny, header: Option<&[String]>) -> PyResult<String> {
+    if let Ok(dict) = row.downcast::<PyDict>() {
+        let Some(header) = header else {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "CSV dict rows require a header",
+            ));
+        };
+        let fields = header
+            .iter()
+            .map(|key| {
+                dict.get_item(key.as_str())?
+                    .map_or_else(|| Ok(String::new()), cell_to_string)
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+        return Ok(encode_record(fields));
+    }
+    if row.is_instance_of::<PyString>() {
+        return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "CSV rows must be sequences or dicts, not str",
+        ));
+    }
+    let fields = row
+        .iter()?
+        .map(|cell| cell_to_string(cell?))
+        .collect::<PyResult<Vec<_>>>()?;
+    Ok(encode_record(fields))
+}
+
+fn cell_to_string(cell: &PyAny) -> PyResult<String> {
+    if cell.is_none() {
+        return Ok(String::new());
+    }
+    if let Ok(s) = cell.downcast::<PyString>() {
+        return Ok(s.to_str()?.to_string());
+    }
+    Ok(cell.str()?.to_str()?.to_string())
+}
@@ -12,6 +12,9 @@
 use pyo3::create_exception;
 use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
+use pyvectora_core::error::{panic_message, Error, RequestErrorKind};
+use pyvectora_core::multipart::{MultipartError, UploadTooLarge};
+use pyvectora_core::validation::RequestValidationError;
 use std::panic::UnwindSafe;
 
 create_exception!(pyvectora, PyVectoraError, pyo3::exceptions::PyException);
@@ -21,6 +24,42 @@ create_exception!(pyvectora, NotFoundError, PyVectoraError);
 create_exception!(pyvectora, AuthenticationError, PyVectoraError);
 create_exception!(pyvectora, DatabaseError, PyVectoraError);
 create_exception!(pyvectora, ConfigurationError, PyVectoraError);
+create_exception!(
+    pyvectora,
+    RequestLimitError,
+    ValidationError,
+    "A request exceeded a configured limit; `limit` and `actual` give the sizes."
+);
+create_exception!(
+    pyvectora,
+    PayloadTooLarge,
+    RequestLimitError,
+    "The body exceeded the size limit (answered 413)."
+);
+create_exception!(
+    pyvectora,
+    UriTooLong,
+    RequestLimitError,
+    "The path and query string exceeded the length limit (answered 414)."
+);
+create_exception!(
+    pyvectora,
+    TooManyQueryParams,
+    RequestLimitError,
+    "The query string had more parameters than allowed (answered 400)."
+);
+create_exception!(
+    pyvectora,
+    HandlerTimeout,
+    pyo3::exceptions::asyncio::CancelledError,
+    "The route timeout fired (answered 504); raised by `context.raise_if_cancelled()`."
+);
+create_exception!(
+    pyvectora,
+    HTTPException,
+    PyVectoraError,
+    "Answered with its `status`, `detail` and `headers` attributes."
+);
 
 /// Internal error type for bindings layer
 #[derive(Debug)]
@@ -94,16 +133,8 @@ pub fn catch_panic<F, T>(f: F) -> PanicSafeResult<T>
 where
     F: FnOnce() -> T + UnwindSafe,
 {
-    std::panic::catch_unwind(f).map_err(|panic_payload| {
-        let msg = if let Some(s) = panic_payload.downcast_ref::<&str>() {
-            s.to_string()
-        } else if let Some(s) = panic_payload.downcast_ref::<String>() {
-            s.clone()
-        } else {
-            "Unknown panic".to_string()
-        };
-        BindingsError::Panic(msg)
-    })
+    std::panic::catch_unwind(f)
+        .map_err(|panic_payload| BindingsError::Panic(panic_message(&*panic_payload)))
 }
 
 /// Execute a closure with panic catching, returning a default on panic
@@ -116,6 +147,65 @@ where
     std::panic::catch_unwind(f).unwrap_or(default)
 }
 
+/// Python exception for a core error reporting an exceeded request limit
+///
+/// `Error::PayloadTooLarge` raises `PayloadTooLarge`, `Error::UriTooLong`
+/// `UriTooLong` and a `TooManyQueryParams` bad request `TooManyQueryParams`,
+/// with `limit` and `actual` attributes where known; other errors give `None`.
+pub fn limit_error(py: Python<'_>, err: &Error) -> Option<PyErr> {
+    let (exc, sizes) = match err {
+        Error::PayloadTooLarge { limit, actual } => (
+            PayloadTooLarge::new_err(err.to_string()),
+            Some((*limit, *actual)),
+        ),
+        Error::UriTooLong { limit, actual } => (
+            UriTooLong::new_err(err.to_string()),
+            Some((*limit, *actual)),
+        ),
+        Error::BadRequest {
+            kind: RequestErrorKind::TooManyQueryParams,
+            message,
+        } => (TooManyQueryParams::new_err(message.clone()), None),
+        _ => return None,
+    };
+    let (limit, actual) = sizes.unzip();
+    // Plain attributes on a fresh exception instance; setting cannot fail
+    let _ = exc.value(py).setattr("limit", limit);
+    let _ = exc.value(py).setattr("actual", actual);
+    Some(exc)
+}
+
+/// HTTP status and reason for exceptions handlers raise on purpose
+///
+/// `NotFoundError` → 404, `PayloadTooLarge` and `UploadTooLarge` → 413,
+/// `UriTooLong` → 414, other `RequestLimitError`s and `MultipartError`s
+/// → 400, `ValidationError` and `RequestValidationError` → 422 and
+/// `AuthenticationError` → 401; anything else is an unexpected failure
+/// (`None`).
+pub fn http_status_for(py: Python<'_>, err: &PyErr) -> Option<(u16, &'static str)> {
+    if err.is_instance_of::<NotFoundError>(py) {
+        Some((404, "Not Found"))
+    } else if err.is_instance_of::<PayloadTooLarge>(py) {
+        Some((413, "Payload Too Large"))
+    } else if err.is_instance_of::<UriTooLong>(py) {
+        Some((414, "URI Too Long"))
+    } else if err.is_instance_of::<RequestLimitError>(py) {
+        Some((400, "Bad Request"))
+    } else if err.is_instance_of::<ValidationError>(py)
+        || err.is_instance_of::<RequestValidationError>(py)
+    {
+        Some((422, "Unprocessable Entity"))
+    } else if err.is_instance_of::<AuthenticationError>(py) {
+        Some((401, "Unauthorized"))
+    } else if err.is_instance_of::<UploadTooLarge>(py) {
+        Some((413, "Payload Too Large"))
+    } else if err.is_instance_of::<MultipartError>(py) {
+        Some((400, "Bad Request"))
+    } else {
+        None
+    }
+}
+
 /// Register error types with Python module
 pub fn register_exceptions(m: &PyModule) -> PyResult<()> {
     m.add("PyVectoraError", m.py().get_type::<PyVectoraError>())?;
@@ -130,6 +220,21 @@ pub fn register_exceptions(m: &PyModule) -> PyResult<()> {
         "ConfigurationError",
         m.py().get_type::<ConfigurationError>(),
     )?;
+    m.add("RequestLimitError", m.py().get_type::<RequestLimitError>())?;
+    m.add("PayloadTooLarge", m.py().get_type::<PayloadTooLarge>())?;
+    m.add("UriTooLong", m.py().get_type::<UriTooLong>())?;
+    m.add(
+        "TooManyQueryParams",
+        m.py().get_type::<TooManyQueryParams>(),
+    )?;
+    m.add("HandlerTimeout", m.py().get_type::<HandlerTimeout>())?;
+    m.add("HTTPException", m.py().get_type::<HTTPException>())?;
+    m.add("MultipartError", m.py().get_type::<MultipartError>())?;
+    m.add("UploadTooLarge", m.py().get_type::<UploadTooLarge>())?;
+    m.add(
+        "RequestValidationError",
+        m.py().get_type::<RequestValidationError>(),
+    )?;
     Ok(())
 }
 
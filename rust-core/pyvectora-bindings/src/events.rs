@@ -0,0 +1,118 @@
+//! # Framework Event Python Bindings
+//!
+//! Python callbacks subscribed to the core event bus, and the
+//! `handler_exception` events only the bindings can see.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only bridges events to Python callables
+//! - **O**: New core event kinds reach Python without changes here
+//! - **D**: Callbacks receive plain dicts, not core types
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyvectora_core::events::{self, Event, EventKind, StackFrame};
+use pyvectora_core::server::PyRequest as RustRequest;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{error, json_to_pyobject};
+
+/// Call `callback(event)` for every framework event named `event`
+///
+/// `event` is one of `request_started`, `request_finished`,
+/// `handler_exception`, `ws_connected`, `db_query`, `cache` or
+/// `rate_limit`; the callback gets a
+/// dict with an `"event"` key and the event's fields. It runs on the
+/// server thread that emitted the event, so it should only record it.
+/// Exceptions it raises are logged and ignored.
+///
+/// Returns the subscription id for `unsubscribe_event`.
+#[pyfunction]
+pub fn subscribe_event(event: &str, callback: PyObject) -> PyResult<u64> {
+    let kind = EventKind::parse(event).ok_or_else(|| {
+        let known: Vec<&str> = EventKind::ALL.iter().map(|k| k.as_str()).collect();
+        PyValueError::new_err(format!(
+            "Unknown event '{event}' (expected one of: {})",
+            known.join(", ")
+        ))
+    })?;
+    let id = events::global().subscribe(
+        kind,
+        Arc::new(move |event: &Event| {
+            Python::with_gil(|py| {
+                let delivered = serde_json::to_value(event)
+                    .map_err(|e| PyValueError::new_err(e.to_string()))
+                    .and_then(|payload| json_to_pyobject(py, &payload))
+                    .and_then(|payload| callback.call1(py, (payload,)));
+                if let Err(err) = delivered {
+                    warn!("Subscriber of '{}' raised: {}", kind, err);
+                }
+            });
+        }),
+    );
+    Ok(id)
+}
+
+/// Remove a subscription made with `subscribe_event`
+///
+/// Returns False if it was already removed.
+#[pyfunction]
+pub fn unsubscribe_event(subscription: u64) -> bool {
+    events::global().unsubscribe(subscription)
+}
+
+/// Report an exception that escaped a handler as `handler_exception`
+///
+/// `HTTPException` is how handlers answer on purpose and is not reported.
+/// Callers check `events::is_active` first to skip taking the GIL.
+pub fn report_handler_exception(py: Python<'_>, err: &PyErr, req: &RustRequest) {
+    if err.is_instance_of::<error::HTTPException>(py) {
+        return;
+    }
+    let exception = err
+        .get_type(py)
+        .name()
+        .map_or_else(|_| "Exception".to_string(), ToString::to_string);
+    events::emit(&Event::HandlerException {
+        request_id: req.header("x-request-id").unwrap_or_default().to_string(),
+        method: req.method.to_string(),
+        path: req.path.clone(),
+        route: req.route.as_deref().map(String::from),
+        user: req.subject(),
+        exception,
+        message: err.value(py).to_string(),
+        frames: traceback_frames(py, err).unwrap_or_default(),
+    });
+}
+
+/// Frames of `err`'s traceback, outermost call first
+fn traceback_frames(py: Python<'_>, err: &PyErr) -> PyResult<Vec<StackFrame>> {
+    let Some(traceback) = err.traceback(py) else {
+        return Ok(Vec::new());
+    };
+    let summary = py
+        .import("traceback")?
+        .call_method1("extract_tb", (traceback,))?;
+    summary
+        .iter()?
+        .map(|frame| {
+            let frame = frame?;
+            let lineno: Option<u32> = frame.getattr("lineno")?.extract()?;
+            let context_line: Option<String> = frame.getattr("line")?.extract()?;
+            Ok(StackFrame {
+                filename: frame.getattr("filename")?.extract()?,
+                function: frame.getattr("name")?.extract()?,
+                lineno: lineno.unwrap_or(0),
+                context_line: context_line.filter(|line| !line.is_empty()),
+            })
+        })
+        .collect()
+}
+
+/// Register event subscription functions with Python module
+pub fn register_event_functions(m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(subscribe_event, m)?)?;
+    m.add_function(wrap_pyfunction!(unsubscribe_event, m)?)?;
+    Ok(())
+}
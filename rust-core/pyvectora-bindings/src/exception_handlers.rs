@@ -0,0 +1,74 @@
+//! # Exception Handler Registry
+//!
+//! Maps Python exception types to callbacks that turn an exception raised
+//! by a handler into a response, before the generic 500 path. Lets apps
+//! answer their own domain errors (`OutOfStock`, `PermissionDenied`) without
+//! wrapping every handler in `try`/`except`.
+//!
+//! Also records whether unhandled exceptions are answered in debug mode,
+//! with their traceback and the request.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only selects exception handlers; calling them is up to the caller
+//! - **O**: New exception types are handled by registration
+//!
+//! ## Lookup
+//!
+//! The exception's MRO is walked from most to least specific, so a handler
+//! registered for a subclass wins over one for its base class.
+
+use pyo3::exceptions::{PyBaseException, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+
+/// Registered `exception type -> handler` pairs
+#[derive(Clone, Default)]
+pub struct ExceptionHandlers {
+    entries: Vec<(Py<PyType>, PyObject)>,
+    debug: bool,
+}
+
+impl ExceptionHandlers {
+    /// Register (or replace) the handler for exception type `ty`
+    pub fn register(&mut self, ty: &PyAny, handler: PyObject) -> PyResult<()> {
+        let ty = ty
+            .downcast::<PyType>()
+            .ok()
+            .filter(|ty| ty.is_subclass_of::<PyBaseException>().unwrap_or(false))
+            .ok_or_else(|| PyTypeError::new_err("exception_handler() expects an exception type"))?;
+        if !handler.as_ref(ty.py()).is_callable() {
+            return Err(PyTypeError::new_err("exception handler must be callable"));
+        }
+        self.entries
+            .retain(|(registered, _)| !registered.as_ref(ty.py()).is(ty));
+        self.entries.push((ty.into(), handler));
+        Ok(())
+    }
+
+    /// Whether no handler is registered
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Answer unhandled exceptions with their traceback (development only)
+    pub fn set_debug(&mut self, debug: bool) {
+        self.debug = debug;
+    }
+
+    /// Whether unhandled exceptions are answered in debug mode
+    pub const fn is_debug(&self) -> bool {
+        self.debug
+    }
+
+    /// The handler registered for `err`'s most specific class, if any
+    pub fn find(&self, py: Python<'_>, err: &PyErr) -> Option<&PyObject> {
+        let mro = err.get_type(py).getattr("__mro__").ok()?;
+        mro.iter().ok()?.flatten().find_map(|cls| {
+            self.entries
+                .iter()
+                .find(|(registered, _)| registered.as_ref(py).is(cls))
+                .map(|(_, handler)| handler)
+        })
+    }
+}
@@ -12,52 +12,109 @@
 
 use pyo3::exceptions::{PyStopAsyncIteration, PyStopIteration};
 use pyo3::prelude::*;
-use pyo3::types::{PyBytes, PyDict, PyString};
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{IntoPyDict, PyBytes, PyDict, PyList, PyString, PyTuple};
+use pyvectora_core::accounting::{GilMetrics, ResourceAccounting};
+use pyvectora_core::acme_client::{AcmeClient, AcmeConfig, AcmeRenewal};
+use pyvectora_core::events::EventKind;
 use pyvectora_core::middleware::{
-    CorsMiddleware, LoggingMiddleware, RateLimitMiddleware, TimingMiddleware,
+    CompressionMiddleware, CorsMiddleware, LoggingMiddleware, RateLimitMiddleware, TierLimit,
+    TieredRateLimitMiddleware, TimingMiddleware,
 };
-use pyvectora_core::middleware::{Middleware, MiddlewareResult};
-use pyvectora_core::router::Method;
+use pyvectora_core::middleware::{Middleware, MiddlewareChain, MiddlewareResult};
+use pyvectora_core::route_table::RouteTable;
+use pyvectora_core::validation::RequestValidationError;
+use pyvectora_core::router::{Method, ParamMismatch, Router};
 use pyvectora_core::server::{
-    Handler, PyRequest as RustRequest, PyResponse as RustResponse, Server,
+    BadRequestHook, Bytes, Handler, PyRequest as RustRequest, PyResponse as RustResponse, Server,
 };
 use std::collections::HashMap;
-use std::sync::{Arc, OnceLock, RwLock};
-use tokio::runtime::Runtime;
-use tokio_util::sync::CancellationToken;
-use tracing::warn;
-use tracing_subscriber::EnvFilter;
-
+use std::sync::{Arc, RwLock};
+use tracing::{error, warn};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{filter, EnvFilter, Layer};
+
+mod accounting;
+mod acme;
+mod csv;
 mod database;
 mod error;
-
-use error::register_exceptions;
-use pyvectora_core::PyRequest;
+mod events;
+mod exception_handlers;
+mod log_context;
+mod logging_bridge;
+mod ndjson;
+mod request_stream;
+mod retry;
+mod runtime;
+mod serializers;
+mod spooled;
+mod sse;
+mod websocket;
+mod wsgi;
+
+use error::{limit_error, register_exceptions, BindingsError};
+use pyvectora_core::metrics::{MetricLabel, RequestMetrics};
+use pyvectora_core::profiling::{self, ProfilingEndpoints};
+use pyvectora_core::read_only::ReadOnlyMode;
+use pyvectora_core::reporting::{self, ReporterConfig};
+use pyvectora_core::signals::ShutdownSignal;
+use pyvectora_core::watchdog::MemoryLimits;
+use pyvectora_core::{
+    AdminBrowser, BindAddress, Canary, Deprecation, ErrorFormat, MultipartLimits, ProblemDetails,
+    PyHeaders, PyRequest, RequestState, ResponsePolicy, RouteLimits, RouteRegistry,
+    RouteRequirements, SameSite, Session, SessionMiddleware, SetCookie, SocketOptions, StickyKey,
+    TargetLimits, TlsConfig, UnixSocket, UploadedFile,
+};
 mod context;
-use context::PyExecutionContext;
-use database::register_database_classes;
-
-/// Global Tokio runtime for test client operations
-///
-/// Lazily initialized on first use, shared across all test requests.
-static GLOBAL_RUNTIME: OnceLock<Runtime> = OnceLock::new();
-
-/// Get or create the global Tokio runtime
-///
-/// Thread-safe, lock-free after first initialization.
-/// Made public for database module access.
-pub(crate) fn get_runtime() -> &'static Runtime {
-    GLOBAL_RUNTIME.get_or_init(|| Runtime::new().expect("Failed to create Tokio runtime"))
-}
+use accounting::{
+    gil_stats_to_py, stats_to_py, HandlerInstrumentation, InvocationMeter,
+    DEFAULT_GIL_WARN_THRESHOLD,
+};
+use acme::{register_acme_classes, PyAcmeChallenges};
+use context::{CancelOnDrop, PyExecutionContext};
+use csv::{is_csv_response, stream_csv_response};
+use database::{register_database_classes, PyDatabaseNative};
+use events::{register_event_functions, report_handler_exception};
+use log_context::{register_log_context_functions, request_context};
+use logging_bridge::{forwarding_layer, register_logging_bridge_functions};
+use exception_handlers::ExceptionHandlers;
+use ndjson::{is_ndjson_response, stream_ndjson_response};
+use request_stream::register_request_stream_classes;
+use retry::register_retry_functions;
+pub(crate) use runtime::get_runtime;
+use runtime::{init_asyncio_once, register_runtime_classes, AppRuntime};
+use serializers::SerializerRegistry;
+use spooled::{is_spooled_response, send_spooled_response};
+use sse::{is_sse_response, register_sse_classes, stream_sse_response};
+use websocket::{create_ws_handler_adapter, register_websocket_classes};
+use wsgi::{WsgiMount, WSGI_METHODS};
 
 /// Initialize tracing for the library
+///
+/// JSON lines on stdout, muted (except for records that came from Python)
+/// while `forward_tracing_to_logging` hands events to Python loggers.
 fn init_tracing() {
-    let _ = tracing_subscriber::fmt()
-        .with_env_filter(
-            EnvFilter::from_default_env().add_directive("pyvectora=info".parse().unwrap()),
-        )
-        .json()
-        .try_init();
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let forwarding = forwarding_layer();
+        let json = tracing_subscriber::fmt::layer().json().with_filter(
+            filter::dynamic_filter_fn(|metadata, _| {
+                !forwarding.is_forwarding()
+                    || metadata.target() == pyvectora_core::log_bridge::PYTHON_TARGET
+            }),
+        );
+        // Python records were already filtered by their logger's level
+        let env_filter = EnvFilter::from_default_env()
+            .add_directive("pyvectora=info".parse().unwrap())
+            .add_directive("python=trace".parse().unwrap());
+        let _ = tracing_subscriber::registry()
+            .with(env_filter)
+            .with(json)
+            .with(forwarding.clone())
+            .try_init();
+    });
 }
 
 /// Convert JSON value to Python object
@@ -92,6 +149,61 @@ fn json_to_pyobject(py: Python<'_>, value: &serde_json::Value) -> PyResult<PyObj
     })
 }
 
+/// Convert a Python object to a JSON value
+///
+/// Supports `None`, `bool`, `int`, `float`, `str`, lists, tuples and dicts
+/// (keys are converted with `str()`). Non-finite floats become `null`.
+fn pyobject_to_json(obj: &PyAny) -> PyResult<serde_json::Value> {
+    if obj.is_none() {
+        return Ok(serde_json::Value::Null);
+    }
+    if let Ok(b) = obj.downcast::<pyo3::types::PyBool>() {
+        return Ok(serde_json::Value::Bool(b.is_true()));
+    }
+    if obj.is_instance_of::<pyo3::types::PyLong>() {
+        if let Ok(i) = obj.extract::<i64>() {
+            return Ok(i.into());
+        }
+        if let Ok(u) = obj.extract::<u64>() {
+            return Ok(u.into());
+        }
+    }
+    if let Ok(f) = obj.downcast::<pyo3::types::PyFloat>() {
+        return Ok(serde_json::Number::from_f64(f.value())
+            .map_or(serde_json::Value::Null, serde_json::Value::Number));
+    }
+    if let Ok(s) = obj.downcast::<PyString>() {
+        return Ok(serde_json::Value::String(s.to_str()?.to_string()));
+    }
+    if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut map = serde_json::Map::with_capacity(dict.len());
+        for (k, v) in dict {
+            map.insert(k.str()?.to_str()?.to_string(), pyobject_to_json(v)?);
+        }
+        return Ok(serde_json::Value::Object(map));
+    }
+    if obj.is_instance_of::<PyList>() || obj.is_instance_of::<pyo3::types::PyTuple>() {
+        return obj
+            .iter()?
+            .map(|item| pyobject_to_json(item?))
+            .collect::<PyResult<Vec<_>>>()
+            .map(serde_json::Value::Array);
+    }
+    Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(format!(
+        "Object of type {} is not JSON serializable",
+        obj.get_type().name()?
+    )))
+}
+
+/// `json.dumps(value)`, with `json.dumps` looked up once per process
+pub(crate) fn json_dumps(value: &PyAny) -> PyResult<String> {
+    static DUMPS: GILOnceCell<PyObject> = GILOnceCell::new();
+    let py = value.py();
+    let dumps =
+        DUMPS.get_or_try_init(py, || py.import("json")?.getattr("dumps").map(Into::into))?;
+    dumps.call1(py, (value,))?.extract(py)
+}
+
 /// Check if a Python object is a coroutine (async result)
 ///
 /// Uses `inspect.iscoroutine()` to detect async handler results.
@@ -126,6 +238,9 @@ pub struct PyResponse {
     content_type: String,
     #[pyo3(get, set)]
     headers: HashMap<String, String>,
+    /// Headers sent once per entry, e.g. one `Set-Cookie` per cookie
+    #[pyo3(get, set)]
+    extra_headers: Vec<(String, String)>,
 }
 
 #[pymethods]
@@ -138,17 +253,16 @@ impl PyResponse {
             body: body.to_string(),
             content_type: content_type.to_string(),
             headers: HashMap::new(),
+            extra_headers: Vec::new(),
         }
     }
 
     /// Create a JSON response
     #[staticmethod]
     #[pyo3(signature = (data, status=200))]
-    fn json(py: Python<'_>, data: &PyAny, status: u16) -> PyResult<Self> {
+    fn json(data: &PyAny, status: u16) -> PyResult<Self> {
         let json_str = if data.is_instance_of::<PyDict>() {
-            let json_module = py.import("json")?;
-            let dumps = json_module.getattr("dumps")?;
-            dumps.call1((data,))?.extract::<String>()?
+            json_dumps(data)?
         } else if data.is_instance_of::<PyString>() {
             data.extract::<String>()?
         } else {
@@ -162,6 +276,7 @@ impl PyResponse {
             body: json_str,
             content_type: "application/json".to_string(),
             headers: HashMap::new(),
+            extra_headers: Vec::new(),
         })
     }
 
@@ -182,6 +297,57 @@ impl PyResponse {
         slf
     }
 
+    /// Append a header, keeping earlier values of the same name
+    fn append_header(&mut self, key: &str, value: &str) {
+        self.extra_headers.push((key.to_string(), value.to_string()));
+    }
+
+    /// Add a `Set-Cookie` header
+    ///
+    /// `same_site` is `"lax"`, `"strict"` or `"none"` (which implies `secure`).
+    /// Raises `ValueError` for names that are not HTTP tokens, values with
+    /// characters outside RFC 6265 cookie octets, and a path or domain
+    /// containing `;` or control characters.
+    #[pyo3(signature = (name, value, max_age=None, path="/", domain=None, secure=false, http_only=false, same_site="lax"))]
+    #[allow(clippy::too_many_arguments)]
+    fn set_cookie(
+        &mut self,
+        name: &str,
+        value: &str,
+        max_age: Option<i64>,
+        path: Option<&str>,
+        domain: Option<&str>,
+        secure: bool,
+        http_only: bool,
+        same_site: Option<&str>,
+    ) -> PyResult<()> {
+        let mut cookie = SetCookie::new(name, value)
+            .secure(secure)
+            .http_only(http_only);
+        if let Some(max_age) = max_age {
+            cookie = cookie.max_age(max_age);
+        }
+        if let Some(path) = path {
+            cookie = cookie.path(path);
+        }
+        if let Some(domain) = domain {
+            cookie = cookie.domain(domain);
+        }
+        if let Some(same_site) = same_site {
+            let same_site = SameSite::parse(same_site).ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "same_site must be 'lax', 'strict' or 'none', not {same_site:?}"
+                ))
+            })?;
+            cookie = cookie.same_site(same_site);
+        }
+        cookie
+            .validate()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        self.append_header("Set-Cookie", &cookie.to_string());
+        Ok(())
+    }
+
     /// Create a text response
     #[staticmethod]
     #[pyo3(signature = (text, status=200))]
@@ -191,6 +357,7 @@ impl PyResponse {
             body: text.to_string(),
             content_type: "text/plain".to_string(),
             headers: HashMap::new(),
+            extra_headers: Vec::new(),
         }
     }
 }
@@ -201,6 +368,51 @@ struct Route {
     path: String,
     handler: PyObject,
     auth: bool,
+    /// Route-scoped Python middleware (from route groups)
+    middlewares: Vec<PyObject>,
+    /// Content types and headers checked before the handler runs
+    requires: RouteRequirements,
+    /// Hand the body to the handler unread, through `request.stream()`
+    stream: bool,
+    /// Parse the body as JSON and pass it as the handler's second argument
+    parse_json: bool,
+    /// Body size limit and handler timeout overriding the app's
+    limits: RouteLimits,
+    /// Sunset announced on responses (and enforced, per the app policy)
+    deprecation: Option<Deprecation>,
+    /// Alternate handler serving a sticky share of the traffic
+    canary: Option<(Canary, PyObject)>,
+}
+
+/// WebSocket route registration for the App
+struct WsRoute {
+    path: String,
+    handler: PyObject,
+}
+
+/// Origin policy passed to `enable_cors_middleware`
+#[derive(Clone)]
+enum CorsOrigin {
+    Single(String),
+    List(Vec<String>),
+    Validator(PyObject),
+}
+
+impl CorsOrigin {
+    fn extract(value: &PyAny) -> PyResult<Self> {
+        if let Ok(origin) = value.extract::<String>() {
+            return Ok(Self::Single(origin));
+        }
+        if let Ok(origins) = value.extract::<Vec<String>>() {
+            return Ok(Self::List(origins));
+        }
+        if value.is_callable() {
+            return Ok(Self::Validator(value.into()));
+        }
+        Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+            "allow_origin must be a str, a list of str or a callable",
+        ))
+    }
 }
 
 #[derive(Clone)]
@@ -210,20 +422,48 @@ enum MiddlewareConfig {
     },
     Timing,
     Cors {
-        allow_origin: String,
+        allow_origin: CorsOrigin,
         allow_methods: String,
         allow_headers: String,
+        max_age: u64,
+        allow_credentials: bool,
+        expose_headers: Option<String>,
     },
     RateLimit {
         capacity: u64,
         refill_per_sec: u64,
     },
+    TieredRateLimit {
+        tiers: HashMap<String, TierLimit>,
+        default_tier: String,
+        claim: String,
+        resolver: Option<PyObject>,
+    },
+    Compression {
+        min_size: usize,
+        content_types: Option<Vec<String>>,
+    },
+    Session {
+        secret_key: String,
+        cookie_name: String,
+        max_age: Option<i64>,
+        encrypt: bool,
+        secure: bool,
+        same_site: SameSite,
+        domain: Option<String>,
+    },
 }
 
 /// Python-exposed App object
 #[pyclass(name = "App")]
 pub struct PyApp {
     routes: Vec<Route>,
+    /// Router mirroring `routes`, to reject conflicts at registration
+    route_check: Router,
+    /// WebSocket routes
+    ws_routes: Vec<WsRoute>,
+    /// WSGI applications mounted under path prefixes
+    wsgi_mounts: Vec<WsgiMount>,
     host: String,
     port: u16,
     /// Application state (Python objects)
@@ -231,29 +471,406 @@ pub struct PyApp {
     state: Arc<RwLock<HashMap<String, PyObject>>>,
     /// JWT Secret for authentication
     jwt_secret: Option<String>,
+    /// TLS certificate and key paths
+    tls: Option<(String, String)>,
+    /// Per-hostname (SNI) certificates: hostname, cert path, key path
+    tls_sni: Vec<(String, String, String)>,
+    /// How often to check the TLS files for renewed certificates
+    tls_reload_interval: Option<std::time::Duration>,
+    /// Python callable building 400 responses for unparseable requests
+    bad_request_handler: Option<PyObject>,
+    /// ACME HTTP-01 challenges served by this app
+    acme_challenges: Option<PyAcmeChallenges>,
+    /// Certificate obtained and renewed from an ACME CA
+    acme: Option<AcmeConfig>,
+    /// Admin-only profiling endpoints
+    profiling: Option<ProfilingEndpoints>,
+    /// Prometheus request metrics endpoint
+    metrics: Option<RequestMetrics>,
+    /// Read-only switch, shared with the running server
+    read_only: Option<ReadOnlyMode>,
+    /// Signals that start a graceful shutdown (core defaults when `None`)
+    shutdown_signals: Option<Vec<ShutdownSignal>>,
+    /// Admin-only database browser (debug mode only)
+    admin: Option<AdminBrowser>,
+    /// Sentry-compatible error reporting
+    error_reporting: Option<ReporterConfig>,
+    /// Compiled extensions to enable: registry name and JSON options
+    rust_extensions: Vec<(String, serde_json::Value)>,
+    /// HTTP/2 enabled, and whether cleartext h2c is accepted
+    http2: Option<bool>,
     /// Middleware configuration
     middlewares: Vec<MiddlewareConfig>,
     /// Max request body size
     max_body_size: usize,
+    /// Time a handler may take before 504 (unlimited when `None`)
+    handler_timeout: Option<std::time::Duration>,
+    /// Migration guide linked from deprecated routes' responses
+    deprecation_link: Option<String>,
+    /// 410 message of deprecated routes past their sunset (`None` = keep serving)
+    deprecation_gone: Option<String>,
+    /// Part size, part count and spooling limits for form uploads
+    multipart_limits: MultipartLimits,
+    /// Request target length and query parameter count limits
+    target_limits: TargetLimits,
+    /// Max concurrently served connections (core default when `None`)
+    max_connections: Option<usize>,
+    /// Unix domain socket served on instead of `host:port`
+    unix_socket: Option<UnixSocket>,
+    /// Addresses accepted on besides `host:port`
+    listeners: Vec<BindAddress>,
+    /// Backlog, `TCP_NODELAY`, keepalive and buffer sizes of TCP sockets
+    socket_options: SocketOptions,
+    /// Serve on sockets passed by systemd when there are any
+    socket_activation: bool,
+    /// How typed path parameters that fail to convert are answered
+    param_mismatch: ParamMismatch,
+    /// Replace a duplicate route with a warning instead of raising
+    replace_duplicate_routes: bool,
+    /// Body format of framework-generated errors
+    error_format: ErrorFormat,
+    /// Header checks applied to every response
+    response_policy: ResponsePolicy,
+    /// RSS limits for refusing work and recycling
+    memory_limits: Option<MemoryLimits>,
+    /// Requests after which the server shuts down gracefully
+    max_requests: Option<u64>,
+    /// Log the startup banner when serving starts
+    startup_banner: bool,
     /// Python middleware objects
     python_middlewares: Vec<PyObject>,
+    /// Per-route handler cost table (when accounting is enabled)
+    accounting: Option<Arc<ResourceAccounting>>,
+    /// GIL hold-time counters shared by all handlers
+    gil_metrics: Arc<GilMetrics>,
+    /// GIL hold time above which sync handlers are reported
+    gil_warn_threshold: Option<std::time::Duration>,
+    /// Response serializers for custom return types
+    serializers: SerializerRegistry,
+    /// Callbacks answering exceptions raised by handlers
+    exception_handlers: ExceptionHandlers,
+    /// Runtime the app serves on (global, owned or shared)
+    runtime: AppRuntime,
+    /// Routes of the running server, for changes after `serve`
+    live_routes: Option<LiveRoutes>,
+}
+
+/// Route registry of a running server and the event loop its handlers use
+struct LiveRoutes {
+    registry: RouteRegistry,
+    locals: pyo3_asyncio::TaskLocals,
 }
 
 #[pymethods]
 impl PyApp {
     #[new]
-    #[pyo3(signature = (host="127.0.0.1", port=8000))]
-    fn new(host: &str, port: u16) -> Self {
-        Self {
+    #[pyo3(signature = (host="127.0.0.1", port=8000, runtime=None))]
+    fn new(host: &str, port: u16, runtime: Option<&PyAny>) -> PyResult<Self> {
+        Ok(Self {
             routes: Vec::new(),
+            route_check: Router::new(),
+            ws_routes: Vec::new(),
+            wsgi_mounts: Vec::new(),
             host: host.to_string(),
             port,
             state: Arc::new(RwLock::new(HashMap::new())),
             jwt_secret: None,
+            tls: None,
+            tls_sni: Vec::new(),
+            tls_reload_interval: None,
+            acme_challenges: None,
+            acme: None,
+            profiling: None,
+            metrics: None,
+            read_only: None,
+            shutdown_signals: None,
+            admin: None,
+            error_reporting: None,
+            rust_extensions: Vec::new(),
+            bad_request_handler: None,
+            http2: None,
             middlewares: Vec::new(),
             max_body_size: 1024 * 1024,
+            handler_timeout: None,
+            deprecation_link: None,
+            deprecation_gone: None,
+            multipart_limits: MultipartLimits::default(),
+            target_limits: TargetLimits::default(),
+            max_connections: None,
+            unix_socket: None,
+            listeners: Vec::new(),
+            socket_options: SocketOptions::default(),
+            socket_activation: false,
+            param_mismatch: ParamMismatch::default(),
+            replace_duplicate_routes: false,
+            error_format: ErrorFormat::default(),
+            response_policy: ResponsePolicy::default(),
+            memory_limits: None,
+            max_requests: None,
+            startup_banner: false,
             python_middlewares: Vec::new(),
+            accounting: None,
+            gil_metrics: Arc::new(GilMetrics::new()),
+            gil_warn_threshold: Some(DEFAULT_GIL_WARN_THRESHOLD),
+            serializers: SerializerRegistry::default(),
+            exception_handlers: ExceptionHandlers::default(),
+            runtime: AppRuntime::extract(runtime)?,
+            live_routes: None,
+        })
+    }
+
+    /// Serve HTTPS with the given PEM certificate chain and private key
+    ///
+    /// With `reload_interval` (seconds) the files are checked for changes
+    /// and renewed certificates are used without a restart.
+    #[pyo3(signature = (cert_path, key_path, reload_interval=None))]
+    fn enable_tls(
+        &mut self,
+        cert_path: &str,
+        key_path: &str,
+        reload_interval: Option<f64>,
+    ) -> PyResult<()> {
+        self.tls_reload_interval = reload_interval
+            .map(std::time::Duration::try_from_secs_f64)
+            .transpose()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        self.tls = Some((cert_path.to_string(), key_path.to_string()));
+        Ok(())
+    }
+
+    /// Serve a different certificate to clients requesting `hostname` via SNI
+    ///
+    /// `hostname` may be a `*.example.com` wildcard; other hosts get the
+    /// default certificate passed to `enable_tls`.
+    fn add_tls_certificate(&mut self, hostname: &str, cert_path: &str, key_path: &str) {
+        self.tls_sni.push((
+            hostname.to_string(),
+            cert_path.to_string(),
+            key_path.to_string(),
+        ));
+    }
+
+    /// Serve ACME HTTP-01 challenges under `/.well-known/acme-challenge/`
+    ///
+    /// Pass a shared store to serve the same challenges from several apps.
+    #[pyo3(signature = (challenges=None))]
+    fn enable_acme_http01(&mut self, challenges: Option<PyAcmeChallenges>) -> PyAcmeChallenges {
+        let challenges = challenges.unwrap_or_default();
+        self.acme_challenges = Some(challenges.clone());
+        challenges
+    }
+
+    /// Obtain the TLS certificate for `domains` from an ACME CA
+    ///
+    /// On `serve` a missing or expiring certificate is issued into
+    /// `storage` (`cert.pem`, `key.pem`) before listening, then renewed
+    /// in the background `renew_before` seconds ahead of expiry. HTTP-01
+    /// challenges go to the `enable_acme_http01` store, answered on
+    /// `http01_port` by a standalone responder when given.
+    #[pyo3(signature = (
+        domains, storage, directory=None, email=None, renew_before=None, http01_port=None
+    ))]
+    fn enable_acme(
+        &mut self,
+        domains: Vec<String>,
+        storage: &str,
+        directory: Option<String>,
+        email: Option<String>,
+        renew_before: Option<f64>,
+        http01_port: Option<u16>,
+    ) -> PyResult<()> {
+        let mut config = AcmeConfig::new(domains, storage);
+        if let Some(directory) = directory {
+            config = config.with_directory(directory);
+        }
+        if let Some(email) = email {
+            config = config.with_contact(email);
+        }
+        if let Some(seconds) = renew_before {
+            let before = std::time::Duration::try_from_secs_f64(seconds)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+            config = config.with_renew_before(before);
+        }
+        config.http01_port = http01_port;
+        self.acme = Some(config);
+        self.acme_client().map(drop)
+    }
+
+    /// Serve admin-only runtime metrics and CPU profiles under `prefix`
+    ///
+    /// Requests need `Authorization: Bearer <token>`. CPU profiles require
+    /// building with the `profiling` feature; `py_spy` adds a `py-spy dump`
+    /// endpoint.
+    #[pyo3(signature = (token, prefix=None, py_spy=false))]
+    fn enable_profiling(
+        &mut self,
+        token: &str,
+        prefix: Option<&str>,
+        py_spy: bool,
+    ) -> PyResult<()> {
+        if token.is_empty() {
+            return Err(BindingsError::Configuration(
+                "profiling token must not be empty".to_string(),
+            )
+            .into());
         }
+        self.profiling = Some(
+            ProfilingEndpoints::new(prefix.unwrap_or(profiling::DEFAULT_PREFIX), token)
+                .with_py_spy(py_spy),
+        );
+        Ok(())
+    }
+
+    /// Serve Prometheus request metrics at `path` (default `/metrics`)
+    ///
+    /// `labels` is the allowlist of labels to attach (`method`, `route`,
+    /// `status`, `status_class`); routes are labelled by pattern, never by
+    /// raw path. Label sets beyond `max_series` are counted in one overflow
+    /// series.
+    #[pyo3(signature = (path=None, labels=None, max_series=None))]
+    fn enable_metrics(
+        &mut self,
+        path: Option<&str>,
+        labels: Option<Vec<String>>,
+        max_series: Option<usize>,
+    ) -> PyResult<()> {
+        let mut metrics = RequestMetrics::new();
+        if let Some(path) = path {
+            metrics = metrics.path(path);
+        }
+        if let Some(labels) = labels {
+            let labels = labels
+                .iter()
+                .map(String::as_str)
+                .map(MetricLabel::parse)
+                .collect::<pyvectora_core::Result<Vec<_>>>()
+                .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+            metrics = metrics.labels(labels);
+        }
+        if let Some(max_series) = max_series {
+            metrics = metrics.max_series(max_series);
+        }
+        self.metrics = Some(metrics);
+        Ok(())
+    }
+
+    /// Signals that start a graceful shutdown, by name (`SIGTERM`,
+    /// `CTRL_BREAK`); signals the platform does not have are ignored
+    fn set_shutdown_signals(&mut self, signals: Vec<String>) -> PyResult<()> {
+        self.shutdown_signals = Some(parse_shutdown_signals(&signals)?);
+        Ok(())
+    }
+
+    /// Refuse unsafe methods with 503 while `enabled`
+    ///
+    /// Routes whose pattern is in `allow` keep accepting writes; with
+    /// `databases` every database pool refuses writes too. Before `serve`
+    /// this configures the mode; while serving only `enabled` changes, and
+    /// the mode must have been configured before `serve`.
+    #[pyo3(signature = (enabled, allow=None, databases=false))]
+    fn set_read_only(
+        &mut self,
+        enabled: bool,
+        allow: Option<Vec<String>>,
+        databases: bool,
+    ) -> PyResult<()> {
+        if self.live_routes.is_some() {
+            let Some(mode) = &self.read_only else {
+                return Err(BindingsError::Configuration(
+                    "read-only mode must be configured before serve()".to_string(),
+                )
+                .into());
+            };
+            mode.set(enabled);
+            return Ok(());
+        }
+        if let Some(previous) = self.read_only.take() {
+            previous.set(false);
+        }
+        let mode = ReadOnlyMode::new()
+            .allow(allow.unwrap_or_default())
+            .databases(databases);
+        mode.set(enabled);
+        self.read_only = Some(mode);
+        Ok(())
+    }
+
+    /// Serve the admin data browser over `databases` under `prefix`
+    ///
+    /// `databases` maps display names to connected `DatabaseNative`
+    /// pools. Pages need the token as a bearer token or Basic auth
+    /// password; the app must run in debug mode.
+    #[pyo3(signature = (token, databases, prefix=None))]
+    fn enable_admin(
+        &mut self,
+        token: &str,
+        databases: &PyDict,
+        prefix: Option<&str>,
+    ) -> PyResult<()> {
+        if token.is_empty() {
+            return Err(
+                BindingsError::Configuration("admin token must not be empty".to_string()).into(),
+            );
+        }
+        let mut browser =
+            AdminBrowser::new(prefix.unwrap_or(pyvectora_core::admin::DEFAULT_PREFIX), token);
+        for (name, db) in databases {
+            let name: &str = name.extract()?;
+            let db: PyRef<'_, PyDatabaseNative> = db.extract()?;
+            let pool = db.pool().ok_or_else(|| {
+                BindingsError::Configuration(format!("database {name:?} is closed"))
+            })?;
+            browser = browser.database(name, pool);
+        }
+        self.admin = Some(browser);
+        Ok(())
+    }
+
+    /// Report unhandled handler exceptions and panics to a Sentry DSN
+    ///
+    /// Reports carry the request, matched route, authenticated user and
+    /// recent breadcrumbs; `sample_rate` keeps that share of them. A
+    /// malformed DSN or rate raises `ConfigurationError`.
+    #[pyo3(signature = (dsn, sample_rate=1.0, environment=None, release=None))]
+    fn enable_error_reporting(
+        &mut self,
+        dsn: &str,
+        sample_rate: f64,
+        environment: Option<String>,
+        release: Option<String>,
+    ) -> PyResult<()> {
+        let mut config = ReporterConfig::new(dsn, sample_rate)
+            .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        if let Some(environment) = environment {
+            config = config.with_environment(environment);
+        }
+        if let Some(release) = release {
+            config = config.with_release(release);
+        }
+        self.error_reporting = Some(config);
+        Ok(())
+    }
+
+    /// Enable a compiled extension registered under `name`
+    ///
+    /// `options` (a JSON-compatible dict) is passed to the extension's
+    /// factory; invalid names or options raise `ConfigurationError`.
+    #[pyo3(signature = (name, options=None))]
+    fn enable_rust_extension(&mut self, name: &str, options: Option<&PyAny>) -> PyResult<()> {
+        let options = options
+            .map(pyobject_to_json)
+            .transpose()?
+            .unwrap_or(serde_json::Value::Null);
+        pyvectora_core::extension::create(name, &options)
+            .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        self.rust_extensions.push((name.to_string(), options));
+        Ok(())
+    }
+
+    /// Enable HTTP/2 (ALPN on TLS; `h2c` also allows cleartext prior knowledge)
+    #[pyo3(signature = (h2c=false))]
+    fn enable_http2(&mut self, h2c: bool) {
+        self.http2 = Some(h2c);
     }
 
     /// Enable JWT authentication
@@ -292,81 +909,413 @@ impl PyApp {
 
     /// Register a GET route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn get(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Get,
-            path: path.to_string(),
-            handler,
-            auth,
-        });
+    fn get(&mut self, py: Python<'_>, path: &str, handler: PyObject, auth: bool) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Get,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
     }
 
     /// Register a POST route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn post(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Post,
-            path: path.to_string(),
-            handler,
-            auth,
-        });
+    fn post(&mut self, py: Python<'_>, path: &str, handler: PyObject, auth: bool) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Post,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
     }
 
     /// Register a PUT route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn put(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Put,
-            path: path.to_string(),
-            handler,
-            auth,
-        });
+    fn put(&mut self, py: Python<'_>, path: &str, handler: PyObject, auth: bool) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Put,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
     }
 
     /// Register a DELETE route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn delete(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Delete,
-            path: path.to_string(),
-            handler,
-            auth,
-        });
+    fn delete(
+        &mut self,
+        py: Python<'_>,
+        path: &str,
+        handler: PyObject,
+        auth: bool,
+    ) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Delete,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
     }
 
     /// Register a PATCH route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn patch(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Patch,
-            path: path.to_string(),
-            handler,
-            auth,
-        });
+    fn patch(&mut self, py: Python<'_>, path: &str, handler: PyObject, auth: bool) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Patch,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
     }
 
     /// Register a HEAD route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn head(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Head,
-            path: path.to_string(),
-            handler,
-            auth,
-        });
+    fn head(&mut self, py: Python<'_>, path: &str, handler: PyObject, auth: bool) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Head,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
     }
 
     /// Register an OPTIONS route
     #[pyo3(signature = (path, handler, auth=false))]
-    fn options(&mut self, path: &str, handler: PyObject, auth: bool) {
-        self.routes.push(Route {
-            method: Method::Options,
-            path: path.to_string(),
+    fn options(
+        &mut self,
+        py: Python<'_>,
+        path: &str,
+        handler: PyObject,
+        auth: bool,
+    ) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: Method::Options,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: Vec::new(),
+                requires: RouteRequirements::default(),
+                stream: false,
+                parse_json: false,
+                limits: RouteLimits::default(),
+                deprecation: None,
+                canary: None,
+            },
+        )
+    }
+
+    /// Register a route with route-scoped Python middleware and requirements
+    ///
+    /// Used by route groups; `middlewares` run around this route only,
+    /// after the app-wide middleware. `requires` may set `content_type`
+    /// (a media type or a list of them, else 415) and `headers` (names
+    /// that must be present, else 400), checked before the handler runs.
+    /// With `stream` the body is not collected (nor held to
+    /// `max_body_size`); the handler reads it through `request.stream()`.
+    /// `max_body` (bytes) and `timeout` (seconds) override the app-wide
+    /// body limit and handler timeout for this route. With `parse_json`
+    /// the body is parsed as JSON before the handler runs and passed as its
+    /// second argument; invalid JSON is answered 400. `deprecated_after`
+    /// (`YYYY-MM-DD`) is the last day the route is served: its responses
+    /// carry `Deprecation`/`Sunset` headers, see `set_deprecation`.
+    /// `canary` sends a share of the traffic to another handler: `handler`
+    /// and `percent`, optionally `sticky_cookie` or `sticky_header` (user
+    /// key picking the bucket), `cookie` (assignment cookie name) and
+    /// `ramp` (seconds over which the share grows from 0).
+    #[pyo3(signature = (
+        method, path, handler, auth=false, middlewares=None, requires=None, stream=false,
+        max_body=None, timeout=None, parse_json=false, deprecated_after=None, canary=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_route(
+        &mut self,
+        py: Python<'_>,
+        method: &str,
+        path: &str,
+        handler: PyObject,
+        auth: bool,
+        middlewares: Option<Vec<PyObject>>,
+        requires: Option<&PyDict>,
+        stream: bool,
+        max_body: Option<usize>,
+        timeout: Option<f64>,
+        parse_json: bool,
+        deprecated_after: Option<&str>,
+        canary: Option<&PyDict>,
+    ) -> PyResult<()> {
+        self.push_route(
+            py,
+            Route {
+                method: parse_method(method)?,
+                path: path.to_string(),
+                handler,
+                auth,
+                middlewares: middlewares.unwrap_or_default(),
+                requires: requires.map(extract_requirements).transpose()?.unwrap_or_default(),
+                stream,
+                parse_json,
+                limits: RouteLimits {
+                    max_body_size: max_body,
+                    timeout: timeout.map(timeout_from_secs).transpose()?,
+                },
+                deprecation: deprecated_after
+                    .map(Deprecation::after)
+                    .transpose()
+                    .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?,
+                canary: canary.map(extract_canary).transpose()?,
+            },
+        )
+    }
+
+    /// Add a route, also to the running server if `serve` was called
+    ///
+    /// Lets plugins register endpoints after startup; requests arriving
+    /// afterwards are routed to `handler`.
+    #[pyo3(signature = (
+        method, path, handler, auth=false, middlewares=None, requires=None, stream=false,
+        max_body=None, timeout=None, parse_json=false, deprecated_after=None, canary=None
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn add_route_runtime(
+        &mut self,
+        py: Python<'_>,
+        method: &str,
+        path: &str,
+        handler: PyObject,
+        auth: bool,
+        middlewares: Option<Vec<PyObject>>,
+        requires: Option<&PyDict>,
+        stream: bool,
+        max_body: Option<usize>,
+        timeout: Option<f64>,
+        parse_json: bool,
+        deprecated_after: Option<&str>,
+        canary: Option<&PyDict>,
+    ) -> PyResult<()> {
+        self.add_route(
+            py,
+            method,
+            path,
             handler,
             auth,
+            middlewares,
+            requires,
+            stream,
+            max_body,
+            timeout,
+            parse_json,
+            deprecated_after,
+            canary,
+        )?;
+        let Some(live) = &self.live_routes else {
+            return Ok(());
+        };
+        let route = self.routes.last().expect("route was just added");
+        let rust_handler = self.route_handler(
+            py,
+            route,
+            &live.locals,
+            &Arc::new(self.serializers.clone()),
+            &Arc::new(self.exception_handlers.clone()),
+        );
+        let added = if route.stream {
+            live.registry
+                .add_streaming_route(route.method, &route.path, rust_handler, route.auth)
+        } else {
+            live.registry
+                .add_route(route.method, &route.path, rust_handler, route.auth)
+        };
+        added.map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        let _ = live.registry.set_limits(route.method, &route.path, route.limits);
+        Ok(())
+    }
+
+    /// Remove the route registered for `method` with exactly `path`
+    ///
+    /// Also removes it from the running server. Returns `False` if no such
+    /// route exists.
+    fn remove_route(&mut self, method: &str, path: &str) -> PyResult<bool> {
+        let method = parse_method(method)?;
+        let Some(index) = self
+            .routes
+            .iter()
+            .position(|r| r.method == method && r.path == path)
+        else {
+            return Ok(false);
+        };
+        self.routes.remove(index);
+        self.route_check.remove_route(method, path);
+        if let Some(live) = &self.live_routes {
+            let _ = live.registry.remove_route(method, path);
+        }
+        Ok(true)
+    }
+
+    /// Record wall time, GIL time and allocations per route
+    fn enable_resource_accounting(&mut self) {
+        self.accounting
+            .get_or_insert_with(|| Arc::new(ResourceAccounting::new()));
+    }
+
+    /// Per-route handler cost, most expensive route first
+    fn resource_stats(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
+        self.accounting
+            .as_ref()
+            .map_or_else(|| Ok(Vec::new()), |table| stats_to_py(py, table))
+    }
+
+    /// Warn when a sync handler holds the GIL longer than `millis` (None disables)
+    #[pyo3(signature = (millis))]
+    fn set_gil_warning_threshold(&mut self, millis: Option<f64>) -> PyResult<()> {
+        self.gil_warn_threshold = millis
+            .map(|ms| std::time::Duration::try_from_secs_f64(ms / 1000.0))
+            .transpose()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        Ok(())
+    }
+
+    /// GIL hold-time counters across all handlers
+    fn gil_stats(&self, py: Python<'_>) -> PyResult<PyObject> {
+        gil_stats_to_py(py, &self.gil_metrics)
+    }
+
+    /// Export the registered routes as a JSON route table
+    ///
+    /// Every pattern is compiled, so conflicting routes raise `ValueError`.
+    fn export_routes(&self) -> PyResult<String> {
+        let mut router = Router::new();
+        for route in &self.routes {
+            router
+                .add_route(route.method, &route.path, route.auth)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        }
+        router
+            .export_table()
+            .to_json()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    }
+
+    /// Convert handler results of type `ty` (or a subclass) with `serializer`
+    fn register_serializer(&mut self, ty: &PyAny, serializer: PyObject) -> PyResult<()> {
+        self.serializers.register(ty, serializer)
+    }
+
+    /// Answer exceptions of type `ty` (or a subclass) raised by handlers
+    ///
+    /// `handler(request, exc)` may be async and returns anything a route
+    /// handler can; exceptions it raises take the default error path.
+    fn register_exception_handler(&mut self, ty: &PyAny, handler: PyObject) -> PyResult<()> {
+        self.exception_handlers.register(ty, handler)
+    }
+
+    /// Answer unhandled handler exceptions with their traceback and the request,
+    /// and unmatched requests with the nearest registered routes
+    ///
+    /// For development only: the 500 body exposes source paths and code.
+    fn set_debug(&mut self, debug: bool) {
+        self.exception_handlers.set_debug(debug);
+    }
+
+    /// Register a WebSocket route (handler must be async)
+    fn websocket(&mut self, path: &str, handler: PyObject) {
+        self.ws_routes.push(WsRoute {
+            path: path.to_string(),
+            handler,
         });
     }
 
+    /// Serve a WSGI application for every path under `prefix`
+    ///
+    /// Requests run on the blocking thread pool. Native routes under the
+    /// prefix take precedence over the mounted app.
+    fn mount_wsgi(&mut self, py: Python<'_>, prefix: &str, app: PyObject) -> PyResult<()> {
+        if !prefix.starts_with('/') {
+            return Err(BindingsError::Configuration(format!(
+                "WSGI mount prefix must start with '/': {prefix}"
+            ))
+            .into());
+        }
+        if !app.as_ref(py).is_callable() {
+            return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
+                "WSGI application must be callable",
+            ));
+        }
+        let mount = WsgiMount::new(prefix, app);
+        let mut route_check = self.route_check.clone();
+        for method in WSGI_METHODS {
+            for pattern in mount.patterns() {
+                route_check
+                    .add_route(method, &pattern, false)
+                    .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+            }
+        }
+        self.route_check = route_check;
+        self.wsgi_mounts.push(mount);
+        Ok(())
+    }
+
     /// Enable logging middleware
     #[pyo3(signature = (log_headers=false))]
     fn enable_logging_middleware(&mut self, log_headers: bool) {
@@ -380,18 +1329,40 @@ impl PyApp {
     }
 
     /// Enable CORS middleware
-    #[pyo3(signature = (allow_origin="*", allow_methods="GET, POST, PUT, DELETE, PATCH, OPTIONS", allow_headers="Content-Type, Authorization"))]
+    ///
+    /// `allow_origin` is a single origin (or `"*"`), a list of allowed
+    /// origins, or a callable `(origin) -> bool`. Raises `ValueError` when
+    /// credentials are allowed for `"*"`.
+    #[pyo3(signature = (allow_origin=None, allow_methods="GET, POST, PUT, DELETE, PATCH, OPTIONS", allow_headers="Content-Type, Authorization", max_age=600, allow_credentials=false, expose_headers=None))]
     fn enable_cors_middleware(
         &mut self,
-        allow_origin: &str,
+        allow_origin: Option<&PyAny>,
         allow_methods: &str,
         allow_headers: &str,
-    ) {
+        max_age: u64,
+        allow_credentials: bool,
+        expose_headers: Option<String>,
+    ) -> PyResult<()> {
+        let allow_origin = match allow_origin {
+            Some(value) => CorsOrigin::extract(value)?,
+            None => CorsOrigin::Single("*".to_string()),
+        };
+        if let CorsOrigin::Single(origin) = &allow_origin {
+            CorsMiddleware::new()
+                .allow_origin(origin.clone())
+                .allow_credentials(allow_credentials)
+                .validate()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        }
         self.middlewares.push(MiddlewareConfig::Cors {
-            allow_origin: allow_origin.to_string(),
+            allow_origin,
             allow_methods: allow_methods.to_string(),
             allow_headers: allow_headers.to_string(),
+            max_age,
+            allow_credentials,
+            expose_headers,
         });
+        Ok(())
     }
 
     /// Enable rate limit middleware
@@ -403,11 +1374,350 @@ impl PyApp {
         });
     }
 
+    /// Enable rate limiting by caller tier (`tiers` maps a tier to its
+    /// requests per `window` seconds)
+    ///
+    /// The tier is `resolver(claims)` when given and not `None`, else the
+    /// JWT claim `claim`, else `default_tier`. Raises `ValueError` if
+    /// `default_tier` is not in `tiers` or a tier allows no requests.
+    #[pyo3(signature = (tiers, default_tier, claim="tier", window=60.0, resolver=None))]
+    fn enable_tiered_rate_limit(
+        &mut self,
+        tiers: HashMap<String, u32>,
+        default_tier: String,
+        claim: &str,
+        window: f64,
+        resolver: Option<PyObject>,
+    ) -> PyResult<()> {
+        let window = std::time::Duration::try_from_secs_f64(window)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        let tiers: HashMap<String, TierLimit> = tiers
+            .into_iter()
+            .map(|(tier, requests)| (tier, TierLimit { requests, window }))
+            .collect();
+        TieredRateLimitMiddleware::new(tiers.clone(), default_tier.as_str())
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        self.middlewares.push(MiddlewareConfig::TieredRateLimit {
+            tiers,
+            default_tier,
+            claim: claim.to_string(),
+            resolver,
+        });
+        Ok(())
+    }
+
+    /// Enable gzip/brotli response compression
+    #[pyo3(signature = (min_size=1024, content_types=None))]
+    fn enable_compression_middleware(
+        &mut self,
+        min_size: usize,
+        content_types: Option<Vec<String>>,
+    ) {
+        self.middlewares.push(MiddlewareConfig::Compression {
+            min_size,
+            content_types,
+        });
+    }
+
+    /// Enable signed cookie sessions (`request.session`)
+    ///
+    /// The cookie is HMAC-signed with a key derived from `secret_key` and,
+    /// with `encrypt=True`, also AES-GCM encrypted.
+    #[pyo3(signature = (secret_key, cookie_name="session", max_age=None, encrypt=false, secure=false, same_site="lax", domain=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn enable_session_middleware(
+        &mut self,
+        secret_key: String,
+        cookie_name: &str,
+        max_age: Option<i64>,
+        encrypt: bool,
+        secure: bool,
+        same_site: &str,
+        domain: Option<String>,
+    ) -> PyResult<()> {
+        if secret_key.len() < 32 {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "secret_key must be at least 32 bytes",
+            ));
+        }
+        let same_site = SameSite::parse(same_site).ok_or_else(|| {
+            PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                "same_site must be 'lax', 'strict' or 'none', not {same_site:?}"
+            ))
+        })?;
+        self.middlewares.push(MiddlewareConfig::Session {
+            secret_key,
+            cookie_name: cookie_name.to_string(),
+            max_age,
+            encrypt,
+            secure,
+            same_site,
+            domain,
+        });
+        Ok(())
+    }
+
     /// Set max request body size (bytes)
     fn set_body_limit(&mut self, bytes: usize) {
         self.max_body_size = bytes;
     }
 
+    /// Answer 504 when a handler runs longer than `seconds` (`None` = unlimited)
+    ///
+    /// The handler's asyncio task and request context are cancelled.
+    #[pyo3(signature = (seconds=None))]
+    fn set_handler_timeout(&mut self, seconds: Option<f64>) -> PyResult<()> {
+        self.handler_timeout = seconds.map(timeout_from_secs).transpose()?;
+        Ok(())
+    }
+
+    /// What deprecated routes (`deprecated_after`) do around their sunset
+    ///
+    /// `link` is sent as `Link: <link>; rel="deprecation"`. With `gone`,
+    /// requests after the sunset are answered 410 with that message instead
+    /// of running the handler.
+    #[pyo3(signature = (link=None, gone=None))]
+    fn set_deprecation(&mut self, link: Option<String>, gone: Option<String>) {
+        self.deprecation_link = link;
+        self.deprecation_gone = gone;
+    }
+
+    /// Limits for `request.form()` / `request.files()` uploads
+    ///
+    /// Parts above `max_part_size` bytes raise `UploadTooLarge` (413);
+    /// files above `spool_threshold` bytes are written to `temp_dir`.
+    #[pyo3(signature = (max_part_size=None, max_parts=None, spool_threshold=None, temp_dir=None))]
+    fn set_upload_limits(
+        &mut self,
+        max_part_size: Option<usize>,
+        max_parts: Option<usize>,
+        spool_threshold: Option<usize>,
+        temp_dir: Option<std::path::PathBuf>,
+    ) {
+        let limits = &mut self.multipart_limits;
+        if let Some(bytes) = max_part_size {
+            limits.max_part_size = bytes;
+        }
+        if let Some(count) = max_parts {
+            limits.max_parts = count;
+        }
+        if let Some(bytes) = spool_threshold {
+            limits.spool_threshold = bytes;
+        }
+        if temp_dir.is_some() {
+            limits.temp_dir = temp_dir;
+        }
+    }
+
+    /// Limits on the request target
+    ///
+    /// Paths plus query strings above `max_length` bytes get 414; query
+    /// strings with more than `max_query_params` parameters get 400.
+    #[pyo3(signature = (max_length=None, max_query_params=None))]
+    fn set_uri_limits(&mut self, max_length: Option<usize>, max_query_params: Option<usize>) {
+        if let Some(bytes) = max_length {
+            self.target_limits.max_uri_length = bytes;
+        }
+        if let Some(count) = max_query_params {
+            self.target_limits.max_query_params = count;
+        }
+    }
+
+    /// Cap concurrently served connections; accepting pauses at the cap
+    fn set_max_connections(&mut self, max: usize) {
+        self.max_connections = Some(max);
+    }
+
+    /// Tune TCP sockets: listen backlog, `TCP_NODELAY`, keepalive (idle
+    /// time and probe interval in seconds) and buffer sizes in bytes
+    ///
+    /// Options left `None` keep their current value. Raises `ValueError`
+    /// for a negative or non-finite keepalive time.
+    #[pyo3(signature = (
+        backlog=None,
+        nodelay=None,
+        keepalive_idle=None,
+        keepalive_interval=None,
+        recv_buffer_size=None,
+        send_buffer_size=None,
+    ))]
+    fn set_socket_options(
+        &mut self,
+        backlog: Option<u32>,
+        nodelay: Option<bool>,
+        keepalive_idle: Option<f64>,
+        keepalive_interval: Option<f64>,
+        recv_buffer_size: Option<u32>,
+        send_buffer_size: Option<u32>,
+    ) -> PyResult<()> {
+        let seconds = |value: Option<f64>| {
+            value
+                .map(std::time::Duration::try_from_secs_f64)
+                .transpose()
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+        };
+        let options = &mut self.socket_options;
+        options.backlog = backlog.unwrap_or(options.backlog);
+        options.nodelay = nodelay.unwrap_or(options.nodelay);
+        options.keepalive_idle = seconds(keepalive_idle)?.or(options.keepalive_idle);
+        options.keepalive_interval = seconds(keepalive_interval)?.or(options.keepalive_interval);
+        options.recv_buffer_size = recv_buffer_size.or(options.recv_buffer_size);
+        options.send_buffer_size = send_buffer_size.or(options.send_buffer_size);
+        Ok(())
+    }
+
+    /// Serve on the sockets systemd passes (`LISTEN_FDS`) when
+    /// socket-activated, instead of binding `host:port`
+    fn enable_socket_activation(&mut self) {
+        self.socket_activation = true;
+    }
+
+    /// Serve on the Unix domain socket at `path` instead of `host:port`
+    ///
+    /// `mode` sets the socket file's permission bits (e.g. `0o660`).
+    #[pyo3(signature = (path, mode=None))]
+    fn set_unix_socket(&mut self, path: &str, mode: Option<u32>) -> PyResult<()> {
+        if path.is_empty() {
+            return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+                "Unix socket path must not be empty",
+            ));
+        }
+        let socket = UnixSocket::new(path);
+        self.unix_socket = Some(match mode {
+            Some(mode) if mode > 0o7777 => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "invalid socket mode {mode:#o}"
+                )))
+            }
+            Some(mode) => socket.mode(mode),
+            None => socket,
+        });
+        Ok(())
+    }
+
+    /// Also accept connections on `address` (`ip:port` or `unix:/path`)
+    ///
+    /// Raises `ValueError` for a malformed address.
+    fn add_listener(&mut self, address: &str) -> PyResult<()> {
+        let address = BindAddress::parse(address)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        self.listeners.push(address);
+        Ok(())
+    }
+
+    /// Watch the process RSS (limits in megabytes)
+    ///
+    /// Above `soft_mb` requests get 503 until memory drops; above `hard_mb`
+    /// the server shuts down gracefully so it can be replaced.
+    #[pyo3(signature = (soft_mb=None, hard_mb=None, check_interval=None))]
+    fn set_memory_limits(
+        &mut self,
+        soft_mb: Option<u64>,
+        hard_mb: Option<u64>,
+        check_interval: Option<f64>,
+    ) -> PyResult<()> {
+        let mut limits = MemoryLimits {
+            soft_limit: soft_mb.map(|mb| mb * 1024 * 1024),
+            hard_limit: hard_mb.map(|mb| mb * 1024 * 1024),
+            ..MemoryLimits::default()
+        };
+        if let Some(seconds) = check_interval {
+            limits.check_interval = std::time::Duration::try_from_secs_f64(seconds)
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        }
+        self.memory_limits = Some(limits);
+        Ok(())
+    }
+
+    /// Shut down gracefully after serving about `max` requests
+    fn set_max_requests(&mut self, max: u64) {
+        self.max_requests = Some(max);
+    }
+
+    /// Log address, workers, middleware and the route table when serving starts
+    fn enable_startup_banner(&mut self) {
+        self.startup_banner = true;
+    }
+
+    /// Choose how typed path parameters that fail to convert are answered
+    ///
+    /// `"stringify"` (default) passes the raw string to the handler,
+    /// `"reject"` answers 422 with validation errors and `"not_found"` 404.
+    fn set_param_mismatch(&mut self, mode: &str) -> PyResult<()> {
+        self.param_mismatch = match mode {
+            "stringify" => ParamMismatch::Stringify,
+            "reject" => ParamMismatch::Reject,
+            "not_found" => ParamMismatch::NotFound,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown param mismatch mode: {other}"
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Choose how a route registered twice for the same method is handled
+    ///
+    /// `"error"` (default) raises at registration; `"warn"` logs a warning
+    /// and the later registration replaces the earlier one. Patterns
+    /// differing only in parameter names (`/users/{id}`, `/users/{name}`)
+    /// count as the same route.
+    fn set_duplicate_routes(&mut self, mode: &str) -> PyResult<()> {
+        self.replace_duplicate_routes = match mode {
+            "error" => false,
+            "warn" => true,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown duplicate routes mode: {other}"
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Choose the body format of framework-generated errors
+    ///
+    /// `"json"` (default) keeps `{"error": ...}` bodies, `"problem"` sends
+    /// RFC 7807 `application/problem+json` documents.
+    fn set_error_format(&mut self, format: &str) -> PyResult<()> {
+        self.error_format = match format {
+            "json" => ErrorFormat::Json,
+            "problem" => ErrorFormat::Problem,
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown error format: {other}"
+                )))
+            }
+        };
+        Ok(())
+    }
+
+    /// Choose the header checks applied to every response after all
+    /// middleware (all off by default)
+    #[pyo3(signature = (strip_server=false, utf8_text=false, forbid_powered_by=false))]
+    fn set_response_policy(
+        &mut self,
+        strip_server: bool,
+        utf8_text: bool,
+        forbid_powered_by: bool,
+    ) {
+        self.response_policy = ResponsePolicy {
+            strip_server,
+            utf8_text,
+            forbid_powered_by,
+        };
+    }
+
+    /// Customize the 400 response for requests that cannot be parsed
+    ///
+    /// `handler(kind, message)` is called synchronously with `kind` one of
+    /// `"invalid_header"`, `"body_aborted"` or `"invalid_encoding"`; it may
+    /// return a response, or `None` for the default "Bad Request".
+    fn set_bad_request_handler(&mut self, handler: PyObject) {
+        self.bad_request_handler = Some(handler);
+    }
+
     /// Register a Python middleware object or function
     fn add_python_middleware(&mut self, middleware: PyObject) {
         self.python_middlewares.push(middleware);
@@ -415,151 +1725,460 @@ impl PyApp {
     /// Start the server (blocks until shutdown)
     /// Start the server (returns awaitable future)
     /// Start the server (returns awaitable future)
-    fn serve<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+    fn serve<'p>(&mut self, py: Python<'p>) -> PyResult<&'p PyAny> {
         init_tracing();
 
-        let host = self.host.clone();
-        let port = self.port;
-        let jwt_secret = self.jwt_secret.clone();
-        let middleware_data = self.middlewares.clone();
-        let python_middleware_data: Vec<PyObject> = self
-            .python_middlewares
-            .iter()
-            .map(|m| m.clone_ref(py))
-            .collect();
-        let max_body_size = self.max_body_size;
-
-        struct RouteData {
-            method: Method,
-            path: String,
-            handler: PyObject,
-            auth: bool,
-        }
-
-        let route_data: Vec<RouteData> = self
-            .routes
-            .iter()
-            .map(|r| RouteData {
-                method: r.method,
-                path: r.path.clone(),
-                handler: r.handler.clone_ref(py),
-                auth: r.auth,
-            })
-            .collect();
+        let addr = self.socket_addr()?;
 
         init_asyncio_once(py)?;
 
         let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
         let locals = pyo3_asyncio::TaskLocals::new(event_loop);
 
+        let mut server = self.build_server(py, &locals)?.bind(addr);
+        self.live_routes = Some(LiveRoutes {
+            registry: server.routes(),
+            locals,
+        });
+        if let Some(config) = self.tls_config() {
+            server.set_tls(config);
+        }
+        if let Some(h2c) = self.http2 {
+            server.enable_http2(h2c);
+        }
+        if let Some(max) = self.max_connections {
+            server.set_max_connections(max);
+        }
+        if let Some(socket) = &self.unix_socket {
+            server.set_unix_socket(socket.clone());
+        }
+        for address in &self.listeners {
+            server.add_listener(address.clone());
+        }
+        server.set_socket_options(self.socket_options);
+        if self.socket_activation {
+            server.enable_socket_activation();
+        }
+        if let Some(limits) = &self.memory_limits {
+            server.set_memory_limits(limits.clone());
+        }
+        if let Some(max) = self.max_requests {
+            server.set_max_requests(max);
+        }
+        server.set_startup_banner(self.startup_banner);
+        let acme = self.acme_client()?;
+
+        let serving = async move {
+            let _renewal = match acme {
+                Some(client) => Some(start_acme(client).await?),
+                None => None,
+            };
+            server
+                .serve()
+                .await
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+        };
+        let Some(handle) = self.runtime.dedicated_handle()? else {
+            return pyo3_asyncio::tokio::future_into_py(py, serving);
+        };
         pyo3_asyncio::tokio::future_into_py(py, async move {
-            let addr: std::net::SocketAddr = format!("{}:{}", host, port)
-                .parse()
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{e}")))?;
-
-            let mut server = Server::new(jwt_secret.as_deref().unwrap_or(""));
-            server = server.bind(addr);
-            if let Some(secret) = &jwt_secret {
-                server.enable_auth(secret);
+            handle
+                .spawn(serving)
+                .await
+                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?
+        })
+    }
+
+    /// Create a test client (zero-network)
+    fn test_client(&self, py: Python<'_>) -> PyResult<PyServer> {
+        init_asyncio_once(py)?;
+        let locals = pyo3_asyncio::TaskLocals::new(current_event_loop(py)?);
+
+        Ok(PyServer {
+            inner: self.build_server(py, &locals)?,
+            runtime: self.runtime.handle()?,
+        })
+    }
+
+    /// Check the configuration without serving
+    ///
+    /// Parses the address, loads the TLS certificates and builds the server,
+    /// compiling every route and resolving middleware and extensions, so it
+    /// raises what `serve` would.
+    fn validate(&self, py: Python<'_>) -> PyResult<()> {
+        self.socket_addr()?;
+        if self.acme_client()?.is_none() {
+            // ACME issues missing certificates at startup; otherwise load them now
+            if let Some(config) = self.tls_config() {
+                config
+                    .acceptor(self.http2.is_some())
+                    .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+            }
+        }
+        init_asyncio_once(py)?;
+        let locals = pyo3_asyncio::TaskLocals::new(current_event_loop(py)?);
+        self.build_server(py, &locals).map(drop)
+    }
+}
+
+impl PyApp {
+    /// The `host:port` address, raising `ValueError` when it does not parse
+    fn socket_addr(&self) -> PyResult<std::net::SocketAddr> {
+        format!("{}:{}", self.host, self.port)
+            .parse()
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(format!("{e}")))
+    }
+
+    /// Register a route, rejecting patterns that overlap an earlier one
+    ///
+    /// Raises `ConfigurationError` naming both patterns and their handlers.
+    fn push_route(&mut self, py: Python<'_>, route: Route) -> PyResult<()> {
+        if self.replace_duplicate_routes {
+            if let Some(existing) = self.route_check.duplicate_of(route.method, &route.path) {
+                let existing = existing.to_string();
+                warn!(
+                    "Route {} {} ({}) replaces the earlier registration of {} {existing}",
+                    route.method,
+                    route.path,
+                    describe_handler(py, &route.handler),
+                    route.method,
+                );
+                self.route_check.remove_route(route.method, &existing);
+                self.routes
+                    .retain(|r| !(r.method == route.method && r.path == existing));
+                if let Some(live) = &self.live_routes {
+                    let _ = live.registry.remove_route(route.method, &existing);
+                }
             }
-            server.set_max_body_size(max_body_size);
-            apply_middlewares(&mut server, &middleware_data);
-            apply_python_middlewares(&mut server, &python_middleware_data, locals.clone());
-
-            for route in route_data {
-                let rust_handler = create_handler_adapter(route.handler, locals.clone());
-                server
-                    .add_route(route.method, &route.path, rust_handler, route.auth)
-                    .map_err(|e| {
-                        PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())
-                    })?;
+        }
+        if let Err(err) = self
+            .route_check
+            .add_route(route.method, &route.path, route.auth)
+        {
+            let message = match &err {
+                pyvectora_core::Error::RouteConflict {
+                    method, existing, ..
+                } => {
+                    let previous = self
+                        .routes
+                        .iter()
+                        .find(|r| r.method == route.method && r.path == *existing)
+                        .map(|r| format!(" ({})", describe_handler(py, &r.handler)))
+                        .unwrap_or_default();
+                    format!(
+                        "Route {method} {} ({}) conflicts with {method} {existing}{previous}",
+                        route.path,
+                        describe_handler(py, &route.handler),
+                    )
+                }
+                _ => err.to_string(),
+            };
+            return Err(BindingsError::Configuration(message).into());
+        }
+        self.routes.push(route);
+        Ok(())
+    }
+
+    /// Build a core server with auth, middleware and every route registered
+    ///
+    /// Listener settings (address, TLS, HTTP/2) are left to `serve`.
+    fn build_server(&self, py: Python<'_>, locals: &pyo3_asyncio::TaskLocals) -> PyResult<Server> {
+        let mut server = Server::new(self.jwt_secret.as_deref().unwrap_or(""));
+        if let Some(secret) = &self.jwt_secret {
+            server.enable_auth(secret);
+        }
+        apply_acme_challenges(&mut server, self.acme_challenges.as_ref())?;
+        if let Some(endpoints) = &self.profiling {
+            server
+                .enable_profiling(endpoints)
+                .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        }
+        if let Some(metrics) = &self.metrics {
+            server
+                .enable_metrics(metrics)
+                .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        }
+        if let Some(config) = &self.error_reporting {
+            reporting::install(config.clone());
+        }
+        for (name, options) in &self.rust_extensions {
+            pyvectora_core::extension::create(name, options)
+                .and_then(|extension| server.register_extension(extension))
+                .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        }
+        if let Some(handler) = &self.bad_request_handler {
+            server.set_bad_request_hook(create_bad_request_hook(handler.clone_ref(py)));
+        }
+        if let Some(signals) = &self.shutdown_signals {
+            server.set_shutdown_signals(signals.clone());
+        }
+        server.set_max_body_size(self.max_body_size);
+        server.set_handler_timeout(self.handler_timeout);
+        server.set_multipart_limits(self.multipart_limits.clone());
+        server.set_target_limits(self.target_limits);
+        server.set_param_mismatch(self.param_mismatch);
+        server.set_error_format(self.error_format);
+        server.set_response_policy(self.response_policy);
+        server.set_debug(self.exception_handlers.is_debug());
+        if let Some(browser) = &self.admin {
+            server
+                .enable_admin(browser)
+                .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+        }
+        apply_middlewares(&mut server, &self.middlewares)?;
+        if let Some(mode) = &self.read_only {
+            server.add_middleware(mode.clone());
+        }
+        apply_python_middlewares(&mut server, &self.python_middlewares, locals.clone());
+
+        let serializers = Arc::new(self.serializers.clone());
+        let exception_handlers = Arc::new(self.exception_handlers.clone());
+        for route in &self.routes {
+            let rust_handler =
+                self.route_handler(py, route, locals, &serializers, &exception_handlers);
+            let added = if route.stream {
+                server.add_streaming_route(route.method, &route.path, rust_handler, route.auth)
+            } else {
+                server.add_route(route.method, &route.path, rust_handler, route.auth)
+            };
+            added.map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+            let _ = server.set_route_limits(route.method, &route.path, route.limits);
+        }
+        for mount in &self.wsgi_mounts {
+            let handler = mount.handler(py, &self.host, self.port);
+            for method in WSGI_METHODS {
+                for pattern in mount.patterns() {
+                    server
+                        .add_route(method, &pattern, handler.clone(), false)
+                        .map_err(|e| {
+                            PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string())
+                        })?;
+                }
             }
-
+        }
+        for route in &self.ws_routes {
+            let ws_handler = create_ws_handler_adapter(route.handler.clone_ref(py), locals.clone());
             server
-                .serve()
-                .await
+                .add_websocket_route(&route.path, ws_handler)
                 .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+        }
+        Ok(server)
+    }
 
-            Ok(())
-        })
+    /// Core handler running `route`'s Python handler and route middleware
+    fn route_handler(
+        &self,
+        py: Python<'_>,
+        route: &Route,
+        locals: &pyo3_asyncio::TaskLocals,
+        serializers: &Arc<SerializerRegistry>,
+        exception_handlers: &Arc<ExceptionHandlers>,
+    ) -> Handler {
+        let instrumentation = HandlerInstrumentation::new(
+            format!("{} {}", route.method, route.path),
+            self.accounting.clone(),
+            self.gil_metrics.clone(),
+            self.gil_warn_threshold,
+        );
+        let mut rust_handler = create_handler_adapter(
+            route.handler.clone_ref(py),
+            route.parse_json,
+            locals.clone(),
+            instrumentation,
+            serializers.clone(),
+            exception_handlers.clone(),
+        );
+        if let Some((canary, handler)) = &route.canary {
+            let instrumentation = HandlerInstrumentation::new(
+                format!("{} {} (canary)", route.method, route.path),
+                self.accounting.clone(),
+                self.gil_metrics.clone(),
+                self.gil_warn_threshold,
+            );
+            let canary_handler = create_handler_adapter(
+                handler.clone_ref(py),
+                route.parse_json,
+                locals.clone(),
+                instrumentation,
+                serializers.clone(),
+                exception_handlers.clone(),
+            );
+            rust_handler = canary.split(rust_handler, canary_handler);
+        }
+        let handler = route.requires.wrap(wrap_python_middlewares(
+            rust_handler,
+            &route.middlewares,
+            locals,
+        ));
+        // Outermost, so a retired route answers 410 before any check
+        match &route.deprecation {
+            Some(deprecation) => self.deprecation_policy(deprecation.clone()).wrap(handler),
+            None => handler,
+        }
     }
 
-    /// Create a test client (zero-network)
-    fn test_client(&self, py: Python<'_>) -> PyResult<PyServer> {
-        let jwt_secret = self.jwt_secret.clone();
-        let middleware_data = self.middlewares.clone();
-        let python_middleware_data: Vec<PyObject> = self
-            .python_middlewares
-            .iter()
-            .map(|m| m.clone_ref(py))
-            .collect();
-        let max_body_size = self.max_body_size;
+    /// `deprecation` with the app's link and 410 message applied
+    fn deprecation_policy(&self, mut deprecation: Deprecation) -> Deprecation {
+        if let Some(link) = &self.deprecation_link {
+            deprecation = deprecation.link(link.clone());
+        }
+        if let Some(message) = &self.deprecation_gone {
+            deprecation = deprecation.gone(message.clone());
+        }
+        deprecation
+    }
+
+    /// ACME client for `enable_acme` (`None` when not enabled)
+    ///
+    /// Raises `ConfigurationError` for unusable domains or directory URLs.
+    fn acme_client(&self) -> PyResult<Option<AcmeClient>> {
+        let Some(config) = &self.acme else {
+            return Ok(None);
+        };
+        let challenges = self
+            .acme_challenges
+            .as_ref()
+            .map(|challenges| challenges.inner.clone())
+            .unwrap_or_default();
+        AcmeClient::new(config.clone(), challenges)
+            .map(Some)
+            .map_err(|e| BindingsError::Configuration(e.to_string()).into())
+    }
 
-        struct RouteData {
-            method: Method,
-            path: String,
-            handler: PyObject,
-            auth: bool,
+    /// TLS settings for the core server (`None` when TLS is off)
+    fn tls_config(&self) -> Option<TlsConfig> {
+        let (cert_path, key_path) = self.tls.as_ref()?;
+        let mut config = TlsConfig::new(cert_path, key_path);
+        for (hostname, sni_cert, sni_key) in &self.tls_sni {
+            config = config.with_sni_cert(hostname, sni_cert, sni_key);
         }
+        config.reload_interval = self.tls_reload_interval;
+        Some(config)
+    }
+}
 
-        let route_data: Vec<RouteData> = self
-            .routes
-            .iter()
-            .map(|r| RouteData {
-                method: r.method,
-                path: r.path.clone(),
-                handler: r.handler.clone_ref(py),
-                auth: r.auth,
-            })
-            .collect();
+/// The running asyncio loop, else a new loop set as the current one
+fn current_event_loop(py: Python<'_>) -> PyResult<&PyAny> {
+    let asyncio = py.import("asyncio")?;
+    if let Ok(event_loop) = asyncio.call_method0("get_running_loop") {
+        return Ok(event_loop);
+    }
+    let policy = asyncio.call_method0("get_event_loop_policy")?;
+    let new_loop = policy.call_method0("new_event_loop")?;
+    policy.call_method1("set_event_loop", (new_loop,))?;
+    Ok(new_loop)
+}
 
-        init_asyncio_once(py)?;
+/// Timeout from positive, finite seconds
+fn timeout_from_secs(seconds: f64) -> PyResult<std::time::Duration> {
+    if seconds <= 0.0 {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "timeout must be positive",
+        ));
+    }
+    std::time::Duration::try_from_secs_f64(seconds)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+}
 
-        let asyncio = py.import("asyncio")?;
-        let event_loop = match asyncio.call_method0("get_running_loop") {
-            Ok(loop_) => loop_,
-            Err(_) => {
-                let policy = asyncio.call_method0("get_event_loop_policy")?;
-                let new_loop = policy.call_method0("new_event_loop")?;
-                policy.call_method1("set_event_loop", (new_loop.clone(),))?;
-                new_loop
-            }
-        };
-        let locals = pyo3_asyncio::TaskLocals::new(event_loop);
+/// Parse an HTTP method name, case-insensitively
+fn parse_method(method: &str) -> PyResult<Method> {
+    method.parse().map_err(|e: pyvectora_core::Error| {
+        PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string())
+    })
+}
 
-        let mut server = Server::new(jwt_secret.as_deref().unwrap_or(""));
-        if let Some(secret) = &jwt_secret {
-            server.enable_auth(secret);
+/// Parse a route's `requires` dict into core requirements
+///
+/// Unknown keys raise `ValueError` so typos do not silently disable a check.
+fn extract_requirements(requires: &PyDict) -> PyResult<RouteRequirements> {
+    let mut requirements = RouteRequirements::new();
+    for (key, value) in requires {
+        match key.extract::<&str>()? {
+            "content_type" => {
+                let media_types = value
+                    .extract::<String>()
+                    .map(|media_type| vec![media_type])
+                    .or_else(|_| value.extract::<Vec<String>>())?;
+                for media_type in media_types {
+                    requirements = requirements.content_type(media_type);
+                }
+            }
+            "headers" => {
+                for name in value.extract::<Vec<String>>()? {
+                    requirements = requirements.header(name);
+                }
+            }
+            other => {
+                return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+                    "unknown route requirement: {other}"
+                )))
+            }
         }
-        server.set_max_body_size(max_body_size);
-        apply_middlewares(&mut server, &middleware_data);
-        apply_python_middlewares(&mut server, &python_middleware_data, locals.clone());
+    }
+    Ok(requirements)
+}
 
-        for route in route_data {
-            let rust_handler = create_handler_adapter(route.handler, locals.clone());
-            server
-                .add_route(route.method, &route.path, rust_handler, route.auth)
-                .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+/// Parse a route's `canary` dict into the split and the alternate handler
+///
+/// Unknown keys raise `ValueError`, like route requirements.
+fn extract_canary(canary: &PyDict) -> PyResult<(Canary, PyObject)> {
+    let value_error = |message: String| PyErr::new::<pyo3::exceptions::PyValueError, _>(message);
+    let percent = canary
+        .get_item("percent")?
+        .ok_or_else(|| value_error("canary needs a percent".to_string()))?
+        .extract::<u8>()?;
+    let mut split = Canary::new(percent).map_err(|e| value_error(e.to_string()))?;
+    let mut handler = None;
+    for (key, value) in canary {
+        match key.extract::<&str>()? {
+            "percent" => {}
+            "handler" => handler = Some(value.into_py(value.py())),
+            "sticky_cookie" => split = split.sticky_by(StickyKey::Cookie(value.extract()?)),
+            "sticky_header" => split = split.sticky_by(StickyKey::Header(value.extract()?)),
+            "cookie" => split = split.cookie(value.extract::<String>()?),
+            "ramp" => {
+                let ramp = std::time::Duration::try_from_secs_f64(value.extract()?)
+                    .map_err(|e| value_error(e.to_string()))?;
+                split = split.ramp(ramp);
+            }
+            other => return Err(value_error(format!("unknown canary option: {other}"))),
         }
-
-        Ok(PyServer { inner: server })
     }
+    let handler = handler.ok_or_else(|| value_error("canary needs a handler".to_string()))?;
+    Ok((split, handler))
 }
 
-static INIT_ASYNCIO: std::sync::OnceLock<()> = std::sync::OnceLock::new();
-
-fn init_asyncio_once(_py: Python<'_>) -> PyResult<()> {
-    INIT_ASYNCIO.get_or_init(|| {
-        let _ = pyo3_asyncio::tokio::get_runtime();
+/// `handler name at file:line` for registration errors, best effort
+fn describe_handler(py: Python<'_>, handler: &PyObject) -> String {
+    let handler = py
+        .import("inspect")
+        .and_then(|inspect| inspect.call_method1("unwrap", (handler,)))
+        .unwrap_or_else(|_| handler.as_ref(py));
+    let name = handler
+        .getattr("__qualname__")
+        .and_then(PyAny::extract::<String>)
+        .unwrap_or_else(|_| handler.to_string());
+    let location = handler.getattr("__code__").and_then(|code| {
+        let file: String = code.getattr("co_filename")?.extract()?;
+        let line: u32 = code.getattr("co_firstlineno")?.extract()?;
+        Ok(format!("{file}:{line}"))
     });
-    Ok(())
+    location.map_or_else(
+        |_| format!("handler {name}"),
+        |location| format!("handler {name} at {location}"),
+    )
 }
 
-fn build_tokio_runtime() -> PyResult<Runtime> {
-    Runtime::new().map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+/// Parse signal names, raising `ValueError` for an unknown one
+fn parse_shutdown_signals(names: &[String]) -> PyResult<Vec<ShutdownSignal>> {
+    names
+        .iter()
+        .map(|name| ShutdownSignal::parse(name))
+        .collect::<pyvectora_core::Result<_>>()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
 }
 
-fn apply_middlewares(server: &mut Server, configs: &[MiddlewareConfig]) {
+fn apply_middlewares(server: &mut Server, configs: &[MiddlewareConfig]) -> PyResult<()> {
     for cfg in configs {
         match cfg {
             MiddlewareConfig::Logging { log_headers } => {
@@ -576,11 +2195,36 @@ fn apply_middlewares(server: &mut Server, configs: &[MiddlewareConfig]) {
                 allow_origin,
                 allow_methods,
                 allow_headers,
+                max_age,
+                allow_credentials,
+                expose_headers,
             } => {
-                let mw = CorsMiddleware::new()
-                    .allow_origin(allow_origin.clone())
+                let mut mw = CorsMiddleware::new()
                     .allow_methods(allow_methods.clone())
-                    .allow_headers(allow_headers.clone());
+                    .allow_headers(allow_headers.clone())
+                    .max_age(*max_age)
+                    .allow_credentials(*allow_credentials);
+                mw = match allow_origin {
+                    CorsOrigin::Single(origin) => mw.allow_origin(origin.clone()),
+                    CorsOrigin::List(origins) => mw.allow_origins(origins.iter().cloned()),
+                    CorsOrigin::Validator(callback) => {
+                        let callback = callback.clone();
+                        mw.origin_validator(Arc::new(move |origin: &str| {
+                            Python::with_gil(|py| {
+                                callback
+                                    .call1(py, (origin,))
+                                    .and_then(|allowed| allowed.is_true(py))
+                                    .unwrap_or_else(|e| {
+                                        e.print(py);
+                                        false
+                                    })
+                            })
+                        }))
+                    }
+                };
+                if let Some(expose) = expose_headers {
+                    mw = mw.expose_headers(expose.clone());
+                }
                 server.add_middleware(mw);
             }
             MiddlewareConfig::RateLimit {
@@ -589,8 +2233,72 @@ fn apply_middlewares(server: &mut Server, configs: &[MiddlewareConfig]) {
             } => {
                 server.add_middleware(RateLimitMiddleware::new(*capacity, *refill_per_sec));
             }
+            MiddlewareConfig::TieredRateLimit {
+                tiers,
+                default_tier,
+                claim,
+                resolver,
+            } => {
+                let mut mw = TieredRateLimitMiddleware::new(tiers.clone(), default_tier.as_str())
+                    .map_err(|e| BindingsError::Configuration(e.to_string()))?
+                    .claim(claim.as_str());
+                if let Some(resolver) = resolver {
+                    let resolver = resolver.clone();
+                    mw = mw.resolver(Arc::new(move |req: &PyRequest| {
+                        Python::with_gil(|py| {
+                            let claims = req
+                                .claims
+                                .as_ref()
+                                .map_or_else(|| Ok(py.None()), |c| json_to_pyobject(py, c));
+                            claims
+                                .and_then(|claims| resolver.call1(py, (claims,)))
+                                .and_then(|tier| tier.extract::<Option<String>>(py))
+                                .unwrap_or_else(|e| {
+                                    e.print(py);
+                                    None
+                                })
+                        })
+                    }));
+                }
+                server.add_middleware(mw);
+            }
+            MiddlewareConfig::Compression {
+                min_size,
+                content_types,
+            } => {
+                let mut mw = CompressionMiddleware::new().with_min_size(*min_size);
+                if let Some(content_types) = content_types {
+                    mw = mw.with_content_types(content_types.iter().cloned());
+                }
+                server.add_middleware(mw);
+            }
+            MiddlewareConfig::Session {
+                secret_key,
+                cookie_name,
+                max_age,
+                encrypt,
+                secure,
+                same_site,
+                domain,
+            } => {
+                let mut mw = SessionMiddleware::new(secret_key.as_bytes())
+                    .cookie_name(cookie_name.as_str())
+                    .secure(*secure)
+                    .same_site(*same_site);
+                if let Some(max_age) = max_age {
+                    mw = mw.max_age(*max_age);
+                }
+                if let Some(domain) = domain {
+                    mw = mw.domain(domain.as_str());
+                }
+                if *encrypt {
+                    mw = mw.encrypted();
+                }
+                server.add_middleware(mw);
+            }
         }
     }
+    Ok(())
 }
 
 struct PythonMiddleware {
@@ -603,7 +2311,8 @@ impl PythonMiddleware {
         Self { inner, locals }
     }
 
-    fn before(&self, req: &RustRequest) -> Result<Option<RustResponse>, PyErr> {
+    /// Run the Python hook; changes it makes to the request are kept
+    fn before(&self, req: &mut RustRequest) -> Result<Option<RustResponse>, PyErr> {
         Python::with_gil(|py| {
             let callable = {
                 let any = self.inner.as_ref(py);
@@ -619,14 +2328,15 @@ impl PythonMiddleware {
                 Some(c) => c,
                 None => return Ok(None),
             };
-            let py_req = req.clone().into_py(py);
-            let result = callable.call1(py, (py_req,))?;
+            let py_req = Py::new(py, req.clone())?;
+            let result = callable.call1(py, (py_req.clone_ref(py),))?;
             let obj = result.to_object(py);
             if is_coroutine(py, &obj) {
                 return Err(PyErr::new::<pyo3::exceptions::PyTypeError, _>(
                     "Middleware must be sync",
                 ));
             }
+            *req = py_req.borrow(py).clone();
             if result.is_none(py) {
                 Ok(None)
             } else {
@@ -660,7 +2370,7 @@ impl PythonMiddleware {
 }
 
 impl Middleware for PythonMiddleware {
-    fn before_request(&self, req: &RustRequest) -> MiddlewareResult {
+    fn before_request(&self, req: &mut RustRequest) -> MiddlewareResult {
         match self.before(req) {
             Ok(Some(resp)) => MiddlewareResult::Respond(resp),
             Ok(None) => MiddlewareResult::Continue,
@@ -699,6 +2409,7 @@ fn select_callable(py: Python<'_>, target: &PyObject, method: &str) -> Result<Py
 fn rust_response_to_py(py: Python<'_>, res: &RustResponse) -> PyResult<PyObject> {
     let mut py_resp = PyResponse::new(&res.body, res.status, &res.content_type);
     py_resp.headers = res.headers.clone();
+    py_resp.extra_headers = res.extra_headers.clone();
     let py_resp = Py::new(py, py_resp)?;
     Ok(py_resp.to_object(py))
 }
@@ -715,10 +2426,71 @@ fn apply_python_middlewares(
     });
 }
 
+/// Adapt a sync Python callable to the core `BadRequestHook`
+///
+/// Exceptions and coroutine results are reported and fall back to the
+/// default 400 response.
+fn create_bad_request_hook(handler: PyObject) -> BadRequestHook {
+    Arc::new(move |kind, message| {
+        Python::with_gil(|py| match handler.call1(py, (kind.as_str(), message)) {
+            Ok(result) if result.is_none(py) => None,
+            Ok(result) if is_coroutine(py, &result) => {
+                warn!("Bad request handler must be sync; using the default response");
+                None
+            }
+            Ok(result) => Some(convert_python_response(py, result)),
+            Err(err) => {
+                err.print(py);
+                None
+            }
+        })
+    })
+}
+
+/// Serve ACME HTTP-01 challenges when enabled
+fn apply_acme_challenges(
+    server: &mut Server,
+    challenges: Option<&PyAcmeChallenges>,
+) -> PyResult<()> {
+    challenges.map_or(Ok(()), |challenges| {
+        server
+            .enable_acme_http01(&challenges.inner)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+    })
+}
+
+/// Issue the ACME certificate if due, then keep renewing it in the background
+async fn start_acme(client: AcmeClient) -> PyResult<AcmeRenewal> {
+    client
+        .ensure()
+        .await
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))?;
+    Ok(client.spawn_renewal())
+}
+
+/// Wrap a handler with route-scoped Python middleware
+fn wrap_python_middlewares(
+    handler: Handler,
+    items: &[PyObject],
+    locals: &pyo3_asyncio::TaskLocals,
+) -> Handler {
+    if items.is_empty() {
+        return handler;
+    }
+    let mut chain = MiddlewareChain::new();
+    Python::with_gil(|py| {
+        for item in items {
+            chain.add(PythonMiddleware::new(item.clone_ref(py), locals.clone()));
+        }
+    });
+    chain.wrap(handler)
+}
+
 impl From<RustResponse> for PyResponse {
     fn from(r: RustResponse) -> Self {
         let mut resp = PyResponse::new(&r.body, r.status, &r.content_type);
         resp.headers = r.headers;
+        resp.extra_headers = r.extra_headers;
         resp
     }
 }
@@ -727,18 +2499,65 @@ impl From<RustResponse> for PyResponse {
 ///
 /// This is the critical FFI boundary - all panics MUST be caught here
 /// to prevent crashing the Python interpreter.
-fn create_handler_adapter(handler: PyObject, locals: pyo3_asyncio::TaskLocals) -> Handler {
+///
+/// With `parse_json` the body is parsed before the GIL is taken and
+/// requests with invalid JSON are answered 400 without calling `handler`.
+fn create_handler_adapter(
+    handler: PyObject,
+    parse_json: bool,
+    locals: pyo3_asyncio::TaskLocals,
+    instrumentation: HandlerInstrumentation,
+    serializers: Arc<SerializerRegistry>,
+    exception_handlers: Arc<ExceptionHandlers>,
+) -> Handler {
     Arc::new(move |req, _matched| {
+        let json_body = if parse_json {
+            match req.json_body() {
+                Ok(body) => Some(body),
+                Err(err) => return Box::pin(std::future::ready(invalid_json_response(&err))),
+            }
+        } else {
+            None
+        };
         let handler = handler.clone();
         let locals = locals.clone();
+        let instrumentation = instrumentation.clone();
+        let serializers = serializers.clone();
+        let exception_handlers = exception_handlers.clone();
         let req = req.clone();
-        let token = CancellationToken::new();
-        let ctx = PyExecutionContext::new(token.clone());
-
-        Box::pin(async move { execute_handler(handler, ctx, req, locals).await })
+        let ctx = PyExecutionContext::new(&req);
+
+        Box::pin(async move {
+            let mut meter = instrumentation.meter();
+            let response = execute_handler(
+                handler,
+                json_body,
+                ctx,
+                req,
+                locals,
+                &serializers,
+                &exception_handlers,
+                &mut meter,
+            )
+            .await;
+            instrumentation.record(&meter);
+            response
+        })
     })
 }
 
+/// 400 for a `parse_json` route whose body is not valid JSON
+fn invalid_json_response(err: &pyvectora_core::Error) -> RustResponse {
+    let detail = match err {
+        pyvectora_core::Error::BadRequest { message, .. } => message.clone(),
+        other => other.to_string(),
+    };
+    let body = serde_json::json!({ "error": "Invalid JSON body", "detail": detail });
+    RustResponse::json(body.to_string())
+        .with_status(400)
+        .with_problem(ProblemDetails::new(400).with_detail(detail))
+}
+
 fn is_coroutine_function(handler: &PyObject) -> bool {
     Python::with_gil(|py| {
         let inspect = py.import("inspect").ok();
@@ -753,8 +2572,67 @@ fn is_coroutine_function(handler: &PyObject) -> bool {
     })
 }
 
+/// Response for a raised `HTTPException`: `{"detail": ...}` with its status
+///
+/// Missing or malformed attributes fall back to 500 and the reason phrase.
+fn http_exception_response(exc: &PyAny) -> RustResponse {
+    let status = exc
+        .getattr("status")
+        .and_then(PyAny::extract::<u16>)
+        .ok()
+        .filter(|status| (100..=599).contains(status))
+        .unwrap_or(500);
+    let detail = exc
+        .getattr("detail")
+        .ok()
+        .filter(|detail| !detail.is_none())
+        .map_or_else(
+            || serde_json::Value::from(RustResponse::reason_phrase(status).unwrap_or("Error")),
+            |detail| {
+                pyobject_to_json(detail)
+                    .unwrap_or_else(|_| serde_json::Value::String(detail.to_string()))
+            },
+        );
+    let problem = match &detail {
+        serde_json::Value::String(text) => ProblemDetails::new(status).with_detail(text.as_str()),
+        other => ProblemDetails::new(status).with_extension("detail", other.clone()),
+    };
+    let mut response = RustResponse::json(serde_json::json!({ "detail": detail }).to_string())
+        .with_status(status)
+        .with_problem(problem);
+    let headers = exc
+        .getattr("headers")
+        .and_then(PyAny::extract::<Option<HashMap<String, String>>>)
+        .ok()
+        .flatten();
+    for (key, value) in headers.iter().flatten() {
+        response.set_header(key, value);
+    }
+    response
+}
+
+/// Response for an exception raised by a handler or middleware
+///
+/// `HTTPException` is answered as raised. The bindings' `NotFoundError`, `ValidationError` and `AuthenticationError`
+/// become 404/422/401 JSON errors; anything else is logged and answered 500.
 fn convert_py_error(err: PyErr) -> RustResponse {
     Python::with_gil(|py| {
+        if err.is_instance_of::<error::HTTPException>(py) {
+            return http_exception_response(err.value(py));
+        }
+        if err.is_instance_of::<RequestValidationError>(py) {
+            return request_validation_response(py, &err);
+        }
+        if let Some((status, reason)) = error::http_status_for(py, &err) {
+            let detail = err.value(py).to_string();
+            let body = serde_json::json!({
+                "error": reason,
+                "detail": detail,
+            });
+            return RustResponse::json(body.to_string())
+                .with_status(status)
+                .with_problem(ProblemDetails::new(status).with_detail(detail));
+        }
         err.print(py);
         let error_msg = err.to_string().replace('"', "\\\"");
         RustResponse::json(format!(
@@ -762,35 +2640,177 @@ fn convert_py_error(err: PyErr) -> RustResponse {
             error_msg
         ))
         .with_status(500)
+        .with_problem(ProblemDetails::new(500))
+    })
+}
+
+/// 422 response listing the field errors of a `RequestValidationError`
+///
+/// Same shape as the router's answer to invalid path parameters.
+fn request_validation_response(py: Python<'_>, err: &PyErr) -> RustResponse {
+    let errors = err
+        .value(py)
+        .getattr("errors")
+        .and_then(pyobject_to_json)
+        .unwrap_or_else(|_| serde_json::Value::Array(Vec::new()));
+    let body = serde_json::json!({ "errors": errors });
+    RustResponse::json(body.to_string())
+        .with_status(422)
+        .with_problem(
+            ProblemDetails::new(422)
+                .with_detail(err.value(py).to_string())
+                .with_extension("errors", errors),
+        )
+}
+
+/// Headers whose values are hidden from debug error pages
+const REDACTED_HEADERS: [&str; 3] = ["authorization", "cookie", "proxy-authorization"];
+
+/// Response for an exception raised by a handler in debug mode
+///
+/// Exceptions that would be answered 500 get the traceback and the request
+/// (credentials redacted) in the body and are logged; others are answered
+/// as `convert_py_error` does.
+fn debug_error_response(err: PyErr, req: &RustRequest) -> RustResponse {
+    Python::with_gil(|py| {
+        if err.is_instance_of::<error::HTTPException>(py)
+            || error::http_status_for(py, &err).is_some()
+        {
+            return convert_py_error(err);
+        }
+        let traceback: Vec<String> = py
+            .import("traceback")
+            .and_then(|tb| {
+                tb.call_method1(
+                    "format_exception",
+                    (err.get_type(py), err.value(py), err.traceback(py)),
+                )
+            })
+            .and_then(PyAny::extract::<Vec<String>>)
+            .map_or_else(
+                |_| vec![err.to_string()],
+                |chunks| chunks.concat().lines().map(String::from).collect(),
+            );
+        error!(
+            "Unhandled exception in {} {}:\n{}",
+            req.method,
+            req.path,
+            traceback.join("\n")
+        );
+        let headers: Vec<(String, String)> = req
+            .raw_header_list()
+            .into_iter()
+            .map(|(name, value)| {
+                if REDACTED_HEADERS.contains(&name.as_str()) {
+                    (name, "[redacted]".to_string())
+                } else {
+                    (name, value)
+                }
+            })
+            .collect();
+        let request = serde_json::json!({
+            "method": req.method.to_string(),
+            "path": req.path,
+            "query": req.query_string(),
+            "headers": headers,
+        });
+        let body = serde_json::json!({
+            "error": "Internal Server Error",
+            "details": err.to_string(),
+            "traceback": traceback,
+            "request": request,
+        });
+        let problem = ProblemDetails::new(500)
+            .with_detail(err.to_string())
+            .with_extension("traceback", body["traceback"].clone())
+            .with_extension("request", request);
+        RustResponse::json(body.to_string())
+            .with_status(500)
+            .with_problem(problem)
     })
 }
 
+/// Python result being awaited on the Tokio side
+type PyResultFuture =
+    std::pin::Pin<Box<dyn std::future::Future<Output = PyResult<PyObject>> + Send>>;
+
+/// Let the registered exception handler for `err` produce the result
+///
+/// Returns `err` unchanged when no handler matches its type.
+async fn handle_exception(
+    err: PyErr,
+    req: &RustRequest,
+    exception_handlers: &ExceptionHandlers,
+    locals: &pyo3_asyncio::TaskLocals,
+    meter: &mut InvocationMeter,
+) -> PyResult<PyObject> {
+    let pending = meter.with_gil(|py| -> PyResult<Option<PyResultFuture>> {
+        let Some(handler) = exception_handlers.find(py, &err) else {
+            return Ok(None);
+        };
+        let result = handler.call1(py, (req.clone().into_py(py), err.value(py)))?;
+        if result.as_ref(py).hasattr("__await__")? {
+            let fut = pyo3_asyncio::into_future_with_locals(locals, result.as_ref(py))?;
+            Ok(Some(Box::pin(fut)))
+        } else {
+            Ok(Some(Box::pin(std::future::ready(Ok(result)))))
+        }
+    });
+    match pending {
+        Ok(Some(fut)) => fut.await,
+        Ok(None) => Err(err),
+        Err(e) => Err(e),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn execute_handler(
     handler: PyObject,
+    json_body: Option<serde_json::Value>,
     ctx: PyExecutionContext,
     req: RustRequest,
     locals: pyo3_asyncio::TaskLocals,
+    serializers: &SerializerRegistry,
+    exception_handlers: &ExceptionHandlers,
+    meter: &mut InvocationMeter,
 ) -> RustResponse {
     let is_async = is_coroutine_function(&handler);
+    meter.sync_handler = !is_async;
+    let mut cancel = CancelOnDrop::new(&ctx);
 
-    let fut_result = Python::with_gil(
+    let fut_result = meter.with_gil(
         |py| -> PyResult<
             std::pin::Pin<Box<dyn std::future::Future<Output = PyResult<PyObject>> + Send>>,
         > {
+            let log_context = request_context(py, locals.context(py), &req)?;
+            let py_req = req.clone().into_py(py);
+            let py_ctx = Py::new(py, ctx)?;
+            py_req.as_ref(py).setattr("context", py_ctx)?;
+            let mut args = vec![py_req];
+            if let Some(body) = &json_body {
+                args.push(json_to_pyobject(py, body)?);
+            }
             if is_async {
-                let py_req = req.clone().into_py(py);
-                let py_ctx = Py::new(py, ctx)?;
-                py_req.as_ref(py).setattr("context", py_ctx)?;
-
-                let coro = handler.call1(py, (py_req,))?;
-                let fut = pyo3_asyncio::into_future_with_locals(&locals, coro.as_ref(py))?;
+                let coro = handler.call1(py, PyTuple::new(py, &args))?;
+                // Scheduled as its own task (inheriting the log context) so a
+                // timed out handler can be cancelled from this thread
+                let asyncio = py.import("asyncio")?;
+                let event_loop = locals.event_loop(py);
+                let schedule = asyncio.getattr("run_coroutine_threadsafe")?;
+                let task = log_context.call_method1("run", (schedule, coro, event_loop))?;
+                cancel.watch(task.into());
+                let awaitable = asyncio.call_method(
+                    "wrap_future",
+                    (task,),
+                    Some([("loop", event_loop)].into_py_dict(py)),
+                )?;
+                let fut = pyo3_asyncio::into_future_with_locals(&locals, awaitable)?;
                 Ok(Box::pin(fut))
             } else {
-                let py_req = req.clone().into_py(py);
-                let py_ctx = Py::new(py, ctx)?;
-                py_req.as_ref(py).setattr("context", py_ctx)?;
-
-                let resp = handler.call1(py, (py_req,))?;
+                args.insert(0, handler.clone_ref(py));
+                let resp: PyObject = log_context
+                    .call_method1("run", PyTuple::new(py, &args))?
+                    .into();
                 Ok(Box::pin(std::future::ready(Ok(resp))))
             }
         },
@@ -800,15 +2820,39 @@ async fn execute_handler(
         Ok(fut) => fut.await,
         Err(e) => Err(e),
     };
+    cancel.disarm();
+
+    let result = match result {
+        Err(err) if !exception_handlers.is_empty() => {
+            handle_exception(err, &req, exception_handlers, &locals, meter).await
+        }
+        result => result,
+    };
+
+    let result = result.and_then(|py_resp| meter.with_gil(|py| serializers.apply(py, py_resp)));
+    if let Err(err) = &result {
+        if pyvectora_core::events::is_active(EventKind::HandlerException) {
+            meter.with_gil(|py| report_handler_exception(py, err, &req));
+        }
+    }
 
     match result {
         Ok(py_resp) => {
-            if Python::with_gil(|py| is_streaming_response(py, &py_resp)) {
-                collect_streaming_response(py_resp, &locals).await
+            if meter.with_gil(|py| is_sse_response(py, &py_resp)) {
+                stream_sse_response(&py_resp, &locals)
+            } else if meter.with_gil(|py| is_ndjson_response(py, &py_resp)) {
+                stream_ndjson_response(&py_resp, &locals)
+            } else if meter.with_gil(|py| is_csv_response(py, &py_resp)) {
+                stream_csv_response(&py_resp, &locals)
+            } else if meter.with_gil(|py| is_streaming_response(py, &py_resp)) {
+                stream_python_response(&py_resp, &locals)
+            } else if meter.with_gil(|py| is_spooled_response(py, &py_resp)) {
+                send_spooled_response(&py_resp, &req).await
             } else {
-                Python::with_gil(|py| convert_python_response(py, py_resp))
+                meter.with_gil(|py| convert_python_response(py, py_resp))
             }
         }
+        Err(e) if exception_handlers.is_debug() => debug_error_response(e, &req),
         Err(e) => convert_py_error(e),
     }
 }
@@ -824,6 +2868,9 @@ fn convert_python_response(py: Python<'_>, result: PyObject) -> RustResponse {
             body: resp.body,
             content_type: resp.content_type,
             headers: resp.headers,
+            extra_headers: resp.extra_headers,
+            stream: None,
+            problem: None,
         };
     }
 
@@ -832,6 +2879,13 @@ fn convert_python_response(py: Python<'_>, result: PyObject) -> RustResponse {
     }
 
     let bound = result.as_ref(py);
+    if let Ok(bytes) = bound.downcast::<PyBytes>() {
+        return RustResponse::binary(
+            Bytes::copy_from_slice(bytes.as_bytes()),
+            "application/octet-stream",
+        );
+    }
+
     if let Ok(status_attr) = bound.getattr("status") {
         let status = status_attr.extract::<u16>().unwrap_or(200);
         let body = bound
@@ -846,25 +2900,23 @@ fn convert_python_response(py: Python<'_>, result: PyObject) -> RustResponse {
             .getattr("headers")
             .and_then(|h| h.extract::<HashMap<String, String>>())
             .unwrap_or_default();
+        let extra_headers = bound
+            .getattr("extra_headers")
+            .and_then(|h| h.extract::<Vec<(String, String)>>())
+            .unwrap_or_default();
         return RustResponse {
             status,
             body,
             content_type,
             headers,
+            extra_headers,
+            stream: None,
+            problem: None,
         };
     }
 
-    if let Ok(dict) = result.downcast::<PyDict>(py) {
-        if let Ok(json_module) = py.import("json") {
-            if let Ok(dumps) = json_module.getattr("dumps") {
-                if let Ok(json_result) = dumps.call1((dict,)) {
-                    if let Ok(json_str) = json_result.extract::<String>() {
-                        return RustResponse::json(json_str);
-                    }
-                }
-            }
-        }
-        return RustResponse::json("{}".to_string());
+    if result.downcast::<PyDict>(py).is_ok() || result.downcast::<PyList>(py).is_ok() {
+        return json_dumps(bound).map_or_else(|_| RustResponse::json("{}"), RustResponse::json);
     }
 
     RustResponse::text("Internal Server Error: Unsupported response type").with_status(500)
@@ -878,12 +2930,38 @@ fn is_streaming_response(py: Python<'_>, result: &PyObject) -> bool {
         .unwrap_or(false)
 }
 
-async fn collect_streaming_response(
-    result: PyObject,
-    locals: &pyo3_asyncio::TaskLocals,
-) -> RustResponse {
-    let (status, content_type, headers, content) = match Python::with_gil(|py| {
-        let resp = result.as_ref(py);
+/// Number of chunks buffered between the Python producer and the socket
+const STREAM_CHANNEL_CAPACITY: usize = 16;
+
+/// Turn a Python streaming response into an incremental Rust response
+///
+/// Chunks are pushed into a bounded channel as the generator yields them,
+/// so the client receives data as soon as it is produced.
+fn stream_python_response(result: &PyObject, locals: &pyo3_asyncio::TaskLocals) -> RustResponse {
+    let source = match Python::with_gil(|py| StreamSource::extract(result.as_ref(py), "text/plain"))
+    {
+        Ok(v) => v,
+        Err(err) => return convert_py_error(err),
+    };
+
+    let rx = spawn_chunk_producer(source.iter, source.is_async, locals, py_chunk_to_bytes);
+    let mut response = RustResponse::streaming(rx, source.content_type).with_status(source.status);
+    response.headers = source.headers;
+    response
+}
+
+/// Status, headers and iterator of a Python streaming response
+struct StreamSource {
+    status: u16,
+    content_type: String,
+    headers: HashMap<String, String>,
+    iter: PyObject,
+    is_async: bool,
+}
+
+impl StreamSource {
+    fn extract(resp: &PyAny, default_content_type: &str) -> PyResult<Self> {
+        let py = resp.py();
         let status = resp
             .getattr("status")
             .and_then(|v| v.extract::<u16>())
@@ -891,7 +2969,7 @@ async fn collect_streaming_response(
         let content_type = resp
             .getattr("content_type")
             .and_then(|v| v.extract::<String>())
-            .unwrap_or_else(|_| "text/plain".to_string());
+            .unwrap_or_else(|_| default_content_type.to_string());
         let headers = resp
             .getattr("headers")
             .and_then(|h| h.extract::<HashMap<String, String>>())
@@ -900,128 +2978,137 @@ async fn collect_streaming_response(
         if content.is_callable() {
             content = content.call0()?;
         }
-        Ok((status, content_type, headers, content.into_py(py)))
-    }) {
-        Ok(v) => v,
-        Err(err) => return convert_py_error(err),
-    };
-
-    let mut out = String::new();
+        let is_async = content.hasattr("__anext__").unwrap_or(false)
+            || content.hasattr("__aiter__").unwrap_or(false);
+        let iter = if is_async {
+            content.call_method0("__aiter__")?
+        } else {
+            content.call_method0("__iter__")?
+        };
+        Ok(Self {
+            status,
+            content_type,
+            headers,
+            iter: iter.into_py(py),
+            is_async,
+        })
+    }
+}
 
-    let is_async = Python::with_gil(|py| {
-        let any = content.as_ref(py);
-        any.hasattr("__anext__").unwrap_or(false) || any.hasattr("__aiter__").unwrap_or(false)
-    });
+/// Drive a Python iterator and forward converted items into a channel
+///
+/// Sync iterators run on the blocking pool; async iterators are driven
+/// through the event loop. Errors raised mid-stream are logged and close
+/// the channel early.
+fn spawn_chunk_producer<T, F>(
+    iter: PyObject,
+    is_async: bool,
+    locals: &pyo3_asyncio::TaskLocals,
+    convert: F,
+) -> tokio::sync::mpsc::Receiver<T>
+where
+    T: Send + 'static,
+    F: Fn(&PyAny) -> PyResult<T> + Send + 'static,
+{
+    let (tx, rx) = tokio::sync::mpsc::channel(STREAM_CHANNEL_CAPACITY);
 
     if is_async {
-        let async_iter = Python::with_gil(|py| {
-            content
-                .as_ref(py)
-                .call_method0("__aiter__")
-                .map(|v| v.into_py(py))
-        });
-        let async_iter = match async_iter {
-            Ok(v) => v,
-            Err(err) => return convert_py_error(err),
-        };
-        loop {
-            let fut = Python::with_gil(|py| -> PyResult<_> {
-                let anext = async_iter.as_ref(py).call_method0("__anext__")?;
-                let fut = pyo3_asyncio::into_future_with_locals(locals, anext)?;
-                Ok(fut)
-            });
-            let next = match fut {
-                Ok(fut) => fut.await,
-                Err(err) => return convert_py_error(err),
-            };
-            match next {
-                Ok(item) => {
-                    if let Ok(chunk) = Python::with_gil(|py| py_chunk_to_string(py, item)) {
-                        out.push_str(&chunk);
+        let locals = locals.clone();
+        tokio::spawn(async move {
+            loop {
+                let fut = Python::with_gil(|py| -> PyResult<_> {
+                    let anext = iter.as_ref(py).call_method0("__anext__")?;
+                    pyo3_asyncio::into_future_with_locals(&locals, anext)
+                });
+                let next = match fut {
+                    Ok(fut) => fut.await,
+                    Err(err) => Err(err),
+                };
+                let chunk = match next {
+                    Ok(item) => Python::with_gil(|py| convert(item.as_ref(py))),
+                    Err(err) => {
+                        Python::with_gil(|py| {
+                            if !err.is_instance_of::<PyStopAsyncIteration>(py) {
+                                err.print(py);
+                            }
+                        });
+                        break;
                     }
-                }
-                Err(err) => {
-                    let is_stop =
-                        Python::with_gil(|py| err.is_instance_of::<PyStopAsyncIteration>(py));
-                    if is_stop {
+                };
+                match chunk {
+                    Ok(item) => {
+                        if tx.send(item).await.is_err() {
+                            break;
+                        }
+                    }
+                    Err(err) => {
+                        Python::with_gil(|py| err.print(py));
                         break;
                     }
-                    return convert_py_error(err);
                 }
             }
-        }
-    } else {
-        let iter = Python::with_gil(|py| {
-            content
-                .as_ref(py)
-                .call_method0("__iter__")
-                .map(|v| v.into_py(py))
         });
-        let iter = match iter {
-            Ok(v) => v,
-            Err(err) => return convert_py_error(err),
-        };
-        loop {
-            let next = Python::with_gil(|py| -> PyResult<Option<PyObject>> {
+    } else {
+        tokio::task::spawn_blocking(move || loop {
+            let next = Python::with_gil(|py| -> PyResult<Option<T>> {
                 match iter.as_ref(py).call_method0("__next__") {
-                    Ok(item) => Ok(Some(item.into())),
-                    Err(err) => {
-                        if err.is_instance_of::<PyStopIteration>(py) {
-                            Ok(None)
-                        } else {
-                            Err(err)
-                        }
-                    }
+                    Ok(item) => convert(item).map(Some),
+                    Err(err) if err.is_instance_of::<PyStopIteration>(py) => Ok(None),
+                    Err(err) => Err(err),
                 }
             });
             match next {
                 Ok(Some(item)) => {
-                    if let Ok(chunk) = Python::with_gil(|py| py_chunk_to_string(py, item)) {
-                        out.push_str(&chunk);
+                    if tx.blocking_send(item).is_err() {
+                        break;
                     }
                 }
                 Ok(None) => break,
-                Err(err) => return convert_py_error(err),
+                Err(err) => {
+                    Python::with_gil(|py| err.print(py));
+                    break;
+                }
             }
-        }
+        });
     }
 
-    RustResponse {
-        status,
-        body: out,
-        content_type,
-        headers,
-    }
+    rx
 }
 
-fn py_chunk_to_string(py: Python<'_>, obj: PyObject) -> PyResult<String> {
-    let any = obj.as_ref(py);
+fn py_chunk_to_bytes(any: &PyAny) -> PyResult<Bytes> {
     if let Ok(b) = any.downcast::<PyBytes>() {
-        return Ok(String::from_utf8_lossy(b.as_bytes()).to_string());
+        return Ok(Bytes::copy_from_slice(b.as_bytes()));
     }
     if let Ok(s) = any.downcast::<PyString>() {
-        return Ok(s.to_str()?.to_string());
+        return Ok(Bytes::from(s.to_str()?.to_string()));
     }
-    Ok(any.str()?.to_str()?.to_string())
+    Ok(Bytes::from(any.str()?.to_str()?.to_string()))
 }
 /// Server wrapper for zero-network testing
 #[pyclass(name = "Server")]
 struct PyServer {
     inner: Server,
+    /// Runtime of the app the client was created from
+    runtime: tokio::runtime::Handle,
 }
 
 #[pymethods]
 impl PyServer {
     /// Execute a test request directly (bypassing TCP)
-    #[pyo3(signature = (method, path, headers=None, body=None))]
+    ///
+    /// With `raise_limit_errors`, a request over the body or target limits
+    /// raises `PayloadTooLarge`, `UriTooLong` or `TooManyQueryParams`
+    /// instead of returning the 413, 414 or 400 response.
+    #[pyo3(signature = (method, path, headers=None, body=None, raise_limit_errors=false))]
     fn test_request<'py>(
         &self,
-        _py: Python<'py>,
+        py: Python<'py>,
         method: &str,
         path: String,
         headers: Option<HashMap<String, String>>,
         body: Option<Vec<u8>>,
-    ) -> PyResponse {
+        raise_limit_errors: bool,
+    ) -> PyResult<PyResponse> {
         let method = match method.to_uppercase().as_str() {
             "GET" => pyvectora_core::router::Method::Get,
             "POST" => pyvectora_core::router::Method::Post,
@@ -1035,15 +3122,31 @@ impl PyServer {
 
         let headers_map = headers.unwrap_or_default();
 
-        let body_bytes = body.map(pyvectora_core::server::Bytes::from);
-
-        let rt = get_runtime();
-        let resp = rt.block_on(
-            self.inner
-                .test_request(method, path, headers_map, body_bytes),
-        );
+        let body_bytes = body.map(Bytes::from);
+
+        // Release the GIL so streaming producers on other threads can run.
+        let resp = py.allow_threads(|| {
+            if raise_limit_errors {
+                self.runtime.block_on(self.inner.try_test_request(
+                    method,
+                    path,
+                    headers_map,
+                    body_bytes,
+                ))
+            } else {
+                Ok(self.runtime.block_on(self.inner.test_request(
+                    method,
+                    path,
+                    headers_map,
+                    body_bytes,
+                )))
+            }
+        });
 
-        PyResponse::from(resp)
+        resp.map(PyResponse::from).map_err(|e| {
+            limit_error(py, &e)
+                .unwrap_or_else(|| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+        })
     }
 }
 
@@ -1053,6 +3156,75 @@ fn version() -> &'static str {
     pyvectora_core::VERSION
 }
 
+/// Names of the compiled extensions available to `App.enable_rust_extension`
+#[pyfunction]
+fn rust_extensions() -> Vec<String> {
+    pyvectora_core::extension::available()
+}
+
+/// Compare two exported route tables
+///
+/// Returns a dict with `added`, `removed` and `changed` lists of
+/// `"METHOD /path"` keys. The new table must compile into a valid router.
+#[pyfunction]
+fn diff_route_tables(py: Python<'_>, old: &str, new: &str) -> PyResult<PyObject> {
+    let parse = |json: &str| {
+        RouteTable::from_json(json)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))
+    };
+    let old = parse(old)?;
+    let new = parse(new)?;
+    new.to_router()
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    let diff = serde_json::to_value(old.diff(&new))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    json_to_pyobject(py, &diff)
+}
+
+/// Run `argv` as `workers` supervised worker processes (blocks until a
+/// shutdown signal)
+///
+/// Crashed or hung workers are restarted; with `max_requests` workers are
+/// replaced one at a time after serving that many requests. `signals`
+/// names the shutdown signals (default: `SIGINT`, `SIGTERM`, `SIGHUP`).
+#[cfg(unix)]
+#[pyfunction]
+#[pyo3(signature = (argv, workers, max_requests=None, heartbeat_timeout=None, signals=None))]
+fn supervise(
+    py: Python<'_>,
+    argv: Vec<String>,
+    workers: usize,
+    max_requests: Option<u64>,
+    heartbeat_timeout: Option<f64>,
+    signals: Option<Vec<String>>,
+) -> PyResult<()> {
+    use pyvectora_core::supervisor::{Supervisor, SupervisorConfig};
+
+    let mut argv = argv.into_iter();
+    let Some(program) = argv.next() else {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(
+            "argv must not be empty",
+        ));
+    };
+    let mut config = SupervisorConfig::default().with_workers(workers);
+    if let Some(max) = max_requests {
+        config = config.with_max_requests(max);
+    }
+    if let Some(seconds) = heartbeat_timeout {
+        let timeout = std::time::Duration::try_from_secs_f64(seconds)
+            .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+        config = config.with_heartbeat_timeout(timeout);
+    }
+    if let Some(names) = signals {
+        config = config.with_shutdown_signals(parse_shutdown_signals(&names)?);
+    }
+    init_tracing();
+    let supervisor = Supervisor::new(program, argv, config);
+    let runtime = get_runtime()?;
+    py.allow_threads(|| runtime.block_on(supervisor.run()))
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(e.to_string()))
+}
+
 /// PyVectora Python module
 #[pymodule]
 fn pyvectora_native(_py: Python, m: &PyModule) -> PyResult<()> {
@@ -1060,11 +3232,28 @@ fn pyvectora_native(_py: Python, m: &PyModule) -> PyResult<()> {
 
     m.add_class::<PyApp>()?;
     m.add_class::<PyRequest>()?;
+    m.add_class::<PyHeaders>()?;
+    m.add_class::<Session>()?;
+    m.add_class::<RequestState>()?;
+    m.add_class::<UploadedFile>()?;
     m.add_class::<PyResponse>()?;
     m.add_class::<PyServer>()?;
 
     register_database_classes(m)?;
+    register_websocket_classes(m)?;
+    register_retry_functions(m)?;
+    register_event_functions(m)?;
+    register_log_context_functions(m)?;
+    register_logging_bridge_functions(m)?;
+    register_sse_classes(m)?;
+    register_request_stream_classes(m)?;
+    register_acme_classes(m)?;
+    register_runtime_classes(m)?;
 
     m.add_function(wrap_pyfunction!(version, m)?)?;
+    m.add_function(wrap_pyfunction!(diff_route_tables, m)?)?;
+    m.add_function(wrap_pyfunction!(rust_extensions, m)?)?;
+    #[cfg(unix)]
+    m.add_function(wrap_pyfunction!(supervise, m)?)?;
     Ok(())
 }
@@ -0,0 +1,54 @@
+//! # Request Log Context Python Bindings
+//!
+//! Runs Python handlers in a `contextvars` context whose
+//! `pyvectora_log_context` variable holds the request's correlation
+//! fields, so `pyvectora.logging` can put them on every `logging` record.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only carries `LogContext` into Python contexts
+//! - **D**: Python reads a plain dict, not core types
+
+use pyo3::prelude::*;
+use pyo3::sync::GILOnceCell;
+use pyo3::types::PyDict;
+use pyvectora_core::log_context::LogContext;
+use pyvectora_core::server::PyRequest as RustRequest;
+
+/// The `ContextVar` holding the current request's log context dict
+#[pyfunction]
+pub fn log_context_var(py: Python<'_>) -> PyResult<&PyAny> {
+    static VAR: GILOnceCell<PyObject> = GILOnceCell::new();
+    let var = VAR.get_or_try_init(py, || {
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("default", py.None())?;
+        py.import("contextvars")?
+            .getattr("ContextVar")?
+            .call(("pyvectora_log_context",), Some(kwargs))
+            .map(Into::into)
+    })?;
+    Ok(var.as_ref(py))
+}
+
+/// Copy of `base` (a `contextvars.Context`) with `req`'s log context set
+pub fn request_context<'py>(
+    py: Python<'py>,
+    base: &'py PyAny,
+    req: &RustRequest,
+) -> PyResult<&'py PyAny> {
+    let context = LogContext::of(req);
+    let fields = PyDict::new(py);
+    fields.set_item("request_id", context.request_id)?;
+    fields.set_item("route", context.route)?;
+    fields.set_item("tenant", context.tenant)?;
+    fields.set_item("user", context.user)?;
+    let copy = base.call_method0("copy")?;
+    copy.call_method1("run", (log_context_var(py)?.getattr("set")?, fields))?;
+    Ok(copy)
+}
+
+/// Register log context functions with Python module
+pub fn register_log_context_functions(m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(log_context_var, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! # Python Logging Bridge
+//!
+//! Python `logging` records into the Rust `tracing` output, and Rust
+//! `tracing` events into Python loggers, so one sink, format and rotation
+//! policy covers the whole process.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only converts records between the two logging systems
+//! - **D**: Builds on the core `log_bridge` layer and `emit`
+
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+use pyvectora_core::log_bridge::{self, ForwardingLayer, LogRecord};
+use pyvectora_core::log_context::LogContext;
+use std::sync::{Arc, OnceLock};
+use tracing::Level;
+
+/// Layer installed by `init_tracing`, switched by `forward_tracing_to_logging`
+pub fn forwarding_layer() -> &'static ForwardingLayer {
+    static LAYER: OnceLock<ForwardingLayer> = OnceLock::new();
+    LAYER.get_or_init(ForwardingLayer::new)
+}
+
+/// Emit a Python log record as a Rust `tracing` event (target `python`)
+///
+/// `levelno` is a `logging` level; the request fields are attached to the
+/// event like the Rust core's own `request` span fields.
+#[pyfunction]
+#[pyo3(signature = (levelno, logger, message, request_id=None, route=None, tenant=None, user=None))]
+pub fn log_to_tracing(
+    levelno: u32,
+    logger: &str,
+    message: &str,
+    request_id: Option<String>,
+    route: Option<String>,
+    tenant: Option<String>,
+    user: Option<String>,
+) {
+    crate::init_tracing();
+    let context = LogContext {
+        request_id: request_id.unwrap_or_default(),
+        route,
+        tenant,
+        user,
+    };
+    log_bridge::emit(tracing_level(levelno), logger, message, &context);
+}
+
+/// Send Rust `tracing` events to Python loggers instead of the JSON output
+///
+/// Events go to the logger named after their target
+/// (`pyvectora_core.server`), with `request_id`, `route`, `tenant` and
+/// `user` set on the record. Records are handled under the GIL on the
+/// thread that emitted the event.
+#[pyfunction]
+pub fn forward_tracing_to_logging(enabled: bool) {
+    crate::init_tracing();
+    let sink: Option<log_bridge::LogSink> = enabled.then(|| {
+        Arc::new(|record: &LogRecord| {
+            Python::with_gil(|py| {
+                if let Err(err) = handle_record(py, record) {
+                    err.print(py);
+                }
+            });
+        }) as log_bridge::LogSink
+    });
+    forwarding_layer().set_sink(sink);
+}
+
+fn handle_record(py: Python<'_>, record: &LogRecord) -> PyResult<()> {
+    let levelno = python_level(record.level);
+    let logger = py
+        .import("logging")?
+        .call_method1("getLogger", (record.logger.as_str(),))?;
+    if !logger.call_method1("isEnabledFor", (levelno,))?.is_true()? {
+        return Ok(());
+    }
+    let py_record = logger.call_method1(
+        "makeRecord",
+        (
+            record.logger.as_str(),
+            levelno,
+            record.file.unwrap_or("(unknown file)"),
+            record.line.unwrap_or(0),
+            record.message.as_str(),
+            PyTuple::empty(py),
+            py.None(),
+        ),
+    )?;
+    let context = &record.context;
+    let request_id = Some(context.request_id.as_str()).filter(|id| !id.is_empty());
+    py_record.setattr("request_id", request_id)?;
+    py_record.setattr("route", context.route.as_deref())?;
+    py_record.setattr("tenant", context.tenant.as_deref())?;
+    py_record.setattr("user", context.user.as_deref())?;
+    logger.call_method1("handle", (py_record,))?;
+    Ok(())
+}
+
+const fn tracing_level(levelno: u32) -> Level {
+    match levelno {
+        40.. => Level::ERROR,
+        30..=39 => Level::WARN,
+        20..=29 => Level::INFO,
+        10..=19 => Level::DEBUG,
+        _ => Level::TRACE,
+    }
+}
+
+const fn python_level(level: Level) -> u32 {
+    match level {
+        Level::ERROR => 40,
+        Level::WARN => 30,
+        Level::INFO => 20,
+        Level::DEBUG => 10,
+        Level::TRACE => 5,
+    }
+}
+
+/// Register logging bridge functions with Python module
+pub fn register_logging_bridge_functions(m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(log_to_tracing, m)?)?;
+    m.add_function(wrap_pyfunction!(forward_tracing_to_logging, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,53 @@
+//! # NDJSON Streaming Bindings
+//!
+//! Streams Python iterables as newline-delimited JSON, one document per
+//! yielded item, serialized in Rust and flushed per item.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only maps yielded Python values onto NDJSON lines
+//! - **D**: Iteration and back-pressure are delegated to `spawn_chunk_producer`
+
+use pyo3::prelude::*;
+use pyvectora_core::server::{Bytes, PyResponse as RustResponse};
+
+use crate::{convert_py_error, pyobject_to_json, spawn_chunk_producer, StreamSource};
+
+/// NDJSON content type
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Whether a handler result should be streamed as NDJSON (`_is_ndjson` flag)
+pub fn is_ndjson_response(py: Python<'_>, result: &PyObject) -> bool {
+    result
+        .as_ref(py)
+        .getattr("_is_ndjson")
+        .and_then(PyAny::extract::<bool>)
+        .unwrap_or(false)
+}
+
+/// Stream a Python NDJSON response, one line per yielded item
+pub fn stream_ndjson_response(
+    result: &PyObject,
+    locals: &pyo3_asyncio::TaskLocals,
+) -> RustResponse {
+    let source = match Python::with_gil(|py| StreamSource::extract(result.as_ref(py), CONTENT_TYPE))
+    {
+        Ok(v) => v,
+        Err(err) => return convert_py_error(err),
+    };
+
+    let rx = spawn_chunk_producer(source.iter, source.is_async, locals, py_item_to_line);
+    let mut response = RustResponse::streaming(rx, source.content_type)
+        .with_status(source.status)
+        .with_header("X-Accel-Buffering", "no");
+    response.headers.extend(source.headers);
+    response
+}
+
+fn py_item_to_line(item: &PyAny) -> PyResult<Bytes> {
+    let value = pyobject_to_json(item)?;
+    let mut line = serde_json::to_vec(&value)
+        .map_err(|e| PyErr::new::<pyo3::exceptions::PyValueError, _>(e.to_string()))?;
+    line.push(b'\n');
+    Ok(Bytes::from(line))
+}
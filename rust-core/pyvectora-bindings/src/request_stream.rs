@@ -0,0 +1,54 @@
+//! # Request Body Stream Bindings
+//!
+//! Backs `Request.stream()`: an async iterator yielding the request body as
+//! `bytes` chunks, read from the connection as the handler awaits them on
+//! routes registered with `stream=True`.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only adapts `BodyStream` to the async iterator protocol
+//! - **D**: Installed into the core through `request::set_stream_adapter`
+
+use pyo3::exceptions::{PyConnectionError, PyStopAsyncIteration};
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyvectora_core::body_stream::BodyStream;
+use pyvectora_core::request;
+
+/// Async iterator over request body chunks
+///
+/// Raises `ConnectionError` if the client disconnects mid-body.
+#[pyclass(name = "RequestStream")]
+pub struct PyRequestStream {
+    stream: BodyStream,
+}
+
+#[pymethods]
+impl PyRequestStream {
+    fn __aiter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __anext__<'p>(&self, py: Python<'p>) -> PyResult<Option<&'p PyAny>> {
+        let stream = self.stream.clone();
+        let next = pyo3_asyncio::tokio::future_into_py(py, async move {
+            match stream.next_chunk().await {
+                Ok(Some(chunk)) => Ok(Python::with_gil(|py| PyBytes::new(py, &chunk).into_py(py))),
+                Ok(None) => Err(PyStopAsyncIteration::new_err(())),
+                Err(err) => Err(PyConnectionError::new_err(err.to_string())),
+            }
+        })?;
+        Ok(Some(next))
+    }
+}
+
+fn stream_to_py(py: Python<'_>, stream: BodyStream) -> PyResult<PyObject> {
+    Ok(Py::new(py, PyRequestStream { stream })?.into_py(py))
+}
+
+/// Register the request stream class and back `Request.stream()` with it
+pub fn register_request_stream_classes(m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyRequestStream>()?;
+    request::set_stream_adapter(stream_to_py);
+    Ok(())
+}
@@ -0,0 +1,126 @@
+//! # Retry Helper Python Bindings
+//!
+//! Awaitable `retry()` wrapper driven by the core `RetryPolicy`.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only drives the retry loop, delays come from `RetryPolicy`
+//! - **O**: Works with sync callables and coroutine functions alike
+//! - **D**: Honors cancellation and deadline of the request context
+
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyTuple, PyType};
+use pyvectora_core::retry::{Backoff, RetryPolicy};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::context::PyExecutionContext;
+
+type AttemptFuture = Pin<Box<dyn Future<Output = PyResult<PyObject>> + Send>>;
+
+/// Call `func` until it succeeds, with backoff between attempts
+///
+/// # Example (Python)
+///
+/// ```python
+/// data = await retry(lambda: fetch_upstream(), attempts=5, retry_on=[ConnectionError])
+/// ```
+///
+/// Only exceptions matching `retry_on` (default: `Exception`) are retried;
+/// the last error is re-raised once attempts run out, the request is
+/// cancelled, or the next delay would cross the context deadline.
+#[pyfunction]
+#[pyo3(signature = (func, attempts=3, backoff="exponential", retry_on=None, base_delay=0.1, context=None))]
+pub fn retry<'p>(
+    py: Python<'p>,
+    func: PyObject,
+    attempts: u32,
+    backoff: &str,
+    retry_on: Option<&PyAny>,
+    base_delay: f64,
+    context: Option<&PyExecutionContext>,
+) -> PyResult<&'p PyAny> {
+    let backoff: Backoff = backoff
+        .parse()
+        .map_err(|e: pyvectora_core::Error| PyValueError::new_err(e.to_string()))?;
+    let base_delay = Duration::try_from_secs_f64(base_delay)
+        .map_err(|e| PyValueError::new_err(e.to_string()))?;
+    let defaults = RetryPolicy::default();
+    let mut policy = defaults
+        .clone()
+        .with_attempts(attempts)
+        .with_backoff(backoff)
+        .with_delays(base_delay, defaults.max_delay);
+    let token = context.map(|ctx| {
+        policy.deadline = ctx.deadline;
+        ctx.token.clone()
+    });
+    let retry_on = retryable_exceptions(py, retry_on)?;
+    let locals = pyo3_asyncio::tokio::get_current_locals(py)?;
+
+    pyo3_asyncio::tokio::future_into_py(py, async move {
+        let mut attempt = 1;
+        loop {
+            let outcome = match Python::with_gil(|py| start_attempt(py, &func, &locals)) {
+                Ok(fut) => fut.await,
+                Err(e) => Err(e),
+            };
+            let err = match outcome {
+                Ok(value) => return Ok(value),
+                Err(e) => e,
+            };
+
+            let retryable = Python::with_gil(|py| err.matches(py, retry_on.as_ref(py)));
+            let Some(delay) = policy.next_delay(attempt).filter(|_| retryable) else {
+                return Err(err);
+            };
+            match &token {
+                Some(token) => tokio::select! {
+                    () = token.cancelled() => return Err(err),
+                    () = tokio::time::sleep(delay) => {}
+                },
+                None => tokio::time::sleep(delay).await,
+            }
+            attempt += 1;
+        }
+    })
+}
+
+/// Invoke `func` once, awaiting the result if it is awaitable
+fn start_attempt(
+    py: Python<'_>,
+    func: &PyObject,
+    locals: &pyo3_asyncio::TaskLocals,
+) -> PyResult<AttemptFuture> {
+    let result = func.call0(py)?;
+    let inspect = py.import("inspect")?;
+    if inspect
+        .call_method1("isawaitable", (result.as_ref(py),))?
+        .is_true()?
+    {
+        let fut = pyo3_asyncio::into_future_with_locals(locals, result.as_ref(py))?;
+        Ok(Box::pin(fut))
+    } else {
+        Ok(Box::pin(std::future::ready(Ok(result))))
+    }
+}
+
+/// Normalize `retry_on` (a type or an iterable of types) into a tuple
+fn retryable_exceptions(py: Python<'_>, retry_on: Option<&PyAny>) -> PyResult<PyObject> {
+    match retry_on {
+        None => Ok(py.get_type::<PyException>().into_py(py)),
+        Some(any) if any.is_instance_of::<PyType>() => Ok(any.into_py(py)),
+        Some(any) => {
+            let types = any.iter()?.collect::<PyResult<Vec<_>>>()?;
+            Ok(PyTuple::new(py, types).into_py(py))
+        }
+    }
+}
+
+/// Register retry helpers with Python module
+pub fn register_retry_functions(m: &PyModule) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(retry, m)?)?;
+    Ok(())
+}
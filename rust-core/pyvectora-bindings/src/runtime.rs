@@ -0,0 +1,264 @@
+//! # Runtime Python Bindings
+//!
+//! Tokio runtime tuning for Python: the process-wide runtime used by apps
+//! without their own and by database pools (`configure_runtime`), and
+//! app runtimes that are owned (`App(runtime=RuntimeConfig(...))`) or
+//! explicitly shared between apps (`App(runtime=Runtime(...))`).
+//! Without `configure_runtime`, the process-wide runtime reads the
+//! `PYVECTORA_WORKER_THREADS`, `PYVECTORA_MAX_BLOCKING_THREADS`,
+//! `PYVECTORA_THREAD_NAME` and `PYVECTORA_THREAD_STACK_SIZE` variables.
+//!
+//! Apps on separate runtimes do not share worker threads, so several
+//! independent apps or test servers can run in one interpreter.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Owns runtime creation for the bindings
+//! - **D**: Settings come from the core `RuntimeConfig`
+
+use crate::error::BindingsError;
+use pyo3::prelude::*;
+use pyvectora_core::RuntimeConfig;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use tokio::runtime::{Handle, Runtime};
+
+/// Settings for the process-wide runtime (set before first use)
+static GLOBAL_CONFIG: OnceLock<RuntimeConfig> = OnceLock::new();
+
+/// Global Tokio runtime for serving, test client and database operations
+///
+/// Lazily initialized on first use from `GLOBAL_CONFIG`, else the
+/// `PYVECTORA_*` environment variables.
+static GLOBAL_RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Held while the global runtime is built, so only one thread builds it
+static RUNTIME_INIT: Mutex<()> = Mutex::new(());
+
+static INIT_ASYNCIO: OnceLock<()> = OnceLock::new();
+
+/// Get or create the global Tokio runtime
+///
+/// Thread-safe, lock-free after first initialization. Raises
+/// `ConfigurationError` for an invalid `PYVECTORA_*` runtime variable.
+pub fn get_runtime() -> PyResult<&'static Runtime> {
+    if let Some(runtime) = GLOBAL_RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let _init = RUNTIME_INIT.lock().unwrap_or_else(PoisonError::into_inner);
+    // Another thread may have built it while we waited
+    if let Some(runtime) = GLOBAL_RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let config = match GLOBAL_CONFIG.get() {
+        Some(config) => config,
+        None => {
+            let config = RuntimeConfig::from_env()
+                .map_err(|e| BindingsError::Configuration(e.to_string()))?;
+            GLOBAL_CONFIG.get_or_init(|| config)
+        }
+    };
+    let runtime = config
+        .build()
+        .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+    Ok(GLOBAL_RUNTIME.get_or_init(|| runtime))
+}
+
+/// Make the asyncio bridge run futures on the global runtime
+pub fn init_asyncio_once(_py: Python<'_>) -> PyResult<()> {
+    let runtime = get_runtime()?;
+    INIT_ASYNCIO.get_or_init(|| {
+        let _ = pyo3_asyncio::tokio::init_with_runtime(runtime);
+    });
+    Ok(())
+}
+
+/// Runtime an app serves and runs test requests on
+#[derive(Clone, Default)]
+pub enum AppRuntime {
+    /// The process-wide runtime
+    #[default]
+    Global,
+    /// A runtime of its own, shared by clones
+    Dedicated(Arc<DedicatedRuntime>),
+}
+
+/// Runtime started on first use from its settings
+pub struct DedicatedRuntime {
+    config: RuntimeConfig,
+    runtime: OnceLock<Runtime>,
+}
+
+impl AppRuntime {
+    /// A dedicated runtime built from `config` when first used
+    pub fn dedicated(config: RuntimeConfig) -> Self {
+        Self::Dedicated(Arc::new(DedicatedRuntime {
+            config,
+            runtime: OnceLock::new(),
+        }))
+    }
+
+    /// Accept `RuntimeConfig` (owned runtime) or `Runtime` (shared runtime)
+    pub fn extract(value: Option<&PyAny>) -> PyResult<Self> {
+        let Some(value) = value.filter(|v| !v.is_none()) else {
+            return Ok(Self::Global);
+        };
+        if let Ok(shared) = value.extract::<PyRef<PyRuntime>>() {
+            return Ok(shared.inner.clone());
+        }
+        if let Ok(config) = value.extract::<PyRef<PyRuntimeConfig>>() {
+            return Ok(Self::dedicated(config.inner.clone()));
+        }
+        Err(pyo3::exceptions::PyTypeError::new_err(
+            "runtime must be a RuntimeConfig or a Runtime",
+        ))
+    }
+
+    /// Handle of a dedicated runtime, starting it if needed
+    ///
+    /// `None` for the global runtime.
+    pub fn dedicated_handle(&self) -> PyResult<Option<Handle>> {
+        let Self::Dedicated(dedicated) = self else {
+            return Ok(None);
+        };
+        if let Some(runtime) = dedicated.runtime.get() {
+            return Ok(Some(runtime.handle().clone()));
+        }
+        let runtime = dedicated
+            .config
+            .build()
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e.to_string()))?;
+        Ok(Some(
+            dedicated.runtime.get_or_init(|| runtime).handle().clone(),
+        ))
+    }
+
+    /// Handle of the runtime, starting it if needed
+    pub fn handle(&self) -> PyResult<Handle> {
+        match self.dedicated_handle()? {
+            Some(handle) => Ok(handle),
+            None => Ok(get_runtime()?.handle().clone()),
+        }
+    }
+}
+
+/// A Tokio runtime that several apps can share
+///
+/// # Example (Python)
+///
+/// ```python
+/// shared = Runtime(RuntimeConfig(worker_threads=2))
+/// api = App(port=8000, runtime=shared)
+/// admin = App(port=8001, runtime=shared)
+/// ```
+#[pyclass(name = "Runtime")]
+#[derive(Clone)]
+pub struct PyRuntime {
+    inner: AppRuntime,
+}
+
+#[pymethods]
+impl PyRuntime {
+    #[new]
+    #[pyo3(signature = (config=None))]
+    fn new(config: Option<PyRuntimeConfig>) -> Self {
+        Self {
+            inner: AppRuntime::dedicated(config.unwrap_or_default().inner),
+        }
+    }
+}
+
+/// Tokio runtime settings
+///
+/// # Example (Python)
+///
+/// ```python
+/// config = RuntimeConfig(worker_threads=4, thread_name="api")
+/// configure_runtime(config)   # process-wide, before serving
+/// app = App(runtime=config)   # or a runtime owned by this app
+/// ```
+#[pyclass(name = "RuntimeConfig")]
+#[derive(Clone, Default)]
+pub struct PyRuntimeConfig {
+    pub(crate) inner: RuntimeConfig,
+}
+
+#[pymethods]
+impl PyRuntimeConfig {
+    #[new]
+    #[pyo3(signature = (worker_threads=None, max_blocking_threads=None, thread_name=None, thread_stack_size=None))]
+    fn new(
+        worker_threads: Option<usize>,
+        max_blocking_threads: Option<usize>,
+        thread_name: Option<String>,
+        thread_stack_size: Option<usize>,
+    ) -> Self {
+        let mut inner = RuntimeConfig::new();
+        if let Some(threads) = worker_threads {
+            inner = inner.with_worker_threads(threads);
+        }
+        if let Some(threads) = max_blocking_threads {
+            inner = inner.with_max_blocking_threads(threads);
+        }
+        if let Some(name) = thread_name {
+            inner = inner.with_thread_name(name);
+        }
+        if let Some(bytes) = thread_stack_size {
+            inner = inner.with_thread_stack_size(bytes);
+        }
+        Self { inner }
+    }
+
+    #[getter]
+    const fn worker_threads(&self) -> Option<usize> {
+        self.inner.worker_threads
+    }
+
+    #[getter]
+    const fn max_blocking_threads(&self) -> Option<usize> {
+        self.inner.max_blocking_threads
+    }
+
+    #[getter]
+    fn thread_name(&self) -> Option<String> {
+        self.inner.thread_name.clone()
+    }
+
+    #[getter]
+    const fn thread_stack_size(&self) -> Option<usize> {
+        self.inner.thread_stack_size
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "RuntimeConfig(worker_threads={:?}, max_blocking_threads={:?}, thread_name={:?}, thread_stack_size={:?})",
+            self.inner.worker_threads,
+            self.inner.max_blocking_threads,
+            self.inner.thread_name,
+            self.inner.thread_stack_size,
+        )
+    }
+}
+
+/// Configure the process-wide runtime
+///
+/// Must be called before anything starts it (serving, the test client,
+/// database connections); raises `ConfigurationError` afterwards.
+/// Overrides the `PYVECTORA_*` runtime environment variables.
+#[pyfunction]
+fn configure_runtime(config: PyRuntimeConfig) -> PyResult<()> {
+    if GLOBAL_RUNTIME.get().is_some() || GLOBAL_CONFIG.set(config.inner).is_err() {
+        return Err(BindingsError::Configuration(
+            "the runtime is already configured or running".to_string(),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Register runtime classes and functions with Python module
+pub fn register_runtime_classes(m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyRuntimeConfig>()?;
+    m.add_class::<PyRuntime>()?;
+    m.add_function(wrap_pyfunction!(configure_runtime, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! # Response Serializer Registry
+//!
+//! Maps Python types to functions that turn handler return values into
+//! something the response converter understands (`Response`, `str`,
+//! `dict` or `list`), so domain objects don't need wrapping in every handler.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only selects and applies serializers
+//! - **O**: New types are supported by registration, not by converter changes
+//!
+//! ## Lookup
+//!
+//! The returned object's MRO is walked from most to least specific, so a
+//! serializer registered for a subclass wins over one for its base class.
+
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::PyType;
+
+/// Registered `type -> serializer` pairs
+#[derive(Clone, Default)]
+pub struct SerializerRegistry {
+    entries: Vec<(Py<PyType>, PyObject)>,
+}
+
+impl SerializerRegistry {
+    /// Register (or replace) the serializer for `ty`
+    pub fn register(&mut self, ty: &PyAny, serializer: PyObject) -> PyResult<()> {
+        let ty = ty
+            .downcast::<PyType>()
+            .map_err(|_| PyTypeError::new_err("register_serializer() expects a type"))?;
+        if !serializer.as_ref(ty.py()).is_callable() {
+            return Err(PyTypeError::new_err("serializer must be callable"));
+        }
+        self.entries
+            .retain(|(registered, _)| !registered.as_ref(ty.py()).is(ty));
+        self.entries.push((ty.into(), serializer));
+        Ok(())
+    }
+
+    /// Serialize `value` if a serializer matches its type, else return it unchanged
+    pub fn apply(&self, py: Python<'_>, value: PyObject) -> PyResult<PyObject> {
+        if self.entries.is_empty() {
+            return Ok(value);
+        }
+        let mro = value.as_ref(py).get_type().getattr("__mro__")?;
+        for cls in mro.iter()? {
+            let cls = cls?;
+            if let Some((_, serializer)) = self
+                .entries
+                .iter()
+                .find(|(registered, _)| registered.as_ref(py).is(cls))
+            {
+                return serializer.call1(py, (value,));
+            }
+        }
+        Ok(value)
+    }
+}
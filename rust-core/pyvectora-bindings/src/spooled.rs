@@ -0,0 +1,93 @@
+//! # Spooled Response Bindings
+//!
+//! Sends `SpooledResponse` bodies: the in-memory buffer when the body
+//! stayed below its threshold, otherwise the temp file the handler wrote,
+//! streamed from disk (answering `Range` requests) and removed once sent.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only hands spooled bodies over to the core file responses
+//! - **D**: Reading, ranges and cleanup live in `file_response`
+
+use pyo3::exceptions::PyOSError;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use pyvectora_core::file_response::{bytes_response, file_response};
+use pyvectora_core::server::{Bytes, PyRequest as RustRequest, PyResponse as RustResponse};
+use std::collections::HashMap;
+
+use crate::convert_py_error;
+
+/// Whether a handler result is a spooled response (`_is_spooled` flag)
+pub fn is_spooled_response(py: Python<'_>, result: &PyObject) -> bool {
+    result
+        .as_ref(py)
+        .getattr("_is_spooled")
+        .and_then(PyAny::extract::<bool>)
+        .unwrap_or(false)
+}
+
+/// Send a Python spooled response, from memory or from its temp file
+///
+/// `Range` is only honoured for `200` responses to `GET` requests.
+pub async fn send_spooled_response(result: &PyObject, req: &RustRequest) -> RustResponse {
+    let spooled = match Python::with_gil(|py| Spooled::extract(result.as_ref(py))) {
+        Ok(v) => v,
+        Err(err) => return convert_py_error(err),
+    };
+    let range = if spooled.status == 200 {
+        req.header("range")
+    } else {
+        None
+    };
+
+    let mut response = match spooled.path {
+        Some(path) => {
+            match file_response(path, &spooled.content_type, req.method, range, true).await {
+                Ok(response) => response,
+                Err(err) => return convert_py_error(PyOSError::new_err(err.to_string())),
+            }
+        }
+        None => bytes_response(spooled.body, &spooled.content_type, req.method, range),
+    };
+    if range.is_none() {
+        response.status = spooled.status;
+    }
+    response.headers.extend(spooled.headers);
+    response
+}
+
+/// Status, headers and body location of a Python spooled response
+struct Spooled {
+    status: u16,
+    content_type: String,
+    headers: HashMap<String, String>,
+    path: Option<String>,
+    body: Bytes,
+}
+
+impl Spooled {
+    fn extract(resp: &PyAny) -> PyResult<Self> {
+        let status = resp
+            .getattr("status")
+            .and_then(|v| v.extract::<u16>())
+            .unwrap_or(200);
+        let content_type = resp
+            .getattr("content_type")
+            .and_then(|v| v.extract::<String>())
+            .unwrap_or_else(|_| "application/octet-stream".to_string());
+        let headers = resp
+            .getattr("headers")
+            .and_then(|h| h.extract::<HashMap<String, String>>())
+            .unwrap_or_default();
+        // Takes ownership of the temp file: it is no longer removed by Python
+        let (path, body): (Option<String>, &PyBytes) = resp.call_method0("_take")?.extract()?;
+        Ok(Self {
+            status,
+            content_type,
+            headers,
+            path,
+            body: Bytes::copy_from_slice(body.as_bytes()),
+        })
+    }
+}
@@ -0,0 +1,177 @@
+//! # Server-Sent Events Python Bindings
+//!
+//! Native `SSEResponse` streamed through the core `text/event-stream` support.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only maps Python event producers onto core `SseEvent`s
+//! - **O**: Accepts plain strings or dicts with `data`/`event`/`id`/`retry`
+//! - **D**: Framing and keep-alive are delegated to `pyvectora_core::sse`
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyString};
+use pyvectora_core::server::PyResponse as RustResponse;
+use pyvectora_core::sse::{SseEvent, CONTENT_TYPE, DEFAULT_KEEP_ALIVE};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::{convert_py_error, json_dumps, spawn_chunk_producer, StreamSource};
+
+/// Python-exposed Server-Sent Events response
+///
+/// # Example (Python)
+///
+/// ```python
+/// async def ticks():
+///     for i in range(3):
+///         yield {"data": str(i), "id": str(i)}
+///         await asyncio.sleep(1)
+///
+/// return SSEResponse(ticks(), event_type="tick", retry=5000)
+/// ```
+#[pyclass(name = "SSEResponse")]
+pub struct PySseResponse {
+    #[pyo3(get)]
+    content: PyObject,
+    #[pyo3(get, set)]
+    status: u16,
+    #[pyo3(get)]
+    content_type: String,
+    #[pyo3(get, set)]
+    headers: HashMap<String, String>,
+    #[pyo3(get, set)]
+    event_type: Option<String>,
+    #[pyo3(get, set)]
+    retry: Option<u64>,
+    #[pyo3(get, set)]
+    keep_alive: Option<f64>,
+}
+
+#[pymethods]
+impl PySseResponse {
+    #[new]
+    #[pyo3(signature = (content, status=200, event_type=None, retry=None, keep_alive=Some(DEFAULT_KEEP_ALIVE.as_secs_f64()), headers=None))]
+    fn new(
+        content: PyObject,
+        status: u16,
+        event_type: Option<String>,
+        retry: Option<u64>,
+        keep_alive: Option<f64>,
+        headers: Option<HashMap<String, String>>,
+    ) -> Self {
+        Self {
+            content,
+            status,
+            content_type: CONTENT_TYPE.to_string(),
+            headers: headers.unwrap_or_default(),
+            event_type,
+            retry,
+            keep_alive,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SSEResponse(status={}, event_type={:?})",
+            self.status, self.event_type
+        )
+    }
+}
+
+/// Whether a handler result should be streamed as Server-Sent Events
+///
+/// Matches the native `SSEResponse` and Python objects flagged `_is_sse`
+/// (such as `EventSourceResponse`).
+pub fn is_sse_response(py: Python<'_>, result: &PyObject) -> bool {
+    let result = result.as_ref(py);
+    result.is_instance_of::<PySseResponse>()
+        || result
+            .getattr("_is_sse")
+            .and_then(PyAny::extract::<bool>)
+            .unwrap_or(false)
+}
+
+/// Stream a Python SSE response, one event per yielded item
+pub fn stream_sse_response(result: &PyObject, locals: &pyo3_asyncio::TaskLocals) -> RustResponse {
+    let extracted = Python::with_gil(|py| -> PyResult<_> {
+        let resp = result.as_ref(py);
+        let source = StreamSource::extract(resp, CONTENT_TYPE)?;
+        let event_type = optional_attr::<String>(resp, "event_type");
+        let retry = optional_attr::<u64>(resp, "retry");
+        let keep_alive = match resp.getattr("keep_alive") {
+            Ok(v) => v.extract::<Option<f64>>()?,
+            Err(_) => Some(DEFAULT_KEEP_ALIVE.as_secs_f64()),
+        };
+        Ok((source, event_type, retry, keep_alive))
+    });
+    let (source, event_type, retry, keep_alive) = match extracted {
+        Ok(v) => v,
+        Err(err) => return convert_py_error(err),
+    };
+    let keep_alive = keep_alive.and_then(|secs| Duration::try_from_secs_f64(secs).ok());
+
+    let retry_pending = AtomicBool::new(retry.is_some());
+    let events = spawn_chunk_producer(source.iter, source.is_async, locals, move |item| {
+        let mut event = py_item_to_event(item, event_type.as_deref())?;
+        if retry_pending.swap(false, Ordering::Relaxed) {
+            event.retry = event.retry.or(retry);
+        }
+        Ok(event)
+    });
+
+    let mut response = RustResponse::sse(events, keep_alive).with_status(source.status);
+    response.headers.extend(source.headers);
+    response
+}
+
+fn optional_attr<'a, T: FromPyObject<'a>>(obj: &'a PyAny, name: &str) -> Option<T> {
+    obj.getattr(name)
+        .ok()
+        .and_then(|v| v.extract::<Option<T>>().ok())
+        .flatten()
+}
+
+/// Convert a yielded item into an event
+///
+/// Strings become `data`; dicts may set `data`, `event`, `id` and `retry`.
+/// Non-string `data` values are JSON-encoded.
+fn py_item_to_event(item: &PyAny, default_event: Option<&str>) -> PyResult<SseEvent> {
+    let mut event = if let Ok(dict) = item.downcast::<PyDict>() {
+        let data = match dict.get_item("data")? {
+            Some(data) => py_data_to_string(data)?,
+            None => String::new(),
+        };
+        SseEvent {
+            data,
+            event: dict.get_item("event")?.map(PyAny::extract).transpose()?,
+            id: dict
+                .get_item("id")?
+                .map(|v| v.str().map(ToString::to_string))
+                .transpose()?,
+            retry: dict.get_item("retry")?.map(PyAny::extract).transpose()?,
+        }
+    } else {
+        SseEvent::new(py_data_to_string(item)?)
+    };
+    if event.event.is_none() {
+        event.event = default_event.map(str::to_string);
+    }
+    Ok(event)
+}
+
+fn py_data_to_string(data: &PyAny) -> PyResult<String> {
+    if let Ok(s) = data.downcast::<PyString>() {
+        return Ok(s.to_str()?.to_string());
+    }
+    if let Ok(b) = data.downcast::<PyBytes>() {
+        return Ok(String::from_utf8_lossy(b.as_bytes()).into_owned());
+    }
+    json_dumps(data)
+}
+
+/// Register SSE classes with Python module
+pub fn register_sse_classes(m: &PyModule) -> PyResult<()> {
+    m.add_class::<PySseResponse>()?;
+    Ok(())
+}
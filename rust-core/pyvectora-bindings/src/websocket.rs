@@ -0,0 +1,166 @@
+//! # WebSocket Python Bindings
+//!
+//! Exposes upgraded WebSocket connections to Python handlers.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only handles WebSocket Python bindings
+//! - **I**: Minimal async surface: `send`, `receive`, `close`
+//! - **D**: Core server depends on the `WsHandler` abstraction built here
+//!
+//! ## Concurrency Notes
+//!
+//! - The socket is split so a handler can `receive` and `send` from
+//!   different tasks without blocking each other
+//! - GIL released while awaiting socket I/O
+
+use futures_util::stream::{SplitSink, SplitStream};
+use futures_util::{SinkExt, StreamExt};
+use pyo3::exceptions::{PyConnectionError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyString};
+use pyvectora_core::websocket::{CloseCode, CloseFrame, Message, WebSocket, WsError, WsHandler};
+use pyvectora_core::PyRequest;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Python-exposed WebSocket connection
+///
+/// # Example (Python)
+///
+/// ```python
+/// @app.websocket("/ws")
+/// async def echo(ws):
+///     while (message := await ws.receive()) is not None:
+///         await ws.send(message)
+/// ```
+#[pyclass(name = "WebSocket")]
+pub struct PyWebSocket {
+    sink: Arc<Mutex<SplitSink<WebSocket, Message>>>,
+    stream: Arc<Mutex<SplitStream<WebSocket>>>,
+    request: PyRequest,
+}
+
+#[pymethods]
+impl PyWebSocket {
+    /// The upgrade request (headers, path and path params)
+    #[getter]
+    fn request(&self) -> PyRequest {
+        self.request.clone()
+    }
+
+    /// Request path of the upgrade request
+    #[getter]
+    fn path(&self) -> String {
+        self.request.path.clone()
+    }
+
+    /// Send a message (`str` as text frame, `bytes` as binary frame)
+    fn send<'p>(&self, py: Python<'p>, data: &PyAny) -> PyResult<&'p PyAny> {
+        let message = if let Ok(text) = data.downcast::<PyString>() {
+            Message::Text(text.to_str()?.to_owned())
+        } else if let Ok(bytes) = data.downcast::<PyBytes>() {
+            Message::Binary(bytes.as_bytes().to_vec())
+        } else {
+            return Err(PyTypeError::new_err(
+                "WebSocket.send() expects str or bytes",
+            ));
+        };
+        let sink = self.sink.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            sink.lock()
+                .await
+                .send(message)
+                .await
+                .map_err(|e| to_py_err(&e))?;
+            Ok(())
+        })
+    }
+
+    /// Receive the next message
+    ///
+    /// Returns `str` for text frames, `bytes` for binary frames and
+    /// `None` once the peer closes the connection.
+    fn receive<'p>(&self, py: Python<'p>) -> PyResult<&'p PyAny> {
+        let stream = self.stream.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let mut stream = stream.lock().await;
+            loop {
+                match stream.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        return Ok(Python::with_gil(|py| Some(text.into_py(py))));
+                    }
+                    Some(Ok(Message::Binary(data))) => {
+                        return Ok(Python::with_gil(|py| {
+                            Some(PyBytes::new(py, &data).into_py(py))
+                        }));
+                    }
+                    Some(Ok(Message::Close(_))) | None => return Ok(None),
+                    Some(Ok(_)) => {}
+                    Some(Err(e)) => return Err(to_py_err(&e)),
+                }
+            }
+        })
+    }
+
+    /// Close the connection with an optional status code and reason
+    #[pyo3(signature = (code=1000, reason=String::new()))]
+    fn close<'p>(&self, py: Python<'p>, code: u16, reason: String) -> PyResult<&'p PyAny> {
+        let sink = self.sink.clone();
+
+        pyo3_asyncio::tokio::future_into_py(py, async move {
+            let frame = CloseFrame {
+                code: CloseCode::from(code),
+                reason: reason.into(),
+            };
+            match sink.lock().await.send(Message::Close(Some(frame))).await {
+                Ok(()) | Err(WsError::ConnectionClosed | WsError::AlreadyClosed) => Ok(()),
+                Err(e) => Err(to_py_err(&e)),
+            }
+        })
+    }
+}
+
+fn to_py_err(err: &WsError) -> PyErr {
+    PyConnectionError::new_err(err.to_string())
+}
+
+/// Adapt an async Python WebSocket handler to the core `WsHandler`
+///
+/// Errors raised by the handler are printed; the connection is dropped
+/// when the handler returns.
+pub fn create_ws_handler_adapter(handler: PyObject, locals: pyo3_asyncio::TaskLocals) -> WsHandler {
+    Arc::new(move |request, socket| {
+        let handler = handler.clone();
+        let locals = locals.clone();
+
+        Box::pin(async move {
+            let (sink, stream) = socket.split();
+            let ws = PyWebSocket {
+                sink: Arc::new(Mutex::new(sink)),
+                stream: Arc::new(Mutex::new(stream)),
+                request,
+            };
+
+            let future = Python::with_gil(|py| {
+                let coro = handler.call1(py, (ws,))?;
+                pyo3_asyncio::into_future_with_locals(&locals, coro.as_ref(py))
+            });
+            let result = match future {
+                Ok(future) => future.await.map(drop),
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                Python::with_gil(|py| e.print(py));
+            }
+        })
+    })
+}
+
+/// Register WebSocket classes with Python module
+pub fn register_websocket_classes(m: &PyModule) -> PyResult<()> {
+    m.add_class::<PyWebSocket>()?;
+    Ok(())
+}
@@ -0,0 +1,253 @@
+//! # WSGI Python Bindings
+//!
+//! Mounts a legacy WSGI application (Flask, Django, Bottle) under a path
+//! prefix, so its endpoints keep working next to native routes while an app
+//! is migrated. WSGI apps are synchronous and may block, so each request
+//! runs on Tokio's blocking thread pool instead of a worker thread.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only translates between requests/responses and the WSGI protocol
+//! - **D**: The mounted app is an opaque WSGI callable
+
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyTuple};
+use pyvectora_core::router::Method;
+use pyvectora_core::server::{
+    Bytes, Handler, PyRequest as RustRequest, PyResponse as RustResponse,
+};
+use std::sync::Arc;
+
+use crate::convert_py_error;
+
+/// Methods a mounted WSGI app receives
+pub const WSGI_METHODS: [Method; 7] = [
+    Method::Get,
+    Method::Post,
+    Method::Put,
+    Method::Delete,
+    Method::Patch,
+    Method::Head,
+    Method::Options,
+];
+
+/// A WSGI application mounted under `prefix`
+pub struct WsgiMount {
+    /// Path prefix without trailing slash (`""` for the root)
+    pub prefix: String,
+    /// The WSGI callable
+    pub app: PyObject,
+}
+
+impl WsgiMount {
+    /// Mount `app` under `prefix` (e.g. `/legacy`)
+    pub fn new(prefix: &str, app: PyObject) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            app,
+        }
+    }
+
+    /// Route patterns covering the prefix itself and everything below it
+    pub fn patterns(&self) -> [String; 2] {
+        let root = if self.prefix.is_empty() {
+            "/".to_string()
+        } else {
+            self.prefix.clone()
+        };
+        [root, format!("{}/{{*wsgi_path}}", self.prefix)]
+    }
+
+    /// Handler answering requests with the WSGI app
+    ///
+    /// `server_name` and `server_port` fill the matching environ keys.
+    pub fn handler(&self, py: Python<'_>, server_name: &str, server_port: u16) -> Handler {
+        let target = Arc::new(WsgiTarget {
+            app: self.app.clone_ref(py),
+            script_name: self.prefix.clone(),
+            server_name: server_name.to_string(),
+            server_port: server_port.to_string(),
+        });
+        Arc::new(move |req, _matched| {
+            let target = target.clone();
+            let req = req.clone();
+            Box::pin(async move {
+                tokio::task::spawn_blocking(move || {
+                    Python::with_gil(|py| target.call(py, &req)).unwrap_or_else(convert_py_error)
+                })
+                .await
+                .unwrap_or_else(|err| {
+                    RustResponse::text(format!("WSGI application panicked: {err}")).with_status(500)
+                })
+            })
+        })
+    }
+}
+
+/// What a WSGI request needs besides the request itself
+struct WsgiTarget {
+    app: PyObject,
+    script_name: String,
+    server_name: String,
+    server_port: String,
+}
+
+impl WsgiTarget {
+    /// Run the app for `req` and collect its response
+    fn call(&self, py: Python<'_>, req: &RustRequest) -> PyResult<RustResponse> {
+        let environ = self.environ(py, req)?;
+        let start_response = Py::new(py, StartResponse::default())?;
+        let result = self
+            .app
+            .call1(py, (environ, start_response.clone_ref(py)))?;
+
+        let mut body = Vec::new();
+        let collected = result.as_ref(py).iter().and_then(|chunks| {
+            for chunk in chunks {
+                body.extend_from_slice(chunk?.downcast::<PyBytes>()?.as_bytes());
+            }
+            Ok(())
+        });
+        if result.as_ref(py).hasattr("close")? {
+            result.call_method0(py, "close")?;
+        }
+        collected?;
+
+        let start = start_response.borrow(py);
+        let status = start
+            .status
+            .as_deref()
+            .ok_or_else(|| {
+                PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                    "WSGI application did not call start_response",
+                )
+            })?
+            .split_whitespace()
+            .next()
+            .and_then(|code| code.parse::<u16>().ok())
+            .unwrap_or(500);
+        let mut written = start.written.clone();
+        written.extend_from_slice(&body);
+
+        let response = RustResponse::binary(Bytes::from(written), "text/html; charset=utf-8")
+            .with_status(status);
+        Ok(with_headers(response, &start.headers))
+    }
+
+    /// The PEP 3333 environ for `req`
+    fn environ<'py>(&self, py: Python<'py>, req: &RustRequest) -> PyResult<&'py PyDict> {
+        let environ = PyDict::new(py);
+        let path_info = req
+            .path
+            .strip_prefix(&self.script_name)
+            .unwrap_or(&req.path);
+        environ.set_item("REQUEST_METHOD", req.method.to_string())?;
+        environ.set_item("SCRIPT_NAME", &self.script_name)?;
+        environ.set_item("PATH_INFO", path_info)?;
+        environ.set_item("QUERY_STRING", req.query_string().unwrap_or(""))?;
+        environ.set_item("SERVER_NAME", &self.server_name)?;
+        environ.set_item("SERVER_PORT", &self.server_port)?;
+        environ.set_item("SERVER_PROTOCOL", "HTTP/1.1")?;
+        if let Some(addr) = req.remote_addr {
+            environ.set_item("REMOTE_ADDR", addr.ip().to_string())?;
+            environ.set_item("REMOTE_PORT", addr.port().to_string())?;
+        }
+        for (name, value) in req.raw_header_list() {
+            let key = match name.as_str() {
+                "content-type" => "CONTENT_TYPE".to_string(),
+                "content-length" => "CONTENT_LENGTH".to_string(),
+                _ => format!("HTTP_{}", name.to_ascii_uppercase().replace('-', "_")),
+            };
+            // Repeated headers are joined with commas, as PEP 3333 expects
+            let value = match environ.get_item(&key)? {
+                Some(previous) => format!("{previous},{value}"),
+                None => value,
+            };
+            environ.set_item(key, value)?;
+        }
+
+        let body = PyBytes::new(py, req.body_bytes().unwrap_or_default());
+        environ.set_item(
+            "wsgi.input",
+            py.import("io")?.call_method1("BytesIO", (body,))?,
+        )?;
+        environ.set_item("wsgi.errors", py.import("sys")?.getattr("stderr")?)?;
+        environ.set_item("wsgi.version", PyTuple::new(py, [1, 0]))?;
+        environ.set_item("wsgi.url_scheme", req.scheme)?;
+        environ.set_item("wsgi.multithread", true)?;
+        environ.set_item("wsgi.multiprocess", false)?;
+        environ.set_item("wsgi.run_once", false)?;
+        Ok(environ)
+    }
+}
+
+/// `response` with the headers passed to `start_response`
+///
+/// A repeated name (e.g. several `Set-Cookie`) is appended, not overwritten.
+fn with_headers(mut response: RustResponse, headers: &[(String, String)]) -> RustResponse {
+    for (i, (name, value)) in headers.iter().enumerate() {
+        let repeated = headers[..i]
+            .iter()
+            .any(|(earlier, _)| earlier.eq_ignore_ascii_case(name));
+        if repeated {
+            response.append_header(name, value);
+        } else {
+            response.set_header(name, value);
+        }
+    }
+    response
+}
+
+/// The `start_response` callable handed to the WSGI app
+#[pyclass]
+#[derive(Default)]
+struct StartResponse {
+    status: Option<String>,
+    headers: Vec<(String, String)>,
+    /// Body written through the legacy `write()` callable
+    written: Vec<u8>,
+}
+
+#[pymethods]
+impl StartResponse {
+    #[pyo3(signature = (status, headers, exc_info=None))]
+    fn __call__(
+        slf: &PyCell<Self>,
+        status: String,
+        headers: Vec<(String, String)>,
+        exc_info: Option<&PyAny>,
+    ) -> PyResult<PyObject> {
+        let mut this = slf.borrow_mut();
+        if this.status.is_some() && exc_info.map_or(true, PyAny::is_none) {
+            return Err(PyErr::new::<pyo3::exceptions::PyRuntimeError, _>(
+                "start_response called twice without exc_info",
+            ));
+        }
+        this.status = Some(status);
+        this.headers = headers;
+        drop(this);
+        Ok(slf.getattr("write")?.into())
+    }
+
+    /// Legacy imperative body output (PEP 3333 `write`)
+    fn write(&mut self, data: &[u8]) {
+        self.written.extend_from_slice(data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_headers_are_kept() {
+        let headers = [
+            ("Set-Cookie".to_string(), "a=1".to_string()),
+            ("X-Legacy".to_string(), "yes".to_string()),
+            ("set-cookie".to_string(), "b=2".to_string()),
+        ];
+        let response = with_headers(RustResponse::text("ok"), &headers);
+        assert_eq!(response.header_values("Set-Cookie"), ["a=1", "b=2"]);
+        assert_eq!(response.header_values("X-Legacy"), ["yes"]);
+    }
+}
@@ -0,0 +1,64 @@
+//! Response pipeline benchmarks: handler result to collected response for
+//! the text, JSON and binary response paths.
+//!
+//! Run with `cargo bench -p pyvectora-core --bench responses`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pyvectora_core::router::Method;
+use pyvectora_core::server::{Bytes, Handler, PyResponse, Server};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const BODY_SIZES: [usize; 2] = [64, 16 * 1024];
+
+fn server_with(handler: Handler) -> Server {
+    let mut server = Server::new("");
+    server
+        .add_route(Method::Get, "/", handler, false)
+        .expect("route");
+    server
+}
+
+fn bench_responses(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().expect("runtime");
+    let mut group = c.benchmark_group("response");
+    for size in BODY_SIZES {
+        let text = "x".repeat(size);
+        let json = format!(r#"{{"data": "{}"}}"#, "x".repeat(size));
+        let binary = Bytes::from(vec![0xab; size]);
+        let servers = [
+            (
+                "text",
+                server_with(Arc::new(move |_req, _matched| {
+                    let text = text.clone();
+                    Box::pin(async move { PyResponse::text(text) })
+                })),
+            ),
+            (
+                "json",
+                server_with(Arc::new(move |_req, _matched| {
+                    let json = json.clone();
+                    Box::pin(async move { PyResponse::json(json) })
+                })),
+            ),
+            (
+                "binary",
+                server_with(Arc::new(move |_req, _matched| {
+                    let binary = binary.clone();
+                    Box::pin(async move { PyResponse::binary(binary, "application/octet-stream") })
+                })),
+            ),
+        ];
+        for (kind, server) in &servers {
+            group.bench_with_input(BenchmarkId::new(*kind, size), server, |b, server| {
+                b.to_async(&runtime).iter(|| {
+                    server.test_request(Method::Get, "/".to_string(), HashMap::new(), None)
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_responses);
+criterion_main!(benches);
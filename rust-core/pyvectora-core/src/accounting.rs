@@ -0,0 +1,225 @@
+//! # Resource Accounting
+//!
+//! Per-route aggregation of handler cost (wall time, GIL-held time and
+//! allocation deltas) and process-wide GIL hold-time counters.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only aggregates samples, measuring is done by the caller
+//! - **O**: New counters are added to `HandlerSample`/`RouteStats` together
+//! - **D**: Bindings record through `ResourceAccounting`, not a metrics backend
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Cost of a single handler invocation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HandlerSample {
+    /// Time from dispatch to response
+    pub wall: Duration,
+    /// Time spent holding the GIL
+    pub gil: Duration,
+    /// Net change in allocated memory blocks while holding the GIL
+    pub alloc_blocks: i64,
+}
+
+/// Aggregated cost of one route
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteStats {
+    /// Number of recorded invocations
+    pub calls: u64,
+    /// Sum of wall times
+    pub total_wall: Duration,
+    /// Slowest invocation
+    pub max_wall: Duration,
+    /// Sum of GIL-held times
+    pub total_gil: Duration,
+    /// Sum of allocation deltas
+    pub total_alloc_blocks: i64,
+}
+
+impl RouteStats {
+    /// Fold a sample into the aggregate
+    pub fn record(&mut self, sample: HandlerSample) {
+        self.calls += 1;
+        self.total_wall += sample.wall;
+        self.max_wall = self.max_wall.max(sample.wall);
+        self.total_gil += sample.gil;
+        self.total_alloc_blocks += sample.alloc_blocks;
+    }
+
+    /// Mean wall time per call
+    #[must_use]
+    pub fn mean_wall(&self) -> Duration {
+        u32::try_from(self.calls)
+            .ok()
+            .filter(|&n| n > 0)
+            .map_or(Duration::ZERO, |n| self.total_wall / n)
+    }
+}
+
+/// Thread-safe per-route cost table
+///
+/// Keys are route labels such as `"GET /users/{id}"`.
+#[derive(Debug, Default)]
+pub struct ResourceAccounting {
+    routes: Mutex<HashMap<String, RouteStats>>,
+}
+
+impl ResourceAccounting {
+    /// Create an empty table
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record one invocation of `route`
+    pub fn record(&self, route: &str, sample: HandlerSample) {
+        let mut routes = self
+            .routes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        if let Some(stats) = routes.get_mut(route) {
+            stats.record(sample);
+        } else {
+            let mut stats = RouteStats::default();
+            stats.record(sample);
+            routes.insert(route.to_string(), stats);
+        }
+    }
+
+    /// Current aggregates, most expensive route (by total wall time) first
+    #[must_use]
+    pub fn snapshot(&self) -> Vec<(String, RouteStats)> {
+        let mut rows: Vec<_> = self
+            .routes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .map(|(k, v)| (k.clone(), *v))
+            .collect();
+        rows.sort_by(|a, b| {
+            b.1.total_wall
+                .cmp(&a.1.total_wall)
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        rows
+    }
+
+    /// Drop all recorded data
+    pub fn reset(&self) {
+        self.routes
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clear();
+    }
+}
+
+/// Process-wide GIL hold-time counters
+///
+/// Lock-free so it can stay enabled on every request.
+#[derive(Debug, Default)]
+pub struct GilMetrics {
+    requests: AtomicU64,
+    total_nanos: AtomicU64,
+    max_nanos: AtomicU64,
+    slow_requests: AtomicU64,
+}
+
+/// Point-in-time copy of `GilMetrics`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GilSnapshot {
+    /// Requests measured
+    pub requests: u64,
+    /// Total GIL-held time
+    pub total: Duration,
+    /// Longest single hold
+    pub max: Duration,
+    /// Requests that exceeded the warning threshold
+    pub slow_requests: u64,
+}
+
+impl GilMetrics {
+    /// Create zeroed counters
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record how long one request held the GIL
+    pub fn record(&self, held: Duration, slow: bool) {
+        let nanos = u64::try_from(held.as_nanos()).unwrap_or(u64::MAX);
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.total_nanos.fetch_add(nanos, Ordering::Relaxed);
+        self.max_nanos.fetch_max(nanos, Ordering::Relaxed);
+        if slow {
+            self.slow_requests.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Read the current counters
+    #[must_use]
+    pub fn snapshot(&self) -> GilSnapshot {
+        GilSnapshot {
+            requests: self.requests.load(Ordering::Relaxed),
+            total: Duration::from_nanos(self.total_nanos.load(Ordering::Relaxed)),
+            max: Duration::from_nanos(self.max_nanos.load(Ordering::Relaxed)),
+            slow_requests: self.slow_requests.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(wall_ms: u64, gil_ms: u64, alloc_blocks: i64) -> HandlerSample {
+        HandlerSample {
+            wall: Duration::from_millis(wall_ms),
+            gil: Duration::from_millis(gil_ms),
+            alloc_blocks,
+        }
+    }
+
+    #[test]
+    fn test_route_stats_aggregate() {
+        let mut stats = RouteStats::default();
+        stats.record(sample(10, 4, 100));
+        stats.record(sample(30, 6, -20));
+        assert_eq!(stats.calls, 2);
+        assert_eq!(stats.max_wall, Duration::from_millis(30));
+        assert_eq!(stats.mean_wall(), Duration::from_millis(20));
+        assert_eq!(stats.total_gil, Duration::from_millis(10));
+        assert_eq!(stats.total_alloc_blocks, 80);
+    }
+
+    #[test]
+    fn test_snapshot_orders_by_total_wall() {
+        let accounting = ResourceAccounting::new();
+        accounting.record("GET /fast", sample(1, 1, 0));
+        accounting.record("GET /slow", sample(50, 40, 0));
+        accounting.record("GET /fast", sample(1, 1, 0));
+
+        let rows = accounting.snapshot();
+        assert_eq!(rows[0].0, "GET /slow");
+        assert_eq!(rows[1].1.calls, 2);
+
+        accounting.reset();
+        assert!(accounting.snapshot().is_empty());
+    }
+
+    #[test]
+    fn test_gil_metrics() {
+        let metrics = GilMetrics::new();
+        metrics.record(Duration::from_millis(5), false);
+        metrics.record(Duration::from_millis(120), true);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.requests, 2);
+        assert_eq!(snapshot.total, Duration::from_millis(125));
+        assert_eq!(snapshot.max, Duration::from_millis(120));
+        assert_eq!(snapshot.slow_requests, 1);
+    }
+}
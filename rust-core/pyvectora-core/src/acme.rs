@@ -0,0 +1,125 @@
+//! # ACME HTTP-01 Challenges
+//!
+//! Serves `/.well-known/acme-challenge/{token}` responses for an ACME client
+//! (certbot hooks, a Python ACME library, ...) provisioning certificates.
+//! Issued certificates are written to disk and picked up by the TLS
+//! `CertStore` reload without restarting the server.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only stores and serves pending challenge responses
+//! - **D**: The ACME client drives the store; the server only reads it
+
+use crate::router::Method;
+use crate::server::{Handler, PyResponse, Server};
+use crate::Result;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Route pattern for HTTP-01 challenge requests
+pub const CHALLENGE_PATH: &str = "/.well-known/acme-challenge/{token}";
+
+/// Pending HTTP-01 challenges, shared between an ACME client and the server
+///
+/// Clones share the same underlying map.
+#[derive(Debug, Clone, Default)]
+pub struct ChallengeStore {
+    tokens: Arc<RwLock<HashMap<String, String>>>,
+}
+
+impl ChallengeStore {
+    /// Create an empty store
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publish the key authorization for `token`
+    pub fn insert(&self, token: impl Into<String>, key_authorization: impl Into<String>) {
+        self.tokens
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(token.into(), key_authorization.into());
+    }
+
+    /// Stop serving `token` once the challenge is validated
+    pub fn remove(&self, token: &str) -> Option<String> {
+        self.tokens
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(token)
+    }
+
+    /// Key authorization for `token`, if pending
+    #[must_use]
+    pub fn get(&self, token: &str) -> Option<String> {
+        self.tokens
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get(token)
+            .cloned()
+    }
+
+    /// Handler answering challenge requests (404 for unknown tokens)
+    #[must_use]
+    pub fn handler(&self) -> Handler {
+        let store = self.clone();
+        Arc::new(move |_req, matched| {
+            let response = matched
+                .params
+                .get("token")
+                .and_then(|token| store.get(token))
+                .map_or_else(
+                    || PyResponse::text("Not Found").with_status(404),
+                    PyResponse::text,
+                );
+            Box::pin(std::future::ready(response))
+        })
+    }
+}
+
+impl Server {
+    /// Serve HTTP-01 challenges from `store`
+    ///
+    /// ACME validates HTTP-01 over plain HTTP on port 80, so the route must
+    /// be reachable there (e.g. on a second, non-TLS server sharing the store).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the challenge route conflicts with another route.
+    pub fn enable_acme_http01(&mut self, store: &ChallengeStore) -> Result<()> {
+        self.add_route(Method::Get, CHALLENGE_PATH, store.handler(), false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_challenge_route_serves_pending_tokens() {
+        let store = ChallengeStore::new();
+        let mut server = Server::new("");
+        server.enable_acme_http01(&store).unwrap();
+
+        let path = "/.well-known/acme-challenge/abc123".to_string();
+        let resp = server
+            .test_request(Method::Get, path.clone(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 404);
+
+        store.insert("abc123", "abc123.thumbprint");
+        let resp = server
+            .test_request(Method::Get, path.clone(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, "abc123.thumbprint");
+
+        assert_eq!(store.remove("abc123").as_deref(), Some("abc123.thumbprint"));
+        let resp = server
+            .test_request(Method::Get, path, HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 404);
+    }
+}
@@ -0,0 +1,1181 @@
+//! # ACME Client
+//!
+//! Obtains and renews certificates from an ACME CA such as Let's Encrypt
+//! (RFC 8555): account registration, orders, HTTP-01 challenges published
+//! through a `ChallengeStore`, CSR finalization and certificate download.
+//! The account key, certificate and key are stored on disk, where the TLS
+//! `CertStore` reload picks renewals up without a restart.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only talks to the CA and stores what it issues
+//! - **O**: Requests go through a transport, so tests can stand in for the CA
+//! - **D**: Challenges go to the shared `ChallengeStore`; servers answer them
+
+use crate::acme::ChallengeStore;
+use crate::error::{Error, Result};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderMap, CONTENT_TYPE, HOST, USER_AGENT};
+use hyper::http::uri::PathAndQuery;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode, Uri};
+use hyper_util::rt::TokioIo;
+use rcgen::{CertificateParams, DistinguishedName, DnType, KeyPair};
+use ring::digest::{digest, SHA256};
+use ring::rand::SystemRandom;
+use ring::signature::{EcdsaKeyPair, KeyPair as _, ECDSA_P256_SHA256_FIXED_SIGNING};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::fs;
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::{JoinHandle, JoinSet};
+use tokio_rustls::rustls::crypto::ring as tls_ring;
+use tokio_rustls::rustls::pki_types::ServerName;
+use tokio_rustls::rustls::{ClientConfig, RootCertStore};
+use tokio_rustls::TlsConnector;
+use tracing::{info, warn};
+
+/// Let's Encrypt production directory
+pub const LETS_ENCRYPT: &str = "https://acme-v02.api.letsencrypt.org/directory";
+
+/// Let's Encrypt staging directory (untrusted certificates, generous limits)
+pub const LETS_ENCRYPT_STAGING: &str = "https://acme-staging-v02.api.letsencrypt.org/directory";
+
+const DEFAULT_RENEW_BEFORE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+const POLL_ATTEMPTS: u32 = 60;
+/// Wait after a failed renewal, and at least between renewal checks
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+/// Longest wait between renewal checks
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+const CHALLENGE_PREFIX: &str = "/.well-known/acme-challenge/";
+
+/// Where and how to obtain a certificate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AcmeConfig {
+    /// Directory URL of the CA (default: Let's Encrypt)
+    pub directory: String,
+    /// Domains on the certificate; the first is also its common name
+    pub domains: Vec<String>,
+    /// Contact URL for expiry notices (`mailto:...`)
+    pub contact: Option<String>,
+    /// Directory holding the account key, certificate and certificate key
+    pub storage: PathBuf,
+    /// Renew this long before the certificate expires (default: 30 days)
+    pub renew_before: Duration,
+    /// Answer HTTP-01 challenges on this port while an order is validated
+    /// (`None`: answered by servers sharing the challenge store)
+    pub http01_port: Option<u16>,
+}
+
+impl AcmeConfig {
+    /// Certificate for `domains` from Let's Encrypt, stored in `storage`
+    #[must_use]
+    pub fn new(domains: Vec<String>, storage: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: LETS_ENCRYPT.to_string(),
+            domains,
+            contact: None,
+            storage: storage.into(),
+            renew_before: DEFAULT_RENEW_BEFORE,
+            http01_port: None,
+        }
+    }
+
+    /// Use another CA's directory URL
+    #[must_use]
+    pub fn with_directory(mut self, url: impl Into<String>) -> Self {
+        self.directory = url.into();
+        self
+    }
+
+    /// Register the account with a contact email (or `mailto:` URL)
+    #[must_use]
+    pub fn with_contact(mut self, email: impl Into<String>) -> Self {
+        let email = email.into();
+        self.contact = Some(if email.starts_with("mailto:") {
+            email
+        } else {
+            format!("mailto:{email}")
+        });
+        self
+    }
+
+    /// Renew `before` the certificate expires
+    #[must_use]
+    pub const fn with_renew_before(mut self, before: Duration) -> Self {
+        self.renew_before = before;
+        self
+    }
+
+    /// Answer HTTP-01 challenges on `port` while an order is validated
+    #[must_use]
+    pub const fn with_http01_port(mut self, port: u16) -> Self {
+        self.http01_port = Some(port);
+        self
+    }
+
+    /// PEM certificate chain (`<storage>/cert.pem`)
+    #[must_use]
+    pub fn cert_path(&self) -> PathBuf {
+        self.storage.join("cert.pem")
+    }
+
+    /// PEM certificate key (`<storage>/key.pem`)
+    #[must_use]
+    pub fn key_path(&self) -> PathBuf {
+        self.storage.join("key.pem")
+    }
+
+    fn account_key_path(&self) -> PathBuf {
+        self.storage.join("account.key")
+    }
+
+    fn domains_path(&self) -> PathBuf {
+        self.storage.join("domains")
+    }
+}
+
+/// Issues and renews the certificate described by an `AcmeConfig`
+pub struct AcmeClient {
+    config: AcmeConfig,
+    challenges: ChallengeStore,
+    transport: Box<dyn Transport>,
+    poll_interval: Duration,
+}
+
+impl AcmeClient {
+    /// Client publishing its HTTP-01 challenges to `challenges`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` when there are no domains, a domain is blank
+    /// or the directory is not an `http(s)://` URL.
+    pub fn new(config: AcmeConfig, challenges: ChallengeStore) -> Result<Self> {
+        Self::with_transport(config, challenges, Box::new(HttpsTransport::new()?))
+    }
+
+    fn with_transport(
+        config: AcmeConfig,
+        challenges: ChallengeStore,
+        transport: Box<dyn Transport>,
+    ) -> Result<Self> {
+        let config_error = |message: String| Error::Config { message };
+        if config.domains.is_empty() {
+            return Err(config_error("ACME needs at least one domain".to_string()));
+        }
+        if let Some(domain) = config.domains.iter().find(|domain| {
+            domain.is_empty() || domain.contains(|c: char| c.is_whitespace() || c == '/')
+        }) {
+            return Err(config_error(format!("invalid ACME domain {domain:?}")));
+        }
+        let directory = config.directory.parse::<Uri>().ok();
+        if directory.as_ref().and_then(endpoint).is_none() {
+            return Err(config_error(format!(
+                "invalid ACME directory URL {:?}",
+                config.directory
+            )));
+        }
+        Ok(Self {
+            config,
+            challenges,
+            transport,
+            poll_interval: POLL_INTERVAL,
+        })
+    }
+
+    /// The client's settings
+    #[must_use]
+    pub const fn config(&self) -> &AcmeConfig {
+        &self.config
+    }
+
+    /// Expiry of the stored certificate (`None` when missing or unreadable)
+    #[must_use]
+    pub fn expires_at(&self) -> Option<SystemTime> {
+        let pem = fs::read(self.config.cert_path()).ok()?;
+        let der = rustls_pemfile::certs(&mut pem.as_slice()).next()?.ok()?;
+        let (_, certificate) = x509_parser::parse_x509_certificate(&der).ok()?;
+        let expires = certificate.validity().not_after.timestamp();
+        Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(expires).ok()?))
+    }
+
+    /// Whether the stored certificate is missing, for other domains, or
+    /// within `renew_before` of expiring
+    #[must_use]
+    pub fn needs_renewal(&self) -> bool {
+        let stored = fs::read_to_string(self.config.domains_path()).unwrap_or_default();
+        if stored
+            .lines()
+            .ne(self.config.domains.iter().map(String::as_str))
+        {
+            return true;
+        }
+        match self
+            .expires_at()
+            .map(|expires| expires.duration_since(SystemTime::now()))
+        {
+            Some(Ok(left)) => left <= self.config.renew_before,
+            _ => true,
+        }
+    }
+
+    /// Issue a certificate when `needs_renewal`; returns whether it did
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of a failed issuance (see `issue`).
+    pub async fn ensure(&self) -> Result<bool> {
+        if !self.needs_renewal() {
+            return Ok(false);
+        }
+        self.issue().await?;
+        Ok(true)
+    }
+
+    /// Obtain a new certificate and store it
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Acme` when the CA rejects a request or a domain fails
+    /// validation, and `Error::Io` when storage cannot be written.
+    pub async fn issue(&self) -> Result<()> {
+        fs::create_dir_all(&self.config.storage)?;
+        let _responder = match self.config.http01_port {
+            Some(port) => Some(
+                Responder::bind(port, self.challenges.clone())
+                    .await
+                    .map_err(|e| {
+                        acme_error(format!(
+                            "cannot answer HTTP-01 challenges on port {port}: {e}"
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+
+        let account_key = load_or_create_key(&self.config.account_key_path())?;
+        let mut session = Session::open(self, &account_key).await?;
+        session.register().await?;
+        let (order_url, order) = session.order().await?;
+        let authorizations: Vec<String> = order["authorizations"]
+            .as_array()
+            .map(|urls| {
+                urls.iter()
+                    .filter_map(Value::as_str)
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        for url in &authorizations {
+            session.authorize(url).await?;
+        }
+
+        let cert_key = KeyPair::generate().map_err(|e| acme_error(e.to_string()))?;
+        let mut params = CertificateParams::new(self.config.domains.clone())
+            .map_err(|e| acme_error(e.to_string()))?;
+        params.distinguished_name = DistinguishedName::new();
+        params
+            .distinguished_name
+            .push(DnType::CommonName, self.config.domains[0].as_str());
+        let csr = params
+            .serialize_request(&cert_key)
+            .map_err(|e| acme_error(e.to_string()))?;
+        let chain = session.finalize(&order_url, csr.der()).await?;
+
+        write_file(
+            &self.config.key_path(),
+            cert_key.serialize_pem().as_bytes(),
+            true,
+        )?;
+        write_file(&self.config.cert_path(), &chain, false)?;
+        write_file(
+            &self.config.domains_path(),
+            self.config.domains.join("\n").as_bytes(),
+            false,
+        )?;
+        info!(
+            "Certificate for {} issued by {}",
+            self.config.domains.join(", "),
+            self.config.directory
+        );
+        Ok(())
+    }
+
+    /// Keep the certificate renewed from a background task
+    ///
+    /// The stored certificate is checked right away, then again shortly
+    /// before it is due; failures are logged and retried hourly. Must be
+    /// called from within a Tokio runtime.
+    #[must_use]
+    pub fn spawn_renewal(self) -> AcmeRenewal {
+        let task = tokio::spawn(async move {
+            loop {
+                let wait = match self.ensure().await {
+                    Ok(_) => self.renewal_delay(),
+                    Err(err) => {
+                        warn!("Certificate renewal failed: {}", err);
+                        RETRY_INTERVAL
+                    }
+                };
+                tokio::time::sleep(wait).await;
+            }
+        });
+        AcmeRenewal { task }
+    }
+
+    /// Time until the stored certificate is due for renewal
+    fn renewal_delay(&self) -> Duration {
+        self.expires_at()
+            .and_then(|expires| expires.checked_sub(self.config.renew_before))
+            .and_then(|due| due.duration_since(SystemTime::now()).ok())
+            .unwrap_or_default()
+            .clamp(RETRY_INTERVAL, MAX_CHECK_INTERVAL)
+    }
+
+    async fn send(&self, method: Method, url: &str, body: Option<Vec<u8>>) -> Result<HttpResponse> {
+        let described = format!("{method} {url}");
+        self.transport
+            .request(method, url, body)
+            .await
+            .map_err(|e| acme_error(format!("{described}: {e}")))
+    }
+}
+
+/// Background renewal started by `AcmeClient::spawn_renewal`
+///
+/// Dropping it stops renewing.
+#[derive(Debug)]
+pub struct AcmeRenewal {
+    task: JoinHandle<()>,
+}
+
+impl Drop for AcmeRenewal {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// ACME endpoints from the CA's directory
+struct Directory {
+    nonce: String,
+    account: String,
+    order: String,
+}
+
+/// One issuance: the account, its signing key and the current nonce
+struct Session<'a> {
+    client: &'a AcmeClient,
+    directory: Directory,
+    key: EcdsaKeyPair,
+    rng: SystemRandom,
+    jwk: Value,
+    thumbprint: String,
+    kid: Option<String>,
+    nonce: Option<String>,
+}
+
+impl<'a> Session<'a> {
+    async fn open(client: &'a AcmeClient, account_key: &KeyPair) -> Result<Session<'a>> {
+        let response = client
+            .send(Method::GET, &client.config.directory, None)
+            .await?;
+        if !response.status.is_success() {
+            return Err(acme_error(format!(
+                "{} answered {}",
+                client.config.directory, response.status
+            )));
+        }
+        let directory = response.json()?;
+        let url = |name: &str| {
+            directory[name]
+                .as_str()
+                .map(String::from)
+                .ok_or_else(|| acme_error(format!("the directory has no {name} URL")))
+        };
+        let directory = Directory {
+            nonce: url("newNonce")?,
+            account: url("newAccount")?,
+            order: url("newOrder")?,
+        };
+
+        let rng = SystemRandom::new();
+        let key = EcdsaKeyPair::from_pkcs8(
+            &ECDSA_P256_SHA256_FIXED_SIGNING,
+            &account_key.serialize_der(),
+            &rng,
+        )
+        .map_err(|e| acme_error(format!("unusable account key: {e}")))?;
+        // Uncompressed point: 0x04, then X and Y
+        let (x, y) = key.public_key().as_ref()[1..].split_at(32);
+        let jwk = json!({
+            "crv": "P-256",
+            "kty": "EC",
+            "x": URL_SAFE_NO_PAD.encode(x),
+            "y": URL_SAFE_NO_PAD.encode(y),
+        });
+        Ok(Self {
+            client,
+            directory,
+            key,
+            rng,
+            thumbprint: jwk_thumbprint(&jwk),
+            jwk,
+            kid: None,
+            nonce: None,
+        })
+    }
+
+    /// Create the account, or look up the existing one for this key
+    async fn register(&mut self) -> Result<()> {
+        let mut account = json!({ "termsOfServiceAgreed": true });
+        if let Some(contact) = &self.client.config.contact {
+            account["contact"] = json!([contact]);
+        }
+        let url = self.directory.account.clone();
+        let response = self.post(&url, Some(&account)).await?;
+        self.kid = Some(location(&response)?);
+        Ok(())
+    }
+
+    /// Place an order for the configured domains
+    async fn order(&mut self) -> Result<(String, Value)> {
+        let identifiers: Vec<Value> = self
+            .client
+            .config
+            .domains
+            .iter()
+            .map(|domain| json!({ "type": "dns", "value": domain }))
+            .collect();
+        let url = self.directory.order.clone();
+        let response = self
+            .post(&url, Some(&json!({ "identifiers": identifiers })))
+            .await?;
+        Ok((location(&response)?, response.json()?))
+    }
+
+    /// Complete the HTTP-01 challenge of one authorization
+    async fn authorize(&mut self, url: &str) -> Result<()> {
+        let authorization = self.post(url, None).await?.json()?;
+        if authorization["status"] == "valid" {
+            return Ok(());
+        }
+        let domain = authorization["identifier"]["value"]
+            .as_str()
+            .unwrap_or("?")
+            .to_string();
+        let challenge = authorization["challenges"]
+            .as_array()
+            .and_then(|challenges| challenges.iter().find(|c| c["type"] == "http-01"))
+            .ok_or_else(|| acme_error(format!("no HTTP-01 challenge offered for {domain}")))?;
+        let (Some(token), Some(challenge_url)) =
+            (challenge["token"].as_str(), challenge["url"].as_str())
+        else {
+            return Err(acme_error(format!(
+                "malformed HTTP-01 challenge for {domain}"
+            )));
+        };
+        let (token, challenge_url) = (token.to_string(), challenge_url.to_string());
+
+        let client = self.client;
+        client
+            .challenges
+            .insert(token.clone(), format!("{token}.{}", self.thumbprint));
+        let outcome = match self.post(&challenge_url, Some(&json!({}))).await {
+            Ok(_) => self.poll(url, &["pending", "processing"]).await,
+            Err(err) => Err(err),
+        };
+        client.challenges.remove(&token);
+
+        let authorization = outcome?;
+        if authorization["status"] == "valid" {
+            return Ok(());
+        }
+        let detail = authorization["challenges"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find_map(|c| c["error"]["detail"].as_str())
+            .unwrap_or("no detail");
+        Err(acme_error(format!("{domain} failed validation: {detail}")))
+    }
+
+    /// Submit the CSR once the order is ready and download the certificate
+    async fn finalize(&mut self, order_url: &str, csr: &[u8]) -> Result<Vec<u8>> {
+        let mut order = self.poll(order_url, &["pending"]).await?;
+        if order["status"] == "ready" {
+            let finalize = order["finalize"]
+                .as_str()
+                .ok_or_else(|| acme_error("the order has no finalize URL".to_string()))?
+                .to_string();
+            self.post(
+                &finalize,
+                Some(&json!({ "csr": URL_SAFE_NO_PAD.encode(csr) })),
+            )
+            .await?;
+            order = self.poll(order_url, &["ready", "processing"]).await?;
+        }
+        if order["status"] != "valid" {
+            return Err(acme_error(format!("the order is {}", order["status"])));
+        }
+        let certificate = order["certificate"]
+            .as_str()
+            .ok_or_else(|| acme_error("the order has no certificate URL".to_string()))?
+            .to_string();
+        let chain = self.post(&certificate, None).await?.body;
+        if !chain.starts_with(b"-----BEGIN CERTIFICATE-----") {
+            return Err(acme_error("the CA sent no PEM certificate".to_string()));
+        }
+        Ok(chain)
+    }
+
+    /// POST-as-GET `url` until its status leaves `pending`
+    async fn poll(&mut self, url: &str, pending: &[&str]) -> Result<Value> {
+        for _ in 0..POLL_ATTEMPTS {
+            let resource = self.post(url, None).await?.json()?;
+            if !pending.iter().any(|status| resource["status"] == *status) {
+                return Ok(resource);
+            }
+            tokio::time::sleep(self.client.poll_interval).await;
+        }
+        Err(acme_error(format!(
+            "{url} still pending after {POLL_ATTEMPTS} checks"
+        )))
+    }
+
+    /// Signed POST (`payload` `None` is a POST-as-GET), retrying a bad nonce once
+    async fn post(&mut self, url: &str, payload: Option<&Value>) -> Result<HttpResponse> {
+        let mut retried = false;
+        loop {
+            let nonce = match self.nonce.take() {
+                Some(nonce) => nonce,
+                None => self.new_nonce().await?,
+            };
+            let body = self.sign(url, payload, &nonce)?;
+            let response = self.client.send(Method::POST, url, Some(body)).await?;
+            self.nonce = response.header("replay-nonce").map(String::from);
+            if response.status.is_success() {
+                return Ok(response);
+            }
+            let problem = response.json().unwrap_or(Value::Null);
+            if !retried && problem["type"] == "urn:ietf:params:acme:error:badNonce" {
+                retried = true;
+                continue;
+            }
+            return Err(acme_error(format!(
+                "{url} answered {}: {}",
+                response.status,
+                problem["detail"].as_str().unwrap_or("no detail")
+            )));
+        }
+    }
+
+    async fn new_nonce(&self) -> Result<String> {
+        self.client
+            .send(Method::HEAD, &self.directory.nonce, None)
+            .await?
+            .header("replay-nonce")
+            .map(String::from)
+            .ok_or_else(|| acme_error("the CA sent no Replay-Nonce".to_string()))
+    }
+
+    /// Flattened JWS, identified by the account URL once registered
+    fn sign(&self, url: &str, payload: Option<&Value>, nonce: &str) -> Result<Vec<u8>> {
+        let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+        match &self.kid {
+            Some(kid) => protected["kid"] = json!(kid),
+            None => protected["jwk"] = self.jwk.clone(),
+        }
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        let payload = payload.map_or_else(String::new, |payload| {
+            URL_SAFE_NO_PAD.encode(payload.to_string())
+        });
+        let signature = self
+            .key
+            .sign(&self.rng, format!("{protected}.{payload}").as_bytes())
+            .map_err(|_| acme_error("could not sign the request".to_string()))?;
+        let jws = json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature),
+        });
+        Ok(jws.to_string().into_bytes())
+    }
+}
+
+/// RFC 7638 thumbprint of an EC public key
+fn jwk_thumbprint(jwk: &Value) -> String {
+    // Required members in lexicographic order, without whitespace
+    let canonical = format!(
+        r#"{{"crv":{},"kty":{},"x":{},"y":{}}}"#,
+        jwk["crv"], jwk["kty"], jwk["x"], jwk["y"]
+    );
+    URL_SAFE_NO_PAD.encode(digest(&SHA256, canonical.as_bytes()))
+}
+
+fn location(response: &HttpResponse) -> Result<String> {
+    response
+        .header("location")
+        .map(String::from)
+        .ok_or_else(|| acme_error("the CA sent no Location".to_string()))
+}
+
+const fn acme_error(message: String) -> Error {
+    Error::Acme { message }
+}
+
+/// The account key at `path`, created on first use
+fn load_or_create_key(path: &Path) -> Result<KeyPair> {
+    match fs::read_to_string(path) {
+        Ok(pem) => {
+            KeyPair::from_pem(&pem).map_err(|e| acme_error(format!("{}: {e}", path.display())))
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            let key = KeyPair::generate().map_err(|e| acme_error(e.to_string()))?;
+            write_file(path, key.serialize_pem().as_bytes(), true)?;
+            Ok(key)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Replace `path` atomically; `private` files are readable by the owner only
+fn write_file(path: &Path, contents: &[u8], private: bool) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let _ = fs::remove_file(&tmp);
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+    options.open(&tmp)?.write_all(contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Answers HTTP-01 challenges on a port of its own until dropped
+struct Responder {
+    task: JoinHandle<()>,
+}
+
+impl Responder {
+    async fn bind(port: u16, challenges: ChallengeStore) -> io::Result<Self> {
+        let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+        Ok(Self::serve(listener, challenges))
+    }
+
+    fn serve(listener: TcpListener, challenges: ChallengeStore) -> Self {
+        let task = tokio::spawn(async move {
+            let mut connections = JoinSet::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                while connections.try_join_next().is_some() {}
+                let challenges = challenges.clone();
+                let service = service_fn(move |request: Request<Incoming>| {
+                    let key_authorization = request
+                        .uri()
+                        .path()
+                        .strip_prefix(CHALLENGE_PREFIX)
+                        .and_then(|token| challenges.get(token));
+                    std::future::ready(Ok::<_, Infallible>(challenge_response(key_authorization)))
+                });
+                connections.spawn(
+                    hyper::server::conn::http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service),
+                );
+            }
+        });
+        Self { task }
+    }
+}
+
+impl Drop for Responder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn challenge_response(key_authorization: Option<String>) -> Response<Full<Bytes>> {
+    let (status, body) = key_authorization.map_or_else(
+        || (StatusCode::NOT_FOUND, "Not Found".to_string()),
+        |key_authorization| (StatusCode::OK, key_authorization),
+    );
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, "text/plain".parse().expect("static header"));
+    response
+}
+
+/// Response from the CA
+struct HttpResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    fn json(&self) -> Result<Value> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+}
+
+type ResponseFuture<'a> = Pin<Box<dyn Future<Output = io::Result<HttpResponse>> + Send + 'a>>;
+
+/// Sends requests to the CA
+trait Transport: Send + Sync {
+    fn request<'a>(
+        &'a self,
+        method: Method,
+        url: &'a str,
+        body: Option<Vec<u8>>,
+    ) -> ResponseFuture<'a>;
+}
+
+/// HTTP/1.1 through hyper, over rustls for `https://` (webpki roots)
+struct HttpsTransport {
+    tls: TlsConnector,
+}
+
+impl HttpsTransport {
+    fn new() -> Result<Self> {
+        let roots = RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        let config = ClientConfig::builder_with_provider(Arc::new(tls_ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| acme_error(e.to_string()))?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+        Ok(Self {
+            tls: TlsConnector::from(Arc::new(config)),
+        })
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<Vec<u8>>,
+    ) -> io::Result<HttpResponse> {
+        let uri: Uri = url.parse().map_err(io_error)?;
+        let (secure, host, port) =
+            endpoint(&uri).ok_or_else(|| io_error(format!("unsupported URL {url}")))?;
+        let authority = uri.authority().map_or(host, |authority| authority.as_str());
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri.path_and_query().map_or("/", PathAndQuery::as_str))
+            .header(HOST, authority)
+            .header(USER_AGENT, "pyvectora-acme");
+        if body.is_some() {
+            request = request.header(CONTENT_TYPE, "application/jose+json");
+        }
+        let request = request
+            .body(Full::new(Bytes::from(body.unwrap_or_default())))
+            .map_err(io_error)?;
+
+        let stream = TcpStream::connect((host, port)).await?;
+        if secure {
+            let name = ServerName::try_from(host.to_string()).map_err(io_error)?;
+            exchange(self.tls.connect(name, stream).await?, request).await
+        } else {
+            exchange(stream, request).await
+        }
+    }
+}
+
+impl Transport for HttpsTransport {
+    fn request<'a>(
+        &'a self,
+        method: Method,
+        url: &'a str,
+        body: Option<Vec<u8>>,
+    ) -> ResponseFuture<'a> {
+        Box::pin(async move {
+            tokio::time::timeout(REQUEST_TIMEOUT, self.send(method, url, body))
+                .await
+                .unwrap_or_else(|_| {
+                    Err(io::Error::new(
+                        io::ErrorKind::TimedOut,
+                        "the CA did not answer in time",
+                    ))
+                })
+        })
+    }
+}
+
+/// Send `request` on a fresh HTTP/1.1 connection and read the whole response
+async fn exchange<S>(stream: S, request: Request<Full<Bytes>>) -> io::Result<HttpResponse>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let (mut sender, connection) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+        .await
+        .map_err(io_error)?;
+    tokio::spawn(connection);
+    let response = sender.send_request(request).await.map_err(io_error)?;
+    let (parts, body) = response.into_parts();
+    let body = body.collect().await.map_err(io_error)?.to_bytes();
+    Ok(HttpResponse {
+        status: parts.status,
+        headers: parts.headers,
+        body: body.to_vec(),
+    })
+}
+
+/// Whether `uri` is `https`, and its host and port (`None` unless http(s))
+fn endpoint(uri: &Uri) -> Option<(bool, &str, u16)> {
+    let secure = match uri.scheme_str()? {
+        "https" => true,
+        "http" => false,
+        _ => return None,
+    };
+    let host = uri.host()?.trim_start_matches('[').trim_end_matches(']');
+    let port = uri.port_u16().unwrap_or(if secure { 443 } else { 80 });
+    Some((secure, host, port))
+}
+
+fn io_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ring::signature::{UnparsedPublicKey, ECDSA_P256_SHA256_FIXED};
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use x509_parser::prelude::{FromDer, X509CertificationRequest};
+
+    const CA: &str = "https://ca.test";
+
+    #[derive(Default)]
+    struct CaState {
+        nonce: u32,
+        rejected_nonce: bool,
+        jwk: Option<Value>,
+        validated: bool,
+        finalized: bool,
+    }
+
+    /// Minimal CA checking signatures, the published challenge and the CSR
+    #[derive(Clone)]
+    struct FakeCa {
+        challenges: ChallengeStore,
+        certificate: String,
+        state: Arc<Mutex<CaState>>,
+    }
+
+    impl FakeCa {
+        fn new(challenges: ChallengeStore) -> Self {
+            let key = KeyPair::generate().unwrap();
+            let certificate = CertificateParams::new(vec!["example.com".to_string()])
+                .unwrap()
+                .self_signed(&key)
+                .unwrap()
+                .pem();
+            Self {
+                challenges,
+                certificate,
+                state: Arc::default(),
+            }
+        }
+
+        fn order(state: &CaState) -> Value {
+            let status = if state.finalized {
+                "valid"
+            } else if state.validated {
+                "ready"
+            } else {
+                "pending"
+            };
+            json!({
+                "status": status,
+                "authorizations": [format!("{CA}/authz/1")],
+                "finalize": format!("{CA}/finalize/1"),
+                "certificate": format!("{CA}/cert/1"),
+            })
+        }
+
+        /// Verify the JWS and return its payload (`Null` for POST-as-GET)
+        fn payload(state: &mut CaState, url: &str, body: &[u8]) -> Value {
+            let jws: Value = serde_json::from_slice(body).unwrap();
+            let decode = |field: &str| {
+                URL_SAFE_NO_PAD
+                    .decode(jws[field].as_str().unwrap())
+                    .unwrap()
+            };
+            let protected: Value = serde_json::from_slice(&decode("protected")).unwrap();
+            assert_eq!(protected["url"], url);
+            assert_eq!(protected["alg"], "ES256");
+            if protected["jwk"].is_object() {
+                state.jwk = Some(protected["jwk"].clone());
+            } else {
+                assert_eq!(protected["kid"], format!("{CA}/account/1"));
+            }
+            let jwk = state.jwk.as_ref().unwrap();
+            let mut point = vec![4];
+            for coordinate in ["x", "y"] {
+                point.extend(
+                    URL_SAFE_NO_PAD
+                        .decode(jwk[coordinate].as_str().unwrap())
+                        .unwrap(),
+                );
+            }
+            let signed = format!(
+                "{}.{}",
+                jws["protected"].as_str().unwrap(),
+                jws["payload"].as_str().unwrap()
+            );
+            UnparsedPublicKey::new(&ECDSA_P256_SHA256_FIXED, point)
+                .verify(signed.as_bytes(), &decode("signature"))
+                .unwrap();
+            let payload = decode("payload");
+            if payload.is_empty() {
+                Value::Null
+            } else {
+                serde_json::from_slice(&payload).unwrap()
+            }
+        }
+
+        fn answer(&self, method: &Method, url: &str, body: Option<&[u8]>) -> HttpResponse {
+            let mut state = self.state.lock().unwrap();
+            state.nonce += 1;
+            let mut headers = HeaderMap::new();
+            headers.insert("replay-nonce", format!("n{}", state.nonce).parse().unwrap());
+            let path = url.strip_prefix(CA).unwrap();
+            let (status, body) = match (method.as_str(), path) {
+                ("GET", "/directory") => (
+                    200,
+                    json!({
+                        "newNonce": format!("{CA}/nonce"),
+                        "newAccount": format!("{CA}/account"),
+                        "newOrder": format!("{CA}/order"),
+                    }),
+                ),
+                ("HEAD", "/nonce") => (200, Value::Null),
+                ("POST", _) if !state.rejected_nonce => {
+                    state.rejected_nonce = true;
+                    (
+                        400,
+                        json!({ "type": "urn:ietf:params:acme:error:badNonce" }),
+                    )
+                }
+                ("POST", "/account") => {
+                    let account = Self::payload(&mut state, url, body.unwrap());
+                    assert_eq!(account["termsOfServiceAgreed"], true);
+                    assert_eq!(account["contact"], json!(["mailto:ops@example.com"]));
+                    headers.insert("location", format!("{CA}/account/1").parse().unwrap());
+                    (201, json!({ "status": "valid" }))
+                }
+                ("POST", "/order") => {
+                    let order = Self::payload(&mut state, url, body.unwrap());
+                    assert_eq!(order["identifiers"][0]["value"], "example.com");
+                    headers.insert("location", format!("{CA}/order/1").parse().unwrap());
+                    (201, Self::order(&state))
+                }
+                ("POST", "/authz/1") => {
+                    Self::payload(&mut state, url, body.unwrap());
+                    let status = if state.validated { "valid" } else { "pending" };
+                    (
+                        200,
+                        json!({
+                            "status": status,
+                            "identifier": { "type": "dns", "value": "example.com" },
+                            "challenges": [
+                                { "type": "dns-01", "url": format!("{CA}/chall/2"), "token": "dns" },
+                                { "type": "http-01", "url": format!("{CA}/chall/1"), "token": "tok1" },
+                            ],
+                        }),
+                    )
+                }
+                ("POST", "/chall/1") => {
+                    Self::payload(&mut state, url, body.unwrap());
+                    let expected = format!("tok1.{}", jwk_thumbprint(state.jwk.as_ref().unwrap()));
+                    assert_eq!(self.challenges.get("tok1"), Some(expected));
+                    state.validated = true;
+                    (200, json!({ "status": "processing" }))
+                }
+                ("POST", "/order/1") => {
+                    Self::payload(&mut state, url, body.unwrap());
+                    (200, Self::order(&state))
+                }
+                ("POST", "/finalize/1") => {
+                    let request = Self::payload(&mut state, url, body.unwrap());
+                    let csr = URL_SAFE_NO_PAD
+                        .decode(request["csr"].as_str().unwrap())
+                        .unwrap();
+                    let (rest, csr) = X509CertificationRequest::from_der(&csr).unwrap();
+                    assert!(rest.is_empty());
+                    let subject = csr.certification_request_info.subject.to_string();
+                    assert_eq!(subject, "CN=example.com");
+                    state.finalized = true;
+                    (200, Self::order(&state))
+                }
+                ("POST", "/cert/1") => {
+                    Self::payload(&mut state, url, body.unwrap());
+                    (200, Value::String(self.certificate.clone()))
+                }
+                _ => (404, Value::Null),
+            };
+            drop(state);
+            let body = match body {
+                Value::Null => Vec::new(),
+                Value::String(pem) => pem.into_bytes(),
+                body => body.to_string().into_bytes(),
+            };
+            HttpResponse {
+                status: StatusCode::from_u16(status).unwrap(),
+                headers,
+                body,
+            }
+        }
+    }
+
+    impl Transport for FakeCa {
+        fn request<'a>(
+            &'a self,
+            method: Method,
+            url: &'a str,
+            body: Option<Vec<u8>>,
+        ) -> ResponseFuture<'a> {
+            let response = self.answer(&method, url, body.as_deref());
+            Box::pin(std::future::ready(Ok(response)))
+        }
+    }
+
+    fn temp_storage(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("pyvectora-acme-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[tokio::test]
+    async fn test_issues_and_stores_certificate() {
+        let storage = temp_storage("issue");
+        let challenges = ChallengeStore::new();
+        let ca = FakeCa::new(challenges.clone());
+        let config = AcmeConfig::new(vec!["example.com".to_string()], &storage)
+            .with_directory(format!("{CA}/directory"))
+            .with_contact("ops@example.com");
+        let mut client =
+            AcmeClient::with_transport(config.clone(), challenges.clone(), Box::new(ca.clone()))
+                .unwrap();
+        client.poll_interval = Duration::ZERO;
+
+        assert!(client.needs_renewal());
+        assert!(client.ensure().await.unwrap());
+        assert_eq!(
+            fs::read_to_string(config.cert_path()).unwrap(),
+            ca.certificate
+        );
+        assert!(KeyPair::from_pem(&fs::read_to_string(config.key_path()).unwrap()).is_ok());
+        assert!(config.account_key_path().exists());
+        assert!(client.expires_at().is_some());
+        assert!(!client.needs_renewal());
+        assert!(!client.ensure().await.unwrap());
+        assert_eq!(challenges.get("tok1"), None);
+        assert!(ca.state.lock().unwrap().rejected_nonce);
+
+        let more_domains = AcmeConfig {
+            domains: vec!["example.com".to_string(), "www.example.com".to_string()],
+            ..config
+        };
+        let client = AcmeClient::new(more_domains, challenges).unwrap();
+        assert!(client.needs_renewal());
+        let _ = fs::remove_dir_all(storage);
+    }
+
+    #[test]
+    fn test_config_is_validated() {
+        let store = ChallengeStore::new();
+        for config in [
+            AcmeConfig::new(Vec::new(), "acme"),
+            AcmeConfig::new(vec![" ".to_string()], "acme"),
+            AcmeConfig::new(vec!["example.com".to_string()], "acme").with_directory("ftp://ca"),
+        ] {
+            assert!(matches!(
+                AcmeClient::new(config, store.clone()),
+                Err(Error::Config { .. })
+            ));
+        }
+        let config = AcmeConfig::new(vec!["example.com".to_string()], "acme")
+            .with_contact("mailto:ops@example.com");
+        assert_eq!(config.contact.as_deref(), Some("mailto:ops@example.com"));
+    }
+
+    #[test]
+    fn test_certificate_expiry_is_read() {
+        let storage = temp_storage("expiry");
+        let config = AcmeConfig::new(vec!["example.com".to_string()], &storage);
+        let client = AcmeClient::new(config.clone(), ChallengeStore::new()).unwrap();
+        assert_eq!(client.expires_at(), None);
+
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(vec!["example.com".to_string()]).unwrap();
+        params.not_after = rcgen::date_time_ymd(2030, 1, 2);
+        let certificate = params.self_signed(&key).unwrap();
+        fs::create_dir_all(&storage).unwrap();
+        fs::write(config.cert_path(), certificate.pem()).unwrap();
+        let expected = UNIX_EPOCH + Duration::from_secs(1_893_542_400);
+        assert_eq!(client.expires_at(), Some(expected));
+        let _ = fs::remove_dir_all(storage);
+    }
+
+    #[tokio::test]
+    async fn test_standalone_responder_answers_challenges() {
+        let challenges = ChallengeStore::new();
+        challenges.insert("abc", "abc.thumbprint");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let responder = Responder::serve(listener, challenges);
+
+        let get = |path: &'static str| async move {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            let request =
+                format!("GET {path} HTTP/1.1\r\nHost: example.com\r\nConnection: close\r\n\r\n");
+            stream.write_all(request.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+        let found = get("/.well-known/acme-challenge/abc").await;
+        assert!(found.starts_with("HTTP/1.1 200"));
+        assert!(found.ends_with("\r\n\r\nabc.thumbprint"));
+        let missing = get("/.well-known/acme-challenge/other").await;
+        assert!(missing.starts_with("HTTP/1.1 404"));
+        drop(responder);
+    }
+
+    #[test]
+    fn test_endpoint_of_directory_urls() {
+        let uri: Uri = "https://acme.example:14000/dir?x=1".parse().unwrap();
+        assert_eq!(endpoint(&uri), Some((true, "acme.example", 14000)));
+        let uri: Uri = "http://[::1]/directory".parse().unwrap();
+        assert_eq!(endpoint(&uri), Some((false, "::1", 80)));
+        let uri: Uri = "ftp://example.com".parse().unwrap();
+        assert_eq!(endpoint(&uri), None);
+    }
+}
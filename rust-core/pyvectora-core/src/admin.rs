@@ -0,0 +1,574 @@
+//! # Admin Data Browser
+//!
+//! Server-rendered pages for looking at registered databases while
+//! debugging:
+//!
+//! - `GET {prefix}` - databases and their tables
+//! - `GET {prefix}/{db}/tables/{table}?page=N` - rows, one page at a time
+//! - `GET|POST {prefix}/{db}/query` - run a read-only SQL query
+//!
+//! Requests must authenticate with the admin token, either as
+//! `Authorization: Bearer <token>` or as the password of HTTP Basic auth
+//! (so a browser prompts for it). The browser is refused unless the server
+//! runs in debug mode, keeping it out of production.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only renders database contents; queries run through `DatabasePool`
+//! - **O**: New pages are new routes under the same prefix
+
+use crate::database::{DatabasePool, DbValue, ResultSet};
+use crate::error::{Error, Result};
+use crate::extension::ServerExtension;
+use crate::profiling::token_matches;
+use crate::query::{self, Page, SqlQuery};
+use crate::request::PyRequest;
+use crate::router::Method;
+use crate::server::{Handler, PyResponse, Server};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Default mount point of the browser
+pub const DEFAULT_PREFIX: &str = "/_pyvectora/admin";
+
+/// Rows shown per table page
+pub const PAGE_SIZE: u64 = 50;
+
+/// Rows shown for an ad-hoc query
+const MAX_QUERY_ROWS: usize = 500;
+
+type PageFuture = Pin<Box<dyn Future<Output = PyResponse> + Send>>;
+
+/// Auth-protected HTML browser over named database pools
+#[derive(Clone)]
+pub struct AdminBrowser {
+    prefix: String,
+    token: Arc<str>,
+    databases: Arc<Vec<(String, DatabasePool)>>,
+}
+
+impl std::fmt::Debug for AdminBrowser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminBrowser")
+            .field("prefix", &self.prefix)
+            .field(
+                "databases",
+                &self
+                    .databases
+                    .iter()
+                    .map(|(name, _)| name)
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl AdminBrowser {
+    /// Browser under `prefix`, guarded by the admin `token`
+    #[must_use]
+    pub fn new(prefix: &str, token: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            token: Arc::from(token),
+            databases: Arc::new(Vec::new()),
+        }
+    }
+
+    /// Make `pool` browsable as `name`
+    #[must_use]
+    pub fn database(mut self, name: &str, pool: DatabasePool) -> Self {
+        Arc::make_mut(&mut self.databases).push((name.to_string(), pool));
+        self
+    }
+
+    /// Register the routes on `server`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` unless the server runs in debug mode or if
+    /// the token is empty, and an error if a route conflicts with an
+    /// existing one.
+    pub fn register(&self, server: &mut Server) -> Result<()> {
+        if !server.is_debug() {
+            return Err(Error::Config {
+                message: "the admin data browser is only served in debug mode".to_string(),
+            });
+        }
+        if self.token.is_empty() {
+            return Err(Error::Config {
+                message: "admin token must not be empty".to_string(),
+            });
+        }
+        let index_path = if self.prefix.is_empty() {
+            "/".to_string()
+        } else {
+            self.prefix.clone()
+        };
+        let browser = self.clone();
+        server.add_route(
+            Method::Get,
+            &index_path,
+            self.guarded(move |_req, _params| Box::pin(browser.clone().index())),
+            false,
+        )?;
+        let browser = self.clone();
+        server.add_route(
+            Method::Get,
+            &format!("{}/{{db}}/tables/{{table}}", self.prefix),
+            self.guarded(move |req, params| {
+                let page = req
+                    .query_map()
+                    .get("page")
+                    .and_then(|p| p.parse().ok())
+                    .unwrap_or(1u64)
+                    .max(1);
+                let browser = browser.clone();
+                Box::pin(async move { browser.table(&params[0], &params[1], page).await })
+            }),
+            false,
+        )?;
+        for method in [Method::Get, Method::Post] {
+            let browser = self.clone();
+            server.add_route(
+                method,
+                &format!("{}/{{db}}/query", self.prefix),
+                self.guarded(move |req, params| {
+                    let sql = req
+                        .form_data()
+                        .ok()
+                        .and_then(|form| form.field("sql").map(str::to_string))
+                        .or_else(|| req.query_map().get("sql").cloned());
+                    let browser = browser.clone();
+                    Box::pin(async move { browser.query(&params[0], sql).await })
+                }),
+                false,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Wrap `inner` so requests without the admin token get 401
+    ///
+    /// `inner` receives the `db` and `table` path parameters present.
+    fn guarded<F>(&self, inner: F) -> Handler
+    where
+        F: Fn(&PyRequest, Vec<String>) -> PageFuture + Send + Sync + 'static,
+    {
+        let token = self.token.clone();
+        Arc::new(move |req, matched| {
+            if !is_authorized(req, &token) {
+                return Box::pin(std::future::ready(
+                    PyResponse::text("Admin token required")
+                        .with_status(401)
+                        .with_header("WWW-Authenticate", r#"Basic realm="PyVectora admin""#),
+                ));
+            }
+            let params = ["db", "table"]
+                .iter()
+                .filter_map(|name| matched.params.get(name).map(|v| (*v).to_string()))
+                .collect();
+            inner(req, params)
+        })
+    }
+
+    fn pool(&self, name: &str) -> Option<&DatabasePool> {
+        self.databases
+            .iter()
+            .find(|(db, _)| db == name)
+            .map(|(_, pool)| pool)
+    }
+
+    async fn index(self) -> PyResponse {
+        let mut body = String::from("<h1>Databases</h1>");
+        if self.databases.is_empty() {
+            body.push_str("<p>No databases are registered.</p>");
+        }
+        for (name, pool) in self.databases.as_slice() {
+            let _ = write!(
+                body,
+                "<h2>{}</h2><p><a href=\"{}/{}/query\">Run a query</a></p>",
+                escape(name),
+                self.prefix,
+                escape(name)
+            );
+            match pool.table_names().await {
+                Ok(tables) if tables.is_empty() => body.push_str("<p>No tables.</p>"),
+                Ok(tables) => {
+                    body.push_str("<ul>");
+                    for table in tables {
+                        let _ = write!(
+                            body,
+                            "<li><a href=\"{}/{}/tables/{}\">{}</a></li>",
+                            self.prefix,
+                            escape(name),
+                            escape(&table),
+                            escape(&table)
+                        );
+                    }
+                    body.push_str("</ul>");
+                }
+                Err(e) => error_paragraph(&mut body, &e),
+            }
+        }
+        html_page("Databases", &body)
+    }
+
+    async fn table(self, db: &str, table: &str, page: u64) -> PyResponse {
+        let Some(pool) = self.pool(db) else {
+            return not_found(db);
+        };
+        match pool.table_names().await {
+            Ok(tables) if tables.iter().any(|t| t == table) => {}
+            Ok(_) => return not_found(table),
+            Err(e) => return database_error(&e),
+        }
+        let page_query = query::select_page(
+            pool.dialect(),
+            table,
+            &[],
+            None,
+            &Page {
+                limit: PAGE_SIZE,
+                offset: (page - 1).saturating_mul(PAGE_SIZE),
+                ..Page::default()
+            },
+        );
+        let rows = match page_query {
+            Ok(page_query) => pool.fetch_read_only(&page_query).await,
+            Err(e) => Err(e),
+        };
+        let total = match query::count_active(pool.dialect(), table, &[], None) {
+            Ok(count) => pool.fetch_all_query(&count).await.ok().and_then(|rows| {
+                match rows.first().and_then(|row| row.get("count")) {
+                    Some(DbValue::Int(n)) => u64::try_from(*n).ok(),
+                    _ => None,
+                }
+            }),
+            Err(_) => None,
+        };
+
+        let mut body = format!(
+            "<p><a href=\"{}\">Databases</a> / {}</p><h1>{}</h1>",
+            self.index_href(),
+            escape(db),
+            escape(table)
+        );
+        match rows {
+            Ok(rows) => {
+                let pages = total
+                    .map_or(page, |total| {
+                        total.saturating_add(PAGE_SIZE - 1) / PAGE_SIZE
+                    })
+                    .max(1);
+                let _ = write!(
+                    body,
+                    "<p>Page {page} of {pages}{}</p>",
+                    total.map_or_else(String::new, |total| format!(" ({total} rows)"))
+                );
+                render_rows(&mut body, &rows);
+                let base = format!("{}/{}/tables/{}", self.prefix, escape(db), escape(table));
+                body.push_str("<p>");
+                if page > 1 {
+                    let _ = write!(body, "<a href=\"{base}?page={}\">Previous</a> ", page - 1);
+                }
+                if page < pages {
+                    let _ = write!(body, "<a href=\"{base}?page={}\">Next</a>", page + 1);
+                }
+                body.push_str("</p>");
+            }
+            Err(e) => error_paragraph(&mut body, &e),
+        }
+        html_page(table, &body)
+    }
+
+    async fn query(self, db: &str, sql: Option<String>) -> PyResponse {
+        let Some(pool) = self.pool(db) else {
+            return not_found(db);
+        };
+        let sql = sql.unwrap_or_default();
+        let mut body = format!(
+            "<p><a href=\"{}\">Databases</a> / {}</p><h1>Query</h1>\
+             <form method=\"post\"><textarea name=\"sql\" rows=\"6\" cols=\"100\">{}</textarea>\
+             <p><button type=\"submit\">Run (read-only)</button></p></form>",
+            self.index_href(),
+            escape(db),
+            escape(&sql)
+        );
+        if !sql.trim().is_empty() {
+            let result = pool
+                .fetch_read_only(&SqlQuery {
+                    sql,
+                    params: Vec::new(),
+                })
+                .await;
+            match result {
+                Ok(mut rows) => {
+                    let returned = rows.rows.len();
+                    rows.rows.truncate(MAX_QUERY_ROWS);
+                    let _ = write!(body, "<p>{returned} rows</p>");
+                    if returned > MAX_QUERY_ROWS {
+                        let _ = write!(body, "<p>Showing the first {MAX_QUERY_ROWS}.</p>");
+                    }
+                    render_rows(&mut body, &rows);
+                }
+                Err(e) => error_paragraph(&mut body, &e),
+            }
+        }
+        html_page("Query", &body)
+    }
+
+    fn index_href(&self) -> &str {
+        if self.prefix.is_empty() {
+            "/"
+        } else {
+            &self.prefix
+        }
+    }
+}
+
+/// Whether `req` carries the admin token as a bearer token or Basic password
+fn is_authorized(req: &PyRequest, token: &str) -> bool {
+    let Some(header) = req.header("authorization") else {
+        return false;
+    };
+    if let Some(given) = header.strip_prefix("Bearer ") {
+        return token_matches(given, token);
+    }
+    header
+        .strip_prefix("Basic ")
+        .and_then(|basic| STANDARD.decode(basic.trim()).ok())
+        .and_then(|decoded| String::from_utf8(decoded).ok())
+        .is_some_and(|credentials| {
+            credentials
+                .split_once(':')
+                .is_some_and(|(_, password)| token_matches(password, token))
+        })
+}
+
+/// Append `rows` as an HTML table
+fn render_rows(body: &mut String, rows: &ResultSet) {
+    if rows.rows.is_empty() {
+        body.push_str("<p>No rows.</p>");
+        return;
+    }
+    body.push_str("<table><tr>");
+    for column in &rows.columns {
+        let _ = write!(body, "<th>{}</th>", escape(column));
+    }
+    body.push_str("</tr>");
+    for row in &rows.rows {
+        body.push_str("<tr>");
+        for value in row {
+            let cell = match value {
+                DbValue::Null => "<em>NULL</em>".to_string(),
+                DbValue::Int(v) => v.to_string(),
+                DbValue::Float(v) => v.to_string(),
+                DbValue::String(v) => escape(v),
+                DbValue::Bool(v) => v.to_string(),
+                DbValue::Bytes(v) => format!("<em>{} bytes</em>", v.len()),
+                DbValue::Other { text, .. } => escape(text),
+            };
+            let _ = write!(body, "<td>{cell}</td>");
+        }
+        body.push_str("</tr>");
+    }
+    body.push_str("</table>");
+}
+
+fn error_paragraph(body: &mut String, error: &Error) {
+    let _ = write!(
+        body,
+        "<p class=\"error\">{}</p>",
+        escape(&error.to_string())
+    );
+}
+
+fn not_found(name: &str) -> PyResponse {
+    let mut resp = html_page("Not Found", &format!("<p>{} not found</p>", escape(name)));
+    resp.status = 404;
+    resp
+}
+
+fn database_error(error: &Error) -> PyResponse {
+    let mut body = String::new();
+    error_paragraph(&mut body, error);
+    let mut resp = html_page("Database error", &body);
+    resp.status = 500;
+    resp
+}
+
+/// Full HTML document around `body`
+fn html_page(title: &str, body: &str) -> PyResponse {
+    PyResponse::text(format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <title>{} - PyVectora admin</title><style>\
+         body{{font-family:system-ui,sans-serif;margin:2rem}}\
+         table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:.25rem .5rem;text-align:left;vertical-align:top}}\
+         .error{{color:#b00020}}\
+         </style></head><body>{body}</body></html>",
+        escape(title)
+    ))
+    .with_header("Content-Type", "text/html; charset=utf-8")
+    .with_header("Cache-Control", "no-store")
+}
+
+/// Escape text for HTML element content and quoted attributes
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+impl ServerExtension for AdminBrowser {
+    fn name(&self) -> &'static str {
+        "admin"
+    }
+
+    fn configure(&self, server: &mut Server) -> Result<()> {
+        self.register(server)
+    }
+}
+
+impl Server {
+    /// Serve the admin data browser (debug mode only)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` outside debug mode, or an error if a route
+    /// conflicts with an existing one.
+    pub fn enable_admin(&mut self, browser: &AdminBrowser) -> Result<()> {
+        self.register_extension(Arc::new(browser.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn basic(password: &str) -> HashMap<String, String> {
+        let credentials = STANDARD.encode(format!("admin:{password}"));
+        HashMap::from([("authorization".to_string(), format!("Basic {credentials}"))])
+    }
+
+    async fn server() -> Server {
+        let pool = DatabasePool::connect_sqlite("sqlite::memory:", Some(1))
+            .await
+            .unwrap();
+        pool.execute("CREATE TABLE users (id INTEGER, name TEXT)")
+            .await
+            .unwrap();
+        for id in 1..=60 {
+            pool.execute(&format!("INSERT INTO users VALUES ({id}, '<user {id}>')"))
+                .await
+                .unwrap();
+        }
+        let mut server = Server::new("");
+        server.set_debug(true);
+        server
+            .enable_admin(&AdminBrowser::new(DEFAULT_PREFIX, "s3cret").database("main", pool))
+            .unwrap();
+        server
+    }
+
+    #[tokio::test]
+    async fn test_browser_requires_token_and_debug_mode() {
+        let server = server().await;
+        let resp = server
+            .test_request(
+                Method::Get,
+                DEFAULT_PREFIX.to_string(),
+                HashMap::new(),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 401);
+        assert!(resp.headers.contains_key("WWW-Authenticate"));
+        let resp = server
+            .test_request(Method::Get, DEFAULT_PREFIX.to_string(), basic("nope"), None)
+            .await;
+        assert_eq!(resp.status, 401);
+
+        let resp = server
+            .test_request(
+                Method::Get,
+                DEFAULT_PREFIX.to_string(),
+                basic("s3cret"),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("/_pyvectora/admin/main/tables/users"));
+
+        let mut production = Server::new("");
+        assert!(production
+            .enable_admin(&AdminBrowser::new(DEFAULT_PREFIX, "s3cret"))
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tables_are_paginated_and_escaped() {
+        let server = server().await;
+        let resp = server
+            .test_request(
+                Method::Get,
+                format!("{DEFAULT_PREFIX}/main/tables/users?page=2"),
+                basic("s3cret"),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("Page 2 of 2 (60 rows)"));
+        assert!(resp.body.contains("&lt;user 51&gt;"));
+        assert!(!resp.body.contains("<user 51>"));
+
+        let resp = server
+            .test_request(
+                Method::Get,
+                format!("{DEFAULT_PREFIX}/main/tables/missing"),
+                basic("s3cret"),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_queries_are_read_only() {
+        let server = server().await;
+        let mut headers = basic("s3cret");
+        headers.insert(
+            "content-type".to_string(),
+            "application/x-www-form-urlencoded".to_string(),
+        );
+        let run = |sql: &str| {
+            server.test_request(
+                Method::Post,
+                format!("{DEFAULT_PREFIX}/main/query"),
+                headers.clone(),
+                Some(format!("sql={sql}").into()),
+            )
+        };
+
+        let resp = run("SELECT+COUNT(*)+AS+n+FROM+users").await;
+        assert!(resp.body.contains("<td>60</td>"));
+        let resp = run("DELETE+FROM+users").await;
+        assert!(resp.body.contains("only SELECT"));
+        let resp = run("SELECT+COUNT(*)+AS+n+FROM+users").await;
+        assert!(resp.body.contains("<td>60</td>"));
+    }
+}
@@ -0,0 +1,118 @@
+//! # Startup Banner
+//!
+//! Summary of what a server is about to serve: bound address, runtime
+//! workers, middleware stack and the route table with auth flags. Logged
+//! once by `serve()` when enabled, so a 404 can be checked against the
+//! routes that were actually registered.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only collects and renders the summary; `Server` decides when to log it
+//! - **O**: New sections are added as fields without touching the server loop
+
+use crate::route_table::RouteTable;
+use std::fmt;
+use std::net::SocketAddr;
+
+/// Startup summary of a server
+#[derive(Debug, Clone)]
+pub struct StartupBanner {
+    /// `"http"` or `"https"`
+    pub scheme: &'static str,
+    /// Bound address
+    pub address: SocketAddr,
+    /// Tokio worker threads serving requests
+    pub workers: usize,
+    /// Middleware names, outermost first
+    pub middleware: Vec<&'static str>,
+    /// HTTP routes
+    pub routes: RouteTable,
+    /// WebSocket route patterns
+    pub websocket_routes: Vec<String>,
+}
+
+impl fmt::Display for StartupBanner {
+    /// Renders the summary as an aligned, multi-line table
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "PyVectora {} on {}://{}",
+            crate::VERSION,
+            self.scheme,
+            self.address
+        )?;
+        writeln!(f, "  workers:    {}", self.workers)?;
+        if self.middleware.is_empty() {
+            writeln!(f, "  middleware: (none)")?;
+        } else {
+            writeln!(f, "  middleware: {}", self.middleware.join(" -> "))?;
+        }
+
+        let rows: Vec<(&str, &str, bool)> = self
+            .routes
+            .routes
+            .iter()
+            .map(|route| {
+                (
+                    route.method.as_str(),
+                    route.path.as_str(),
+                    route.auth_required,
+                )
+            })
+            .chain(
+                self.websocket_routes
+                    .iter()
+                    .map(|path| ("WS", path.as_str(), false)),
+            )
+            .collect();
+        write!(f, "  routes ({}):", rows.len())?;
+        let width = rows
+            .iter()
+            .map(|(_, path, _)| path.len())
+            .max()
+            .unwrap_or(0);
+        for (method, path, auth) in rows {
+            if auth {
+                write!(f, "\n    {method:<7} {path:<width$}  auth")?;
+            } else {
+                write!(f, "\n    {method:<7} {path}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::route_table::RouteEntry;
+
+    #[test]
+    fn test_banner_lists_middleware_and_routes() {
+        let route = |method: &str, path: &str, auth_required| RouteEntry {
+            method: method.to_string(),
+            path: path.to_string(),
+            params: std::collections::BTreeMap::new(),
+            auth_required,
+        };
+        let banner = StartupBanner {
+            scheme: "http",
+            address: ([127, 0, 0, 1], 8000).into(),
+            workers: 4,
+            middleware: vec!["LoggingMiddleware", "CorsMiddleware"],
+            routes: RouteTable::new(vec![
+                route("POST", "/users", true),
+                route("GET", "/", false),
+            ]),
+            websocket_routes: vec!["/ws".to_string()],
+        };
+
+        let text = banner.to_string();
+        assert!(text.contains("on http://127.0.0.1:8000"));
+        assert!(text.contains("workers:    4"));
+        assert!(text.contains("middleware: LoggingMiddleware -> CorsMiddleware"));
+        assert!(
+            text.ends_with("routes (3):\n    GET     /\n    POST    /users  auth\n    WS      /ws")
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! # Request Body Streams
+//!
+//! Chunk-by-chunk access to a request body. Routes registered as streaming
+//! get the connection's body unread, so large uploads are processed as they
+//! arrive instead of being collected (and bounded by `max_body_size`)
+//! first; elsewhere the collected body is handed out as a single chunk.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only hands out body chunks in order
+//! - **O**: Any `http_body::Body` of `Bytes` can back a stream
+//! - **D**: Callers see `Bytes` chunks, not hyper frames
+
+use crate::error::{Error, RequestErrorKind, Result};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::BodyExt;
+use hyper::body::{Body, Bytes};
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Request body read chunk by chunk
+///
+/// Clones share the position: every chunk is handed out once, to
+/// whichever clone asks first.
+#[derive(Clone)]
+pub struct BodyStream {
+    source: Arc<Mutex<Source>>,
+}
+
+enum Source {
+    /// A collected body, until handed out
+    Buffered(Option<Bytes>),
+    /// A body still arriving
+    Streaming(UnsyncBoxBody<Bytes, String>),
+    /// Every chunk was handed out
+    Done,
+}
+
+impl BodyStream {
+    /// Stream the chunks of `body` as they arrive
+    pub fn new<B>(body: B) -> Self
+    where
+        B: Body<Data = Bytes> + Send + 'static,
+        B::Error: fmt::Display,
+    {
+        let body = body.map_err(|e| e.to_string()).boxed_unsync();
+        Self::from_source(Source::Streaming(body))
+    }
+
+    /// Stream an already collected body as one chunk
+    #[must_use]
+    pub fn buffered(body: Bytes) -> Self {
+        Self::from_source(Source::Buffered(Some(body).filter(|b| !b.is_empty())))
+    }
+
+    fn from_source(source: Source) -> Self {
+        Self {
+            source: Arc::new(Mutex::new(source)),
+        }
+    }
+
+    /// The next non-empty chunk, `None` once the body is complete
+    ///
+    /// Trailers are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BadRequest` (`BodyAborted`) if the body could not be
+    /// read, e.g. because the client disconnected; later calls return `None`.
+    pub async fn next_chunk(&self) -> Result<Option<Bytes>> {
+        self.source.lock().await.next_chunk().await
+    }
+}
+
+impl Source {
+    async fn next_chunk(&mut self) -> Result<Option<Bytes>> {
+        let body = match self {
+            Self::Buffered(chunk) => return Ok(chunk.take()),
+            Self::Streaming(body) => body,
+            Self::Done => return Ok(None),
+        };
+        loop {
+            match body.frame().await {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(chunk) if !chunk.is_empty() => return Ok(Some(chunk)),
+                    _ => {}
+                },
+                Some(Err(message)) => {
+                    *self = Self::Done;
+                    return Err(Error::BadRequest {
+                        kind: RequestErrorKind::BodyAborted,
+                        message,
+                    });
+                }
+                None => {
+                    *self = Self::Done;
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for BodyStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyStream").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+
+    #[tokio::test]
+    async fn test_chunks_are_handed_out_once_in_order() {
+        let frames = vec![
+            Ok::<_, std::io::Error>(Frame::data(Bytes::from("ab"))),
+            Ok(Frame::data(Bytes::new())),
+            Ok(Frame::data(Bytes::from("cd"))),
+            Ok(Frame::trailers(hyper::HeaderMap::new())),
+        ];
+        let stream = BodyStream::new(StreamBody::new(futures_util::stream::iter(frames)));
+        let other = stream.clone();
+
+        assert_eq!(stream.next_chunk().await.unwrap().unwrap(), "ab");
+        assert_eq!(other.next_chunk().await.unwrap().unwrap(), "cd");
+        assert!(stream.next_chunk().await.unwrap().is_none());
+        assert!(other.next_chunk().await.unwrap().is_none());
+
+        let buffered = BodyStream::buffered(Bytes::from("whole"));
+        assert_eq!(buffered.next_chunk().await.unwrap().unwrap(), "whole");
+        assert!(buffered.next_chunk().await.unwrap().is_none());
+        assert!(BodyStream::buffered(Bytes::new())
+            .next_chunk()
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_read_errors_end_the_stream() {
+        let frames = vec![
+            Ok(Frame::data(Bytes::from("partial"))),
+            Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionReset,
+                "connection reset",
+            )),
+        ];
+        let stream = BodyStream::new(StreamBody::new(futures_util::stream::iter(frames)));
+
+        assert_eq!(stream.next_chunk().await.unwrap().unwrap(), "partial");
+        let err = stream.next_chunk().await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::BadRequest {
+                kind: RequestErrorKind::BodyAborted,
+                ..
+            }
+        ));
+        assert!(stream.next_chunk().await.unwrap().is_none());
+    }
+}
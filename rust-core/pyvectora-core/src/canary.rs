@@ -0,0 +1,241 @@
+//! # Canary Routing
+//!
+//! Sends a percentage of a route's traffic to an alternate handler, so a
+//! new implementation of one endpoint can be released gradually. Clients
+//! are placed in one of 100 buckets that stays the same across requests —
+//! derived from a user key (a cookie or header) when one is configured,
+//! else remembered in an assignment cookie — so a client does not flip
+//! between versions. The percentage can ramp up over time.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only assigns requests to the stable or the canary handler
+//! - **O**: Wraps any pair of `Handler`s, like route requirements
+
+use crate::cookie::{SameSite, SetCookie};
+use crate::error::{Error, Result};
+use crate::server::{Handler, PyRequest};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Buckets requests are spread over (one per percent)
+const BUCKETS: u8 = 100;
+
+/// Lifetime of the assignment cookie (30 days)
+const COOKIE_MAX_AGE: i64 = 30 * 24 * 60 * 60;
+
+/// Request value identifying a user, whose hash picks the bucket
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StickyKey {
+    /// A cookie, e.g. a session or user id
+    Cookie(String),
+    /// A header, e.g. `X-User-Id` set by an auth proxy
+    Header(String),
+}
+
+/// Share of a route's traffic sent to a canary handler
+#[derive(Debug, Clone)]
+pub struct Canary {
+    percent: u8,
+    sticky: Option<StickyKey>,
+    cookie: String,
+    ramp: Option<(Instant, Duration)>,
+}
+
+/// Where a request goes, and the assignment cookie to set if it is new
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Assignment {
+    /// Whether the canary handler serves the request
+    pub canary: bool,
+    /// Cookie remembering the bucket of a client seen for the first time
+    pub set_cookie: Option<SetCookie>,
+}
+
+impl Canary {
+    /// Send `percent` (0-100) of the traffic to the canary
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `percent` is above 100.
+    pub fn new(percent: u8) -> Result<Self> {
+        if percent > BUCKETS {
+            return Err(Error::Config {
+                message: format!("canary percent must be 0-100, got {percent}"),
+            });
+        }
+        Ok(Self {
+            percent,
+            sticky: None,
+            cookie: "pv_canary".to_string(),
+            ramp: None,
+        })
+    }
+
+    /// Pick the bucket from `key` when the request carries it
+    #[must_use]
+    pub fn sticky_by(mut self, key: StickyKey) -> Self {
+        self.sticky = Some(key);
+        self
+    }
+
+    /// Name of the cookie remembering assignments (default `pv_canary`)
+    #[must_use]
+    pub fn cookie(mut self, name: impl Into<String>) -> Self {
+        self.cookie = name.into();
+        self
+    }
+
+    /// Raise the share linearly from 0 to the percentage over `duration`
+    ///
+    /// The ramp starts now. Buckets never change, so clients already on
+    /// the canary stay there while the share grows.
+    #[must_use]
+    pub fn ramp(mut self, duration: Duration) -> Self {
+        self.ramp = Some((Instant::now(), duration));
+        self
+    }
+
+    /// Percentage of traffic sent to the canary at `now`
+    #[must_use]
+    pub fn percent_at(&self, now: Instant) -> u8 {
+        let Some((start, duration)) = self.ramp else {
+            return self.percent;
+        };
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed >= duration {
+            return self.percent;
+        }
+        let share = u128::from(self.percent) * elapsed.as_millis() / duration.as_millis().max(1);
+        u8::try_from(share).unwrap_or(self.percent)
+    }
+
+    /// Assign `req` to the stable or the canary handler at `now`
+    #[must_use]
+    pub fn assign(&self, req: &PyRequest, now: Instant) -> Assignment {
+        let (bucket, set_cookie) = self.known_bucket(req).map_or_else(
+            || {
+                let bucket = bucket_of(RandomState::new().build_hasher().finish());
+                let cookie = SetCookie::new(&self.cookie, bucket.to_string())
+                    .path("/")
+                    .max_age(COOKIE_MAX_AGE)
+                    .http_only(true)
+                    .same_site(SameSite::Lax);
+                (bucket, Some(cookie))
+            },
+            |bucket| (bucket, None),
+        );
+        Assignment {
+            canary: bucket < self.percent_at(now),
+            set_cookie,
+        }
+    }
+
+    /// Wrap `stable` so the canary's share of requests runs `canary` instead
+    #[must_use]
+    pub fn split(&self, stable: Handler, canary: Handler) -> Handler {
+        let split = self.clone();
+        Arc::new(move |req, matched| {
+            let assignment = split.assign(req, Instant::now());
+            let handler = if assignment.canary { &canary } else { &stable };
+            let response = handler(req, matched);
+            let Some(cookie) = assignment.set_cookie else {
+                return response;
+            };
+            Box::pin(async move {
+                let mut response = response.await;
+                response.set_cookie(&cookie);
+                response
+            })
+        })
+    }
+
+    /// Bucket from the sticky key, else from the assignment cookie
+    fn known_bucket(&self, req: &PyRequest) -> Option<u8> {
+        let key = match &self.sticky {
+            Some(StickyKey::Cookie(name)) => req.cookie_map().remove(name),
+            Some(StickyKey::Header(name)) => req.header(name).map(str::to_string),
+            None => None,
+        };
+        if let Some(key) = key.filter(|k| !k.is_empty()) {
+            return Some(bucket_of(fnv1a(key.as_bytes())));
+        }
+        req.cookie_map()
+            .get(&self.cookie)
+            .and_then(|v| v.parse::<u8>().ok())
+            .filter(|bucket| *bucket < BUCKETS)
+    }
+}
+
+/// Bucket (0-99) of a hash
+fn bucket_of(hash: u64) -> u8 {
+    // Below 100, so always fits
+    u8::try_from(hash % u64::from(BUCKETS)).unwrap_or(0)
+}
+
+/// FNV-1a: stable across processes and releases, unlike `DefaultHasher`
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::Method;
+    use std::collections::HashMap;
+
+    fn request(headers: &[(&str, &str)]) -> PyRequest {
+        let headers = headers
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect::<HashMap<_, _>>();
+        PyRequest::new(Method::Get, "/search".to_string(), headers, None)
+    }
+
+    #[test]
+    fn test_assignment_is_sticky() {
+        assert!(Canary::new(101).is_err());
+        let canary = Canary::new(30).unwrap();
+        let now = Instant::now();
+
+        let first = canary.assign(&request(&[]), now);
+        let cookie = first.set_cookie.expect("new clients get a bucket cookie");
+        let rendered = cookie.to_string();
+        let pair = rendered.split(';').next().unwrap();
+        assert!(pair.starts_with("pv_canary=") && rendered.contains("Max-Age=2592000"));
+        let returning = request(&[("Cookie", pair)]);
+        let again = canary.assign(&returning, now);
+        assert_eq!(again.canary, first.canary);
+        assert!(again.set_cookie.is_none());
+
+        assert!(
+            canary
+                .assign(&request(&[("Cookie", "pv_canary=7")]), now)
+                .canary
+        );
+        assert!(
+            !canary
+                .assign(&request(&[("Cookie", "pv_canary=30")]), now)
+                .canary
+        );
+
+        let by_user = canary.sticky_by(StickyKey::Header("X-User-Id".into()));
+        let user = request(&[("X-User-Id", "42")]);
+        let assigned = by_user.assign(&user, now);
+        assert!(assigned.set_cookie.is_none());
+        assert_eq!(assigned.canary, bucket_of(fnv1a(b"42")) < 30);
+    }
+
+    #[test]
+    fn test_ramp_raises_the_share() {
+        let canary = Canary::new(50).unwrap().ramp(Duration::from_secs(100));
+        let (start, _) = canary.ramp.unwrap();
+        assert_eq!(canary.percent_at(start), 0);
+        assert_eq!(canary.percent_at(start + Duration::from_secs(50)), 25);
+        assert_eq!(canary.percent_at(start + Duration::from_secs(500)), 50);
+        assert_eq!(Canary::new(0).unwrap().percent_at(start), 0);
+    }
+}
@@ -0,0 +1,243 @@
+//! # Response Cookies
+//!
+//! `Set-Cookie` header values with the usual attributes. A response sends
+//! one `Set-Cookie` header per cookie, so they are appended to the
+//! response's repeated headers rather than its header map. Names and values
+//! are sent verbatim, so cookies built from caller input are checked with
+//! `SetCookie::validate` first.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only renders cookies; parsing request cookies lives in `request`
+//! - **O**: Attributes are added through builder methods
+
+use std::fmt;
+
+/// The `SameSite` cookie attribute
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// Only sent on same-site requests
+    Strict,
+    /// Also sent on top-level cross-site navigations
+    Lax,
+    /// Sent on every request (implies `Secure`)
+    None,
+}
+
+impl SameSite {
+    /// Parse `"strict"`, `"lax"` or `"none"` (case-insensitive)
+    #[must_use]
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "lax" => Some(Self::Lax),
+            "none" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Attribute value as sent on the wire
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Strict => "Strict",
+            Self::Lax => "Lax",
+            Self::None => "None",
+        }
+    }
+}
+
+/// A cookie to send in a `Set-Cookie` header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SetCookie {
+    name: String,
+    value: String,
+    max_age: Option<i64>,
+    path: Option<String>,
+    domain: Option<String>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl SetCookie {
+    /// A session cookie without attributes
+    #[must_use]
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            max_age: None,
+            path: None,
+            domain: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    /// Lifetime in seconds (`0` or less deletes the cookie)
+    #[must_use]
+    pub const fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Path the cookie is sent for
+    #[must_use]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Domain the cookie is sent to (and its subdomains)
+    #[must_use]
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Only send the cookie over HTTPS
+    #[must_use]
+    pub const fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// Hide the cookie from JavaScript
+    #[must_use]
+    pub const fn http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Restrict cross-site sending
+    #[must_use]
+    pub const fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Cookie name
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Check that the cookie renders as a single, well-formed header value
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` when the name is not an HTTP token, the value
+    /// has characters outside RFC 6265 `cookie-octet` (spaces, `;`, `"`,
+    /// `,`, `\`, controls, non-ASCII), or the path or domain contains `;` or
+    /// control characters.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        let invalid = |what: &str, value: &str| {
+            Err(crate::error::Error::Config {
+                message: format!("invalid cookie {what} {value:?}"),
+            })
+        };
+        if self.name.is_empty() || !self.name.bytes().all(is_token_char) {
+            return invalid("name", &self.name);
+        }
+        if !self.value.bytes().all(is_cookie_octet) {
+            return invalid("value", &self.value);
+        }
+        let attributes = [("path", &self.path), ("domain", &self.domain)];
+        for (what, value) in attributes {
+            if let Some(value) = value {
+                if value.chars().any(|c| c == ';' || c.is_control()) {
+                    return invalid(what, value);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SetCookie {
+    /// Renders the `Set-Cookie` header value (see `SetCookie::validate`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.max(0))?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={domain}")?;
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={path}")?;
+        }
+        if self.secure || self.same_site == Some(SameSite::None) {
+            f.write_str("; Secure")?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// RFC 6265 `cookie-octet`: printable ASCII except `"`, `,`, `;` and `\`
+const fn is_cookie_octet(byte: u8) -> bool {
+    matches!(byte, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+/// RFC 9110 `tchar`, the characters of a cookie name
+fn is_token_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_cookie_renders_attributes() {
+        let cookie = SetCookie::new("session", "abc123")
+            .max_age(3600)
+            .path("/")
+            .domain("example.com")
+            .http_only(true)
+            .same_site(SameSite::Lax);
+        assert_eq!(
+            cookie.to_string(),
+            "session=abc123; Max-Age=3600; Domain=example.com; Path=/; HttpOnly; SameSite=Lax"
+        );
+    }
+
+    #[test]
+    fn test_set_cookie_secures_same_site_none() {
+        let cookie = SetCookie::new("theme", "dark").same_site(SameSite::None);
+        assert_eq!(cookie.to_string(), "theme=dark; Secure; SameSite=None");
+        assert_eq!(SameSite::parse("STRICT"), Some(SameSite::Strict));
+        assert_eq!(SameSite::parse("sometimes"), None);
+    }
+
+    #[test]
+    fn test_set_cookie_rejects_attribute_injection() {
+        assert!(SetCookie::new("session", "abc-123_~")
+            .path("/app")
+            .domain("example.com")
+            .validate()
+            .is_ok());
+        for cookie in [
+            SetCookie::new("a", "1; Domain=evil.example"),
+            SetCookie::new("a", "two words"),
+            SetCookie::new("a", "\"quoted\""),
+            SetCookie::new("a=b", "1"),
+            SetCookie::new("", "1"),
+            SetCookie::new("a", "1").path("/; HttpOnly"),
+            SetCookie::new("a", "1").domain("example.com\r\nX-Injected: 1"),
+        ] {
+            assert!(
+                matches!(cookie.validate(), Err(crate::error::Error::Config { .. })),
+                "{cookie} was accepted"
+            );
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! # Route Deprecation
+//!
+//! Lifecycle headers for routes being retired. A deprecated route keeps
+//! working but every response announces it with `Deprecation`, `Sunset`
+//! (RFC 8594) and, when a migration guide is known, a `Link` header. Once
+//! the sunset passes the route can answer 410 Gone instead of running its
+//! handler.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only announces and enforces a route's sunset
+//! - **O**: Wraps any `Handler`, like route requirements
+
+use crate::error::{Error, Result};
+use crate::problem::ProblemDetails;
+use crate::server::{Handler, PyResponse};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Sunset of a deprecated route and what happens after it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Deprecation {
+    /// UNIX time at which the route sunsets
+    sunset: i64,
+    /// Documentation of the deprecation (e.g. a migration guide)
+    link: Option<String>,
+    /// Message of the 410 sent after the sunset (`None` = keep serving)
+    gone: Option<String>,
+}
+
+impl Deprecation {
+    /// Deprecate a route served through `last_day` (`YYYY-MM-DD`, UTC)
+    ///
+    /// The route sunsets at the start of the following day.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `last_day` is not a valid date.
+    pub fn after(last_day: &str) -> Result<Self> {
+        let days = parse_date(last_day).ok_or_else(|| Error::Config {
+            message: format!("invalid deprecation date {last_day:?}, expected YYYY-MM-DD"),
+        })?;
+        Ok(Self {
+            sunset: (days + 1) * SECONDS_PER_DAY,
+            link: None,
+            gone: None,
+        })
+    }
+
+    /// Point clients at `url` (`Link: <url>; rel="deprecation"`)
+    #[must_use]
+    pub fn link(mut self, url: impl Into<String>) -> Self {
+        self.link = Some(url.into());
+        self
+    }
+
+    /// Answer 410 Gone with `message` once the sunset has passed
+    #[must_use]
+    pub fn gone(mut self, message: impl Into<String>) -> Self {
+        self.gone = Some(message.into());
+        self
+    }
+
+    /// The sunset as an HTTP-date (`Sat, 02 Jan 2027 00:00:00 GMT`)
+    #[must_use]
+    pub fn sunset_date(&self) -> String {
+        http_date(self.sunset)
+    }
+
+    /// Whether the sunset has passed at `now`
+    #[must_use]
+    pub fn is_past(&self, now: SystemTime) -> bool {
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+        now >= self.sunset
+    }
+
+    /// Add the deprecation headers to `res`
+    pub fn announce(&self, res: &mut PyResponse) {
+        res.set_header("Deprecation", "true");
+        res.set_header("Sunset", &self.sunset_date());
+        if let Some(link) = &self.link {
+            res.append_header("Link", &format!("<{link}>; rel=\"deprecation\""));
+        }
+    }
+
+    /// The response for a request at `now`: 410 after the sunset if the
+    /// route is gone, else `None` (run the handler)
+    #[must_use]
+    pub fn check(&self, now: SystemTime) -> Option<PyResponse> {
+        let message = self.gone.as_deref().filter(|_| self.is_past(now))?;
+        let body = serde_json::json!({ "error": "Gone", "detail": message });
+        let mut res = PyResponse::json(body.to_string())
+            .with_status(410)
+            .with_problem(ProblemDetails::new(410).with_detail(message));
+        self.announce(&mut res);
+        Some(res)
+    }
+
+    /// Wrap `handler` so its responses announce the deprecation
+    #[must_use]
+    pub fn wrap(&self, handler: Handler) -> Handler {
+        let deprecation = self.clone();
+        Arc::new(move |req, matched| {
+            if let Some(resp) = deprecation.check(SystemTime::now()) {
+                return Box::pin(std::future::ready(resp));
+            }
+            let response = handler(req, matched);
+            let deprecation = deprecation.clone();
+            Box::pin(async move {
+                let mut response = response.await;
+                deprecation.announce(&mut response);
+                response
+            })
+        })
+    }
+}
+
+/// Days since 1970-01-01 of a `YYYY-MM-DD` date
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-');
+    let mut next = |len: usize| {
+        parts
+            .next()
+            .filter(|p| p.len() == len && p.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|p| p.parse::<i64>().ok())
+    };
+    let (year, month, day) = (next(4)?, next(2)?, next(2)?);
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=month_days)
+        .contains(&day)
+        .then(|| days_from_civil(year, month, day))
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date
+const fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// IMF-fixdate of a UNIX time (`Sun, 06 Nov 1994 08:49:37 GMT`)
+fn http_date(time: i64) -> String {
+    const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let days = time.div_euclid(SECONDS_PER_DAY);
+    let secs = time.rem_euclid(SECONDS_PER_DAY);
+
+    // Inverse of `days_from_civil`
+    let shifted = days + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+    let index = |n: i64| usize::try_from(n).unwrap_or_default();
+    let weekday = WEEKDAYS[index(days.rem_euclid(7))];
+    let month = MONTHS[index(month - 1)];
+    format!(
+        "{weekday}, {day:02} {month} {year} {:02}:{:02}:{:02} GMT",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-02-29"), Some(19_782));
+        assert_eq!(parse_date("2023-02-29"), None);
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("2024-1-01"), None);
+        assert_eq!(parse_date("2024-01-01T00:00"), None);
+        assert_eq!(http_date(784_111_777), "Sun, 06 Nov 1994 08:49:37 GMT");
+        assert!(Deprecation::after("2024-02-30").is_err());
+    }
+
+    #[test]
+    fn test_sunset_headers_and_gone() {
+        let deprecation = Deprecation::after("2027-01-01")
+            .unwrap()
+            .link("https://docs.example.com/v2");
+        assert_eq!(deprecation.sunset_date(), "Sat, 02 Jan 2027 00:00:00 GMT");
+
+        let sunset = UNIX_EPOCH + Duration::from_secs(1_798_848_000);
+        let before = sunset - Duration::from_secs(1);
+        assert!(!deprecation.is_past(before) && deprecation.is_past(sunset));
+        // Past the sunset, but not gone: keeps serving
+        assert!(deprecation.check(sunset).is_none());
+
+        let mut res = PyResponse::text("ok");
+        deprecation.announce(&mut res);
+        assert_eq!(res.headers["Deprecation"], "true");
+        assert_eq!(
+            res.header_values("link"),
+            vec!["<https://docs.example.com/v2>; rel=\"deprecation\""]
+        );
+
+        let deprecation = deprecation.gone("Use /v2/users");
+        assert!(deprecation.check(before).is_none());
+        let res = deprecation.check(sunset).unwrap();
+        assert_eq!(res.status, 410);
+        assert!(res.body.contains("Use /v2/users"));
+        assert_eq!(res.headers["Sunset"], "Sat, 02 Jan 2027 00:00:00 GMT");
+    }
+}
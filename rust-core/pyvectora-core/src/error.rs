@@ -3,6 +3,12 @@
 //! Centralized error types for PyVectora core.
 //! Uses `thiserror` for ergonomic error definitions.
 
+use crate::validation::ValidationErrors;
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::cell::RefCell;
+use std::fmt;
+use std::sync::Once;
 use thiserror::Error;
 
 /// Result type alias for PyVectora operations
@@ -28,6 +34,26 @@ pub enum Error {
         path: String,
     },
 
+    /// Route overlaps one registered earlier for the same method
+    #[error("Route {method} {pattern} conflicts with existing route {method} {existing}")]
+    RouteConflict {
+        /// HTTP method of both routes
+        method: String,
+        /// The pattern being registered
+        pattern: String,
+        /// The previously registered pattern it collides with
+        existing: String,
+    },
+
+    /// Typed path parameters failed to convert
+    #[error("Invalid path parameters for {path}")]
+    InvalidParams {
+        /// The request path
+        path: String,
+        /// One entry per parameter that failed to convert
+        errors: ValidationErrors,
+    },
+
     /// Invalid route pattern provided
     #[error("Invalid route pattern: {pattern}: {reason}")]
     InvalidRoutePattern {
@@ -71,6 +97,120 @@ pub enum Error {
         /// Actual size
         actual: usize,
     },
+
+    /// Request target (path and query) too long
+    #[error("URI too long: limit={limit} bytes, received={actual} bytes")]
+    UriTooLong {
+        /// Max allowed length
+        limit: usize,
+        /// Actual length
+        actual: usize,
+    },
+
+    /// Incoming request could not be parsed
+    #[error("Bad request ({kind}): {message}")]
+    BadRequest {
+        /// Which part of the request was malformed
+        kind: RequestErrorKind,
+        /// Description of the failure
+        message: String,
+    },
+
+    /// TLS certificate or key could not be loaded
+    #[error("TLS error: {message}")]
+    Tls {
+        /// Description of the failure
+        message: String,
+    },
+
+    /// Certificate could not be obtained from an ACME CA
+    #[error("ACME error: {message}")]
+    Acme {
+        /// Description of the failure
+        message: String,
+    },
+
+    /// Invalid configuration value
+    #[error("Invalid configuration: {message}")]
+    Config {
+        /// Description of the invalid value
+        message: String,
+    },
+}
+
+/// Classification of request parse failures (all answered with 400)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestErrorKind {
+    /// A header value is malformed (e.g. non-numeric `Content-Length`)
+    InvalidHeader,
+    /// The body could not be read to the end (client aborted, protocol error)
+    BodyAborted,
+    /// Percent-encoded data does not decode to valid UTF-8
+    InvalidEncoding,
+    /// A `multipart/form-data` body is malformed
+    InvalidMultipart,
+    /// The query string has more parameters than allowed
+    TooManyQueryParams,
+    /// The body of a `parse_json` route is not valid JSON
+    InvalidJson,
+}
+
+impl RequestErrorKind {
+    /// Stable identifier exposed to Python hooks
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidHeader => "invalid_header",
+            Self::BodyAborted => "body_aborted",
+            Self::InvalidEncoding => "invalid_encoding",
+            Self::InvalidMultipart => "invalid_multipart",
+            Self::TooManyQueryParams => "too_many_query_params",
+            Self::InvalidJson => "invalid_json",
+        }
+    }
+}
+
+impl fmt::Display for RequestErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Message of a caught panic (`"Unknown panic"` for non-string payloads)
+#[must_use]
+pub fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "Unknown panic".to_string())
+}
+
+thread_local! {
+    static PANIC_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+/// Record the backtrace of every panic for `take_panic_backtrace`
+///
+/// Installs a panic hook (once) that keeps the backtrace on the panicking
+/// thread, then runs the previous hook.
+pub fn capture_panic_backtraces() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            PANIC_BACKTRACE.with(|slot| *slot.borrow_mut() = Some(Backtrace::force_capture()));
+            previous(info);
+        }));
+    });
+}
+
+/// Backtrace of the last panic on this thread, once
+///
+/// `None` unless `capture_panic_backtraces` was called before the panic.
+#[must_use]
+pub fn take_panic_backtrace() -> Option<Backtrace> {
+    PANIC_BACKTRACE.with(|slot| slot.borrow_mut().take())
 }
 
 #[cfg(test)]
@@ -94,4 +234,15 @@ mod tests {
         };
         assert!(err.to_string().contains("0.0.0.0:8000"));
     }
+
+    #[test]
+    fn test_panic_message_and_backtrace() {
+        capture_panic_backtraces();
+        let payload = std::panic::catch_unwind(|| panic!("boom {}", 1)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "boom 1");
+        assert!(take_panic_backtrace().is_some());
+        assert!(take_panic_backtrace().is_none());
+        let payload = std::panic::catch_unwind(|| std::panic::panic_any(7_u8)).unwrap_err();
+        assert_eq!(panic_message(&*payload), "Unknown panic");
+    }
 }
@@ -0,0 +1,390 @@
+//! # Framework Events
+//!
+//! Process-wide bus on which the server, WebSocket acceptor, database
+//! pool, query cache and rate limiters announce what they are doing
+//! (`request_started`, `request_finished`, `handler_exception`,
+//! `ws_connected`, `db_query`, `cache`, `rate_limit`).
+//! APM and logging integrations subscribe once instead of wrapping every
+//! middleware and handler.
+//!
+//! Subscribers run synchronously on the emitting task, so they should only
+//! record the event and return. Emitting an event kind nobody subscribed
+//! to costs one atomic load.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only delivers events; emitters decide what to report
+//! - **O**: New event kinds add a variant without touching subscribers
+//! - **D**: Emitters and subscribers only share `Event`
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+use std::time::Duration;
+
+/// Kind of a framework event, used to subscribe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// A request was parsed and is about to be routed
+    RequestStarted,
+    /// A response was produced for a request
+    RequestFinished,
+    /// A handler raised an exception no exception handler answered
+    HandlerException,
+    /// A WebSocket upgrade completed
+    WsConnected,
+    /// A database query finished (successfully or not)
+    DbQuery,
+    /// A cache was looked up, or dropped entries
+    Cache,
+    /// A rate limiter allowed or denied a request
+    RateLimit,
+}
+
+impl EventKind {
+    /// Every event kind
+    pub const ALL: [Self; 7] = [
+        Self::RequestStarted,
+        Self::RequestFinished,
+        Self::HandlerException,
+        Self::WsConnected,
+        Self::DbQuery,
+        Self::Cache,
+        Self::RateLimit,
+    ];
+
+    /// Name used on the Python side (`"request_started"`, ...)
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::RequestStarted => "request_started",
+            Self::RequestFinished => "request_finished",
+            Self::HandlerException => "handler_exception",
+            Self::WsConnected => "ws_connected",
+            Self::DbQuery => "db_query",
+            Self::Cache => "cache",
+            Self::RateLimit => "rate_limit",
+        }
+    }
+
+    /// Kind named `name`, if any
+    #[must_use]
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == name)
+    }
+
+    const fn bit(self) -> u32 {
+        1 << self as u32
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A framework event
+///
+/// Serializes to a flat object tagged with `"event"`, durations in
+/// milliseconds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    /// A request was parsed and is about to be routed
+    RequestStarted {
+        /// Request id (`x-request-id`)
+        request_id: String,
+        /// HTTP method
+        method: String,
+        /// Request path
+        path: String,
+    },
+    /// A response was produced for a request
+    RequestFinished {
+        /// Request id (`x-request-id`)
+        request_id: String,
+        /// HTTP method
+        method: String,
+        /// Request path
+        path: String,
+        /// Matched route (`"GET /users/{id}"`), if any; label metrics
+        /// with this rather than `path`
+        #[serde(skip_serializing_if = "Option::is_none")]
+        route: Option<String>,
+        /// Response status code
+        status: u16,
+        /// Time from `request_started` to the response
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+    },
+    /// A handler raised an exception no exception handler answered
+    HandlerException {
+        /// Request id (`x-request-id`)
+        request_id: String,
+        /// HTTP method
+        method: String,
+        /// Request path
+        path: String,
+        /// Matched route (`"GET /users/{id}"`), if known
+        #[serde(skip_serializing_if = "Option::is_none")]
+        route: Option<String>,
+        /// Authenticated user (the JWT `sub` claim), if any
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<String>,
+        /// Exception type name
+        exception: String,
+        /// Exception message
+        message: String,
+        /// Traceback, outermost call first
+        frames: Vec<StackFrame>,
+    },
+    /// A WebSocket upgrade completed
+    WsConnected {
+        /// Request path
+        path: String,
+    },
+    /// A database query finished
+    DbQuery {
+        /// SQL text (parameters are not included)
+        sql: String,
+        /// Time spent executing the query
+        #[serde(rename = "duration_ms", serialize_with = "as_millis")]
+        duration: Duration,
+        /// Rows returned or affected (0 on error)
+        rows: u64,
+        /// Error message when the query failed
+        #[serde(skip_serializing_if = "Option::is_none")]
+        error: Option<String>,
+    },
+    /// A cache was looked up, or dropped entries
+    Cache {
+        /// Cache name (`query`)
+        cache: String,
+        /// What happened
+        outcome: CacheOutcome,
+        /// Entries concerned (1 for lookups)
+        count: u64,
+    },
+    /// A rate limiter allowed or denied a request
+    RateLimit {
+        /// Rule that decided: the tier of a tiered limiter, else `default`
+        rule: String,
+        /// Whether the request was let through
+        allowed: bool,
+    },
+}
+
+/// Result of a cache operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheOutcome {
+    /// Served from the cache
+    Hit,
+    /// Not cached (or expired): fetched and stored
+    Miss,
+    /// Expired entries dropped
+    Eviction,
+    /// Entries dropped on request
+    Invalidation,
+}
+
+impl CacheOutcome {
+    /// Name as serialized (`hit`, `miss`, ...)
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Hit => "hit",
+            Self::Miss => "miss",
+            Self::Eviction => "eviction",
+            Self::Invalidation => "invalidation",
+        }
+    }
+}
+
+impl Event {
+    /// Kind of this event
+    #[must_use]
+    pub const fn kind(&self) -> EventKind {
+        match self {
+            Self::RequestStarted { .. } => EventKind::RequestStarted,
+            Self::RequestFinished { .. } => EventKind::RequestFinished,
+            Self::HandlerException { .. } => EventKind::HandlerException,
+            Self::WsConnected { .. } => EventKind::WsConnected,
+            Self::DbQuery { .. } => EventKind::DbQuery,
+            Self::Cache { .. } => EventKind::Cache,
+            Self::RateLimit { .. } => EventKind::RateLimit,
+        }
+    }
+}
+
+/// One frame of a handler exception's traceback
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StackFrame {
+    /// Source file
+    pub filename: String,
+    /// Function name
+    pub function: String,
+    /// Line number
+    pub lineno: u32,
+    /// Source line, when available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context_line: Option<String>,
+}
+
+fn as_millis<S: serde::Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+/// Callback receiving the events it subscribed to
+pub type Subscriber = Arc<dyn Fn(&Event) + Send + Sync>;
+
+/// Handle returned by `subscribe`, used to unsubscribe
+pub type SubscriptionId = u64;
+
+struct Subscription {
+    id: SubscriptionId,
+    kind: EventKind,
+    subscriber: Subscriber,
+}
+
+/// Subscribers by event kind
+#[derive(Default)]
+pub struct EventBus {
+    subscriptions: RwLock<Vec<Subscription>>,
+    /// One bit per `EventKind` that has at least one subscriber
+    active: AtomicU32,
+    next_id: AtomicU64,
+}
+
+impl EventBus {
+    /// Empty bus
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `subscriber` for every event of `kind`
+    pub fn subscribe(&self, kind: EventKind, subscriber: Subscriber) -> SubscriptionId {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        self.subscriptions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Subscription {
+                id,
+                kind,
+                subscriber,
+            });
+        self.active.fetch_or(kind.bit(), Ordering::Release);
+        id
+    }
+
+    /// Remove a subscription; returns whether it existed
+    pub fn unsubscribe(&self, id: SubscriptionId) -> bool {
+        let mut subscriptions = self
+            .subscriptions
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        let before = subscriptions.len();
+        subscriptions.retain(|s| s.id != id);
+        let active = subscriptions.iter().fold(0, |bits, s| bits | s.kind.bit());
+        self.active.store(active, Ordering::Release);
+        subscriptions.len() != before
+    }
+
+    /// Whether anything listens to `kind`
+    ///
+    /// Emitters check this before building an event.
+    #[must_use]
+    pub fn is_active(&self, kind: EventKind) -> bool {
+        self.active.load(Ordering::Acquire) & kind.bit() != 0
+    }
+
+    /// Deliver `event` to the subscribers of its kind, in subscription order
+    pub fn emit(&self, event: &Event) {
+        let kind = event.kind();
+        if !self.is_active(kind) {
+            return;
+        }
+        // Subscribers are cloned out so they may (un)subscribe themselves
+        let subscribers: Vec<Subscriber> = self
+            .subscriptions
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|s| s.kind == kind)
+            .map(|s| Arc::clone(&s.subscriber))
+            .collect();
+        for subscriber in subscribers {
+            subscriber(event);
+        }
+    }
+}
+
+/// The process-wide bus the framework emits on
+pub fn global() -> &'static EventBus {
+    static BUS: OnceLock<EventBus> = OnceLock::new();
+    BUS.get_or_init(EventBus::new)
+}
+
+/// Whether anything listens to `kind` on the global bus
+#[must_use]
+pub fn is_active(kind: EventKind) -> bool {
+    global().is_active(kind)
+}
+
+/// Emit `event` on the global bus
+pub fn emit(event: &Event) {
+    global().emit(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_delivers_only_subscribed_kind() {
+        let bus = EventBus::new();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&seen);
+        let id = bus.subscribe(
+            EventKind::WsConnected,
+            Arc::new(move |event: &Event| sink.lock().unwrap().push(event.kind())),
+        );
+        assert!(bus.is_active(EventKind::WsConnected));
+        assert!(!bus.is_active(EventKind::DbQuery));
+
+        bus.emit(&Event::WsConnected { path: "/ws".into() });
+        bus.emit(&Event::DbQuery {
+            sql: "SELECT 1".into(),
+            duration: Duration::ZERO,
+            rows: 1,
+            error: None,
+        });
+        assert_eq!(*seen.lock().unwrap(), vec![EventKind::WsConnected]);
+
+        assert!(bus.unsubscribe(id));
+        assert!(!bus.unsubscribe(id));
+        assert!(!bus.is_active(EventKind::WsConnected));
+    }
+
+    #[test]
+    fn test_event_serializes_flat_with_millis() {
+        let event = Event::RequestFinished {
+            request_id: "1".into(),
+            method: "GET".into(),
+            path: "/users/7".into(),
+            route: Some("GET /users/{id}".into()),
+            status: 200,
+            duration: Duration::from_micros(1500),
+        };
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["event"], "request_finished");
+        assert_eq!(value["status"], 200);
+        assert_eq!(value["route"], "GET /users/{id}");
+        assert_eq!(value["duration_ms"], 1.5);
+        assert_eq!(EventKind::parse("request_finished"), Some(event.kind()));
+        assert_eq!(EventKind::parse("nope"), None);
+    }
+}
@@ -0,0 +1,164 @@
+//! # Server Extensions
+//!
+//! Compiled plugins that hook into a `Server` without forking it: they add
+//! routes and middleware while the server is built, and get called when it
+//! starts and stops.
+//!
+//! Plugin crates linked into the bindings publish a named factory with
+//! [`register_factory`]; Python apps then enable them by name with
+//! options (`app.enable_rust_extension("metrics", {...})`).
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only defines the plugin contract and the factory registry
+//! - **O**: New plugins implement `ServerExtension`; the server is unchanged
+//! - **D**: Plugins see `Server` and `RouteRegistry`, not connection internals
+
+use crate::error::{Error, Result};
+use crate::route_registry::RouteRegistry;
+use crate::server::Server;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// A plugin extending the server
+///
+/// Every hook but `name` is optional.
+pub trait ServerExtension: Send + Sync {
+    /// Unique name, used in logs and to reject double registration
+    fn name(&self) -> &'static str;
+
+    /// Add routes, middleware and settings while the server is built
+    ///
+    /// # Errors
+    ///
+    /// Returning an error aborts the registration (e.g. a route conflict).
+    fn configure(&self, _server: &mut Server) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once the server is listening, before the first connection
+    ///
+    /// `routes` can be kept to add or remove routes while serving.
+    fn on_start(&self, _routes: &RouteRegistry) {}
+
+    /// Called after open connections were drained
+    fn on_shutdown(&self) {}
+}
+
+/// Builds an extension from its JSON options
+pub type ExtensionFactory = fn(&serde_json::Value) -> Result<Arc<dyn ServerExtension>>;
+
+fn factories() -> &'static RwLock<HashMap<String, ExtensionFactory>> {
+    static FACTORIES: OnceLock<RwLock<HashMap<String, ExtensionFactory>>> = OnceLock::new();
+    FACTORIES.get_or_init(RwLock::default)
+}
+
+/// Make an extension available under `name`
+///
+/// A later registration under the same name replaces the earlier one.
+pub fn register_factory(name: &str, factory: ExtensionFactory) {
+    factories()
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(name.to_string(), factory);
+}
+
+/// Names of the registered extension factories, sorted
+#[must_use]
+pub fn available() -> Vec<String> {
+    let mut names: Vec<String> = factories()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    names
+}
+
+/// Build the extension registered under `name`
+///
+/// # Errors
+///
+/// Returns `Error::Config` if no factory has that name, or the factory's
+/// error for invalid options.
+pub fn create(name: &str, options: &serde_json::Value) -> Result<Arc<dyn ServerExtension>> {
+    let factory = factories()
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(name)
+        .copied()
+        .ok_or_else(|| Error::Config {
+            message: format!("unknown extension '{name}'"),
+        })?;
+    factory(options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::Method;
+    use crate::server::{Handler, PyResponse};
+    use std::collections::HashMap;
+
+    struct Ping {
+        reply: String,
+    }
+
+    impl ServerExtension for Ping {
+        fn name(&self) -> &'static str {
+            "ping"
+        }
+
+        fn configure(&self, server: &mut Server) -> Result<()> {
+            let reply = self.reply.clone();
+            let handler: Handler = Arc::new(move |_req, _matched| {
+                let reply = reply.clone();
+                Box::pin(async move { PyResponse::text(reply) })
+            });
+            server.add_route(Method::Get, "/ping", handler, false)
+        }
+    }
+
+    fn ping(options: &serde_json::Value) -> Result<Arc<dyn ServerExtension>> {
+        let reply = match options.get("reply") {
+            None => "pong",
+            Some(reply) => reply.as_str().ok_or_else(|| Error::Config {
+                message: "ping: `reply` must be a string".to_string(),
+            })?,
+        };
+        Ok(Arc::new(Ping {
+            reply: reply.to_string(),
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_extension_configures_server() {
+        let mut server = Server::new("");
+        server
+            .register_extension(ping(&serde_json::Value::Null).unwrap())
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/ping".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.body, "pong");
+
+        let duplicate = server.register_extension(ping(&serde_json::Value::Null).unwrap());
+        assert!(matches!(duplicate, Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_factory_registry() {
+        register_factory("test-ping", ping);
+        assert!(available().contains(&"test-ping".to_string()));
+        assert_eq!(
+            create("test-ping", &serde_json::Value::Null)
+                .unwrap()
+                .name(),
+            "ping"
+        );
+        assert!(create("test-ping", &serde_json::json!({"reply": 1})).is_err());
+        assert!(create("missing", &serde_json::Value::Null).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! # File Responses
+//!
+//! Response bodies read from a file while the client consumes them, so a
+//! large generated response (spilled to a temp file by the handler) never
+//! has to be held in memory. Single `Range` requests on `GET` are answered
+//! with `206 Partial Content`, letting clients resume interrupted downloads.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only maps a file (or buffer) and a `Range` header onto a response
+//! - **D**: File bodies flow through the same channel as other streamed bodies
+
+use crate::error::Result;
+use crate::router::Method;
+use crate::server::{Bytes, PyResponse};
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use tokio::sync::mpsc;
+use tracing::warn;
+
+/// Bytes read from the file per chunk
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunks buffered between the file and the socket
+const CHANNEL_CAPACITY: usize = 4;
+
+/// Part of a body of known length selected by a `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// No usable range: send the whole body
+    Full,
+    /// Bytes `start..=end`
+    Partial {
+        /// First byte offset
+        start: u64,
+        /// Last byte offset (inclusive)
+        end: u64,
+    },
+    /// No byte of the range exists (`416 Range Not Satisfiable`)
+    Unsatisfiable,
+}
+
+impl ByteRange {
+    /// Resolve a `Range` header against a body of `len` bytes
+    ///
+    /// Only single `bytes=` ranges are honoured; malformed headers and
+    /// multi-range requests get the whole body, as RFC 9110 allows.
+    #[must_use]
+    pub fn parse(header: Option<&str>, len: u64) -> Self {
+        let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+            return Self::Full;
+        };
+        let Some((first, last)) = spec.split_once('-').filter(|_| !spec.contains(',')) else {
+            return Self::Full;
+        };
+        let (first, last) = (first.trim(), last.trim());
+
+        if first.is_empty() {
+            // Suffix range: the last `n` bytes
+            return match last.parse::<u64>() {
+                Ok(n) if n > 0 && len > 0 => Self::Partial {
+                    start: len.saturating_sub(n),
+                    end: len - 1,
+                },
+                Ok(_) => Self::Unsatisfiable,
+                Err(_) => Self::Full,
+            };
+        }
+        let Ok(start) = first.parse::<u64>() else {
+            return Self::Full;
+        };
+        let end = if last.is_empty() {
+            u64::MAX
+        } else {
+            match last.parse::<u64>() {
+                Ok(end) if end >= start => end,
+                _ => return Self::Full,
+            }
+        };
+        if start >= len {
+            return Self::Unsatisfiable;
+        }
+        Self::Partial {
+            start,
+            end: end.min(len - 1),
+        }
+    }
+
+    /// Byte offset and length of the selected part of a `len`-byte body
+    const fn span(self, len: u64) -> (u64, u64) {
+        match self {
+            Self::Partial { start, end } => (start, end - start + 1),
+            Self::Full | Self::Unsatisfiable => (0, len),
+        }
+    }
+}
+
+/// Response sending `body`, or the part of it selected by `range`
+///
+/// `range` only applies to `GET` requests; other methods get the whole body.
+#[must_use]
+pub fn bytes_response(
+    body: Bytes,
+    content_type: &str,
+    method: Method,
+    range: Option<&str>,
+) -> PyResponse {
+    let len = body.len() as u64;
+    let selected = ByteRange::parse(range_of(method, range), len);
+    let part = match selected {
+        ByteRange::Partial { start, end } => {
+            // Offsets are within `body`, so they fit in usize
+            let offset = |n: u64| usize::try_from(n).unwrap_or(usize::MAX);
+            body.slice(offset(start)..=offset(end))
+        }
+        ByteRange::Full => body,
+        ByteRange::Unsatisfiable => return unsatisfiable(content_type, len),
+    };
+    with_range_headers(PyResponse::binary(part, content_type), selected, len)
+}
+
+/// Response streaming the file at `path`, or the part selected by `range`
+///
+/// As with `bytes_response`, `range` only applies to `GET` requests. With `remove_after`, the file is deleted once it has been sent, or as
+/// soon as the client goes away.
+///
+/// # Errors
+///
+/// Returns `Error::Io` if the file cannot be opened or sought; it is still
+/// removed when `remove_after` is set.
+pub async fn file_response(
+    path: impl Into<PathBuf>,
+    content_type: &str,
+    method: Method,
+    range: Option<&str>,
+    remove_after: bool,
+) -> Result<PyResponse> {
+    let path = path.into();
+    let cleanup = remove_after.then(|| RemoveOnDrop(path.clone()));
+
+    let mut file = tokio::fs::File::open(&path).await?;
+    let len = file.metadata().await?.len();
+    let selected = ByteRange::parse(range_of(method, range), len);
+    if selected == ByteRange::Unsatisfiable {
+        return Ok(unsatisfiable(content_type, len));
+    }
+    let (start, count) = selected.span(len);
+    file.seek(SeekFrom::Start(start)).await?;
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::spawn(async move {
+        let mut remaining = count;
+        while remaining > 0 {
+            let size = usize::try_from(remaining).map_or(CHUNK_SIZE, |r| r.min(CHUNK_SIZE));
+            let mut chunk = vec![0; size];
+            match file.read(&mut chunk).await {
+                Ok(0) => break,
+                Ok(read) => {
+                    chunk.truncate(read);
+                    remaining -= read as u64;
+                    if tx.send(Bytes::from(chunk)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    warn!("Reading response file {} failed: {}", path.display(), e);
+                    break;
+                }
+            }
+        }
+        // Gone before the receiver sees the end of the body
+        drop(cleanup);
+        drop(tx);
+    });
+
+    let response =
+        PyResponse::streaming(rx, content_type).with_header("Content-Length", &count.to_string());
+    Ok(with_range_headers(response, selected, len))
+}
+
+/// `Range` header of a request, ignored unless it is a `GET`
+fn range_of(method: Method, range: Option<&str>) -> Option<&str> {
+    range.filter(|_| method == Method::Get)
+}
+
+fn with_range_headers(response: PyResponse, selected: ByteRange, len: u64) -> PyResponse {
+    let response = response.with_header("Accept-Ranges", "bytes");
+    match selected {
+        ByteRange::Partial { start, end } => response
+            .with_status(206)
+            .with_header("Content-Range", &format!("bytes {start}-{end}/{len}")),
+        ByteRange::Full | ByteRange::Unsatisfiable => response,
+    }
+}
+
+fn unsatisfiable(content_type: &str, len: u64) -> PyResponse {
+    PyResponse::binary(Bytes::new(), content_type)
+        .with_status(416)
+        .with_header("Accept-Ranges", "bytes")
+        .with_header("Content-Range", &format!("bytes */{len}"))
+}
+
+/// Deletes a file when dropped
+struct RemoveOnDrop(PathBuf);
+
+impl Drop for RemoveOnDrop {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ranges_select_part_of_the_body() {
+        assert_eq!(ByteRange::parse(None, 10), ByteRange::Full);
+        assert_eq!(
+            ByteRange::parse(Some("bytes=2-5"), 10),
+            ByteRange::Partial { start: 2, end: 5 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=4-"), 10),
+            ByteRange::Partial { start: 4, end: 9 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=-3"), 10),
+            ByteRange::Partial { start: 7, end: 9 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=8-100"), 10),
+            ByteRange::Partial { start: 8, end: 9 }
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=10-"), 10),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(
+            ByteRange::parse(Some("bytes=-0"), 10),
+            ByteRange::Unsatisfiable
+        );
+        assert_eq!(ByteRange::parse(Some("bytes=5-2"), 10), ByteRange::Full);
+        assert_eq!(ByteRange::parse(Some("bytes=0-1,4-5"), 10), ByteRange::Full);
+        assert_eq!(ByteRange::parse(Some("items=0-1"), 10), ByteRange::Full);
+
+        let body = Bytes::from("0123456789");
+        let mut resp = bytes_response(body.clone(), "text/plain", Method::Get, Some("bytes=-3"));
+        assert_eq!(resp.status, 206);
+        assert_eq!(resp.headers["Content-Range"], "bytes 7-9/10");
+        resp.collect_stream().await;
+        assert_eq!(resp.body, "789");
+
+        let mut resp = bytes_response(body, "text/plain", Method::Post, Some("bytes=-3"));
+        assert_eq!(resp.status, 200);
+        assert!(!resp.headers.contains_key("Content-Range"));
+        resp.collect_stream().await;
+        assert_eq!(resp.body, "0123456789");
+    }
+
+    #[tokio::test]
+    async fn test_file_is_streamed_and_removed() {
+        let path = std::env::temp_dir().join(format!("pyvectora-test-{}.txt", std::process::id()));
+        std::fs::write(&path, "0123456789").unwrap();
+
+        let mut resp = file_response(&path, "text/plain", Method::Get, Some("bytes=2-5"), true)
+            .await
+            .unwrap();
+        assert_eq!(resp.status, 206);
+        assert_eq!(resp.headers["Content-Length"], "4");
+        assert_eq!(resp.headers["Content-Range"], "bytes 2-5/10");
+        resp.collect_stream().await;
+        assert_eq!(resp.body, "2345");
+        assert!(!path.exists());
+
+        std::fs::write(&path, "0123456789").unwrap();
+        let resp = file_response(&path, "text/plain", Method::Get, Some("bytes=20-"), false)
+            .await
+            .unwrap();
+        assert_eq!(resp.status, 416);
+        assert_eq!(resp.headers["Content-Range"], "bytes */10");
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,211 @@
+//! # Route Groups
+//!
+//! Sub-routers sharing a path prefix, middleware and an auth flag, merged
+//! into the server's router when mounted.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only collects routes and applies group-level settings
+//! - **O**: Groups nest; each level adds its prefix and middleware
+//! - **D**: Group middleware wraps handlers, the server's router stays flat
+
+use crate::middleware::{Middleware, MiddlewareChain};
+use crate::router::Method;
+use crate::server::Handler;
+
+/// Join a group prefix and a route path into a normalized path
+///
+/// `join_path("/api/v1/", "users")` is `"/api/v1/users"`; a `"/"` route
+/// maps to the prefix itself.
+#[must_use]
+pub fn join_path(prefix: &str, path: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+    let path = path.trim_start_matches('/');
+    let mut joined = String::with_capacity(prefix.len() + path.len() + 2);
+    if !prefix.is_empty() && !prefix.starts_with('/') {
+        joined.push('/');
+    }
+    joined.push_str(prefix);
+    if !path.is_empty() || joined.is_empty() {
+        joined.push('/');
+        joined.push_str(path);
+    }
+    joined
+}
+
+/// A route collected by a group
+pub struct GroupRoute {
+    /// HTTP method
+    pub method: Method,
+    /// Full path, including the group prefix
+    pub path: String,
+    /// Handler, wrapped with the group middleware
+    pub handler: Handler,
+    /// Whether JWT authentication is required
+    pub auth_required: bool,
+}
+
+/// Routes sharing a prefix, middleware and auth requirement
+///
+/// # Example
+///
+/// ```ignore
+/// let mut api = RouteGroup::new("/api/v1").with_auth(true);
+/// api.add_middleware(TimingMiddleware::new());
+/// api.add_route(Method::Get, "/users", handler, false);
+/// server.mount(api)?;
+/// ```
+#[derive(Default)]
+pub struct RouteGroup {
+    prefix: String,
+    auth_required: bool,
+    middleware: MiddlewareChain,
+    routes: Vec<GroupRoute>,
+}
+
+impl RouteGroup {
+    /// Create an empty group with a path prefix
+    #[must_use]
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Require authentication for every route in the group
+    #[must_use]
+    pub const fn with_auth(mut self, auth_required: bool) -> Self {
+        self.auth_required = auth_required;
+        self
+    }
+
+    /// Group prefix
+    #[must_use]
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Add middleware applied to the group's routes only
+    ///
+    /// Must be added before routes; it wraps handlers as they are added.
+    pub fn add_middleware<M: Middleware + 'static>(&mut self, middleware: M) {
+        self.middleware.add(middleware);
+    }
+
+    /// Add a route relative to the group prefix
+    pub fn add_route(&mut self, method: Method, path: &str, handler: Handler, auth_required: bool) {
+        self.routes.push(GroupRoute {
+            method,
+            path: join_path(&self.prefix, path),
+            handler: self.middleware.wrap(handler),
+            auth_required: auth_required || self.auth_required,
+        });
+    }
+
+    /// Nest another group under this one
+    pub fn nest(&mut self, group: Self) {
+        for route in group.routes {
+            self.add_route(
+                route.method,
+                &route.path,
+                route.handler,
+                route.auth_required,
+            );
+        }
+    }
+
+    /// Number of routes in the group
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.routes.len()
+    }
+
+    /// Whether the group has no routes
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.routes.is_empty()
+    }
+
+    /// Consume the group into its routes
+    #[must_use]
+    pub fn into_routes(self) -> Vec<GroupRoute> {
+        self.routes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::MiddlewareResult;
+    use crate::server::{PyRequest, PyResponse, Server};
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    struct Deny;
+
+    impl Middleware for Deny {
+        fn before_request(&self, _req: &mut PyRequest) -> MiddlewareResult {
+            MiddlewareResult::Respond(PyResponse::text("denied").with_status(403))
+        }
+    }
+
+    fn ok_handler() -> Handler {
+        Arc::new(|_req, _matched| Box::pin(async { PyResponse::text("ok") }))
+    }
+
+    #[test]
+    fn test_join_path() {
+        assert_eq!(join_path("/api/v1/", "users"), "/api/v1/users");
+        assert_eq!(join_path("api", "/users/"), "/api/users/");
+        assert_eq!(join_path("/api", "/"), "/api");
+        assert_eq!(join_path("", "/"), "/");
+        assert_eq!(join_path("", "health"), "/health");
+    }
+
+    #[test]
+    fn test_nested_groups_combine_prefix_and_auth() {
+        let mut users = RouteGroup::new("/users").with_auth(true);
+        users.add_route(Method::Get, "/{id:int}", ok_handler(), false);
+        let mut api = RouteGroup::new("/api/v1");
+        api.add_route(Method::Get, "/health", ok_handler(), false);
+        api.nest(users);
+
+        let routes = api.into_routes();
+        let summary: Vec<_> = routes
+            .iter()
+            .map(|r| (r.path.as_str(), r.auth_required))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("/api/v1/health", false), ("/api/v1/users/{id:int}", true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_group_middleware_only_applies_to_group_routes() {
+        let mut admin = RouteGroup::new("/admin");
+        admin.add_middleware(Deny);
+        admin.add_route(Method::Get, "/stats", ok_handler(), false);
+
+        let mut server = Server::new("");
+        server
+            .add_route(Method::Get, "/public", ok_handler(), false)
+            .unwrap();
+        server.mount(admin).unwrap();
+
+        let resp = server
+            .test_request(
+                Method::Get,
+                "/admin/stats".to_string(),
+                HashMap::new(),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 403);
+        let resp = server
+            .test_request(Method::Get, "/public".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.body, "ok");
+    }
+}
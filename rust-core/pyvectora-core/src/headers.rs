@@ -0,0 +1,152 @@
+//! # Request Headers
+//!
+//! Read-only, case-insensitive mapping over a request's `HeaderMap` for
+//! Python. Lookups read the Rust map directly instead of copying every
+//! header into a new dict on each `request.headers` access.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only exposes headers; the request owns and mutates them
+//! - **L**: Behaves like a read-only `dict` (`[]`, `get`, `in`, iteration)
+
+use hyper::HeaderMap;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyKeyError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::{PyIterator, PyList};
+use std::sync::Arc;
+
+/// Request headers as a read-only mapping
+///
+/// Names are matched case-insensitively and iterated lowercase. For a
+/// repeated header the last value wins, as it did with the plain dict;
+/// `get_all` returns every value. Values that are not valid UTF-8 are
+/// left out (see `Request.raw_headers`).
+#[cfg_attr(feature = "python", pyclass(name = "Headers"))]
+#[derive(Debug, Clone)]
+pub struct PyHeaders {
+    headers: Arc<HeaderMap>,
+}
+
+impl PyHeaders {
+    /// Wrap a request's headers without copying them
+    #[must_use]
+    pub const fn new(headers: Arc<HeaderMap>) -> Self {
+        Self { headers }
+    }
+
+    /// The last UTF-8 value of `name` (case-insensitive)
+    #[must_use]
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get_all(name)
+            .iter()
+            .rev()
+            .find_map(|v| v.to_str().ok())
+    }
+
+    /// Lowercase names that have a UTF-8 value, once each
+    #[must_use]
+    pub fn names(&self) -> Vec<&str> {
+        self.headers
+            .keys()
+            .map(hyper::header::HeaderName::as_str)
+            .filter(|name| self.value(name).is_some())
+            .collect()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl PyHeaders {
+    fn __getitem__(&self, name: &str) -> PyResult<String> {
+        self.value(name)
+            .map(String::from)
+            .ok_or_else(|| PyKeyError::new_err(name.to_string()))
+    }
+
+    /// Get a header value, or `default` if it is missing
+    #[pyo3(signature = (name, default=None))]
+    fn get(&self, py: Python<'_>, name: &str, default: Option<PyObject>) -> PyObject {
+        self.value(name).map_or_else(
+            || default.unwrap_or_else(|| py.None()),
+            |value| value.into_py(py),
+        )
+    }
+
+    /// Get every value of a header, in arrival order
+    fn get_all(&self, name: &str) -> Vec<String> {
+        self.headers
+            .get_all(name)
+            .iter()
+            .filter_map(|v| v.to_str().ok().map(String::from))
+            .collect()
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.value(name).is_some()
+    }
+
+    fn __len__(&self) -> usize {
+        self.names().len()
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<Py<PyIterator>> {
+        Ok(PyIterator::from_object(PyList::new(py, self.names()))?.into())
+    }
+
+    /// Header names, lowercase
+    fn keys(&self) -> Vec<&str> {
+        self.names()
+    }
+
+    /// Header values, in the order of `keys()`
+    fn values(&self) -> Vec<&str> {
+        self.names()
+            .into_iter()
+            .filter_map(|name| self.value(name))
+            .collect()
+    }
+
+    /// `(name, value)` pairs, in the order of `keys()`
+    fn items(&self) -> Vec<(&str, &str)> {
+        self.names()
+            .into_iter()
+            .filter_map(|name| self.value(name).map(|value| (name, value)))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        let items: Vec<String> = self
+            .items()
+            .into_iter()
+            .map(|(name, value)| format!("{name:?}: {value:?}"))
+            .collect();
+        format!("Headers({{{}}})", items.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup_is_case_insensitive_and_last_value_wins() {
+        let mut map = HeaderMap::new();
+        map.insert("content-type", "application/json".parse().unwrap());
+        map.append("x-forwarded-for", "10.0.0.1".parse().unwrap());
+        map.append("x-forwarded-for", "10.0.0.2".parse().unwrap());
+        map.insert(
+            "x-binary",
+            hyper::header::HeaderValue::from_bytes(b"\xff").unwrap(),
+        );
+        let headers = PyHeaders::new(Arc::new(map));
+
+        assert_eq!(headers.value("Content-Type"), Some("application/json"));
+        assert_eq!(headers.value("x-forwarded-for"), Some("10.0.0.2"));
+        assert_eq!(headers.value("x-binary"), None);
+        assert_eq!(headers.names(), vec!["content-type", "x-forwarded-for"]);
+    }
+}
@@ -11,47 +11,176 @@
 //! ## Modules
 //!
 //! - `server` - HTTP server built on Hyper
+//! - `listener` - TCP and Unix socket addresses a server accepts on
 //! - `router` - High-performance routing using matchit (radix trie)
+//! - `runtime` - Tokio runtime configuration (threads, names, stacks)
 //! - `route` - Route metadata and information
+//! - `route_table` - Route table export, import and diffing
+//! - `route_registry` - Routes that can change while serving
+//! - `suggest` - Nearest-route hints for requests that match no route
+//! - `banner` - Startup summary of address, middleware and routes
+//! - `group` - Route groups with shared prefix, middleware and auth
+//! - `extension` - Compiled server plugins and their registry
 //! - `request` - HTTP request wrapper with headers and query parsing
+//! - `request_state` - Request-scoped values set by middleware for handlers
+//! - `body_stream` - Chunk-by-chunk request bodies for streaming routes
+//! - `canary` - Sticky percentage split of a route between two handlers
+//! - `headers` - Read-only request header mapping for Python
+//! - `multipart` - Streaming form parsing with spooled file uploads
+//! - `cookie` - `Set-Cookie` rendering for responses
+//! - `file_response` - File-backed response bodies with `Range` support
+//! - `session` - HMAC-signed (optionally encrypted) cookie sessions
 //! - `middleware` - Request/response middleware system
+//! - `sanitize` - Final header policy applied to every response
+//! - `read_only` - Switchable read-only mode for requests and database pools
 //! - `json` - High-performance JSON parsing with simd-json
 //! - `validation` - Structured validation errors
+//! - `requirements` - Per-route content type and header preconditions
+//! - `deprecation` - `Deprecation`/`Sunset` headers and 410 for retired routes
+//! - `problem` - RFC 7807 problem+json error bodies
+//! - `retry` - Retry policies with backoff and jitter
+//! - `sse` - Server-Sent Events framing and keep-alive
+//! - `csv` - CSV record encoding and streaming exports
+//! - `tls` - rustls-based HTTPS termination
+//! - `acme` - ACME HTTP-01 challenge serving
+//! - `acme_client` - Certificate issuance and renewal from an ACME CA
+//! - `signals` - Signals that trigger graceful shutdown (Unix and Windows)
+//! - `supervisor` - Worker process supervision and restarts (Unix)
 //! - `state` - Thread-safe application state
 //! - `database` - SQLx database connectivity (SQLite, PostgreSQL)
+//! - `query` - Parameterized, soft-delete aware table queries
+//! - `query_cache` - TTL cache of query results with `ETag`s and invalidation
+//! - `secrets` - Secret files and variables substituted into connection URLs
 //! - `types` - Path parameter types and conversion
+//! - `websocket` - WebSocket upgrade handling
+//! - `accounting` - Per-route handler cost aggregation
+//! - `watchdog` - RSS memory limits (refuse work, recycle)
+//! - `metrics` - Prometheus request metrics labelled by route pattern
+//! - `profiling` - Admin-only runtime metrics and CPU profiles
+//! - `admin` - Admin-only HTML browser over registered databases
+//! - `events` - Framework event bus for APM and logging integrations
+//! - `log_context` - Request correlation fields on log lines
+//! - `log_bridge` - Log records between `tracing` and another logging system
+//! - `reporting` - Sentry-compatible reporting of handler exceptions and panics
 //! - `error` - Error types and handling
+//!
+//! ## Features
+//!
+//! - `python` (default) - `#[pyclass]` `Request`, `Headers`, `Session` and
+//!   `UploadedFile` types for the bindings. Build with `default-features = false`
+//!   to use the server, router and middleware as a pure-Rust framework without PyO3.
+//! - `profiling` - pprof CPU profiles on the profiling endpoints
 
 #![warn(missing_docs)]
 #![warn(clippy::all)]
 #![warn(clippy::pedantic)]
 
+pub mod accounting;
+pub mod acme;
+pub mod acme_client;
+pub mod admin;
+pub mod banner;
+pub mod body_stream;
+pub mod canary;
+pub mod cookie;
+pub mod csv;
 pub mod database;
+pub mod deprecation;
 pub mod error;
+pub mod events;
+pub mod extension;
+pub mod file_response;
+pub mod group;
+pub mod headers;
 pub mod json;
+pub mod listener;
+pub mod log_bridge;
+pub mod log_context;
+pub mod metrics;
 pub mod middleware;
+pub mod multipart;
+pub mod problem;
+pub mod profiling;
+pub mod query;
+pub mod query_cache;
+pub mod read_only;
+pub mod reporting;
 pub mod request;
+pub mod request_state;
+pub mod requirements;
+pub mod retry;
 pub mod route;
+pub mod route_registry;
+pub mod route_table;
 pub mod router;
+pub mod runtime;
+pub mod sanitize;
+pub mod secrets;
 pub mod server;
+pub mod session;
+pub mod signals;
+pub mod sse;
 pub mod state;
+pub mod suggest;
+#[cfg(unix)]
+pub mod supervisor;
+pub mod tls;
 pub mod types;
 pub mod validation;
+pub mod watchdog;
+pub mod websocket;
 
-pub use database::{DatabasePool, DbValue};
-pub use error::{Error, Result};
+pub use accounting::{GilMetrics, HandlerSample, ResourceAccounting, RouteStats};
+pub use acme_client::{AcmeClient, AcmeConfig, AcmeRenewal};
+pub use admin::AdminBrowser;
+pub use banner::StartupBanner;
+pub use body_stream::BodyStream;
+pub use canary::{Canary, StickyKey};
+pub use cookie::{SameSite, SetCookie};
+pub use database::{DatabasePool, DbValue, ResultSet};
+pub use deprecation::Deprecation;
+pub use error::{Error, RequestErrorKind, Result};
+pub use events::{Event, EventBus, EventKind, StackFrame};
+pub use extension::ServerExtension;
+pub use file_response::ByteRange;
+pub use group::RouteGroup;
+pub use headers::PyHeaders;
 pub use json::{parse_json, to_json};
+pub use listener::{BindAddress, SocketOptions, UnixSocket};
+pub use log_bridge::{ForwardingLayer, LogRecord};
+pub use log_context::LogContext;
+pub use metrics::{MetricLabel, RequestMetrics};
 pub use middleware::{
-    CorsMiddleware, LoggingMiddleware, Middleware, MiddlewareChain, RateLimitMiddleware,
-    TimingMiddleware,
+    CompressionMiddleware, CorsMiddleware, LoggingMiddleware, Middleware, MiddlewareChain,
+    RateLimitMiddleware, TierLimit, TieredRateLimitMiddleware, TimingMiddleware,
 };
-pub use request::PyRequest;
-pub use route::RouteInfo;
-pub use router::Router;
+pub use multipart::{FormData, MultipartLimits, UploadedFile};
+pub use problem::{ErrorFormat, ProblemDetails};
+pub use query::{Dialect, Page, SoftDelete, SoftDeleteConfig, SqlQuery};
+pub use query_cache::{CachedRows, QueryCache};
+pub use read_only::ReadOnlyMode;
+pub use reporting::{ErrorReporter, ReporterConfig};
+pub use request::{PyRequest, TargetLimits};
+pub use request_state::{RequestState, StateValue};
+pub use requirements::RouteRequirements;
+pub use retry::{Backoff, RetryPolicy};
+pub use route::{RouteInfo, RouteLimits};
+pub use route_registry::RouteRegistry;
+pub use route_table::{RouteDiff, RouteTable};
+pub use router::{ParamMismatch, Router};
+pub use runtime::RuntimeConfig;
+pub use sanitize::ResponsePolicy;
+pub use secrets::{ResolvedUrl, SecretStore};
 pub use server::Server;
+pub use session::{Session, SessionMiddleware};
+pub use signals::ShutdownSignal;
+pub use sse::SseEvent;
 pub use state::{AppState, TypeState};
+pub use suggest::RouteSuggestion;
+pub use tls::{CertStore, SniCert, TlsConfig};
 pub use types::{ParamType, ParamValue};
 pub use validation::{FieldError, ValidationCode, ValidationErrors, ValidationResult};
+pub use websocket::{WebSocket, WsHandler};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,568 @@
+//! # Listeners
+//!
+//! Addresses a server accepts connections on. Its main address is a TCP
+//! address or, on Unix, a Unix domain socket (the usual deployment behind
+//! nginx or envoy on the same host); further TCP addresses and Unix sockets
+//! can be added. Every listener feeds the same router; TCP listeners and
+//! the connections they accept are tuned by `SocketOptions`.
+//!
+//! With socket activation the main listeners are instead inherited from
+//! systemd (`LISTEN_FDS`): it binds them, even to privileged ports, before
+//! the server starts and keeps them open across restarts, so deploys drop
+//! no connections.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only binds addresses and accepts connections
+//! - **D**: The server serves a `Connection`, unaware of the socket family
+
+use crate::error::{Error, Result};
+use futures_util::future::{self, FutureExt};
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::net::{TcpListener, TcpSocket, TcpStream};
+use tracing::debug;
+
+/// Accepted stream: TCP, or a Unix domain socket
+#[cfg(unix)]
+pub type Connection = tokio_util::either::Either<TcpStream, tokio::net::UnixStream>;
+
+/// Accepted stream
+#[cfg(not(unix))]
+pub type Connection = TcpStream;
+
+/// An address to accept connections on
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BindAddress {
+    /// TCP socket address (`127.0.0.1:8000`, `[::]:8000`)
+    Tcp(SocketAddr),
+    /// Unix domain socket path (`unix:/run/app.sock`; Unix only)
+    Unix(PathBuf),
+}
+
+impl BindAddress {
+    /// Parse `host:port` (an IP address) or `unix:<path>`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` for anything else.
+    pub fn parse(address: &str) -> Result<Self> {
+        if let Some(path) = address.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(Error::Config {
+                    message: "unix: listener needs a socket path".to_string(),
+                });
+            }
+            return Ok(Self::Unix(PathBuf::from(path)));
+        }
+        address.parse().map(Self::Tcp).map_err(|_| Error::Config {
+            message: format!("invalid listen address {address:?} (expected ip:port or unix:/path)"),
+        })
+    }
+}
+
+/// Options of TCP listeners and the connections they accept
+///
+/// Unset options keep the operating system's defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SocketOptions {
+    /// Length of the queue of connections not yet accepted (default 1024)
+    pub backlog: u32,
+    /// Send small writes immediately instead of coalescing them (Nagle's
+    /// algorithm off)
+    pub nodelay: bool,
+    /// Idle time before TCP keepalive probes start; enables keepalive
+    pub keepalive_idle: Option<Duration>,
+    /// Time between keepalive probes (where the platform supports it)
+    pub keepalive_interval: Option<Duration>,
+    /// `SO_RCVBUF` in bytes
+    pub recv_buffer_size: Option<u32>,
+    /// `SO_SNDBUF` in bytes
+    pub send_buffer_size: Option<u32>,
+}
+
+impl Default for SocketOptions {
+    fn default() -> Self {
+        Self {
+            backlog: 1024,
+            nodelay: false,
+            keepalive_idle: None,
+            keepalive_interval: None,
+            recv_buffer_size: None,
+            send_buffer_size: None,
+        }
+    }
+}
+
+impl SocketOptions {
+    /// Apply the per-connection options to an accepted stream
+    fn apply(&self, stream: &TcpStream) -> std::io::Result<()> {
+        if self.nodelay {
+            stream.set_nodelay(true)?;
+        }
+        let socket = socket2::SockRef::from(stream);
+        if let Some(idle) = self.keepalive_idle {
+            let keepalive = socket2::TcpKeepalive::new().with_time(idle);
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "android",
+                target_os = "macos",
+                target_os = "ios",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "windows"
+            ))]
+            let keepalive = match self.keepalive_interval {
+                Some(interval) => keepalive.with_interval(interval),
+                None => keepalive,
+            };
+            socket.set_tcp_keepalive(&keepalive)?;
+        }
+        if let Some(size) = self.recv_buffer_size {
+            socket.set_recv_buffer_size(size as usize)?;
+        }
+        if let Some(size) = self.send_buffer_size {
+            socket.set_send_buffer_size(size as usize)?;
+        }
+        Ok(())
+    }
+}
+
+/// A Unix domain socket to serve on instead of a TCP address
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnixSocket {
+    /// Socket file path
+    pub path: PathBuf,
+    /// Permission bits of the socket file (e.g. `0o660` so only the proxy's
+    /// group can connect); the umask decides when `None`
+    pub mode: Option<u32>,
+}
+
+impl UnixSocket {
+    /// Socket at `path` with default permissions
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            mode: None,
+        }
+    }
+
+    /// Set the permission bits of the socket file
+    #[must_use]
+    pub const fn mode(mut self, mode: u32) -> Self {
+        self.mode = Some(mode);
+        self
+    }
+
+    /// Bind the socket and apply its permissions
+    pub(crate) fn bind(&self) -> Result<Listener> {
+        let address = BindAddress::Unix(self.path.clone());
+        let listener = Listener::bind(&address, SocketOptions::default())?;
+        #[cfg(unix)]
+        if let Some(mode) = self.mode {
+            use std::os::unix::fs::PermissionsExt;
+
+            std::fs::set_permissions(&self.path, std::fs::Permissions::from_mode(mode)).map_err(
+                |source| Error::BindError {
+                    address: address.to_string(),
+                    source,
+                },
+            )?;
+        }
+        Ok(listener)
+    }
+}
+
+impl From<SocketAddr> for BindAddress {
+    fn from(addr: SocketAddr) -> Self {
+        Self::Tcp(addr)
+    }
+}
+
+impl fmt::Display for BindAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tcp(addr) => write!(f, "{addr}"),
+            Self::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// A bound listening socket
+///
+/// The file of a Unix socket the server bound (path `Some`) is removed when
+/// the listener is dropped; an inherited socket's file is left alone.
+#[derive(Debug)]
+pub(crate) enum Listener {
+    Tcp(TcpListener, SocketOptions),
+    #[cfg(unix)]
+    Unix(tokio::net::UnixListener, Option<PathBuf>),
+}
+
+impl Listener {
+    /// Bind `address`, replacing a stale Unix socket file left by an
+    /// earlier run; `options` apply to TCP
+    pub(crate) fn bind(address: &BindAddress, options: SocketOptions) -> Result<Self> {
+        let bind_error = |source| Error::BindError {
+            address: address.to_string(),
+            source,
+        };
+        match address {
+            BindAddress::Tcp(addr) => bind_tcp(*addr, &options)
+                .map(|listener| Self::Tcp(listener, options))
+                .map_err(bind_error),
+            #[cfg(unix)]
+            BindAddress::Unix(path) => {
+                remove_stale_socket(path).map_err(bind_error)?;
+                tokio::net::UnixListener::bind(path)
+                    .map(|listener| Self::Unix(listener, Some(path.clone())))
+                    .map_err(bind_error)
+            }
+            #[cfg(not(unix))]
+            BindAddress::Unix(_) => Err(bind_error(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "Unix domain sockets are only available on Unix",
+            ))),
+        }
+    }
+
+    /// Accept the next connection, with the peer address for TCP
+    pub(crate) async fn accept(&self) -> std::io::Result<(Connection, Option<SocketAddr>)> {
+        match self {
+            #[cfg(unix)]
+            Self::Tcp(listener, options) => {
+                let (stream, addr) = accept_tcp(listener, options).await?;
+                Ok((tokio_util::either::Either::Left(stream), Some(addr)))
+            }
+            #[cfg(not(unix))]
+            Self::Tcp(listener, options) => {
+                let (stream, addr) = accept_tcp(listener, options).await?;
+                Ok((stream, Some(addr)))
+            }
+            #[cfg(unix)]
+            Self::Unix(listener, _) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((tokio_util::either::Either::Right(stream), None))
+            }
+        }
+    }
+}
+
+impl Drop for Listener {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Self::Unix(_, Some(path)) = self {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// First descriptor passed by systemd (`SD_LISTEN_FDS_START`)
+#[cfg(unix)]
+const LISTEN_FDS_START: i32 = 3;
+
+/// Whether the inherited descriptors have been adopted by this process
+#[cfg(unix)]
+static ADOPTED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Listeners inherited through systemd socket activation, with their
+/// addresses
+///
+/// Empty when `LISTEN_PID` does not name this process (not socket
+/// activated), and on every call after the first that adopted them. The
+/// environment is left untouched: processes the server starts do not take
+/// the sockets, as `LISTEN_PID` does not name them.
+///
+/// # Errors
+///
+/// Returns `Error::Config` for malformed variables and `Error::BindError`
+/// for a descriptor that is not a TCP or Unix stream socket.
+#[cfg(unix)]
+pub(crate) fn inherited(options: SocketOptions) -> Result<Vec<(BindAddress, Listener)>> {
+    let pid = std::env::var("LISTEN_PID").ok();
+    let fds = std::env::var("LISTEN_FDS").ok();
+    let count = listen_fd_count(pid.as_deref(), fds.as_deref(), std::process::id())?;
+    if count == 0 || ADOPTED.swap(true, std::sync::atomic::Ordering::SeqCst) {
+        return Ok(Vec::new());
+    }
+    (LISTEN_FDS_START..)
+        .take(count)
+        .map(|fd| adopt(fd, options))
+        .collect()
+}
+
+/// Listeners inherited through socket activation: none off Unix
+///
+/// # Errors
+///
+/// Never fails.
+#[cfg(not(unix))]
+pub(crate) fn inherited(_options: SocketOptions) -> Result<Vec<(BindAddress, Listener)>> {
+    Ok(Vec::new())
+}
+
+/// Number of descriptors passed to process `own_pid`, from `LISTEN_PID`
+/// and `LISTEN_FDS`
+#[cfg(unix)]
+fn listen_fd_count(pid: Option<&str>, fds: Option<&str>, own_pid: u32) -> Result<usize> {
+    let Some(pid) = pid else {
+        return Ok(0);
+    };
+    let malformed = |name: &str, value: &str| Error::Config {
+        message: format!("malformed {name}={value:?} from socket activation"),
+    };
+    if pid
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| malformed("LISTEN_PID", pid))?
+        != own_pid
+    {
+        return Ok(0);
+    }
+    let fds = fds.unwrap_or("0");
+    fds.trim().parse().map_err(|_| malformed("LISTEN_FDS", fds))
+}
+
+/// Take ownership of inherited listening socket `fd`
+#[cfg(unix)]
+#[allow(unsafe_code)]
+fn adopt(fd: i32, options: SocketOptions) -> Result<(BindAddress, Listener)> {
+    use std::os::fd::{FromRawFd, OwnedFd};
+
+    let adopt_error = |source| Error::BindError {
+        address: format!("fd:{fd}"),
+        source,
+    };
+    // SAFETY: systemd passes descriptors LISTEN_FDS_START.. to this process
+    // (LISTEN_PID matched) and nothing else in it owns them; `ADOPTED`
+    // ensures each is adopted at most once
+    let socket = socket2::Socket::from(unsafe { OwnedFd::from_raw_fd(fd) });
+    socket.set_nonblocking(true).map_err(adopt_error)?;
+    let local = socket.local_addr().map_err(adopt_error)?;
+    if let Some(addr) = local.as_socket() {
+        let listener = TcpListener::from_std(socket.into()).map_err(adopt_error)?;
+        return Ok((BindAddress::Tcp(addr), Listener::Tcp(listener, options)));
+    }
+    if local.is_unix() {
+        let path = local.as_pathname().map(PathBuf::from).unwrap_or_default();
+        let listener = tokio::net::UnixListener::from_std(socket.into()).map_err(adopt_error)?;
+        return Ok((BindAddress::Unix(path), Listener::Unix(listener, None)));
+    }
+    Err(adopt_error(std::io::Error::new(
+        std::io::ErrorKind::InvalidInput,
+        "inherited descriptor is not a TCP or Unix socket",
+    )))
+}
+
+/// Accept the next connection on any of `listeners`
+pub(crate) async fn accept_any(
+    listeners: &[Listener],
+) -> std::io::Result<(Connection, Option<SocketAddr>)> {
+    future::select_all(listeners.iter().map(|listener| listener.accept().boxed()))
+        .await
+        .0
+}
+
+/// Accept a TCP connection and apply `options` to it
+///
+/// A connection the options cannot be applied to is still served.
+async fn accept_tcp(
+    listener: &TcpListener,
+    options: &SocketOptions,
+) -> std::io::Result<(TcpStream, SocketAddr)> {
+    let (stream, addr) = listener.accept().await?;
+    if let Err(err) = options.apply(&stream) {
+        debug!("Cannot apply socket options to connection from {addr}: {err}");
+    }
+    Ok((stream, addr))
+}
+
+/// TCP listener with `SO_REUSEADDR` (and `SO_REUSEPORT` off Windows), so
+/// worker processes can share the address
+///
+/// Buffer sizes are set before `listen`, so the TCP window scale of
+/// accepted connections is negotiated for them.
+fn bind_tcp(addr: SocketAddr, options: &SocketOptions) -> std::io::Result<TcpListener> {
+    let socket = if addr.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    socket.set_reuseaddr(true)?;
+    #[cfg(not(windows))]
+    socket.set_reuseport(true)?;
+    if let Some(size) = options.recv_buffer_size {
+        socket.set_recv_buffer_size(size)?;
+    }
+    if let Some(size) = options.send_buffer_size {
+        socket.set_send_buffer_size(size)?;
+    }
+    socket.bind(addr)?;
+    socket.listen(options.backlog)
+}
+
+/// Remove the socket file at `path` left by a run that did not clean up
+///
+/// A socket still accepting connections, or any other kind of file, is
+/// kept (binding then fails), so a Unix socket cannot be shared by
+/// supervised workers the way a TCP address is.
+#[cfg(unix)]
+fn remove_stale_socket(path: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::FileTypeExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(meta)
+            if meta.file_type().is_socket()
+                && std::os::unix::net::UnixStream::connect(path).is_err() =>
+        {
+            std::fs::remove_file(path)
+        }
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bind_addresses() {
+        assert_eq!(
+            BindAddress::parse("127.0.0.1:8000").unwrap(),
+            BindAddress::Tcp(([127, 0, 0, 1], 8000).into())
+        );
+        let unix = BindAddress::parse("unix:/run/app.sock").unwrap();
+        assert_eq!(unix, BindAddress::Unix(PathBuf::from("/run/app.sock")));
+        assert_eq!(unix.to_string(), "unix:/run/app.sock");
+        assert!(BindAddress::parse("[::1]:9000").is_ok());
+        assert!(BindAddress::parse("localhost").is_err());
+        assert!(BindAddress::parse("unix:").is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_accepts_on_every_listener() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let path = std::env::temp_dir().join(format!("pv-listener-{}.sock", std::process::id()));
+        let tcp = Listener::bind(
+            &BindAddress::Tcp(([127, 0, 0, 1], 0).into()),
+            SocketOptions::default(),
+        )
+        .unwrap();
+        let Listener::Tcp(inner, _) = &tcp else {
+            unreachable!()
+        };
+        let tcp_addr = inner.local_addr().unwrap();
+        let unix =
+            Listener::bind(&BindAddress::Unix(path.clone()), SocketOptions::default()).unwrap();
+        let listeners = [tcp, unix];
+
+        let mut client = tokio::net::UnixStream::connect(&path).await.unwrap();
+        let (mut conn, peer) = accept_any(&listeners).await.unwrap();
+        assert!(peer.is_none());
+        client.write_all(b"ping").await.unwrap();
+        let mut buf = [0; 4];
+        conn.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+
+        let _client = TcpStream::connect(tcp_addr).await.unwrap();
+        let (_, peer) = accept_any(&listeners).await.unwrap();
+        assert!(peer.is_some());
+
+        // A live socket is kept
+        assert!(matches!(
+            Listener::bind(&BindAddress::Unix(path.clone()), SocketOptions::default()),
+            Err(Error::BindError { .. })
+        ));
+        // A stale socket file is replaced; dropping the listener removes it
+        drop(listeners);
+        assert!(!path.exists());
+        let stale = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        drop(stale);
+        let rebound =
+            Listener::bind(&BindAddress::Unix(path.clone()), SocketOptions::default()).unwrap();
+        drop(rebound);
+        assert!(!path.exists());
+
+        let restricted = UnixSocket::new(&path).mode(0o600).bind().unwrap();
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        drop(restricted);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_listen_fd_count() {
+        assert_eq!(listen_fd_count(None, Some("2"), 42).unwrap(), 0);
+        assert_eq!(listen_fd_count(Some("7"), Some("2"), 42).unwrap(), 0);
+        assert_eq!(listen_fd_count(Some("42"), Some("2"), 42).unwrap(), 2);
+        assert_eq!(listen_fd_count(Some("42"), None, 42).unwrap(), 0);
+        assert!(listen_fd_count(Some("42"), Some("two"), 42).is_err());
+        assert!(listen_fd_count(Some("pid"), Some("1"), 42).is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_adopts_inherited_sockets() {
+        use std::os::fd::IntoRawFd;
+
+        let std_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = std_listener.local_addr().unwrap();
+        let (address, listener) =
+            adopt(std_listener.into_raw_fd(), SocketOptions::default()).unwrap();
+        assert_eq!(address, BindAddress::Tcp(addr));
+        let _client = TcpStream::connect(addr).await.unwrap();
+        let (_, peer) = listener.accept().await.unwrap();
+        assert!(peer.is_some());
+
+        let path = std::env::temp_dir().join(format!("pv-adopt-{}.sock", std::process::id()));
+        let unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        let (address, listener) = adopt(unix.into_raw_fd(), SocketOptions::default()).unwrap();
+        assert_eq!(address, BindAddress::Unix(path.clone()));
+        // systemd owns the file of an inherited socket
+        drop(listener);
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_socket_options_apply_to_accepted_connections() {
+        let options = SocketOptions {
+            backlog: 16,
+            nodelay: true,
+            keepalive_idle: Some(Duration::from_secs(30)),
+            keepalive_interval: Some(Duration::from_secs(5)),
+            recv_buffer_size: Some(64 * 1024),
+            send_buffer_size: None,
+        };
+        let listener =
+            Listener::bind(&BindAddress::Tcp(([127, 0, 0, 1], 0).into()), options).unwrap();
+        let Listener::Tcp(inner, _) = &listener else {
+            unreachable!()
+        };
+        let _client = TcpStream::connect(inner.local_addr().unwrap())
+            .await
+            .unwrap();
+
+        let (conn, _) = listener.accept().await.unwrap();
+        #[cfg(unix)]
+        let tokio_util::either::Either::Left(conn) = conn
+        else {
+            unreachable!()
+        };
+        assert!(conn.nodelay().unwrap());
+        let socket = socket2::SockRef::from(&conn);
+        assert!(socket.keepalive().unwrap());
+        // The kernel may round (Linux doubles) the requested size
+        assert!(socket.recv_buffer_size().unwrap() >= 64 * 1024);
+    }
+}
@@ -0,0 +1,277 @@
+//! # Log Bridge
+//!
+//! Connects `tracing` with another logging system (Python `logging` in
+//! the bindings) so one sink, format and rotation policy covers the whole
+//! process:
+//!
+//! - `emit` turns a foreign log record into a `tracing` event under the
+//!   `python` target, carrying its request context
+//! - `ForwardingLayer` hands `tracing` events to a `LogSink` while one is
+//!   set, with the `request_id`, `route`, `tenant` and `user` of the
+//!   enclosing `request` span
+//!
+//! Events under the `python` target are never forwarded back, and events
+//! emitted by a sink while it runs are dropped, so the two directions can
+//! be enabled together without looping.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only moves log records across the boundary
+//! - **O**: Any destination plugs in as a `LogSink`
+//! - **D**: Depends on `tracing` abstractions, not a concrete formatter
+
+use crate::log_context::LogContext;
+use std::cell::Cell;
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Target of events that came from the foreign logging system
+pub const PYTHON_TARGET: &str = "python";
+
+/// A `tracing` event as handed to a `LogSink`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    /// Severity
+    pub level: Level,
+    /// Dotted logger name derived from the target (`pyvectora_core.server`)
+    pub logger: String,
+    /// Message followed by the event's other fields as `key=value`
+    pub message: String,
+    /// Source file, when known
+    pub file: Option<&'static str>,
+    /// Source line, when known
+    pub line: Option<u32>,
+    /// Correlation fields of the enclosing `request` span
+    pub context: LogContext,
+}
+
+/// Destination of forwarded events
+pub type LogSink = Arc<dyn Fn(&LogRecord) + Send + Sync>;
+
+thread_local! {
+    /// Set while a sink runs on this thread
+    static IN_SINK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// `tracing` layer handing events to a switchable `LogSink`
+///
+/// Clones share the sink, so one clone can be installed in the
+/// subscriber while another switches forwarding on and off.
+#[derive(Clone, Default)]
+pub struct ForwardingLayer {
+    sink: Arc<RwLock<Option<LogSink>>>,
+    forwarding: Arc<AtomicBool>,
+}
+
+impl ForwardingLayer {
+    /// Layer forwarding nothing until a sink is set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Forward events to `sink` from now on; `None` stops forwarding
+    pub fn set_sink(&self, sink: Option<LogSink>) {
+        let forwarding = sink.is_some();
+        *self.sink.write().unwrap_or_else(PoisonError::into_inner) = sink;
+        self.forwarding.store(forwarding, Ordering::Release);
+    }
+
+    /// Whether a sink is set
+    ///
+    /// The process's own formatter can mute itself while this holds, so
+    /// events are written once, by the sink's logging system.
+    #[must_use]
+    pub fn is_forwarding(&self) -> bool {
+        self.forwarding.load(Ordering::Acquire)
+    }
+}
+
+impl<S> Layer<S> for ForwardingLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if attrs.metadata().name() != "request" {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            let mut context = LogContext::default();
+            attrs.record(&mut ContextVisitor(&mut context));
+            span.extensions_mut().insert(context);
+        }
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(context) = span.extensions_mut().get_mut::<LogContext>() {
+                values.record(&mut ContextVisitor(context));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        if !self.is_forwarding() || metadata.target() == PYTHON_TARGET || IN_SINK.with(Cell::get) {
+            return;
+        }
+        let Some(sink) = self
+            .sink
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+        else {
+            return;
+        };
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+        let context = ctx
+            .event_scope(event)
+            .and_then(|scope| {
+                scope
+                    .into_iter()
+                    .find_map(|span| span.extensions().get::<LogContext>().cloned())
+            })
+            .unwrap_or_default();
+        let record = LogRecord {
+            level: *metadata.level(),
+            logger: metadata.target().replace("::", "."),
+            message: visitor.finish(),
+            file: metadata.file(),
+            line: metadata.line(),
+            context,
+        };
+        IN_SINK.with(|in_sink| in_sink.set(true));
+        sink(&record);
+        IN_SINK.with(|in_sink| in_sink.set(false));
+    }
+}
+
+/// Collects the `message` field and the other fields as `key=value`
+#[derive(Default)]
+struct MessageVisitor {
+    message: String,
+    fields: String,
+}
+
+impl MessageVisitor {
+    fn finish(mut self) -> String {
+        if self.message.is_empty() {
+            return self.fields.trim_start().to_string();
+        }
+        self.message.push_str(&self.fields);
+        self.message
+    }
+}
+
+impl Visit for MessageVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{value:?}");
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+/// Fills a `LogContext` from the fields of a `request` span
+struct ContextVisitor<'a>(&'a mut LogContext);
+
+impl Visit for ContextVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        let value = value.to_string();
+        match field.name() {
+            "request_id" => self.0.request_id = value,
+            "route" => self.0.route = Some(value),
+            "tenant" => self.0.tenant = Some(value),
+            "user" => self.0.user = Some(value),
+            _ => {}
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.record_str(field, &format!("{value:?}"));
+    }
+}
+
+/// Emit a foreign log record as a `tracing` event under `PYTHON_TARGET`
+///
+/// `logger` and the request `context` become fields of the event.
+pub fn emit(level: Level, logger: &str, message: &str, context: &LogContext) {
+    macro_rules! forward {
+        ($level:expr) => {
+            tracing::event!(
+                target: PYTHON_TARGET,
+                $level,
+                logger,
+                request_id = Some(context.request_id.as_str()).filter(|id| !id.is_empty()),
+                route = context.route.as_deref(),
+                tenant = context.tenant.as_deref(),
+                user = context.user.as_deref(),
+                "{}",
+                message
+            )
+        };
+    }
+    match level {
+        Level::ERROR => forward!(Level::ERROR),
+        Level::WARN => forward!(Level::WARN),
+        Level::INFO => forward!(Level::INFO),
+        Level::DEBUG => forward!(Level::DEBUG),
+        Level::TRACE => forward!(Level::TRACE),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_events_are_forwarded_with_request_context() {
+        let layer = ForwardingLayer::new();
+        let records = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&records);
+        layer.set_sink(Some(Arc::new(move |record: &LogRecord| {
+            sink.lock().unwrap().push(record.clone());
+        })));
+        let subscriber = tracing_subscriber::registry().with(layer.clone());
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                "request",
+                request_id = "req-1",
+                route = tracing::field::Empty
+            );
+            let _entered = span.enter();
+            span.record("route", "GET /orders");
+            tracing::warn!(attempt = 2, "retrying {}", "payment");
+            emit(Level::INFO, "app", "from python", &LogContext::default());
+            layer.set_sink(None);
+            tracing::warn!("not forwarded");
+        });
+
+        let records = records.lock().unwrap().clone();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].level, Level::WARN);
+        assert_eq!(records[0].message, "retrying payment attempt=2");
+        assert_eq!(records[0].logger, "pyvectora_core.log_bridge.tests");
+        assert_eq!(records[0].context.request_id, "req-1");
+        assert_eq!(records[0].context.route.as_deref(), Some("GET /orders"));
+    }
+}
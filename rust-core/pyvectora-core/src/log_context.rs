@@ -0,0 +1,130 @@
+//! # Request Log Context
+//!
+//! Correlation fields of the request a log line belongs to: `request_id`,
+//! `route`, `tenant` and `user`. The server wraps routing and the handler
+//! in a `request` span carrying them, so every `tracing` event emitted
+//! while handling a request is enriched without formatting them by hand
+//! (the JSON formatter prints the fields of the current span). The Python
+//! bindings hand the same fields to `logging` records.
+//!
+//! The tenant comes from the `X-Tenant-Id` header, else the `tenant`
+//! claim of a validated JWT; the user is the JWT `sub` claim.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only derives and records correlation fields
+//! - **D**: Reads them from `PyRequest`; code that logs needs no changes
+
+use crate::request::PyRequest;
+use serde::Serialize;
+use serde_json::Value;
+use tracing::field::Empty;
+use tracing::{info_span, Span};
+
+/// Header naming the tenant a request acts for
+pub const TENANT_HEADER: &str = "x-tenant-id";
+
+/// JWT claim naming the tenant when the header is absent
+pub const TENANT_CLAIM: &str = "tenant";
+
+/// Correlation fields of one request
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct LogContext {
+    /// Request id (`x-request-id`)
+    pub request_id: String,
+    /// Matched route (`"GET /users/{id}"`), once routed
+    pub route: Option<String>,
+    /// Tenant the request acts for, if any
+    pub tenant: Option<String>,
+    /// Authenticated user (the JWT `sub` claim), if any
+    pub user: Option<String>,
+}
+
+impl LogContext {
+    /// Fields of `req` as known so far
+    #[must_use]
+    pub fn of(req: &PyRequest) -> Self {
+        Self {
+            request_id: req.header("x-request-id").unwrap_or_default().to_string(),
+            route: req.route.as_deref().map(String::from),
+            tenant: tenant_of(req),
+            user: req.subject(),
+        }
+    }
+}
+
+/// Tenant of `req`: the `X-Tenant-Id` header, else the JWT `tenant` claim
+#[must_use]
+pub fn tenant_of(req: &PyRequest) -> Option<String> {
+    if let Some(tenant) = req.header(TENANT_HEADER).filter(|t| !t.is_empty()) {
+        return Some(tenant.to_string());
+    }
+    match req.claims.as_ref()?.get(TENANT_CLAIM)? {
+        Value::String(tenant) => Some(tenant.clone()),
+        Value::Number(tenant) => Some(tenant.to_string()),
+        _ => None,
+    }
+}
+
+/// `request` span for `req`, with the fields known before routing
+pub fn request_span(req: &PyRequest) -> Span {
+    let span = info_span!(
+        "request",
+        request_id = req.header("x-request-id").unwrap_or_default(),
+        method = %req.method,
+        path = %req.path,
+        route = Empty,
+        tenant = Empty,
+        user = Empty,
+    );
+    record(&span, req);
+    span
+}
+
+/// Record the route, tenant and user `req` has by now on `span`
+pub fn record(span: &Span, req: &PyRequest) {
+    if span.is_disabled() {
+        return;
+    }
+    let context = LogContext::of(req);
+    if let Some(route) = &context.route {
+        span.record("route", route.as_str());
+    }
+    if let Some(tenant) = &context.tenant {
+        span.record("tenant", tenant.as_str());
+    }
+    if let Some(user) = &context.user {
+        span.record("user", user.as_str());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::Method;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_context_from_headers_and_claims() {
+        let mut req = PyRequest::new(Method::Get, "/orders".into(), HashMap::new(), None);
+        req.set_header("x-request-id", "req-1");
+        assert_eq!(
+            LogContext::of(&req),
+            LogContext {
+                request_id: "req-1".into(),
+                ..LogContext::default()
+            }
+        );
+
+        req.route = Some(Arc::from("GET /orders"));
+        req.claims = Some(serde_json::json!({ "sub": 42, "tenant": "acme" }));
+        let context = LogContext::of(&req);
+        assert_eq!(context.route.as_deref(), Some("GET /orders"));
+        assert_eq!(context.tenant.as_deref(), Some("acme"));
+        assert_eq!(context.user.as_deref(), Some("42"));
+
+        req.set_header("X-Tenant-Id", "globex");
+        assert_eq!(tenant_of(&req).as_deref(), Some("globex"));
+    }
+}
@@ -0,0 +1,492 @@
+//! # Request Metrics
+//!
+//! Request counts and latency histograms in the Prometheus text format,
+//! served from a scrape endpoint. Series are labelled by route pattern
+//! (`/users/{id}`), never by raw path, so one route is one series no
+//! matter how many ids it serves. Only allowlisted labels are attached,
+//! and the number of series is capped: label sets beyond the cap are
+//! folded into a single overflow series instead of growing without bound.
+//!
+//! Query cache hits, misses, evictions and invalidations, and rate limiter
+//! decisions per rule, are counted alongside, so cache TTLs and rate limits
+//! can be tuned from data. Their labels come from configuration (cache
+//! names, tier names), so they need no cap.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only aggregates finished requests and renders them
+//! - **O**: Plugs in as a `ServerExtension`, fed by `request_finished`,
+//!   `cache` and `rate_limit` events
+//! - **D**: Reads the event bus, not the server's request path
+
+use crate::error::{Error, Result};
+use crate::events::{self, CacheOutcome, Event, EventKind, SubscriptionId};
+use crate::extension::ServerExtension;
+use crate::route_registry::RouteRegistry;
+use crate::router::Method;
+use crate::server::{PyResponse, Server};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+use tracing::warn;
+
+/// Default scrape endpoint
+pub const DEFAULT_PATH: &str = "/metrics";
+
+/// Default cap on distinct label sets
+pub const DEFAULT_MAX_SERIES: usize = 1000;
+
+/// Upper bounds (seconds) of the latency histogram buckets
+const BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Value of every label on the series collecting label sets over the cap
+const OVERFLOW: &str = "__overflow__";
+
+/// Route label of requests that matched no route
+const UNMATCHED: &str = "__unmatched__";
+
+/// A label metrics may carry
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricLabel {
+    /// HTTP method
+    Method,
+    /// Route pattern (`/users/{id}`)
+    Route,
+    /// Response status code (`200`)
+    Status,
+    /// Response status class (`2xx`)
+    StatusClass,
+}
+
+impl MetricLabel {
+    /// Label name as exported
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Method => "method",
+            Self::Route => "route",
+            Self::Status => "status",
+            Self::StatusClass => "status_class",
+        }
+    }
+
+    /// Parse a label name
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` for names that are not allowlisted labels,
+    /// including `path`, which would give every URL its own series.
+    pub fn parse(name: &str) -> Result<Self> {
+        match name {
+            "method" => Ok(Self::Method),
+            "route" => Ok(Self::Route),
+            "status" => Ok(Self::Status),
+            "status_class" => Ok(Self::StatusClass),
+            other => Err(Error::Config {
+                message: format!(
+                    "unknown metrics label {other:?} (expected method, route, status or status_class)"
+                ),
+            }),
+        }
+    }
+}
+
+/// Count, latency sum and latency buckets of one label set
+#[derive(Debug, Clone, Default)]
+struct Series {
+    count: u64,
+    sum: f64,
+    buckets: [u64; BUCKETS.len()],
+}
+
+impl Series {
+    fn observe(&mut self, seconds: f64) {
+        self.count += 1;
+        self.sum += seconds;
+        for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+            if seconds <= bound {
+                *bucket += 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    series: BTreeMap<Vec<String>, Series>,
+    /// Observations folded into the overflow series
+    overflowed: u64,
+    /// Cache operations by cache name and outcome
+    cache: BTreeMap<(String, CacheOutcome), u64>,
+    /// Rate limiter decisions by rule and whether the request was allowed
+    rate_limits: BTreeMap<(String, bool), u64>,
+    subscriptions: Vec<SubscriptionId>,
+}
+
+/// Request metrics with a scrape endpoint
+#[derive(Debug, Clone)]
+pub struct RequestMetrics {
+    path: String,
+    labels: Vec<MetricLabel>,
+    max_series: usize,
+    state: Arc<Mutex<State>>,
+}
+
+impl Default for RequestMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RequestMetrics {
+    /// Metrics at `/metrics`, labelled by method, route and status
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            path: DEFAULT_PATH.to_string(),
+            labels: vec![MetricLabel::Method, MetricLabel::Route, MetricLabel::Status],
+            max_series: DEFAULT_MAX_SERIES,
+            state: Arc::default(),
+        }
+    }
+
+    /// Serve the metrics at `path`
+    #[must_use]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Attach exactly `labels`, in this order
+    #[must_use]
+    pub fn labels(mut self, labels: Vec<MetricLabel>) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    /// Fold label sets beyond `max_series` into one overflow series
+    #[must_use]
+    pub const fn max_series(mut self, max_series: usize) -> Self {
+        self.max_series = max_series;
+        self
+    }
+
+    /// Record a finished request
+    ///
+    /// `route` is the matched route as set on the request
+    /// (`"GET /users/{id}"`), `None` when no route matched.
+    pub fn observe(&self, method: &str, route: Option<&str>, status: u16, duration: Duration) {
+        let pattern = route.map_or(UNMATCHED, |r| r.split_once(' ').map_or(r, |(_, p)| p));
+        let values: Vec<String> = self
+            .labels
+            .iter()
+            .map(|label| match label {
+                MetricLabel::Method => method.to_string(),
+                MetricLabel::Route => pattern.to_string(),
+                MetricLabel::Status => status.to_string(),
+                MetricLabel::StatusClass => format!("{}xx", status / 100),
+            })
+            .collect();
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let key = if state.series.contains_key(&values) || state.series.len() < self.max_series {
+            values
+        } else {
+            if state.overflowed == 0 {
+                warn!(
+                    "Metrics reached {} series; further label sets are counted as {OVERFLOW}",
+                    self.max_series
+                );
+            }
+            state.overflowed += 1;
+            vec![OVERFLOW.to_string(); self.labels.len()]
+        };
+        state
+            .series
+            .entry(key)
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    /// Record `count` operations of `cache` with `outcome`
+    pub fn observe_cache(&self, cache: &str, outcome: CacheOutcome, count: u64) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        *state.cache.entry((cache.to_string(), outcome)).or_default() += count;
+    }
+
+    /// Record a rate limiter decision made by `rule`
+    pub fn observe_rate_limit(&self, rule: &str, allowed: bool) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        *state
+            .rate_limits
+            .entry((rule.to_string(), allowed))
+            .or_default() += 1;
+    }
+
+    /// The metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let (all_series, overflowed, cache, rate_limits) = {
+            let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            (
+                state.series.clone(),
+                state.overflowed,
+                state.cache.clone(),
+                state.rate_limits.clone(),
+            )
+        };
+        let mut out = String::new();
+        out.push_str("# HELP pyvectora_requests_total Requests answered.\n");
+        out.push_str("# TYPE pyvectora_requests_total counter\n");
+        for (values, series) in &all_series {
+            let labels = self.label_set(values, None);
+            let _ = writeln!(out, "pyvectora_requests_total{labels} {}", series.count);
+        }
+
+        out.push_str("# HELP pyvectora_request_duration_seconds Time to answer requests.\n");
+        out.push_str("# TYPE pyvectora_request_duration_seconds histogram\n");
+        for (values, series) in &all_series {
+            for (bound, count) in BUCKETS.iter().zip(series.buckets) {
+                let labels = self.label_set(values, Some(&bound.to_string()));
+                let _ = writeln!(
+                    out,
+                    "pyvectora_request_duration_seconds_bucket{labels} {count}"
+                );
+            }
+            let labels = self.label_set(values, Some("+Inf"));
+            let _ = writeln!(
+                out,
+                "pyvectora_request_duration_seconds_bucket{labels} {}",
+                series.count
+            );
+            let labels = self.label_set(values, None);
+            let _ = writeln!(
+                out,
+                "pyvectora_request_duration_seconds_sum{labels} {}",
+                series.sum
+            );
+            let _ = writeln!(
+                out,
+                "pyvectora_request_duration_seconds_count{labels} {}",
+                series.count
+            );
+        }
+
+        out.push_str(
+            "# HELP pyvectora_metrics_overflow_total Requests counted in the overflow series.\n",
+        );
+        out.push_str("# TYPE pyvectora_metrics_overflow_total counter\n");
+        let _ = writeln!(out, "pyvectora_metrics_overflow_total {overflowed}");
+
+        out.push_str(
+            "# HELP pyvectora_cache_operations_total Cache lookups and dropped entries.\n",
+        );
+        out.push_str("# TYPE pyvectora_cache_operations_total counter\n");
+        for ((name, outcome), count) in &cache {
+            let _ = writeln!(
+                out,
+                "pyvectora_cache_operations_total{{cache=\"{}\",outcome=\"{}\"}} {count}",
+                escape(name),
+                outcome.as_str()
+            );
+        }
+
+        out.push_str(
+            "# HELP pyvectora_rate_limit_decisions_total Requests allowed or denied by rate limiters.\n",
+        );
+        out.push_str("# TYPE pyvectora_rate_limit_decisions_total counter\n");
+        for ((rule, allowed), count) in &rate_limits {
+            let decision = if *allowed { "allow" } else { "deny" };
+            let _ = writeln!(
+                out,
+                "pyvectora_rate_limit_decisions_total{{rule=\"{}\",decision=\"{decision}\"}} {count}",
+                escape(rule)
+            );
+        }
+        out
+    }
+
+    /// Count `event` if it is one metrics are kept for
+    fn record(&self, event: &Event) {
+        match event {
+            Event::RequestFinished {
+                method,
+                route,
+                status,
+                duration,
+                ..
+            } => self.observe(method, route.as_deref(), *status, *duration),
+            Event::Cache {
+                cache,
+                outcome,
+                count,
+            } => self.observe_cache(cache, *outcome, *count),
+            Event::RateLimit { rule, allowed } => self.observe_rate_limit(rule, *allowed),
+            _ => {}
+        }
+    }
+
+    /// `{name="value",...}` for `values`, plus `le` for histogram buckets
+    fn label_set(&self, values: &[String], le: Option<&str>) -> String {
+        let mut pairs: Vec<String> = self
+            .labels
+            .iter()
+            .zip(values)
+            .map(|(label, value)| format!("{}=\"{}\"", label.as_str(), escape(value)))
+            .collect();
+        if let Some(le) = le {
+            pairs.push(format!("le=\"{le}\""));
+        }
+        if pairs.is_empty() {
+            return String::new();
+        }
+        format!("{{{}}}", pairs.join(","))
+    }
+}
+
+/// Escape a label value (`\`, `"` and newlines)
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
+
+impl ServerExtension for RequestMetrics {
+    fn name(&self) -> &'static str {
+        "metrics"
+    }
+
+    fn configure(&self, server: &mut Server) -> Result<()> {
+        let metrics = self.clone();
+        server.add_route(
+            Method::Get,
+            &self.path,
+            Arc::new(move |_req, _matched| {
+                let body = metrics.render();
+                Box::pin(std::future::ready(PyResponse::text(body).with_header(
+                    "Content-Type",
+                    "text/plain; version=0.0.4; charset=utf-8",
+                )))
+            }),
+            false,
+        )
+    }
+
+    fn on_start(&self, _routes: &RouteRegistry) {
+        let ids: Vec<SubscriptionId> = [
+            EventKind::RequestFinished,
+            EventKind::Cache,
+            EventKind::RateLimit,
+        ]
+        .into_iter()
+        .map(|kind| {
+            let metrics = self.clone();
+            events::global().subscribe(kind, Arc::new(move |event: &Event| metrics.record(event)))
+        })
+        .collect();
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        state.subscriptions.extend(ids);
+    }
+
+    fn on_shutdown(&self) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        for id in state.subscriptions.drain(..) {
+            events::global().unsubscribe(id);
+        }
+    }
+}
+
+impl Server {
+    /// Serve request metrics (see [`RequestMetrics`])
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metrics path conflicts with a route.
+    pub fn enable_metrics(&mut self, metrics: &RequestMetrics) -> Result<()> {
+        self.register_extension(Arc::new(metrics.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_series_are_labelled_by_route_pattern() {
+        let metrics = RequestMetrics::new();
+        let ms = Duration::from_millis;
+        metrics.observe("GET", Some("GET /users/{id}"), 200, ms(3));
+        metrics.observe("GET", Some("GET /users/{id}"), 200, ms(70));
+        metrics.observe("GET", None, 404, ms(1));
+
+        let text = metrics.render();
+        assert!(text.contains(
+            r#"pyvectora_requests_total{method="GET",route="/users/{id}",status="200"} 2"#
+        ));
+        assert!(text.contains(r#"route="__unmatched__",status="404"} 1"#));
+        assert!(text.contains(
+            r#"pyvectora_request_duration_seconds_bucket{method="GET",route="/users/{id}",status="200",le="0.005"} 1"#
+        ));
+        assert!(text.contains(
+            r#"pyvectora_request_duration_seconds_bucket{method="GET",route="/users/{id}",status="200",le="+Inf"} 2"#
+        ));
+        assert!(!text.contains("/users/7"));
+    }
+
+    #[test]
+    fn test_labels_are_allowlisted_and_series_capped() {
+        assert!(MetricLabel::parse("path").is_err());
+        let metrics = RequestMetrics::new()
+            .labels(vec![MetricLabel::Route, MetricLabel::StatusClass])
+            .max_series(2);
+        let ms = Duration::from_millis(5);
+        metrics.observe("GET", Some("GET /a"), 200, ms);
+        metrics.observe("POST", Some("POST /a"), 201, ms);
+        metrics.observe("GET", Some("GET /b"), 200, ms);
+        metrics.observe("GET", Some("GET /c"), 500, ms);
+        metrics.observe("GET", Some("GET /d"), 200, ms);
+        metrics.observe("GET", Some("GET /a"), 204, ms);
+
+        let text = metrics.render();
+        assert!(text.contains(r#"pyvectora_requests_total{route="/a",status_class="2xx"} 3"#));
+        assert!(text.contains(
+            r#"pyvectora_requests_total{route="__overflow__",status_class="__overflow__"} 2"#
+        ));
+        assert!(text.contains("pyvectora_metrics_overflow_total 2"));
+        assert!(!text.contains("method="));
+    }
+
+    #[test]
+    fn test_cache_and_rate_limit_counters() {
+        let metrics = RequestMetrics::new();
+        metrics.record(&Event::Cache {
+            cache: "query".into(),
+            outcome: CacheOutcome::Hit,
+            count: 1,
+        });
+        metrics.observe_cache("query", CacheOutcome::Hit, 1);
+        metrics.observe_cache("query", CacheOutcome::Eviction, 3);
+        metrics.record(&Event::RateLimit {
+            rule: "free".into(),
+            allowed: false,
+        });
+        metrics.observe_rate_limit("free", true);
+        metrics.observe_rate_limit("pro", true);
+
+        let text = metrics.render();
+        assert!(text.contains(r#"pyvectora_cache_operations_total{cache="query",outcome="hit"} 2"#));
+        assert!(text
+            .contains(r#"pyvectora_cache_operations_total{cache="query",outcome="eviction"} 3"#));
+        assert!(!text.contains(r#"outcome="miss""#));
+        assert!(
+            text.contains(r#"pyvectora_rate_limit_decisions_total{rule="free",decision="deny"} 1"#)
+        );
+        assert!(
+            text.contains(r#"pyvectora_rate_limit_decisions_total{rule="pro",decision="allow"} 1"#)
+        );
+    }
+}
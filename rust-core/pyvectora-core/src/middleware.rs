@@ -1,6 +1,6 @@
 //! # Middleware System
 //!
-//! Request/response interception for logging, timing, CORS, etc.
+//! Request/response interception for logging, timing, CORS, compression, etc.
 //!
 //! ## Design Principles (SOLID)
 //!
@@ -8,11 +8,17 @@
 //! - **O**: Extensible via Middleware trait
 //! - **D**: Server depends on abstract trait, not concrete implementations
 
-use crate::server::{PyRequest, PyResponse};
+use crate::events::{self, Event, EventKind};
+use crate::problem::ProblemDetails;
+use crate::server::{Handler, PyRequest, PyResponse};
+use flate2::write::GzEncoder;
+use hyper::body::Bytes;
 use std::collections::HashMap;
+use std::io::Write;
 use std::sync::Arc;
-use std::sync::Mutex;
-use std::time::Instant;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 use tracing::{debug, info};
 
 /// Middleware trait for request/response interception
@@ -21,8 +27,10 @@ use tracing::{debug, info};
 pub trait Middleware: Send + Sync {
     /// Called before the request handler
     ///
-    /// Can modify the request or return early with a response.
-    fn before_request(&self, _req: &PyRequest) -> MiddlewareResult {
+    /// Can modify the request (add headers, rewrite the path) or return
+    /// early with a response. When server middleware changes the method or
+    /// path, the request is routed again before a handler runs.
+    fn before_request(&self, _req: &mut PyRequest) -> MiddlewareResult {
         MiddlewareResult::Continue
     }
 
@@ -65,7 +73,7 @@ impl MiddlewareChain {
     }
 
     /// Execute before_request for all middlewares
-    pub fn run_before(&self, req: &PyRequest) -> MiddlewareResult {
+    pub fn run_before(&self, req: &mut PyRequest) -> MiddlewareResult {
         for mw in &self.middlewares {
             match mw.before_request(req) {
                 MiddlewareResult::Continue => continue,
@@ -82,6 +90,37 @@ impl MiddlewareChain {
         }
     }
 
+    /// Wrap a handler so this chain runs around it (used for route groups)
+    #[must_use]
+    pub fn wrap(&self, handler: Handler) -> Handler {
+        if self.is_empty() {
+            return handler;
+        }
+        let chain = self.clone();
+        // The route is already matched: a rewritten path is not routed again
+        Arc::new(move |req, matched| {
+            let mut req = req.clone();
+            match chain.run_before(&mut req) {
+                MiddlewareResult::Respond(resp) => Box::pin(std::future::ready(resp)),
+                MiddlewareResult::Continue => {
+                    let response = handler(&req, matched);
+                    let chain = chain.clone();
+                    Box::pin(async move {
+                        let mut response = response.await;
+                        chain.run_after(&req, &mut response);
+                        response
+                    })
+                }
+            }
+        })
+    }
+
+    /// Middleware names in the order `run_before` calls them
+    #[must_use]
+    pub fn names(&self) -> Vec<&'static str> {
+        self.middlewares.iter().map(|mw| mw.name()).collect()
+    }
+
     /// Get the number of middlewares
     #[must_use]
     pub fn len(&self) -> usize {
@@ -117,7 +156,7 @@ impl LoggingMiddleware {
 }
 
 impl Middleware for LoggingMiddleware {
-    fn before_request(&self, req: &PyRequest) -> MiddlewareResult {
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
         let request_id = req.header("x-request-id").unwrap_or("-");
         info!(
             method = %req.method,
@@ -167,7 +206,7 @@ impl TimingMiddleware {
 }
 
 impl Middleware for TimingMiddleware {
-    fn before_request(&self, req: &PyRequest) -> MiddlewareResult {
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
         let key = format!("{}:{}", req.method, req.path);
         if let Ok(mut times) = self.start_times.lock() {
             times.insert(key, Instant::now());
@@ -195,20 +234,42 @@ impl Middleware for TimingMiddleware {
     }
 }
 
+/// Callback deciding whether a request `Origin` is allowed
+pub type OriginValidator = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
 /// CORS middleware - adds Cross-Origin Resource Sharing headers
+///
+/// Preflight requests (`OPTIONS` with `Access-Control-Request-Method`) are
+/// answered directly with 204, whether or not the route has an OPTIONS handler.
+///
+/// Origins are matched, in order of precedence, by a validator callback, an
+/// allowlist, or the single `allow_origin` value. The first two reflect the
+/// request origin (adding `Vary: Origin`). Credentials cannot be allowed for
+/// `*` (see `validate`): browsers reject that literal, and reflecting every
+/// origin instead would hand credentialed responses to any site.
 #[derive(Clone)]
 pub struct CorsMiddleware {
     allow_origin: String,
+    allowed_origins: Vec<String>,
+    origin_validator: Option<OriginValidator>,
     allow_methods: String,
     allow_headers: String,
+    expose_headers: Option<String>,
+    allow_credentials: bool,
+    max_age: u64,
 }
 
 impl Default for CorsMiddleware {
     fn default() -> Self {
         Self {
             allow_origin: "*".to_string(),
+            allowed_origins: Vec::new(),
+            origin_validator: None,
             allow_methods: "GET, POST, PUT, DELETE, PATCH, OPTIONS".to_string(),
             allow_headers: "Content-Type, Authorization".to_string(),
+            expose_headers: None,
+            allow_credentials: false,
+            max_age: 600,
         }
     }
 }
@@ -227,6 +288,24 @@ impl CorsMiddleware {
         self
     }
 
+    /// Allow only origins from this list (reflected when matched)
+    #[must_use]
+    pub fn allow_origins<I, S>(mut self, origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_origins = origins.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Reflect any origin accepted by `validator`
+    #[must_use]
+    pub fn origin_validator(mut self, validator: OriginValidator) -> Self {
+        self.origin_validator = Some(validator);
+        self
+    }
+
     /// Set allowed methods
     #[must_use]
     pub fn allow_methods(mut self, methods: impl Into<String>) -> Self {
@@ -241,6 +320,90 @@ impl CorsMiddleware {
         self
     }
 
+    /// Set response headers readable by the browser (`Access-Control-Expose-Headers`)
+    #[must_use]
+    pub fn expose_headers(mut self, headers: impl Into<String>) -> Self {
+        self.expose_headers = Some(headers.into());
+        self
+    }
+
+    /// Allow cookies and auth headers on cross-origin requests
+    #[must_use]
+    pub const fn allow_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// Set how long (seconds) browsers may cache preflight results
+    #[must_use]
+    pub const fn max_age(mut self, seconds: u64) -> Self {
+        self.max_age = seconds;
+        self
+    }
+
+    /// Whether `req` is a CORS preflight request
+    #[must_use]
+    pub fn is_preflight(req: &PyRequest) -> bool {
+        req.method == crate::router::Method::Options
+            && req.header("origin").is_some()
+            && req.header("access-control-request-method").is_some()
+    }
+
+    /// Check the settings before serving
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` when credentials are allowed for the `*`
+    /// origin without an allowlist or validator.
+    pub fn validate(&self) -> crate::error::Result<()> {
+        if self.allow_credentials && !self.reflects_origin() && self.allow_origin == "*" {
+            return Err(crate::error::Error::Config {
+                message: "CORS credentials cannot be allowed for origin \"*\"; \
+                          list the allowed origins or pass a validator"
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    fn reflects_origin(&self) -> bool {
+        self.origin_validator.is_some() || !self.allowed_origins.is_empty()
+    }
+
+    /// `Access-Control-Allow-Origin` value for a request origin, if allowed
+    #[must_use]
+    pub fn resolve_origin(&self, origin: Option<&str>) -> Option<String> {
+        if let Some(validator) = &self.origin_validator {
+            return origin.filter(|o| validator(o)).map(str::to_string);
+        }
+        if !self.allowed_origins.is_empty() {
+            return origin
+                .filter(|o| self.allowed_origins.iter().any(|allowed| allowed == o))
+                .map(str::to_string);
+        }
+        Some(self.allow_origin.clone())
+    }
+
+    /// Set CORS headers; returns `false` when the origin is not allowed
+    fn apply_headers(&self, req: &PyRequest, res: &mut PyResponse) -> bool {
+        if self.reflects_origin() {
+            append_vary(res, "Origin");
+        }
+        let Some(origin) = self.resolve_origin(req.header("origin")) else {
+            return false;
+        };
+        res.set_header("Access-Control-Allow-Origin", &origin);
+        res.set_header("Access-Control-Allow-Methods", &self.allow_methods);
+        res.set_header("Access-Control-Allow-Headers", &self.allow_headers);
+        if self.allow_credentials {
+            res.set_header("Access-Control-Allow-Credentials", "true");
+        }
+        if let Some(expose) = &self.expose_headers {
+            res.set_header("Access-Control-Expose-Headers", expose);
+        }
+        true
+    }
+
     /// Get the Access-Control-Allow-Origin header value
     #[must_use]
     pub fn origin(&self) -> &str {
@@ -249,10 +412,24 @@ impl CorsMiddleware {
 }
 
 impl Middleware for CorsMiddleware {
-    fn after_response(&self, _req: &PyRequest, res: &mut PyResponse) {
-        res.set_header("Access-Control-Allow-Origin", &self.allow_origin);
-        res.set_header("Access-Control-Allow-Methods", &self.allow_methods);
-        res.set_header("Access-Control-Allow-Headers", &self.allow_headers);
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
+        if !Self::is_preflight(req) {
+            return MiddlewareResult::Continue;
+        }
+        let mut res = PyResponse::text("").with_status(204);
+        if !self.apply_headers(req, &mut res) {
+            return MiddlewareResult::Respond(
+                PyResponse::text(r#"{"error":"CORS origin not allowed"}"#)
+                    .with_status(403)
+                    .with_header("Content-Type", "application/json"),
+            );
+        }
+        res.set_header("Access-Control-Max-Age", &self.max_age.to_string());
+        MiddlewareResult::Respond(res)
+    }
+
+    fn after_response(&self, req: &PyRequest, res: &mut PyResponse) {
+        self.apply_headers(req, res);
     }
 
     fn name(&self) -> &'static str {
@@ -260,6 +437,26 @@ impl Middleware for CorsMiddleware {
     }
 }
 
+/// Add `value` to the response's `Vary` header
+fn append_vary(res: &mut PyResponse, value: &str) {
+    let vary = match res
+        .headers
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case("vary"))
+    {
+        Some((_, existing))
+            if existing
+                .split(',')
+                .any(|v| v.trim().eq_ignore_ascii_case(value)) =>
+        {
+            return;
+        }
+        Some((_, existing)) => format!("{existing}, {value}"),
+        None => value.to_string(),
+    };
+    res.set_header("Vary", &vary);
+}
+
 /// Token bucket rate limiting middleware
 pub struct RateLimitMiddleware {
     /// Maximum burst capacity
@@ -309,15 +506,20 @@ impl RateLimitMiddleware {
 }
 
 impl Middleware for RateLimitMiddleware {
-    fn before_request(&self, req: &PyRequest) -> MiddlewareResult {
-        let key = req.header("x-client-ip").unwrap_or("unknown");
-        if self.allow(key) {
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
+        let key = req
+            .remote_addr
+            .map_or_else(|| "unknown".to_string(), |addr| addr.ip().to_string());
+        let allowed = self.allow(&key);
+        report_rate_limit("default", allowed);
+        if allowed {
             MiddlewareResult::Continue
         } else {
             MiddlewareResult::Respond(
                 PyResponse::text(r#"{"error":"Rate limit exceeded"}"#)
                     .with_status(429)
-                    .with_header("Content-Type", "application/json"),
+                    .with_header("Content-Type", "application/json")
+                    .with_problem(ProblemDetails::new(429)),
             )
         }
     }
@@ -327,6 +529,402 @@ impl Middleware for RateLimitMiddleware {
     }
 }
 
+/// Requests allowed per window for one rate tier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TierLimit {
+    /// Requests allowed per window (also the burst size)
+    pub requests: u32,
+    /// Window over which `requests` refill
+    pub window: Duration,
+}
+
+impl TierLimit {
+    /// `requests` per minute
+    #[must_use]
+    pub const fn per_minute(requests: u32) -> Self {
+        Self {
+            requests,
+            window: Duration::from_secs(60),
+        }
+    }
+
+    /// Time for one request to refill
+    fn interval(self) -> Duration {
+        self.window / self.requests.max(1)
+    }
+}
+
+/// Callback choosing the rate tier of a request (`None` falls back to the
+/// tier claim)
+pub type TierResolver = Arc<dyn Fn(&PyRequest) -> Option<String> + Send + Sync>;
+
+/// Request state key under which the applied tier is exposed
+pub const RATE_LIMIT_STATE_KEY: &str = "rate_limit";
+
+/// Rate limiting whose limit depends on the caller's tier
+///
+/// The tier comes from the resolver callback, else the JWT claim (`tier` by
+/// default, so run after authentication), else the default tier. Callers are
+/// told apart by JWT subject, else by IP address. Each request gets
+/// `{"tier", "limit", "remaining"}` under `request.state.rate_limit`, and
+/// responses carry `X-RateLimit-Limit` and `X-RateLimit-Remaining`.
+pub struct TieredRateLimitMiddleware {
+    tiers: HashMap<String, TierLimit>,
+    default_tier: String,
+    claim: String,
+    resolver: Option<TierResolver>,
+    /// When each (tier, caller) bucket is full again
+    state: Mutex<HashMap<(String, String), Instant>>,
+}
+
+impl TieredRateLimitMiddleware {
+    /// Limiter applying `tiers`, with `default_tier` for callers without one
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if `default_tier` is not one of `tiers` or a
+    /// tier allows no requests.
+    pub fn new(
+        tiers: HashMap<String, TierLimit>,
+        default_tier: impl Into<String>,
+    ) -> crate::error::Result<Self> {
+        let default_tier = default_tier.into();
+        if !tiers.contains_key(&default_tier) {
+            return Err(crate::error::Error::Config {
+                message: format!("default rate tier {default_tier:?} has no limit"),
+            });
+        }
+        if let Some((name, _)) = tiers
+            .iter()
+            .find(|(_, limit)| limit.requests == 0 || limit.window.is_zero())
+        {
+            return Err(crate::error::Error::Config {
+                message: format!("rate tier {name:?} must allow requests over a window"),
+            });
+        }
+        Ok(Self {
+            tiers,
+            default_tier,
+            claim: "tier".to_string(),
+            resolver: None,
+            state: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Read the tier from JWT claim `claim` instead of `tier`
+    #[must_use]
+    pub fn claim(mut self, claim: impl Into<String>) -> Self {
+        self.claim = claim.into();
+        self
+    }
+
+    /// Choose tiers with `resolver` before looking at the claim
+    #[must_use]
+    pub fn resolver(mut self, resolver: TierResolver) -> Self {
+        self.resolver = Some(resolver);
+        self
+    }
+
+    /// Tier applied to `req`: unknown tiers get the default
+    #[must_use]
+    pub fn tier_of(&self, req: &PyRequest) -> (&str, TierLimit) {
+        let chosen = self
+            .resolver
+            .as_ref()
+            .and_then(|resolve| resolve(req))
+            .or_else(|| match req.claims.as_ref()?.get(&self.claim)? {
+                serde_json::Value::String(tier) => Some(tier.clone()),
+                _ => None,
+            });
+        chosen
+            .and_then(|tier| self.tiers.get_key_value(&tier))
+            .or_else(|| self.tiers.get_key_value(&self.default_tier))
+            .map_or_else(
+                || (self.default_tier.as_str(), TierLimit::per_minute(0)),
+                |(name, limit)| (name.as_str(), *limit),
+            )
+    }
+
+    /// Count a request by `caller` in `tier`
+    ///
+    /// Returns the requests left, or how long until one is allowed. The
+    /// bucket is tracked as the time it is full again (GCRA), so per-minute
+    /// rates refill exactly instead of in whole tokens.
+    fn take(&self, tier: &str, limit: TierLimit, caller: String) -> Result<u64, Duration> {
+        let now = Instant::now();
+        let interval = limit.interval();
+        let mut map = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let full_at = map.entry((tier.to_string(), caller)).or_insert(now);
+        let next = (*full_at).max(now) + interval;
+        let debt = next - now;
+        if debt > limit.window {
+            return Err(debt.saturating_sub(limit.window));
+        }
+        *full_at = next;
+        drop(map);
+        let left = limit.window.saturating_sub(debt).as_nanos() / interval.as_nanos().max(1);
+        Ok(u64::try_from(left).unwrap_or(u64::MAX))
+    }
+}
+
+impl Middleware for TieredRateLimitMiddleware {
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
+        let (tier, limit) = self.tier_of(req);
+        let caller = req.subject().map_or_else(
+            || {
+                req.remote_addr
+                    .map_or_else(|| "unknown".to_string(), |addr| addr.ip().to_string())
+            },
+            |sub| format!("sub:{sub}"),
+        );
+        let taken = self.take(tier, limit, caller);
+        report_rate_limit(tier, taken.is_ok());
+        req.state.set(
+            RATE_LIMIT_STATE_KEY,
+            serde_json::json!({
+                "tier": tier,
+                "limit": limit.requests,
+                "remaining": taken.unwrap_or(0),
+            }),
+        );
+        let Err(wait) = taken else {
+            return MiddlewareResult::Continue;
+        };
+        let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+        MiddlewareResult::Respond(
+            PyResponse::text(r#"{"error":"Rate limit exceeded"}"#)
+                .with_status(429)
+                .with_header("Content-Type", "application/json")
+                .with_header("Retry-After", &retry_after.to_string())
+                .with_problem(
+                    ProblemDetails::new(429)
+                        .with_detail(format!("Rate limit of tier {tier} exceeded")),
+                ),
+        )
+    }
+
+    fn after_response(&self, req: &PyRequest, res: &mut PyResponse) {
+        let Some(info) = req.state.get(RATE_LIMIT_STATE_KEY) else {
+            return;
+        };
+        for (header, field) in [
+            ("X-RateLimit-Limit", "limit"),
+            ("X-RateLimit-Remaining", "remaining"),
+        ] {
+            if let Some(value) = info.get(field) {
+                res.set_header(header, &value.to_string());
+            }
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "TieredRateLimitMiddleware"
+    }
+}
+
+/// Report a rate limiter decision by `rule` as a `rate_limit` event
+fn report_rate_limit(rule: &str, allowed: bool) {
+    if events::is_active(EventKind::RateLimit) {
+        events::emit(&Event::RateLimit {
+            rule: rule.to_string(),
+            allowed,
+        });
+    }
+}
+
+/// Content codings supported by `CompressionMiddleware`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    /// `br`
+    Brotli,
+    /// `gzip`
+    Gzip,
+}
+
+impl ContentEncoding {
+    /// `Content-Encoding` header value
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Brotli => "br",
+            Self::Gzip => "gzip",
+        }
+    }
+
+    /// Pick the preferred supported coding from an `Accept-Encoding` value
+    ///
+    /// Highest q-value wins; brotli is preferred on ties. Codings with
+    /// `q=0` are never selected.
+    #[must_use]
+    pub fn negotiate(accept_encoding: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for item in accept_encoding.split(',') {
+            let mut parts = item.split(';');
+            let coding = parts.next().unwrap_or("").trim().to_ascii_lowercase();
+            let q = parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+            let encoding = match coding.as_str() {
+                "br" | "*" => Self::Brotli,
+                "gzip" | "x-gzip" => Self::Gzip,
+                _ => continue,
+            };
+            if q <= 0.0 {
+                continue;
+            }
+            let better = best.map_or(true, |(current, best_q)| match q.total_cmp(&best_q) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Equal => encoding == Self::Brotli && current != Self::Brotli,
+                std::cmp::Ordering::Less => false,
+            });
+            if better {
+                best = Some((encoding, q));
+            }
+        }
+        best.map(|(encoding, _)| encoding)
+    }
+
+    /// Compress `data` with this coding
+    ///
+    /// # Errors
+    ///
+    /// Returns an I/O error if the encoder fails.
+    pub fn compress(self, data: &[u8]) -> std::io::Result<Vec<u8>> {
+        match self {
+            Self::Brotli => {
+                let mut writer = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+                writer.write_all(data)?;
+                writer.flush()?;
+                Ok(writer.into_inner())
+            }
+            Self::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+/// Response compression middleware - gzip/brotli based on `Accept-Encoding`
+///
+/// Only buffered bodies are compressed; streaming responses (including SSE)
+/// pass through untouched.
+#[derive(Clone)]
+pub struct CompressionMiddleware {
+    min_size: usize,
+    content_types: Vec<String>,
+}
+
+impl Default for CompressionMiddleware {
+    fn default() -> Self {
+        Self {
+            min_size: 1024,
+            content_types: [
+                "text/",
+                "application/json",
+                "application/javascript",
+                "application/xml",
+                "image/svg+xml",
+            ]
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        }
+    }
+}
+
+impl CompressionMiddleware {
+    /// Create a new compression middleware with default settings
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum body size (bytes) worth compressing
+    #[must_use]
+    pub const fn with_min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the compressible content types (entries ending in `/` match a prefix)
+    #[must_use]
+    pub fn with_content_types<I, S>(mut self, content_types: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.content_types = content_types
+            .into_iter()
+            .map(|ct| ct.into().to_ascii_lowercase())
+            .collect();
+        self
+    }
+
+    fn is_compressible(&self, content_type: &str) -> bool {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or("")
+            .trim()
+            .to_ascii_lowercase();
+        self.content_types.iter().any(|allowed| {
+            if allowed.ends_with('/') {
+                mime.starts_with(allowed.as_str())
+            } else {
+                mime == *allowed
+            }
+        })
+    }
+
+    /// Compress `res` in place if the client and response allow it
+    pub fn compress_response(&self, accept_encoding: Option<&str>, res: &mut PyResponse) {
+        if res.is_streaming()
+            || res.body.len() < self.min_size
+            || matches!(res.status, 100..=199 | 204 | 304)
+            || res
+                .headers
+                .keys()
+                .any(|k| k.eq_ignore_ascii_case("content-encoding"))
+            || !self.is_compressible(&res.content_type)
+        {
+            return;
+        }
+        let Some(encoding) = accept_encoding.and_then(ContentEncoding::negotiate) else {
+            return;
+        };
+        let compressed = match encoding.compress(res.body.as_bytes()) {
+            Ok(compressed) if compressed.len() < res.body.len() => compressed,
+            Ok(_) => return,
+            Err(e) => {
+                debug!(error = %e, "Response compression failed");
+                return;
+            }
+        };
+
+        let (tx, rx) = mpsc::channel(1);
+        if tx.try_send(Bytes::from(compressed)).is_err() {
+            return;
+        }
+        res.body.clear();
+        res.stream = Some(rx);
+        res.set_header("Content-Encoding", encoding.as_str());
+        append_vary(res, "Accept-Encoding");
+    }
+}
+
+impl Middleware for CompressionMiddleware {
+    fn after_response(&self, req: &PyRequest, res: &mut PyResponse) {
+        self.compress_response(req.header("accept-encoding"), res);
+    }
+
+    fn name(&self) -> &'static str {
+        "CompressionMiddleware"
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -376,4 +974,196 @@ mod tests {
         let mw = CorsMiddleware::new().allow_origin("https://example.com");
         assert_eq!(mw.origin(), "https://example.com");
     }
+
+    #[test]
+    fn test_cors_preflight_short_circuits() {
+        let mw = CorsMiddleware::new().max_age(3600);
+        let headers = HashMap::from([
+            ("Origin".to_string(), "https://example.com".to_string()),
+            (
+                "Access-Control-Request-Method".to_string(),
+                "POST".to_string(),
+            ),
+        ]);
+        let mut preflight = PyRequest::new(Method::Options, "/items".into(), headers, None);
+        let MiddlewareResult::Respond(res) = mw.before_request(&mut preflight) else {
+            panic!("preflight was not answered");
+        };
+        assert_eq!(res.status, 204);
+        assert_eq!(res.headers.get("Access-Control-Max-Age").unwrap(), "3600");
+        assert_eq!(res.headers.get("Access-Control-Allow-Origin").unwrap(), "*");
+
+        let mut plain = PyRequest::new(Method::Options, "/items".into(), HashMap::new(), None);
+        assert!(matches!(
+            mw.before_request(&mut plain),
+            MiddlewareResult::Continue
+        ));
+    }
+
+    #[test]
+    fn test_tiered_rate_limit() {
+        let tiers = HashMap::from([
+            ("free".to_string(), TierLimit::per_minute(2)),
+            ("pro".to_string(), TierLimit::per_minute(600)),
+        ]);
+        assert!(TieredRateLimitMiddleware::new(tiers.clone(), "anonymous").is_err());
+        let mw = TieredRateLimitMiddleware::new(tiers, "free").unwrap();
+        let request = |claims: serde_json::Value| {
+            let mut req = PyRequest::new(Method::Get, "/".into(), HashMap::new(), None);
+            req.claims = Some(claims);
+            req
+        };
+
+        let mut free = request(serde_json::json!({ "sub": "ann" }));
+        assert!(matches!(
+            mw.before_request(&mut free),
+            MiddlewareResult::Continue
+        ));
+        assert_eq!(
+            free.state.get(RATE_LIMIT_STATE_KEY).unwrap(),
+            serde_json::json!({ "tier": "free", "limit": 2, "remaining": 1 })
+        );
+        let mut res = PyResponse::json("{}");
+        mw.after_response(&free, &mut res);
+        assert_eq!(res.headers.get("X-RateLimit-Remaining").unwrap(), "1");
+        assert!(matches!(
+            mw.before_request(&mut free),
+            MiddlewareResult::Continue
+        ));
+        let MiddlewareResult::Respond(limited) = mw.before_request(&mut free) else {
+            panic!("third free request was allowed");
+        };
+        assert_eq!(limited.status, 429);
+        assert_eq!(limited.headers.get("Retry-After").unwrap(), "30");
+
+        // Another caller, and a higher tier for the same caller, have their own buckets
+        let mut other = request(serde_json::json!({ "sub": "bob", "tier": "gold" }));
+        assert!(matches!(
+            mw.before_request(&mut other),
+            MiddlewareResult::Continue
+        ));
+        let mut pro = request(serde_json::json!({ "sub": "ann", "tier": "pro" }));
+        assert!(matches!(
+            mw.before_request(&mut pro),
+            MiddlewareResult::Continue
+        ));
+        assert_eq!(mw.tier_of(&pro).0, "pro");
+
+        let mw = mw.resolver(Arc::new(|req: &PyRequest| {
+            req.header("x-plan").map(String::from)
+        }));
+        pro.set_header("x-plan", "free");
+        assert_eq!(mw.tier_of(&pro).0, "free");
+    }
+
+    fn request_from(origin: &str) -> PyRequest {
+        let headers = HashMap::from([("Origin".to_string(), origin.to_string())]);
+        PyRequest::new(Method::Get, "/".into(), headers, None)
+    }
+
+    #[test]
+    fn test_cors_origin_allowlist_reflects_and_varies() {
+        let mw = CorsMiddleware::new()
+            .allow_origins(["https://a.example", "https://b.example"])
+            .allow_credentials(true)
+            .expose_headers("X-Total-Count");
+
+        let mut res = PyResponse::json("{}");
+        mw.after_response(&request_from("https://b.example"), &mut res);
+        assert_eq!(
+            res.headers.get("Access-Control-Allow-Origin").unwrap(),
+            "https://b.example"
+        );
+        assert_eq!(
+            res.headers.get("Access-Control-Allow-Credentials").unwrap(),
+            "true"
+        );
+        assert_eq!(
+            res.headers.get("Access-Control-Expose-Headers").unwrap(),
+            "X-Total-Count"
+        );
+        assert_eq!(res.headers.get("Vary").unwrap(), "Origin");
+
+        let mut res = PyResponse::json("{}");
+        mw.after_response(&request_from("https://evil.example"), &mut res);
+        assert!(!res.headers.contains_key("Access-Control-Allow-Origin"));
+    }
+
+    #[test]
+    fn test_cors_validator_and_credentials_for_wildcard_rejected() {
+        let mw = CorsMiddleware::new()
+            .origin_validator(Arc::new(|origin: &str| origin.ends_with(".example.com")))
+            .allow_credentials(true);
+        assert!(mw.validate().is_ok());
+        assert_eq!(
+            mw.resolve_origin(Some("https://app.example.com"))
+                .as_deref(),
+            Some("https://app.example.com")
+        );
+        assert_eq!(mw.resolve_origin(Some("https://example.org")), None);
+
+        let wildcard = CorsMiddleware::new().allow_credentials(true);
+        assert!(matches!(
+            wildcard.validate(),
+            Err(crate::error::Error::Config { .. })
+        ));
+        assert!(CorsMiddleware::new().validate().is_ok());
+        assert_eq!(
+            CorsMiddleware::new()
+                .resolve_origin(Some("https://x.dev"))
+                .as_deref(),
+            Some("*")
+        );
+    }
+
+    #[test]
+    fn test_content_encoding_negotiation() {
+        assert_eq!(
+            ContentEncoding::negotiate("gzip, deflate, br"),
+            Some(ContentEncoding::Brotli)
+        );
+        assert_eq!(
+            ContentEncoding::negotiate("br;q=0.5, gzip"),
+            Some(ContentEncoding::Gzip)
+        );
+        assert_eq!(ContentEncoding::negotiate("br;q=0, identity"), None);
+        assert_eq!(ContentEncoding::negotiate(""), None);
+    }
+
+    #[test]
+    fn test_compression_middleware_gzip_round_trip() {
+        use std::io::Read;
+
+        let body = "hello world ".repeat(200);
+        let mut res = PyResponse::text(body.clone()).with_header("Vary", "Origin");
+        CompressionMiddleware::new().compress_response(Some("gzip"), &mut res);
+
+        assert_eq!(res.headers.get("Content-Encoding").unwrap(), "gzip");
+        assert_eq!(res.headers.get("Vary").unwrap(), "Origin, Accept-Encoding");
+        let chunk = res.stream.as_mut().unwrap().try_recv().unwrap();
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&chunk[..])
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, body);
+    }
+
+    #[test]
+    fn test_compression_middleware_skips_small_and_disallowed() {
+        let mw = CompressionMiddleware::new().with_min_size(16);
+
+        let mut small = PyResponse::text("tiny");
+        mw.compress_response(Some("br"), &mut small);
+        assert!(!small.is_streaming());
+
+        let mut binary = PyResponse::text("x".repeat(64)).with_header("Content-Type", "image/png");
+        mw.compress_response(Some("br"), &mut binary);
+        assert!(!binary.is_streaming());
+
+        let mut json = PyResponse::json("x".repeat(64));
+        mw.compress_response(None, &mut json);
+        assert!(!json.is_streaming());
+        mw.compress_response(Some("br"), &mut json);
+        assert_eq!(json.headers.get("Content-Encoding").unwrap(), "br");
+    }
 }
@@ -0,0 +1,664 @@
+//! # Multipart Forms
+//!
+//! Streaming `multipart/form-data` parser. The body is fed in chunks of
+//! any size and each part is written out as it arrives: text fields stay in
+//! memory, uploads above `spool_threshold` move to a temp file, and every
+//! part is held to `max_part_size`.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only splits and stores parts; the request decides when to parse
+//! - **O**: `feed` accepts any chunking, so a streamed body can drive it
+
+use crate::error::{Error, RequestErrorKind, Result};
+use hyper::body::Bytes;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyBytes;
+use std::fs::File;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Largest header block accepted for one part
+const MAX_PART_HEADER_SIZE: usize = 8 * 1024;
+
+/// Chunk size `parse` feeds a collected body in
+const FEED_CHUNK_SIZE: usize = 64 * 1024;
+
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    pyvectora,
+    MultipartError,
+    pyo3::exceptions::PyValueError,
+    "Malformed form body (answered 400)."
+);
+
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    pyvectora,
+    UploadTooLarge,
+    MultipartError,
+    "A form part exceeded `max_part_size` (answered 413)."
+);
+
+/// Limits applied while parsing a multipart body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultipartLimits {
+    /// Largest single part (field or file) in bytes
+    pub max_part_size: usize,
+    /// Most parts accepted in one body
+    pub max_parts: usize,
+    /// Upload size above which the file is spooled to disk
+    pub spool_threshold: usize,
+    /// Directory for spooled uploads (`None` = system temp dir)
+    pub temp_dir: Option<PathBuf>,
+}
+
+impl Default for MultipartLimits {
+    fn default() -> Self {
+        Self {
+            max_part_size: 10 * 1024 * 1024,
+            max_parts: 100,
+            spool_threshold: 1024 * 1024,
+            temp_dir: None,
+        }
+    }
+}
+
+/// Fields and files of a parsed form body
+#[derive(Debug, Default)]
+pub struct FormData {
+    /// Text fields in body order
+    pub fields: Vec<(String, String)>,
+    /// Uploaded files in body order
+    pub files: Vec<UploadedFile>,
+}
+
+impl FormData {
+    /// Last value sent for field `name`
+    #[must_use]
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .rev()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Last file uploaded as `name`
+    #[must_use]
+    pub fn file(&self, name: &str) -> Option<&UploadedFile> {
+        self.files.iter().rev().find(|file| file.name == name)
+    }
+}
+
+/// Temp file holding a spooled upload, removed when dropped
+#[derive(Debug)]
+pub struct SpooledFile {
+    path: PathBuf,
+}
+
+impl SpooledFile {
+    /// Create an empty, uniquely named file in `dir`
+    fn create(dir: &Path) -> Result<(Self, File)> {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.subsec_nanos());
+        let path = dir.join(format!(
+            "pyvectora-upload-{}-{}-{nanos:08x}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let file = options.open(&path)?;
+        Ok((Self { path }, file))
+    }
+
+    /// Location of the temp file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for SpooledFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Where an upload's bytes are kept
+#[derive(Debug, Clone)]
+enum FileContent {
+    Memory(Bytes),
+    Spooled(Arc<SpooledFile>),
+}
+
+/// A file part of a form body
+///
+/// Clones share the content; a spooled temp file is removed once the last
+/// clone is dropped.
+#[cfg_attr(feature = "python", pyclass(name = "UploadedFile"))]
+#[derive(Debug, Clone)]
+pub struct UploadedFile {
+    /// Form field name
+    pub name: String,
+    /// Filename sent by the client
+    pub filename: String,
+    /// `Content-Type` of the part, if sent
+    pub content_type: Option<String>,
+    /// Size in bytes
+    pub size: usize,
+    content: FileContent,
+}
+
+impl UploadedFile {
+    /// Temp file path when the upload was spooled to disk
+    #[must_use]
+    pub fn path(&self) -> Option<&Path> {
+        match &self.content {
+            FileContent::Memory(_) => None,
+            FileContent::Spooled(file) => Some(file.path()),
+        }
+    }
+
+    /// Read the whole upload
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if a spooled file cannot be read.
+    pub fn read(&self) -> Result<Bytes> {
+        match &self.content {
+            FileContent::Memory(bytes) => Ok(bytes.clone()),
+            FileContent::Spooled(file) => Ok(Bytes::from(std::fs::read(file.path())?)),
+        }
+    }
+
+    /// Copy the upload to `dest`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Io` if `dest` cannot be written.
+    pub fn save(&self, dest: &Path) -> Result<()> {
+        match &self.content {
+            FileContent::Memory(bytes) => std::fs::write(dest, bytes)?,
+            FileContent::Spooled(file) => {
+                std::fs::copy(file.path(), dest)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl UploadedFile {
+    /// Form field name
+    #[getter(name)]
+    fn py_name(&self) -> String {
+        self.name.clone()
+    }
+
+    /// Filename sent by the client
+    #[getter(filename)]
+    fn py_filename(&self) -> String {
+        self.filename.clone()
+    }
+
+    /// `Content-Type` of the part, if sent
+    #[getter(content_type)]
+    fn py_content_type(&self) -> Option<String> {
+        self.content_type.clone()
+    }
+
+    /// Size in bytes
+    #[getter(size)]
+    fn py_size(&self) -> usize {
+        self.size
+    }
+
+    /// Temp file path when spooled to disk, else None
+    #[getter(path)]
+    fn py_path(&self) -> Option<String> {
+        self.path().map(|path| path.to_string_lossy().into_owned())
+    }
+
+    /// Read the whole upload as bytes
+    #[pyo3(name = "read")]
+    fn py_read(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let bytes = self.read().map_err(to_py_error)?;
+        Ok(PyBytes::new(py, &bytes).into())
+    }
+
+    /// Copy the upload to `path`
+    #[pyo3(name = "save")]
+    fn py_save(&self, path: PathBuf) -> PyResult<()> {
+        self.save(&path).map_err(to_py_error)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "UploadedFile(name={:?}, filename={:?}, size={})",
+            self.name, self.filename, self.size
+        )
+    }
+}
+
+/// Python exception for a parse or storage failure
+///
+/// Oversized parts raise `UploadTooLarge`, I/O failures `OSError` and
+/// anything else `MultipartError`.
+#[cfg(feature = "python")]
+pub fn to_py_error(err: Error) -> PyErr {
+    match err {
+        Error::PayloadTooLarge { .. } => UploadTooLarge::new_err(err.to_string()),
+        Error::Io(e) => e.into(),
+        other => MultipartError::new_err(other.to_string()),
+    }
+}
+
+/// Boundary of a `multipart/form-data` content type, if it is one
+#[must_use]
+pub fn boundary(content_type: &str) -> Option<String> {
+    let mut params = content_type.split(';');
+    let media_type = params.next()?.trim();
+    if !media_type.eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params
+        .filter_map(|param| param.split_once('='))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .filter(|boundary| (1..=70).contains(&boundary.len()))
+}
+
+/// Parse a collected multipart body sent with `content_type`
+///
+/// # Errors
+///
+/// Returns `Error::BadRequest` for a malformed body or content type,
+/// `Error::PayloadTooLarge` for a part above `max_part_size` and
+/// `Error::Io` if an upload cannot be spooled.
+pub fn parse(content_type: &str, body: &[u8], limits: &MultipartLimits) -> Result<FormData> {
+    let boundary = boundary(content_type)
+        .ok_or_else(|| invalid("Content-Type is not multipart/form-data with a boundary"))?;
+    let mut parser = MultipartParser::new(&boundary, limits.clone());
+    for chunk in body.chunks(FEED_CHUNK_SIZE) {
+        parser.feed(chunk)?;
+    }
+    parser.finish()
+}
+
+fn invalid(message: impl Into<String>) -> Error {
+    Error::BadRequest {
+        kind: RequestErrorKind::InvalidMultipart,
+        message: message.into(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the first boundary
+    Preamble,
+    /// Just after a boundary: `--` ends the body, CRLF starts a part
+    Boundary,
+    /// Reading a part's headers
+    Headers,
+    /// Reading a part's body up to the next boundary
+    Body,
+    /// After the closing boundary
+    Done,
+}
+
+/// Incremental `multipart/form-data` parser
+///
+/// Feed body chunks with [`feed`](Self::feed), then call
+/// [`finish`](Self::finish). Only a delimiter's worth of bytes is buffered
+/// between chunks, besides parts still below the spool threshold.
+#[derive(Debug)]
+pub struct MultipartParser {
+    /// `\r\n--boundary`
+    delimiter: Vec<u8>,
+    limits: MultipartLimits,
+    state: State,
+    buf: Vec<u8>,
+    part: Option<PartWriter>,
+    form: FormData,
+    parts: usize,
+}
+
+impl MultipartParser {
+    /// Parser for a body delimited by `boundary`
+    #[must_use]
+    pub fn new(boundary: &str, limits: MultipartLimits) -> Self {
+        let delimiter = [b"\r\n--", boundary.as_bytes()].concat();
+        Self {
+            delimiter,
+            limits,
+            state: State::Preamble,
+            // The first boundary need not follow a line break
+            buf: b"\r\n".to_vec(),
+            part: None,
+            form: FormData::default(),
+            parts: 0,
+        }
+    }
+
+    /// Consume the next chunk of the body
+    ///
+    /// # Errors
+    ///
+    /// Fails as [`parse`] does; the parser must not be fed afterwards.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<()> {
+        if self.state == State::Done {
+            return Ok(());
+        }
+        self.buf.extend_from_slice(chunk);
+        while self.step()? {}
+        Ok(())
+    }
+
+    /// Finish after the last chunk, returning the parsed form
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BadRequest` if the closing boundary was not seen.
+    pub fn finish(self) -> Result<FormData> {
+        if self.state != State::Done {
+            return Err(invalid("body ended before the closing boundary"));
+        }
+        Ok(self.form)
+    }
+
+    /// Advance one state; `false` when more input is needed
+    fn step(&mut self) -> Result<bool> {
+        match self.state {
+            State::Preamble => match find(&self.buf, &self.delimiter) {
+                Some(pos) => {
+                    self.buf.drain(..pos + self.delimiter.len());
+                    self.state = State::Boundary;
+                    Ok(true)
+                }
+                None => {
+                    let keep = self.delimiter.len() - 1;
+                    let discard = self.buf.len().saturating_sub(keep);
+                    self.buf.drain(..discard);
+                    Ok(false)
+                }
+            },
+            State::Boundary => {
+                if self.buf.len() < 2 {
+                    return Ok(false);
+                }
+                match &self.buf[..2] {
+                    b"--" => {
+                        self.buf.clear();
+                        self.state = State::Done;
+                        Ok(false)
+                    }
+                    b"\r\n" => {
+                        self.buf.drain(..2);
+                        self.state = State::Headers;
+                        Ok(true)
+                    }
+                    _ => Err(invalid("boundary is not followed by a line break")),
+                }
+            }
+            State::Headers => {
+                let (block, consumed) = if self.buf.starts_with(b"\r\n") {
+                    (String::new(), 2)
+                } else if let Some(pos) = find(&self.buf, b"\r\n\r\n") {
+                    let block = std::str::from_utf8(&self.buf[..pos])
+                        .map_err(|_| invalid("part headers are not valid UTF-8"))?
+                        .to_string();
+                    (block, pos + 4)
+                } else if self.buf.len() > MAX_PART_HEADER_SIZE {
+                    return Err(invalid("part headers are too large"));
+                } else {
+                    return Ok(false);
+                };
+                self.buf.drain(..consumed);
+                self.parts += 1;
+                if self.parts > self.limits.max_parts {
+                    return Err(invalid(format!(
+                        "more than {} parts in form body",
+                        self.limits.max_parts
+                    )));
+                }
+                self.part = Some(PartWriter::from_headers(&block)?);
+                self.state = State::Body;
+                Ok(true)
+            }
+            State::Body => {
+                let found = find(&self.buf, &self.delimiter);
+                let end = found
+                    .unwrap_or_else(|| self.buf.len().saturating_sub(self.delimiter.len() - 1));
+                let part = self.part.as_mut().expect("part is open in body state");
+                part.write(&self.buf[..end], &self.limits)?;
+                match found {
+                    Some(pos) => {
+                        self.buf.drain(..pos + self.delimiter.len());
+                        let part = self.part.take().expect("part is open in body state");
+                        part.finish(&mut self.form)?;
+                        self.state = State::Boundary;
+                        Ok(true)
+                    }
+                    None => {
+                        self.buf.drain(..end);
+                        Ok(false)
+                    }
+                }
+            }
+            State::Done => Ok(false),
+        }
+    }
+}
+
+/// A part being written
+#[derive(Debug)]
+struct PartWriter {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    size: usize,
+    memory: Vec<u8>,
+    spooled: Option<(SpooledFile, File)>,
+}
+
+impl PartWriter {
+    /// Open a part from its header block
+    fn from_headers(block: &str) -> Result<Self> {
+        let mut name = None;
+        let mut filename = None;
+        let mut content_type = None;
+        for line in block.split("\r\n") {
+            let Some((key, value)) = line.split_once(':') else {
+                return Err(invalid(format!("malformed part header {line:?}")));
+            };
+            let key = key.trim();
+            if key.eq_ignore_ascii_case("content-disposition") {
+                for param in value.split(';').skip(1) {
+                    let Some((param, value)) = param.split_once('=') else {
+                        continue;
+                    };
+                    let value = value.trim().trim_matches('"').to_string();
+                    match param.trim().to_ascii_lowercase().as_str() {
+                        "name" => name = Some(value),
+                        "filename" => filename = Some(value),
+                        _ => {}
+                    }
+                }
+            } else if key.eq_ignore_ascii_case("content-type") {
+                content_type = Some(value.trim().to_string());
+            }
+        }
+        Ok(Self {
+            name: name.ok_or_else(|| invalid("part has no Content-Disposition name"))?,
+            filename,
+            content_type,
+            size: 0,
+            memory: Vec::new(),
+            spooled: None,
+        })
+    }
+
+    /// Append body bytes, spooling uploads that outgrow memory
+    fn write(&mut self, data: &[u8], limits: &MultipartLimits) -> Result<()> {
+        self.size += data.len();
+        if self.size > limits.max_part_size {
+            return Err(Error::PayloadTooLarge {
+                limit: limits.max_part_size,
+                actual: self.size,
+            });
+        }
+        if let Some((_, file)) = &mut self.spooled {
+            file.write_all(data)?;
+            return Ok(());
+        }
+        self.memory.extend_from_slice(data);
+        if self.filename.is_some() && self.memory.len() > limits.spool_threshold {
+            let dir = limits.temp_dir.clone().unwrap_or_else(std::env::temp_dir);
+            let (spooled, mut file) = SpooledFile::create(&dir)?;
+            file.write_all(&self.memory)?;
+            self.memory = Vec::new();
+            self.spooled = Some((spooled, file));
+        }
+        Ok(())
+    }
+
+    /// Add the completed part to `form`
+    fn finish(self, form: &mut FormData) -> Result<()> {
+        let Some(filename) = self.filename else {
+            let value = String::from_utf8(self.memory)
+                .map_err(|_| invalid(format!("field {:?} is not valid UTF-8", self.name)))?;
+            form.fields.push((self.name, value));
+            return Ok(());
+        };
+        let content = match self.spooled {
+            Some((spooled, mut file)) => {
+                file.flush()?;
+                FileContent::Spooled(Arc::new(spooled))
+            }
+            None => FileContent::Memory(Bytes::from(self.memory)),
+        };
+        form.files.push(UploadedFile {
+            name: self.name,
+            filename,
+            content_type: self.content_type,
+            size: self.size,
+            content,
+        });
+        Ok(())
+    }
+}
+
+/// Position of the first `needle` in `haystack`
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT_TYPE: &str = "multipart/form-data; boundary=XyZ";
+
+    fn body() -> Vec<u8> {
+        [
+            "--XyZ\r\n",
+            "Content-Disposition: form-data; name=\"title\"\r\n\r\n",
+            "Quarterly report\r\n",
+            "--XyZ\r\n",
+            "Content-Disposition: form-data; name=\"doc\"; filename=\"q3.csv\"\r\n",
+            "Content-Type: text/csv\r\n\r\n",
+            "region,total\r\neu,42\r\n",
+            "--XyZ--\r\n",
+        ]
+        .concat()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_boundary() {
+        assert_eq!(boundary(CONTENT_TYPE).as_deref(), Some("XyZ"));
+        assert_eq!(
+            boundary("Multipart/Form-Data; charset=utf-8; boundary=\"a b\"").as_deref(),
+            Some("a b")
+        );
+        assert_eq!(boundary("application/json"), None);
+        assert_eq!(boundary("multipart/form-data"), None);
+    }
+
+    #[test]
+    fn test_fields_and_files_across_chunkings() {
+        let body = body();
+        for chunk_size in [1, 3, 7, body.len()] {
+            let mut parser = MultipartParser::new("XyZ", MultipartLimits::default());
+            for chunk in body.chunks(chunk_size) {
+                parser.feed(chunk).unwrap();
+            }
+            let form = parser.finish().unwrap();
+            assert_eq!(form.field("title"), Some("Quarterly report"));
+            let doc = form.file("doc").unwrap();
+            assert_eq!(doc.filename, "q3.csv");
+            assert_eq!(doc.content_type.as_deref(), Some("text/csv"));
+            assert_eq!(&doc.read().unwrap()[..], b"region,total\r\neu,42");
+            assert!(doc.path().is_none());
+        }
+    }
+
+    #[test]
+    fn test_large_uploads_are_spooled_and_removed() {
+        let limits = MultipartLimits {
+            spool_threshold: 8,
+            ..MultipartLimits::default()
+        };
+        let form = parse(CONTENT_TYPE, &body(), &limits).unwrap();
+        let doc = form.file("doc").unwrap().clone();
+        let path = doc.path().unwrap().to_path_buf();
+        assert_eq!(std::fs::read(&path).unwrap(), b"region,total\r\neu,42");
+        assert_eq!(doc.size, 19);
+
+        drop(form);
+        assert!(path.exists());
+        drop(doc);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_limits_and_malformed_bodies() {
+        let limits = MultipartLimits {
+            max_part_size: 10,
+            ..MultipartLimits::default()
+        };
+        assert!(matches!(
+            parse(CONTENT_TYPE, &body(), &limits),
+            Err(Error::PayloadTooLarge { limit: 10, .. })
+        ));
+
+        let limits = MultipartLimits {
+            max_parts: 1,
+            ..MultipartLimits::default()
+        };
+        assert!(parse(CONTENT_TYPE, &body(), &limits).is_err());
+
+        let truncated = &body()[..40];
+        assert!(matches!(
+            parse(CONTENT_TYPE, truncated, &MultipartLimits::default()),
+            Err(Error::BadRequest {
+                kind: RequestErrorKind::InvalidMultipart,
+                ..
+            })
+        ));
+        assert!(parse("text/plain", &body(), &MultipartLimits::default()).is_err());
+    }
+}
@@ -0,0 +1,110 @@
+//! # Problem Details
+//!
+//! RFC 7807 `application/problem+json` bodies for errors the framework
+//! generates itself (404, 413, 422, 429, 500, ...). Such responses carry a
+//! [`ProblemDetails`] next to their default JSON body; servers set to
+//! [`ErrorFormat::Problem`] send it instead, with the request path as
+//! `instance`.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only describes and renders problem documents
+//! - **O**: Error sites attach details; the output format is chosen per server
+
+use serde_json::{Map, Value};
+
+/// Content type of problem documents
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// Body format of framework-generated errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorFormat {
+    /// `{"error": ...}` JSON bodies (backward compatible)
+    #[default]
+    Json,
+    /// RFC 7807 `application/problem+json` documents
+    Problem,
+}
+
+/// An RFC 7807 problem document
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type (`about:blank` for plain HTTP errors)
+    pub problem_type: String,
+    /// HTTP status code
+    pub status: u16,
+    /// Short summary, the reason phrase for `about:blank`
+    pub title: String,
+    /// Explanation of this occurrence
+    pub detail: Option<String>,
+    /// Extension members (e.g. `errors` for validation failures)
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    /// A plain HTTP error titled with the status' reason phrase
+    #[must_use]
+    pub fn new(status: u16) -> Self {
+        Self {
+            problem_type: "about:blank".to_string(),
+            status,
+            title: crate::server::PyResponse::reason_phrase(status)
+                .unwrap_or("Error")
+                .to_string(),
+            detail: None,
+            extensions: Map::new(),
+        }
+    }
+
+    /// Explain this occurrence
+    #[must_use]
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// Add an extension member
+    #[must_use]
+    pub fn with_extension(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.extensions.insert(name.into(), value);
+        self
+    }
+
+    /// Render the document, identifying the occurrence by `instance`
+    ///
+    /// Extension members never override the standard ones.
+    #[must_use]
+    pub fn to_json(&self, instance: Option<&str>) -> String {
+        let mut doc = self.extensions.clone();
+        doc.insert("type".to_string(), Value::from(self.problem_type.as_str()));
+        doc.insert("title".to_string(), Value::from(self.title.as_str()));
+        doc.insert("status".to_string(), Value::from(self.status));
+        if let Some(detail) = &self.detail {
+            doc.insert("detail".to_string(), Value::from(detail.as_str()));
+        }
+        if let Some(instance) = instance {
+            doc.insert("instance".to_string(), Value::from(instance));
+        }
+        Value::Object(doc).to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_problem_document_fields() {
+        let problem = ProblemDetails::new(422)
+            .with_detail("Invalid path parameters")
+            .with_extension("errors", serde_json::json!([]))
+            .with_extension("status", Value::from(200));
+        let doc: Value = serde_json::from_str(&problem.to_json(Some("/users/abc"))).unwrap();
+        assert_eq!(doc["type"], "about:blank");
+        assert_eq!(doc["title"], "Unprocessable Entity");
+        assert_eq!(doc["status"], 422);
+        assert_eq!(doc["detail"], "Invalid path parameters");
+        assert_eq!(doc["instance"], "/users/abc");
+        assert_eq!(doc["errors"], serde_json::json!([]));
+    }
+}
@@ -0,0 +1,393 @@
+//! # Profiling Endpoints
+//!
+//! Admin-only endpoints for debugging latency in a running server without
+//! restarting it:
+//!
+//! - `GET {prefix}/runtime` - Tokio runtime metrics and RSS as JSON
+//! - `GET {prefix}/cpu?seconds=10&format=pprof|flamegraph` - CPU profile
+//!   (requires the `profiling` feature)
+//! - `GET {prefix}/py-spy` - `py-spy dump` of the process, when enabled and
+//!   `py-spy` is on `PATH`
+//!
+//! Requests must send `Authorization: Bearer <admin token>`. The token is
+//! separate from the JWT secret so API users cannot reach these endpoints.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only captures diagnostics; routing and auth stay in `Server`
+//! - **O**: New diagnostics are new routes under the same prefix
+
+use crate::error::{Error, Result};
+use crate::extension::ServerExtension;
+use crate::request::PyRequest;
+use crate::router::Method;
+use crate::server::{Bytes, Handler, PyResponse, Server};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Default mount point for the profiling endpoints
+pub const DEFAULT_PREFIX: &str = "/_pyvectora/profile";
+
+/// CPU profile length when `seconds` is not given
+const DEFAULT_PROFILE_SECONDS: u64 = 10;
+
+/// Upper bound on a single CPU profile
+const MAX_PROFILE_SECONDS: u64 = 60;
+
+/// CPU sampling frequency in Hz
+#[cfg(feature = "profiling")]
+const SAMPLE_FREQUENCY: i32 = 99;
+
+/// Point-in-time view of the async runtime
+#[derive(Debug, Clone, Serialize)]
+pub struct RuntimeSnapshot {
+    /// Process id (for attaching external profilers)
+    pub pid: u32,
+    /// Runtime worker threads
+    pub workers: usize,
+    /// Tasks currently alive
+    pub alive_tasks: usize,
+    /// Tasks waiting in the global injection queue
+    pub global_queue_depth: usize,
+    /// Resident set size in bytes, if known
+    pub rss_bytes: Option<u64>,
+}
+
+impl RuntimeSnapshot {
+    /// Capture metrics of the current Tokio runtime
+    ///
+    /// Runtime counters are zero outside a runtime.
+    #[must_use]
+    pub fn capture() -> Self {
+        let (workers, alive_tasks, global_queue_depth) = tokio::runtime::Handle::try_current()
+            .map_or((0, 0, 0), |handle| {
+                let metrics = handle.metrics();
+                (
+                    metrics.num_workers(),
+                    metrics.num_alive_tasks(),
+                    metrics.global_queue_depth(),
+                )
+            });
+        Self {
+            pid: std::process::id(),
+            workers,
+            alive_tasks,
+            global_queue_depth,
+            rss_bytes: crate::watchdog::current_rss(),
+        }
+    }
+}
+
+/// Output format of a CPU profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileFormat {
+    /// pprof protobuf (`go tool pprof`, Pyroscope, ...)
+    Pprof,
+    /// Flamegraph SVG
+    Flamegraph,
+}
+
+impl ProfileFormat {
+    /// Parse the `format` query value
+    #[must_use]
+    pub fn from_query(value: &str) -> Option<Self> {
+        match value {
+            "pprof" => Some(Self::Pprof),
+            "flamegraph" | "svg" => Some(Self::Flamegraph),
+            _ => None,
+        }
+    }
+
+    /// Content type of the encoded profile
+    #[must_use]
+    pub const fn content_type(self) -> &'static str {
+        match self {
+            Self::Pprof => "application/octet-stream",
+            Self::Flamegraph => "image/svg+xml",
+        }
+    }
+}
+
+/// Sample the whole process for `duration` and encode the profile
+///
+/// Blocks the calling thread for `duration`; run it off the async runtime.
+///
+/// # Errors
+///
+/// Returns `Error::Config` if the profiler cannot be started or the report
+/// cannot be encoded.
+#[cfg(feature = "profiling")]
+pub fn cpu_profile(duration: Duration, format: ProfileFormat) -> Result<Vec<u8>> {
+    let profile_error = |e: pprof::Error| Error::Config {
+        message: format!("CPU profiling failed: {e}"),
+    };
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(SAMPLE_FREQUENCY)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .map_err(profile_error)?;
+    std::thread::sleep(duration);
+    let report = guard.report().build().map_err(profile_error)?;
+    let mut out = Vec::new();
+    match format {
+        ProfileFormat::Pprof => {
+            use pprof::protos::Message;
+            report
+                .pprof()
+                .map_err(profile_error)?
+                .write_to_vec(&mut out)
+                .map_err(|e| Error::Config {
+                    message: format!("CPU profiling failed: {e}"),
+                })?;
+        }
+        ProfileFormat::Flamegraph => report.flamegraph(&mut out).map_err(profile_error)?,
+    }
+    Ok(out)
+}
+
+/// Sample the whole process for `duration` and encode the profile
+///
+/// # Errors
+///
+/// Always returns `Error::Config`: built without the `profiling` feature.
+#[cfg(not(feature = "profiling"))]
+pub fn cpu_profile(duration: Duration, format: ProfileFormat) -> Result<Vec<u8>> {
+    let _ = (duration, format);
+    Err(Error::Config {
+        message: "CPU profiling requires the `profiling` feature".to_string(),
+    })
+}
+
+/// Admin-protected profiling routes
+#[derive(Debug, Clone)]
+pub struct ProfilingEndpoints {
+    prefix: String,
+    token: Arc<str>,
+    py_spy: bool,
+}
+
+impl ProfilingEndpoints {
+    /// Endpoints under `prefix`, guarded by the bearer `token`
+    #[must_use]
+    pub fn new(prefix: &str, token: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('/').to_string(),
+            token: Arc::from(token),
+            py_spy: false,
+        }
+    }
+
+    /// Also expose `{prefix}/py-spy` (runs `py-spy dump` on this process)
+    #[must_use]
+    pub const fn with_py_spy(mut self, enabled: bool) -> Self {
+        self.py_spy = enabled;
+        self
+    }
+
+    /// Register the routes on `server`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a route conflicts with an existing one.
+    pub fn register(&self, server: &mut Server) -> Result<()> {
+        server.add_route(
+            Method::Get,
+            &format!("{}/runtime", self.prefix),
+            self.guarded(|_req| Box::pin(async { runtime_response() })),
+            false,
+        )?;
+        server.add_route(
+            Method::Get,
+            &format!("{}/cpu", self.prefix),
+            self.guarded(|req| {
+                let query = req.query_map();
+                let seconds = query
+                    .get("seconds")
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(DEFAULT_PROFILE_SECONDS)
+                    .clamp(1, MAX_PROFILE_SECONDS);
+                let format = query.get("format").map(|f| ProfileFormat::from_query(f));
+                Box::pin(async move {
+                    match format.unwrap_or(Some(ProfileFormat::Pprof)) {
+                        Some(format) => cpu_response(Duration::from_secs(seconds), format).await,
+                        None => {
+                            PyResponse::text("format must be pprof or flamegraph").with_status(400)
+                        }
+                    }
+                })
+            }),
+            false,
+        )?;
+        if self.py_spy {
+            server.add_route(
+                Method::Get,
+                &format!("{}/py-spy", self.prefix),
+                self.guarded(|_req| Box::pin(py_spy_response())),
+                false,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Wrap `inner` so requests without the admin token get 401
+    fn guarded<F>(&self, inner: F) -> Handler
+    where
+        F: Fn(
+                &PyRequest,
+            )
+                -> std::pin::Pin<Box<dyn std::future::Future<Output = PyResponse> + Send>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let token = self.token.clone();
+        Arc::new(move |req, _matched| {
+            if !is_authorized(req, &token) {
+                return Box::pin(std::future::ready(
+                    PyResponse::text(r#"{"error": "Unauthorized"}"#)
+                        .with_status(401)
+                        .with_header("Content-Type", "application/json"),
+                ));
+            }
+            inner(req)
+        })
+    }
+}
+
+/// Whether `req` carries `Authorization: Bearer <token>` (constant time)
+fn is_authorized(req: &PyRequest, token: &str) -> bool {
+    req.header("authorization")
+        .and_then(|h| h.strip_prefix("Bearer "))
+        .is_some_and(|given| token_matches(given, token))
+}
+
+/// Whether `given` equals the non-empty admin `token` (constant time)
+pub(crate) fn token_matches(given: &str, token: &str) -> bool {
+    !token.is_empty()
+        && given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn runtime_response() -> PyResponse {
+    match serde_json::to_string(&RuntimeSnapshot::capture()) {
+        Ok(body) => PyResponse::json(body),
+        Err(e) => PyResponse::text(e.to_string()).with_status(500),
+    }
+}
+
+async fn cpu_response(duration: Duration, format: ProfileFormat) -> PyResponse {
+    let profile = tokio::task::spawn_blocking(move || cpu_profile(duration, format)).await;
+    match profile {
+        Err(e) => PyResponse::text(e.to_string()).with_status(500),
+        Ok(Ok(profile)) => PyResponse::binary(Bytes::from(profile), format.content_type()),
+        Ok(Err(e)) => PyResponse::text(e.to_string()).with_status(501),
+    }
+}
+
+async fn py_spy_response() -> PyResponse {
+    let output = tokio::process::Command::new("py-spy")
+        .args(["dump", "--pid", &std::process::id().to_string()])
+        .output()
+        .await;
+    match output {
+        Ok(output) if output.status.success() => {
+            PyResponse::text(String::from_utf8_lossy(&output.stdout).into_owned())
+        }
+        Ok(output) => {
+            PyResponse::text(String::from_utf8_lossy(&output.stderr).into_owned()).with_status(500)
+        }
+        Err(e) => PyResponse::text(format!("py-spy is not available: {e}")).with_status(501),
+    }
+}
+
+impl ServerExtension for ProfilingEndpoints {
+    fn name(&self) -> &'static str {
+        "profiling"
+    }
+
+    fn configure(&self, server: &mut Server) -> Result<()> {
+        self.register(server)
+    }
+}
+
+impl Server {
+    /// Serve admin-only profiling endpoints under `prefix`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a route conflicts with an existing one.
+    pub fn enable_profiling(&mut self, endpoints: &ProfilingEndpoints) -> Result<()> {
+        self.register_extension(Arc::new(endpoints.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn bearer(token: &str) -> HashMap<String, String> {
+        HashMap::from([("authorization".to_string(), format!("Bearer {token}"))])
+    }
+
+    #[tokio::test]
+    async fn test_runtime_endpoint_requires_admin_token() {
+        let mut server = Server::new("");
+        server
+            .enable_profiling(&ProfilingEndpoints::new(DEFAULT_PREFIX, "s3cret"))
+            .unwrap();
+        let path = format!("{DEFAULT_PREFIX}/runtime");
+
+        let resp = server
+            .test_request(Method::Get, path.clone(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 401);
+
+        let resp = server
+            .test_request(Method::Get, path.clone(), bearer("wrong!"), None)
+            .await;
+        assert_eq!(resp.status, 401);
+
+        let resp = server
+            .test_request(Method::Get, path, bearer("s3cret"), None)
+            .await;
+        assert_eq!(resp.status, 200);
+        assert!(resp.body.contains("alive_tasks"));
+    }
+
+    #[tokio::test]
+    async fn test_cpu_endpoint_rejects_unknown_format() {
+        let mut server = Server::new("");
+        server
+            .enable_profiling(&ProfilingEndpoints::new("/admin/", "t"))
+            .unwrap();
+
+        let resp = server
+            .test_request(
+                Method::Get,
+                "/admin/cpu?format=gif".to_string(),
+                bearer("t"),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 400);
+    }
+
+    #[cfg(feature = "profiling")]
+    #[test]
+    fn test_cpu_profile_encodes_pprof() {
+        let profile = cpu_profile(Duration::from_millis(100), ProfileFormat::Pprof).unwrap();
+        assert!(!profile.is_empty());
+    }
+
+    #[test]
+    fn test_empty_token_never_authorizes() {
+        let req = PyRequest::new(Method::Get, "/".to_string(), bearer(""), None);
+        assert!(!is_authorized(&req, ""));
+    }
+}
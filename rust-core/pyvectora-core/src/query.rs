@@ -0,0 +1,552 @@
+//! # Query Helpers
+//!
+//! Parameterized SQL for common table operations that respect soft deletes:
+//! rows marked deleted are hidden by `select_active`, and `soft_delete`
+//! marks rows instead of removing them. Table and column names are
+//! validated and quoted; filter values are always bound, never inlined.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only generates SQL; `DatabasePool` executes it
+//! - **O**: New operations reuse the same placeholder builder
+//! - **D**: Callers pass a `Dialect`, not a driver type
+
+use crate::database::DbValue;
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+
+/// Placeholder style of a database backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dialect {
+    /// `?` placeholders
+    Sqlite,
+    /// `$1`, `$2`, ... placeholders
+    Postgres,
+}
+
+/// SQL text with the values for its placeholders, in order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SqlQuery {
+    /// SQL text
+    pub sql: String,
+    /// Bound values
+    pub params: Vec<DbValue>,
+}
+
+/// Ordering and window of a page of rows
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Page {
+    /// Rows per page
+    pub limit: u64,
+    /// Rows skipped before the page
+    pub offset: u64,
+    /// Column to sort by (database order when `None`)
+    pub order_by: Option<String>,
+    /// Sort descending
+    pub descending: bool,
+}
+
+/// How a table marks deleted rows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SoftDelete {
+    /// A timestamp column, `NULL` while the row is active
+    Timestamp(String),
+    /// A boolean column, true once the row is deleted
+    Flag(String),
+}
+
+impl SoftDelete {
+    /// Name of the marking column
+    #[must_use]
+    pub fn column(&self) -> &str {
+        match self {
+            Self::Timestamp(column) | Self::Flag(column) => column,
+        }
+    }
+
+    fn condition(&self, query: &mut Builder, deleted: bool) -> Result<String> {
+        let column = quote_identifier(self.column())?;
+        Ok(match self {
+            Self::Timestamp(_) if deleted => format!("{column} IS NOT NULL"),
+            Self::Timestamp(_) => format!("{column} IS NULL"),
+            Self::Flag(_) => format!("{column} = {}", query.bind(DbValue::Bool(deleted))),
+        })
+    }
+
+    fn assignment(&self, query: &mut Builder, deleted: bool) -> Result<String> {
+        let column = quote_identifier(self.column())?;
+        Ok(match self {
+            Self::Timestamp(_) if deleted => format!("{column} = CURRENT_TIMESTAMP"),
+            Self::Timestamp(_) => format!("{column} = NULL"),
+            Self::Flag(_) => format!("{column} = {}", query.bind(DbValue::Bool(deleted))),
+        })
+    }
+}
+
+/// Soft-delete columns by table, with a default for unlisted tables
+#[derive(Debug, Clone, Default)]
+pub struct SoftDeleteConfig {
+    default: Option<SoftDelete>,
+    tables: HashMap<String, Option<SoftDelete>>,
+}
+
+impl SoftDeleteConfig {
+    /// No table uses soft deletes
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Soft-delete column of tables without their own setting
+    pub fn set_default(&mut self, soft_delete: Option<SoftDelete>) {
+        self.default = soft_delete;
+    }
+
+    /// Soft-delete column of `table` (`None` turns soft deletes off for it)
+    pub fn set_table(&mut self, table: impl Into<String>, soft_delete: Option<SoftDelete>) {
+        self.tables.insert(table.into(), soft_delete);
+    }
+
+    /// Soft-delete column used for `table`
+    #[must_use]
+    pub fn for_table(&self, table: &str) -> Option<&SoftDelete> {
+        match self.tables.get(table) {
+            Some(soft_delete) => soft_delete.as_ref(),
+            None => self.default.as_ref(),
+        }
+    }
+}
+
+/// `SELECT *` of the rows of `table` matching `filters` that are not soft-deleted
+///
+/// Filters are equality tests joined with `AND`; a `Null` value tests
+/// `IS NULL`. Without `soft_delete` every matching row is selected.
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid table or column name.
+pub fn select_active(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    soft_delete: Option<&SoftDelete>,
+) -> Result<SqlQuery> {
+    let mut query = Builder::new(dialect);
+    let sql = format!(
+        "SELECT * FROM {}{}",
+        quote_identifier(table)?,
+        query.where_clause(filters, soft_delete)?
+    );
+    Ok(query.finish(sql))
+}
+
+/// [`select_active`] limited to one `page`
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid table or column name.
+pub fn select_page(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    soft_delete: Option<&SoftDelete>,
+    page: &Page,
+) -> Result<SqlQuery> {
+    let mut query = Builder::new(dialect);
+    let mut sql = format!(
+        "SELECT * FROM {}{}",
+        quote_identifier(table)?,
+        query.where_clause(filters, soft_delete)?
+    );
+    if let Some(column) = &page.order_by {
+        let direction = if page.descending { "DESC" } else { "ASC" };
+        sql.push_str(&format!(
+            " ORDER BY {} {direction}",
+            quote_identifier(column)?
+        ));
+    }
+    let limit = query.bind(DbValue::Int(i64::try_from(page.limit).unwrap_or(i64::MAX)));
+    let offset = query.bind(DbValue::Int(i64::try_from(page.offset).unwrap_or(i64::MAX)));
+    sql.push_str(&format!(" LIMIT {limit} OFFSET {offset}"));
+    Ok(query.finish(sql))
+}
+
+/// `SELECT COUNT(*) AS "count"` of the rows [`select_active`] selects
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid table or column name.
+pub fn count_active(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    soft_delete: Option<&SoftDelete>,
+) -> Result<SqlQuery> {
+    let mut query = Builder::new(dialect);
+    let sql = format!(
+        "SELECT COUNT(*) AS \"count\" FROM {}{}",
+        quote_identifier(table)?,
+        query.where_clause(filters, soft_delete)?
+    );
+    Ok(query.finish(sql))
+}
+
+/// `INSERT` of one row of `values`, returning the stored row
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid name or empty `values`.
+pub fn insert(dialect: Dialect, table: &str, values: &[(String, DbValue)]) -> Result<SqlQuery> {
+    if values.is_empty() {
+        return Err(Error::Database {
+            message: format!("no values to insert into {table}"),
+        });
+    }
+    let mut query = Builder::new(dialect);
+    let columns = values
+        .iter()
+        .map(|(column, _)| quote_identifier(column))
+        .collect::<Result<Vec<_>>>()?;
+    let placeholders: Vec<String> = values
+        .iter()
+        .map(|(_, value)| query.bind(value.clone()))
+        .collect();
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) RETURNING *",
+        quote_identifier(table)?,
+        columns.join(", "),
+        placeholders.join(", ")
+    );
+    Ok(query.finish(sql))
+}
+
+/// `UPDATE` setting `values` on the active rows matching `filters`, returning them
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid name, empty `values` or empty
+/// `filters`.
+pub fn update(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    values: &[(String, DbValue)],
+    soft_delete: Option<&SoftDelete>,
+) -> Result<SqlQuery> {
+    require_filters(table, filters)?;
+    if values.is_empty() {
+        return Err(Error::Database {
+            message: format!("no values to update in {table}"),
+        });
+    }
+    let mut query = Builder::new(dialect);
+    let assignments = values
+        .iter()
+        .map(|(column, value)| {
+            Ok(format!(
+                "{} = {}",
+                quote_identifier(column)?,
+                query.bind(value.clone())
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let sql = format!(
+        "UPDATE {} SET {}{} RETURNING *",
+        quote_identifier(table)?,
+        assignments.join(", "),
+        query.where_clause(filters, soft_delete)?
+    );
+    Ok(query.finish(sql))
+}
+
+/// `DELETE` of the rows matching `filters`
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid name or empty `filters`.
+pub fn delete(dialect: Dialect, table: &str, filters: &[(String, DbValue)]) -> Result<SqlQuery> {
+    require_filters(table, filters)?;
+    let mut query = Builder::new(dialect);
+    let sql = format!(
+        "DELETE FROM {}{}",
+        quote_identifier(table)?,
+        query.where_clause(filters, None)?
+    );
+    Ok(query.finish(sql))
+}
+
+/// `UPDATE` marking the active rows of `table` matching `filters` as deleted
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid name or empty `filters`, so a
+/// missing filter cannot mark the whole table.
+pub fn soft_delete(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    soft_delete: &SoftDelete,
+) -> Result<SqlQuery> {
+    mark(dialect, table, filters, soft_delete, true)
+}
+
+/// `UPDATE` restoring the soft-deleted rows of `table` matching `filters`
+///
+/// # Errors
+///
+/// Returns `Error::Database` for an invalid name or empty `filters`.
+pub fn restore(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    soft_delete: &SoftDelete,
+) -> Result<SqlQuery> {
+    mark(dialect, table, filters, soft_delete, false)
+}
+
+fn mark(
+    dialect: Dialect,
+    table: &str,
+    filters: &[(String, DbValue)],
+    soft_delete: &SoftDelete,
+    deleted: bool,
+) -> Result<SqlQuery> {
+    require_filters(table, filters)?;
+    let mut query = Builder::new(dialect);
+    let table = quote_identifier(table)?;
+    let assignment = soft_delete.assignment(&mut query, deleted)?;
+    let mut conditions = query.filters(filters)?;
+    conditions.push(soft_delete.condition(&mut query, !deleted)?);
+    let sql = format!(
+        "UPDATE {table} SET {assignment} WHERE {}",
+        conditions.join(" AND ")
+    );
+    Ok(query.finish(sql))
+}
+
+/// Reject empty `filters`, so a missing filter cannot change the whole table
+fn require_filters(table: &str, filters: &[(String, DbValue)]) -> Result<()> {
+    if filters.is_empty() {
+        return Err(Error::Database {
+            message: format!("refusing to change every row of {table}: no filters given"),
+        });
+    }
+    Ok(())
+}
+
+/// Collects bound values while SQL text is generated left to right
+struct Builder {
+    dialect: Dialect,
+    params: Vec<DbValue>,
+}
+
+impl Builder {
+    const fn new(dialect: Dialect) -> Self {
+        Self {
+            dialect,
+            params: Vec::new(),
+        }
+    }
+
+    /// Bind `value`, returning its placeholder
+    fn bind(&mut self, value: DbValue) -> String {
+        self.params.push(value);
+        match self.dialect {
+            Dialect::Sqlite => "?".to_string(),
+            Dialect::Postgres => format!("${}", self.params.len()),
+        }
+    }
+
+    fn filters(&mut self, filters: &[(String, DbValue)]) -> Result<Vec<String>> {
+        filters
+            .iter()
+            .map(|(column, value)| {
+                let column = quote_identifier(column)?;
+                Ok(match value {
+                    DbValue::Null => format!("{column} IS NULL"),
+                    value => format!("{column} = {}", self.bind(value.clone())),
+                })
+            })
+            .collect()
+    }
+
+    /// `" WHERE ..."` for `filters` and active rows, or `""` without conditions
+    fn where_clause(
+        &mut self,
+        filters: &[(String, DbValue)],
+        soft_delete: Option<&SoftDelete>,
+    ) -> Result<String> {
+        let mut conditions = self.filters(filters)?;
+        if let Some(soft_delete) = soft_delete {
+            conditions.push(soft_delete.condition(self, false)?);
+        }
+        if conditions.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(format!(" WHERE {}", conditions.join(" AND ")))
+    }
+
+    fn finish(self, sql: String) -> SqlQuery {
+        SqlQuery {
+            sql,
+            params: self.params,
+        }
+    }
+}
+
+/// Double-quote a (possibly `schema.`-qualified) identifier
+///
+/// Only letters, digits and `_` are accepted, not starting with a digit.
+fn quote_identifier(name: &str) -> Result<String> {
+    let valid = |part: &str| {
+        part.chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+    };
+    if !name.split('.').all(valid) {
+        return Err(Error::Database {
+            message: format!("invalid identifier: {name:?}"),
+        });
+    }
+    Ok(name
+        .split('.')
+        .map(|part| format!("\"{part}\""))
+        .collect::<Vec<_>>()
+        .join("."))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filters(pairs: &[(&str, DbValue)]) -> Vec<(String, DbValue)> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_select_active_binds_filters() {
+        let soft_delete = SoftDelete::Timestamp("deleted_at".to_string());
+        let query = select_active(
+            Dialect::Postgres,
+            "users",
+            &filters(&[
+                ("org_id", DbValue::Int(7)),
+                ("email", DbValue::String("a@b.c".to_string())),
+                ("manager_id", DbValue::Null),
+            ]),
+            Some(&soft_delete),
+        )
+        .unwrap();
+        assert_eq!(
+            query.sql,
+            "SELECT * FROM \"users\" WHERE \"org_id\" = $1 AND \"email\" = $2 \
+             AND \"manager_id\" IS NULL AND \"deleted_at\" IS NULL"
+        );
+        assert_eq!(
+            query.params,
+            vec![DbValue::Int(7), DbValue::String("a@b.c".to_string())]
+        );
+
+        let query = select_active(Dialect::Sqlite, "users", &[], None).unwrap();
+        assert_eq!(query.sql, "SELECT * FROM \"users\"");
+    }
+
+    #[test]
+    fn test_soft_delete_and_restore_with_flag_column() {
+        let soft_delete = SoftDelete::Flag("is_deleted".to_string());
+        let by_id = filters(&[("id", DbValue::Int(3))]);
+
+        let query = super::soft_delete(Dialect::Postgres, "posts", &by_id, &soft_delete).unwrap();
+        assert_eq!(
+            query.sql,
+            "UPDATE \"posts\" SET \"is_deleted\" = $1 WHERE \"id\" = $2 AND \"is_deleted\" = $3"
+        );
+        assert_eq!(
+            query.params,
+            vec![DbValue::Bool(true), DbValue::Int(3), DbValue::Bool(false)]
+        );
+
+        let query = restore(Dialect::Sqlite, "posts", &by_id, &soft_delete).unwrap();
+        assert_eq!(
+            query.sql,
+            "UPDATE \"posts\" SET \"is_deleted\" = ? WHERE \"id\" = ? AND \"is_deleted\" = ?"
+        );
+        assert_eq!(query.params[0], DbValue::Bool(false));
+
+        assert!(super::soft_delete(Dialect::Sqlite, "posts", &[], &soft_delete).is_err());
+    }
+
+    #[test]
+    fn test_page_insert_and_update_queries() {
+        let page = Page {
+            limit: 20,
+            offset: 40,
+            order_by: Some("created_at".to_string()),
+            descending: true,
+        };
+        let query = select_page(Dialect::Postgres, "users", &[], None, &page).unwrap();
+        assert_eq!(
+            query.sql,
+            "SELECT * FROM \"users\" ORDER BY \"created_at\" DESC LIMIT $1 OFFSET $2"
+        );
+        assert_eq!(query.params, vec![DbValue::Int(20), DbValue::Int(40)]);
+
+        let values = filters(&[
+            ("name", DbValue::String("Ada".to_string())),
+            ("manager_id", DbValue::Null),
+        ]);
+        let query = insert(Dialect::Sqlite, "users", &values).unwrap();
+        assert_eq!(
+            query.sql,
+            "INSERT INTO \"users\" (\"name\", \"manager_id\") VALUES (?, ?) RETURNING *"
+        );
+        assert_eq!(query.params[1], DbValue::Null);
+
+        let soft_delete = SoftDelete::Timestamp("deleted_at".to_string());
+        let by_id = filters(&[("id", DbValue::Int(5))]);
+        let query = update(
+            Dialect::Postgres,
+            "users",
+            &by_id,
+            &values,
+            Some(&soft_delete),
+        )
+        .unwrap();
+        assert_eq!(
+            query.sql,
+            "UPDATE \"users\" SET \"name\" = $1, \"manager_id\" = $2 \
+             WHERE \"id\" = $3 AND \"deleted_at\" IS NULL RETURNING *"
+        );
+        assert!(update(Dialect::Sqlite, "users", &[], &values, None).is_err());
+        assert!(delete(Dialect::Sqlite, "users", &[]).is_err());
+    }
+
+    #[test]
+    fn test_identifiers_are_validated() {
+        assert_eq!(
+            quote_identifier("public.users").unwrap(),
+            "\"public\".\"users\""
+        );
+        assert!(quote_identifier("users; DROP TABLE users").is_err());
+        assert!(quote_identifier("1users").is_err());
+        assert!(quote_identifier("users.").is_err());
+        let injected = filters(&[("id = 1 OR 1", DbValue::Int(1))]);
+        assert!(select_active(Dialect::Sqlite, "users", &injected, None).is_err());
+    }
+
+    #[test]
+    fn test_config_falls_back_to_default() {
+        let mut config = SoftDeleteConfig::new();
+        config.set_default(Some(SoftDelete::Timestamp("deleted_at".to_string())));
+        config.set_table("audit_log", None);
+        config.set_table("posts", Some(SoftDelete::Flag("is_deleted".to_string())));
+
+        assert_eq!(config.for_table("users").unwrap().column(), "deleted_at");
+        assert_eq!(config.for_table("posts").unwrap().column(), "is_deleted");
+        assert!(config.for_table("audit_log").is_none());
+    }
+}
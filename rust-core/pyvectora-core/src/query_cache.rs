@@ -0,0 +1,278 @@
+//! # Query Cache
+//!
+//! Short-lived cache of `SELECT` results, keyed by SQL text and bound
+//! parameters, for the "cache this query for 30 seconds" pattern. Each
+//! result carries an `ETag` derived from its rows, so handlers can answer
+//! conditional requests with `304 Not Modified` without re-rendering.
+//! Entries expire after their TTL or are dropped explicitly, one query at
+//! a time or by tag (e.g. every query reading a table after a write).
+//! Hits, misses, evictions and invalidations are reported as `cache`
+//! events, from which metrics count them.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only stores and expires query results
+//! - **D**: Fetches through `DatabasePool`, unaware of the backend
+
+use crate::database::{DatabasePool, DbValue};
+use crate::error::Result;
+use crate::events::{self, CacheOutcome, Event, EventKind};
+use crate::query::SqlQuery;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Rows of a query result
+pub type Rows = Vec<HashMap<String, DbValue>>;
+
+/// A query result served from (or just stored in) the cache
+#[derive(Debug, Clone)]
+pub struct CachedRows {
+    /// The rows
+    pub rows: Arc<Rows>,
+    /// Strong `ETag` of the rows (`"<hex>"`), equal for equal results
+    pub etag: String,
+    /// Whether the rows came from the cache rather than the database
+    pub hit: bool,
+}
+
+#[derive(Debug)]
+struct Entry {
+    rows: Arc<Rows>,
+    etag: String,
+    expires: Instant,
+    tags: Vec<String>,
+}
+
+/// Query results cached until their TTL passes or they are invalidated
+///
+/// Clones share the same entries.
+#[derive(Debug, Clone, Default)]
+pub struct QueryCache {
+    entries: Arc<Mutex<HashMap<String, Entry>>>,
+}
+
+impl QueryCache {
+    /// Empty cache
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rows of `query`, from the cache while fresh, else fetched from `pool`
+    /// and kept for `ttl` under `tags`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Database` if the query fails; nothing is cached.
+    pub async fn fetch(
+        &self,
+        pool: &DatabasePool,
+        query: &SqlQuery,
+        ttl: Duration,
+        tags: &[String],
+    ) -> Result<CachedRows> {
+        if let Some(cached) = self.get(query, Instant::now()) {
+            return Ok(cached);
+        }
+        let rows = pool.fetch_all_query(query).await?;
+        Ok(self.insert(query, rows, ttl, tags, Instant::now()))
+    }
+
+    /// Fresh cached rows of `query` at `now`
+    #[must_use]
+    pub fn get(&self, query: &SqlQuery, now: Instant) -> Option<CachedRows> {
+        let cached = self
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&cache_key(query))
+            .filter(|entry| entry.expires > now)
+            .map(|entry| CachedRows {
+                rows: Arc::clone(&entry.rows),
+                etag: entry.etag.clone(),
+                hit: true,
+            });
+        let outcome = if cached.is_some() {
+            CacheOutcome::Hit
+        } else {
+            CacheOutcome::Miss
+        };
+        report(outcome, 1);
+        cached
+    }
+
+    /// Store `rows` as the result of `query` until `now + ttl`
+    ///
+    /// Expired entries are dropped on the way.
+    pub fn insert(
+        &self,
+        query: &SqlQuery,
+        rows: Rows,
+        ttl: Duration,
+        tags: &[String],
+        now: Instant,
+    ) -> CachedRows {
+        let rows = Arc::new(rows);
+        let etag = etag_of(&rows);
+        let entry = Entry {
+            rows: Arc::clone(&rows),
+            etag: etag.clone(),
+            expires: now + ttl,
+            tags: tags.to_vec(),
+        };
+        let evicted = {
+            let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            let before = entries.len();
+            entries.retain(|_, entry| entry.expires > now);
+            let evicted = before - entries.len();
+            entries.insert(cache_key(query), entry);
+            evicted
+        };
+        report(CacheOutcome::Eviction, evicted);
+        CachedRows {
+            rows,
+            etag,
+            hit: false,
+        }
+    }
+
+    /// Drop the cached result of `query`; returns whether there was one
+    pub fn invalidate(&self, query: &SqlQuery) -> bool {
+        let removed = self
+            .entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&cache_key(query))
+            .is_some();
+        report(CacheOutcome::Invalidation, usize::from(removed));
+        removed
+    }
+
+    /// Drop every result cached under `tag`; returns how many there were
+    pub fn invalidate_tag(&self, tag: &str) -> usize {
+        let removed = {
+            let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            let before = entries.len();
+            entries.retain(|_, entry| !entry.tags.iter().any(|t| t == tag));
+            before - entries.len()
+        };
+        report(CacheOutcome::Invalidation, removed);
+        removed
+    }
+
+    /// Drop every cached result
+    pub fn clear(&self) {
+        let removed = {
+            let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+            let removed = entries.len();
+            entries.clear();
+            removed
+        };
+        report(CacheOutcome::Invalidation, removed);
+    }
+}
+
+/// Report `count` entries with `outcome` as a `cache` event
+fn report(outcome: CacheOutcome, count: usize) {
+    if count > 0 && events::is_active(EventKind::Cache) {
+        events::emit(&Event::Cache {
+            cache: "query".to_string(),
+            outcome,
+            count: count as u64,
+        });
+    }
+}
+
+/// Key of `query`: its SQL and its parameters, with their types
+fn cache_key(query: &SqlQuery) -> String {
+    let params = serde_json::to_string(&query.params).unwrap_or_default();
+    format!("{}\u{0}{params}", query.sql)
+}
+
+/// SHA-256 of the rows (columns sorted), as a quoted 32-digit hex `ETag`
+fn etag_of(rows: &Rows) -> String {
+    let sorted: Vec<BTreeMap<&String, &DbValue>> =
+        rows.iter().map(|row| row.iter().collect()).collect();
+    let body = serde_json::to_vec(&sorted).unwrap_or_default();
+    let digest = ring::digest::digest(&ring::digest::SHA256, &body);
+    let mut etag = String::from("\"");
+    for byte in &digest.as_ref()[..16] {
+        let _ = write!(etag, "{byte:02x}");
+    }
+    etag.push('"');
+    etag
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn query(sql: &str, params: Vec<DbValue>) -> SqlQuery {
+        SqlQuery {
+            sql: sql.to_string(),
+            params,
+        }
+    }
+
+    fn rows(name: &str) -> Rows {
+        vec![HashMap::from([
+            ("id".to_string(), DbValue::Int(1)),
+            ("name".to_string(), DbValue::String(name.to_string())),
+        ])]
+    }
+
+    #[test]
+    fn test_results_are_keyed_by_params_and_expire() {
+        let cache = QueryCache::new();
+        let now = Instant::now();
+        let ttl = Duration::from_secs(30);
+        let by_id = |id| query("SELECT * FROM users WHERE id = ?", vec![DbValue::Int(id)]);
+
+        let stored = cache.insert(&by_id(1), rows("Alice"), ttl, &[], now);
+        assert!(!stored.hit);
+        assert_eq!(stored.etag.len(), 34);
+        let cached = cache.get(&by_id(1), now).unwrap();
+        assert!(cached.hit);
+        assert_eq!(cached.etag, stored.etag);
+        assert!(cache.get(&by_id(2), now).is_none());
+        // Same text, different parameter type
+        let by_text = query(
+            "SELECT * FROM users WHERE id = ?",
+            vec![DbValue::String("1".into())],
+        );
+        assert!(cache.get(&by_text, now).is_none());
+        assert!(cache.get(&by_id(1), now + ttl).is_none());
+
+        let other = cache.insert(&by_id(2), rows("Bob"), ttl, &[], now);
+        assert_ne!(other.etag, stored.etag);
+        assert_eq!(etag_of(&rows("Bob")), other.etag);
+    }
+
+    #[test]
+    fn test_invalidation() {
+        let cache = QueryCache::new();
+        let now = Instant::now();
+        let ttl = Duration::from_secs(30);
+        let users = ["users".to_string()];
+        let all = query("SELECT * FROM users", Vec::new());
+        let active = query(
+            "SELECT * FROM users WHERE active = ?",
+            vec![DbValue::Bool(true)],
+        );
+        let plans = query("SELECT * FROM plans", Vec::new());
+        cache.insert(&all, rows("Alice"), ttl, &users, now);
+        cache.insert(&active, rows("Alice"), ttl, &users, now);
+        cache.insert(&plans, rows("Pro"), ttl, &[], now);
+
+        assert!(cache.invalidate(&plans));
+        assert!(!cache.invalidate(&plans));
+        assert_eq!(cache.invalidate_tag("users"), 2);
+        assert!(cache.get(&all, now).is_none());
+
+        cache.insert(&plans, rows("Pro"), ttl, &[], now);
+        cache.clear();
+        assert!(cache.get(&plans, now).is_none());
+    }
+}
@@ -0,0 +1,146 @@
+//! # Read-Only Mode
+//!
+//! A switch for migrations and incident response: while on, requests with
+//! unsafe methods (`POST`, `PUT`, `PATCH`, `DELETE`) get 503 unless their
+//! route is allowlisted, and database pools can be made to refuse writes
+//! too. The switch is shared, so it can be flipped while serving.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only refuses writes while the mode is on
+//! - **O**: Plugs into the server as ordinary `Middleware`
+
+use crate::database;
+use crate::middleware::{Middleware, MiddlewareResult};
+use crate::problem::ProblemDetails;
+use crate::router::Method;
+use crate::server::{PyRequest, PyResponse};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared read-only switch, usable as middleware
+///
+/// Clones share the switch.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOnlyMode {
+    enabled: Arc<AtomicBool>,
+    /// Also make database pools refuse writes
+    databases: bool,
+    /// Route patterns still accepting unsafe methods
+    allow: Arc<HashSet<String>>,
+}
+
+impl ReadOnlyMode {
+    /// Switch that starts off, refusing nothing
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep accepting writes on the routes with these patterns
+    /// (`/admin/read-only`, `/users/{id}`)
+    #[must_use]
+    pub fn allow<I, S>(mut self, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allow = Arc::new(patterns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Also switch database pools to read-only (see `database::set_read_only`)
+    #[must_use]
+    pub const fn databases(mut self, databases: bool) -> Self {
+        self.databases = databases;
+        self
+    }
+
+    /// Turn the mode on or off
+    pub fn set(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        if self.databases {
+            database::set_read_only(enabled);
+        }
+    }
+
+    /// Whether the mode is on
+    #[must_use]
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// 503 for `req` if the mode refuses it
+    #[must_use]
+    pub fn check(&self, req: &PyRequest) -> Option<PyResponse> {
+        if !self.is_enabled() || is_safe(req.method) {
+            return None;
+        }
+        if req
+            .route
+            .as_deref()
+            .is_some_and(|route| self.allow.contains(route))
+        {
+            return None;
+        }
+        let detail = "The service is in read-only mode; try again later";
+        let body = serde_json::json!({ "error": "Service Unavailable", "detail": detail });
+        Some(
+            PyResponse::json(body.to_string())
+                .with_status(503)
+                .with_problem(ProblemDetails::new(503).with_detail(detail)),
+        )
+    }
+}
+
+impl Middleware for ReadOnlyMode {
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
+        self.check(req)
+            .map_or(MiddlewareResult::Continue, MiddlewareResult::Respond)
+    }
+
+    fn name(&self) -> &'static str {
+        "ReadOnlyMiddleware"
+    }
+}
+
+/// Methods that do not change state
+const fn is_safe(method: Method) -> bool {
+    matches!(method, Method::Get | Method::Head | Method::Options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn request(method: Method, route: &str) -> PyRequest {
+        let mut req = PyRequest::new(method, route.to_string(), HashMap::new(), None);
+        req.route = Some(Arc::from(route));
+        req
+    }
+
+    #[test]
+    fn test_refuses_unsafe_methods_while_on() {
+        let mode = ReadOnlyMode::new().allow(["/admin/read-only"]);
+        let shared = mode.clone();
+        assert!(mode.check(&request(Method::Post, "/orders")).is_none());
+
+        shared.set(true);
+        assert!(mode.is_enabled());
+        let res = mode.check(&request(Method::Post, "/orders")).unwrap();
+        assert_eq!(res.status, 503);
+        assert!(res.body.contains("read-only"));
+        assert!(mode.check(&request(Method::Delete, "/orders")).is_some());
+        assert!(mode.check(&request(Method::Get, "/orders")).is_none());
+        assert!(mode
+            .check(&request(Method::Post, "/admin/read-only"))
+            .is_none());
+
+        shared.set(false);
+        assert!(mode.check(&request(Method::Post, "/orders")).is_none());
+        // Pools are only switched when asked to
+        assert!(!database::is_read_only());
+    }
+}
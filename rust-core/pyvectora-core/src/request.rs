@@ -8,16 +8,77 @@
 //! - **O**: Extensible via new methods without breaking changes
 //! - **D**: Does not expose hyper types to Python layer
 
-use crate::error::Result;
+use crate::body_stream::BodyStream;
+use crate::error::{Error, RequestErrorKind, Result};
+#[cfg(feature = "python")]
+use crate::headers::PyHeaders;
+#[cfg(feature = "python")]
+use crate::multipart::UploadedFile;
+use crate::multipart::{self, FormData, MultipartLimits};
+use crate::request_state::RequestState;
 use crate::router::Method;
+use crate::session::Session;
 use crate::types::ParamValue;
+use crate::validation::{FieldError, ValidationErrors, ValidationResult};
 use http_body_util::BodyExt;
-use hyper::body::Bytes;
+use hyper::body::{Body, Bytes, Incoming};
 use hyper::Request;
+#[cfg(feature = "python")]
 use pyo3::prelude::*;
+#[cfg(feature = "python")]
 use pyo3::types::{PyBytes, PyDict, PyString};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Limits on the request target, checked before the query is parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetLimits {
+    /// Longest path plus query string in bytes (longer answers 414)
+    pub max_uri_length: usize,
+    /// Most `&`-separated query parameters (more answers 400)
+    pub max_query_params: usize,
+}
+
+impl Default for TargetLimits {
+    fn default() -> Self {
+        Self {
+            max_uri_length: 8 * 1024,
+            max_query_params: 1000,
+        }
+    }
+}
+
+impl TargetLimits {
+    /// Check a request's `path` and raw `query` against the limits
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UriTooLong` or `Error::BadRequest`
+    /// (`TooManyQueryParams`) for the first limit exceeded.
+    pub fn check(&self, path: &str, query: Option<&str>) -> Result<()> {
+        let length = path.len() + query.map_or(0, |q| q.len() + 1);
+        if length > self.max_uri_length {
+            return Err(Error::UriTooLong {
+                limit: self.max_uri_length,
+                actual: length,
+            });
+        }
+        if query.is_some_and(|q| q.split('&').nth(self.max_query_params).is_some()) {
+            return Err(Error::BadRequest {
+                kind: RequestErrorKind::TooManyQueryParams,
+                message: format!(
+                    "query string has more than {} parameters",
+                    self.max_query_params
+                ),
+            });
+        }
+        Ok(())
+    }
+}
 
 /// HTTP Request wrapper for Python interop
 ///
@@ -25,7 +86,7 @@ use std::collections::HashMap;
 /// - Headers are stored but accessed on-demand
 /// - Body is collected once and cached
 /// - Query string is parsed on first access
-#[pyclass(name = "Request", dict)]
+#[cfg_attr(feature = "python", pyclass(name = "Request", dict))]
 #[derive(Debug, Clone)]
 pub struct PyRequest {
     /// HTTP method
@@ -38,14 +99,39 @@ pub struct PyRequest {
     query_params: HashMap<String, String>,
     /// Typed path parameters (FAZ 2)
     pub typed_params: HashMap<String, ParamValue>,
-    /// Request headers
-    headers: hyper::HeaderMap,
+    /// Request headers, shared by clones and `Headers` views
+    headers: Arc<hyper::HeaderMap>,
     /// Request body (collected)
     body: Option<Bytes>,
+    /// Unread body of a streaming route
+    body_stream: Option<BodyStream>,
     /// Validated JWT claims
     pub claims: Option<Value>,
+    /// Cookie session, loaded by `SessionMiddleware`
+    pub session: Session,
+    /// Values set by middleware for the handler, shared by clones
+    pub state: RequestState,
+    /// Limits applied when parsing a `multipart/form-data` body
+    pub multipart_limits: Arc<MultipartLimits>,
+    /// Form body, parsed on first access and shared by clones
+    form_data: Arc<Mutex<Option<Arc<FormData>>>>,
+    /// Address of the connected peer (`None` outside a real connection)
+    pub remote_addr: Option<SocketAddr>,
+    /// `"https"` for requests received over TLS, else `"http"`
+    pub scheme: &'static str,
+    /// Authority of the request target (HTTP/2 `:authority`, absolute URIs)
+    authority: Option<String>,
+    /// Matched route (`"GET /users/{id}"`), set before the handler runs
+    pub route: Option<Arc<str>>,
+    /// Cancelled when the client disconnects before the response is sent
+    pub cancellation: CancellationToken,
+    /// When the request head was received
+    pub received_at: Instant,
+    /// When the handler times out (route or server timeout), set before it runs
+    pub deadline: Option<Instant>,
 }
 
+#[cfg(feature = "python")]
 #[pymethods]
 impl PyRequest {
     /// Get the HTTP method
@@ -60,6 +146,18 @@ impl PyRequest {
         self.path.clone()
     }
 
+    /// Rewrite the request path (in middleware: the request is routed again)
+    #[setter(path)]
+    fn py_set_path(&mut self, path: String) -> PyResult<()> {
+        if !path.starts_with('/') {
+            return Err(pyo3::exceptions::PyValueError::new_err(format!(
+                "path must start with '/': {path:?}"
+            )));
+        }
+        self.path = path;
+        Ok(())
+    }
+
     /// Get path parameters as a dict with typed values
     #[getter]
     fn params(&self, py: Python<'_>) -> PyResult<PyObject> {
@@ -85,16 +183,112 @@ impl PyRequest {
         Ok(dict.into())
     }
 
-    /// Get all request headers as a dict
+    /// Get query parameter `name` as an int, or `default` when absent
+    ///
+    /// Raises `RequestValidationError` (answered 422) when the value is
+    /// not an integer.
+    #[pyo3(signature = (name, default=None))]
+    fn query_int(&self, py: Python<'_>, name: &str, default: Option<i64>) -> PyResult<Option<i64>> {
+        self.query_i64(name)
+            .map(|value| value.or(default))
+            .map_err(|errors| errors.into_py_err(py))
+    }
+
+    /// Get query parameter `name` as a float, or `default` when absent
+    ///
+    /// Raises `RequestValidationError` (answered 422) when the value is
+    /// not a finite number.
+    #[pyo3(signature = (name, default=None))]
+    fn query_float(
+        &self,
+        py: Python<'_>,
+        name: &str,
+        default: Option<f64>,
+    ) -> PyResult<Option<f64>> {
+        self.query_f64(name)
+            .map(|value| value.or(default))
+            .map_err(|errors| errors.into_py_err(py))
+    }
+
+    /// Get query parameter `name` as a bool, or `default` when absent
+    ///
+    /// Accepts `true/false`, `1/0`, `yes/no` and `on/off` (any case); raises
+    /// `RequestValidationError` (answered 422) for other values.
+    #[pyo3(signature = (name, default=None))]
+    fn query_bool(
+        &self,
+        py: Python<'_>,
+        name: &str,
+        default: Option<bool>,
+    ) -> PyResult<Option<bool>> {
+        self.query_flag(name)
+            .map(|value| value.or(default))
+            .map_err(|errors| errors.into_py_err(py))
+    }
+
+    /// Get all headers as `(name, value)` tuples, keeping duplicates
+    ///
+    /// Names are lowercase. Repeated headers (e.g. several
+    /// `X-Forwarded-For` lines) keep their arrival order but are grouped
+    /// after the first header of that name.
     #[getter]
-    fn headers(&self, py: Python<'_>) -> PyResult<PyObject> {
-        let dict = PyDict::new(py);
-        for (k, v) in &self.headers {
-            if let Ok(val) = v.to_str() {
-                dict.set_item(k.as_str(), val)?;
-            }
-        }
-        Ok(dict.into())
+    fn raw_headers(&self) -> Vec<(String, String)> {
+        self.raw_header_list()
+    }
+
+    /// Get every value of a header (case-insensitive), in arrival order
+    fn get_all(&self, name: &str) -> Vec<String> {
+        self.header_values(name)
+    }
+
+    /// Set or override a header (e.g. in middleware, for the handler)
+    ///
+    /// Raises `ValueError` for an invalid name or value.
+    #[pyo3(name = "set_header")]
+    fn py_set_header(&mut self, name: &str, value: &str) -> PyResult<()> {
+        let name = hyper::header::HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let value = hyper::header::HeaderValue::from_str(value)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        Arc::make_mut(&mut self.headers).insert(name, value);
+        Ok(())
+    }
+
+    /// Remove every value of a header, returning whether there was one
+    #[pyo3(name = "remove_header")]
+    fn py_remove_header(&mut self, name: &str) -> bool {
+        self.remove_header(name)
+    }
+
+    /// Get the connected peer as an `(ip, port)` tuple (None in tests)
+    #[getter]
+    fn client(&self) -> Option<(String, u16)> {
+        self.remote_addr
+            .map(|addr| (addr.ip().to_string(), addr.port()))
+    }
+
+    /// Get the full URL of the request (scheme, host, path and query)
+    #[getter(url)]
+    fn py_url(&self) -> String {
+        self.url()
+    }
+
+    /// Get the request headers as a read-only, case-insensitive mapping
+    ///
+    /// The mapping shares the request's header map: no header is copied
+    /// until it is looked up, however many middleware read `headers`.
+    #[getter]
+    fn headers(&self) -> PyHeaders {
+        PyHeaders::new(Arc::clone(&self.headers))
+    }
+
+    /// Get the cookies sent in `Cookie` headers as a dict
+    ///
+    /// Quoted values are unquoted; for a repeated name the first value
+    /// (the most specific cookie) wins.
+    #[getter]
+    fn cookies(&self) -> HashMap<String, String> {
+        self.cookie_map()
     }
 
     /// Get the request body as bytes
@@ -118,6 +312,48 @@ impl PyRequest {
         }
     }
 
+    /// Values set by middleware for the handler (`request.state.user`)
+    #[getter(state)]
+    fn py_state(&self) -> RequestState {
+        self.state.clone()
+    }
+
+    /// Get the cookie session (dict-like, saved on response when changed)
+    #[getter]
+    fn session(&self) -> PyResult<Session> {
+        if self.session.is_active() {
+            Ok(self.session.clone())
+        } else {
+            Err(pyo3::exceptions::PyRuntimeError::new_err(
+                "sessions are not enabled; call app.enable_sessions(secret_key)",
+            ))
+        }
+    }
+
+    /// Get the text fields of a form body as a dict
+    ///
+    /// Parses `multipart/form-data` and `application/x-www-form-urlencoded`
+    /// bodies (other bodies give an empty dict); a repeated name keeps its
+    /// last value. Raises `MultipartError` for a malformed body and
+    /// `UploadTooLarge` for a part above the upload limit.
+    fn form(&self) -> PyResult<HashMap<String, String>> {
+        let form = self.form_data().map_err(multipart::to_py_error)?;
+        Ok(form.fields.iter().cloned().collect())
+    }
+
+    /// Get the uploaded files of a `multipart/form-data` body as a dict
+    ///
+    /// Values are `UploadedFile`s; uploads above the spool threshold are
+    /// kept in temp files removed once no `UploadedFile` refers to them.
+    fn files(&self) -> PyResult<HashMap<String, UploadedFile>> {
+        let form = self.form_data().map_err(multipart::to_py_error)?;
+        Ok(form
+            .files
+            .iter()
+            .map(|file| (file.name.clone(), file.clone()))
+            .collect())
+    }
+
     /// Parse request body as JSON
     fn json(&self, py: Python<'_>) -> PyResult<PyObject> {
         match &self.body {
@@ -129,6 +365,33 @@ impl PyRequest {
             None => Ok(PyDict::new(py).into()),
         }
     }
+
+    /// Iterate over the body in chunks: `async for chunk in request.stream()`
+    ///
+    /// On routes registered with `stream=True` the chunks are read from the
+    /// connection as they arrive and `body` is `None`; elsewhere the whole
+    /// body is yielded as one chunk. Each chunk is yielded once.
+    fn stream(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let adapter = STREAM_ADAPTER.get().ok_or_else(|| {
+            pyo3::exceptions::PyRuntimeError::new_err("request streaming needs the native runtime")
+        })?;
+        adapter(py, self.body_stream())
+    }
+}
+
+/// Builds the Python async iterator `Request.stream()` returns
+///
+/// The core has no asyncio bridge; the bindings install one at import.
+#[cfg(feature = "python")]
+pub type StreamAdapter = fn(Python<'_>, BodyStream) -> PyResult<PyObject>;
+
+#[cfg(feature = "python")]
+static STREAM_ADAPTER: std::sync::OnceLock<StreamAdapter> = std::sync::OnceLock::new();
+
+/// Install the adapter behind `Request.stream()` (the first one wins)
+#[cfg(feature = "python")]
+pub fn set_stream_adapter(adapter: StreamAdapter) {
+    let _ = STREAM_ADAPTER.set(adapter);
 }
 
 impl PyRequest {
@@ -163,22 +426,52 @@ impl PyRequest {
             query_string,
             query_params,
             typed_params: HashMap::new(),
-            headers,
+            headers: Arc::new(headers),
             body,
+            body_stream: None,
             claims: None,
+            session: Session::default(),
+            state: RequestState::default(),
+            multipart_limits: Arc::default(),
+            form_data: Arc::default(),
+            remote_addr: None,
+            scheme: "http",
+            authority: None,
+            route: None,
+            cancellation: CancellationToken::new(),
+            received_at: Instant::now(),
+            deadline: None,
         }
     }
 
     /// Create from hyper request
-    pub async fn from_hyper(req: Request<hyper::body::Incoming>) -> Result<Self> {
+    pub async fn from_hyper(req: Request<Incoming>) -> Result<Self> {
         Self::from_hyper_with_limit(req, usize::MAX).await
     }
 
     /// Create from hyper request with body size limit
     pub async fn from_hyper_with_limit(
-        req: Request<hyper::body::Incoming>,
+        req: Request<Incoming>,
         max_body_size: usize,
     ) -> Result<Self> {
+        let (mut request, body) = Self::from_hyper_head(req, TargetLimits::default())?;
+        request.read_body(body, max_body_size).await?;
+        Ok(request)
+    }
+
+    /// Create from the head of a hyper request, returning its body unread
+    ///
+    /// Finish with `read_body` or `stream_body`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UriTooLong` for a request target above
+    /// `limits.max_uri_length` and `Error::BadRequest` for a malformed query
+    /// string or one with more than `limits.max_query_params` parameters.
+    pub fn from_hyper_head(
+        req: Request<Incoming>,
+        limits: TargetLimits,
+    ) -> Result<(Self, Incoming)> {
         let method = match *req.method() {
             hyper::Method::GET => Method::Get,
             hyper::Method::POST => Method::Post,
@@ -193,47 +486,100 @@ impl PyRequest {
         let uri = req.uri();
         let path = uri.path().to_string();
         let query_string = uri.query().map(String::from);
+        let authority = uri.authority().map(ToString::to_string);
 
-        let query_params = parse_query_string(query_string.as_deref());
-
-        let headers = req.headers().clone();
-        if let Some(len) = headers.get(hyper::header::CONTENT_LENGTH) {
-            if let Ok(len_str) = len.to_str() {
-                if let Ok(content_len) = len_str.parse::<usize>() {
-                    if content_len > max_body_size {
-                        return Err(crate::error::Error::PayloadTooLarge {
-                            limit: max_body_size,
-                            actual: content_len,
-                        });
-                    }
-                }
-            }
-        }
-
-        let body = match BodyExt::collect(req.into_body()).await {
-            Ok(collected) => {
-                let bytes = collected.to_bytes();
-                if bytes.len() > max_body_size {
-                    return Err(crate::error::Error::PayloadTooLarge {
-                        limit: max_body_size,
-                        actual: bytes.len(),
-                    });
-                }
-                Some(bytes)
-            }
-            Err(_) => None,
-        };
+        limits.check(&path, query_string.as_deref())?;
+        let query_params = try_parse_query_string(query_string.as_deref())?;
 
-        Ok(Self {
+        let (parts, body) = req.into_parts();
+        let request = Self {
             method,
             path,
             query_string,
             query_params,
-            headers,
-            body,
+            headers: Arc::new(parts.headers),
+            body: None,
+            body_stream: None,
             typed_params: HashMap::new(),
             claims: None,
-        })
+            session: Session::default(),
+            state: RequestState::default(),
+            multipart_limits: Arc::default(),
+            form_data: Arc::default(),
+            remote_addr: None,
+            scheme: "http",
+            authority,
+            route: None,
+            cancellation: CancellationToken::new(),
+            received_at: Instant::now(),
+            deadline: None,
+        };
+        Ok((request, body))
+    }
+
+    /// Collect `body` as the request body
+    ///
+    /// The limit is enforced as frames arrive, so a chunked body without
+    /// `Content-Length` is rejected as soon as it crosses `max_body_size`
+    /// rather than once fully buffered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PayloadTooLarge` for a body (or `Content-Length`)
+    /// above `max_body_size` and `Error::BadRequest` if the body could not
+    /// be read.
+    pub async fn read_body<B>(&mut self, body: B, max_body_size: usize) -> Result<()>
+    where
+        B: Body<Data = Bytes> + Unpin,
+        B::Error: std::fmt::Display,
+    {
+        check_content_length(&self.headers, max_body_size)?;
+
+        let mut body = body;
+        let mut chunks = Vec::new();
+        let mut received = 0usize;
+        while let Some(frame) = body.frame().await {
+            let frame = frame.map_err(|e| Error::BadRequest {
+                kind: RequestErrorKind::BodyAborted,
+                message: e.to_string(),
+            })?;
+            let Ok(chunk) = frame.into_data() else {
+                continue;
+            };
+            received = received.saturating_add(chunk.len());
+            if received > max_body_size {
+                return Err(Error::PayloadTooLarge {
+                    limit: max_body_size,
+                    actual: received,
+                });
+            }
+            chunks.push(chunk);
+        }
+        self.body = Some(if chunks.len() == 1 {
+            chunks.swap_remove(0)
+        } else {
+            Bytes::from(chunks.concat())
+        });
+        Ok(())
+    }
+
+    /// Leave `body` unread for the handler to consume through `stream()`
+    ///
+    /// The buffered accessors (`body`, `json()`, forms) then see no body.
+    pub fn stream_body(&mut self, body: Incoming) {
+        self.body = None;
+        self.body_stream = Some(BodyStream::new(body));
+    }
+
+    /// The body as a chunk stream
+    ///
+    /// The unread body of a streaming route, else the collected body as
+    /// one chunk.
+    #[must_use]
+    pub fn body_stream(&self) -> BodyStream {
+        self.body_stream
+            .clone()
+            .unwrap_or_else(|| BodyStream::buffered(self.body.clone().unwrap_or_default()))
     }
 
     /// Get a header value by name (case-insensitive)
@@ -242,16 +588,62 @@ impl PyRequest {
         self.headers.get(name).and_then(|v| v.to_str().ok())
     }
 
+    /// Get every value of a header (case-insensitive), in arrival order
+    ///
+    /// Values that are not valid UTF-8 are decoded lossily rather than
+    /// dropped, so security checks see every entry.
+    #[must_use]
+    pub fn header_values(&self, name: &str) -> Vec<String> {
+        self.headers
+            .get_all(name)
+            .iter()
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned())
+            .collect()
+    }
+
+    /// Get all headers as `(name, value)` pairs, keeping duplicates
+    #[must_use]
+    pub fn raw_header_list(&self) -> Vec<(String, String)> {
+        self.headers
+            .iter()
+            .map(|(k, v)| {
+                (
+                    k.as_str().to_string(),
+                    String::from_utf8_lossy(v.as_bytes()).into_owned(),
+                )
+            })
+            .collect()
+    }
+
+    /// Append a header value, keeping existing values of the same name
+    pub fn append_header(&mut self, name: &str, value: &str) {
+        if let (Ok(n), Ok(v)) = (
+            hyper::header::HeaderName::from_bytes(name.as_bytes()),
+            hyper::header::HeaderValue::from_str(value),
+        ) {
+            Arc::make_mut(&mut self.headers).append(n, v);
+        }
+    }
+
     /// Set or override a header
     pub fn set_header(&mut self, name: &str, value: &str) {
         if let (Ok(n), Ok(v)) = (
             hyper::header::HeaderName::from_bytes(name.as_bytes()),
             hyper::header::HeaderValue::from_str(value),
         ) {
-            self.headers.insert(n, v);
+            Arc::make_mut(&mut self.headers).insert(n, v);
         }
     }
 
+    /// Remove every value of a header, returning whether there was one
+    pub fn remove_header(&mut self, name: &str) -> bool {
+        if !self.headers.contains_key(name) {
+            return false;
+        }
+        Arc::make_mut(&mut self.headers).remove(name);
+        true
+    }
+
     /// Get all headers as a HashMap
     #[must_use]
     pub fn headers_map(&self) -> HashMap<String, String> {
@@ -265,12 +657,106 @@ impl PyRequest {
             .collect()
     }
 
+    /// Get the cookies sent in `Cookie` headers as a `HashMap`
+    #[must_use]
+    pub fn cookie_map(&self) -> HashMap<String, String> {
+        parse_cookies(
+            self.headers
+                .get_all(hyper::header::COOKIE)
+                .iter()
+                .filter_map(|v| v.to_str().ok()),
+        )
+    }
+
     /// Get query parameters as a HashMap
     #[must_use]
     pub fn query_map(&self) -> &HashMap<String, String> {
         &self.query_params
     }
 
+    /// Subject (`sub` claim) of the validated JWT, if any
+    #[must_use]
+    pub fn subject(&self) -> Option<String> {
+        match self.claims.as_ref()?.get("sub")? {
+            Value::String(sub) => Some(sub.clone()),
+            Value::Number(sub) => Some(sub.to_string()),
+            _ => None,
+        }
+    }
+
+    /// Full URL of the request: scheme, host, path and query string
+    ///
+    /// The host comes from the `Host` header, else the request target's
+    /// authority, else `localhost`.
+    #[must_use]
+    pub fn url(&self) -> String {
+        let host = self
+            .header("host")
+            .or(self.authority.as_deref())
+            .unwrap_or("localhost");
+        let mut url = format!("{}://{}{}", self.scheme, host, self.path);
+        if let Some(query) = self.query_string.as_deref().filter(|q| !q.is_empty()) {
+            url.push('?');
+            url.push_str(query);
+        }
+        url
+    }
+
+    /// Get query parameter `name` parsed as an integer
+    ///
+    /// # Errors
+    ///
+    /// Returns an `INVALID_TYPE` error for `name` when the parameter is
+    /// present but not an integer.
+    pub fn query_i64(&self, name: &str) -> ValidationResult<Option<i64>> {
+        self.typed_query(name, "an integer", |v| v.parse().ok())
+    }
+
+    /// Get query parameter `name` parsed as a finite number
+    ///
+    /// # Errors
+    ///
+    /// Returns an `INVALID_TYPE` error for `name` when the parameter is
+    /// present but not a finite number.
+    pub fn query_f64(&self, name: &str) -> ValidationResult<Option<f64>> {
+        self.typed_query(name, "a number", |v| {
+            v.parse::<f64>().ok().filter(|f| f.is_finite())
+        })
+    }
+
+    /// Get query parameter `name` parsed as a boolean
+    ///
+    /// `true/1/yes/on` and `false/0/no/off` are accepted in any case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an `INVALID_TYPE` error for `name` for any other value.
+    pub fn query_flag(&self, name: &str) -> ValidationResult<Option<bool>> {
+        self.typed_query(name, "a boolean", |v| {
+            match v.to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Some(true),
+                "false" | "0" | "no" | "off" => Some(false),
+                _ => None,
+            }
+        })
+    }
+
+    fn typed_query<T>(
+        &self,
+        name: &str,
+        expected: &str,
+        parse: impl FnOnce(&str) -> Option<T>,
+    ) -> ValidationResult<Option<T>> {
+        let Some(raw) = self.query_params.get(name) else {
+            return Ok(None);
+        };
+        parse(raw.trim()).map(Some).ok_or_else(|| {
+            let mut errors = ValidationErrors::new();
+            errors.add(FieldError::invalid_type(name, expected));
+            errors
+        })
+    }
+
     /// Get raw query string
     #[must_use]
     pub fn query_string(&self) -> Option<&str> {
@@ -288,6 +774,137 @@ impl PyRequest {
     pub fn body_str(&self) -> Option<&str> {
         self.body_bytes().and_then(|b| std::str::from_utf8(b).ok())
     }
+
+    /// Parse the body as JSON, for routes registered with `parse_json`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::BadRequest` (`InvalidJson`) for an empty body or one
+    /// that is not valid JSON; the message names the line and column.
+    pub fn json_body(&self) -> Result<Value> {
+        let body = self.body_bytes().unwrap_or_default();
+        if body.is_empty() {
+            return Err(Error::BadRequest {
+                kind: RequestErrorKind::InvalidJson,
+                message: "request body is empty".to_string(),
+            });
+        }
+        serde_json::from_slice(body).map_err(|e| Error::BadRequest {
+            kind: RequestErrorKind::InvalidJson,
+            message: e.to_string(),
+        })
+    }
+
+    /// Parse the body as a form, once per request
+    ///
+    /// `multipart/form-data` bodies are parsed with `multipart_limits` and
+    /// `application/x-www-form-urlencoded` bodies like a query string; any
+    /// other body gives an empty form.
+    ///
+    /// # Errors
+    ///
+    /// Fails as `multipart::parse` does, or with `Error::BadRequest` for
+    /// urlencoded data that is not UTF-8.
+    pub fn form_data(&self) -> Result<Arc<FormData>> {
+        let mut cached = self
+            .form_data
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(form) = cached.as_ref() {
+            return Ok(Arc::clone(form));
+        }
+        let content_type = self.header("content-type").unwrap_or_default();
+        let media_type = content_type.split(';').next().unwrap_or_default().trim();
+        let body = self.body_bytes().unwrap_or_default();
+        let form = if media_type.eq_ignore_ascii_case("multipart/form-data") {
+            multipart::parse(content_type, body, &self.multipart_limits)?
+        } else if media_type.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+            let text = std::str::from_utf8(body).map_err(|_| Error::BadRequest {
+                kind: RequestErrorKind::InvalidEncoding,
+                message: "form body is not valid UTF-8".to_string(),
+            })?;
+            FormData {
+                fields: try_parse_query_string(Some(text))?
+                    .into_iter()
+                    .filter(|(name, _)| !name.is_empty())
+                    .collect(),
+                files: Vec::new(),
+            }
+        } else {
+            FormData::default()
+        };
+        let form = Arc::new(form);
+        *cached = Some(Arc::clone(&form));
+        Ok(form)
+    }
+}
+
+/// Reject a malformed `Content-Length` or one above `max_body_size`
+fn check_content_length(headers: &hyper::HeaderMap, max_body_size: usize) -> Result<()> {
+    let Some(value) = headers.get(hyper::header::CONTENT_LENGTH) else {
+        return Ok(());
+    };
+    let content_len = value
+        .to_str()
+        .ok()
+        .and_then(|s| s.trim().parse::<usize>().ok())
+        .ok_or_else(|| Error::BadRequest {
+            kind: RequestErrorKind::InvalidHeader,
+            message: "Content-Length is not a valid length".to_string(),
+        })?;
+    if content_len > max_body_size {
+        return Err(Error::PayloadTooLarge {
+            limit: max_body_size,
+            actual: content_len,
+        });
+    }
+    Ok(())
+}
+
+/// Parse a query string, rejecting percent-encoded data that is not UTF-8
+fn try_parse_query_string(query: Option<&str>) -> Result<HashMap<String, String>> {
+    let Some(query) = query else {
+        return Ok(HashMap::new());
+    };
+    let mut params = HashMap::new();
+    for pair in query.split('&') {
+        let mut parts = pair.splitn(2, '=');
+        let key = parts.next().unwrap_or("");
+        let value = parts.next().unwrap_or("");
+        let decode = |s: &str| {
+            String::from_utf8(url_decode_bytes(s)).map_err(|_| Error::BadRequest {
+                kind: RequestErrorKind::InvalidEncoding,
+                message: format!("query parameter {key:?} is not valid UTF-8"),
+            })
+        };
+        params.insert(decode(key)?, decode(value)?);
+    }
+    Ok(params)
+}
+
+/// Parse `Cookie` header values (`a=1; b="two"`) into a `HashMap`
+///
+/// Pairs without `=` or a name are skipped; the first value of a name wins.
+fn parse_cookies<'a>(headers: impl Iterator<Item = &'a str>) -> HashMap<String, String> {
+    let mut cookies = HashMap::new();
+    for pair in headers.flat_map(|header| header.split(';')) {
+        let Some((name, value)) = pair.split_once('=') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.is_empty() {
+            continue;
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .unwrap_or(value);
+        cookies
+            .entry(name.to_string())
+            .or_insert_with(|| value.to_string());
+    }
+    cookies
 }
 
 /// Parse query string into HashMap
@@ -310,30 +927,34 @@ fn parse_query_string(query: Option<&str>) -> HashMap<String, String> {
         .unwrap_or_default()
 }
 
-/// Basic URL decoding
+/// Basic URL decoding (invalid UTF-8 is replaced)
 fn url_decode(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars().peekable();
-
-    while let Some(c) = chars.next() {
-        match c {
-            '+' => result.push(' '),
-            '%' => {
-                let hex: String = chars.by_ref().take(2).collect();
-                if hex.len() == 2 {
-                    if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                        result.push(byte as char);
-                    } else {
-                        result.push('%');
-                        result.push_str(&hex);
-                    }
+    String::from_utf8_lossy(&url_decode_bytes(s)).into_owned()
+}
+
+/// Percent-decode into raw bytes; malformed escapes are kept literally
+fn url_decode_bytes(s: &str) -> Vec<u8> {
+    let bytes = s.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => result.push(b' '),
+            b'%' => {
+                let decoded = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                if let Some(byte) = decoded {
+                    result.push(byte);
+                    i += 2;
                 } else {
-                    result.push('%');
-                    result.push_str(&hex);
+                    result.push(b'%');
                 }
             }
-            _ => result.push(c),
+            b => result.push(b),
         }
+        i += 1;
     }
     result
 }
@@ -342,6 +963,47 @@ fn url_decode(s: &str) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_duplicate_headers_are_preserved() {
+        let mut req = PyRequest::new(Method::Get, "/".to_string(), HashMap::new(), None);
+        req.append_header("X-Forwarded-For", "203.0.113.7");
+        req.append_header("Accept", "*/*");
+        req.append_header("x-forwarded-for", "10.0.0.1");
+
+        assert_eq!(
+            req.header_values("X-FORWARDED-FOR"),
+            vec!["203.0.113.7", "10.0.0.1"]
+        );
+        assert_eq!(
+            req.raw_header_list(),
+            vec![
+                ("x-forwarded-for".to_string(), "203.0.113.7".to_string()),
+                ("x-forwarded-for".to_string(), "10.0.0.1".to_string()),
+                ("accept".to_string(), "*/*".to_string()),
+            ]
+        );
+        assert_eq!(req.headers_map().len(), 2);
+
+        let shared = req.clone();
+        assert!(req.remove_header("X-Forwarded-For"));
+        assert!(!req.remove_header("x-forwarded-for"));
+        assert!(req.header("x-forwarded-for").is_none());
+        assert_eq!(shared.header_values("x-forwarded-for").len(), 2);
+    }
+
+    #[test]
+    fn test_cookies_are_parsed() {
+        let mut req = PyRequest::new(Method::Get, "/".to_string(), HashMap::new(), None);
+        req.append_header("Cookie", r#"session=abc123; theme="dark mode"; ;flag"#);
+        req.append_header("Cookie", "session=older; lang=tr");
+
+        let cookies = req.cookie_map();
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies["session"], "abc123");
+        assert_eq!(cookies["theme"], "dark mode");
+        assert_eq!(cookies["lang"], "tr");
+    }
+
     #[test]
     fn test_parse_query_string_simple() {
         let result = parse_query_string(Some("page=1&limit=10"));
@@ -349,6 +1011,41 @@ mod tests {
         assert_eq!(result.get("limit"), Some(&"10".to_string()));
     }
 
+    #[test]
+    fn test_typed_query_accessors() {
+        let req = PyRequest::new(
+            Method::Get,
+            "/items?page=2&ratio=0.5&draft=Yes&limit=ten&x=inf".to_string(),
+            HashMap::new(),
+            None,
+        );
+        assert_eq!(req.query_i64("page").unwrap(), Some(2));
+        assert_eq!(req.query_i64("missing").unwrap(), None);
+        assert_eq!(req.query_f64("ratio").unwrap(), Some(0.5));
+        assert_eq!(req.query_flag("draft").unwrap(), Some(true));
+
+        let errors = req.query_i64("limit").unwrap_err();
+        assert_eq!(errors.errors[0].field, "limit");
+        assert_eq!(errors.errors[0].message, "limit must be an integer");
+        assert!(req.query_f64("x").is_err());
+        assert!(req.query_flag("page").is_err());
+    }
+
+    #[test]
+    fn test_url_is_reconstructed_from_host_header() {
+        let mut req = PyRequest::new(
+            Method::Get,
+            "/search?q=rust".to_string(),
+            HashMap::from([("host".to_string(), "example.com:8443".to_string())]),
+            None,
+        );
+        req.scheme = "https";
+        assert_eq!(req.url(), "https://example.com:8443/search?q=rust");
+
+        let req = PyRequest::new(Method::Get, "/".to_string(), HashMap::new(), None);
+        assert_eq!(req.url(), "http://localhost/");
+    }
+
     #[test]
     fn test_parse_query_string_empty() {
         let result = parse_query_string(None);
@@ -367,5 +1064,146 @@ mod tests {
         assert_eq!(url_decode("hello+world"), "hello world");
         assert_eq!(url_decode("hello%20world"), "hello world");
         assert_eq!(url_decode("100%25"), "100%");
+        assert_eq!(url_decode("caf%C3%A9"), "café");
+        assert_eq!(url_decode("50%"), "50%");
+        assert_eq!(url_decode("%zz"), "%zz");
+    }
+
+    #[test]
+    fn test_query_string_invalid_utf8_is_rejected() {
+        let err = try_parse_query_string(Some("name=%FF%FE")).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::BadRequest {
+                kind: RequestErrorKind::InvalidEncoding,
+                ..
+            }
+        ));
+        let params = try_parse_query_string(Some("q=caf%C3%A9&x")).unwrap();
+        assert_eq!(params.get("q").unwrap(), "café");
+        assert_eq!(params.get("x").unwrap(), "");
+    }
+
+    #[test]
+    fn test_invalid_content_length_is_rejected() {
+        let mut headers = hyper::HeaderMap::new();
+        headers.insert(hyper::header::CONTENT_LENGTH, "12abc".parse().unwrap());
+        let err = check_content_length(&headers, 1024).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::BadRequest {
+                kind: RequestErrorKind::InvalidHeader,
+                ..
+            }
+        ));
+
+        headers.insert(hyper::header::CONTENT_LENGTH, "4096".parse().unwrap());
+        assert!(matches!(
+            check_content_length(&headers, 1024),
+            Err(Error::PayloadTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_form_data_is_parsed_once_and_shared() {
+        let body = "--b\r\nContent-Disposition: form-data; name=\"tag\"\r\n\r\nrust\r\n--b--\r\n";
+        let req = PyRequest::new(
+            Method::Post,
+            "/upload".to_string(),
+            HashMap::from([(
+                "content-type".to_string(),
+                "multipart/form-data; boundary=b".to_string(),
+            )]),
+            Some(Bytes::from(body)),
+        );
+        let form = req.form_data().unwrap();
+        assert_eq!(form.field("tag"), Some("rust"));
+        assert!(Arc::ptr_eq(&form, &req.clone().form_data().unwrap()));
+
+        let req = PyRequest::new(
+            Method::Post,
+            "/login".to_string(),
+            HashMap::from([(
+                "content-type".to_string(),
+                "application/x-www-form-urlencoded".to_string(),
+            )]),
+            Some(Bytes::from("user=ada+l&next=%2Fhome")),
+        );
+        let form = req.form_data().unwrap();
+        assert_eq!(form.field("user"), Some("ada l"));
+        assert_eq!(form.field("next"), Some("/home"));
+    }
+
+    #[tokio::test]
+    async fn test_chunked_body_is_rejected_once_over_the_limit() {
+        use futures_util::stream::{self, StreamExt};
+        use http_body_util::StreamBody;
+        use hyper::body::Frame;
+
+        let frames = |chunks: [&'static str; 3]| {
+            stream::iter(chunks.map(|c| Ok::<_, std::io::Error>(Frame::data(Bytes::from(c)))))
+        };
+        let mut req = PyRequest::new(Method::Post, "/upload".into(), HashMap::new(), None);
+
+        // The body never ends: the limit must apply before it completes
+        let endless = StreamBody::new(frames(["abcd", "efgh", "ijkl"]).chain(stream::pending()));
+        let err = req.read_body(endless, 10).await.unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PayloadTooLarge {
+                limit: 10,
+                actual: 12
+            }
+        ));
+
+        let body = StreamBody::new(frames(["ab", "", "cd"]));
+        req.read_body(body, 10).await.unwrap();
+        assert_eq!(req.body.as_deref(), Some(&b"abcd"[..]));
+    }
+
+    #[test]
+    fn test_json_body() {
+        let request = |body: &'static str| {
+            PyRequest::new(
+                Method::Post,
+                "/orders".to_string(),
+                HashMap::new(),
+                Some(Bytes::from(body)),
+            )
+        };
+        assert_eq!(
+            request(r#"{"qty": 2}"#).json_body().unwrap(),
+            serde_json::json!({"qty": 2})
+        );
+        for body in ["", r#"{"qty": }"#] {
+            let Err(Error::BadRequest { kind, message }) = request(body).json_body() else {
+                panic!("{body:?} was accepted");
+            };
+            assert_eq!(kind, RequestErrorKind::InvalidJson);
+            assert!(!message.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_target_limits() {
+        let limits = TargetLimits {
+            max_uri_length: 24,
+            max_query_params: 2,
+        };
+        assert!(limits.check("/search", Some("a=1&b=2")).is_ok());
+        assert!(matches!(
+            limits.check("/search", Some("a=1&b=2&c")),
+            Err(Error::BadRequest {
+                kind: RequestErrorKind::TooManyQueryParams,
+                ..
+            })
+        ));
+        assert!(matches!(
+            limits.check("/search/everything", Some("q=every")),
+            Err(Error::UriTooLong {
+                limit: 24,
+                actual: 26
+            })
+        ));
     }
 }
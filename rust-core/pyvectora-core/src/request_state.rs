@@ -0,0 +1,150 @@
+//! # Request State
+//!
+//! Values attached to one request by middleware and read by its handler,
+//! such as the authenticated user. Rust middleware stores JSON values;
+//! Python code sees the state as `request.state` and may store any object.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only holds request-scoped values
+//! - **D**: Middleware and handlers share values, not headers or globals
+
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyAttributeError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Value stored in a request's state
+#[derive(Debug, Clone)]
+pub enum StateValue {
+    /// Set from Rust
+    Json(Value),
+    /// Set from Python (a `PyObject`), opaque to Rust
+    Object(Arc<dyn Any + Send + Sync>),
+}
+
+/// Request-scoped values, shared by every clone of the request
+#[cfg_attr(feature = "python", pyclass(name = "State"))]
+#[derive(Debug, Clone, Default)]
+pub struct RequestState {
+    values: Arc<Mutex<BTreeMap<String, StateValue>>>,
+}
+
+impl RequestState {
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, StateValue>> {
+        self.values.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Store `value` under `key`, replacing any previous value
+    pub fn set(&self, key: impl Into<String>, value: Value) {
+        self.lock().insert(key.into(), StateValue::Json(value));
+    }
+
+    /// JSON value under `key` (`None` if missing or set from Python)
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Value> {
+        match self.lock().get(key)? {
+            StateValue::Json(value) => Some(value.clone()),
+            StateValue::Object(_) => None,
+        }
+    }
+
+    /// Remove the value under `key`, returning whether there was one
+    #[must_use]
+    pub fn remove(&self, key: &str) -> bool {
+        self.lock().remove(key).is_some()
+    }
+
+    /// Whether a value is stored under `key`
+    #[must_use]
+    pub fn contains(&self, key: &str) -> bool {
+        self.lock().contains_key(key)
+    }
+
+    /// Stored keys, sorted
+    #[must_use]
+    pub fn keys(&self) -> Vec<String> {
+        self.lock().keys().cloned().collect()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl RequestState {
+    fn __getattr__(&self, py: Python<'_>, name: &str) -> PyResult<PyObject> {
+        self.get_py(py, name)
+            .ok_or_else(|| PyAttributeError::new_err(format!("request state has no {name:?}")))?
+    }
+
+    fn __setattr__(&self, name: String, value: PyObject) {
+        self.lock()
+            .insert(name, StateValue::Object(Arc::new(value)));
+    }
+
+    fn __delattr__(&self, name: &str) -> PyResult<()> {
+        if self.remove(name) {
+            Ok(())
+        } else {
+            Err(PyAttributeError::new_err(format!(
+                "request state has no {name:?}"
+            )))
+        }
+    }
+
+    fn __contains__(&self, name: &str) -> bool {
+        self.contains(name)
+    }
+
+    /// Value under `name`, or `default`
+    #[pyo3(name = "get", signature = (name, default=None))]
+    fn py_get(&self, py: Python<'_>, name: &str, default: Option<PyObject>) -> PyResult<PyObject> {
+        self.get_py(py, name)
+            .unwrap_or_else(|| Ok(default.unwrap_or_else(|| py.None())))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("State({})", self.keys().join(", "))
+    }
+}
+
+#[cfg(feature = "python")]
+impl RequestState {
+    fn get_py(&self, py: Python<'_>, name: &str) -> Option<PyResult<PyObject>> {
+        let value = self.lock().get(name)?.clone();
+        Some(match value {
+            StateValue::Json(value) => py
+                .import("json")
+                .and_then(|json| json.call_method1("loads", (value.to_string(),)))
+                .map(Into::into),
+            StateValue::Object(object) => object
+                .downcast_ref::<PyObject>()
+                .map(|object| object.clone_ref(py))
+                .ok_or_else(|| {
+                    PyAttributeError::new_err(format!("{name:?} is not a Python value"))
+                }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clones_share_values() {
+        let state = RequestState::default();
+        let other = state.clone();
+        state.set("user", serde_json::json!({"id": 7}));
+
+        assert!(other.contains("user"));
+        assert_eq!(other.get("user").unwrap()["id"], 7);
+        assert_eq!(other.keys(), vec!["user".to_string()]);
+        assert!(other.remove("user"));
+        assert!(!state.contains("user"));
+        assert!(!state.remove("user"));
+    }
+}
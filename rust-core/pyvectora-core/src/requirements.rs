@@ -0,0 +1,186 @@
+//! # Route Requirements
+//!
+//! Per-route preconditions on the request — accepted content types and
+//! required headers — checked before the handler runs. Failures are
+//! answered with 415 or 400 and a structured body, so handlers never see
+//! a request they declared they cannot handle.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only declares and checks preconditions
+//! - **O**: Wraps any `Handler`, like route-group middleware
+
+use crate::problem::ProblemDetails;
+use crate::server::{Handler, PyRequest, PyResponse};
+use crate::validation::{FieldError, ValidationErrors};
+use std::sync::Arc;
+
+/// Preconditions a request must meet to reach a route's handler
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RouteRequirements {
+    content_types: Vec<String>,
+    headers: Vec<String>,
+}
+
+impl RouteRequirements {
+    /// No requirements
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `media_type` (e.g. `"application/json"`) as the request's `Content-Type`
+    ///
+    /// Parameters such as `charset` are ignored when comparing. With
+    /// several accepted types any of them may be sent.
+    #[must_use]
+    pub fn content_type(mut self, media_type: impl Into<String>) -> Self {
+        self.content_types.push(media_type.into());
+        self
+    }
+
+    /// Require the `name` header (case-insensitive) to be present
+    #[must_use]
+    pub fn header(mut self, name: impl Into<String>) -> Self {
+        self.headers.push(name.into());
+        self
+    }
+
+    /// Whether any requirement is declared
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.content_types.is_empty() && self.headers.is_empty()
+    }
+
+    /// The error response for `req`, or `None` if it meets every requirement
+    ///
+    /// A missing or unaccepted `Content-Type` is answered 415; missing
+    /// headers are answered 400 with one `REQUIRED` error per header.
+    #[must_use]
+    pub fn check(&self, req: &PyRequest) -> Option<PyResponse> {
+        if !self.content_types.is_empty() {
+            let media_type = req
+                .header("content-type")
+                .and_then(|value| value.split(';').next())
+                .map(str::trim);
+            let accepted = media_type.is_some_and(|media_type| {
+                self.content_types
+                    .iter()
+                    .any(|accepted| accepted.eq_ignore_ascii_case(media_type))
+            });
+            if !accepted {
+                return Some(self.unsupported_media_type(media_type));
+            }
+        }
+
+        let mut errors = ValidationErrors::new();
+        for name in &self.headers {
+            if req.header(name).is_none() {
+                errors.add(FieldError::required(name.as_str()));
+            }
+        }
+        if errors.is_empty() {
+            return None;
+        }
+        let body =
+            serde_json::json!({ "error": "Missing required headers", "errors": errors.errors });
+        Some(
+            PyResponse::json(body.to_string())
+                .with_status(400)
+                .with_problem(
+                    ProblemDetails::new(400)
+                        .with_detail("Missing required headers")
+                        .with_extension("errors", body["errors"].clone()),
+                ),
+        )
+    }
+
+    /// Wrap `handler` so requests failing `check` never reach it
+    #[must_use]
+    pub fn wrap(&self, handler: Handler) -> Handler {
+        if self.is_empty() {
+            return handler;
+        }
+        let requirements = self.clone();
+        Arc::new(move |req, matched| match requirements.check(req) {
+            Some(resp) => Box::pin(std::future::ready(resp)),
+            None => handler(req, matched),
+        })
+    }
+
+    fn unsupported_media_type(&self, received: Option<&str>) -> PyResponse {
+        let detail = format!("Content-Type must be {}", self.content_types.join(" or "));
+        let body = serde_json::json!({
+            "error": "Unsupported Media Type",
+            "detail": detail,
+            "expected": self.content_types,
+            "received": received,
+        });
+        PyResponse::json(body.to_string())
+            .with_status(415)
+            .with_problem(
+                ProblemDetails::new(415)
+                    .with_detail(detail)
+                    .with_extension("expected", body["expected"].clone()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::Method;
+    use std::collections::HashMap;
+
+    fn request(headers: &[(&str, &str)]) -> PyRequest {
+        let headers = headers
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+            .collect::<HashMap<_, _>>();
+        PyRequest::new(Method::Post, "/orders".to_string(), headers, None)
+    }
+
+    #[test]
+    fn test_content_type_is_enforced() {
+        let requires = RouteRequirements::new().content_type("application/json");
+
+        let resp = requires.check(&request(&[])).unwrap();
+        assert_eq!(resp.status, 415);
+        let resp = requires
+            .check(&request(&[("Content-Type", "text/plain")]))
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_str(&resp.body).unwrap();
+        assert_eq!(body["expected"][0], "application/json");
+        assert_eq!(body["received"], "text/plain");
+
+        assert!(requires
+            .check(&request(&[(
+                "Content-Type",
+                "Application/JSON; charset=utf-8"
+            )]))
+            .is_none());
+    }
+
+    #[test]
+    fn test_missing_headers_are_listed() {
+        let requires = RouteRequirements::new()
+            .header("X-Tenant-Id")
+            .header("X-Request-Source");
+
+        let resp = requires
+            .check(&request(&[("x-tenant-id", "acme")]))
+            .unwrap();
+        assert_eq!(resp.status, 400);
+        let body: serde_json::Value = serde_json::from_str(&resp.body).unwrap();
+        assert_eq!(body["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(body["errors"][0]["field"], "X-Request-Source");
+        assert_eq!(body["errors"][0]["code"], "REQUIRED");
+
+        assert!(requires
+            .check(&request(&[
+                ("X-Tenant-Id", "acme"),
+                ("X-Request-Source", "cli")
+            ]))
+            .is_none());
+    }
+}
@@ -0,0 +1,220 @@
+//! # Retry Policies
+//!
+//! Backoff schedules for retrying fallible operations.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only computes retry delays and deadlines, never runs the operation
+//! - **O**: New schedules are added as `Backoff` variants
+//! - **D**: Callers (e.g. Python bindings) drive the loop and decide what is retryable
+
+use crate::error::{Error, Result};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Delay growth between attempts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backoff {
+    /// Same delay before every retry
+    Constant,
+    /// Delay grows by `base_delay` each retry
+    Linear,
+    /// Delay doubles each retry
+    Exponential,
+}
+
+impl FromStr for Backoff {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "constant" | "fixed" => Ok(Self::Constant),
+            "linear" => Ok(Self::Linear),
+            "exponential" => Ok(Self::Exponential),
+            other => Err(Error::Config {
+                message: format!(
+                    "unknown backoff '{other}', expected constant, linear or exponential"
+                ),
+            }),
+        }
+    }
+}
+
+/// Retry policy: attempt budget, backoff schedule and optional deadline
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub attempts: u32,
+    /// Delay growth between attempts
+    pub backoff: Backoff,
+    /// Delay before the first retry
+    pub base_delay: Duration,
+    /// Upper bound for a single delay
+    pub max_delay: Duration,
+    /// Randomize each delay to avoid synchronized retries
+    pub jitter: bool,
+    /// Give up instead of sleeping past this point
+    pub deadline: Option<Instant>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Backoff::Exponential,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            deadline: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Set the total number of attempts (at least one)
+    #[must_use]
+    pub fn with_attempts(mut self, attempts: u32) -> Self {
+        self.attempts = attempts.max(1);
+        self
+    }
+
+    /// Set the backoff schedule
+    #[must_use]
+    pub const fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Set the base and maximum delay
+    #[must_use]
+    pub fn with_delays(mut self, base_delay: Duration, max_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self.max_delay = max_delay.max(base_delay);
+        self
+    }
+
+    /// Enable or disable jitter
+    #[must_use]
+    pub const fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Stop retrying once `timeout` has elapsed from now
+    #[must_use]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.deadline = Some(Instant::now() + timeout);
+        self
+    }
+
+    /// Delay before retry number `retry` (1-based), without jitter
+    #[must_use]
+    pub fn base_delay_for(&self, retry: u32) -> Duration {
+        let delay = match self.backoff {
+            Backoff::Constant => self.base_delay,
+            Backoff::Linear => self.base_delay.saturating_mul(retry),
+            Backoff::Exponential => self
+                .base_delay
+                .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1))),
+        };
+        delay.min(self.max_delay)
+    }
+
+    /// Delay to wait after failed attempt `attempt` (1-based)
+    ///
+    /// Returns `None` when the attempt budget is spent or sleeping would
+    /// cross the deadline. With jitter the delay is drawn uniformly from
+    /// `[delay / 2, delay]`.
+    #[must_use]
+    pub fn next_delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt >= self.attempts {
+            return None;
+        }
+        let mut delay = self.base_delay_for(attempt);
+        if self.jitter {
+            delay = jittered(delay);
+        }
+        match self.deadline {
+            Some(deadline) if Instant::now() + delay >= deadline => None,
+            _ => Some(delay),
+        }
+    }
+}
+
+/// Pick a delay uniformly from `[delay / 2, delay]`
+fn jittered(delay: Duration) -> Duration {
+    let half = delay / 2;
+    let span = u64::try_from(delay.saturating_sub(half).as_nanos()).unwrap_or(u64::MAX);
+    if span == 0 {
+        return delay;
+    }
+    let random = RandomState::new().build_hasher().finish();
+    half + Duration::from_nanos(random % span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(backoff: Backoff) -> RetryPolicy {
+        RetryPolicy::default()
+            .with_attempts(5)
+            .with_backoff(backoff)
+            .with_delays(Duration::from_millis(100), Duration::from_millis(500))
+            .with_jitter(false)
+    }
+
+    #[test]
+    fn test_backoff_from_str() {
+        assert_eq!(
+            "exponential".parse::<Backoff>().unwrap(),
+            Backoff::Exponential
+        );
+        assert_eq!("Linear".parse::<Backoff>().unwrap(), Backoff::Linear);
+        assert_eq!("fixed".parse::<Backoff>().unwrap(), Backoff::Constant);
+        assert!("random".parse::<Backoff>().is_err());
+    }
+
+    #[test]
+    fn test_exponential_delays_are_capped() {
+        let p = policy(Backoff::Exponential);
+        let delays: Vec<_> = (1..=4).map(|n| p.base_delay_for(n).as_millis()).collect();
+        assert_eq!(delays, vec![100, 200, 400, 500]);
+    }
+
+    #[test]
+    fn test_linear_and_constant_delays() {
+        assert_eq!(
+            policy(Backoff::Linear).base_delay_for(3),
+            Duration::from_millis(300)
+        );
+        assert_eq!(
+            policy(Backoff::Constant).base_delay_for(3),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    fn test_next_delay_respects_attempts() {
+        let p = policy(Backoff::Constant);
+        assert!(p.next_delay(4).is_some());
+        assert!(p.next_delay(5).is_none());
+    }
+
+    #[test]
+    fn test_next_delay_respects_deadline() {
+        let p = policy(Backoff::Constant).with_timeout(Duration::from_millis(50));
+        assert!(p.next_delay(1).is_none());
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        let p = policy(Backoff::Exponential).with_jitter(true);
+        for _ in 0..32 {
+            let delay = p.next_delay(2).unwrap();
+            assert!(delay >= Duration::from_millis(100) && delay <= Duration::from_millis(200));
+        }
+    }
+}
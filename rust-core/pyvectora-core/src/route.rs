@@ -11,6 +11,16 @@
 use crate::router::HandlerId;
 use crate::types::ParamType;
 use std::collections::HashMap;
+use std::time::Duration;
+
+/// Per-route overrides of server-wide limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RouteLimits {
+    /// Max request body size in bytes (`None` = `ServerConfig::max_body_size`)
+    pub max_body_size: Option<usize>,
+    /// Time the handler may take (`None` = `ServerConfig::handler_timeout`)
+    pub timeout: Option<Duration>,
+}
 
 /// Route metadata containing handler and type information
 ///
@@ -28,6 +38,8 @@ pub struct RouteInfo {
     pub param_types: HashMap<String, ParamType>,
     /// Whether authentication is required for this route
     pub auth_required: bool,
+    /// Body size and handler time limits overriding the server's
+    pub limits: RouteLimits,
 }
 
 impl RouteInfo {
@@ -51,6 +63,7 @@ impl RouteInfo {
             match_pattern,
             param_types,
             auth_required,
+            limits: RouteLimits::default(),
         }
     }
 
@@ -58,6 +71,7 @@ impl RouteInfo {
     ///
     /// Converts `{name:type}` to `{name}` for matchit compatibility
     /// and builds the param_types map.
+    /// Catch-all parameters (`{*name}` or `{name:path}`) become `{*name}`.
     ///
     /// # Returns
     ///
@@ -72,8 +86,12 @@ impl RouteInfo {
             }
 
             if let Some((name, param_type)) = crate::types::parse_param_pattern(segment) {
-                param_types.insert(name.clone(), param_type);
-                normalized_parts.push(format!("{{{}}}", name));
+                if param_type == ParamType::Path {
+                    normalized_parts.push(format!("{{*{name}}}"));
+                } else {
+                    normalized_parts.push(format!("{{{}}}", name));
+                }
+                param_types.insert(name, param_type);
             } else {
                 normalized_parts.push(segment.to_string());
             }
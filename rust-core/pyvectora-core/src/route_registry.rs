@@ -0,0 +1,267 @@
+//! # Route Registry
+//!
+//! Runtime-mutable set of HTTP routes and their handlers. The server reads
+//! an immutable snapshot per request; adding or removing a route publishes a
+//! new snapshot, so plugins can register endpoints after `serve()` started
+//! without pausing traffic.
+//!
+//! Requests already matched keep the snapshot they started with.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only owns the route/handler pairing and its publication
+//! - **D**: Matching stays in `Router`, request handling in `Server`
+
+use crate::error::Result;
+use crate::problem::ProblemDetails;
+use crate::route::RouteLimits;
+use crate::router::{Method, ParamMismatch, Router};
+use crate::server::{Handler, PyResponse};
+use std::collections::HashSet;
+use std::sync::{Arc, PoisonError, RwLock};
+
+/// Routes and handlers, swapped as a unit
+///
+/// `handlers` is indexed by the router's handler IDs.
+#[derive(Clone, Default)]
+pub struct RouteSnapshot {
+    /// Router matching requests to handler IDs
+    pub router: Router,
+    /// Handlers indexed by handler ID
+    pub handlers: Vec<Handler>,
+    /// Handler IDs of routes whose request body is streamed, not collected
+    pub streaming: HashSet<usize>,
+}
+
+impl RouteSnapshot {
+    /// Whether the route matching `method` and `path` streams its body
+    #[must_use]
+    pub fn streams_body(&self, method: Method, path: &str) -> bool {
+        !self.streaming.is_empty()
+            && self
+                .router
+                .route_info(method, path)
+                .is_some_and(|route| self.streaming.contains(&route.handler_id))
+    }
+
+    /// Limits of the route matching `method` and `path` (none if unmatched)
+    #[must_use]
+    pub fn limits(&self, method: Method, path: &str) -> RouteLimits {
+        self.router
+            .route_info(method, path)
+            .map(|route| route.limits)
+            .unwrap_or_default()
+    }
+}
+
+/// Shared, hot-swappable route set
+///
+/// Cloning is cheap and every clone sees the same routes.
+#[derive(Clone, Default)]
+pub struct RouteRegistry {
+    current: Arc<RwLock<Arc<RouteSnapshot>>>,
+}
+
+impl RouteRegistry {
+    /// Create an empty registry
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The routes new requests are matched against
+    #[must_use]
+    pub fn snapshot(&self) -> Arc<RouteSnapshot> {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Add a route and its handler
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RouteConflict` or `Error::InvalidRoutePattern` as
+    /// `Router::add_route` does; the registry is unchanged in that case.
+    pub fn add_route(
+        &self,
+        method: Method,
+        path: &str,
+        handler: Handler,
+        auth_required: bool,
+    ) -> Result<()> {
+        self.insert(method, path, handler, auth_required, false)
+    }
+
+    /// Add a route whose handler reads the request body as a stream
+    ///
+    /// The body is not collected first, so `max_body_size` does not apply;
+    /// the handler consumes it through `PyRequest::body_stream`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RouteConflict` or `Error::InvalidRoutePattern` as
+    /// `Router::add_route` does; the registry is unchanged in that case.
+    pub fn add_streaming_route(
+        &self,
+        method: Method,
+        path: &str,
+        handler: Handler,
+        auth_required: bool,
+    ) -> Result<()> {
+        self.insert(method, path, handler, auth_required, true)
+    }
+
+    fn insert(
+        &self,
+        method: Method,
+        path: &str,
+        handler: Handler,
+        auth_required: bool,
+        streaming: bool,
+    ) -> Result<()> {
+        self.update(|routes| {
+            let handler_id = routes.router.add_route(method, path, auth_required)?;
+            debug_assert_eq!(handler_id, routes.handlers.len());
+            routes.handlers.push(handler);
+            if streaming {
+                routes.streaming.insert(handler_id);
+            }
+            Ok(())
+        })
+    }
+
+    /// Remove the route registered for `method` with exactly `path`
+    ///
+    /// Returns `false` if there was no such route. The handler is released
+    /// once requests still running it finish.
+    #[must_use]
+    pub fn remove_route(&self, method: Method, path: &str) -> bool {
+        self.update(|routes| {
+            let Some(handler_id) = routes.router.remove_route(method, path) else {
+                return Err(());
+            };
+            routes.handlers[handler_id] = removed_handler();
+            routes.streaming.remove(&handler_id);
+            Ok(())
+        })
+        .is_ok()
+    }
+
+    /// Set the limits of the route registered for `method` with exactly `path`
+    ///
+    /// Returns `false` if there is no such route.
+    #[must_use]
+    pub fn set_limits(&self, method: Method, path: &str, limits: RouteLimits) -> bool {
+        self.update(|routes| {
+            if routes.router.set_limits(method, path, limits) {
+                Ok(())
+            } else {
+                Err(())
+            }
+        })
+        .is_ok()
+    }
+
+    /// Choose how typed path parameters that fail to convert are answered
+    pub fn set_param_mismatch(&self, policy: ParamMismatch) {
+        let _ = self.update(|routes| {
+            routes.router.set_param_mismatch(policy);
+            Ok::<_, ()>(())
+        });
+    }
+
+    /// Apply `change` to the routes new requests will see
+    ///
+    /// The routes are copied first only while requests hold the current
+    /// snapshot; `change` must leave them untouched when it fails.
+    fn update<E>(
+        &self,
+        change: impl FnOnce(&mut RouteSnapshot) -> std::result::Result<(), E>,
+    ) -> std::result::Result<(), E> {
+        change(Arc::make_mut(
+            &mut self.current.write().unwrap_or_else(PoisonError::into_inner),
+        ))
+    }
+}
+
+/// Placeholder for a removed route's handler slot (never matched)
+fn removed_handler() -> Handler {
+    Arc::new(|_req, _matched| {
+        Box::pin(std::future::ready(
+            PyResponse::text(r#"{"error": "Not Found"}"#)
+                .with_status(404)
+                .with_header("Content-Type", "application/json")
+                .with_problem(ProblemDetails::new(404)),
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn handler(body: &'static str) -> Handler {
+        Arc::new(move |_req, _matched| Box::pin(std::future::ready(PyResponse::text(body))))
+    }
+
+    #[test]
+    fn test_snapshots_are_isolated_from_updates() {
+        let registry = RouteRegistry::new();
+        registry
+            .add_route(Method::Get, "/a", handler("a"), false)
+            .unwrap();
+        let before = registry.snapshot();
+
+        registry
+            .add_route(Method::Get, "/b", handler("b"), false)
+            .unwrap();
+        assert!(before.router.match_route(Method::Get, "/b").is_err());
+        assert!(registry
+            .snapshot()
+            .router
+            .match_route(Method::Get, "/b")
+            .is_ok());
+
+        assert!(registry.remove_route(Method::Get, "/a"));
+        assert!(!registry.remove_route(Method::Get, "/a"));
+        assert!(before.router.match_route(Method::Get, "/a").is_ok());
+        assert!(registry
+            .snapshot()
+            .router
+            .match_route(Method::Get, "/a")
+            .is_err());
+    }
+
+    #[test]
+    fn test_conflict_leaves_registry_unchanged() {
+        let registry = RouteRegistry::new();
+        registry
+            .add_route(Method::Get, "/users/{id}", handler("id"), false)
+            .unwrap();
+        assert!(registry
+            .add_route(Method::Get, "/users/{name}", handler("name"), false)
+            .is_err());
+        assert_eq!(registry.snapshot().handlers.len(), 1);
+    }
+
+    #[test]
+    fn test_streaming_routes_are_flagged() {
+        let registry = RouteRegistry::new();
+        registry
+            .add_streaming_route(Method::Post, "/uploads/{name}", handler("up"), false)
+            .unwrap();
+        registry
+            .add_route(Method::Post, "/forms", handler("form"), false)
+            .unwrap();
+
+        let routes = registry.snapshot();
+        assert!(routes.streams_body(Method::Post, "/uploads/big.bin"));
+        assert!(!routes.streams_body(Method::Post, "/forms"));
+        assert!(!routes.streams_body(Method::Get, "/uploads/big.bin"));
+
+        assert!(registry.remove_route(Method::Post, "/uploads/{name}"));
+        assert!(registry.snapshot().streaming.is_empty());
+    }
+}
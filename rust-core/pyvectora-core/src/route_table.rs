@@ -0,0 +1,211 @@
+//! # Route Table Export
+//!
+//! Serializable snapshot of a compiled router, for warm starts and for
+//! diffing the endpoint surface between deploys.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only describes, validates and compares route tables
+//! - **O**: New per-route metadata is added as `RouteEntry` fields (serde defaults)
+//! - **D**: Works from `Router` exports, independent of handler implementations
+
+use crate::error::{Error, Result};
+use crate::router::Router;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Current export format version
+pub const ROUTE_TABLE_VERSION: u32 = 1;
+
+/// One exported route
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct RouteEntry {
+    /// HTTP method (e.g. `"GET"`)
+    pub method: String,
+    /// Original path pattern (e.g. `"/users/{id:int}"`)
+    pub path: String,
+    /// Parameter name to type name (e.g. `{"id": "int"}`)
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    /// Whether JWT authentication is required
+    #[serde(default)]
+    pub auth_required: bool,
+}
+
+impl RouteEntry {
+    /// `"METHOD /path"` label used as the diff key
+    #[must_use]
+    pub fn key(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+}
+
+/// Exported route table
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RouteTable {
+    /// Format version
+    pub version: u32,
+    /// Routes sorted by path, then method
+    pub routes: Vec<RouteEntry>,
+}
+
+/// Differences between two route tables
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct RouteDiff {
+    /// Routes only present in the new table
+    pub added: Vec<String>,
+    /// Routes only present in the old table
+    pub removed: Vec<String>,
+    /// Routes present in both whose params or auth changed
+    pub changed: Vec<String>,
+}
+
+impl RouteDiff {
+    /// Whether the tables describe the same endpoints
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl RouteTable {
+    /// Build a table from entries (sorted for stable output)
+    #[must_use]
+    pub fn new(mut routes: Vec<RouteEntry>) -> Self {
+        routes.sort_by(|a, b| a.path.cmp(&b.path).then_with(|| a.method.cmp(&b.method)));
+        Self {
+            version: ROUTE_TABLE_VERSION,
+            routes,
+        }
+    }
+
+    /// Serialize to pretty-printed JSON
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Json` if serialization fails.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Parse a table exported by `to_json`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Json` for malformed input and `Error::Config` for
+    /// an unsupported format version.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let table: Self = serde_json::from_str(json)?;
+        if table.version > ROUTE_TABLE_VERSION {
+            return Err(Error::Config {
+                message: format!("unsupported route table version {}", table.version),
+            });
+        }
+        Ok(table)
+    }
+
+    /// Rebuild a router from the table, validating every pattern
+    ///
+    /// # Errors
+    ///
+    /// Returns an error for unknown methods, malformed patterns or
+    /// conflicting routes.
+    pub fn to_router(&self) -> Result<Router> {
+        let mut router = Router::new();
+        for route in &self.routes {
+            router.add_route(route.method.parse()?, &route.path, route.auth_required)?;
+        }
+        Ok(router)
+    }
+
+    /// Compare against a newer table
+    #[must_use]
+    pub fn diff(&self, new: &Self) -> RouteDiff {
+        let old_routes: BTreeMap<_, _> = self.routes.iter().map(|r| (r.key(), r)).collect();
+        let new_routes: BTreeMap<_, _> = new.routes.iter().map(|r| (r.key(), r)).collect();
+        let keys: BTreeSet<_> = old_routes.keys().chain(new_routes.keys()).collect();
+
+        let mut diff = RouteDiff::default();
+        for key in keys {
+            match (old_routes.get(key), new_routes.get(key)) {
+                (Some(_), None) => diff.removed.push(key.clone()),
+                (None, Some(_)) => diff.added.push(key.clone()),
+                (Some(old), Some(new)) if old != new => diff.changed.push(key.clone()),
+                _ => {}
+            }
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::Method;
+
+    fn router() -> Router {
+        let mut router = Router::new();
+        router.get("/users").unwrap();
+        router
+            .add_route(Method::Get, "/users/{id:int}", true)
+            .unwrap();
+        router.post("/users").unwrap();
+        router
+    }
+
+    #[test]
+    fn test_export_is_sorted_and_typed() {
+        let table = router().export_table();
+        let keys: Vec<_> = table.routes.iter().map(RouteEntry::key).collect();
+        assert_eq!(
+            keys,
+            vec!["GET /users", "POST /users", "GET /users/{id:int}"]
+        );
+        assert_eq!(table.routes[2].params.get("id").unwrap(), "int");
+        assert!(table.routes[2].auth_required);
+    }
+
+    #[test]
+    fn test_json_round_trip_rebuilds_router() {
+        let json = router().export_table().to_json().unwrap();
+        let table = RouteTable::from_json(&json).unwrap();
+        let rebuilt = table.to_router().unwrap();
+        let matched = rebuilt.match_route(Method::Get, "/users/7").unwrap();
+        assert!(matched.auth_required);
+        assert_eq!(rebuilt.export_table(), table);
+    }
+
+    #[test]
+    fn test_diff_detects_removed_and_changed_routes() {
+        let old = router().export_table();
+        let mut new_router = Router::new();
+        new_router.get("/users").unwrap();
+        new_router.get("/users/{id:int}").unwrap();
+        new_router.get("/health").unwrap();
+
+        let diff = old.diff(&new_router.export_table());
+        assert_eq!(diff.added, vec!["GET /health"]);
+        assert_eq!(diff.removed, vec!["POST /users"]);
+        assert_eq!(diff.changed, vec!["GET /users/{id:int}"]);
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn test_to_router_rejects_conflicts() {
+        let table = RouteTable::new(vec![
+            RouteEntry {
+                method: "GET".into(),
+                path: "/a/{x}".into(),
+                params: BTreeMap::new(),
+                auth_required: false,
+            },
+            RouteEntry {
+                method: "GET".into(),
+                path: "/a/{y}".into(),
+                params: BTreeMap::new(),
+                auth_required: false,
+            },
+        ]);
+        assert!(table.to_router().is_err());
+    }
+}
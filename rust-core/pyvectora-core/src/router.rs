@@ -17,8 +17,10 @@
 //! - **D**: Depends on `types::convert_param`, not concrete conversion logic
 
 use crate::error::{Error, Result};
-use crate::route::RouteInfo;
+use crate::route::{RouteInfo, RouteLimits};
+use crate::route_table::{RouteEntry, RouteTable};
 use crate::types::{convert_param, ParamValue};
+use crate::validation::{FieldError, ValidationErrors};
 use matchit::Router as MatchitRouter;
 use std::collections::HashMap;
 
@@ -41,6 +43,19 @@ pub enum Method {
     Options,
 }
 
+impl Method {
+    /// Every supported method, in `Allow` header order
+    pub const ALL: [Self; 7] = [
+        Self::Get,
+        Self::Head,
+        Self::Post,
+        Self::Put,
+        Self::Patch,
+        Self::Delete,
+        Self::Options,
+    ];
+}
+
 impl std::fmt::Display for Method {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -55,6 +70,25 @@ impl std::fmt::Display for Method {
     }
 }
 
+impl std::str::FromStr for Method {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_uppercase().as_str() {
+            "GET" => Ok(Self::Get),
+            "POST" => Ok(Self::Post),
+            "PUT" => Ok(Self::Put),
+            "DELETE" => Ok(Self::Delete),
+            "PATCH" => Ok(Self::Patch),
+            "HEAD" => Ok(Self::Head),
+            "OPTIONS" => Ok(Self::Options),
+            other => Err(Error::Config {
+                message: format!("unsupported HTTP method '{other}'"),
+            }),
+        }
+    }
+}
+
 /// Route handler identifier
 pub type HandlerId = usize;
 
@@ -72,6 +106,10 @@ pub struct Match<'a> {
     pub typed_params: HashMap<String, ParamValue>,
     /// Whether authentication is required (Phase 4)
     pub auth_required: bool,
+    /// Path pattern of the matched route (`/users/{id:int}`)
+    pub pattern: &'a str,
+    /// Limits set for the matched route
+    pub limits: RouteLimits,
 }
 
 impl<'a> Match<'a> {
@@ -102,6 +140,20 @@ impl<'a> Match<'a> {
     }
 }
 
+/// What to do when a typed path parameter fails to convert
+///
+/// `/users/abc` against `/users/{id:int}` is the canonical case.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParamMismatch {
+    /// Keep the raw value as `ParamValue::String` (backward compatible)
+    #[default]
+    Stringify,
+    /// Fail the match with `Error::InvalidParams` (answered with 422)
+    Reject,
+    /// Treat the route as not matching (answered with 404)
+    NotFound,
+}
+
 /// Per-method storage for routes
 #[derive(Clone)]
 struct MethodRoutes {
@@ -133,6 +185,8 @@ pub struct Router {
     method_routes: HashMap<Method, MethodRoutes>,
     /// Counter for generating handler IDs
     next_handler_id: HandlerId,
+    /// Policy for typed parameters that fail to convert
+    param_mismatch: ParamMismatch,
 }
 
 impl Default for Router {
@@ -148,9 +202,15 @@ impl Router {
         Self {
             method_routes: HashMap::new(),
             next_handler_id: 0,
+            param_mismatch: ParamMismatch::default(),
         }
     }
 
+    /// Choose how typed parameter conversion failures are handled
+    pub fn set_param_mismatch(&mut self, policy: ParamMismatch) {
+        self.param_mismatch = policy;
+    }
+
     /// Register a route with the given method and path pattern
     ///
     /// Supports typed parameters: `/users/{id:int}`, `/products/{price:float}`
@@ -166,8 +226,9 @@ impl Router {
     ///
     /// # Errors
     ///
-    /// Returns `Error::InvalidRoutePattern` if the pattern is malformed
-    /// Returns `Error::InvalidRoutePattern` if the pattern is malformed
+    /// Returns `Error::RouteConflict` if the pattern overlaps a route already
+    /// registered for `method` (e.g. `/users/{id}` and `/users/{name}`), and
+    /// `Error::InvalidRoutePattern` if the pattern is malformed
     pub fn add_route(
         &mut self,
         method: Method,
@@ -175,7 +236,6 @@ impl Router {
         auth_required: bool,
     ) -> Result<HandlerId> {
         let handler_id = self.next_handler_id;
-        self.next_handler_id += 1;
 
         let route_info = RouteInfo::new(handler_id, path, auth_required);
         let match_pattern = route_info.match_pattern.clone();
@@ -188,16 +248,80 @@ impl Router {
         method_routes
             .router
             .insert(&match_pattern, handler_id)
-            .map_err(|e| Error::InvalidRoutePattern {
-                pattern: path.to_string(),
-                reason: e.to_string(),
+            .map_err(|e| match e {
+                matchit::InsertError::Conflict { with } => Error::RouteConflict {
+                    method: method.to_string(),
+                    pattern: path.to_string(),
+                    existing: method_routes
+                        .routes
+                        .iter()
+                        .find(|r| r.match_pattern == with)
+                        .map_or(with, |r| r.path_pattern.clone()),
+                },
+                other => Error::InvalidRoutePattern {
+                    pattern: path.to_string(),
+                    reason: other.to_string(),
+                },
             })?;
 
         method_routes.routes.push(route_info);
+        self.next_handler_id += 1;
 
         Ok(handler_id)
     }
 
+    /// The registered pattern `path` duplicates for `method`, if any
+    ///
+    /// Patterns are duplicates when they differ at most in parameter names
+    /// and types (`/users/{id:int}` and `/users/{name}`): both would match
+    /// exactly the same requests.
+    #[must_use]
+    pub fn duplicate_of(&self, method: Method, path: &str) -> Option<&str> {
+        let shape = route_shape(path);
+        self.method_routes
+            .get(&method)?
+            .routes
+            .iter()
+            .find(|r| route_shape(&r.path_pattern) == shape)
+            .map(|r| r.path_pattern.as_str())
+    }
+
+    /// Unregister the route registered for `method` with exactly `path`
+    ///
+    /// Returns the removed route's handler ID, or `None` if there was no
+    /// such route. IDs are never reused.
+    pub fn remove_route(&mut self, method: Method, path: &str) -> Option<HandlerId> {
+        let method_routes = self.method_routes.get_mut(&method)?;
+        let index = method_routes
+            .routes
+            .iter()
+            .position(|r| r.path_pattern == path)?;
+        let route_info = method_routes.routes.remove(index);
+        method_routes.router.remove(route_info.match_pattern);
+        Some(route_info.handler_id)
+    }
+
+    /// Whether any method has a route matching the request `path`
+    #[must_use]
+    pub fn matches_any(&self, path: &str) -> bool {
+        self.method_routes
+            .values()
+            .any(|routes| routes.router.at(path).is_ok())
+    }
+
+    /// Methods with a route matching the request `path`, in `Method::ALL` order
+    #[must_use]
+    pub fn allowed_methods(&self, path: &str) -> Vec<Method> {
+        Method::ALL
+            .into_iter()
+            .filter(|method| {
+                self.method_routes
+                    .get(method)
+                    .is_some_and(|routes| routes.router.at(path).is_ok())
+            })
+            .collect()
+    }
+
     /// Match a request path against registered routes
     ///
     /// Returns both raw string params (backward compatible) and
@@ -214,7 +338,9 @@ impl Router {
     ///
     /// # Errors
     ///
-    /// Returns `Error::RouteNotFound` if no matching route exists
+    /// Returns `Error::RouteNotFound` if no matching route exists, and
+    /// `Error::InvalidParams` when a typed parameter fails to convert under
+    /// `ParamMismatch::Reject`
     pub fn match_route<'a>(&'a self, method: Method, path: &'a str) -> Result<Match<'a>> {
         let method_routes =
             self.method_routes
@@ -243,21 +369,92 @@ impl Router {
         let params: HashMap<&str, &str> = matched.params.iter().collect();
 
         let mut typed_params = HashMap::new();
+        let mut errors = ValidationErrors::new();
         for (name, value) in &params {
             let param_type = route_info.get_param_type(name);
-            let typed_value = convert_param(value, param_type)
-                .unwrap_or_else(|_| ParamValue::String((*value).to_string()));
+            let typed_value = match convert_param(value, param_type) {
+                Ok(typed) => typed,
+                Err(_) if self.param_mismatch == ParamMismatch::Stringify => {
+                    ParamValue::String((*value).to_string())
+                }
+                Err(_) => {
+                    errors.add(FieldError::invalid_type(*name, param_type.type_name()));
+                    continue;
+                }
+            };
             typed_params.insert((*name).to_string(), typed_value);
         }
 
+        if !errors.is_empty() {
+            return Err(match self.param_mismatch {
+                ParamMismatch::NotFound => Error::RouteNotFound {
+                    path: path.to_string(),
+                },
+                _ => Error::InvalidParams {
+                    path: path.to_string(),
+                    errors,
+                },
+            });
+        }
+
         Ok(Match {
             handler_id,
             params,
             typed_params,
             auth_required: route_info.auth_required,
+            pattern: &route_info.path_pattern,
+            limits: route_info.limits,
         })
     }
 
+    /// Route `path` matches for `method`, without converting parameters
+    #[must_use]
+    pub fn route_info(&self, method: Method, path: &str) -> Option<&RouteInfo> {
+        let method_routes = self.method_routes.get(&method)?;
+        let handler_id = *method_routes.router.at(path).ok()?.value;
+        method_routes
+            .routes
+            .iter()
+            .find(|r| r.handler_id == handler_id)
+    }
+
+    /// Set the limits of the route registered for `method` with exactly `path`
+    ///
+    /// Returns `false` if there is no such route.
+    pub fn set_limits(&mut self, method: Method, path: &str, limits: RouteLimits) -> bool {
+        let Some(route_info) = self
+            .method_routes
+            .get_mut(&method)
+            .and_then(|m| m.routes.iter_mut().find(|r| r.path_pattern == path))
+        else {
+            return false;
+        };
+        route_info.limits = limits;
+        true
+    }
+
+    /// Export the registered routes as a serializable table
+    #[must_use]
+    pub fn export_table(&self) -> RouteTable {
+        let routes = self
+            .method_routes
+            .iter()
+            .flat_map(|(method, method_routes)| {
+                method_routes.routes.iter().map(move |info| RouteEntry {
+                    method: method.to_string(),
+                    path: info.path_pattern.clone(),
+                    params: info
+                        .param_types
+                        .iter()
+                        .map(|(name, ty)| (name.clone(), ty.to_string()))
+                        .collect(),
+                    auth_required: info.auth_required,
+                })
+            })
+            .collect();
+        RouteTable::new(routes)
+    }
+
     /// Convenience method to add a GET route
     pub fn get(&mut self, path: &str) -> Result<HandlerId> {
         self.add_route(Method::Get, path, false)
@@ -279,6 +476,24 @@ impl Router {
     }
 }
 
+/// `path` with parameter names and types erased (`/users/{}`, `/files/{*}`)
+fn route_shape(path: &str) -> String {
+    let mut shape = String::with_capacity(path.len());
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        shape.push_str(&rest[..=start]);
+        if rest[start..].starts_with("{*") {
+            shape.push('*');
+        }
+        shape.push('}');
+        rest = rest[start..]
+            .find('}')
+            .map_or("", |end| &rest[start + end + 1..]);
+    }
+    shape.push_str(rest);
+    shape
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -382,6 +597,141 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_invalid_type_rejected() {
+        let mut router = Router::new();
+        router.set_param_mismatch(ParamMismatch::Reject);
+        router.get("/users/{id:int}").unwrap();
+
+        let Err(Error::InvalidParams { errors, .. }) =
+            router.match_route(Method::Get, "/users/abc")
+        else {
+            panic!("expected InvalidParams");
+        };
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.errors[0].field, "id");
+
+        let m = router.match_route(Method::Get, "/users/7").unwrap();
+        assert_eq!(m.get_int("id"), Some(7));
+    }
+
+    #[test]
+    fn test_invalid_type_not_found() {
+        let mut router = Router::new();
+        router.set_param_mismatch(ParamMismatch::NotFound);
+        router.get("/users/{id:int}").unwrap();
+
+        let result = router.match_route(Method::Get, "/users/abc");
+        assert!(matches!(result, Err(Error::RouteNotFound { .. })));
+    }
+
+    #[test]
+    fn test_catch_all_parameter() {
+        let mut router = Router::new();
+        router.get("/files/{*path}").unwrap();
+        router.get("/static/{rest:path}").unwrap();
+        router.get("/files/index").unwrap();
+
+        let m = router
+            .match_route(Method::Get, "/files/docs/2024/report.pdf")
+            .unwrap();
+        assert_eq!(m.params.get("path"), Some(&"docs/2024/report.pdf"));
+        assert_eq!(
+            m.typed_params.get("path"),
+            Some(&ParamValue::String("docs/2024/report.pdf".to_string()))
+        );
+
+        let m = router
+            .match_route(Method::Get, "/static/css/app.css")
+            .unwrap();
+        assert_eq!(m.params.get("rest"), Some(&"css/app.css"));
+
+        let m = router.match_route(Method::Get, "/files/index").unwrap();
+        assert!(m.params.is_empty());
+    }
+
+    #[test]
+    fn test_catch_all_must_be_last() {
+        let mut router = Router::new();
+        assert!(router.get("/files/{*path}/meta").is_err());
+    }
+
+    #[test]
+    fn test_conflicting_routes_name_existing_pattern() {
+        let mut router = Router::new();
+        router.get("/users/{id:int}").unwrap();
+
+        let err = router.get("/users/{name}").unwrap_err();
+        let Error::RouteConflict {
+            method, existing, ..
+        } = &err
+        else {
+            panic!("expected RouteConflict, got {err}");
+        };
+        assert_eq!(method, "GET");
+        assert_eq!(existing, "/users/{id:int}");
+
+        assert!(matches!(
+            router.get("/users/{id:int}"),
+            Err(Error::RouteConflict { .. })
+        ));
+        assert!(router.post("/users/{name}").is_ok());
+    }
+
+    #[test]
+    fn test_allowed_methods() {
+        let mut router = Router::new();
+        router.post("/users").unwrap();
+        router.get("/users").unwrap();
+        router.delete("/users/{id:int}").unwrap();
+
+        assert_eq!(
+            router.allowed_methods("/users"),
+            vec![Method::Get, Method::Post]
+        );
+        assert_eq!(router.allowed_methods("/users/7"), vec![Method::Delete]);
+        assert!(router.allowed_methods("/missing").is_empty());
+        assert!(router.matches_any("/users/7"));
+        assert!(!router.matches_any("/missing"));
+    }
+
+    #[test]
+    fn test_duplicate_of_ignores_param_names() {
+        let mut router = Router::new();
+        router.get("/users/{id:int}").unwrap();
+        router.get("/files/{*path}").unwrap();
+
+        assert_eq!(
+            router.duplicate_of(Method::Get, "/users/{name}"),
+            Some("/users/{id:int}")
+        );
+        assert_eq!(
+            router.duplicate_of(Method::Get, "/files/{*rest}"),
+            Some("/files/{*path}")
+        );
+        assert_eq!(router.duplicate_of(Method::Get, "/users/{id}/posts"), None);
+        assert_eq!(router.duplicate_of(Method::Post, "/users/{id}"), None);
+    }
+
+    #[test]
+    fn test_remove_route() {
+        let mut router = Router::new();
+        let id = router.get("/users/{id:int}").unwrap();
+        router.post("/users/{id:int}").unwrap();
+
+        assert_eq!(
+            router.remove_route(Method::Get, "/users/{id:int}"),
+            Some(id)
+        );
+        assert_eq!(router.remove_route(Method::Get, "/users/{id:int}"), None);
+        assert!(router.match_route(Method::Get, "/users/1").is_err());
+        assert!(router.match_route(Method::Post, "/users/1").is_ok());
+
+        let new_id = router.get("/users/{name}").unwrap();
+        assert_ne!(new_id, id);
+        assert!(router.match_route(Method::Get, "/users/bob").is_ok());
+    }
+
     #[test]
     fn test_route_not_found() {
         let router = Router::new();
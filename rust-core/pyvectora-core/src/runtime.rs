@@ -0,0 +1,204 @@
+//! # Runtime Configuration
+//!
+//! Settings for the multi-threaded Tokio runtime that drives the server,
+//! replacing a bare `Runtime::new()` with tunable worker and blocking pools.
+//! Operators can also tune it without code changes through `PYVECTORA_*`
+//! environment variables (`RuntimeConfig::from_env`).
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only describes and builds runtimes; who owns them is up to the caller
+//! - **O**: Unset fields keep Tokio's defaults
+
+use crate::error::{Error, Result};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::runtime::{Builder, Runtime};
+
+/// Number of async worker threads
+pub const WORKER_THREADS_ENV: &str = "PYVECTORA_WORKER_THREADS";
+/// Upper bound on the blocking thread pool
+pub const MAX_BLOCKING_THREADS_ENV: &str = "PYVECTORA_MAX_BLOCKING_THREADS";
+/// Thread name prefix
+pub const THREAD_NAME_ENV: &str = "PYVECTORA_THREAD_NAME";
+/// Thread stack size in bytes
+pub const THREAD_STACK_SIZE_ENV: &str = "PYVECTORA_THREAD_STACK_SIZE";
+
+/// Tunables for a multi-threaded Tokio runtime (`None` = Tokio default)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Async worker threads (default: one per CPU core)
+    pub worker_threads: Option<usize>,
+    /// Upper bound on the blocking thread pool (default: 512)
+    pub max_blocking_threads: Option<usize>,
+    /// Thread name prefix; threads are numbered `<name>-<n>`
+    pub thread_name: Option<String>,
+    /// Stack size of each thread in bytes (default: 2 MiB)
+    pub thread_stack_size: Option<usize>,
+}
+
+impl RuntimeConfig {
+    /// Create a config with every setting at its default
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Config from `PYVECTORA_WORKER_THREADS`, `PYVECTORA_MAX_BLOCKING_THREADS`,
+    /// `PYVECTORA_THREAD_NAME` and `PYVECTORA_THREAD_STACK_SIZE`
+    ///
+    /// Unset or empty variables keep their default.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` naming a variable that is not a number.
+    pub fn from_env() -> Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+        let number = |name: &str| {
+            var(name)
+                .map(|value| {
+                    value.trim().parse::<usize>().map_err(|_| Error::Config {
+                        message: format!("{name} must be a non-negative integer, got {value:?}"),
+                    })
+                })
+                .transpose()
+        };
+        let mut config = Self::new();
+        if let Some(threads) = number(WORKER_THREADS_ENV)? {
+            config = config.with_worker_threads(threads);
+        }
+        if let Some(threads) = number(MAX_BLOCKING_THREADS_ENV)? {
+            config = config.with_max_blocking_threads(threads);
+        }
+        if let Some(name) = var(THREAD_NAME_ENV) {
+            config = config.with_thread_name(name);
+        }
+        if let Some(bytes) = number(THREAD_STACK_SIZE_ENV)? {
+            config = config.with_thread_stack_size(bytes);
+        }
+        Ok(config)
+    }
+
+    /// Set the number of async worker threads (at least 1)
+    #[must_use]
+    pub fn with_worker_threads(mut self, threads: usize) -> Self {
+        self.worker_threads = Some(threads.max(1));
+        self
+    }
+
+    /// Cap the blocking thread pool (at least 1)
+    #[must_use]
+    pub fn with_max_blocking_threads(mut self, threads: usize) -> Self {
+        self.max_blocking_threads = Some(threads.max(1));
+        self
+    }
+
+    /// Name runtime threads `<name>-<n>`
+    #[must_use]
+    pub fn with_thread_name(mut self, name: impl Into<String>) -> Self {
+        self.thread_name = Some(name.into());
+        self
+    }
+
+    /// Set the stack size of runtime threads in bytes
+    #[must_use]
+    pub const fn with_thread_stack_size(mut self, bytes: usize) -> Self {
+        self.thread_stack_size = Some(bytes);
+        self
+    }
+
+    /// A runtime builder with these settings and all drivers enabled
+    #[must_use]
+    pub fn builder(&self) -> Builder {
+        let mut builder = Builder::new_multi_thread();
+        builder.enable_all();
+        if let Some(threads) = self.worker_threads {
+            builder.worker_threads(threads);
+        }
+        if let Some(threads) = self.max_blocking_threads {
+            builder.max_blocking_threads(threads);
+        }
+        if let Some(bytes) = self.thread_stack_size {
+            builder.thread_stack_size(bytes);
+        }
+        if let Some(name) = &self.thread_name {
+            let name = name.clone();
+            let counter = Arc::new(AtomicUsize::new(0));
+            builder.thread_name_fn(move || {
+                format!("{name}-{}", counter.fetch_add(1, Ordering::Relaxed))
+            });
+        }
+        builder
+    }
+
+    /// Build the runtime
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the runtime threads cannot be started.
+    pub fn build(&self) -> std::io::Result<Runtime> {
+        self.builder().build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_runtime_uses_configured_threads() {
+        let runtime = RuntimeConfig::new()
+            .with_worker_threads(2)
+            .with_max_blocking_threads(4)
+            .with_thread_name("pv-test")
+            .with_thread_stack_size(1024 * 1024)
+            .build()
+            .unwrap();
+        assert_eq!(runtime.metrics().num_workers(), 2);
+
+        let name = runtime
+            .block_on(async {
+                tokio::spawn(async { std::thread::current().name().map(String::from) }).await
+            })
+            .unwrap()
+            .unwrap();
+        assert!(name.starts_with("pv-test-"));
+    }
+
+    #[test]
+    fn test_config_from_env_vars() {
+        let vars = |name: &str| match name {
+            WORKER_THREADS_ENV => Some("3".to_string()),
+            MAX_BLOCKING_THREADS_ENV => Some(" 16 ".to_string()),
+            THREAD_NAME_ENV => Some("api".to_string()),
+            THREAD_STACK_SIZE_ENV => Some(String::new()),
+            _ => None,
+        };
+        let config = RuntimeConfig::from_vars(vars).unwrap();
+        assert_eq!(config.worker_threads, Some(3));
+        assert_eq!(config.max_blocking_threads, Some(16));
+        assert_eq!(config.thread_name.as_deref(), Some("api"));
+        assert_eq!(config.thread_stack_size, None);
+
+        assert_eq!(
+            RuntimeConfig::from_vars(|_| None).unwrap(),
+            RuntimeConfig::new()
+        );
+
+        let err = RuntimeConfig::from_vars(|name| {
+            (name == WORKER_THREADS_ENV).then(|| "many".to_string())
+        })
+        .unwrap_err();
+        assert!(err.to_string().contains(WORKER_THREADS_ENV));
+    }
+
+    #[test]
+    fn test_zero_threads_clamped() {
+        let config = RuntimeConfig::new().with_worker_threads(0);
+        assert_eq!(config.worker_threads, Some(1));
+    }
+}
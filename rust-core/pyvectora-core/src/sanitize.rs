@@ -0,0 +1,113 @@
+//! # Response Sanitization
+//!
+//! Last stage of every response, after the handler and all middleware
+//! (and for responses middleware never sees, such as 401s): headers that
+//! leak the stack are stripped and text types get an explicit charset, so
+//! the security posture does not depend on each handler doing it.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only rewrites response headers by policy
+//! - **O**: Each check is an independent switch on `ResponsePolicy`
+
+use crate::server::PyResponse;
+
+/// Checks applied to every response just before it is sent
+///
+/// All off by default; `strict()` turns every check on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ResponsePolicy {
+    /// Remove `Server` headers
+    pub strip_server: bool,
+    /// Add `charset=utf-8` to `text/*` content types that declare none
+    pub utf8_text: bool,
+    /// Remove `X-Powered-By` headers
+    pub forbid_powered_by: bool,
+}
+
+impl ResponsePolicy {
+    /// Every check on
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            strip_server: true,
+            utf8_text: true,
+            forbid_powered_by: true,
+        }
+    }
+
+    /// Whether no check is on
+    #[must_use]
+    pub const fn is_off(self) -> bool {
+        !self.strip_server && !self.utf8_text && !self.forbid_powered_by
+    }
+
+    /// Rewrite `response` to satisfy the policy
+    pub fn apply(self, response: &mut PyResponse) {
+        if self.is_off() {
+            return;
+        }
+        let forbidden = |name: &str| {
+            (self.strip_server && name.eq_ignore_ascii_case("server"))
+                || (self.forbid_powered_by && name.eq_ignore_ascii_case("x-powered-by"))
+        };
+        response.headers.retain(|name, _| !forbidden(name));
+        response.extra_headers.retain(|(name, _)| !forbidden(name));
+        if self.utf8_text && needs_charset(&response.content_type) {
+            response.content_type.push_str("; charset=utf-8");
+        }
+    }
+}
+
+/// `text/*` content type without a `charset` parameter
+fn needs_charset(content_type: &str) -> bool {
+    let mut parts = content_type.split(';');
+    let is_text = parts.next().is_some_and(|media| {
+        media
+            .trim()
+            .get(..5)
+            .is_some_and(|prefix| prefix.eq_ignore_ascii_case("text/"))
+    });
+    is_text
+        && !parts.any(|param| {
+            param
+                .split_once('=')
+                .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strict_policy() {
+        let mut response = PyResponse::text("hello")
+            .with_header("Server", "gunicorn/21")
+            .with_header("x-powered-by", "PHP/8")
+            .with_header("X-Frame-Options", "DENY");
+        response
+            .extra_headers
+            .push(("X-Powered-By".to_string(), "Express".to_string()));
+        ResponsePolicy::strict().apply(&mut response);
+
+        assert_eq!(response.headers.len(), 1);
+        assert!(response.headers.contains_key("X-Frame-Options"));
+        assert!(response.extra_headers.is_empty());
+        assert_eq!(response.content_type, "text/plain; charset=utf-8");
+    }
+
+    #[test]
+    fn test_charset_only_added_to_text_without_one() {
+        assert!(needs_charset("text/html"));
+        assert!(needs_charset("Text/CSV; header=present"));
+        assert!(!needs_charset("text/html; Charset=iso-8859-1"));
+        assert!(!needs_charset("application/json"));
+        assert!(!needs_charset("text"));
+
+        let mut response = PyResponse::text("x").with_header("Server", "pyvectora");
+        ResponsePolicy::default().apply(&mut response);
+        assert_eq!(response.content_type, "text/plain");
+        assert!(response.headers.contains_key("Server"));
+    }
+}
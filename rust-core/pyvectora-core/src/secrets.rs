@@ -0,0 +1,220 @@
+//! # Secrets
+//!
+//! Credentials kept out of configuration and application code. A secret
+//! is read from a file in the secrets directory (`/run/secrets/<name>`,
+//! as mounted by Docker and Kubernetes) or from a `PYVECTORA_SECRET_<NAME>`
+//! environment variable. Connection URLs reference secrets as
+//! `${secret:<name>}`; they are substituted in Rust when a pool connects,
+//! so the values never reach Python code, and are masked in error messages.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only looks up secrets and substitutes them into URLs
+//! - **D**: Consumers get a `ResolvedUrl`, unaware of where secrets live
+
+use crate::error::{Error, Result};
+use std::fmt::{self, Write as _};
+use std::path::PathBuf;
+
+/// Directory read when `PYVECTORA_SECRETS_DIR` is not set
+const DEFAULT_DIR: &str = "/run/secrets";
+
+/// Prefix of secret environment variables (`PYVECTORA_SECRET_DB_PASS`)
+const ENV_PREFIX: &str = "PYVECTORA_SECRET_";
+
+/// Where secrets are looked up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretStore {
+    dir: Option<PathBuf>,
+    env_prefix: Option<String>,
+}
+
+/// A URL with its secrets substituted
+///
+/// `Debug` and `Display` mask the secrets; `expose` gives the real URL.
+#[derive(Clone, PartialEq, Eq)]
+pub struct ResolvedUrl {
+    url: String,
+    secrets: Vec<String>,
+}
+
+impl SecretStore {
+    /// Store reading `PYVECTORA_SECRETS_DIR` (default `/run/secrets`) and
+    /// `PYVECTORA_SECRET_*` variables
+    #[must_use]
+    pub fn from_env() -> Self {
+        let dir = std::env::var_os("PYVECTORA_SECRETS_DIR")
+            .map_or_else(|| DEFAULT_DIR.into(), PathBuf::from);
+        Self {
+            dir: Some(dir),
+            env_prefix: Some(ENV_PREFIX.to_string()),
+        }
+    }
+
+    /// Store reading only files in `dir`
+    #[must_use]
+    pub fn dir(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: Some(dir.into()),
+            env_prefix: None,
+        }
+    }
+
+    /// Value of secret `name`: the file `<dir>/<name>` (without its trailing
+    /// newline), else the variable `<prefix><NAME>`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<String> {
+        if !is_valid_name(name) {
+            return None;
+        }
+        let from_file = self.dir.as_ref().and_then(|dir| {
+            std::fs::read_to_string(dir.join(name))
+                .ok()
+                .map(|value| value.trim_end_matches(['\r', '\n']).to_string())
+        });
+        from_file.or_else(|| {
+            let prefix = self.env_prefix.as_ref()?;
+            std::env::var(format!("{prefix}{}", name.to_ascii_uppercase())).ok()
+        })
+    }
+
+    /// Substitute every `${secret:<name>}` in `url`
+    ///
+    /// Values are percent-encoded, so passwords with `@`, `/` or `?` keep
+    /// the URL valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` naming (never showing) a secret that is not
+    /// set, or for an unterminated reference.
+    pub fn resolve_url(&self, url: &str) -> Result<ResolvedUrl> {
+        let mut resolved = String::with_capacity(url.len());
+        let mut secrets = Vec::new();
+        let mut rest = url;
+        while let Some(start) = rest.find("${secret:") {
+            resolved.push_str(&rest[..start]);
+            let reference = &rest[start + "${secret:".len()..];
+            let end = reference.find('}').ok_or_else(|| Error::Config {
+                message: "unterminated ${secret:...} reference in database URL".to_string(),
+            })?;
+            let name = &reference[..end];
+            let value = self.get(name).ok_or_else(|| Error::Config {
+                message: format!("secret {name:?} referenced by the database URL is not set"),
+            })?;
+            let encoded = percent_encode(&value);
+            resolved.push_str(&encoded);
+            secrets.push(value);
+            secrets.push(encoded);
+            rest = &reference[end + 1..];
+        }
+        resolved.push_str(rest);
+        secrets.retain(|secret| !secret.is_empty());
+        Ok(ResolvedUrl {
+            url: resolved,
+            secrets,
+        })
+    }
+}
+
+impl Default for SecretStore {
+    fn default() -> Self {
+        Self::from_env()
+    }
+}
+
+impl ResolvedUrl {
+    /// The URL with the real secret values
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.url
+    }
+
+    /// `text` with the secrets replaced by `***` (e.g. a driver error)
+    #[must_use]
+    pub fn redact(&self, text: &str) -> String {
+        self.secrets.iter().fold(text.to_string(), |text, secret| {
+            text.replace(secret.as_str(), "***")
+        })
+    }
+}
+
+impl fmt::Display for ResolvedUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.redact(&self.url))
+    }
+}
+
+impl fmt::Debug for ResolvedUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ResolvedUrl")
+            .field(&self.to_string())
+            .finish()
+    }
+}
+
+/// Secret names are file names: no separators or parent directories
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'_' | b'-' | b'.'))
+        && !name.starts_with('.')
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    value.bytes().fold(String::new(), |mut encoded, byte| {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+        encoded
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `${secret:<name>}`
+    fn reference(name: &str) -> String {
+        format!("${{secret:{name}}}")
+    }
+
+    #[test]
+    fn test_resolve_url_from_secret_files() {
+        let dir = std::env::temp_dir().join(format!("pv-secrets-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("db_pass"), "p@ss/word\n").unwrap();
+        let store = SecretStore::dir(&dir);
+
+        let template = format!(
+            "postgres://app:{}@db/app?sslmode=require",
+            reference("db_pass")
+        );
+        let url = store.resolve_url(&template).unwrap();
+        assert_eq!(
+            url.expose(),
+            "postgres://app:p%40ss%2Fword@db/app?sslmode=require"
+        );
+        assert_eq!(url.to_string(), "postgres://app:***@db/app?sslmode=require");
+        assert!(!format!("{url:?}").contains("word"));
+        assert_eq!(
+            url.redact("password authentication failed for p@ss/word"),
+            "password authentication failed for ***"
+        );
+
+        let missing = store
+            .resolve_url(&format!("postgres://app:{}@db", reference("other")))
+            .unwrap_err();
+        assert!(missing.to_string().contains("\"other\""));
+        assert!(store.resolve_url("postgres://${secret:db_pass@db").is_err());
+        assert!(store.get("../db_pass").is_none());
+        assert_eq!(
+            store.resolve_url("sqlite::memory:").unwrap().expose(),
+            "sqlite::memory:"
+        );
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -6,25 +6,61 @@
 //! ## Key Features
 //!
 //! - Async request handling with Tokio runtime
-//! - Graceful shutdown on SIGINT/SIGTERM
+//! - Graceful shutdown on configurable signals (SIGINT, SIGTERM, SIGHUP,
+//!   Windows console events)
 //! - Connection keep-alive support
+//! - Several listeners (TCP addresses, Unix sockets) sharing one router
+//! - systemd socket activation (`LISTEN_FDS`)
 //! - Zero-copy body streaming
+//! - Incremental (chunked) response bodies fed from a channel
+//! - WebSocket upgrades dispatched to dedicated handlers
 
-use crate::error::Result;
-use crate::router::{Match, Method, Router};
-use http_body_util::Full;
+use crate::banner::StartupBanner;
+use crate::cookie::SetCookie;
+use crate::error::{self, Error, RequestErrorKind, Result};
+use crate::events::{self, Event, EventKind};
+use crate::extension::ServerExtension;
+use crate::listener::{self, BindAddress, Listener, SocketOptions, UnixSocket};
+use crate::log_context;
+use crate::multipart::MultipartLimits;
+use crate::problem::{self, ErrorFormat, ProblemDetails};
+use crate::request::TargetLimits;
+use crate::route::RouteLimits;
+use crate::route_registry::RouteRegistry;
+use crate::router::{Match, Method, ParamMismatch, Router};
+use crate::sanitize::ResponsePolicy;
+use crate::signals::{self, ShutdownSignal};
+use crate::sse::{self, SseEvent};
+use crate::suggest::{self, RouteSuggestion};
+use crate::tls::{CertStore, TlsConfig};
+use crate::watchdog::MemoryLimits;
+use crate::websocket::{self, WsHandler};
+use futures_util::FutureExt;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Empty, Full};
 pub use hyper::body::Bytes;
+use hyper::body::{Body, Frame};
 use hyper::server::conn::http1;
 use hyper::service::service_fn;
 use hyper::{Request, Response, StatusCode};
-use hyper_util::rt::TokioIo;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use hyper_util::server::conn::auto;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use std::any::Any;
 use std::collections::HashMap;
+use std::convert::Infallible;
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
-use tracing::{error, info, warn};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::sync::{mpsc, OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinSet;
+use tracing::{error, info, warn, Instrument, Span};
 
 /// Authentication Configuration (JWT)
 #[derive(Clone)]
@@ -50,27 +86,122 @@ impl AuthConfig {
 pub struct ServerConfig {
     /// Address to bind the server to
     pub address: SocketAddr,
+    /// Unix domain socket served on instead of `address`
+    pub unix_socket: Option<UnixSocket>,
+    /// More addresses (TCP or Unix sockets) accepted on alongside `address`
+    pub listeners: Vec<BindAddress>,
+    /// Backlog, `TCP_NODELAY`, keepalive and buffer sizes of TCP listeners
+    pub socket_options: SocketOptions,
+    /// Serve on sockets passed by systemd (`LISTEN_FDS`) instead of binding
+    /// `address`, when there are any
+    pub socket_activation: bool,
     /// Enable keep-alive connections
     pub keep_alive: bool,
     /// Shutdown timeout for graceful shutdown (default: 30 seconds)
     pub shutdown_timeout: Duration,
     /// Max request body size in bytes
     pub max_body_size: usize,
+    /// Time a handler may take before 504 is sent (`None` = unlimited)
+    pub handler_timeout: Option<Duration>,
+    /// Part size, part count and spooling limits for form uploads
+    pub multipart_limits: MultipartLimits,
+    /// Request target length and query parameter count limits
+    pub target_limits: TargetLimits,
+    /// Max concurrently served connections; accepting pauses at the cap
+    pub max_connections: usize,
+    /// Certificate and key for HTTPS (plain HTTP when `None`)
+    pub tls: Option<TlsConfig>,
+    /// Offer HTTP/2 via ALPN on TLS connections
+    pub http2: bool,
+    /// Accept cleartext HTTP/2 with prior knowledge (h2c) on plain connections
+    pub h2c: bool,
+    /// RSS limits for refusing work and recycling (`None` = unwatched)
+    pub memory_limits: Option<MemoryLimits>,
+    /// Requests after which the server shuts down gracefully (`None` = never)
+    pub max_requests: Option<u64>,
+    /// Body format of framework-generated errors
+    pub error_format: ErrorFormat,
+    /// Log a `StartupBanner` (address, workers, middleware, routes) on `serve()`
+    pub startup_banner: bool,
+    /// Suggest nearby routes in 404 bodies and logs (development only)
+    pub debug: bool,
+    /// Signals that start a graceful shutdown
+    pub shutdown_signals: Vec<ShutdownSignal>,
+    /// Header checks applied to every response after all middleware
+    pub response_policy: ResponsePolicy,
 }
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             address: ([127, 0, 0, 1], 8000).into(),
+            unix_socket: None,
+            listeners: Vec::new(),
+            socket_options: SocketOptions::default(),
+            socket_activation: false,
             keep_alive: true,
             shutdown_timeout: Duration::from_secs(30),
             max_body_size: 1024 * 1024,
+            handler_timeout: None,
+            multipart_limits: MultipartLimits::default(),
+            target_limits: TargetLimits::default(),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+            tls: None,
+            http2: false,
+            h2c: false,
+            memory_limits: None,
+            max_requests: None,
+            error_format: ErrorFormat::default(),
+            startup_banner: false,
+            debug: false,
+            shutdown_signals: ShutdownSignal::defaults(),
+            response_policy: ResponsePolicy::default(),
         }
     }
 }
 
 pub use crate::request::PyRequest;
 
+/// Default cap on concurrently served connections
+pub const DEFAULT_MAX_CONNECTIONS: usize = 10_000;
+
+/// Body type sent over the wire (buffered or streaming)
+pub type ResponseBody = BoxBody<Bytes, Infallible>;
+
+/// Streaming response body fed by an mpsc channel
+///
+/// Each chunk received on the channel is flushed to the client as a
+/// separate data frame. The body ends when every sender is dropped.
+pub struct StreamingBody {
+    rx: mpsc::Receiver<Bytes>,
+}
+
+impl StreamingBody {
+    /// Wrap a chunk receiver as a response body
+    #[must_use]
+    pub const fn new(rx: mpsc::Receiver<Bytes>) -> Self {
+        Self { rx }
+    }
+}
+
+impl Body for StreamingBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<std::result::Result<Frame<Self::Data>, Self::Error>>> {
+        self.rx
+            .poll_recv(cx)
+            .map(|chunk| chunk.map(|bytes| Ok(Frame::data(bytes))))
+    }
+}
+
+fn full_body(body: impl Into<Bytes>) -> ResponseBody {
+    Full::new(body.into()).boxed()
+}
+
 /// HTTP Response wrapper for Python interop
 pub struct PyResponse {
     /// HTTP status code
@@ -81,6 +212,12 @@ pub struct PyResponse {
     pub content_type: String,
     /// Response headers
     pub headers: HashMap<String, String>,
+    /// Headers sent once per entry after `headers` (e.g. several `Set-Cookie`)
+    pub extra_headers: Vec<(String, String)>,
+    /// Incremental body chunks (takes precedence over `body` when set)
+    pub stream: Option<mpsc::Receiver<Bytes>>,
+    /// Problem document replacing the body under `ErrorFormat::Problem`
+    pub problem: Option<Box<ProblemDetails>>,
 }
 
 impl std::fmt::Debug for PyResponse {
@@ -90,6 +227,9 @@ impl std::fmt::Debug for PyResponse {
             .field("body", &self.body)
             .field("content_type", &self.content_type)
             .field("headers", &self.headers)
+            .field("extra_headers", &self.extra_headers)
+            .field("streaming", &self.stream.is_some())
+            .field("problem", &self.problem)
             .finish()
     }
 }
@@ -101,6 +241,9 @@ impl Default for PyResponse {
             body: String::new(),
             content_type: "application/json".to_string(),
             headers: HashMap::new(),
+            extra_headers: Vec::new(),
+            stream: None,
+            problem: None,
         }
     }
 }
@@ -114,6 +257,9 @@ impl PyResponse {
             body: body.into(),
             content_type: "application/json".to_string(),
             headers: HashMap::new(),
+            extra_headers: Vec::new(),
+            stream: None,
+            problem: None,
         }
     }
 
@@ -125,6 +271,89 @@ impl PyResponse {
             body: body.into(),
             content_type: "text/plain".to_string(),
             headers: HashMap::new(),
+            extra_headers: Vec::new(),
+            stream: None,
+            problem: None,
+        }
+    }
+
+    /// Create a streaming response whose body is read from `rx`
+    #[must_use]
+    pub fn streaming(rx: mpsc::Receiver<Bytes>, content_type: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            body: String::new(),
+            content_type: content_type.into(),
+            headers: HashMap::new(),
+            extra_headers: Vec::new(),
+            stream: Some(rx),
+            problem: None,
+        }
+    }
+
+    /// Create a response with a binary body sent as is
+    ///
+    /// `body` is not required to be UTF-8 (unlike `body` of text responses).
+    #[must_use]
+    pub fn binary(body: Bytes, content_type: impl Into<String>) -> Self {
+        let (tx, rx) = mpsc::channel(1);
+        let _ = tx.try_send(body);
+        Self::streaming(rx, content_type)
+    }
+
+    /// Create a Server-Sent Events response fed from `events`
+    ///
+    /// Sets the `text/event-stream` content type and disables caching and
+    /// proxy buffering so each event reaches the client immediately.
+    #[must_use]
+    pub fn sse(events: mpsc::Receiver<SseEvent>, keep_alive: Option<Duration>) -> Self {
+        Self::streaming(sse::event_stream(events, keep_alive), sse::CONTENT_TYPE)
+            .with_header("Cache-Control", "no-cache")
+            .with_header("X-Accel-Buffering", "no")
+    }
+
+    /// Standard reason phrase for `status` (e.g. "Not Found"), if any
+    #[must_use]
+    pub fn reason_phrase(status: u16) -> Option<&'static str> {
+        StatusCode::from_u16(status).ok()?.canonical_reason()
+    }
+
+    /// Attach the problem document sent under `ErrorFormat::Problem`
+    #[must_use]
+    pub fn with_problem(mut self, problem: ProblemDetails) -> Self {
+        self.problem = Some(Box::new(problem));
+        self
+    }
+
+    /// Replace the body with the attached problem document if `format` asks for it
+    ///
+    /// `instance` identifies the occurrence, usually the request path.
+    #[must_use]
+    pub fn with_error_format(mut self, format: ErrorFormat, instance: &str) -> Self {
+        if format == ErrorFormat::Problem {
+            if let Some(problem) = self.problem.take() {
+                self.body = problem.to_json(Some(instance));
+                self.content_type = problem::CONTENT_TYPE.to_string();
+                self.stream = None;
+            }
+        }
+        self
+    }
+
+    /// Whether the body is streamed incrementally
+    #[must_use]
+    pub const fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Drain a streaming body into `body` (used by the in-process test path)
+    pub async fn collect_stream(&mut self) {
+        if let Some(mut rx) = self.stream.take() {
+            let mut buf = Vec::new();
+            while let Some(chunk) = rx.recv().await {
+                buf.extend_from_slice(&chunk);
+            }
+            self.body.push_str(&String::from_utf8_lossy(&buf));
         }
     }
 
@@ -155,25 +384,51 @@ impl PyResponse {
         }
     }
 
+    /// Append a header, keeping earlier values of the same name
+    pub fn append_header(&mut self, key: &str, value: &str) {
+        self.extra_headers
+            .push((key.to_string(), value.to_string()));
+    }
+
+    /// Add a `Set-Cookie` header for `cookie`
+    pub fn set_cookie(&mut self, cookie: &SetCookie) {
+        self.append_header("Set-Cookie", &cookie.to_string());
+    }
+
+    /// Every value of a header (case-insensitive), `headers` first
+    #[must_use]
+    pub fn header_values(&self, key: &str) -> Vec<&str> {
+        self.headers
+            .iter()
+            .chain(self.extra_headers.iter().map(|(k, v)| (k, v)))
+            .filter(|(k, _)| k.eq_ignore_ascii_case(key))
+            .map(|(_, v)| v.as_str())
+            .collect()
+    }
+
     /// Convert to hyper Response
-    fn into_hyper(self) -> Response<Full<Bytes>> {
+    fn into_hyper(self) -> Response<ResponseBody> {
         let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
         let mut builder = Response::builder().status(status);
         builder = builder.header("Content-Type", &self.content_type);
-        for (k, v) in &self.headers {
+        let extra = self.extra_headers.iter().map(|(k, v)| (k, v));
+        for (k, v) in self.headers.iter().chain(extra) {
             if !k.eq_ignore_ascii_case("content-type") {
                 builder = builder.header(k.as_str(), v.as_str());
             }
         }
 
-        builder
-            .body(Full::new(Bytes::from(self.body)))
-            .unwrap_or_else(|_| {
-                Response::builder()
-                    .status(StatusCode::INTERNAL_SERVER_ERROR)
-                    .body(Full::new(Bytes::from("Internal Server Error")))
-                    .unwrap()
-            })
+        let body = match self.stream {
+            Some(rx) => StreamingBody::new(rx).boxed(),
+            None => full_body(self.body),
+        };
+
+        builder.body(body).unwrap_or_else(|_| {
+            Response::builder()
+                .status(StatusCode::INTERNAL_SERVER_ERROR)
+                .body(full_body("Internal Server Error"))
+                .unwrap()
+        })
     }
 }
 
@@ -187,13 +442,21 @@ pub type Handler = Arc<
         + Sync,
 >;
 
+/// Hook building the 400 response for a request that failed to parse
+///
+/// Receives the failure kind and a description; `None` falls back to the
+/// default plain-text "Bad Request".
+pub type BadRequestHook = Arc<dyn Fn(RequestErrorKind, &str) -> Option<PyResponse> + Send + Sync>;
+
 /// High-performance HTTP server
 pub struct Server {
     config: ServerConfig,
-    router: Router,
-    handlers: Vec<Handler>,
+    routes: RouteRegistry,
+    ws_routes: WebSocketRoutes,
     auth_config: Option<Arc<AuthConfig>>,
     middleware: crate::middleware::MiddlewareChain,
+    bad_request_hook: Option<BadRequestHook>,
+    extensions: Vec<Arc<dyn ServerExtension>>,
 }
 
 impl Server {
@@ -201,14 +464,16 @@ impl Server {
     pub fn new(secret: &str) -> Self {
         Self {
             config: ServerConfig::default(),
-            router: Router::new(),
-            handlers: Vec::new(),
+            routes: RouteRegistry::new(),
+            ws_routes: WebSocketRoutes::default(),
             auth_config: if secret.is_empty() {
                 None
             } else {
                 Some(Arc::new(AuthConfig::new(secret)))
             },
             middleware: crate::middleware::MiddlewareChain::new(),
+            bad_request_hook: None,
+            extensions: Vec::new(),
         }
     }
 
@@ -218,16 +483,202 @@ impl Server {
         self
     }
 
+    /// Tune the TCP listeners and the connections they accept
+    pub fn set_socket_options(&mut self, options: SocketOptions) {
+        self.config.socket_options = options;
+    }
+
+    /// Serve on the sockets systemd passes when socket-activated, binding
+    /// the configured address only when started without any
+    pub fn enable_socket_activation(&mut self) {
+        self.config.socket_activation = true;
+    }
+
+    /// Serve on a Unix domain socket instead of the TCP address
+    pub fn set_unix_socket(&mut self, socket: UnixSocket) {
+        self.config.unix_socket = Some(socket);
+    }
+
+    /// Also accept connections on `address`, with the same routes
+    pub fn add_listener(&mut self, address: BindAddress) {
+        self.config.listeners.push(address);
+    }
+
+    /// Header checks applied to every response after all middleware
+    pub fn set_response_policy(&mut self, policy: ResponsePolicy) {
+        self.config.response_policy = policy;
+    }
+
     /// Set max request body size
     pub fn set_max_body_size(&mut self, bytes: usize) {
         self.config.max_body_size = bytes;
     }
 
+    /// Set the request target length (414) and query parameter count (400) limits
+    pub fn set_target_limits(&mut self, limits: TargetLimits) {
+        self.config.target_limits = limits;
+    }
+
+    /// Send 504 when a handler takes longer than `timeout` (`None` = unlimited)
+    ///
+    /// Routes may set their own with `set_route_limits`. On expiry the
+    /// handler future is dropped, which cancels a Python handler's task and
+    /// its request context, so a hung handler cannot hold its connection.
+    pub fn set_handler_timeout(&mut self, timeout: Option<Duration>) {
+        self.config.handler_timeout = timeout;
+    }
+
+    /// Set the limits `request.form()` / `request.files()` parse uploads with
+    ///
+    /// Uploads are also bounded by the body limit, so raise that as well
+    /// to accept files above 1 MiB.
+    pub fn set_multipart_limits(&mut self, limits: MultipartLimits) {
+        self.config.multipart_limits = limits;
+    }
+
+    /// Cap concurrently served connections (at least 1)
+    ///
+    /// At the cap new connections wait in the listen backlog until a slot
+    /// frees up.
+    pub fn set_max_connections(&mut self, max: usize) {
+        self.config.max_connections = max.max(1);
+    }
+
+    /// Watch the process RSS against `limits`
+    ///
+    /// Above the soft limit requests get 503 until memory drops; above the
+    /// hard limit the server shuts down gracefully to be replaced.
+    pub fn set_memory_limits(&mut self, limits: MemoryLimits) {
+        self.config.memory_limits = Some(limits);
+    }
+
+    /// Shut down gracefully after serving about `max` requests
+    ///
+    /// Lets a process manager or supervisor recycle leaky workers.
+    pub fn set_max_requests(&mut self, max: u64) {
+        self.config.max_requests = Some(max.max(1));
+    }
+
+    /// Signals that start a graceful shutdown
+    ///
+    /// Defaults to `SIGINT`, `SIGTERM` and `SIGHUP` on Unix and Ctrl+C,
+    /// Ctrl+Break and console close on Windows. Signals the platform does
+    /// not have are ignored; an empty set disables signal handling.
+    pub fn set_shutdown_signals(&mut self, signals: Vec<ShutdownSignal>) {
+        self.config.shutdown_signals = signals;
+    }
+
+    /// Choose how typed path parameters that fail to convert are answered
+    ///
+    /// See [`ParamMismatch`]; the default keeps the raw string value.
+    pub fn set_param_mismatch(&mut self, policy: ParamMismatch) {
+        self.routes.set_param_mismatch(policy);
+    }
+
+    /// Choose the body format of framework-generated errors
+    ///
+    /// See [`ErrorFormat`]; the default keeps `{"error": ...}` JSON bodies.
+    pub fn set_error_format(&mut self, format: ErrorFormat) {
+        self.config.error_format = format;
+    }
+
+    /// Suggest the nearest registered routes when a request matches none
+    ///
+    /// Suggestions go to the 404 body and the log. For development: every
+    /// miss is compared against the whole route table.
+    pub fn set_debug(&mut self, debug: bool) {
+        self.config.debug = debug;
+    }
+
+    /// Whether the server runs in debug mode
+    #[must_use]
+    pub const fn is_debug(&self) -> bool {
+        self.config.debug
+    }
+
+    /// Log the address, workers, middleware and route table when serving starts
+    pub fn set_startup_banner(&mut self, enabled: bool) {
+        self.config.startup_banner = enabled;
+    }
+
+    /// Summary of what `serve()` serves, as logged by the startup banner
+    #[must_use]
+    pub fn startup_banner(&self, scheme: &'static str) -> StartupBanner {
+        StartupBanner {
+            scheme,
+            address: self.config.address,
+            workers: tokio::runtime::Handle::try_current()
+                .map_or(0, |handle| handle.metrics().num_workers()),
+            middleware: self.middleware.names(),
+            routes: self.routes.snapshot().router.export_table(),
+            websocket_routes: self
+                .ws_routes
+                .router
+                .export_table()
+                .routes
+                .into_iter()
+                .map(|route| route.path)
+                .collect(),
+        }
+    }
+
     /// Enable JWT authentication
     pub fn enable_auth(&mut self, secret: &str) {
         self.auth_config = Some(Arc::new(AuthConfig::new(secret)));
     }
 
+    /// Enable HTTP/2 alongside HTTP/1.1
+    ///
+    /// On TLS listeners `h2` is negotiated via ALPN. With `h2c`, plain
+    /// listeners also accept HTTP/2 prior-knowledge connections.
+    pub fn enable_http2(&mut self, h2c: bool) {
+        self.config.http2 = true;
+        self.config.h2c = h2c;
+    }
+
+    /// Serve HTTPS using the given PEM certificate chain and private key
+    ///
+    /// Files are read when `serve()` starts, so a bad path fails startup
+    /// instead of individual connections.
+    pub fn enable_tls(&mut self, cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) {
+        self.config.tls = Some(TlsConfig::new(cert_path, key_path));
+    }
+
+    /// Serve HTTPS with a prepared configuration (SNI certificates, reloading)
+    pub fn set_tls(&mut self, config: TlsConfig) {
+        self.config.tls = Some(config);
+    }
+
+    /// Serve a different certificate to clients requesting `hostname` (SNI)
+    ///
+    /// `hostname` may be a `*.example.com` wildcard. Has no effect unless
+    /// TLS is enabled.
+    pub fn add_tls_certificate(
+        &mut self,
+        hostname: &str,
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) {
+        if let Some(tls) = self.config.tls.take() {
+            self.config.tls = Some(tls.with_sni_cert(hostname, cert_path, key_path));
+        }
+    }
+
+    /// Check the TLS certificate and key for changes every `interval`
+    ///
+    /// Renewed certificates are swapped into the acceptor without a restart.
+    /// Has no effect unless TLS is enabled.
+    pub fn set_tls_reload_interval(&mut self, interval: Duration) {
+        if let Some(tls) = self.config.tls.take() {
+            self.config.tls = Some(tls.with_reload_interval(interval));
+        }
+    }
+
+    /// Customize the 400 response sent when a request cannot be parsed
+    pub fn set_bad_request_hook(&mut self, hook: BadRequestHook) {
+        self.bad_request_hook = Some(hook);
+    }
+
     /// Add a middleware to the chain
     pub fn add_middleware<M: crate::middleware::Middleware + 'static>(&mut self, middleware: M) {
         self.middleware.add(middleware);
@@ -241,120 +692,250 @@ impl Server {
         handler: Handler,
         auth_required: bool,
     ) -> Result<()> {
-        self.router.add_route(method, path, auth_required)?;
-        self.handlers.push(handler);
+        self.routes.add_route(method, path, handler, auth_required)
+    }
+
+    /// Add a route whose handler reads the request body as a stream
+    ///
+    /// See `RouteRegistry::add_streaming_route`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the route conflicts or has an invalid pattern.
+    pub fn add_streaming_route(
+        &mut self,
+        method: Method,
+        path: &str,
+        handler: Handler,
+        auth_required: bool,
+    ) -> Result<()> {
+        self.routes
+            .add_streaming_route(method, path, handler, auth_required)
+    }
+
+    /// Override the body size limit and handler timeout of one route
+    ///
+    /// Returns `false` if no route is registered for `method` with exactly
+    /// `path`.
+    #[must_use]
+    pub fn set_route_limits(&mut self, method: Method, path: &str, limits: RouteLimits) -> bool {
+        self.routes.set_limits(method, path, limits)
+    }
+
+    /// Handle for adding and removing routes while the server is running
+    ///
+    /// Changes apply to requests arriving after them.
+    #[must_use]
+    pub fn routes(&self) -> RouteRegistry {
+        self.routes.clone()
+    }
+
+    /// Merge a route group into the router
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a group route conflicts or has an invalid pattern.
+    pub fn mount(&mut self, group: crate::group::RouteGroup) -> Result<()> {
+        for route in group.into_routes() {
+            self.add_route(
+                route.method,
+                &route.path,
+                route.handler,
+                route.auth_required,
+            )?;
+        }
         Ok(())
     }
 
-    /// Start the server with graceful shutdown
-    pub async fn serve(&self) -> Result<()> {
-        let addr = self.config.address;
+    /// Add a WebSocket route and its handler
+    ///
+    /// Upgrade requests to `path` are handed to `handler`; plain HTTP
+    /// requests to the same path still go through the regular router.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` conflicts with another WebSocket route.
+    pub fn add_websocket_route(&mut self, path: &str, handler: WsHandler) -> Result<()> {
+        self.ws_routes.router.add_route(Method::Get, path, false)?;
+        self.ws_routes.handlers.push(handler);
+        Ok(())
+    }
 
-        let socket = tokio::net::TcpSocket::new_v4()?;
-        socket.set_reuseaddr(true)?;
-        #[cfg(not(windows))]
+    /// Plug in a compiled extension
+    ///
+    /// Runs its `configure` hook now; `on_start` and `on_shutdown` run
+    /// around `serve`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` if an extension with the same name is
+    /// already registered, or the error of its `configure` hook.
+    pub fn register_extension(&mut self, extension: Arc<dyn ServerExtension>) -> Result<()> {
+        if self
+            .extensions
+            .iter()
+            .any(|registered| registered.name() == extension.name())
         {
-            socket.set_reuseport(true)?;
+            return Err(Error::Config {
+                message: format!("extension '{}' is already registered", extension.name()),
+            });
         }
-        socket.bind(addr)?;
+        extension.configure(self)?;
+        self.extensions.push(extension);
+        Ok(())
+    }
+
+    /// Bind the main address (or Unix socket) and every extra listener
+    ///
+    /// Sockets inherited through socket activation replace the main address.
+    fn bind_listeners(&self) -> Result<(Vec<BindAddress>, Vec<Listener>)> {
+        let options = self.config.socket_options;
+        let mut bound = if self.config.socket_activation {
+            listener::inherited(options)?
+        } else {
+            Vec::new()
+        };
+        if !bound.is_empty() {
+            info!("Inherited {} socket(s) from systemd", bound.len());
+        } else if let Some(socket) = &self.config.unix_socket {
+            bound.push((BindAddress::Unix(socket.path.clone()), socket.bind()?));
+        } else {
+            let address = BindAddress::from(self.config.address);
+            let listener = Listener::bind(&address, options)?;
+            bound.push((address, listener));
+        }
+        for address in &self.config.listeners {
+            bound.push((address.clone(), Listener::bind(address, options)?));
+        }
+        Ok(bound.into_iter().unzip())
+    }
+
+    /// Start the server with graceful shutdown
+    pub async fn serve(&self) -> Result<()> {
+        error::capture_panic_backtraces();
 
-        let listener = socket.listen(1024)?;
+        let (addresses, listeners) = self.bind_listeners()?;
 
-        info!("Server listening on http://{}", addr);
+        let cert_store = self
+            .config
+            .tls
+            .clone()
+            .map(CertStore::load)
+            .transpose()?
+            .map(Arc::new);
+        let tls_acceptor = cert_store
+            .as_ref()
+            .map(|store| store.acceptor(self.config.http2))
+            .transpose()?;
+        let cert_watcher = cert_store.and_then(|store| {
+            let interval = store.config().reload_interval?;
+            Some(tokio::spawn(store.watch(interval)))
+        });
+        let scheme = if tls_acceptor.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+        for address in &addresses {
+            info!("Server listening on {}://{}", scheme, address);
+        }
+        if self.config.startup_banner {
+            let banner = self.startup_banner(scheme);
+            info!(
+                workers = banner.workers,
+                middleware = banner.middleware.len(),
+                routes = banner.routes.routes.len() + banner.websocket_routes.len(),
+                "{banner}"
+            );
+        }
+        for extension in &self.extensions {
+            extension.on_start(&self.routes);
+        }
 
-        let router = Arc::new(self.router.clone());
-        let handlers = Arc::new(self.handlers.clone());
-        let auth_config = self.auth_config.clone();
-        let middleware = Arc::new(self.middleware.clone());
-        let active = Arc::new(AtomicUsize::new(0));
-        let max_body_size = self.config.max_body_size;
+        let ctx = Arc::new(self.connection_context());
+        let limiter = Arc::new(Semaphore::new(self.config.max_connections));
+        let mut connections = JoinSet::new();
+        let shutdown = shutdown_signal(&self.config.shutdown_signals, ctx.requests.clone());
+        tokio::pin!(shutdown);
+        let memory_exhausted =
+            memory_exhausted(self.config.memory_limits.clone(), ctx.overloaded.clone());
+        tokio::pin!(memory_exhausted);
 
         loop {
+            while let Some(finished) = connections.try_join_next() {
+                log_connection_panic(finished);
+            }
+            if let Some(max) = self.config.max_requests {
+                if ctx.requests.load(Ordering::Relaxed) >= max {
+                    info!("Served {} requests, recycling...", max);
+                    break;
+                }
+            }
             tokio::select! {
-                accept_result = listener.accept() => {
-                    let (stream, remote_addr) = accept_result?;
-                    let io = TokioIo::new(stream);
-
-                    let router = router.clone();
-                    let handlers = handlers.clone();
-                    let auth_config = auth_config.clone();
-                    let middleware = middleware.clone();
-                    let active = active.clone();
-
-                    tokio::task::spawn(async move {
-                        active.fetch_add(1, Ordering::Relaxed);
-
-                        if let Err(err) = http1::Builder::new()
-                            .serve_connection(io, service_fn(move |req| {
-                                    let router = router.clone();
-                                    let handlers = handlers.clone();
-                                    let auth_config = auth_config.clone();
-                                    let middleware = middleware.clone();
-                                 async move {
-                                     let method = req.method().clone();
-                                     let path = req.uri().path().to_string();
-                                     let version = format!("{:?}", req.version()); // e.g., HTTP/1.1
-
-                                     let result = handle_request(
-                                         req,
-                                         &router,
-                                         &handlers,
-                                         auth_config.as_deref(),
-                                         &middleware,
-                                         remote_addr,
-                                         max_body_size
-                                     ).await;
-
-                                     match &result {
-                                         Ok(resp) => {
-                                             let status_code = resp.status();
-                                             info!("    {} - \"{} {} {}\" {}",
-                                                 remote_addr,
-                                                 method,
-                                                 path,
-                                                 version,
-                                                 status_code
-                                             );
-                                         },
-                                         Err(_) => {
-                                             error!("    {} - \"{} {} {}\" ERROR",
-                                                 remote_addr,
-                                                 method,
-                                                 path,
-                                                 version
-                                             );
-                                         }
-                                     }
-                                     result
-                                 }
-                            }))
-                            .await
-                        {
-                            error!("Error serving connection: {:?}", err);
+                accept_result = accept_within_limit(&listeners, &limiter) => {
+                    let (stream, remote_addr, permit) = accept_result?;
+                    let ctx = ctx.clone();
+                    let tls_acceptor = tls_acceptor.clone();
+
+                    connections.spawn(async move {
+                        let _permit = permit;
+                        match tls_acceptor {
+                            Some(acceptor) => match acceptor.accept(stream).await {
+                                Ok(tls_stream) => {
+                                    serve_connection(tls_stream, remote_addr, ctx, true).await;
+                                }
+                                Err(err) => warn!("TLS handshake with {} failed: {}", Peer(remote_addr), err),
+                            },
+                            None => serve_connection(stream, remote_addr, ctx, false).await,
                         }
-                        active.fetch_sub(1, Ordering::Relaxed);
                     });
                 }
-                _ = shutdown_signal() => {
+                () = &mut shutdown => {
                     info!("Shutdown signal received, stopping server...");
                     break;
                 }
-            }
-        }
-        let timeout = self.config.shutdown_timeout;
-        let drain = async {
-            loop {
-                if active.load(Ordering::Relaxed) == 0 {
+                () = &mut memory_exhausted => {
+                    info!("Memory limit reached, recycling...");
                     break;
                 }
-                tokio::time::sleep(Duration::from_millis(50)).await;
             }
-        };
-        let _ = tokio::time::timeout(timeout, drain).await;
+        }
+        drain_connections(&mut connections, self.config.shutdown_timeout).await;
+        for extension in self.extensions.iter().rev() {
+            extension.on_shutdown();
+        }
+        if let Some(watcher) = cert_watcher {
+            watcher.abort();
+        }
         Ok(())
     }
 
+    /// State shared by the connections of one `serve()` run
+    fn connection_context(&self) -> ConnectionContext {
+        ConnectionContext {
+            routes: self.routes.clone(),
+            ws_routes: self.ws_routes.clone(),
+            auth_config: self.auth_config.clone(),
+            middleware: self.middleware.clone(),
+            max_body_size: self.config.max_body_size,
+            handler_timeout: self.config.handler_timeout,
+            target_limits: self.config.target_limits,
+            multipart_limits: Arc::new(self.config.multipart_limits.clone()),
+            bad_request_hook: self.bad_request_hook.clone(),
+            error_format: self.config.error_format,
+            response_policy: self.config.response_policy,
+            debug: self.config.debug,
+            http2: self.config.http2,
+            h2c: self.config.h2c,
+            requests: Arc::new(AtomicU64::new(0)),
+            overloaded: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
     /// Execute a test request directly without network stack
+    ///
+    /// Requests over the body or target limits are answered 413, 414 or 400
+    /// as on a connection.
     pub async fn test_request(
         &self,
         method: Method,
@@ -362,136 +943,718 @@ impl Server {
         headers: std::collections::HashMap<String, String>,
         body: Option<Bytes>,
     ) -> PyResponse {
-        if let Some(b) = body.as_ref() {
-            if b.len() > self.config.max_body_size {
-                return PyResponse::text(r#"{"error": "Payload Too Large"}"#)
+        let target = path.clone();
+        let error_format = self.config.error_format;
+        match self.try_test_request(method, path, headers, body).await {
+            Ok(response) => response,
+            Err(Error::PayloadTooLarge { limit, .. }) => {
+                PyResponse::text(r#"{"error": "Payload Too Large"}"#)
                     .with_status(413)
-                    .with_header("Content-Type", "application/json");
+                    .with_header("Content-Type", "application/json")
+                    .with_problem(payload_too_large(limit))
+                    .with_error_format(error_format, &target)
+            }
+            Err(e) => parse_error_response(&e, self.bad_request_hook.as_ref())
+                .with_error_format(error_format, &target),
+        }
+    }
+
+    /// Execute a test request, returning a limit it exceeds as an error
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::PayloadTooLarge` for a body over the route's limit,
+    /// and `Error::UriTooLong` or `Error::BadRequest` (`TooManyQueryParams`)
+    /// for a target over the target limits; the handler is not run.
+    pub async fn try_test_request(
+        &self,
+        method: Method,
+        path: String,
+        headers: std::collections::HashMap<String, String>,
+        body: Option<Bytes>,
+    ) -> Result<PyResponse> {
+        let (route_path, query) = path
+            .split_once('?')
+            .map_or((path.as_str(), None), |(p, q)| (p, Some(q)));
+        self.config.target_limits.check(route_path, query)?;
+        let routes = self.routes.snapshot();
+        let max_body_size = routes
+            .limits(method, route_path)
+            .max_body_size
+            .unwrap_or(self.config.max_body_size);
+        if let Some(b) = body.as_ref() {
+            if b.len() > max_body_size {
+                return Err(Error::PayloadTooLarge {
+                    limit: max_body_size,
+                    actual: b.len(),
+                });
             }
         }
         let mut req = PyRequest::new(method, path, headers, body);
-        req.set_header("x-client-ip", "test");
+        req.multipart_limits = Arc::new(self.config.multipart_limits.clone());
 
-        process_request(
+        let settings = RoutingSettings {
+            error_format: self.config.error_format,
+            debug: self.config.debug,
+            handler_timeout: self.config.handler_timeout,
+            response_policy: self.config.response_policy,
+        };
+        let mut response = process_request(
             &mut req,
-            &self.router,
-            &self.handlers,
+            &routes.router,
+            &routes.handlers,
             self.auth_config.as_deref(),
             &self.middleware,
+            settings,
         )
-        .await
+        .await;
+        response.collect_stream().await;
+        Ok(response)
     }
 }
 
-async fn shutdown_signal() {
-    tokio::signal::ctrl_c()
+/// Wait for a connection slot, then accept the next connection
+async fn accept_within_limit(
+    listeners: &[Listener],
+    limiter: &Arc<Semaphore>,
+) -> std::io::Result<(
+    listener::Connection,
+    Option<SocketAddr>,
+    OwnedSemaphorePermit,
+)> {
+    let permit = limiter
+        .clone()
+        .acquire_owned()
         .await
-        .expect("Failed to install CTRL+C signal handler");
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+    let (stream, remote_addr) = listener::accept_any(listeners).await?;
+    Ok((stream, remote_addr, permit))
+}
+
+/// Let open connections finish within `timeout`, then abort the rest
+async fn drain_connections(connections: &mut JoinSet<()>, timeout: Duration) {
+    let drain = async {
+        while let Some(finished) = connections.join_next().await {
+            log_connection_panic(finished);
+        }
+    };
+    if tokio::time::timeout(timeout, drain).await.is_err() {
+        warn!(
+            "Aborting {} connections still open after {:?}",
+            connections.len(),
+            timeout
+        );
+        connections.abort_all();
+        while connections.join_next().await.is_some() {}
+    }
+}
+
+fn log_connection_panic(finished: std::result::Result<(), tokio::task::JoinError>) {
+    if let Err(err) = finished {
+        if err.is_panic() {
+            error!("Connection task panicked: {}", err);
+        }
+    }
+}
+
+/// Resolve when the server should stop accepting (one of `signals`)
+///
+/// Under a supervisor this also resolves when the supervisor retires the
+/// worker; `requests` is reported to it in the meantime.
+async fn shutdown_signal(signals: &[ShutdownSignal], requests: Arc<AtomicU64>) {
+    let received = async {
+        let signal = signals::wait_for(signals).await;
+        info!("Received {signal}");
+    };
+    #[cfg(unix)]
+    if let Some(link) = crate::supervisor::WorkerLink::from_env().await {
+        tokio::select! {
+            () = received => {}
+            () = link.run(requests, crate::supervisor::HEARTBEAT_INTERVAL) => {
+                info!("Supervisor requested shutdown");
+            }
+        }
+        return;
+    }
+    #[cfg(not(unix))]
+    drop(requests);
+    received.await;
+}
+
+/// Resolve once RSS crosses the hard limit (never without limits)
+async fn memory_exhausted(limits: Option<MemoryLimits>, overloaded: Arc<AtomicBool>) {
+    match limits {
+        Some(limits) => crate::watchdog::watch(limits, overloaded).await,
+        None => std::future::pending().await,
+    }
 }
 
 /// Core request processing logic (network agnostic)
+///
+/// Assigns the request id, reports `request_started` and
+/// `request_finished` on the event bus and runs `route_request` in the
+/// request's log context span.
 async fn process_request(
     req: &mut PyRequest,
     router: &Router,
     handlers: &[Handler],
     auth_config: Option<&AuthConfig>,
     middleware: &crate::middleware::MiddlewareChain,
+    settings: RoutingSettings,
 ) -> PyResponse {
     if req.header("x-request-id").is_none() {
         let request_id = generate_request_id();
         req.set_header("x-request-id", &request_id);
     }
 
-    let matched = match router.match_route(req.method, &req.path) {
-        Ok(m) => m,
+    if events::is_active(EventKind::RequestStarted) {
+        events::emit(&Event::RequestStarted {
+            request_id: request_id_of(req),
+            method: req.method.to_string(),
+            path: req.path.clone(),
+        });
+    }
+    let started = Instant::now();
+    let span = log_context::request_span(req);
+    let mut response = route_request(req, router, handlers, auth_config, middleware, settings)
+        .instrument(span)
+        .await;
+    settings.response_policy.apply(&mut response);
+    if events::is_active(EventKind::RequestFinished) {
+        events::emit(&Event::RequestFinished {
+            request_id: request_id_of(req),
+            method: req.method.to_string(),
+            path: req.path.clone(),
+            route: req.route.as_deref().map(String::from),
+            status: response.status,
+            duration: started.elapsed(),
+        });
+    }
+    response
+}
+
+fn request_id_of(req: &PyRequest) -> String {
+    req.header("x-request-id").unwrap_or_default().to_string()
+}
+
+/// Server-wide settings applied while routing a request
+#[derive(Debug, Clone, Copy)]
+struct RoutingSettings {
+    /// Body format of framework-generated errors
+    error_format: ErrorFormat,
+    /// Suggest nearby routes in 404 bodies
+    debug: bool,
+    /// Timeout of handlers whose route sets none
+    handler_timeout: Option<Duration>,
+    /// Header checks applied to the final response
+    response_policy: ResponsePolicy,
+}
+
+/// Route, authenticate and run the handler for `req`
+async fn route_request(
+    req: &mut PyRequest,
+    router: &Router,
+    handlers: &[Handler],
+    auth_config: Option<&AuthConfig>,
+    middleware: &crate::middleware::MiddlewareChain,
+    settings: RoutingSettings,
+) -> PyResponse {
+    let RoutingSettings {
+        error_format,
+        debug,
+        handler_timeout,
+        ..
+    } = settings;
+    let (method, path) = (req.method, req.path.clone());
+    let target = resolve(req, router, &path, auth_config, debug);
+    if let Target::Rejected(response) = target {
+        return response.with_error_format(error_format, &req.path);
+    }
+
+    let outcome = middleware.run_before(req);
+    let rewritten;
+    let target = if matches!(outcome, crate::middleware::MiddlewareResult::Continue)
+        && (req.method != method || req.path != path)
+    {
+        // Middleware rewrote the request: route it again (without
+        // running the middleware twice)
+        rewritten = req.path.clone();
+        req.route = None;
+        req.typed_params.clear();
+        match resolve(req, router, &rewritten, auth_config, debug) {
+            Target::Rejected(response) => {
+                return response.with_error_format(error_format, &req.path)
+            }
+            target => target,
+        }
+    } else {
+        target
+    };
+
+    let mut response = match (outcome, target) {
+        (crate::middleware::MiddlewareResult::Respond(resp), _) => resp,
+        (
+            crate::middleware::MiddlewareResult::Continue,
+            Target::Unmatched(fallback) | Target::Rejected(fallback),
+        ) => fallback,
+        (crate::middleware::MiddlewareResult::Continue, Target::Route(matched)) => {
+            let timeout = matched.limits.timeout.or(handler_timeout);
+            req.deadline = timeout.map(|timeout| Instant::now() + timeout);
+            let handler = &handlers[matched.handler_id];
+            // A panicking handler must not take the connection task down
+            let run = AssertUnwindSafe(async {
+                match timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, handler(req, &matched))
+                        .await
+                        .unwrap_or_else(|_| handler_timed_out(req, timeout)),
+                    None => handler(req, &matched).await,
+                }
+            });
+            match run.catch_unwind().await {
+                Ok(response) => response,
+                Err(payload) => handler_panicked(req, matched.pattern, &*payload),
+            }
+        }
+    }
+    .with_error_format(error_format, &req.path);
+
+    if let Some(request_id) = req.header("x-request-id") {
+        response.set_header("x-request-id", request_id);
+    }
+    middleware.run_after(req, &mut response);
+    response
+}
+
+/// Where routing sends a request
+enum Target<'a> {
+    /// Run the matched route's handler
+    Route(Match<'a>),
+    /// No route matched: send this response (404, 422 for rejected typed
+    /// parameters, or 204 for `OPTIONS`) unless a middleware answers first
+    Unmatched(PyResponse),
+    /// Authentication failed: send this response without running middleware
+    Rejected(PyResponse),
+}
+
+/// Match `path` for `req` and authenticate the matched route
+fn resolve<'a>(
+    req: &mut PyRequest,
+    router: &'a Router,
+    path: &'a str,
+    auth_config: Option<&AuthConfig>,
+    debug: bool,
+) -> Target<'a> {
+    let matched = match router.match_route(req.method, path) {
+        Ok(matched) => matched,
+        Err(Error::InvalidParams { errors, .. }) => {
+            let problem = ProblemDetails::new(422)
+                .with_detail("Invalid path parameters")
+                .with_extension(
+                    "errors",
+                    serde_json::to_value(&errors.errors).unwrap_or_default(),
+                );
+            let response = PyResponse::text(errors.to_json())
+                .with_status(422)
+                .with_header("Content-Type", "application/json")
+                .with_problem(problem);
+            return Target::Unmatched(response);
+        }
+        Err(_) if req.method == Method::Options && router.matches_any(path) => {
+            return Target::Unmatched(options_response(router, path));
+        }
+        Err(_) if debug => {
+            let response = not_found_with_suggestions(router, req.method, path);
+            return Target::Unmatched(response);
+        }
         Err(_) => {
-            return PyResponse::text(r#"{"error": "Not Found"}"#)
+            let response = PyResponse::text(r#"{"error": "Not Found"}"#)
                 .with_status(404)
-                .with_header("Content-Type", "application/json");
+                .with_header("Content-Type", "application/json")
+                .with_problem(ProblemDetails::new(404));
+            return Target::Unmatched(response);
         }
     };
 
     req.typed_params = matched.typed_params.clone();
+    req.route = Some(format!("{} {}", req.method, matched.pattern).into());
 
     if matched.auth_required {
-        if let Some(config) = auth_config {
-            let auth_header = req.header("authorization");
-            if let Some(token) = auth_header.and_then(|h| h.strip_prefix("Bearer ")) {
-                match decode::<serde_json::Value>(token, &config.decoding_key, &config.validation) {
-                    Ok(token_data) => {
-                        req.claims = Some(token_data.claims);
-                    }
-                    Err(e) => {
-                        warn!("JWT validation failed: {}", e);
-                        return PyResponse::text(r#"{"error": "Unauthorized"}"#)
-                            .with_status(401)
-                            .with_header("Content-Type", "application/json");
-                    }
-                }
-            } else {
-                return PyResponse::text(r#"{"error": "Missing or invalid Authorization header"}"#)
-                    .with_status(401)
-                    .with_header("Content-Type", "application/json");
-            }
-        } else {
-            error!("Route requires auth but server has no JWT secret configured");
-            return PyResponse::text(
+        if let Some(rejection) = authenticate(req, auth_config) {
+            return Target::Rejected(rejection);
+        }
+    }
+
+    log_context::record(&Span::current(), req);
+    Target::Route(matched)
+}
+
+/// Validate the bearer token of `req`, storing its claims
+///
+/// Returns the rejection sent when the token is missing or invalid.
+fn authenticate(req: &mut PyRequest, auth_config: Option<&AuthConfig>) -> Option<PyResponse> {
+    let Some(config) = auth_config else {
+        error!("Route requires auth but server has no JWT secret configured");
+        return Some(
+            PyResponse::text(
                 r#"{"error": "Server misconfigured: Auth required but no secret set"}"#,
             )
             .with_status(500)
-            .with_header("Content-Type", "application/json");
+            .with_header("Content-Type", "application/json")
+            .with_problem(ProblemDetails::new(500)),
+        );
+    };
+    let auth_header = req.header("authorization");
+    let Some(token) = auth_header.and_then(|h| h.strip_prefix("Bearer ")) else {
+        return Some(
+            PyResponse::text(r#"{"error": "Missing or invalid Authorization header"}"#)
+                .with_status(401)
+                .with_header("Content-Type", "application/json")
+                .with_problem(
+                    ProblemDetails::new(401).with_detail("Missing or invalid Authorization header"),
+                ),
+        );
+    };
+    match decode::<serde_json::Value>(token, &config.decoding_key, &config.validation) {
+        Ok(token_data) => {
+            req.claims = Some(token_data.claims);
+            None
+        }
+        Err(e) => {
+            warn!("JWT validation failed: {}", e);
+            Some(
+                PyResponse::text(r#"{"error": "Unauthorized"}"#)
+                    .with_status(401)
+                    .with_header("Content-Type", "application/json")
+                    .with_problem(ProblemDetails::new(401).with_detail("Invalid token")),
+            )
         }
     }
+}
 
-    let mut response = match middleware.run_before(req) {
-        crate::middleware::MiddlewareResult::Continue => {
-            let handler = &handlers[matched.handler_id];
-            handler(req, &matched).await
+/// 504 for a handler that did not finish within its timeout
+fn handler_timed_out(req: &PyRequest, timeout: Duration) -> PyResponse {
+    warn!(
+        "Handler for {} {} timed out after {:?}",
+        req.method, req.path, timeout
+    );
+    PyResponse::text("Gateway Timeout")
+        .with_status(504)
+        .with_problem(
+            ProblemDetails::new(504)
+                .with_detail(format!("Handler did not finish within {timeout:?}")),
+        )
+}
+
+/// 500 for a handler that panicked, logged with the panic's backtrace
+fn handler_panicked(req: &PyRequest, route: &str, payload: &(dyn Any + Send)) -> PyResponse {
+    let message = error::panic_message(payload);
+    let backtrace =
+        error::take_panic_backtrace().map_or_else(|| "unavailable".to_string(), |b| b.to_string());
+    error!(
+        route,
+        "Handler for {} {} panicked: {message}\nBacktrace:\n{backtrace}", req.method, req.path
+    );
+    PyResponse::text("Internal Server Error")
+        .with_status(500)
+        .with_problem(ProblemDetails::new(500))
+}
+
+/// 204 with `Allow` for an `OPTIONS` request to a path with routes
+///
+/// CORS preflights are answered by the CORS middleware before this
+/// response is used.
+fn options_response(router: &Router, path: &str) -> PyResponse {
+    let allowed = router.allowed_methods(path);
+    let allow = allowed
+        .iter()
+        .chain((!allowed.contains(&Method::Options)).then_some(&Method::Options))
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(", ");
+    PyResponse::text("")
+        .with_status(204)
+        .with_header("Allow", &allow)
+}
+
+/// 404 listing the registered routes nearest to the missed request
+fn not_found_with_suggestions(router: &Router, method: Method, path: &str) -> PyResponse {
+    let suggestions: Vec<String> = suggest::suggest_routes(router, method, path)
+        .iter()
+        .map(RouteSuggestion::label)
+        .collect();
+    if suggestions.is_empty() {
+        info!("No route for {} {}", method, path);
+    } else {
+        info!(
+            "No route for {} {}; did you mean {}?",
+            method,
+            path,
+            suggestions.join(", ")
+        );
+    }
+    let body = serde_json::json!({ "error": "Not Found", "suggestions": suggestions });
+    PyResponse::json(body.to_string())
+        .with_status(404)
+        .with_problem(
+            ProblemDetails::new(404).with_extension("suggestions", body["suggestions"].clone()),
+        )
+}
+
+/// Shared server state handed to every connection
+struct ConnectionContext {
+    routes: RouteRegistry,
+    ws_routes: WebSocketRoutes,
+    auth_config: Option<Arc<AuthConfig>>,
+    middleware: crate::middleware::MiddlewareChain,
+    max_body_size: usize,
+    handler_timeout: Option<Duration>,
+    target_limits: TargetLimits,
+    multipart_limits: Arc<MultipartLimits>,
+    bad_request_hook: Option<BadRequestHook>,
+    error_format: ErrorFormat,
+    response_policy: ResponsePolicy,
+    debug: bool,
+    http2: bool,
+    h2c: bool,
+    /// Requests served, reported to a supervising process
+    requests: Arc<AtomicU64>,
+    /// Set by the memory watchdog above the soft limit: refuse new requests
+    overloaded: Arc<AtomicBool>,
+}
+
+/// Serve an accepted plain or TLS stream
+///
+/// When HTTP/2 is enabled for the stream the protocol is detected from the
+/// connection preface, so HTTP/1.1 clients (and WebSocket upgrades) still work.
+async fn serve_connection<S>(
+    stream: S,
+    remote_addr: Option<SocketAddr>,
+    ctx: Arc<ConnectionContext>,
+    tls: bool,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let http2 = if tls { ctx.http2 } else { ctx.h2c };
+    let service = service_fn(move |mut req| {
+        let ctx = ctx.clone();
+        async move {
+            if let Some(resp) = ctx.ws_routes.upgrade(&mut req, remote_addr) {
+                return Ok(resp);
+            }
+            if ctx.overloaded.load(Ordering::Relaxed) {
+                let mut response = PyResponse::text("Service Unavailable")
+                    .with_status(503)
+                    .with_header("Retry-After", "1")
+                    .with_problem(ProblemDetails::new(503))
+                    .with_error_format(ctx.error_format, req.uri().path());
+                ctx.response_policy.apply(&mut response);
+                return Ok(response.into_hyper());
+            }
+            ctx.requests.fetch_add(1, Ordering::Relaxed);
+            let method = req.method().clone();
+            let path = req.uri().path().to_string();
+            let version = format!("{:?}", req.version()); // e.g., HTTP/1.1
+
+            let result = handle_request(req, &ctx, remote_addr, tls, &path).await;
+
+            log_access(remote_addr, &method, &path, &version, &result);
+            result
         }
-        crate::middleware::MiddlewareResult::Respond(resp) => resp,
+    });
+
+    let io = TokioIo::new(stream);
+    let result = if http2 {
+        auto::Builder::new(TokioExecutor::new())
+            .serve_connection_with_upgrades(io, service)
+            .await
+    } else {
+        http1::Builder::new()
+            .serve_connection(io, service)
+            .with_upgrades()
+            .await
+            .map_err(Into::into)
     };
+    if let Err(err) = result {
+        error!("Error serving connection: {:?}", err);
+    }
+}
 
-    if let Some(request_id) = req.header("x-request-id") {
-        response.set_header("x-request-id", request_id);
+/// Peer of a connection in log lines (`unix` for Unix socket peers)
+struct Peer(Option<SocketAddr>);
+
+impl std::fmt::Display for Peer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(addr) => write!(f, "{addr}"),
+            None => f.write_str("unix"),
+        }
+    }
+}
+
+/// Write the access log line for a served request
+fn log_access(
+    remote_addr: Option<SocketAddr>,
+    method: &hyper::Method,
+    path: &str,
+    version: &str,
+    result: &std::result::Result<Response<ResponseBody>, hyper::Error>,
+) {
+    if let Ok(resp) = result {
+        info!(
+            "    {} - \"{} {} {}\" {}",
+            Peer(remote_addr),
+            method,
+            path,
+            version,
+            resp.status()
+        );
+    } else {
+        error!(
+            "    {} - \"{} {} {}\" ERROR",
+            Peer(remote_addr),
+            method,
+            path,
+            version
+        );
+    }
+}
+
+/// WebSocket routes, matched separately from the HTTP router
+#[derive(Clone, Default)]
+struct WebSocketRoutes {
+    router: Router,
+    handlers: Vec<WsHandler>,
+}
+
+impl WebSocketRoutes {
+    /// Hand a WebSocket upgrade request for a registered path to its handler
+    ///
+    /// Returns `None` when the request is not an upgrade or no WebSocket
+    /// route matches, so it continues down the HTTP pipeline.
+    fn upgrade<B: Send + 'static>(
+        &self,
+        req: &mut Request<B>,
+        remote_addr: Option<SocketAddr>,
+    ) -> Option<Response<ResponseBody>> {
+        if !websocket::is_upgrade_request(req) {
+            return None;
+        }
+        let matched = self
+            .router
+            .match_route(Method::Get, req.uri().path())
+            .ok()?;
+        let handler = self.handlers[matched.handler_id].clone();
+        let typed_params = matched.typed_params;
+
+        info!(
+            "    {} - \"GET {} WebSocket\" 101",
+            Peer(remote_addr),
+            req.uri().path()
+        );
+        let response = websocket::accept(req, typed_params, handler);
+        Some(response.map(|()| Empty::<Bytes>::new().boxed()))
     }
-    middleware.run_after(req, &mut response);
-    response
 }
 
 async fn handle_request(
     req: Request<hyper::body::Incoming>,
-    router: &Router,
-    handlers: &[Handler],
-    auth_config: Option<&AuthConfig>,
-    middleware: &crate::middleware::MiddlewareChain,
-    remote_addr: std::net::SocketAddr,
-    max_body_size: usize,
-) -> std::result::Result<Response<Full<Bytes>>, hyper::Error> {
-    let mut py_request = match PyRequest::from_hyper_with_limit(req, max_body_size).await {
+    ctx: &ConnectionContext,
+    remote_addr: Option<SocketAddr>,
+    tls: bool,
+    path: &str,
+) -> std::result::Result<Response<ResponseBody>, hyper::Error> {
+    let routes = ctx.routes.snapshot();
+    let version = req.version();
+    let parsed = match PyRequest::from_hyper_head(req, ctx.target_limits) {
+        Ok((mut request, body)) if routes.streams_body(request.method, &request.path) => {
+            request.stream_body(body);
+            Ok(request)
+        }
+        Ok((mut request, body)) => {
+            let max_body_size = routes
+                .limits(request.method, &request.path)
+                .max_body_size
+                .unwrap_or(ctx.max_body_size);
+            request
+                .read_body(body, max_body_size)
+                .await
+                .map(|()| request)
+        }
+        Err(e) => Err(e),
+    };
+    let mut py_request = match parsed {
         Ok(r) => r,
-        Err(e) => match e {
-            crate::error::Error::PayloadTooLarge { .. } => {
-                return Ok(Response::builder()
-                    .status(StatusCode::PAYLOAD_TOO_LARGE)
-                    .body(Full::new(Bytes::from("Payload Too Large")))
-                    .unwrap());
+        Err(e) => {
+            let mut response = parse_error_response(&e, ctx.bad_request_hook.as_ref())
+                .with_error_format(ctx.error_format, path);
+            // The rest of an oversized body is never read; don't reuse the connection
+            if matches!(e, Error::PayloadTooLarge { .. }) && version < hyper::Version::HTTP_2 {
+                response = response.with_header("Connection", "close");
             }
-            _ => {
-                error!("Failed to parse request: {}", e);
-                return Ok(Response::builder()
-                    .status(StatusCode::BAD_REQUEST)
-                    .body(Full::new(Bytes::from("Bad Request")))
-                    .unwrap());
-            }
-        },
+            ctx.response_policy.apply(&mut response);
+            return Ok(response.into_hyper());
+        }
     };
 
-    py_request.set_header("x-client-ip", &remote_addr.ip().to_string());
-    let response =
-        process_request(&mut py_request, router, handlers, auth_config, middleware).await;
+    py_request.remote_addr = remote_addr;
+    py_request.scheme = if tls { "https" } else { "http" };
+    py_request.multipart_limits = Arc::clone(&ctx.multipart_limits);
+    // hyper drops this future when the client goes away (EOF on HTTP/1, a
+    // reset stream on HTTP/2), which cancels the request's token
+    let disconnected = py_request.cancellation.clone().drop_guard();
+    let response = process_request(
+        &mut py_request,
+        &routes.router,
+        &routes.handlers,
+        ctx.auth_config.as_deref(),
+        &ctx.middleware,
+        RoutingSettings {
+            error_format: ctx.error_format,
+            debug: ctx.debug,
+            handler_timeout: ctx.handler_timeout,
+            response_policy: ctx.response_policy,
+        },
+    )
+    .await;
+    disconnected.disarm();
     Ok(response.into_hyper())
 }
 
+/// Response for a request that failed to parse (413, 414 or 400)
+fn parse_error_response(err: &Error, hook: Option<&BadRequestHook>) -> PyResponse {
+    match err {
+        Error::PayloadTooLarge { limit, .. } => PyResponse::text("Payload Too Large")
+            .with_status(413)
+            .with_problem(payload_too_large(*limit)),
+        Error::UriTooLong { limit, .. } => PyResponse::text("URI Too Long")
+            .with_status(414)
+            .with_problem(
+                ProblemDetails::new(414)
+                    .with_detail(format!("Request target exceeds {limit} bytes")),
+            ),
+        Error::BadRequest { kind, message } => {
+            warn!("Rejected malformed request ({}): {}", kind, message);
+            hook.and_then(|hook| hook(*kind, message))
+                .unwrap_or_else(|| {
+                    PyResponse::text("Bad Request")
+                        .with_status(400)
+                        .with_problem(ProblemDetails::new(400).with_detail(message.as_str()))
+                })
+        }
+        _ => {
+            error!("Failed to parse request: {}", err);
+            PyResponse::text("Bad Request")
+                .with_status(400)
+                .with_problem(ProblemDetails::new(400))
+        }
+    }
+}
+
+/// Problem document for a body above `limit` bytes
+fn payload_too_large(limit: usize) -> ProblemDetails {
+    ProblemDetails::new(413).with_detail(format!("Request body exceeds {limit} bytes"))
+}
+
 static REQUEST_COUNTER: AtomicUsize = AtomicUsize::new(1);
 
 fn generate_request_id() -> String {
@@ -520,10 +1683,543 @@ mod tests {
         assert_eq!(resp.status, 404);
     }
 
+    #[test]
+    fn test_reason_phrase() {
+        assert_eq!(PyResponse::reason_phrase(404), Some("Not Found"));
+        assert_eq!(PyResponse::reason_phrase(299), None);
+        assert_eq!(PyResponse::reason_phrase(1000), None);
+    }
+
+    #[test]
+    fn test_set_cookies_are_sent_as_separate_headers() {
+        let mut resp = PyResponse::json("{}");
+        resp.set_cookie(&SetCookie::new("a", "1").http_only(true));
+        resp.set_cookie(&SetCookie::new("b", "2").path("/"));
+        assert_eq!(
+            resp.header_values("set-cookie"),
+            vec!["a=1; HttpOnly", "b=2; Path=/"]
+        );
+
+        let hyper_resp = resp.into_hyper();
+        let cookies: Vec<_> = hyper_resp
+            .headers()
+            .get_all(hyper::header::SET_COOKIE)
+            .iter()
+            .collect();
+        assert_eq!(cookies.len(), 2);
+    }
+
     #[test]
     fn test_server_config_default() {
         let config = ServerConfig::default();
         assert_eq!(config.address.port(), 8000);
         assert!(config.keep_alive);
     }
+
+    #[tokio::test]
+    async fn test_binary_body_is_sent_unchanged() {
+        let payload = Bytes::from_static(&[0xff, 0x00, 0xfe]);
+        let resp = PyResponse::binary(payload.clone(), "application/octet-stream").into_hyper();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, payload);
+    }
+
+    #[tokio::test]
+    async fn test_streaming_body_yields_chunks_in_order() {
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            for chunk in ["a", "b", "c"] {
+                tx.send(Bytes::from(chunk)).await.unwrap();
+            }
+        });
+
+        let resp = PyResponse::streaming(rx, "text/plain").into_hyper();
+        let body = resp.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&body[..], b"abc");
+    }
+
+    #[tokio::test]
+    async fn test_request_collects_streaming_body() {
+        let mut server = Server::new("");
+        let handler: Handler = Arc::new(|_req, _matched| {
+            Box::pin(async {
+                let (tx, rx) = mpsc::channel(4);
+                tokio::spawn(async move {
+                    let _ = tx.send(Bytes::from("chunk-1\n")).await;
+                    let _ = tx.send(Bytes::from("chunk-2\n")).await;
+                });
+                PyResponse::streaming(rx, "text/plain")
+            })
+        });
+        server
+            .add_route(Method::Get, "/stream", handler, false)
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/stream".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, "chunk-1\nchunk-2\n");
+        assert!(!resp.is_streaming());
+    }
+
+    #[tokio::test]
+    async fn test_accept_waits_for_free_slot() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let listener = [Listener::Tcp(listener, SocketOptions::default())];
+        let limiter = Arc::new(Semaphore::new(1));
+        let _first = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let _second = tokio::net::TcpStream::connect(addr).await.unwrap();
+
+        let (_, _, permit) = accept_within_limit(&listener, &limiter).await.unwrap();
+        let blocked = tokio::time::timeout(
+            Duration::from_millis(50),
+            accept_within_limit(&listener, &limiter),
+        )
+        .await;
+        assert!(blocked.is_err());
+
+        drop(permit);
+        let accepted = tokio::time::timeout(
+            Duration::from_secs(1),
+            accept_within_limit(&listener, &limiter),
+        )
+        .await;
+        assert!(accepted.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_client_disconnect_cancels_the_request() {
+        use tokio::io::AsyncWriteExt;
+
+        let mut server = Server::new("");
+        let (started_tx, started_rx) = tokio::sync::oneshot::channel();
+        let (cancelled_tx, cancelled_rx) = tokio::sync::oneshot::channel();
+        let signals = Arc::new(std::sync::Mutex::new(Some((started_tx, cancelled_tx))));
+        let handler: Handler = Arc::new(move |req, _matched| {
+            let token = req.cancellation.clone();
+            let signals = signals.lock().unwrap().take();
+            Box::pin(async move {
+                let (started, cancelled) = signals.unwrap();
+                let _ = started.send(());
+                tokio::spawn(async move {
+                    token.cancelled().await;
+                    let _ = cancelled.send(());
+                });
+                std::future::pending().await
+            })
+        });
+        server
+            .add_route(Method::Get, "/wait", handler, false)
+            .unwrap();
+
+        let (mut client, conn) = tokio::io::duplex(1024);
+        let ctx = Arc::new(server.connection_context());
+        tokio::spawn(serve_connection(
+            conn,
+            Some(([127, 0, 0, 1], 0).into()),
+            ctx,
+            false,
+        ));
+        client
+            .write_all(b"GET /wait HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .await
+            .unwrap();
+        started_rx.await.unwrap();
+        drop(client);
+
+        let cancelled = tokio::time::timeout(Duration::from_secs(1), cancelled_rx).await;
+        assert!(cancelled.unwrap().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_drain_aborts_connections_after_timeout() {
+        let mut connections = JoinSet::new();
+        connections.spawn(async {});
+        connections.spawn(std::future::pending::<()>());
+        drain_connections(&mut connections, Duration::from_millis(20)).await;
+        assert!(connections.is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_response_uses_hook() {
+        let err = Error::BadRequest {
+            kind: RequestErrorKind::BodyAborted,
+            message: "connection reset".to_string(),
+        };
+        assert_eq!(parse_error_response(&err, None).status, 400);
+
+        let hook: BadRequestHook = Arc::new(|kind, message| {
+            (kind == RequestErrorKind::BodyAborted).then(|| {
+                PyResponse::json(format!(r#"{{"error": "{kind}", "detail": "{message}"}}"#))
+                    .with_status(400)
+            })
+        });
+        let resp = parse_error_response(&err, Some(&hook));
+        assert_eq!(resp.status, 400);
+        assert!(resp.body.contains("body_aborted"));
+
+        let err = Error::BadRequest {
+            kind: RequestErrorKind::InvalidHeader,
+            message: "bad length".to_string(),
+        };
+        assert_eq!(parse_error_response(&err, Some(&hook)).body, "Bad Request");
+
+        let err = Error::PayloadTooLarge {
+            limit: 1,
+            actual: 2,
+        };
+        assert_eq!(parse_error_response(&err, Some(&hook)).status, 413);
+
+        let err = Error::UriTooLong {
+            limit: 1,
+            actual: 2,
+        };
+        assert_eq!(parse_error_response(&err, Some(&hook)).status, 414);
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_preflight_reaches_middleware() {
+        let mut server = Server::new("");
+        let handler: Handler =
+            Arc::new(|_req, _matched| Box::pin(async { PyResponse::json("{}") }));
+        server
+            .add_route(Method::Post, "/items", handler, false)
+            .unwrap();
+        server.add_middleware(crate::middleware::CorsMiddleware::new());
+
+        let headers = HashMap::from([
+            ("Origin".to_string(), "https://example.com".to_string()),
+            (
+                "Access-Control-Request-Method".to_string(),
+                "POST".to_string(),
+            ),
+        ]);
+        let resp = server
+            .test_request(Method::Options, "/items".to_string(), headers, None)
+            .await;
+        assert_eq!(resp.status, 204);
+        assert!(resp.headers.contains_key("Access-Control-Max-Age"));
+
+        let resp = server
+            .test_request(Method::Get, "/missing".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 404);
+        assert_eq!(
+            resp.headers.get("Access-Control-Allow-Origin").unwrap(),
+            "*"
+        );
+    }
+
+    /// Strips a `/v1` prefix and tags the request
+    struct Rewrite;
+
+    impl crate::middleware::Middleware for Rewrite {
+        fn before_request(&self, req: &mut PyRequest) -> crate::middleware::MiddlewareResult {
+            req.set_header("x-tenant", "acme");
+            if let Some(rest) = req.path.strip_prefix("/v1") {
+                req.path = rest.to_string();
+            }
+            crate::middleware::MiddlewareResult::Continue
+        }
+    }
+
+    #[tokio::test]
+    async fn test_middleware_rewrites_are_routed_again() {
+        let mut server = Server::new("secret");
+        let handler: Handler = Arc::new(|req, matched| {
+            let body = format!(
+                "{} {} {}",
+                req.header("x-tenant").unwrap_or("-"),
+                req.route.as_deref().unwrap_or("-"),
+                matched.params["id"]
+            );
+            Box::pin(async move { PyResponse::text(body) })
+        });
+        server
+            .add_route(Method::Get, "/items/{id}", handler.clone(), false)
+            .unwrap();
+        server
+            .add_route(Method::Get, "/admin/{id}", handler, true)
+            .unwrap();
+        server.add_middleware(Rewrite);
+
+        let get =
+            |path: &str| server.test_request(Method::Get, path.to_string(), HashMap::new(), None);
+        let resp = get("/v1/items/7").await;
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.body, "acme GET /items/{id} 7");
+        assert_eq!(get("/items/7").await.body, "acme GET /items/{id} 7");
+        assert_eq!(get("/v1/missing").await.status, 404);
+        // The rewritten route's auth still applies
+        assert_eq!(get("/v1/admin/7").await.status, 401);
+    }
+
+    #[tokio::test]
+    async fn test_options_lists_allowed_methods() {
+        let mut server = Server::new("");
+        let handler: Handler =
+            Arc::new(|_req, _matched| Box::pin(async { PyResponse::json("{}") }));
+        server
+            .add_route(Method::Post, "/items", handler.clone(), false)
+            .unwrap();
+        server
+            .add_route(Method::Get, "/items", handler, false)
+            .unwrap();
+
+        let options = |path: &str| {
+            server.test_request(Method::Options, path.to_string(), HashMap::new(), None)
+        };
+        let resp = options("/items").await;
+        assert_eq!(resp.status, 204);
+        assert_eq!(resp.headers.get("Allow").unwrap(), "GET, POST, OPTIONS");
+        assert_eq!(options("/missing").await.status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_typed_param_returns_422() {
+        let mut server = Server::new("");
+        server.set_param_mismatch(ParamMismatch::Reject);
+        let handler: Handler =
+            Arc::new(|_req, _matched| Box::pin(async { PyResponse::json("{}") }));
+        server
+            .add_route(Method::Get, "/users/{id:int}", handler, false)
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/users/abc".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 422);
+        assert!(resp.body.contains("INVALID_TYPE"));
+
+        let resp = server
+            .test_request(Method::Get, "/users/1".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 200);
+    }
+
+    #[tokio::test]
+    async fn test_route_limits_override_server_limits() {
+        let mut server = Server::new("");
+        server.set_max_body_size(4);
+        server.set_handler_timeout(Some(Duration::from_secs(60)));
+        let slow: Handler = Arc::new(|_req, _matched| {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                PyResponse::text("done")
+            })
+        });
+        server
+            .add_route(Method::Post, "/uploads", slow, false)
+            .unwrap();
+        assert!(server.set_route_limits(
+            Method::Post,
+            "/uploads",
+            RouteLimits {
+                max_body_size: Some(8),
+                timeout: Some(Duration::from_millis(10)),
+            },
+        ));
+        assert!(!server.set_route_limits(Method::Get, "/uploads", RouteLimits::default()));
+
+        let post = |body: &'static str| {
+            server.test_request(
+                Method::Post,
+                "/uploads".to_string(),
+                HashMap::new(),
+                Some(Bytes::from(body)),
+            )
+        };
+        assert_eq!(post("123456789").await.status, 413);
+        let err = server
+            .try_test_request(
+                Method::Post,
+                "/uploads".to_string(),
+                HashMap::new(),
+                Some(Bytes::from("123456789")),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            Error::PayloadTooLarge {
+                limit: 8,
+                actual: 9
+            }
+        ));
+        let resp = post("12345678").await;
+        assert_eq!(resp.status, 504);
+        assert_eq!(resp.body, "Gateway Timeout");
+
+        server.set_target_limits(TargetLimits {
+            max_uri_length: 12,
+            max_query_params: 1,
+        });
+        let get = |path: &str| {
+            server.try_test_request(Method::Get, path.to_string(), HashMap::new(), None)
+        };
+        assert!(matches!(
+            get("/uploads?page=2").await,
+            Err(Error::UriTooLong { limit: 12, .. })
+        ));
+        assert!(matches!(
+            get("/u?a&b").await,
+            Err(Error::BadRequest {
+                kind: RequestErrorKind::TooManyQueryParams,
+                ..
+            })
+        ));
+        let resp = server
+            .test_request(
+                Method::Get,
+                "/uploads?page=2".to_string(),
+                HashMap::new(),
+                None,
+            )
+            .await;
+        assert_eq!(resp.status, 414);
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handler_answers_500() {
+        let mut server = Server::new("");
+        let sync_panic: Handler = Arc::new(|_req, _matched| panic!("handler bug"));
+        let async_panic: Handler = Arc::new(|_req, _matched| {
+            Box::pin(async {
+                let items: Vec<u8> = Vec::new();
+                PyResponse::text(items[0].to_string())
+            })
+        });
+        server
+            .add_route(Method::Get, "/sync", sync_panic, false)
+            .unwrap();
+        server
+            .add_route(Method::Get, "/async", async_panic, false)
+            .unwrap();
+        server.set_handler_timeout(Some(Duration::from_secs(5)));
+
+        for path in ["/sync", "/async"] {
+            let resp = server
+                .test_request(Method::Get, path.to_string(), HashMap::new(), None)
+                .await;
+            assert_eq!(resp.status, 500);
+            assert_eq!(resp.body, "Internal Server Error");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_response_policy_applies_after_the_handler() {
+        let mut server = Server::new("");
+        let handler: Handler = Arc::new(|_req, _matched| {
+            Box::pin(async { PyResponse::text("hi").with_header("X-Powered-By", "PHP/8") })
+        });
+        server.add_route(Method::Get, "/", handler, false).unwrap();
+        server.set_response_policy(ResponsePolicy::strict());
+
+        let resp = server
+            .test_request(Method::Get, "/".to_string(), HashMap::new(), None)
+            .await;
+        assert!(!resp.headers.contains_key("X-Powered-By"));
+        assert!(resp.headers.contains_key("x-request-id"));
+        assert_eq!(resp.content_type, "text/plain; charset=utf-8");
+    }
+
+    #[tokio::test]
+    async fn test_handler_sees_its_deadline() {
+        let mut server = Server::new("");
+        server.set_handler_timeout(Some(Duration::from_secs(60)));
+        let handler: Handler = Arc::new(|req, _matched| {
+            let budget = req
+                .deadline
+                .map(|deadline| deadline.duration_since(req.received_at).as_secs());
+            Box::pin(async move { PyResponse::text(format!("{budget:?}")) })
+        });
+        server
+            .add_route(Method::Get, "/report", handler, false)
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/report".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.body, "Some(60)");
+    }
+
+    #[tokio::test]
+    async fn test_problem_error_format() {
+        let mut server = Server::new("");
+        server.set_param_mismatch(ParamMismatch::Reject);
+        let handler: Handler =
+            Arc::new(|_req, _matched| Box::pin(async { PyResponse::json("{}") }));
+        server
+            .add_route(Method::Get, "/users/{id:int}", handler, false)
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/missing".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.content_type, "application/json");
+
+        server.set_error_format(ErrorFormat::Problem);
+        let resp = server
+            .test_request(Method::Get, "/missing".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 404);
+        assert_eq!(resp.content_type, problem::CONTENT_TYPE);
+        let doc: serde_json::Value = serde_json::from_str(&resp.body).unwrap();
+        assert_eq!(doc["title"], "Not Found");
+        assert_eq!(doc["instance"], "/missing");
+
+        let resp = server
+            .test_request(Method::Get, "/users/abc".to_string(), HashMap::new(), None)
+            .await;
+        let doc: serde_json::Value = serde_json::from_str(&resp.body).unwrap();
+        assert_eq!(doc["status"], 422);
+        assert_eq!(doc["errors"][0]["code"], "INVALID_TYPE");
+    }
+
+    #[tokio::test]
+    async fn test_debug_404_suggests_nearby_routes() {
+        let mut server = Server::new("");
+        let handler: Handler =
+            Arc::new(|_req, _matched| Box::pin(async { PyResponse::json("{}") }));
+        server
+            .add_route(Method::Get, "/users", handler, false)
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/usres".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.body, r#"{"error": "Not Found"}"#);
+
+        server.set_debug(true);
+        let resp = server
+            .test_request(Method::Get, "/usres".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 404);
+        let body: serde_json::Value = serde_json::from_str(&resp.body).unwrap();
+        assert_eq!(body["suggestions"][0], "GET /users");
+    }
+
+    #[tokio::test]
+    async fn test_routes_added_and_removed_through_handle() {
+        let server = Server::new("");
+        let routes = server.routes();
+        let handler: Handler =
+            Arc::new(|_req, _matched| Box::pin(async { PyResponse::text("plugin") }));
+        routes
+            .add_route(Method::Get, "/plugin", handler, false)
+            .unwrap();
+
+        let resp = server
+            .test_request(Method::Get, "/plugin".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.body, "plugin");
+
+        assert!(routes.remove_route(Method::Get, "/plugin"));
+        let resp = server
+            .test_request(Method::Get, "/plugin".to_string(), HashMap::new(), None)
+            .await;
+        assert_eq!(resp.status, 404);
+    }
 }
@@ -0,0 +1,538 @@
+//! # Signed Sessions
+//!
+//! Cookie-backed sessions. The session data is serialized as JSON, optionally
+//! encrypted with AES-256-GCM, and signed with HMAC-SHA256, so the client
+//! stores it but cannot forge or (when encrypted) read it.
+//!
+//! `SessionMiddleware` loads the cookie into the request's [`Session`] before
+//! the handler runs and writes it back after the response when the handler
+//! changed it; an emptied session deletes the cookie.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: `Session` only holds data; `SessionMiddleware` only encodes it
+//! - **O**: Plugs into the existing `Middleware` chain
+//! - **D**: Handlers see a dict-like session, never the cookie format
+
+use crate::cookie::{SameSite, SetCookie};
+use crate::middleware::{Middleware, MiddlewareResult};
+use crate::server::{PyRequest, PyResponse};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+#[cfg(feature = "python")]
+use pyo3::exceptions::{PyKeyError, PyTypeError};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::PyList;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde_json::{Map, Value};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Browsers drop cookies larger than this
+const MAX_COOKIE_SIZE: usize = 4096;
+
+/// Session data of one request, shared by every clone of the request
+///
+/// Inactive until a `SessionMiddleware` loads it.
+#[cfg_attr(feature = "python", pyclass(name = "Session"))]
+#[derive(Debug, Clone, Default)]
+pub struct Session {
+    state: Arc<Mutex<SessionState>>,
+}
+
+#[derive(Debug, Default)]
+struct SessionState {
+    active: bool,
+    modified: bool,
+    data: Map<String, Value>,
+}
+
+impl Session {
+    fn lock(&self) -> MutexGuard<'_, SessionState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Activate the session with data decoded from the request
+    pub fn load(&self, data: Map<String, Value>) {
+        let mut state = self.lock();
+        state.active = true;
+        state.modified = false;
+        state.data = data;
+    }
+
+    /// Whether a `SessionMiddleware` loaded this session
+    #[must_use]
+    pub fn is_active(&self) -> bool {
+        self.lock().active
+    }
+
+    /// Whether the data changed since it was loaded
+    #[must_use]
+    pub fn is_modified(&self) -> bool {
+        self.lock().modified
+    }
+
+    /// Value stored under `key`
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.lock().data.get(key).cloned()
+    }
+
+    /// Store `value` under `key`
+    pub fn insert(&self, key: impl Into<String>, value: Value) {
+        let mut state = self.lock();
+        state.data.insert(key.into(), value);
+        state.modified = true;
+    }
+
+    /// Remove and return the value under `key`
+    pub fn remove(&self, key: &str) -> Option<Value> {
+        let mut state = self.lock();
+        let removed = state.data.remove(key);
+        state.modified |= removed.is_some();
+        removed
+    }
+
+    /// Remove every key (the cookie is deleted on response)
+    pub fn clear(&self) {
+        let mut state = self.lock();
+        state.modified |= !state.data.is_empty();
+        state.data.clear();
+    }
+
+    /// Stored keys
+    #[must_use]
+    pub fn keys(&self) -> Vec<String> {
+        self.lock().data.keys().cloned().collect()
+    }
+
+    /// Number of stored keys
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lock().data.len()
+    }
+
+    /// Whether nothing is stored
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lock().data.is_empty()
+    }
+
+    /// Copy of the stored data
+    #[must_use]
+    pub fn data(&self) -> Map<String, Value> {
+        self.lock().data.clone()
+    }
+}
+
+#[cfg(feature = "python")]
+#[pymethods]
+impl Session {
+    fn __getitem__(&self, py: Python<'_>, key: &str) -> PyResult<PyObject> {
+        match self.get(key) {
+            Some(value) => value_to_py(py, &value),
+            None => Err(PyKeyError::new_err(key.to_string())),
+        }
+    }
+
+    fn __setitem__(&self, key: String, value: &PyAny) -> PyResult<()> {
+        self.insert(key, value_from_py(value)?);
+        Ok(())
+    }
+
+    fn __delitem__(&self, key: &str) -> PyResult<()> {
+        self.remove(key)
+            .map(drop)
+            .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+    }
+
+    fn __contains__(&self, key: &str) -> bool {
+        self.lock().data.contains_key(key)
+    }
+
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    fn __iter__(&self, py: Python<'_>) -> PyResult<PyObject> {
+        Ok(PyList::new(py, self.keys())
+            .call_method0("__iter__")?
+            .into())
+    }
+
+    /// Value under `key`, or `default`
+    #[pyo3(name = "get", signature = (key, default=None))]
+    fn py_get(&self, py: Python<'_>, key: &str, default: Option<PyObject>) -> PyResult<PyObject> {
+        match self.get(key) {
+            Some(value) => value_to_py(py, &value),
+            None => Ok(default.unwrap_or_else(|| py.None())),
+        }
+    }
+
+    /// Remove and return the value under `key`, or `default`
+    #[pyo3(name = "pop", signature = (key, default=None))]
+    fn py_pop(&self, py: Python<'_>, key: &str, default: Option<PyObject>) -> PyResult<PyObject> {
+        match self.remove(key) {
+            Some(value) => value_to_py(py, &value),
+            None => default.ok_or_else(|| PyKeyError::new_err(key.to_string())),
+        }
+    }
+
+    /// Remove every key
+    #[pyo3(name = "clear")]
+    fn py_clear(&self) {
+        self.clear();
+    }
+
+    /// Stored keys
+    #[pyo3(name = "keys")]
+    fn py_keys(&self) -> Vec<String> {
+        self.keys()
+    }
+
+    /// `(key, value)` pairs
+    fn items(&self, py: Python<'_>) -> PyResult<Vec<(String, PyObject)>> {
+        self.data()
+            .iter()
+            .map(|(key, value)| Ok((key.clone(), value_to_py(py, value)?)))
+            .collect()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Session({})", Value::Object(self.data()))
+    }
+}
+
+/// JSON value to a Python object
+#[cfg(feature = "python")]
+fn value_to_py(py: Python<'_>, value: &Value) -> PyResult<PyObject> {
+    Ok(py
+        .import("json")?
+        .call_method1("loads", (value.to_string(),))?
+        .into())
+}
+
+/// Python object to a JSON value (`TypeError` if not JSON-serializable)
+#[cfg(feature = "python")]
+fn value_from_py(value: &PyAny) -> PyResult<Value> {
+    let text: String = value
+        .py()
+        .import("json")?
+        .call_method1("dumps", (value,))?
+        .extract()?;
+    serde_json::from_str(&text).map_err(|e| PyTypeError::new_err(e.to_string()))
+}
+
+/// Middleware storing `request.session` in a signed cookie
+pub struct SessionMiddleware {
+    cookie_name: String,
+    signing_key: hmac::Key,
+    encryption_key: hmac::Tag,
+    cipher: Option<LessSafeKey>,
+    max_age: Option<i64>,
+    path: String,
+    domain: Option<String>,
+    secure: bool,
+    same_site: SameSite,
+}
+
+impl SessionMiddleware {
+    /// Sessions signed with a key derived from `secret`
+    ///
+    /// The cookie is named `session`, sent for path `/`, `HttpOnly` and
+    /// `SameSite=Lax`, and lasts for the browser session.
+    #[must_use]
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            cookie_name: "session".to_string(),
+            signing_key: hmac::Key::new(
+                hmac::HMAC_SHA256,
+                derive_key(secret, b"pyvectora.session.sign").as_ref(),
+            ),
+            encryption_key: derive_key(secret, b"pyvectora.session.encrypt"),
+            cipher: None,
+            max_age: None,
+            path: "/".to_string(),
+            domain: None,
+            secure: false,
+            same_site: SameSite::Lax,
+        }
+    }
+
+    /// Also encrypt the data so clients cannot read it
+    #[must_use]
+    pub fn encrypted(mut self) -> Self {
+        self.cipher = UnboundKey::new(&AES_256_GCM, self.encryption_key.as_ref())
+            .ok()
+            .map(LessSafeKey::new);
+        self
+    }
+
+    /// Cookie name
+    #[must_use]
+    pub fn cookie_name(mut self, name: impl Into<String>) -> Self {
+        self.cookie_name = name.into();
+        self
+    }
+
+    /// Lifetime in seconds; older cookies are rejected even if the client keeps them
+    #[must_use]
+    pub const fn max_age(mut self, seconds: i64) -> Self {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Path the cookie is sent for
+    #[must_use]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path = path.into();
+        self
+    }
+
+    /// Domain the cookie is sent to
+    #[must_use]
+    pub fn domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Only send the cookie over HTTPS
+    #[must_use]
+    pub const fn secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// `SameSite` attribute of the cookie
+    #[must_use]
+    pub const fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Cookie value carrying `data`
+    ///
+    /// Returns `None` only if the system random generator fails while
+    /// drawing an encryption nonce.
+    #[must_use]
+    pub fn encode(&self, data: &Map<String, Value>) -> Option<String> {
+        let mut payload = serde_json::json!({ "d": data });
+        if let Some(max_age) = self.max_age {
+            payload["e"] = Value::from(unix_now().saturating_add(max_age));
+        }
+        let mut payload = payload.to_string().into_bytes();
+
+        if let Some(cipher) = &self.cipher {
+            let mut nonce = [0u8; NONCE_LEN];
+            SystemRandom::new().fill(&mut nonce).ok()?;
+            cipher
+                .seal_in_place_append_tag(
+                    Nonce::assume_unique_for_key(nonce),
+                    Aad::empty(),
+                    &mut payload,
+                )
+                .ok()?;
+            payload = [&nonce[..], &payload[..]].concat();
+        }
+
+        let tag = hmac::sign(&self.signing_key, &payload);
+        Some(format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(&payload),
+            URL_SAFE_NO_PAD.encode(tag.as_ref())
+        ))
+    }
+
+    /// Session data in a cookie value, or `None` if it is forged, corrupt or expired
+    #[must_use]
+    pub fn decode(&self, value: &str) -> Option<Map<String, Value>> {
+        let (payload, tag) = value.split_once('.')?;
+        let mut payload = URL_SAFE_NO_PAD.decode(payload).ok()?;
+        let tag = URL_SAFE_NO_PAD.decode(tag).ok()?;
+        hmac::verify(&self.signing_key, &payload, &tag).ok()?;
+
+        let plaintext = match &self.cipher {
+            Some(cipher) => {
+                if payload.len() < NONCE_LEN {
+                    return None;
+                }
+                let (nonce, sealed) = payload.split_at_mut(NONCE_LEN);
+                let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+                &*cipher.open_in_place(nonce, Aad::empty(), sealed).ok()?
+            }
+            None => &payload[..],
+        };
+
+        let mut payload: Map<String, Value> = serde_json::from_slice(plaintext).ok()?;
+        if let Some(expires) = payload.get("e") {
+            if expires.as_i64()? <= unix_now() {
+                return None;
+            }
+        }
+        match payload.remove("d")? {
+            Value::Object(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    fn cookie(&self, value: &str, max_age: Option<i64>) -> SetCookie {
+        let mut cookie = SetCookie::new(self.cookie_name.as_str(), value)
+            .path(self.path.as_str())
+            .secure(self.secure)
+            .http_only(true)
+            .same_site(self.same_site);
+        if let Some(domain) = &self.domain {
+            cookie = cookie.domain(domain.as_str());
+        }
+        if let Some(max_age) = max_age {
+            cookie = cookie.max_age(max_age);
+        }
+        cookie
+    }
+}
+
+impl Middleware for SessionMiddleware {
+    fn before_request(&self, req: &mut PyRequest) -> MiddlewareResult {
+        let data = req
+            .cookie_map()
+            .get(&self.cookie_name)
+            .and_then(|value| self.decode(value))
+            .unwrap_or_default();
+        req.session.load(data);
+        MiddlewareResult::Continue
+    }
+
+    fn after_response(&self, req: &PyRequest, res: &mut PyResponse) {
+        if !req.session.is_modified() {
+            return;
+        }
+        if req.session.is_empty() {
+            res.set_cookie(&self.cookie("", Some(0)));
+            return;
+        }
+        let Some(value) = self.encode(&req.session.data()) else {
+            warn!(path = %req.path, "Session not saved: random generator failed");
+            return;
+        };
+        if value.len() > MAX_COOKIE_SIZE {
+            warn!(
+                path = %req.path,
+                size = value.len(),
+                "Session cookie exceeds 4096 bytes and may be dropped by browsers"
+            );
+        }
+        res.set_cookie(&self.cookie(&value, self.max_age));
+    }
+
+    fn name(&self) -> &'static str {
+        "SessionMiddleware"
+    }
+}
+
+/// Purpose-specific key from the application secret
+fn derive_key(secret: &[u8], purpose: &[u8]) -> hmac::Tag {
+    hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, secret), purpose)
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::router::Method;
+    use std::collections::HashMap;
+
+    const SECRET: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    fn request(cookie: Option<&str>) -> PyRequest {
+        let mut headers = HashMap::new();
+        if let Some(cookie) = cookie {
+            headers.insert("cookie".to_string(), cookie.to_string());
+        }
+        PyRequest::new(Method::Get, "/".to_string(), headers, None)
+    }
+
+    fn data(pairs: &[(&str, Value)]) -> Map<String, Value> {
+        pairs
+            .iter()
+            .map(|(k, v)| ((*k).to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_signed_cookie_round_trips_and_rejects_tampering() {
+        let mw = SessionMiddleware::new(SECRET);
+        let value = mw.encode(&data(&[("user_id", Value::from(7))])).unwrap();
+        assert_eq!(mw.decode(&value).unwrap()["user_id"], 7);
+
+        let (payload, tag) = value.split_once('.').unwrap();
+        let forged = URL_SAFE_NO_PAD.encode(br#"{"d":{"user_id":1}}"#);
+        assert!(mw.decode(&format!("{forged}.{tag}")).is_none());
+        assert!(SessionMiddleware::new(b"another secret")
+            .decode(&value)
+            .is_none());
+        assert!(mw.decode(payload).is_none());
+    }
+
+    #[test]
+    fn test_encrypted_cookie_hides_data() {
+        let mw = SessionMiddleware::new(SECRET).encrypted();
+        let value = mw.encode(&data(&[("role", Value::from("admin"))])).unwrap();
+        let payload = URL_SAFE_NO_PAD
+            .decode(value.split_once('.').unwrap().0)
+            .unwrap();
+        assert!(!String::from_utf8_lossy(&payload).contains("admin"));
+        assert_eq!(mw.decode(&value).unwrap()["role"], "admin");
+        assert!(SessionMiddleware::new(SECRET).decode(&value).is_none());
+    }
+
+    #[test]
+    fn test_expired_cookie_is_rejected() {
+        let mw = SessionMiddleware::new(SECRET).max_age(-1);
+        let value = mw.encode(&data(&[("a", Value::from(1))])).unwrap();
+        assert!(mw.decode(&value).is_none());
+    }
+
+    #[test]
+    fn test_middleware_saves_only_changed_sessions() {
+        let mw = SessionMiddleware::new(SECRET).cookie_name("sid");
+
+        let mut req = request(None);
+        assert!(!req.session.is_active());
+        mw.before_request(&mut req);
+        assert!(req.session.is_active() && req.session.is_empty());
+        let mut res = PyResponse::text("ok");
+        mw.after_response(&req, &mut res);
+        assert!(res.header_values("set-cookie").is_empty());
+
+        req.clone().session.insert("cart", Value::from(3));
+        let mut res = PyResponse::text("ok");
+        mw.after_response(&req, &mut res);
+        let cookie = res.header_values("set-cookie")[0].to_string();
+        assert!(cookie.contains("; Path=/; HttpOnly; SameSite=Lax"));
+
+        let value = cookie
+            .strip_prefix("sid=")
+            .and_then(|rest| rest.split(';').next())
+            .unwrap();
+        let mut next = request(Some(&format!("sid={value}")));
+        mw.before_request(&mut next);
+        assert_eq!(next.session.get("cart"), Some(Value::from(3)));
+
+        next.session.clear();
+        let mut res = PyResponse::text("ok");
+        mw.after_response(&next, &mut res);
+        assert!(res.header_values("set-cookie")[0].starts_with("sid=; Max-Age=0"));
+    }
+}
@@ -0,0 +1,249 @@
+//! # Shutdown Signals
+//!
+//! Process signals that stop the server gracefully. Container runtimes
+//! stop a container with `SIGTERM` and kill it after a grace period, so
+//! listening for Ctrl+C alone loses in-flight requests. On Unix the default
+//! set is `SIGINT`, `SIGTERM` and `SIGHUP`; on Windows Ctrl+C, Ctrl+Break
+//! and closing the console window. Signals of the other platform are
+//! ignored, so one configuration serves both.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only waits for the configured signals
+//! - **D**: The server waits on `wait_for`, unaware of the platform
+
+use crate::error::{Error, Result};
+use futures_util::future::{self, BoxFuture, FutureExt};
+use std::fmt;
+use tracing::warn;
+
+/// A signal that triggers graceful shutdown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ShutdownSignal {
+    /// `SIGINT` on Unix, Ctrl+C on Windows
+    Interrupt,
+    /// `SIGTERM` (Unix)
+    Terminate,
+    /// `SIGHUP`: the controlling terminal closed (Unix)
+    Hangup,
+    /// `SIGQUIT` (Unix)
+    Quit,
+    /// Ctrl+Break (Windows)
+    CtrlBreak,
+    /// The console window was closed (Windows)
+    CtrlClose,
+    /// The system is shutting down (Windows services)
+    CtrlShutdown,
+    /// The user is logging off (Windows services)
+    CtrlLogoff,
+}
+
+impl ShutdownSignal {
+    /// Every signal, in documentation order
+    pub const ALL: [Self; 8] = [
+        Self::Interrupt,
+        Self::Terminate,
+        Self::Hangup,
+        Self::Quit,
+        Self::CtrlBreak,
+        Self::CtrlClose,
+        Self::CtrlShutdown,
+        Self::CtrlLogoff,
+    ];
+
+    /// Signals listened for unless configured otherwise
+    #[must_use]
+    pub fn defaults() -> Vec<Self> {
+        if cfg!(windows) {
+            vec![Self::Interrupt, Self::CtrlBreak, Self::CtrlClose]
+        } else {
+            vec![Self::Interrupt, Self::Terminate, Self::Hangup]
+        }
+    }
+
+    /// Conventional name (`SIGTERM`, `CTRL_BREAK`)
+    #[must_use]
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Interrupt => "SIGINT",
+            Self::Terminate => "SIGTERM",
+            Self::Hangup => "SIGHUP",
+            Self::Quit => "SIGQUIT",
+            Self::CtrlBreak => "CTRL_BREAK",
+            Self::CtrlClose => "CTRL_CLOSE",
+            Self::CtrlShutdown => "CTRL_SHUTDOWN",
+            Self::CtrlLogoff => "CTRL_LOGOFF",
+        }
+    }
+
+    /// Signal named `name`, case-insensitively (`SIGTERM`, `term`,
+    /// `CTRL_C` for `SIGINT`)
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Config` for an unknown name.
+    pub fn parse(name: &str) -> Result<Self> {
+        let upper = name.trim().to_ascii_uppercase();
+        if upper == "CTRL_C" {
+            return Ok(Self::Interrupt);
+        }
+        Self::ALL
+            .into_iter()
+            .find(|signal| {
+                let full = signal.name();
+                full == upper || full.strip_prefix("SIG") == Some(upper.as_str())
+            })
+            .ok_or_else(|| Error::Config {
+                message: format!(
+                    "unknown shutdown signal {name:?} (expected one of: CTRL_C, {})",
+                    Self::ALL.map(Self::name).join(", ")
+                ),
+            })
+    }
+}
+
+impl fmt::Display for ShutdownSignal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Resolve with the first of `signals` the process receives
+///
+/// Signals the platform does not have are skipped; with none left this
+/// never resolves.
+pub async fn wait_for(signals: &[ShutdownSignal]) -> ShutdownSignal {
+    let listeners: Vec<BoxFuture<'static, ShutdownSignal>> = signals
+        .iter()
+        .filter_map(|signal| listen(*signal))
+        .collect();
+    if listeners.is_empty() {
+        return future::pending().await;
+    }
+    future::select_all(listeners).await.0
+}
+
+/// Future resolving when `signal` arrives, if the platform has it
+#[cfg(unix)]
+fn listen(signal: ShutdownSignal) -> Option<BoxFuture<'static, ShutdownSignal>> {
+    use tokio::signal::unix::{signal as unix_signal, SignalKind};
+
+    let kind = match signal {
+        ShutdownSignal::Interrupt => SignalKind::interrupt(),
+        ShutdownSignal::Terminate => SignalKind::terminate(),
+        ShutdownSignal::Hangup => SignalKind::hangup(),
+        ShutdownSignal::Quit => SignalKind::quit(),
+        _ => return None,
+    };
+    match unix_signal(kind) {
+        Ok(mut stream) => Some(
+            async move {
+                stream.recv().await;
+                signal
+            }
+            .boxed(),
+        ),
+        Err(err) => {
+            warn!("Cannot listen for {signal}: {err}");
+            None
+        }
+    }
+}
+
+/// Future resolving when `signal` arrives, if the platform has it
+#[cfg(windows)]
+fn listen(signal: ShutdownSignal) -> Option<BoxFuture<'static, ShutdownSignal>> {
+    use tokio::signal::windows;
+
+    macro_rules! console {
+        ($install:path) => {
+            match $install() {
+                Ok(mut stream) => Some(
+                    async move {
+                        stream.recv().await;
+                        signal
+                    }
+                    .boxed(),
+                ),
+                Err(err) => {
+                    warn!("Cannot listen for {signal}: {err}");
+                    None
+                }
+            }
+        };
+    }
+    match signal {
+        ShutdownSignal::Interrupt => console!(windows::ctrl_c),
+        ShutdownSignal::CtrlBreak => console!(windows::ctrl_break),
+        ShutdownSignal::CtrlClose => console!(windows::ctrl_close),
+        ShutdownSignal::CtrlShutdown => console!(windows::ctrl_shutdown),
+        ShutdownSignal::CtrlLogoff => console!(windows::ctrl_logoff),
+        _ => None,
+    }
+}
+
+/// Future resolving when `signal` arrives, if the platform has it
+#[cfg(not(any(unix, windows)))]
+fn listen(signal: ShutdownSignal) -> Option<BoxFuture<'static, ShutdownSignal>> {
+    (signal == ShutdownSignal::Interrupt).then(|| {
+        async move {
+            let _ = tokio::signal::ctrl_c().await;
+            signal
+        }
+        .boxed()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_signal_names() {
+        assert_eq!(
+            ShutdownSignal::parse("SIGTERM").unwrap(),
+            ShutdownSignal::Terminate
+        );
+        assert_eq!(
+            ShutdownSignal::parse("hup").unwrap(),
+            ShutdownSignal::Hangup
+        );
+        assert_eq!(
+            ShutdownSignal::parse("ctrl_c").unwrap(),
+            ShutdownSignal::Interrupt
+        );
+        assert_eq!(
+            ShutdownSignal::parse("CTRL_BREAK").unwrap(),
+            ShutdownSignal::CtrlBreak
+        );
+        let err = ShutdownSignal::parse("SIGKILL").unwrap_err();
+        assert!(err.to_string().contains("SIGTERM"));
+        for signal in ShutdownSignal::ALL {
+            assert_eq!(ShutdownSignal::parse(signal.name()).unwrap(), signal);
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_waits_for_a_configured_signal() {
+        // Only signals of the other platform: never resolves
+        let windows_only = [ShutdownSignal::CtrlClose];
+        assert!(tokio::time::timeout(
+            std::time::Duration::from_millis(20),
+            wait_for(&windows_only)
+        )
+        .await
+        .is_err());
+
+        let waiting = wait_for(&[ShutdownSignal::Quit]);
+        tokio::pin!(waiting);
+        // The first poll installs the handler, so the signal is caught
+        let first_poll = tokio::time::timeout(std::time::Duration::ZERO, &mut waiting);
+        assert!(first_poll.await.is_err());
+        std::process::Command::new("kill")
+            .args(["-QUIT", &std::process::id().to_string()])
+            .status()
+            .unwrap();
+        assert_eq!(waiting.await, ShutdownSignal::Quit);
+    }
+}
@@ -0,0 +1,180 @@
+//! # Server-Sent Events
+//!
+//! `text/event-stream` framing and keep-alive handling.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only formats events and paces the stream, producers live elsewhere
+//! - **O**: Events are plain data; any producer feeding a channel can stream them
+//! - **D**: The server sees an ordinary streaming body of `Bytes`
+
+use hyper::body::Bytes;
+use std::fmt::Write;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::{interval_at, Instant, MissedTickBehavior};
+
+/// Content type of an SSE stream
+pub const CONTENT_TYPE: &str = "text/event-stream";
+
+/// Comment line sent while idle so proxies keep the connection open
+pub const KEEP_ALIVE_COMMENT: &[u8] = b": keep-alive\n\n";
+
+/// Default idle interval between keep-alive comments
+pub const DEFAULT_KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// A single Server-Sent Event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SseEvent {
+    /// Event payload (split into one `data:` line per line)
+    pub data: String,
+    /// Event type (`event:` field)
+    pub event: Option<String>,
+    /// Event ID (`id:` field)
+    pub id: Option<String>,
+    /// Client reconnection delay in milliseconds (`retry:` field)
+    pub retry: Option<u64>,
+}
+
+impl SseEvent {
+    /// Create an event carrying `data`
+    #[must_use]
+    pub fn new(data: impl Into<String>) -> Self {
+        Self {
+            data: data.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the event type
+    #[must_use]
+    pub fn with_event(mut self, event: impl Into<String>) -> Self {
+        self.event = Some(event.into());
+        self
+    }
+
+    /// Set the event ID
+    #[must_use]
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Set the reconnection delay in milliseconds
+    #[must_use]
+    pub const fn with_retry(mut self, retry: u64) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Serialize to wire format, terminated by a blank line
+    ///
+    /// Newlines in `event` and `id` are stripped since they would end the field.
+    #[must_use]
+    pub fn to_bytes(&self) -> Bytes {
+        let mut out = String::with_capacity(self.data.len() + 16);
+        if let Some(id) = &self.id {
+            let _ = writeln!(out, "id: {}", single_line(id));
+        }
+        if let Some(event) = &self.event {
+            let _ = writeln!(out, "event: {}", single_line(event));
+        }
+        if let Some(retry) = self.retry {
+            let _ = writeln!(out, "retry: {retry}");
+        }
+        for line in self.data.split('\n') {
+            let _ = writeln!(out, "data: {}", line.strip_suffix('\r').unwrap_or(line));
+        }
+        out.push('\n');
+        Bytes::from(out)
+    }
+}
+
+fn single_line(value: &str) -> String {
+    value.replace(['\r', '\n'], "")
+}
+
+/// Turn a channel of events into a channel of SSE-framed bytes
+///
+/// Each event is flushed as soon as it arrives. When `keep_alive` is set, a
+/// comment line is emitted after that much idle time. The output closes once
+/// the event channel closes or the client goes away.
+#[must_use]
+pub fn event_stream(
+    mut events: mpsc::Receiver<SseEvent>,
+    keep_alive: Option<Duration>,
+) -> mpsc::Receiver<Bytes> {
+    let (tx, rx) = mpsc::channel(16);
+
+    tokio::spawn(async move {
+        let Some(period) = keep_alive.filter(|d| !d.is_zero()) else {
+            while let Some(event) = events.recv().await {
+                if tx.send(event.to_bytes()).await.is_err() {
+                    break;
+                }
+            }
+            return;
+        };
+
+        let mut ticker = interval_at(Instant::now() + period, period);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            let chunk = tokio::select! {
+                event = events.recv() => match event {
+                    Some(event) => {
+                        ticker.reset();
+                        event.to_bytes()
+                    }
+                    None => break,
+                },
+                _ = ticker.tick() => Bytes::from_static(KEEP_ALIVE_COMMENT),
+            };
+            if tx.send(chunk).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_formatting() {
+        let event = SseEvent::new("hello\nworld")
+            .with_event("greeting")
+            .with_id("7")
+            .with_retry(3000);
+        assert_eq!(
+            event.to_bytes(),
+            "id: 7\nevent: greeting\nretry: 3000\ndata: hello\ndata: world\n\n"
+        );
+    }
+
+    #[test]
+    fn test_event_fields_cannot_inject_lines() {
+        let event = SseEvent::new("x").with_event("a\nid: 1");
+        assert_eq!(event.to_bytes(), "event: aid: 1\ndata: x\n\n");
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_forwards_events() {
+        let (tx, rx) = mpsc::channel(4);
+        let mut out = event_stream(rx, None);
+        tx.send(SseEvent::new("one")).await.unwrap();
+        drop(tx);
+        assert_eq!(out.recv().await.unwrap(), "data: one\n\n");
+        assert!(out.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_sends_keep_alive_when_idle() {
+        let (tx, rx) = mpsc::channel::<SseEvent>(4);
+        let mut out = event_stream(rx, Some(Duration::from_millis(20)));
+        assert_eq!(out.recv().await.unwrap(), KEEP_ALIVE_COMMENT);
+        drop(tx);
+    }
+}
@@ -0,0 +1,211 @@
+//! # Route Suggestions
+//!
+//! "Did you mean ...?" hints for requests no route matched: the same path
+//! registered under another method, then the routes closest to the path by
+//! edit distance or prefix. Only computed in debug mode, since every miss
+//! walks the whole route table.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only ranks candidate routes; the server decides where hints go
+//! - **D**: Works from `Router` exports, independent of handlers
+
+use crate::router::{Method, Router};
+
+/// Suggestions returned for one miss
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// A registered route close to a missed request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteSuggestion {
+    /// HTTP method of the route
+    pub method: String,
+    /// Path pattern of the route
+    pub path: String,
+    /// Lower is closer: `0` when only the method differs, `1` when the
+    /// route extends the request path, else the edit distance
+    pub score: usize,
+}
+
+impl RouteSuggestion {
+    /// `"METHOD /path"` label
+    #[must_use]
+    pub fn label(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+}
+
+/// Nearest registered routes for a `method` `path` request that missed
+///
+/// Routes matching `path` under another method come first, then routes
+/// extending `path` or within a small edit distance of it (parameters
+/// match any segment), closest first. At most [`MAX_SUGGESTIONS`] are returned.
+#[must_use]
+pub fn suggest_routes(router: &Router, method: Method, path: &str) -> Vec<RouteSuggestion> {
+    let method = method.to_string();
+    let request = path.trim_end_matches('/');
+    let max_distance = (request.len() / 4).max(2);
+    let mut suggestions: Vec<RouteSuggestion> = router
+        .export_table()
+        .routes
+        .into_iter()
+        .filter(|route| route.method != method)
+        .filter(|route| instantiate(&route.path, request) == request)
+        .filter_map(|route| {
+            let route_method = route.method.parse().ok()?;
+            router.match_route(route_method, path).ok()?;
+            Some(RouteSuggestion {
+                method: route.method,
+                path: route.path,
+                score: 0,
+            })
+        })
+        .collect();
+
+    let mut nearby: Vec<RouteSuggestion> = router
+        .export_table()
+        .routes
+        .into_iter()
+        .filter_map(|route| {
+            let candidate = instantiate(&route.path, request);
+            let distance = levenshtein(request, &candidate);
+            let extends = !request.is_empty()
+                && candidate.starts_with(request)
+                && candidate[request.len()..].starts_with('/');
+            let score = if extends { 1 } else { distance };
+            (distance > 0 && score <= max_distance).then(|| RouteSuggestion {
+                method: route.method,
+                path: route.path,
+                score,
+            })
+        })
+        .filter(|s| !suggestions.iter().any(|m| m.path == s.path))
+        .collect();
+    nearby.sort_by(|a, b| {
+        a.score
+            .cmp(&b.score)
+            .then_with(|| (a.method != method).cmp(&(b.method != method)))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    suggestions.extend(nearby);
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// `pattern` with each parameter replaced by the request's segment there
+///
+/// A catch-all takes the rest of the request path; parameters past the end
+/// of the request keep their `{...}` text.
+fn instantiate(pattern: &str, request: &str) -> String {
+    let mut values = request.split('/').skip(1);
+    let mut out = String::with_capacity(pattern.len());
+    for segment in pattern.trim_end_matches('/').split('/').skip(1) {
+        out.push('/');
+        let value = values.next();
+        let is_param = segment.starts_with('{') && segment.ends_with('}');
+        match value {
+            Some(value)
+                if is_param && (segment.starts_with("{*") || segment.ends_with(":path}")) =>
+            {
+                out.push_str(value);
+                for rest in values.by_ref() {
+                    out.push('/');
+                    out.push_str(rest);
+                }
+            }
+            Some(value) if is_param => out.push_str(value),
+            _ => out.push_str(segment),
+        }
+    }
+    out
+}
+
+/// Character-level Levenshtein distance
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn router() -> Router {
+        let mut router = Router::new();
+        router.add_route(Method::Get, "/users", false).unwrap();
+        router.add_route(Method::Post, "/users", true).unwrap();
+        router
+            .add_route(Method::Get, "/users/{id:int}", false)
+            .unwrap();
+        router
+            .add_route(Method::Get, "/orders/{id}/items", false)
+            .unwrap();
+        router.add_route(Method::Get, "/health", false).unwrap();
+        router
+    }
+
+    fn labels(suggestions: &[RouteSuggestion]) -> Vec<String> {
+        suggestions.iter().map(RouteSuggestion::label).collect()
+    }
+
+    #[test]
+    fn test_typos_and_other_methods_are_suggested() {
+        let router = router();
+        assert_eq!(
+            labels(&suggest_routes(&router, Method::Get, "/usres")),
+            vec!["GET /users", "POST /users"]
+        );
+        assert_eq!(
+            labels(&suggest_routes(&router, Method::Delete, "/users")),
+            vec!["GET /users", "POST /users", "GET /users/{id:int}"]
+        );
+        assert_eq!(
+            labels(&suggest_routes(&router, Method::Get, "/orders/7/item")),
+            vec!["GET /orders/{id}/items"]
+        );
+    }
+
+    #[test]
+    fn test_prefixes_are_suggested_and_unrelated_paths_are_not() {
+        let router = router();
+        assert_eq!(
+            labels(&suggest_routes(&router, Method::Get, "/orders/7")),
+            vec!["GET /orders/{id}/items"]
+        );
+        assert!(suggest_routes(&router, Method::Get, "/completely/unrelated").is_empty());
+    }
+
+    #[test]
+    fn test_other_methods_need_their_own_pattern_to_match() {
+        let mut router = Router::new();
+        router.add_route(Method::Get, "/items/{id}", false).unwrap();
+        router
+            .add_route(Method::Get, "/items/special", false)
+            .unwrap();
+        router
+            .add_route(Method::Put, "/items/special", false)
+            .unwrap();
+        assert_eq!(
+            labels(&suggest_routes(&router, Method::Delete, "/items/42")),
+            vec!["GET /items/{id}"]
+        );
+    }
+
+    #[test]
+    fn test_levenshtein() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("same", "same"), 0);
+    }
+}
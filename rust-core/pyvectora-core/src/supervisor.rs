@@ -0,0 +1,501 @@
+//! # Worker Supervisor
+//!
+//! Runs several worker processes sharing one listening port (`SO_REUSEPORT`)
+//! and keeps them alive: crashed or unresponsive workers are restarted and
+//! workers that served `max_requests` are replaced one at a time.
+//!
+//! Workers report over a Unix socket whose path is passed in
+//! [`SUPERVISOR_SOCKET_ENV`]. Each heartbeat line is `<worker id> <requests>`;
+//! the supervisor answers `stop` to retire a worker gracefully. A worker
+//! also stops when the supervisor goes away.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: The supervisor only manages process lifecycles; serving stays in `Server`
+//! - **D**: A worker is any command running a `Server`; the link is found via env
+
+use crate::signals::{self, ShutdownSignal};
+use crate::Result;
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::unix::OwnedWriteHalf;
+use tokio::net::{UnixListener, UnixStream};
+use tokio::process::{Child, Command};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+/// Environment variable carrying the worker id
+pub const WORKER_ID_ENV: &str = "PYVECTORA_WORKER_ID";
+
+/// Environment variable carrying the supervisor socket path
+pub const SUPERVISOR_SOCKET_ENV: &str = "PYVECTORA_SUPERVISOR_SOCKET";
+
+/// How often workers report and the supervisor checks on them
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Line sent to a worker to make it shut down gracefully
+const STOP_COMMAND: &str = "stop";
+
+/// Supervisor settings
+#[derive(Debug, Clone)]
+pub struct SupervisorConfig {
+    /// Number of workers kept running
+    pub workers: usize,
+    /// Requests after which a worker is replaced (`None` = never)
+    pub max_requests: Option<u64>,
+    /// Silence after which a worker is considered hung and killed
+    pub heartbeat_timeout: Duration,
+    /// Pause before replacing a crashed worker
+    pub restart_delay: Duration,
+    /// Time a stopping worker gets to drain before it is killed
+    pub shutdown_timeout: Duration,
+    /// Signals that stop the supervisor and its workers
+    pub shutdown_signals: Vec<ShutdownSignal>,
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            workers: std::thread::available_parallelism().map_or(1, usize::from),
+            max_requests: None,
+            heartbeat_timeout: Duration::from_secs(30),
+            restart_delay: Duration::from_secs(1),
+            shutdown_timeout: Duration::from_secs(30),
+            shutdown_signals: ShutdownSignal::defaults(),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Set the number of workers (at least 1)
+    #[must_use]
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Replace workers after they served `max_requests` requests
+    #[must_use]
+    pub const fn with_max_requests(mut self, max_requests: u64) -> Self {
+        self.max_requests = Some(max_requests);
+        self
+    }
+
+    /// Set how long a worker may go without a heartbeat
+    #[must_use]
+    pub const fn with_heartbeat_timeout(mut self, timeout: Duration) -> Self {
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Set the pause before replacing a crashed worker
+    #[must_use]
+    pub const fn with_restart_delay(mut self, delay: Duration) -> Self {
+        self.restart_delay = delay;
+        self
+    }
+
+    /// Set the signals that stop the supervisor
+    #[must_use]
+    pub fn with_shutdown_signals(mut self, signals: Vec<ShutdownSignal>) -> Self {
+        self.shutdown_signals = signals;
+        self
+    }
+}
+
+/// Parent process keeping a pool of workers running
+pub struct Supervisor {
+    program: OsString,
+    args: Vec<OsString>,
+    config: SupervisorConfig,
+}
+
+impl Supervisor {
+    /// Supervise workers started as `program args...`
+    #[must_use]
+    pub fn new<I, S>(program: impl Into<OsString>, args: I, config: SupervisorConfig) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<OsString>,
+    {
+        Self {
+            program: program.into(),
+            args: args.into_iter().map(Into::into).collect(),
+            config,
+        }
+    }
+
+    /// Run until Ctrl+C, then stop every worker gracefully
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the supervisor socket cannot be created or a
+    /// worker cannot be spawned.
+    pub async fn run(self) -> Result<()> {
+        let socket_path =
+            std::env::temp_dir().join(format!("pyvectora-supervisor-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&socket_path);
+        let listener = UnixListener::bind(&socket_path)?;
+        info!(
+            "Supervisor started with {} worker(s), pid {}",
+            self.config.workers,
+            std::process::id()
+        );
+
+        let signals = self.config.shutdown_signals.clone();
+        let mut pool = WorkerPool::new(self, socket_path);
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let mut tick = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let shutdown = signals::wait_for(&signals);
+        tokio::pin!(shutdown);
+
+        let result = loop {
+            tokio::select! {
+                accepted = listener.accept() => {
+                    if let Ok((stream, _)) = accepted {
+                        tokio::spawn(read_link(stream, events_tx.clone()));
+                    }
+                }
+                Some(event) = events_rx.recv() => pool.handle(event),
+                _ = tick.tick() => {
+                    if let Err(err) = pool.maintain().await {
+                        break Err(err);
+                    }
+                }
+                signal = &mut shutdown => {
+                    info!("{signal} received, stopping workers...");
+                    break Ok(());
+                }
+            }
+        };
+
+        pool.stop_all().await;
+        let _ = std::fs::remove_file(&pool.socket_path);
+        result
+    }
+}
+
+/// Message from a worker connection to the supervisor loop
+enum LinkEvent {
+    /// First heartbeat of a connection, with the channel back to the worker
+    Connected(u64, OwnedWriteHalf),
+    /// Request count reported by a worker
+    Heartbeat(u64, u64),
+}
+
+/// A running worker process
+struct Worker {
+    child: Child,
+    started: Instant,
+    last_heartbeat: Option<Instant>,
+    requests: u64,
+    link: Option<OwnedWriteHalf>,
+    /// When the worker was asked to stop (it no longer counts as active)
+    retired_at: Option<Instant>,
+}
+
+/// Worker processes and the bookkeeping to keep them at the target count
+struct WorkerPool {
+    supervisor: Supervisor,
+    socket_path: PathBuf,
+    workers: HashMap<u64, Worker>,
+    next_id: u64,
+    next_spawn_at: Instant,
+}
+
+impl WorkerPool {
+    fn new(supervisor: Supervisor, socket_path: PathBuf) -> Self {
+        Self {
+            supervisor,
+            socket_path,
+            workers: HashMap::new(),
+            next_id: 0,
+            next_spawn_at: Instant::now(),
+        }
+    }
+
+    const fn config(&self) -> &SupervisorConfig {
+        &self.supervisor.config
+    }
+
+    fn spawn(&mut self) -> Result<()> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let child = Command::new(&self.supervisor.program)
+            .args(&self.supervisor.args)
+            .env(WORKER_ID_ENV, id.to_string())
+            .env(SUPERVISOR_SOCKET_ENV, &self.socket_path)
+            .kill_on_drop(true)
+            .spawn()?;
+        info!("Started worker {} (pid {:?})", id, child.id());
+        self.workers.insert(
+            id,
+            Worker {
+                child,
+                started: Instant::now(),
+                last_heartbeat: None,
+                requests: 0,
+                link: None,
+                retired_at: None,
+            },
+        );
+        Ok(())
+    }
+
+    fn handle(&mut self, event: LinkEvent) {
+        match event {
+            LinkEvent::Connected(id, link) => {
+                if let Some(worker) = self.workers.get_mut(&id) {
+                    worker.link = Some(link);
+                }
+            }
+            LinkEvent::Heartbeat(id, requests) => {
+                if let Some(worker) = self.workers.get_mut(&id) {
+                    worker.last_heartbeat = Some(Instant::now());
+                    worker.requests = requests;
+                }
+            }
+        }
+    }
+
+    /// Reap exited workers, kill hung ones, recycle and top the pool up
+    async fn maintain(&mut self) -> Result<()> {
+        self.reap();
+        self.kill_unresponsive();
+        self.retire_exhausted().await;
+
+        let active = self
+            .workers
+            .values()
+            .filter(|w| w.retired_at.is_none())
+            .count();
+        if Instant::now() >= self.next_spawn_at {
+            for _ in active..self.config().workers {
+                self.spawn()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn reap(&mut self) {
+        let restart_delay = self.config().restart_delay;
+        let mut crashed = false;
+        self.workers.retain(|id, worker| {
+            let Ok(Some(status)) = worker.child.try_wait() else {
+                return true;
+            };
+            if worker.retired_at.is_none() && !status.success() {
+                warn!("Worker {} exited unexpectedly ({})", id, status);
+                crashed = true;
+            } else {
+                info!("Worker {} exited ({})", id, status);
+            }
+            false
+        });
+        if crashed {
+            self.next_spawn_at = Instant::now() + restart_delay;
+        }
+    }
+
+    fn kill_unresponsive(&mut self) {
+        let heartbeat_timeout = self.config().heartbeat_timeout;
+        let shutdown_timeout = self.config().shutdown_timeout;
+        for (id, worker) in &mut self.workers {
+            let overdue = match worker.retired_at {
+                Some(retired_at) => retired_at.elapsed() > shutdown_timeout,
+                None => {
+                    worker.last_heartbeat.unwrap_or(worker.started).elapsed() > heartbeat_timeout
+                }
+            };
+            if overdue {
+                warn!("Worker {} is unresponsive, killing it", id);
+                let _ = worker.child.start_kill();
+                worker.retired_at.get_or_insert_with(Instant::now);
+            }
+        }
+    }
+
+    /// Ask one worker past `max_requests` to stop; its replacement starts
+    /// right away, so at most one worker is draining at a time
+    async fn retire_exhausted(&mut self) {
+        let Some(max_requests) = self.config().max_requests else {
+            return;
+        };
+        if self.workers.values().any(|w| w.retired_at.is_some()) {
+            return;
+        }
+        let exhausted = self
+            .workers
+            .iter_mut()
+            .find(|(_, w)| w.requests >= max_requests && w.link.is_some());
+        if let Some((id, worker)) = exhausted {
+            info!(
+                "Worker {} served {} requests, recycling",
+                id, worker.requests
+            );
+            worker.retired_at = Some(Instant::now());
+            if let Some(link) = worker.link.as_mut() {
+                let _ = link.write_all(format!("{STOP_COMMAND}\n").as_bytes()).await;
+            }
+        }
+    }
+
+    /// Ask every worker to stop, killing those still running after the timeout
+    async fn stop_all(&mut self) {
+        for worker in self.workers.values_mut() {
+            if let Some(link) = worker.link.as_mut() {
+                let _ = link.write_all(format!("{STOP_COMMAND}\n").as_bytes()).await;
+            }
+        }
+        let deadline = tokio::time::Instant::now() + self.config().shutdown_timeout;
+        for (id, worker) in &mut self.workers {
+            if tokio::time::timeout_at(deadline, worker.child.wait())
+                .await
+                .is_err()
+            {
+                warn!("Worker {} did not stop in time, killing it", id);
+                let _ = worker.child.kill().await;
+            }
+        }
+        self.workers.clear();
+    }
+}
+
+/// Forward heartbeats from one worker connection to the supervisor loop
+async fn read_link(stream: UnixStream, events: mpsc::UnboundedSender<LinkEvent>) {
+    let (reader, writer) = stream.into_split();
+    let mut writer = Some(writer);
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Some((id, requests)) = parse_heartbeat(&line) else {
+            continue;
+        };
+        if let Some(writer) = writer.take() {
+            let _ = events.send(LinkEvent::Connected(id, writer));
+        }
+        if events.send(LinkEvent::Heartbeat(id, requests)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Parse a `<worker id> <requests>` heartbeat line
+fn parse_heartbeat(line: &str) -> Option<(u64, u64)> {
+    let (id, requests) = line.trim().split_once(' ')?;
+    Some((id.parse().ok()?, requests.parse().ok()?))
+}
+
+/// Connection from a worker process to its supervisor
+pub struct WorkerLink {
+    id: u64,
+    stream: UnixStream,
+}
+
+impl WorkerLink {
+    /// Connect to the supervisor named in the environment, if any
+    pub async fn from_env() -> Option<Self> {
+        let path = std::env::var_os(SUPERVISOR_SOCKET_ENV)?;
+        let id = std::env::var(WORKER_ID_ENV).ok()?.parse().ok()?;
+        match Self::connect(Path::new(&path), id).await {
+            Ok(link) => Some(link),
+            Err(err) => {
+                warn!("Could not reach supervisor: {}", err);
+                None
+            }
+        }
+    }
+
+    /// Connect to the supervisor socket at `path` as worker `id`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the socket cannot be connected.
+    pub async fn connect(path: &Path, id: u64) -> std::io::Result<Self> {
+        let stream = UnixStream::connect(path).await?;
+        Ok(Self { id, stream })
+    }
+
+    /// Report `requests` every `interval` until told to stop
+    ///
+    /// Resolves when the supervisor asks the worker to stop or goes away.
+    pub async fn run(self, requests: Arc<AtomicU64>, interval: Duration) {
+        let (reader, mut writer) = self.stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let mut tick = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = tick.tick() => {
+                    let line = format!("{} {}\n", self.id, requests.load(Ordering::Relaxed));
+                    if writer.write_all(line.as_bytes()).await.is_err() {
+                        return;
+                    }
+                }
+                line = lines.next_line() => match line {
+                    Ok(Some(command)) if command.trim() != STOP_COMMAND => {}
+                    _ => return,
+                },
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_heartbeat() {
+        assert_eq!(parse_heartbeat("3 120\n"), Some((3, 120)));
+        assert_eq!(parse_heartbeat("3"), None);
+        assert_eq!(parse_heartbeat("x 1"), None);
+    }
+
+    #[tokio::test]
+    async fn test_worker_link_reports_and_stops() {
+        let path =
+            std::env::temp_dir().join(format!("pyvectora-link-test-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let requests = Arc::new(AtomicU64::new(7));
+        let link = WorkerLink::connect(&path, 4).await.unwrap();
+        let worker = tokio::spawn(link.run(requests, Duration::from_millis(10)));
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+        let heartbeat = lines.next_line().await.unwrap().unwrap();
+        assert_eq!(parse_heartbeat(&heartbeat), Some((4, 7)));
+
+        writer.write_all(b"stop\n").await.unwrap();
+        tokio::time::timeout(Duration::from_secs(1), worker)
+            .await
+            .unwrap()
+            .unwrap();
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    async fn test_crashed_worker_is_restarted() {
+        let config = SupervisorConfig::default()
+            .with_workers(1)
+            .with_restart_delay(Duration::ZERO);
+        let supervisor = Supervisor::new("sh", ["-c", "exit 3"], config);
+        let mut pool = WorkerPool::new(supervisor, PathBuf::from("/nonexistent.sock"));
+
+        pool.maintain().await.unwrap();
+        assert_eq!(pool.workers.len(), 1);
+        for worker in pool.workers.values_mut() {
+            worker.child.wait().await.unwrap();
+        }
+        pool.maintain().await.unwrap();
+        assert_eq!(pool.workers.len(), 1);
+        assert_eq!(pool.next_id, 2);
+        pool.stop_all().await;
+    }
+}
@@ -0,0 +1,408 @@
+//! # TLS Termination
+//!
+//! rustls-based HTTPS support for the built-in server.
+//!
+//! Certificates are served from a `CertStore` that can be reloaded while
+//! the server runs, so renewed certificates (e.g. written by an ACME
+//! client) are picked up without a restart. Additional certificates can be
+//! registered per hostname and are selected via SNI.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only loads, reloads and serves certificates
+//! - **O**: Certificate sources beyond PEM files can be added as constructors
+//! - **D**: The accept loop depends on `TlsAcceptor`, not on rustls details
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::ServerConfig as RustlsConfig;
+use tokio_rustls::TlsAcceptor;
+use tracing::{info, warn};
+
+/// Certificate chain and private key locations (PEM)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// Path to the PEM certificate chain (leaf first)
+    pub cert_path: PathBuf,
+    /// Path to the PEM private key (PKCS#8, PKCS#1 or SEC1)
+    pub key_path: PathBuf,
+    /// How often to check the files for changes (no reloading when `None`)
+    pub reload_interval: Option<Duration>,
+    /// Per-hostname certificates selected via SNI
+    pub sni: Vec<SniCert>,
+}
+
+/// Certificate served to clients requesting a specific hostname via SNI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SniCert {
+    /// Hostname, or a `*.example.com` wildcard matching one label
+    pub hostname: String,
+    /// Path to the PEM certificate chain (leaf first)
+    pub cert_path: PathBuf,
+    /// Path to the PEM private key
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// Create a TLS configuration from certificate and key paths
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            reload_interval: None,
+            sni: Vec::new(),
+        }
+    }
+
+    /// Serve a different certificate to clients requesting `hostname`
+    ///
+    /// Clients without SNI, or asking for an unknown host, get the default
+    /// certificate.
+    #[must_use]
+    pub fn with_sni_cert(
+        mut self,
+        hostname: &str,
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> Self {
+        self.sni.push(SniCert {
+            hostname: normalize_hostname(hostname),
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+        });
+        self
+    }
+
+    /// Re-read the certificate and key when they change on disk
+    #[must_use]
+    pub const fn with_reload_interval(mut self, interval: Duration) -> Self {
+        self.reload_interval = Some(interval);
+        self
+    }
+
+    /// Load the certificate and key and build an acceptor
+    ///
+    /// ALPN advertises `h2` ahead of `http/1.1` when `http2` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tls` if a file cannot be read, contains no usable
+    /// certificate or key, or the key does not match the certificate.
+    pub fn acceptor(&self, http2: bool) -> Result<TlsAcceptor> {
+        Arc::new(CertStore::load(self.clone())?).acceptor(http2)
+    }
+}
+
+/// Hot-swappable certificate and key served to new TLS handshakes
+///
+/// Connections already established keep the certificate they negotiated.
+#[derive(Debug)]
+pub struct CertStore {
+    config: TlsConfig,
+    current: RwLock<Arc<LoadedCerts>>,
+    modified: Mutex<Option<Vec<SystemTime>>>,
+}
+
+/// Default certificate plus the SNI certificates, swapped as a unit
+#[derive(Debug)]
+struct LoadedCerts {
+    default: Arc<CertifiedKey>,
+    by_host: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl LoadedCerts {
+    fn load(config: &TlsConfig) -> Result<Self> {
+        let default = Arc::new(load_certified_key(&config.cert_path, &config.key_path)?);
+        let mut by_host = HashMap::with_capacity(config.sni.len());
+        for sni in &config.sni {
+            let key = load_certified_key(&sni.cert_path, &sni.key_path)?;
+            by_host.insert(sni.hostname.clone(), Arc::new(key));
+        }
+        Ok(Self { default, by_host })
+    }
+
+    /// Exact hostname first, then a one-label wildcard, then the default
+    fn resolve(&self, server_name: Option<&str>) -> Arc<CertifiedKey> {
+        let Some(name) = server_name.map(normalize_hostname) else {
+            return self.default.clone();
+        };
+        if let Some(key) = self.by_host.get(&name) {
+            return key.clone();
+        }
+        name.split_once('.')
+            .and_then(|(_, parent)| self.by_host.get(&format!("*.{parent}")))
+            .unwrap_or(&self.default)
+            .clone()
+    }
+}
+
+impl CertStore {
+    /// Load the certificate and key from `config`
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tls` if the files cannot be loaded or do not match.
+    pub fn load(config: TlsConfig) -> Result<Self> {
+        let certs = LoadedCerts::load(&config)?;
+        let modified = modification_times(&config);
+        Ok(Self {
+            config,
+            current: RwLock::new(Arc::new(certs)),
+            modified: Mutex::new(modified),
+        })
+    }
+
+    /// Configuration the store loads from
+    #[must_use]
+    pub const fn config(&self) -> &TlsConfig {
+        &self.config
+    }
+
+    /// Default certificate currently offered to new handshakes
+    #[must_use]
+    pub fn current(&self) -> Arc<CertifiedKey> {
+        self.resolve_host(None)
+    }
+
+    /// Certificate offered to a client requesting `server_name` via SNI
+    #[must_use]
+    pub fn resolve_host(&self, server_name: Option<&str>) -> Arc<CertifiedKey> {
+        self.current
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .resolve(server_name)
+    }
+
+    /// Re-read the files and swap in the new certificate
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tls` if loading fails; the previous certificate
+    /// stays in use.
+    pub fn reload(&self) -> Result<()> {
+        let certs = LoadedCerts::load(&self.config)?;
+        *self
+            .current
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = Arc::new(certs);
+        *self
+            .modified
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = modification_times(&self.config);
+        Ok(())
+    }
+
+    /// Reload only if any file's modification time changed
+    ///
+    /// Returns whether a new certificate was swapped in.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tls` if the changed files cannot be loaded.
+    pub fn reload_if_changed(&self) -> Result<bool> {
+        let modified = modification_times(&self.config);
+        let unchanged = *self
+            .modified
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            == modified;
+        if unchanged {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+
+    /// Build an acceptor that resolves certificates through this store
+    ///
+    /// ALPN advertises `h2` ahead of `http/1.1` when `http2` is set.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Tls` if the rustls configuration cannot be built.
+    pub fn acceptor(self: &Arc<Self>, http2: bool) -> Result<TlsAcceptor> {
+        let mut config = RustlsConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .map_err(|e| tls_error(&self.config.cert_path, e))?
+            .with_no_client_auth()
+            .with_cert_resolver(self.clone());
+        config.alpn_protocols = if http2 {
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+        } else {
+            vec![b"http/1.1".to_vec()]
+        };
+
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+
+    /// Poll the files every `interval` and reload them when they change
+    ///
+    /// Runs until the task is dropped or aborted. Failed reloads are logged
+    /// and the previous certificate keeps being served.
+    pub async fn watch(self: Arc<Self>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.tick().await;
+        loop {
+            ticker.tick().await;
+            match self.reload_if_changed() {
+                Ok(true) => info!(
+                    "Reloaded TLS certificate {}",
+                    self.config.cert_path.display()
+                ),
+                Ok(false) => {}
+                Err(err) => warn!("Keeping previous TLS certificate: {}", err),
+            }
+        }
+    }
+}
+
+impl ResolvesServerCert for CertStore {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        Some(self.resolve_host(client_hello.server_name()))
+    }
+}
+
+fn load_certified_key(cert_path: &Path, key_path: &Path) -> Result<CertifiedKey> {
+    let certs = load_certs(cert_path)?;
+    let key = load_key(key_path)?;
+    CertifiedKey::from_der(certs, key, &ring::default_provider())
+        .map_err(|e| tls_error(key_path, e))
+}
+
+fn modification_times(config: &TlsConfig) -> Option<Vec<SystemTime>> {
+    let sni_paths = config
+        .sni
+        .iter()
+        .flat_map(|sni| [sni.cert_path.as_path(), sni.key_path.as_path()]);
+    [config.cert_path.as_path(), config.key_path.as_path()]
+        .into_iter()
+        .chain(sni_paths)
+        .map(|path| std::fs::metadata(path).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+fn normalize_hostname(hostname: &str) -> String {
+    hostname.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let file = File::open(path).map_err(|e| tls_error(path, e))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<std::io::Result<Vec<_>>>()
+        .map_err(|e| tls_error(path, e))?;
+    if certs.is_empty() {
+        return Err(tls_error(path, "no certificates found"));
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let file = File::open(path).map_err(|e| tls_error(path, e))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .map_err(|e| tls_error(path, e))?
+        .ok_or_else(|| tls_error(path, "no private key found"))
+}
+
+fn tls_error(path: &Path, reason: impl std::fmt::Display) -> Error {
+    Error::Tls {
+        message: format!("{}: {reason}", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_certificate_file() {
+        let config = TlsConfig::new("/nonexistent/cert.pem", "/nonexistent/key.pem");
+        let Err(err) = config.acceptor(false) else {
+            panic!("expected missing certificate to fail");
+        };
+        assert!(matches!(err, Error::Tls { .. }));
+        assert!(err.to_string().contains("/nonexistent/cert.pem"));
+    }
+
+    #[test]
+    fn test_file_without_certificates() {
+        let path = std::env::temp_dir().join("pyvectora-tls-empty.pem");
+        std::fs::write(&path, "not a certificate\n").unwrap();
+        let err = load_certs(&path).unwrap_err();
+        assert!(err.to_string().contains("no certificates found"));
+        let _ = std::fs::remove_file(path);
+    }
+
+    fn write_self_signed(cert_path: &Path, key_path: &Path) {
+        let generated = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        std::fs::write(cert_path, generated.cert.pem()).unwrap();
+        std::fs::write(key_path, generated.key_pair.serialize_pem()).unwrap();
+    }
+
+    #[test]
+    fn test_reload_swaps_certificate() {
+        let dir = std::env::temp_dir().join(format!("pyvectora-tls-reload-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (cert_path, key_path) = (dir.join("cert.pem"), dir.join("key.pem"));
+        write_self_signed(&cert_path, &key_path);
+
+        let store = CertStore::load(TlsConfig::new(&cert_path, &key_path)).unwrap();
+        let before = store.current();
+        assert!(!store.reload_if_changed().unwrap());
+
+        write_self_signed(&cert_path, &key_path);
+        store.reload().unwrap();
+        assert_ne!(store.current().cert, before.cert);
+
+        std::fs::write(&key_path, "not a key\n").unwrap();
+        assert!(store.reload().is_err());
+        assert_ne!(store.current().cert, before.cert);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_sni_certificate_selection() {
+        let dir = std::env::temp_dir().join(format!("pyvectora-tls-sni-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = |name: &str| {
+            (
+                dir.join(format!("{name}.crt")),
+                dir.join(format!("{name}.key")),
+            )
+        };
+        let (default, tenant, wildcard) = (paths("default"), paths("tenant"), paths("wildcard"));
+        for (cert, key) in [&default, &tenant, &wildcard] {
+            write_self_signed(cert, key);
+        }
+
+        let config = TlsConfig::new(&default.0, &default.1)
+            .with_sni_cert("Shop.Example.com.", &tenant.0, &tenant.1)
+            .with_sni_cert("*.example.org", &wildcard.0, &wildcard.1);
+        let store = CertStore::load(config).unwrap();
+        let cert_of = |name: Option<&str>| store.resolve_host(name).cert.clone();
+
+        let tenant_cert = cert_of(Some("shop.example.com"));
+        assert_ne!(tenant_cert, cert_of(None));
+        assert_eq!(cert_of(Some("SHOP.example.com")), tenant_cert);
+        assert_ne!(cert_of(Some("a.example.org")), cert_of(None));
+        assert_eq!(cert_of(Some("a.b.example.org")), cert_of(None));
+        assert_eq!(cert_of(Some("unknown.test")), cert_of(None));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn test_reload_interval_builder() {
+        let config =
+            TlsConfig::new("cert.pem", "key.pem").with_reload_interval(Duration::from_secs(60));
+        assert_eq!(config.reload_interval, Some(Duration::from_secs(60)));
+        assert!(CertStore::load(config).is_err());
+    }
+}
@@ -28,6 +28,8 @@ pub enum ParamType {
     Float,
     /// Boolean type - parses "true"/"false" or "1"/"0"
     Bool,
+    /// Catch-all (`{*name}` or `{name:path}`) - remaining segments as a string
+    Path,
 }
 
 impl ParamType {
@@ -38,6 +40,7 @@ impl ParamType {
             "int" | "integer" | "i64" => Self::Int,
             "float" | "f64" | "number" => Self::Float,
             "bool" | "boolean" => Self::Bool,
+            "path" => Self::Path,
             _ => Self::String,
         }
     }
@@ -50,6 +53,7 @@ impl ParamType {
             Self::Int => "int",
             Self::Float => "float",
             Self::Bool => "bool",
+            Self::Path => "path",
         }
     }
 }
@@ -132,7 +136,7 @@ impl ParamValue {
 /// Returns `Error::InvalidRoutePattern` if conversion fails.
 pub fn convert_param(raw: &str, param_type: ParamType) -> Result<ParamValue> {
     match param_type {
-        ParamType::String => Ok(ParamValue::String(raw.to_string())),
+        ParamType::String | ParamType::Path => Ok(ParamValue::String(raw.to_string())),
         ParamType::Int => {
             raw.parse::<i64>()
                 .map(ParamValue::Int)
@@ -166,6 +170,7 @@ pub fn convert_param(raw: &str, param_type: ParamType) -> Result<ParamValue> {
 /// - `{id}` -> ("id", ParamType::String)
 /// - `{id:int}` -> ("id", ParamType::Int)
 /// - `{price:float}` -> ("price", ParamType::Float)
+/// - `{*path}` -> ("path", `ParamType::Path`)
 ///
 /// # Returns
 ///
@@ -175,6 +180,10 @@ pub fn parse_param_pattern(segment: &str) -> Option<(String, ParamType)> {
     if segment.starts_with('{') && segment.ends_with('}') {
         let inner = &segment[1..segment.len() - 1];
 
+        if let Some(name) = inner.strip_prefix('*') {
+            return Some((name.to_string(), ParamType::Path));
+        }
+
         if let Some((name, type_spec)) = inner.split_once(':') {
             Some((name.to_string(), ParamType::from_specifier(type_spec)))
         } else {
@@ -260,6 +269,14 @@ mod tests {
             parse_param_pattern("{price:float}"),
             Some(("price".to_string(), ParamType::Float))
         );
+        assert_eq!(
+            parse_param_pattern("{*path}"),
+            Some(("path".to_string(), ParamType::Path))
+        );
+        assert_eq!(
+            parse_param_pattern("{rest:path}"),
+            Some(("rest".to_string(), ParamType::Path))
+        );
         assert_eq!(parse_param_pattern("static"), None);
     }
 
@@ -8,9 +8,21 @@
 //! - **O**: Extensible error codes via enum
 //! - **L**: All validation errors implement common traits
 
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+#[cfg(feature = "python")]
+use pyo3::types::{PyDict, PyList};
 use serde::Serialize;
 use std::collections::HashMap;
 
+#[cfg(feature = "python")]
+pyo3::create_exception!(
+    pyvectora,
+    RequestValidationError,
+    pyo3::exceptions::PyValueError,
+    "Invalid request input (answered 422); `errors` lists the field errors."
+);
+
 /// Error code for categorizing validation failures
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
@@ -154,6 +166,55 @@ impl ValidationErrors {
     }
 }
 
+impl ValidationCode {
+    /// Code as serialized (`"INVALID_TYPE"`, ...)
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Required => "REQUIRED",
+            Self::InvalidType => "INVALID_TYPE",
+            Self::TooShort => "TOO_SHORT",
+            Self::TooLong => "TOO_LONG",
+            Self::TooSmall => "TOO_SMALL",
+            Self::TooLarge => "TOO_LARGE",
+            Self::InvalidFormat => "INVALID_FORMAT",
+            Self::NotUnique => "NOT_UNIQUE",
+            Self::InvalidChoice => "INVALID_CHOICE",
+            Self::Custom => "CUSTOM",
+        }
+    }
+}
+
+#[cfg(feature = "python")]
+impl ValidationErrors {
+    /// `RequestValidationError` carrying these errors in its `errors` attribute
+    pub fn into_py_err(self, py: Python<'_>) -> PyErr {
+        let message = self
+            .errors
+            .iter()
+            .map(|e| e.message.as_str())
+            .collect::<Vec<_>>()
+            .join("; ");
+        let err = RequestValidationError::new_err(message);
+        let errors = PyList::empty(py);
+        for error in &self.errors {
+            let dict = PyDict::new(py);
+            let items = dict
+                .set_item("field", &error.field)
+                .and_then(|()| dict.set_item("message", &error.message))
+                .and_then(|()| dict.set_item("code", error.code.as_str()))
+                .and_then(|()| errors.append(dict));
+            if let Err(e) = items {
+                return e;
+            }
+        }
+        if let Err(e) = err.value(py).setattr("errors", errors) {
+            return e;
+        }
+        err
+    }
+}
+
 /// Result type for validation operations
 pub type ValidationResult<T> = std::result::Result<T, ValidationErrors>;
 
@@ -202,6 +263,13 @@ mod tests {
         assert_eq!(e3.code, ValidationCode::TooLong);
     }
 
+    #[test]
+    fn test_code_as_str_matches_serialization() {
+        for code in [ValidationCode::InvalidType, ValidationCode::NotUnique] {
+            assert_eq!(serde_json::to_value(code).unwrap(), code.as_str());
+        }
+    }
+
     #[test]
     fn test_by_field() {
         let mut errors = ValidationErrors::new();
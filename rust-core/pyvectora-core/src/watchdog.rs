@@ -0,0 +1,177 @@
+//! # Memory Watchdog
+//!
+//! Samples the process resident set size (RSS) to protect long-running
+//! workers from slow leaks: above the soft limit new requests are refused
+//! with 503 until memory drops again, above the hard limit the server shuts
+//! down gracefully so a supervisor (or process manager) can replace it.
+//!
+//! RSS is read from `/proc/self/status`; where that is unavailable the
+//! watchdog stays idle.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only measures memory and classifies it against the limits
+//! - **D**: The server decides what refusing work and recycling mean
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Memory thresholds in bytes
+#[derive(Debug, Clone)]
+pub struct MemoryLimits {
+    /// RSS above which new requests are refused with 503
+    pub soft_limit: Option<u64>,
+    /// RSS above which the server shuts down gracefully
+    pub hard_limit: Option<u64>,
+    /// How often RSS is sampled (default: 5 seconds)
+    pub check_interval: Duration,
+}
+
+impl Default for MemoryLimits {
+    fn default() -> Self {
+        Self {
+            soft_limit: None,
+            hard_limit: None,
+            check_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Where the current RSS sits relative to the limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MemoryState {
+    /// Below every limit
+    Normal,
+    /// Above the soft limit: refuse new work
+    Overloaded,
+    /// Above the hard limit: recycle the process
+    Exhausted,
+}
+
+impl MemoryLimits {
+    /// Refuse new requests above `bytes`
+    #[must_use]
+    pub const fn with_soft_limit(mut self, bytes: u64) -> Self {
+        self.soft_limit = Some(bytes);
+        self
+    }
+
+    /// Shut down gracefully above `bytes`
+    #[must_use]
+    pub const fn with_hard_limit(mut self, bytes: u64) -> Self {
+        self.hard_limit = Some(bytes);
+        self
+    }
+
+    /// Set how often RSS is sampled
+    #[must_use]
+    pub const fn with_check_interval(mut self, interval: Duration) -> Self {
+        self.check_interval = interval;
+        self
+    }
+
+    /// Classify an RSS reading
+    #[must_use]
+    pub fn classify(&self, rss: u64) -> MemoryState {
+        if self.hard_limit.is_some_and(|limit| rss >= limit) {
+            MemoryState::Exhausted
+        } else if self.soft_limit.is_some_and(|limit| rss >= limit) {
+            MemoryState::Overloaded
+        } else {
+            MemoryState::Normal
+        }
+    }
+}
+
+/// Resident set size of the current process in bytes, if known
+#[must_use]
+pub fn current_rss() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_rss(&status)
+}
+
+/// Extract `VmRSS` (reported in kB) from `/proc/<pid>/status` contents
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kilobytes: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kilobytes * 1024)
+}
+
+/// Sample RSS until the hard limit is reached
+///
+/// `overloaded` tracks the soft limit for the request path. Never resolves
+/// when RSS cannot be read.
+pub async fn watch(limits: MemoryLimits, overloaded: Arc<AtomicBool>) {
+    let mut tick = tokio::time::interval(limits.check_interval);
+    loop {
+        tick.tick().await;
+        let Some(rss) = current_rss() else {
+            warn!("Memory watchdog disabled: RSS is not available on this platform");
+            return std::future::pending().await;
+        };
+        let state = limits.classify(rss);
+        let was_overloaded = overloaded.swap(state != MemoryState::Normal, Ordering::Relaxed);
+        match state {
+            MemoryState::Exhausted => {
+                warn!("RSS {} bytes reached the hard memory limit", rss);
+                return;
+            }
+            MemoryState::Overloaded if !was_overloaded => {
+                warn!(
+                    "RSS {} bytes above the soft memory limit, refusing new requests",
+                    rss
+                );
+            }
+            MemoryState::Normal if was_overloaded => {
+                info!(
+                    "RSS {} bytes back under the memory limit, accepting requests",
+                    rss
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_rss() {
+        let status = "Name:\tpython\nVmPeak:\t  900 kB\nVmRSS:\t    2048 kB\nThreads:\t4\n";
+        assert_eq!(parse_vm_rss(status), Some(2048 * 1024));
+        assert_eq!(parse_vm_rss("Name:\tpython\n"), None);
+    }
+
+    #[test]
+    fn test_classify() {
+        let limits = MemoryLimits::default()
+            .with_soft_limit(100)
+            .with_hard_limit(200);
+        assert_eq!(limits.classify(50), MemoryState::Normal);
+        assert_eq!(limits.classify(150), MemoryState::Overloaded);
+        assert_eq!(limits.classify(250), MemoryState::Exhausted);
+        assert_eq!(
+            MemoryLimits::default().classify(u64::MAX),
+            MemoryState::Normal
+        );
+    }
+
+    #[tokio::test]
+    async fn test_watch_resolves_at_hard_limit() {
+        if current_rss().is_none() {
+            return;
+        }
+        let limits = MemoryLimits::default()
+            .with_hard_limit(1)
+            .with_check_interval(Duration::from_millis(10));
+        let overloaded = Arc::new(AtomicBool::new(false));
+        tokio::time::timeout(Duration::from_secs(1), watch(limits, overloaded.clone()))
+            .await
+            .unwrap();
+        assert!(overloaded.load(Ordering::Relaxed));
+    }
+}
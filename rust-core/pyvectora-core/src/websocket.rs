@@ -0,0 +1,179 @@
+//! # WebSocket Support
+//!
+//! HTTP/1.1 upgrade handling and WebSocket route dispatch.
+//!
+//! ## Design Principles (SOLID)
+//!
+//! - **S**: Only handles the upgrade handshake and socket hand-off
+//! - **O**: Handlers receive a plain `WebSocket` stream, protocol logic lives in callers
+//! - **D**: Server depends on the `WsHandler` abstraction, not Python specifics
+
+use crate::events::{self, Event, EventKind};
+use crate::request::PyRequest;
+use crate::router::Method;
+use hyper::header::{self, HeaderValue};
+use hyper::upgrade::Upgraded;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::WebSocketStream;
+use tracing::{debug, error};
+
+pub use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+pub use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+pub use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+
+/// Server-side WebSocket connection over an upgraded HTTP connection
+pub type WebSocket = WebSocketStream<TokioIo<Upgraded>>;
+
+/// WebSocket handler function type (async)
+///
+/// Receives the upgrade request (headers, path and typed params) and
+/// owns the socket until the returned future completes.
+pub type WsHandler =
+    Arc<dyn Fn(PyRequest, WebSocket) -> Pin<Box<dyn Future<Output = ()> + Send>> + Send + Sync>;
+
+/// Check whether a request asks for a WebSocket upgrade
+///
+/// Requires `Connection: upgrade`, `Upgrade: websocket`, version 13 and a
+/// `Sec-WebSocket-Key` header, per RFC 6455 section 4.2.1.
+#[must_use]
+pub fn is_upgrade_request<B>(req: &Request<B>) -> bool {
+    let headers = req.headers();
+    let connection_upgrade = headers
+        .get_all(header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|token| token.trim().eq_ignore_ascii_case("upgrade"));
+    let upgrade_websocket = headers
+        .get(header::UPGRADE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.eq_ignore_ascii_case("websocket"));
+    let version_ok = headers
+        .get(header::SEC_WEBSOCKET_VERSION)
+        .is_some_and(|v| v == "13");
+
+    req.method() == hyper::Method::GET
+        && connection_upgrade
+        && upgrade_websocket
+        && version_ok
+        && headers.contains_key(header::SEC_WEBSOCKET_KEY)
+}
+
+/// Accept a WebSocket upgrade and hand the socket to `handler`
+///
+/// Returns the `101 Switching Protocols` response immediately; the handler
+/// runs on a spawned task once hyper completes the upgrade.
+pub fn accept<B>(
+    req: &mut Request<B>,
+    typed_params: impl IntoIterator<Item = (String, crate::types::ParamValue)>,
+    handler: WsHandler,
+) -> Response<()>
+where
+    B: Send + 'static,
+{
+    let Some(accept_key) = req
+        .headers()
+        .get(header::SEC_WEBSOCKET_KEY)
+        .map(|key| derive_accept_key(key.as_bytes()))
+    else {
+        return Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(())
+            .unwrap_or_default();
+    };
+
+    let headers: HashMap<String, String> = req
+        .headers()
+        .iter()
+        .filter_map(|(k, v)| v.to_str().ok().map(|v| (k.to_string(), v.to_string())))
+        .collect();
+    let target = req
+        .uri()
+        .path_and_query()
+        .map_or_else(|| req.uri().path().to_string(), ToString::to_string);
+    let mut py_request = PyRequest::new(Method::Get, target, headers, None);
+    py_request.typed_params = typed_params.into_iter().collect();
+
+    let on_upgrade = hyper::upgrade::on(req);
+    tokio::spawn(async move {
+        match on_upgrade.await {
+            Ok(upgraded) => {
+                let socket =
+                    WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None)
+                        .await;
+                debug!(path = %py_request.path, "WebSocket connection opened");
+                if events::is_active(EventKind::WsConnected) {
+                    events::emit(&Event::WsConnected {
+                        path: py_request.path.clone(),
+                    });
+                }
+                handler(py_request, socket).await;
+            }
+            Err(e) => error!("WebSocket upgrade failed: {}", e),
+        }
+    });
+
+    let mut response = Response::new(());
+    *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    let headers = response.headers_mut();
+    headers.insert(header::CONNECTION, HeaderValue::from_static("upgrade"));
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    if let Ok(value) = HeaderValue::from_str(&accept_key) {
+        headers.insert(header::SEC_WEBSOCKET_ACCEPT, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn upgrade_request() -> hyper::http::request::Builder {
+        Request::builder()
+            .method("GET")
+            .uri("/ws")
+            .header("Connection", "keep-alive, Upgrade")
+            .header("Upgrade", "websocket")
+            .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", "dGhlIHNhbXBsZSBub25jZQ==")
+    }
+
+    #[test]
+    fn test_is_upgrade_request() {
+        let req = upgrade_request().body(()).unwrap();
+        assert!(is_upgrade_request(&req));
+    }
+
+    #[test]
+    fn test_plain_request_is_not_upgrade() {
+        let req = Request::builder().uri("/ws").body(()).unwrap();
+        assert!(!is_upgrade_request(&req));
+    }
+
+    #[test]
+    fn test_upgrade_requires_get() {
+        let req = upgrade_request().method("POST").body(()).unwrap();
+        assert!(!is_upgrade_request(&req));
+    }
+
+    #[tokio::test]
+    async fn test_accept_returns_switching_protocols() {
+        let mut req = upgrade_request().body(()).unwrap();
+        let handler: WsHandler = Arc::new(|_req, _socket| Box::pin(async {}));
+
+        let resp = accept(&mut req, HashMap::new(), handler);
+        assert_eq!(resp.status(), StatusCode::SWITCHING_PROTOCOLS);
+        // Sample key/accept pair from RFC 6455 section 1.3
+        assert_eq!(
+            resp.headers().get(header::SEC_WEBSOCKET_ACCEPT).unwrap(),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+}
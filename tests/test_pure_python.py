@@ -7,3 +7,908 @@ def test_response_headers():
 def test_request_text():
     req = Request(body="hello")
     assert req.text == "hello"
+
+
+def test_enable_acme_validates_and_stores_settings():
+    import os
+    from pyvectora import App
+
+    app = App()
+    challenges = object()
+    for kwargs in ({}, {"domains": [], "agree_tos": True},
+                   {"renew_before_days": 0, "agree_tos": True},
+                   {"http01_port": 70000, "agree_tos": True}):
+        try:
+            app.enable_acme(**{"domains": ["example.com"], **kwargs}, challenges=challenges)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"accepted {kwargs}")
+    assert app._acme is None
+
+    store = app.enable_acme(["example.com"], email="ops@example.com", storage="certs",
+                            agree_tos=True, challenges=challenges)
+    assert store is challenges
+    assert app._acme["renew_before"] == 30 * 86400
+    assert app._acme["http01_port"] == 80
+    assert app._tls[:2] == (os.path.join("certs", "cert.pem"), os.path.join("certs", "key.pem"))
+    try:
+        app.serve(workers=2)
+    except ValueError as exc:
+        assert "single worker" in str(exc)
+    else:
+        raise AssertionError("several workers accepted with ACME")
+
+
+def test_response_set_cookie():
+    resp = Response.json({}).set_cookie("a", "1", http_only=True).set_cookie("b", "two%20words")
+    assert resp.extra_headers == [
+        ("Set-Cookie", "a=1; Path=/; HttpOnly; SameSite=Lax"),
+        ("Set-Cookie", "b=two%20words; Path=/; SameSite=Lax"),
+    ]
+    for kwargs in (
+        {"name": "a", "value": "1; Domain=evil.example"},
+        {"name": "a", "value": "two words"},
+        {"name": "a=b", "value": "1"},
+        {"name": "a", "value": "1", "path": "/; HttpOnly"},
+        {"name": "a", "value": "1", "domain": "example.com\r\nX-Injected: 1"},
+    ):
+        try:
+            Response.json({}).set_cookie(**kwargs)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"accepted {kwargs}")
+
+def test_request_session_requires_sessions():
+    assert Request(session={"user_id": 1}).session["user_id"] == 1
+    try:
+        Request().session
+    except RuntimeError:
+        pass
+    else:
+        raise AssertionError("expected RuntimeError")
+
+
+def test_request_form_parses_urlencoded_body():
+    request = Request(method="POST", body="user=ada+l&next=%2Fhome&user=grace")
+    assert request.form() == {"user": "grace", "next": "/home"}
+    assert Request(form={"title": "report"}).form() == {"title": "report"}
+    assert Request().files() == {}
+
+
+def test_health_registry_aggregates_and_caches():
+    import asyncio
+    from pyvectora import HealthRegistry
+
+    calls = []
+    health = HealthRegistry(cache_interval=60)
+    health.register("db", lambda: calls.append("db"))
+
+    @health.probe("cache", critical=False)
+    async def cache():
+        raise ConnectionError("refused")
+
+    @health.probe("search", timeout=0.01)
+    async def search():
+        await asyncio.sleep(1)
+
+    async def run():
+        first = await health.check()
+        second = await health.check()
+        return first, second
+
+    first, second = asyncio.run(run())
+    assert first is second and calls == ["db"]
+    assert first["status"] == "down"
+    assert first["checks"]["db"]["status"] == "up"
+    assert first["checks"]["cache"]["error"] == "ConnectionError: refused"
+    assert first["checks"]["search"]["error"].startswith("timed out")
+
+
+def test_events_subscribe_validates_callbacks():
+    from pyvectora import events
+
+    async def async_subscriber(event):
+        pass
+
+    for name, callback, error in [
+        ("request_done", print, ValueError),
+        ("db_query", async_subscriber, TypeError),
+    ]:
+        try:
+            events.subscribe(name, callback)
+        except error:
+            pass
+        else:
+            raise AssertionError(f"expected {error.__name__}")
+
+
+def test_request_typed_query_accessors():
+    from pyvectora import RequestValidationError
+
+    request = Request(query={"page": "2", "ratio": "0.5", "draft": "Off", "limit": "ten"})
+    assert request.query_int("page", default=1) == 2
+    assert request.query_int("missing", default=1) == 1
+    assert request.query_float("ratio") == 0.5
+    assert request.query_bool("draft") is False
+    try:
+        request.query_int("limit")
+    except RequestValidationError as exc:
+        assert exc.errors == [
+            {"field": "limit", "message": "limit must be an integer", "code": "INVALID_TYPE"}
+        ]
+    else:
+        raise AssertionError("expected RequestValidationError")
+
+
+def test_request_client_and_url():
+    request = Request(
+        path="/search",
+        query={"q": "rust"},
+        raw_headers=[("Host", "example.com")],
+        client=("10.0.0.7", 52114),
+        scheme="https",
+    )
+    assert request.client == ("10.0.0.7", 52114)
+    assert request.url == "https://example.com/search?q=rust"
+    assert Request().url == "http://localhost/"
+
+
+def test_error_reporting_config_is_validated():
+    from pyvectora import App
+
+    app = App()
+    app.enable_error_reporting(
+        "https://key@sentry.example.com/42", sample_rate=0.25, environment="staging"
+    )
+    assert app._error_reporting == {
+        "dsn": "https://key@sentry.example.com/42",
+        "sample_rate": 0.25,
+        "environment": "staging",
+        "release": None,
+    }
+    for dsn, rate in [("", 1.0), ("https://key@sentry.example.com/42", 1.5)]:
+        try:
+            app.enable_error_reporting(dsn, sample_rate=rate)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError("expected ValueError")
+
+
+def test_log_records_carry_request_context():
+    import logging
+
+    from pyvectora import logging as log_context
+
+    log_context.install()
+    records = []
+
+    class Collect(logging.Handler):
+        def emit(self, record):
+            records.append(record)
+
+    logger = logging.getLogger("pyvectora.test.log_context")
+    logger.addHandler(Collect())
+    logger.propagate = False
+    logger.warning("outside")
+    with log_context.bind(request_id="req-1", route="GET /orders", tenant="acme"):
+        logger.warning("inside")
+        assert log_context.current_context()["user"] is None
+    assert (records[0].request_id, records[0].route) == (None, None)
+    assert (records[1].request_id, records[1].route, records[1].tenant) == (
+        "req-1",
+        "GET /orders",
+        "acme",
+    )
+
+
+def test_tracing_handler_forwards_records_with_context():
+    import logging
+
+    from pyvectora import logging as log_context
+
+    calls = []
+    original = log_context._log_to_tracing
+    log_context._log_to_tracing = lambda *args: calls.append(args)
+    try:
+        logger = logging.getLogger("pyvectora.test.bridge")
+        logger.propagate = False
+        handler = log_context.forward_to_tracing("pyvectora.test.bridge")
+        with log_context.bind(request_id="req-7", user="42"):
+            logger.error("payment %s failed", "p-1")
+        logger.removeHandler(handler)
+    finally:
+        log_context._log_to_tracing = original
+    assert calls == [
+        (40, "pyvectora.test.bridge", "payment p-1 failed", "req-7", None, None, "42")
+    ]
+
+
+def test_streaming_routes_and_request_stream():
+    import asyncio
+
+    from pyvectora import App, Request
+
+    app = App()
+
+    @app.post("/uploads", stream=True)
+    async def upload(request):
+        return {"size": sum([len(chunk) async for chunk in request.stream()])}
+
+    app.post("/forms", lambda request: {})
+    assert [(r.path, r.stream) for r in app._routes] == [("/uploads", True), ("/forms", False)]
+
+    async def read(request):
+        return [chunk async for chunk in request.stream()]
+
+    assert asyncio.run(read(Request(body="payload"))) == [b"payload"]
+    assert asyncio.run(read(Request())) == []
+    assert asyncio.run(upload(Request(body="abc"))) == {"size": 3}
+
+
+def test_route_body_limits_and_timeouts():
+    from pyvectora import App
+
+    app = App()
+
+    @app.post("/uploads", max_body=10 * 1024 * 1024, timeout=30)
+    async def upload(request):
+        return {}
+
+    app.get("/report", lambda request: {}, timeout=2.5)
+    app.set_handler_timeout(5)
+    routes = {r.path: (r.max_body, r.timeout) for r in app._routes}
+    assert routes == {"/uploads": (10 * 1024 * 1024, 30), "/report": (None, 2.5)}
+
+    for bad in ({"timeout": 0}, {"max_body": -1}):
+        try:
+            app.put("/bad", lambda request: {}, **bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad} was accepted")
+
+
+def test_uri_limits():
+    from pyvectora import App
+
+    app = App()
+    app.set_uri_limits(max_length=2048)
+    assert app._uri_limits == {"max_length": 2048, "max_query_params": None}
+    app.set_uri_limits(max_query_params=50)
+    assert app._uri_limits == {"max_length": None, "max_query_params": 50}
+
+    try:
+        app.set_uri_limits(max_query_params=0)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("max_query_params=0 was accepted")
+
+
+def test_duplicate_routes():
+    import warnings
+
+    from pyvectora import App
+
+    app = App()
+    app.get("/users/{id:int}", lambda request: {"first": True})
+    app.post("/users/{id}", lambda request: {})
+    try:
+        app.get("/users/{name}", lambda request: {})
+    except ValueError as exc:
+        assert "GET /users/{id:int}" in str(exc)
+    else:
+        raise AssertionError("duplicate route was accepted")
+
+    app.set_duplicate_routes("warn")
+    with warnings.catch_warnings(record=True) as caught:
+        warnings.simplefilter("always")
+        app.get("/users/{name}", lambda request: {"second": True})
+    assert [w.category for w in caught] == [RuntimeWarning]
+    assert [(r.method, r.path) for r in app._routes] == [
+        ("POST", "/users/{id}"),
+        ("GET", "/users/{name}"),
+    ]
+    app.get("/files/{*path}", lambda request: {})
+    with warnings.catch_warnings(record=True):
+        warnings.simplefilter("always")
+        app.get("/files/{*rest}", lambda request: {})
+    assert [r.path for r in app._routes][-1:] == ["/files/{*rest}"]
+    assert len(app._routes) == 3
+
+
+def test_parse_json_routes():
+    from pyvectora import App
+
+    app = App()
+
+    @app.post("/orders", parse_json=True)
+    async def create_order(request, body):
+        return body
+
+    app.put("/orders/{id}", lambda request, body: body, parse_json=True)
+    app.get("/orders", lambda request: [])
+    flags = {(r.method, r.path): r.parse_json for r in app._routes}
+    assert flags == {
+        ("POST", "/orders"): True,
+        ("PUT", "/orders/{id}"): True,
+        ("GET", "/orders"): False,
+    }
+
+
+def test_request_state():
+    from pyvectora import Request
+
+    request = Request()
+    request.state.user = {"id": 7}
+    assert request.state.user == {"id": 7}
+    assert "user" in request.state
+    assert request.state.get("tenant", "default") == "default"
+    del request.state.user
+    assert "user" not in request.state
+
+
+def test_spooled_response():
+    import os
+
+    from pyvectora import Response
+
+    small = Response.spooled(content_type="text/csv", filename="report.csv", threshold=8)
+    small.write("a,b\n")
+    assert small.path is None
+    assert small.getvalue() == b"a,b\n"
+    assert small.headers["Content-Disposition"] == 'attachment; filename="report.csv"'
+
+    large = Response.spooled(threshold=8)
+    large.writelines(["0123", "4567", b"89"])
+    path = large.path
+    assert path is not None and os.path.exists(path)
+    assert large.getvalue() == b"0123456789"
+    del large
+    assert not os.path.exists(path)
+
+
+def test_middleware_can_change_the_request():
+    from pyvectora import Request
+
+    def strip_version(request):
+        request.set_header("X-Tenant", "acme")
+        request.remove_header("x-debug")
+        if request.path.startswith("/v1/"):
+            request.path = request.path[3:]
+
+    request = Request(path="/v1/items", raw_headers=[("X-Debug", "1"), ("X-Tenant", "old")])
+    assert strip_version(request) is None
+    assert request.path == "/items"
+    assert request.headers == {"x-tenant": "acme"}
+    try:
+        request.path = "items"
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("relative path accepted")
+
+
+def test_deprecated_routes():
+    from datetime import date, datetime
+
+    from pyvectora import App
+
+    app = App()
+
+    @app.get("/v1/users", deprecated_after=date(2027, 1, 1))
+    async def list_users(request):
+        return []
+
+    app.post("/v1/users", lambda request: {}, deprecated_after="2027-03-31")
+    app.delete("/v1/users/{id}", lambda request: {}, deprecated_after=datetime(2027, 6, 1, 12))
+    app.get("/v2/users", lambda request: [])
+    sunsets = {(r.method, r.path): r.deprecated_after for r in app._routes}
+    assert sunsets == {
+        ("GET", "/v1/users"): "2027-01-01",
+        ("POST", "/v1/users"): "2027-03-31",
+        ("DELETE", "/v1/users/{id}"): "2027-06-01",
+        ("GET", "/v2/users"): None,
+    }
+
+    app.set_deprecation(link="https://docs.example.com/v2", gone="Use /v2/users")
+    assert app._deprecation == {"link": "https://docs.example.com/v2", "gone": "Use /v2/users"}
+    try:
+        app.put("/v1/users/{id}", lambda request: {}, deprecated_after="2027-02-30")
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("invalid date accepted")
+
+
+def test_canary_routes():
+    from pyvectora import App
+
+    app = App()
+    app.get("/search", lambda request: {"version": 1})
+
+    @app.canary("GET", "/search", percent=10, sticky_header="X-User-Id", ramp=600)
+    async def search_v2(request):
+        return {"version": 2}
+
+    (route,) = app._routes
+    assert route.canary == {
+        "handler": search_v2,
+        "percent": 10,
+        "cookie": "pv_canary",
+        "sticky_header": "X-User-Id",
+        "ramp": 600,
+    }
+
+    for bad in (
+        {"method": "GET", "path": "/missing"},
+        {"method": "GET", "path": "/search", "percent": 101},
+        {"method": "GET", "path": "/search", "sticky_cookie": "sid", "sticky_header": "X"},
+    ):
+        try:
+            app.canary(handler=search_v2, **bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad} was accepted")
+
+
+def test_metrics_labels_are_allowlisted():
+    from pyvectora import App
+
+    app = App()
+    app.enable_metrics(labels=["route", "status_class"], max_series=200)
+    assert app._metrics == {
+        "path": "/metrics",
+        "labels": ["route", "status_class"],
+        "max_series": 200,
+    }
+    for bad in ({"labels": ["path"]}, {"max_series": 0}):
+        try:
+            app.enable_metrics(**bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad} was accepted")
+
+
+def test_fetch_cached_rows():
+    import asyncio
+
+    from pyvectora.database import CachedRows, Database
+
+    class FakeNative:
+        async def fetch_cached(self, query, params, ttl, tags):
+            self.call = (query, params, ttl, tags)
+            return [{"id": 1}], '"abc"', False
+
+    db = Database.__new__(Database)
+    db._db = FakeNative()
+    rows = asyncio.run(db.fetch_cached("SELECT * FROM plans WHERE id = ?", [1], tags=["plans"]))
+    assert rows == [{"id": 1}] and rows.etag == '"abc"' and not rows.hit
+    assert db._db.call == ("SELECT * FROM plans WHERE id = ?", [1], 30.0, ["plans"])
+
+    cached = CachedRows([], '"abc"', True)
+    assert cached.matches('"xyz", "abc"') and cached.matches('W/"abc"') and cached.matches("*")
+    assert not cached.matches(None) and not cached.matches('"xyz"')
+    try:
+        asyncio.run(db.fetch_cached("SELECT 1", ttl=0))
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("ttl=0 was accepted")
+
+
+def test_raw_values_keep_text_and_type():
+    import json
+    from decimal import Decimal
+
+    from pyvectora.database import RawValue
+
+    price = RawValue("12345.678", "NUMERIC")
+    assert price == "12345.678" and price.type_name == "NUMERIC"
+    assert Decimal(price) == Decimal("12345.678")
+    assert json.loads(RawValue('{"a": 1}', "JSONB")) == {"a": 1}
+    assert repr(price) == "RawValue('12345.678', type_name='NUMERIC')"
+
+
+def test_read_only_mode():
+    from pyvectora import App
+
+    app = App()
+    app.read_only(False, allow=["/admin/read-only"], databases=True)
+    assert app._read_only == {
+        "enabled": False,
+        "allow": ["/admin/read-only"],
+        "databases": True,
+    }
+
+    class FakeNative:
+        def set_read_only(self, enabled, allow, databases):
+            self.call = (enabled, allow, databases)
+
+    app.native_app = FakeNative()
+    app.read_only()
+    assert app.native_app.call == (True, ["/admin/read-only"], True)
+    try:
+        app.read_only(allow=["admin"])
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("a relative path was accepted")
+
+
+def test_shutdown_signals():
+    from pyvectora import App
+
+    app = App()
+    assert app._shutdown_signals is None
+    app.set_shutdown_signals(["SIGTERM", "sigint", "CTRL_BREAK"])
+    assert app._shutdown_signals == ["SIGTERM", "SIGINT", "CTRL_BREAK"]
+    app.set_shutdown_signals([])
+    assert app._shutdown_signals == []
+    try:
+        app.set_shutdown_signals(["SIGKILL"])
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("SIGKILL was accepted")
+
+
+def test_warmup_requests():
+    from pyvectora import App
+
+    app = App()
+    app.warmup([("get", "/heavy"), ("POST", "/search", {"X-Warmup": "1"})])
+    assert app._warmup == [("GET", "/heavy", None), ("POST", "/search", {"X-Warmup": "1"})]
+
+    class FakeResponse:
+        status = 200
+
+    class FakeServer:
+        def __init__(self):
+            self.sent = []
+
+        def test_request(self, method, path, headers, body):
+            self.sent.append((method, path, headers))
+            if path == "/search":
+                raise RuntimeError("boom")
+            return FakeResponse()
+
+    class FakeNative:
+        server = FakeServer()
+
+        def test_client(self):
+            return self.server
+
+    native = FakeNative()
+    app._run_warmup(native, app._warmup)
+    assert native.server.sent == [
+        ("GET", "/heavy", None),
+        ("POST", "/search", {"X-Warmup": "1"}),
+    ]
+    for bad in ([("FETCH", "/x")], [("GET", "x")], [("GET",)]):
+        try:
+            app.warmup(bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad} was accepted")
+
+
+def test_limit_errors_are_validation_errors():
+    from pyvectora import PayloadTooLarge, RequestLimitError, UriTooLong, ValidationError
+    from pyvectora.test_client import TestClient
+
+    assert issubclass(PayloadTooLarge, RequestLimitError)
+    assert issubclass(UriTooLong, RequestLimitError)
+    assert issubclass(RequestLimitError, ValidationError)
+
+    class FakeServer:
+        def test_request(self, method, path, headers, body, raise_limit_errors=False):
+            if raise_limit_errors:
+                raise PayloadTooLarge("Payload too large: limit=4 bytes, received=5 bytes")
+            raise AssertionError("limit errors were not requested")
+
+    class FakeNative:
+        def test_client(self):
+            return FakeServer()
+
+    class FakeApp:
+        native_app = FakeNative()
+
+    client = TestClient(FakeApp(), raise_limit_errors=True)
+    try:
+        client.post("/upload", data="12345")
+    except ValidationError as e:
+        assert isinstance(e, PayloadTooLarge)
+    else:
+        raise AssertionError("PayloadTooLarge was not raised")
+
+
+def test_add_listener():
+    from pyvectora import App
+
+    app = App()
+    app.add_listener("unix:/run/myapp.sock")
+    app.add_listener("[::1]:8001")
+    app.add_listener("0.0.0.0:9000")
+    assert app._listeners == ["unix:/run/myapp.sock", "[::1]:8001", "0.0.0.0:9000"]
+    for bad in ("unix:", "localhost:8000", "127.0.0.1", "127.0.0.1:http"):
+        try:
+            app.add_listener(bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad!r} was accepted")
+
+
+def test_handler_timeout_is_a_cancellation():
+    import asyncio
+
+    from pyvectora import HandlerTimeout
+
+    assert issubclass(HandlerTimeout, asyncio.CancelledError)
+
+    async def handler():
+        try:
+            raise HandlerTimeout("Handler timed out")
+        except asyncio.CancelledError as e:
+            return type(e).__name__
+
+    assert asyncio.run(handler()) == "HandlerTimeout"
+
+
+def test_response_policy():
+    from pyvectora import App
+
+    app = App()
+    assert app._response_policy is None
+    app.set_response_policy()
+    assert app._response_policy == {
+        "strip_server": True,
+        "utf8_text": True,
+        "forbid_powered_by": True,
+    }
+    app.set_response_policy(utf8_text=False)
+    assert app._response_policy["utf8_text"] is False
+    assert app._response_policy["strip_server"] is True
+
+
+def test_unix_domain_socket():
+    from pyvectora import App
+
+    app = App(uds="/run/myapp.sock", uds_mode=0o660)
+    assert (app.uds, app.uds_mode) == ("/run/myapp.sock", 0o660)
+    assert App().uds is None
+    for kwargs in ({"uds": ""}, {"uds_mode": 0o600}, {"uds": "/tmp/a.sock", "uds_mode": 0o10000}):
+        try:
+            App(**kwargs)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{kwargs!r} was accepted")
+
+
+def test_tiered_rate_limit():
+    from pyvectora import App
+
+    app = App()
+    app.enable_tiered_rate_limit({"free": 60, "pro": 600}, default_tier="free")
+    name, cfg = app._middlewares[-1]
+    assert name == "tiered_rate_limit"
+    assert cfg["tiers"] == {"free": 60, "pro": 600}
+    assert (cfg["claim"], cfg["window"], cfg["resolver"]) == ("tier", 60.0, None)
+    for kwargs in (
+        {"tiers": {"pro": 600}, "default_tier": "free"},
+        {"tiers": {"free": 0}, "default_tier": "free"},
+        {"tiers": {"free": 60}, "default_tier": "free", "window": 0},
+    ):
+        try:
+            app.enable_tiered_rate_limit(**kwargs)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{kwargs!r} was accepted")
+
+
+def test_socket_options():
+    from pyvectora import App
+
+    app = App()
+    assert app._socket_options is None
+    app.set_socket_options(backlog=4096, nodelay=True, keepalive_idle=60)
+    assert app._socket_options == {
+        "backlog": 4096,
+        "nodelay": True,
+        "keepalive_idle": 60,
+        "keepalive_interval": None,
+        "recv_buffer_size": None,
+        "send_buffer_size": None,
+    }
+    for kwargs in ({"backlog": 0}, {"recv_buffer_size": 2**32}, {"keepalive_interval": 0}):
+        try:
+            app.set_socket_options(**kwargs)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{kwargs!r} was accepted")
+
+
+def test_socket_activation():
+    from pyvectora import App
+
+    app = App()
+    assert app._socket_activation is False
+    app.enable_socket_activation()
+    assert app._socket_activation is True
+    try:
+        app.serve(workers=2)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("socket activation was accepted with several workers")
+
+
+def test_cache_and_rate_limit_events():
+    from pyvectora import events
+
+    assert {"cache", "rate_limit"} <= set(events.EVENTS)
+
+    async def async_subscriber(event):
+        pass
+
+    try:
+        events.subscribe("rate_limit", async_subscriber)
+    except TypeError:
+        pass
+    else:
+        raise AssertionError("async subscriber was accepted")
+
+
+def test_serve_worker_count():
+    import os
+
+    from pyvectora.app import _worker_count
+
+    assert _worker_count(4) == 4
+    assert _worker_count("auto") == (os.cpu_count() or 1)
+    for bad in (0, -2, "four", 2.5, True):
+        try:
+            _worker_count(bad)
+        except ValueError:
+            pass
+        else:
+            raise AssertionError(f"{bad!r} was accepted")
+
+
+def test_build_then_run():
+    import dataclasses
+
+    from pyvectora import App, Application, ConfigurationError
+
+    app = App()
+
+    @app.get("/users/{id}")
+    def user(request):
+        return {}
+
+    app.enable_cors()
+
+    class FakeNative:
+        def __init__(self, error=None):
+            self.error = error
+
+        def validate(self):
+            if self.error:
+                raise self.error
+
+        def export_routes(self):
+            return "[]"
+
+    app._build_native_app = lambda: FakeNative()
+    application = app.build(workers=2)
+    assert isinstance(application, Application)
+    assert application.workers == 2
+    assert application.routes == ("GET /users/{id}",)
+    assert application.middlewares == ("cors",)
+    assert application.export_routes() == "[]"
+
+    @app.get("/later")
+    def later(request):
+        return {}
+
+    assert application.routes == ("GET /users/{id}",)
+    try:
+        application.workers = 1
+    except dataclasses.FrozenInstanceError:
+        pass
+    else:
+        raise AssertionError("Application was mutated")
+
+    app._build_native_app = lambda: FakeNative(ConfigurationError("bad config"))
+    try:
+        app.build()
+    except ConfigurationError:
+        pass
+    else:
+        raise AssertionError("configuration error did not surface at build time")
+
+
+def test_built_application_ignores_later_app_changes():
+    from pyvectora import App
+
+    app = App(port=8000)
+    calls = []
+
+    @app.on_startup
+    def before():
+        calls.append("before")
+
+    class FakeNative:
+        def __init__(self, name):
+            self.name = name
+
+        def validate(self):
+            pass
+
+        def test_client(self):
+            return self.name
+
+        async def serve(self):
+            calls.append(f"serve {self.name}")
+
+    app._build_native_app = lambda: FakeNative("first")
+    application = app.build()
+
+    @app.on_startup
+    def after():
+        calls.append("after")
+
+    app.port = 9000
+    app.add_listener("127.0.0.1:9001")
+    app._build_native_app = lambda: FakeNative("second")
+    second = app.build()
+
+    assert application.address == "127.0.0.1:8000"
+    assert application.listeners == ()
+    assert application.test_client().server == "first"
+    assert second.test_client().server == "second"
+    assert app.native_app is None
+
+    application.run()
+    assert calls == ["before", "serve first"]
+    assert app.native_app is application.native_app
+
+
+def test_enable_cors_rejects_credentials_for_any_origin():
+    from pyvectora import App
+
+    app = App()
+    try:
+        app.enable_cors(allow_credentials=True)
+    except ValueError:
+        pass
+    else:
+        raise AssertionError("credentials accepted for every origin")
+    app.enable_cors(allow_origin=["https://app.example"], allow_credentials=True)
+    app.enable_cors(allow_origin=lambda origin: True, allow_credentials=True)
+    assert len(app._middlewares) == 2
+
+
+def test_build_rejects_missing_tls_certificate():
+    import pytest
+    pytest.importorskip("pyvectora.pyvectora_native")
+    from pyvectora import App, ConfigurationError
+
+    app = App()
+    app.enable_tls("/nonexistent/cert.pem", "/nonexistent/key.pem")
+    try:
+        app.build()
+    except ConfigurationError:
+        pass
+    else:
+        raise AssertionError("built with a missing certificate")